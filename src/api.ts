@@ -0,0 +1,24 @@
+/**
+ * ImportLens library API, for embedding the analyzer in other tools:
+ *
+ * ```ts
+ * import { Analyzer, ImportLensError } from 'importlens/api';
+ *
+ * const analyzer = Analyzer.builder().language('rust').build();
+ * const report = analyzer.analyzeSource(source);
+ * for (const finding of report.findings) {
+ *   console.log(`${finding.line}: ${finding.message}`);
+ * }
+ * ```
+ *
+ * Everything exported here follows the package's semantic versioning; reports use
+ * the same schema as `importlens-cli --format=json` (see `REPORT_SCHEMA_VERSION`).
+ */
+
+export { Analyzer, AnalyzerBuilder, AnalyzerOptions, ImportLensError, ImportLensErrorKind, Span } from './cli/Analyzer';
+export { Finding, Report, ReportFix, ReportSummary, REPORT_SCHEMA_VERSION } from './cli/Report';
+export { CancellationToken, ProgressCallback, ProgressEvent, RunOptions } from './cli/Progress';
+export { CONFIDENCE_LEVELS, ConfidenceLevel, FINDING_KINDS, FindingKind, isRuleKind, RuleKind, SourceSpan, SuggestedFix, UnusedImport } from './cli/CLIAnalyzer';
+export { FileContext, ImportItem, Rule } from './cli/Rules';
+export { Config, FixConfig, Severity } from './cli/Config';
+export { TextEdit } from './cli/FixEngine';
@@ -10,17 +10,61 @@
  *   importlens-cli src/
  *   importlens-cli --check --format=github src/
  *   importlens-cli --fix --safe-mode src/
+ *   importlens-cli --format=html --output=report.html src/
+ *   importlens-cli --fix --dry-run src/
+ *   importlens-cli --fix --interactive src/
+ *   importlens-cli --fix --organize src/
+ *   importlens-cli --check-organized src/
+ *   importlens-cli --fix --expand-globs src/
+ *   importlens-cli --check --fail-on warning --severity duplicate-import=error src/
+ *   git diff --name-only | importlens-cli --files-from -
+ *   importlens-cli --check --diff-base origin/main... src/
+ *   importlens-cli --stdin --stdin-filename src/lib.rs --fix < buffer.rs
+ *   importlens-cli cache clear
+ *   importlens-cli config --print-effective src/lib.rs
+ *   importlens-cli watch src/
+ *   importlens-cli lsp
+ *   importlens-cli baseline generate src/ > importlens-baseline.json
+ *   importlens-cli graph src/ --collapse crates > crates.dot
+ *   importlens-cli stats --format=json . > stats.json
+ *   importlens-cli hook --staged
+ *   importlens-cli hook install
+ *   importlens-cli rename --from crate::utils::parse --to crate::parsing src/
  */
 
 import * as fs from 'fs';
 import * as path from 'path';
-import { parseArgs } from './cli/ArgumentParser';
-import { FileDiscovery } from './cli/FileDiscovery';
-import { CLIAnalyzer } from './cli/CLIAnalyzer';
-import { OutputFormatter } from './cli/OutputFormatter';
+import { CLIArguments, parseArgs } from './cli/ArgumentParser';
+import { FileDiscovery, parseFileList } from './cli/FileDiscovery';
+import { AnalysisResult, CLIAnalyzer, ConfidenceLevel, meetsConfidence, SEVERITY_LEVELS } from './cli/CLIAnalyzer';
+import { cancelledError, ImportLensError } from './cli/Analyzer';
+import { CancellationToken, nextTurn, ProgressBar } from './cli/Progress';
+import { OutputFormatter, useColor } from './cli/OutputFormatter';
 import { BaselineManager } from './cli/BaselineManager';
+import { AnalysisCache, DEFAULT_CACHE_DIR } from './cli/AnalysisCache';
+import { ConfigResolver } from './cli/Config';
+import { toolVersion } from './cli/Version';
+import { Watcher } from './cli/Watcher';
+import { LspServer } from './cli/LspServer';
+import { FixEngine, isFixable } from './cli/FixEngine';
+import { InteractiveReview, terminalPrompt } from './cli/InteractiveReview';
+import { LineIndex } from './cli/LineIndex';
+import { displayPath, toPosixPath } from './cli/Paths';
+import { createUnifiedDiff } from './cli/UnifiedDiff';
+import { diffRoot, filterToDiff, gitDiff, parseUnifiedDiff } from './cli/DiffFilter';
+import { buildImportGraph, formatDot, formatGraphML } from './cli/ImportGraph';
+import { buildImportStats, formatStatsText } from './cli/ImportStats';
+import { dirtyFiles, gitRoot, installHook, restageFix, stagedContent, stagedFiles } from './cli/GitHook';
+import { renameModulePath } from './cli/RustRename';
 import { TeamAnalyticsEngine, HealthScoreCalculator } from './analytics/TeamAnalytics';
 
+/** Exit status when the check finds problems; operational errors exit with EXIT_ERROR */
+const EXIT_FINDINGS = 1;
+/** Exit status when ImportLens itself fails: bad arguments, unreadable files, failed fixes */
+const EXIT_ERROR = 2;
+/** Exit status after Ctrl-C: 128 + SIGINT, as shells report a process the signal killed */
+const EXIT_CANCELLED = 130;
+
 async function main() {
   try {
     // Parse command-line arguments
@@ -38,20 +82,142 @@ async function main() {
       process.exit(0);
     }
 
-    // Discover files to analyze
-    const discovery = new FileDiscovery(args);
-    const files = await discovery.discoverFiles();
+    // Checked before analysis, so a pipe or CI job fails at once instead of waiting for answers
+    if (args.interactive && !process.stdin.isTTY) {
+      throw new Error('--interactive needs a terminal to prompt on; stdin is not a TTY');
+    }
+
+    if (args.command === 'cache-clear') {
+      const dir = args.cacheDir ?? DEFAULT_CACHE_DIR;
+      console.log(AnalysisCache.clear(dir) ? `Cleared analysis cache ${dir}` : `No analysis cache at ${dir}`);
+      process.exit(0);
+    }
+
+    // Per-directory importlens.toml settings, shared by discovery, analysis and fixes
+    const config = new ConfigResolver(args.configOverrides, args.language);
+
+    if (args.filesFrom !== undefined) {
+      const list = fs.readFileSync(args.filesFrom === '-' ? 0 : args.filesFrom, 'utf-8');
+      const listed = new FileDiscovery(args, config).filterListedFiles(parseFileList(list, args.nullSeparated));
+      // An empty list is not an error, e.g. a commit that touches no source files
+      if (listed.length === 0 && args.files.length === 0) {
+        console.error('No files to analyze');
+        process.exit(0);
+      }
+      args.files.push(...listed);
+    }
+
+    if (args.command === 'watch') {
+      const watcher = new Watcher(args);
+      process.on('SIGINT', () => {
+        watcher.close();
+        process.exit(0);
+      });
+      await watcher.start();
+      return;
+    }
 
-    if (files.length === 0) {
-      console.error('No files found to analyze');
-      process.exit(1);
+    if (args.command === 'lsp') {
+      // stdout carries the protocol, so anything else printed goes to stderr
+      console.log = console.error;
+      new LspServer(args, process.stdout).listen(process.stdin);
+      return;
+    }
+
+    if (args.command === 'print-config') {
+      if (args.files.length !== 1) {
+        throw new Error('config --print-effective takes exactly one file path');
+      }
+      console.log(JSON.stringify(config.resolve(args.files[0]), null, 2));
+      process.exit(0);
+    }
+
+    if (args.command === 'graph') {
+      exportGraph(await new FileDiscovery(args, config).discoverFiles(), args, config);
+      process.exit(0);
+    }
+
+    if (args.command === 'rename') {
+      process.exit(runRename(await new FileDiscovery(args, config).discoverFiles(), args, config) ? EXIT_ERROR : 0);
+    }
+
+    if (args.command === 'hook-install') {
+      console.log(`Installed pre-commit hook ${installHook()}`);
+      process.exit(0);
     }
 
     // Initialize analyzer
-    const analyzer = new CLIAnalyzer(args);
+    const analyzer = new CLIAnalyzer(args, config);
+    const cancellation = new CancellationToken();
+
+    // Ctrl-C stops before the next file: the cache keeps what was analyzed, and a file
+    // being fixed is written whole. A second Ctrl-C exits at once.
+    process.on('SIGINT', () => {
+      if (cancellation.isCancelled) process.exit(EXIT_CANCELLED);
+      cancellation.cancel();
+    });
+
+    // Sources read from stdin, by the path they are analyzed as
+    const sources = new Map<string, string>();
+    let files: string[];
+    let results: AnalysisResult[];
+
+    if (args.stdin) {
+      const filePath = args.stdinFilename ? path.resolve(args.stdinFilename) : '<stdin>';
+      sources.set(filePath, await readStdin(cancellation));
+      files = [filePath];
+      results = await analyzeSources(analyzer, files, sources, cancellation);
+    } else if (args.command === 'hook') {
+      const root = gitRoot();
+      files = stagedFiles(root)
+        .map(file => path.join(root, file))
+        .filter(file => isSelected(file, args.files) && !config.resolve(file).ignored && config.resolve(file).language !== 'unknown');
+      // Nothing to commit that ImportLens understands: let the commit through quietly
+      if (files.length === 0) {
+        process.exit(0);
+      }
+
+      const staged = (file: string) => stagedContent(root, toPosixPath(path.relative(root, file)));
+      for (const file of files) sources.set(file, staged(file));
+      results = await analyzeSources(analyzer, files, sources, cancellation);
+
+      if (args.fixStaged) {
+        if (await fixStaged(results, sources, root, args, config, cancellation)) process.exit(EXIT_ERROR);
+        if (cancellation.isCancelled) process.exit(EXIT_CANCELLED);
+        // Report what the fixes left
+        results = await analyzeSources(analyzer, files, sources, cancellation);
+      }
+    } else {
+      // Discover files to analyze
+      const discovery = new FileDiscovery(args, config);
+      files = await discovery.discoverFiles();
+
+      for (const warning of discovery.warnings) {
+        console.error(`[WARNING] ${warning}`);
+      }
+
+      if (files.length === 0) {
+        console.error('No files found to analyze');
+        process.exit(EXIT_ERROR);
+      }
 
-    // Process files
-    const results = await analyzer.analyzeFiles(files);
+      // Process files
+      const progress = ProgressBar.forArgs(args);
+      try {
+        results = await analyzer.analyzeFiles(files, { onProgress: event => progress?.update(event), cancellation });
+      } finally {
+        progress?.clear();
+      }
+    }
+
+    for (const result of results) {
+      result.diagnostics?.forEach(message => console.error(message));
+    }
+
+    if (args.command === 'stats') {
+      exportStats(files, results, args, config);
+      process.exit(0);
+    }
 
     // Handle analytics mode
     if (args.analytics) {
@@ -59,10 +225,37 @@ async function main() {
       process.exit(0);
     }
 
+    // Handle fix mode
+    if (args.stdin && (args.fix || args.organize)) {
+      process.exit(fixStdin(results[0], sources.get(results[0].filePath) ?? '', args, config) ? EXIT_ERROR : 0);
+    }
+
+    if (args.fix || args.organize) {
+      const reviewed = args.interactive ? await reviewFixes(results, args, config) : results;
+      const failed = await runFixes(reviewed, args, config, cancellation);
+      process.exit(failed ? EXIT_ERROR : cancellation.isCancelled ? EXIT_CANCELLED : 0);
+    }
+
+    if (args.checkOrganized) {
+      process.exit(reportUnorganized(results, sources) ? EXIT_FINDINGS : 0);
+    }
+
     // Initialize baseline manager
     const baselineManager = new BaselineManager(args.baseline);
 
     // Handle baseline operations
+    if (args.command === 'baseline-generate') {
+      // Printed rather than saved, so it can be redirected to any file
+      console.log(JSON.stringify(baselineManager.generateBaseline(results), null, 2));
+      process.exit(0);
+    }
+
+    if (args.command === 'baseline-update') {
+      const { kept, pruned } = baselineManager.pruneBaseline(results);
+      console.log(`  Removed ${pruned} stale ${pruned === 1 ? 'entry' : 'entries'}, kept ${kept}`);
+      process.exit(0);
+    }
+
     if (args.baselineGenerate) {
       const baseline = baselineManager.generateBaseline(results);
       baselineManager.saveBaseline(baseline);
@@ -74,47 +267,88 @@ async function main() {
       process.exit(0);
     }
 
-    if (args.baselineCheck || baselineManager.baselineExists()) {
+    // Findings the diff doesn't touch are left out before the baseline is applied
+    let outsideDiff: number | undefined;
+    if (args.diffBase !== undefined || args.diff !== undefined) {
+      const root = diffRoot();
+      const diff = args.diff !== undefined
+        ? fs.readFileSync(args.diff === '-' ? 0 : args.diff, 'utf-8')
+        : gitDiff(args.diffBase ?? 'HEAD', root);
+      const filtered = filterToDiff(results, parseUnifiedDiff(diff), root, args.diffScope ?? 'imports');
+      results = filtered.results;
+      outsideDiff = filtered.hidden;
+    }
+
+    // Findings recorded in the baseline are left out of the report and don't fail the check
+    let baselined: number | undefined;
+    if (args.baselineCheck || args.baseline !== undefined || baselineManager.baselineExists()) {
       const baseline = baselineManager.loadBaseline();
 
       if (!baseline) {
-        console.log('[WARNING] No baseline found. Run with --baseline-generate to create one.');
-        console.log('          Continuing with standard analysis...\n');
+        console.error(`[WARNING] No baseline found at ${args.baseline ?? '.importlens-baseline.json'}. Run \`importlens-cli baseline generate\` to create one.`);
       } else {
-        // Compare against baseline
-        const comparison = baselineManager.compareWithBaseline(results, baseline);
-        baselineManager.printComparisonSummary(comparison);
-
-        // Exit with error code if new issues found
-        if (args.check && comparison.summary.totalNew > 0) {
-          console.log('\n[ERROR] New unused imports detected beyond baseline!');
-          process.exit(1);
-        }
-
-        if (comparison.summary.totalNew === 0) {
-          console.log('\n[SUCCESS] All checks passed!');
-          process.exit(0);
-        }
+        const filtered = baselineManager.filterResults(results, baseline);
+        results = filtered.results;
+        baselined = filtered.baselined;
       }
     }
 
-    // Format and output results (if not using baseline mode)
-    const formatter = new OutputFormatter(args.format);
+    // Format and output results
+    const formatter = OutputFormatter.forArgs(args);
     const output = formatter.format(results);
 
-    console.log(output);
+    if (args.output) {
+      fs.writeFileSync(args.output, output.endsWith('\n') ? output : output + '\n');
+      console.error(`Report written to ${args.output}`);
+    } else {
+      console.log(output);
+    }
+
+    // Machine-readable formats keep stdout to the report itself
+    const printSummary = (summary: string) => {
+      if (args.format === 'text') {
+        console.log(summary);
+      } else {
+        console.error(summary);
+      }
+    };
+
+    if (outsideDiff !== undefined) {
+      printSummary(`${outsideDiff} ${outsideDiff === 1 ? 'finding' : 'findings'} outside the diff`);
+    }
+
+    if (baselined !== undefined) {
+      const found = results.reduce((sum, r) => sum + r.unusedImports.length, 0);
+      printSummary(`${found} new, ${baselined} baselined`);
+    }
 
-    // Exit with appropriate code
-    const hasIssues = results.some(r => r.unusedImports.length > 0);
+    // Exit with appropriate code; only findings at or above --fail-on fail the check
+    const threshold = SEVERITY_LEVELS.indexOf(args.failOn);
+    const findings = results.flatMap(r => r.unusedImports);
+    const hasIssues = findings.some(u => SEVERITY_LEVELS.indexOf(u.severity ?? 'error') >= threshold);
+    const warnings = findings.filter(u => u.severity === 'warning').length;
+    const tooManyWarnings = args.maxWarnings !== undefined && warnings > args.maxWarnings;
 
-    if (args.check && hasIssues) {
-      process.exit(1); // Fail CI if issues found in check mode
+    if (tooManyWarnings) {
+      console.error(`[ERROR] Too many warnings: ${warnings} (maximum: ${args.maxWarnings})`);
+    }
+
+    if (results.some(r => r.error)) {
+      process.exit(EXIT_ERROR); // Files that couldn't be analyzed
+    }
+
+    if (args.check && (hasIssues || tooManyWarnings)) {
+      process.exit(EXIT_FINDINGS); // Fail CI if issues found in check mode
     }
 
     process.exit(0);
   } catch (error) {
+    if (error instanceof ImportLensError && error.kind === 'cancelled') {
+      console.error(`[INTERRUPTED] ${error.message}`);
+      process.exit(EXIT_CANCELLED);
+    }
     console.error('Error:', error instanceof Error ? error.message : String(error));
-    process.exit(1);
+    process.exit(EXIT_ERROR);
   }
 }
 
@@ -124,20 +358,116 @@ ImportLens CLI - Unused Import Analyzer for CI/CD
 
 USAGE:
   importlens-cli [OPTIONS] [FILES...]
+  importlens-cli cache clear [--cache-dir=<dir>]
+  importlens-cli config --print-effective <file>
+  importlens-cli watch [OPTIONS] [FILES...] [--exec=<cmd>]
+  importlens-cli lsp [OPTIONS]
+  importlens-cli baseline generate [OPTIONS] [FILES...] > <file>
+  importlens-cli baseline update [--baseline <file>] [OPTIONS] [FILES...]
+  importlens-cli hook --staged|--fix-staged [OPTIONS] [FILES...]
+  importlens-cli hook install
+  importlens-cli graph [--format=dot|graphml] [--collapse crates] [--filter <pattern>] [FILES...]
+  importlens-cli stats [--format=text|json] [OPTIONS] [FILES...]
+  importlens-cli rename --from <path> --to <path> [--dry-run] [--allow-dirty] [FILES...]
 
 OPTIONS:
   --check              Check for unused imports without fixing (exit 1 if found)
-  --fix                Automatically fix unused imports
+  --fix                Automatically fix unused imports (Rust)
+  --dry-run            With --fix, --organize or rename, print a unified diff instead of writing files
+  --output-patch <file>
+                       With --fix or --organize, write the unified diff to <file>
+                       (- for stdout) for git apply, instead of writing files
+  --interactive        With --fix, show each import and ask before removing it
+                       (y/n, a: rest of file, k: rest of this kind, s: skip file, q: quit)
+  --organize           Rust: group use items (std, external, crate), sort and merge them
+  --check-organized    Rust: report imports --organize would change, without rewriting
+  --expand-globs       Rust: with --fix, replace used glob imports of crate modules
+                       with the items used from them
   --safe-mode          Preserve side-effect imports (default: true)
   --aggressive         Remove all unused imports including side-effects
-  --format=<type>      Output format: text, json, github, junit (default: text)
+  --format=<type>      Output format: text, json, github, junit, sarif, html (default: text)
+  --group-by <what>    Text output: group findings by file (default), kind or crate
+  --top <n>            Text output: only show the <n> files, kinds or crates with the
+                       most findings
+  --verbose            Text output: show every finding instead of the first 3 of each
+                       kind in a file, and the files without findings
+  --color <when>       Color severities in text output: auto (on a terminal, unless
+                       NO_COLOR is set), always or never (default: auto)
+  --quiet, -q          Don't show the progress bar drawn on stderr when it is a terminal
+  --output=<file>, -o <file>
+                       Write the report to <file> instead of stdout
+  --collapse <level>   With graph: a node per module (modules, the default) or per crate (crates)
+  --filter <pattern>   With graph: keep only modules matching <pattern>, e.g. 'crate::services::*'
+  --from <path>        With rename: the Rust module or item path to rewrite, e.g. crate::utils::parse
+  --to <path>          With rename: the path to rewrite it to, e.g. crate::parsing
+  --allow-dirty        With rename: write files even with uncommitted changes in the git tree
   --config=<file>      Path to configuration file (.importlensrc.json)
   --exclude=<pattern>  Glob pattern to exclude files (can be used multiple times)
+  --hidden             Include dotfiles and dot-directories when walking directories
+  --max-file-size=<size>
+                       Skip files larger than <size>, e.g. 500KB or 20MB, with a warning (default: 10MB)
+  --include-large-files
+                       Analyze files over --max-file-size too
+  --strict-macros      Rust: don't count identifiers inside macro bodies as uses
+  --report-unused-suppressions
+                       Rust: report allow(unused_imports) / importlens: ignore markers
+                       that only cover used imports
+  --check-reexports    Rust: report pub(crate)/pub(super) re-exports no module of the crate uses
+  --crate-root <file>  Rust: build the module tree of the crate rooted at <file> (e.g.
+                       src/lib.rs) and count uses of imports from other modules
+  --check-dependencies Rust: report Cargo.toml dependencies no source file of the package refers to
+  --check-doctests     Rust: report unused imports inside doc comment code blocks, each
+                       checked as its own snippet
+  --check-qualified-paths[=<n>]
+                       Rust: report paths written out <n> or more times in a module
+                       without an import (default: 3); --fix imports and shortens them.
+                       Also reports written-out paths that importlens.toml bans
+  --check-prelude      Rust: report imports of what the prelude already provides, e.g.
+                       use std::vec::Vec; --fix removes them
+  --edition <year>     Rust: edition whose prelude --check-prelude uses: 2015, 2018, 2021
+                       or 2024 (default: from the nearest Cargo.toml)
+  --check-test-only    Rust: report imports only #[cfg(test)] modules and #[test] functions
+                       use; --fix moves them into the test module. Also on where
+                       importlens.toml gives test-only-import a severity
+  --cfg <spec>         Rust: pin a cfg option like rustc's --cfg, e.g. --cfg unix or
+                       --cfg 'feature="serde"' (can be used multiple times)
+  --min-confidence=<level>
+                       Hide findings below <level>: low, medium, high (default: medium);
+                       --fix removes only high findings unless it is given
+  --severity <kind=level,...>
+                       Set the severity of finding kinds: error, warn, info or off,
+                       e.g. --severity duplicate-import=error,type-only-import=off
+  --fail-on=<level>    With --check, fail on findings at or above <level>: info,
+                       warning, error (default: error)
+  --max-warnings=<n>   With --check, also fail when there are more than <n> warnings
+  --diff-base <ref>    Only report findings on lines changed since git <ref>, working
+                       tree included (origin/main... diffs from the merge base)
+  --diff <file>        Only report findings on lines changed in a unified diff (- for stdin)
+  --diff-scope=<scope> With --diff-base or --diff: imports (the import line changed) or
+                       any (also a changed line mentioning the name) (default: imports)
+  --language=<lang>    Analyze files as <lang> instead of detecting it from the extension
+                       (typescript, javascript, python, java, go, rust, cpp, c)
+  --jobs=<n>, -j <n>   Number of worker threads (default: number of CPU cores; 1 = no workers)
+  --no-cache           Analyze every file instead of reusing results for unchanged files
+  --cache-dir=<dir>    Location of the analysis cache (default: .importlens-cache)
+  --exec=<cmd>         With watch, run <cmd> after every re-analysis
+                       (IMPORTLENS_FINDINGS and IMPORTLENS_FILES are set)
+  --staged             With hook, analyze the staged content of the files to commit
+                       (exit 1 only on error-severity findings, unless --fail-on says otherwise)
+  --fix-staged         With hook, remove fixable findings from the staged content first and
+                       apply the same change to the working tree
+  --stdin              Analyze source read from stdin; with --fix or --organize, print
+                       the fixed source instead of writing files
+  --stdin-filename <path>
+                       With --stdin, the path used for language detection and reports
+  --files-from <file>  Also analyze the paths listed in <file>, one per line (- for stdin)
+  -0                   With --files-from, paths are NUL-separated
   --help               Show this help message
   --version            Show version information
 
 BASELINE OPTIONS (for CI/CD):
-  --baseline=<file>         Path to baseline file (default: .importlens-baseline.json)
+  --baseline <file>         Report only findings not in <file>, with an "N new, M baselined"
+                            summary (default file: .importlens-baseline.json, used if present)
   --baseline-generate       Generate a new baseline from current results
   --baseline-update         Update existing baseline with current results
   --baseline-check          Check for new issues beyond baseline (auto-enabled if baseline exists)
@@ -149,21 +479,72 @@ EXAMPLES:
   # Fix unused imports with safe mode
   importlens-cli --fix --safe-mode src/
 
+  # Choose which imports to remove, one at a time
+  importlens-cli --fix --interactive src/
+
+  # Preview fixes as a patch without touching files
+  importlens-cli --fix --dry-run src/ > fixes.patch
+
+  # Proposed fixes as a patch file, e.g. for a review bot
+  importlens-cli --fix --output-patch fixes.patch src/
+
   # GitHub Actions format for annotations
   importlens-cli --check --format=github .
 
+  # The ten crates of a workspace with the most findings
+  importlens-cli --check --group-by crate --top 10 .
+
+  # Only Python files in a polyglot repository
+  importlens-cli --check --language=python .
+
+  # Re-analyze files as they are saved
+  importlens-cli watch src/
+
+  # Language server over stdio, for editors other than VS Code
+  importlens-cli lsp
+
+  # Drop cached results, e.g. after a suspected cache problem
+  importlens-cli cache clear
+
+  # Pre-commit hook: check what is about to be committed
+  importlens-cli hook install
+
+  # Pull requests: only imports changed since the merge base
+  importlens-cli --check --diff-base origin/main... src/
+
+  # Editor integration: fix a buffer through stdin
+  importlens-cli --stdin --stdin-filename src/lib.rs --fix < buffer.rs
+
   # JSON output for custom processing
   importlens-cli --check --format=json src/ > report.json
 
+  # Self-contained HTML report with source excerpts
+  importlens-cli --format=html --output=report.html src/
+
+  # Import graph of the workspace's crates, rendered with Graphviz
+  importlens-cli graph --collapse crates . | dot -Tsvg > crates.svg
+
+  # Import metrics for a dashboard: totals, unused share, most imported crates
+  importlens-cli stats --format=json --output=stats.json .
+
+  # Update imports and paths after moving a module, previewing the changes first
+  importlens-cli rename --from crate::utils::parse --to crate::parsing --dry-run .
+
   # Baseline workflow for CI/CD:
   # 1. Generate baseline to capture current technical debt
-  importlens-cli --baseline-generate src/
+  importlens-cli baseline generate src/ > importlens-baseline.json
+
+  # 2. In CI, fail only on NEW issues beyond baseline
+  importlens-cli --check --baseline importlens-baseline.json src/
 
-  # 2. In CI, check for NEW issues beyond baseline
-  importlens-cli --check src/  # Auto-detects baseline
+  # 3. Drop entries for findings that have since been fixed
+  importlens-cli baseline update --baseline importlens-baseline.json src/
 
-  # 3. Update baseline when intentionally accepting new debt
-  importlens-cli --baseline-update src/
+EXIT CODES:
+  0  No findings at or above --fail-on (default: error), and no more warnings than --max-warnings
+  1  Findings in --check mode, or unorganized imports with --check-organized
+  2  ImportLens failed: invalid arguments, unreadable files, fixes that couldn't be applied
+  130  Interrupted by Ctrl-C; files already analyzed stay cached, files already fixed stay fixed
 
 CONFIGURATION:
   Create .importlensrc.json in your project root:
@@ -173,9 +554,27 @@ CONFIGURATION:
     "excludedLanguages": ["markdown"]
   }
 
-EXIT CODES:
-  0 - Success (no unused imports or successfully fixed)
-  1 - Failure (unused imports found in check mode or error occurred)
+  Per-directory settings go in importlens.toml files; each file is governed by
+  every importlens.toml from its directory up, nearer files winning:
+    kinds = ["unused-import", "duplicate-import"]   # finding kinds to report
+    ignore = ["generated/", "*.pb.rs"]              # gitignore-style, relative to the file
+    [severity]
+    redundant-import = "warn"                       # error | warn | info | off
+    [languages]
+    "*.h" = "cpp"
+    [headers]
+    "mylib/log.h" = ["log_info", "mylib::Logger"]   # C/C++: identifiers a header provides
+    [fix]
+    enabled = true
+    kinds = ["unused-import"]                       # what --fix may remove
+
+  Show the settings that apply to a file:
+    importlens-cli config --print-effective src/lib.rs
+
+DIRECTORY SCANNING:
+  Directories are walked recursively. Paths matched by .gitignore, .ignore or
+  .importlensignore files are skipped, as are target/, node_modules/, dist/,
+  out/, build/ and .git/. Files named explicitly are always analyzed.
 
 ANALYTICS:
   --analytics                    Generate team analytics report
@@ -189,6 +588,394 @@ ANALYTICS:
 `);
 }
 
+/**
+ * Remove unused imports from every fixable file, limited to the finding kinds
+ * each file's `importlens.toml` lets `--fix` remove, with `--expand-globs` replace
+ * used glob imports with the items used, and with `--organize` sort and group the
+ * imports that remain.
+ * In dry-run mode the combined diff goes to stdout and status lines to stderr;
+ * with `--output-patch` files are left untouched too and the diff is written to
+ * the patch file instead.
+ * Once `cancellation` is cancelled no further file is fixed; the files fixed so far
+ * stay fixed.
+ * Returns true if any file could not be fixed.
+ */
+async function runFixes(results: AnalysisResult[], args: CLIArguments, config: ConfigResolver, cancellation: CancellationToken): Promise<boolean> {
+  const engine = new FixEngine();
+  const { organize } = args;
+  const dryRun = args.dryRun || args.outputPatch !== undefined;
+  const log = dryRun ? console.error : console.log;
+  const patch: string[] = [];
+  let failed = false;
+  let totalFixed = 0;
+  let totalExpanded = 0;
+  let totalOrganized = 0;
+  let belowConfidence = 0;
+
+  for (const [index, result] of results.entries()) {
+    // Fixing is synchronous, so Ctrl-C is only seen between files
+    await nextTurn();
+    if (cancellation.isCancelled) {
+      log(`[INTERRUPTED] Stopped before the remaining ${results.length - index} of ${results.length} file(s)`);
+      break;
+    }
+
+    const removable = args.fix ? result.unusedImports.length + (result.globExpansions?.length ?? 0) : 0;
+    if (result.error || (removable === 0 && !(organize && engine.canOrganize(result.language)))) continue;
+
+    const { fix: settings } = config.resolve(result.filePath);
+    if (!settings.enabled) {
+      log(`[SKIP] ${result.filePath}: fixes are disabled by importlens.toml`);
+      continue;
+    }
+
+    if (!engine.canFix(result.language)) {
+      log(`[SKIP] ${result.filePath}: automatic fixes are not supported for ${result.language}`);
+      continue;
+    }
+
+    const allowed = args.fix ? result.unusedImports.filter(u => settings.kinds.includes(u.kind ?? 'unused-import')) : [];
+    // Answers to --interactive already chose among the reported findings
+    const unusedImports = args.interactive ? allowed : allowed.filter(u => meetsConfidence(u, fixConfidence(args)));
+    belowConfidence += allowed.length - unusedImports.length;
+    const fix = engine.fixFile({ ...result, unusedImports }, dryRun, organize);
+    if (fix.error) {
+      log(`[ERROR] ${result.filePath}: ${fix.error}`);
+      failed = true;
+      continue;
+    }
+
+    if (fix.fixedCount === 0 && fix.expandedCount === 0 && !fix.organized) continue;
+    totalFixed += fix.fixedCount;
+    totalExpanded += fix.expandedCount;
+    if (fix.organized) totalOrganized++;
+
+    if (args.outputPatch !== undefined) {
+      patch.push(fix.diff);
+      continue;
+    }
+    if (dryRun) {
+      process.stdout.write(fix.diff);
+      continue;
+    }
+
+    const changes = [
+      fix.fixedCount > 0 ? `removed ${fix.fixedCount} unused import(s)` : '',
+      fix.expandedCount > 0 ? `expanded ${fix.expandedCount} glob import(s)` : '',
+      fix.organized ? 'organized imports' : '',
+    ].filter(change => change !== '');
+    const last = changes.pop();
+    log(`[FIXED] ${result.filePath}: ${changes.length > 0 ? `${changes.join(', ')} and ${last}` : last}`);
+  }
+
+  if (args.fix) {
+    log(dryRun
+      ? `\n${totalFixed} unused import(s) would be removed`
+      : `\n[SUCCESS] Removed ${totalFixed} unused import(s)`);
+  }
+  if (belowConfidence > 0) {
+    log(`[SKIP] ${belowConfidence} finding(s) below ${fixConfidence(args)} confidence left in place; ` +
+      'review them with --fix --interactive, or lower --min-confidence to remove them');
+  }
+  if (args.expandGlobs) {
+    log(dryRun
+      ? `${totalExpanded} glob import(s) would be expanded`
+      : `[SUCCESS] Expanded ${totalExpanded} glob import(s)`);
+  }
+  if (organize) {
+    log(dryRun
+      ? `${args.fix ? '' : '\n'}${totalOrganized} file(s) would have their imports organized`
+      : `${args.fix ? '' : '\n'}[SUCCESS] Organized imports in ${totalOrganized} file(s)`);
+  }
+
+  if (args.outputPatch === '-') {
+    process.stdout.write(patch.join(''));
+  } else if (args.outputPatch !== undefined) {
+    fs.writeFileSync(args.outputPatch, patch.join(''));
+    log(`Patch written to ${args.outputPatch}; apply it with \`git apply ${args.outputPatch}\``);
+  }
+
+  return failed;
+}
+
+/**
+ * Lowest confidence `--fix` removes: `high`, unless `--min-confidence` is given
+ */
+function fixConfidence(args: CLIArguments): ConfidenceLevel {
+  return args.minConfidence ?? 'high';
+}
+
+/**
+ * `--fix --interactive`: ask about each finding `--fix` would remove and keep only
+ * the accepted ones. Glob expansions and `--organize` still apply as usual.
+ */
+async function reviewFixes(results: AnalysisResult[], args: CLIArguments, config: ConfigResolver): Promise<AnalysisResult[]> {
+  const engine = new FixEngine();
+  const prompt = terminalPrompt();
+  try {
+    const review = new InteractiveReview(prompt, useColor(args.color, process.stderr));
+    return await review.review(results, (result, finding) => {
+      const { fix: settings } = config.resolve(result.filePath);
+      return !result.error && settings.enabled && engine.canFix(result.language) &&
+        settings.kinds.includes(finding.kind ?? 'unused-import') && isFixable(finding);
+    });
+  } finally {
+    prompt.close();
+  }
+}
+
+/**
+ * Whether `file` is one of `paths` or inside one of them; no paths select everything
+ */
+function isSelected(file: string, paths: string[]): boolean {
+  return paths.length === 0 || paths.some(selected => {
+    const relative = path.relative(path.resolve(selected), file);
+    return relative === '' || (!relative.startsWith('..') && !path.isAbsolute(relative));
+  });
+}
+
+/**
+ * `hook --fix-staged`: remove the fixable findings from the staged content of each
+ * file and re-stage just those changes, applying them to the working tree too.
+ * `sources` is updated to the new staged content. Ctrl-C stops between files, as
+ * with `--fix`. Returns true if any file could not be fixed.
+ */
+async function fixStaged(
+  results: AnalysisResult[],
+  sources: Map<string, string>,
+  root: string,
+  args: CLIArguments,
+  config: ConfigResolver,
+  cancellation: CancellationToken
+): Promise<boolean> {
+  const engine = new FixEngine();
+  let failed = false;
+
+  for (const [index, result] of results.entries()) {
+    await nextTurn();
+    if (cancellation.isCancelled) {
+      console.error(`[INTERRUPTED] Stopped before the remaining ${results.length - index} of ${results.length} file(s)`);
+      break;
+    }
+
+    const { fix: settings } = config.resolve(result.filePath);
+    if (result.error || !settings.enabled || !engine.canFix(result.language)) continue;
+
+    const staged = sources.get(result.filePath) ?? '';
+    const unusedImports = result.unusedImports.filter(u => settings.kinds.includes(u.kind ?? 'unused-import') && meetsConfidence(u, fixConfidence(args)));
+    const fix = engine.fixSource(staged, { ...result, unusedImports });
+    if (fix.fixed === staged) continue;
+
+    const file = toPosixPath(path.relative(root, result.filePath));
+    try {
+      restageFix(root, file, staged, fix.fixed);
+      sources.set(result.filePath, fix.fixed);
+      console.error(`[FIXED] ${file}: removed ${fix.fixedCount} unused import(s) and re-staged the change`);
+    } catch (error) {
+      console.error(`[ERROR] ${error instanceof Error ? error.message : String(error)}`);
+      failed = true;
+    }
+  }
+
+  return failed;
+}
+
+/**
+ * Analyze sources read into memory, stdin or the staged files, one at a time. Like
+ * `analyzeFiles`, this rejects with a `cancelled` error once Ctrl-C is seen between files.
+ */
+async function analyzeSources(
+  analyzer: CLIAnalyzer,
+  files: string[],
+  sources: Map<string, string>,
+  cancellation: CancellationToken
+): Promise<AnalysisResult[]> {
+  const results: AnalysisResult[] = [];
+  for (const file of files) {
+    await nextTurn();
+    if (cancellation.isCancelled) throw cancelledError(results.length, files.length);
+    results.push(analyzer.analyzeContent(file, sources.get(file) ?? ''));
+  }
+  await nextTurn();
+  if (cancellation.isCancelled) throw cancelledError(results.length, files.length);
+  return results;
+}
+
+/**
+ * All of stdin. It is read asynchronously so Ctrl-C is seen, and rejects with a
+ * `cancelled` error then, since nothing has been analyzed yet.
+ */
+function readStdin(cancellation: CancellationToken): Promise<string> {
+  return new Promise((resolve, reject) => {
+    const chunks: Buffer[] = [];
+    const stop = cancellation.onCancel(() => {
+      process.stdin.destroy();
+      reject(cancelledError(0, 1));
+    });
+    process.stdin.on('data', chunk => chunks.push(chunk));
+    process.stdin.on('end', () => {
+      stop();
+      resolve(Buffer.concat(chunks).toString('utf-8'));
+    });
+    process.stdin.on('error', error => {
+      stop();
+      reject(error);
+    });
+  });
+}
+
+/**
+ * `--fix` or `--organize` with `--stdin`: write the fixed source to stdout, or with
+ * `--dry-run` a unified diff, and never touch the file it is analyzed as. Sources
+ * that can't be fixed are written back unchanged. Returns true if fixing failed.
+ */
+function fixStdin(result: AnalysisResult, content: string, args: CLIArguments, config: ConfigResolver): boolean {
+  const engine = new FixEngine();
+  const { fix: settings } = config.resolve(result.filePath);
+  let fixed = content;
+
+  if (result.error) {
+    console.error(`[ERROR] ${result.filePath}: ${result.error}`);
+  } else if (!settings.enabled) {
+    console.error(`[SKIP] ${result.filePath}: fixes are disabled by importlens.toml`);
+  } else if (!engine.canFix(result.language)) {
+    console.error(`[SKIP] ${result.filePath}: automatic fixes are not supported for ${result.language}`);
+  } else {
+    const unusedImports = args.fix
+      ? result.unusedImports.filter(u => settings.kinds.includes(u.kind ?? 'unused-import') && meetsConfidence(u, fixConfidence(args)))
+      : [];
+    fixed = engine.fixSource(content, { ...result, unusedImports }, args.organize).fixed;
+  }
+
+  if (args.dryRun) {
+    const file = displayPath(result.filePath);
+    process.stdout.write(createUnifiedDiff(`a/${file}`, `b/${file}`, content, fixed));
+  } else {
+    process.stdout.write(fixed);
+  }
+  return result.error !== undefined;
+}
+
+/**
+ * `--check-organized`: list the runs of imports `--organize` would rewrite.
+ * Returns true if any file is not organized.
+ */
+function reportUnorganized(results: AnalysisResult[], sources: Map<string, string>): boolean {
+  const engine = new FixEngine();
+  let count = 0;
+
+  for (const result of results) {
+    if (result.error || !engine.canOrganize(result.language)) continue;
+
+    let content: string;
+    try {
+      content = sources.get(result.filePath) ?? fs.readFileSync(result.filePath, 'utf-8');
+    } catch {
+      continue;
+    }
+
+    const index = new LineIndex(content);
+    for (const edit of engine.organizeEdits(content, result.language)) {
+      const { line } = index.positionAt(edit.start);
+      console.log(`[UNORGANIZED] ${result.filePath}:${line}: imports are not grouped and sorted`);
+      count++;
+    }
+  }
+
+  console.log(count === 0
+    ? '[SUCCESS] All imports are organized'
+    : `\n[ERROR] ${count} block(s) of imports are not organized; run with --organize to fix`);
+  return count > 0;
+}
+
+/**
+ * `graph`: print the import graph of the Rust files among `files`, or write it to
+ * `--output`. Other files are left out with a note, since only Rust module trees are
+ * understood; findings don't matter, nothing is analyzed.
+ */
+function exportGraph(files: string[], args: CLIArguments, config: ConfigResolver): void {
+  const rustFiles = files.filter(file => config.resolve(file).language === 'rust');
+  if (rustFiles.length < files.length) {
+    console.error(`[NOTE] graph only covers Rust; skipped ${files.length - rustFiles.length} other file(s)`);
+  }
+
+  const graph = buildImportGraph(rustFiles, { crateRoot: args.crateRoot, granularity: args.collapse, filter: args.graphFilter });
+  const output = args.graphFormat === 'graphml' ? formatGraphML(graph) : formatDot(graph);
+
+  if (args.output) {
+    fs.writeFileSync(args.output, output);
+    console.error(`Graph written to ${args.output}`);
+  } else {
+    process.stdout.write(output);
+  }
+}
+
+/**
+ * `rename`: rewrite the paths to `--from` in the Rust files among `files` to `--to`,
+ * or with `--dry-run` print the changes as a unified diff. Files are only written
+ * when the git working tree is clean, or with `--allow-dirty`, so the rewrite can be
+ * reviewed and undone on its own. Returns whether it refused to.
+ */
+function runRename(files: string[], args: CLIArguments, config: ConfigResolver): boolean {
+  const log = args.dryRun ? console.error : console.log;
+  if (!args.dryRun && !args.allowDirty) {
+    const dirty = dirtyFiles();
+    if (dirty === undefined) {
+      console.error('[ERROR] rename rewrites files in place and needs a git repository to undo it with; pass --allow-dirty to rewrite them anyway');
+      return true;
+    }
+    if (dirty.length > 0) {
+      const listed = dirty.slice(0, 5).join(', ') + (dirty.length > 5 ? ` and ${dirty.length - 5} more` : '');
+      console.error(`[ERROR] rename rewrites files in place, but the working tree has uncommitted changes (${listed}); commit or stash them first, or pass --allow-dirty`);
+      return true;
+    }
+  }
+
+  const rustFiles = files.filter(file => config.resolve(file).language === 'rust');
+  if (rustFiles.length < files.length) {
+    console.error(`[NOTE] rename only covers Rust; skipped ${files.length - rustFiles.length} other file(s)`);
+  }
+
+  const renamed = renameModulePath(rustFiles, args.renameFrom!, args.renameTo!, { crateRoot: args.crateRoot });
+  let total = 0;
+  for (const file of renamed) {
+    const display = displayPath(file.filePath);
+    total += file.paths;
+    if (args.dryRun) {
+      process.stdout.write(createUnifiedDiff(`a/${display}`, `b/${display}`, file.before, file.after));
+    } else {
+      fs.writeFileSync(file.filePath, file.after);
+    }
+    log(`[RENAMED] ${display}: ${file.paths} path(s)`);
+  }
+
+  log(args.dryRun
+    ? `\n${total} path(s) in ${renamed.length} file(s) would be rewritten`
+    : `\n[SUCCESS] Rewrote ${total} path(s) in ${renamed.length} file(s)`);
+  return false;
+}
+
+/**
+ * `stats`: print import metrics of the analyzed Rust files among `files` as a table,
+ * or as JSON with `--format=json`, or write them to `--output`
+ */
+function exportStats(files: string[], results: AnalysisResult[], args: CLIArguments, config: ConfigResolver): void {
+  const rustFiles = files.filter(file => config.resolve(file).language === 'rust');
+  if (rustFiles.length < files.length) {
+    console.error(`[NOTE] stats only covers Rust; skipped ${files.length - rustFiles.length} other file(s)`);
+  }
+
+  const stats = buildImportStats(results, { crateRoot: args.crateRoot });
+  const output = args.format === 'json' ? JSON.stringify(stats, null, 2) + '\n' : formatStatsText(stats);
+
+  if (args.output) {
+    fs.writeFileSync(args.output, output);
+    console.error(`Stats written to ${args.output}`);
+  } else {
+    process.stdout.write(output);
+  }
+}
+
 async function generateAnalyticsReport(
   files: string[],
   results: any[],
@@ -214,12 +1001,18 @@ async function generateAnalyticsReport(
       '.tsx': 'TypeScript React',
       '.js': 'JavaScript',
       '.jsx': 'JavaScript React',
+      '.mjs': 'JavaScript',
+      '.cjs': 'JavaScript',
       '.py': 'Python',
       '.java': 'Java',
       '.go': 'Go',
       '.rs': 'Rust',
       '.cpp': 'C++',
+      '.cc': 'C++',
+      '.cxx': 'C++',
+      '.hpp': 'C++',
       '.c': 'C',
+      '.h': 'C',
     };
     const language = languageMap[ext] || 'Unknown';
 
@@ -300,8 +1093,8 @@ function countImportsInFile(filePath: string): number {
     const ext = path.extname(filePath);
 
     // Different import patterns for different languages
-    if (['.ts', '.tsx', '.js', '.jsx'].includes(ext)) {
-      return (content.match(/^import\s+/gm) || []).length;
+    if (['.ts', '.tsx', '.js', '.jsx', '.mjs', '.cjs'].includes(ext)) {
+      return (content.match(/^import\s+|\brequire\(['"]/gm) || []).length;
     } else if (ext === '.py') {
       return (content.match(/^(import\s+|from\s+.*\s+import\s+)/gm) || []).length;
     } else if (ext === '.java') {
@@ -310,8 +1103,8 @@ function countImportsInFile(filePath: string): number {
       return (content.match(/^import\s+/gm) || []).length;
     } else if (ext === '.rs') {
       return (content.match(/^use\s+/gm) || []).length;
-    } else if (['.cpp', '.c'].includes(ext)) {
-      return (content.match(/^#include\s+/gm) || []).length;
+    } else if (['.c', '.h', '.cc', '.cpp', '.cxx', '.hpp'].includes(ext)) {
+      return (content.match(/^\s*#\s*include\s*[<"]/gm) || []).length;
     }
 
     return 0;
@@ -321,10 +1114,7 @@ function countImportsInFile(filePath: string): number {
 }
 
 function showVersion() {
-  const packageJson = JSON.parse(
-    fs.readFileSync(path.join(__dirname, '../../package.json'), 'utf-8')
-  );
-  console.log(`ImportLens CLI v${packageJson.version}`);
+  console.log(`ImportLens CLI v${toolVersion()}`);
 }
 
 // Run CLI
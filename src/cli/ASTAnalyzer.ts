@@ -2,6 +2,20 @@ import * as parser from '@babel/parser';
 // @ts-ignore - @babel/traverse doesn't have built-in types
 import traverse from '@babel/traverse';
 import * as t from '@babel/types';
+import { DiagnosticSink, printDiagnostic } from './CLIAnalyzer';
+
+// Node types whose contents are only seen by the type checker
+const TYPE_CONTEXTS = new Set([
+  'TSTypeAnnotation',
+  'TSTypeAliasDeclaration',
+  'TSInterfaceDeclaration',
+  'TSExpressionWithTypeArguments',
+  'TSInterfaceHeritage',
+  'TSClassImplements',
+  'TSTypeParameterInstantiation',
+  'TSTypeParameterDeclaration',
+  'TSDeclareFunction',
+]);
 
 /**
  * AST-based analyzer for TypeScript/JavaScript
@@ -9,14 +23,23 @@ import * as t from '@babel/types';
  */
 export class ASTAnalyzer {
   /**
-   * Analyze TypeScript/JavaScript file using AST
+   * Analyze TypeScript/JavaScript file using AST.
+   * ES imports and CommonJS `require` assignments are checked; uses in type
+   * positions are tracked apart from runtime uses, so a value import that is
+   * only referenced by types is reported in `typeOnlySpecifiers`. A file that
+   * doesn't parse has no findings, and the parse error goes to `diagnose`.
    */
-  analyzeTypeScriptFile(code: string, filePath: string): {
+  analyzeTypeScriptFile(code: string, filePath: string, diagnose: DiagnosticSink = printDiagnostic): {
     imports: ImportInfo[];
     unusedImports: UnusedImport[];
   } {
     const imports: ImportInfo[] = [];
-    const usedIdentifiers = new Set<string>();
+    const requires: ImportInfo[] = [];
+    const runtimeIdentifiers = new Set<string>();
+    const typeIdentifiers = new Set<string>();
+    // Binding patterns of `const x = require('y')`, which declare rather than use names
+    const requireBindings = new Set<any>();
+    let hasJSX = false;
 
     try {
       // Parse with TypeScript and JSX support
@@ -32,13 +55,21 @@ export class ASTAnalyzer {
         errorRecovery: true
       });
 
-      // First pass: Collect all imports
+      // First pass: Collect all imports and require assignments
       traverse(ast, {
         ImportDeclaration: (path: any) => {
           const importInfo = this.extractImportInfo(path.node);
           if (importInfo) {
             imports.push(importInfo);
           }
+        },
+
+        VariableDeclarator: (path: any) => {
+          const requireInfo = this.extractRequireInfo(path.node);
+          if (requireInfo) {
+            requires.push(requireInfo);
+            requireBindings.add(path.node.id);
+          }
         }
       });
 
@@ -50,31 +81,53 @@ export class ASTAnalyzer {
             return;
           }
 
-          // Skip if it's a property key (not a reference)
-          if (path.key === 'key' && t.isObjectProperty(path.parent)) {
+          if (requireBindings.has(path.node) || path.findParent((p: any) => requireBindings.has(p.node))) {
             return;
           }
 
-          // Skip if it's a property name in member expression
-          if (t.isMemberExpression(path.parent) && path.parent.property === path.node) {
+          if (!this.isReference(path)) {
             return;
           }
 
-          usedIdentifiers.add(path.node.name);
+          if (this.isTypePosition(path)) {
+            typeIdentifiers.add(path.node.name);
+          } else {
+            runtimeIdentifiers.add(path.node.name);
+          }
         },
 
-        // JSX usage
+        // JSX usage: component names (`<Button />`) and member roots (`<UI.Button />`)
         JSXIdentifier: (path: any) => {
-          usedIdentifiers.add(path.node.name);
+          const parent = path.parent;
+          const isElementName = (t.isJSXOpeningElement(parent) || t.isJSXClosingElement(parent)) && parent.name === path.node;
+          const isMemberRoot = t.isJSXMemberExpression(parent) && parent.object === path.node;
+
+          // Lowercase element names are intrinsic elements like <div>
+          if (isMemberRoot || (isElementName && !/^[a-z]/.test(path.node.name))) {
+            runtimeIdentifiers.add(path.node.name);
+          }
+        },
+
+        JSXElement: () => {
+          hasJSX = true;
+        },
+
+        JSXFragment: () => {
+          hasJSX = true;
         }
       });
 
+      // The classic JSX runtime compiles elements to calls on `React` (or the `@jsx` pragma)
+      if (hasJSX && !/@jsxRuntime\s+automatic\b/.test(code)) {
+        runtimeIdentifiers.add(this.jsxPragma(code));
+      }
+
       // Determine unused imports
-      const unusedImports = this.findUnusedImports(imports, usedIdentifiers, code);
+      const unusedImports = this.findUnusedImports([...imports, ...requires], runtimeIdentifiers, typeIdentifiers);
 
       return { imports, unusedImports };
     } catch (error) {
-      console.error(`AST parse error for ${filePath}:`, error);
+      diagnose(`AST parse error for ${filePath}: ${error instanceof Error ? error.message : String(error)}`);
       // Fallback to empty results on parse error
       return { imports: [], unusedImports: [] };
     }
@@ -86,6 +139,8 @@ export class ASTAnalyzer {
   private extractImportInfo(node: any): ImportInfo | null {
     const source = node.source.value;
     const line = node.loc?.start.line || 0;
+    const column = (node.loc?.start.column ?? 0) + 1;
+    const isTypeOnly = node.importKind === 'type';
     const specifiers: ImportSpecifier[] = [];
 
     for (const spec of node.specifiers) {
@@ -93,19 +148,22 @@ export class ASTAnalyzer {
         specifiers.push({
           type: 'default',
           imported: 'default',
-          local: spec.local.name
+          local: spec.local.name,
+          isTypeOnly
         });
       } else if (t.isImportNamespaceSpecifier(spec)) {
         specifiers.push({
           type: 'namespace',
           imported: '*',
-          local: spec.local.name
+          local: spec.local.name,
+          isTypeOnly
         });
       } else if (t.isImportSpecifier(spec)) {
         specifiers.push({
           type: 'named',
           imported: t.isIdentifier(spec.imported) ? spec.imported.name : spec.imported.value,
-          local: spec.local.name
+          local: spec.local.name,
+          isTypeOnly: isTypeOnly || spec.importKind === 'type'
         });
       }
     }
@@ -114,21 +172,129 @@ export class ASTAnalyzer {
     const isSideEffect = specifiers.length === 0;
 
     return {
+      kind: 'import',
       source,
       line,
+      column,
       specifiers,
       isSideEffect,
+      isTypeOnly,
       raw: ''  // Will be filled from actual source
     };
   }
 
+  /**
+   * Extract bindings from `const x = require('y')`, `const { a, b: c } = require('y')`
+   * and `const x = require('y').member`
+   */
+  private extractRequireInfo(node: any): ImportInfo | null {
+    let init = node.init;
+    let member: string | undefined;
+    if (t.isMemberExpression(init) && !init.computed && t.isIdentifier(init.property)) {
+      member = init.property.name;
+      init = init.object;
+    }
+
+    const isRequire = t.isCallExpression(init) &&
+      t.isIdentifier(init.callee, { name: 'require' }) &&
+      init.arguments.length === 1 &&
+      t.isStringLiteral(init.arguments[0]);
+    if (!isRequire) return null;
+
+    const specifiers: ImportSpecifier[] = [];
+    if (t.isIdentifier(node.id)) {
+      specifiers.push({
+        type: member ? 'named' : 'namespace',
+        imported: member ?? '*',
+        local: node.id.name,
+        isTypeOnly: false
+      });
+    } else if (t.isObjectPattern(node.id) && !member) {
+      for (const prop of node.id.properties) {
+        if (t.isObjectProperty(prop) && t.isIdentifier(prop.value)) {
+          specifiers.push({
+            type: 'named',
+            imported: t.isIdentifier(prop.key) ? prop.key.name : prop.value.name,
+            local: prop.value.name,
+            isTypeOnly: false
+          });
+        } else if (t.isRestElement(prop) && t.isIdentifier(prop.argument)) {
+          specifiers.push({ type: 'namespace', imported: '*', local: prop.argument.name, isTypeOnly: false });
+        }
+      }
+    }
+
+    if (specifiers.length === 0) return null;
+
+    return {
+      kind: 'require',
+      source: init.arguments[0].value,
+      line: node.loc?.start.line || 0,
+      column: (node.loc?.start.column ?? 0) + 1,
+      specifiers,
+      isSideEffect: false,
+      isTypeOnly: false,
+      raw: ''
+    };
+  }
+
+  /**
+   * False for identifiers that name something rather than reference a binding:
+   * property keys, non-computed member properties, qualified type names and labels
+   */
+  private isReference(path: any): boolean {
+    const parent = path.parent;
+    const key = path.key;
+
+    if (key === 'key' && !parent.computed && (
+      t.isObjectProperty(parent) || t.isObjectMethod(parent) ||
+      t.isClassProperty(parent) || t.isClassMethod(parent) ||
+      t.isTSPropertySignature(parent) || t.isTSMethodSignature(parent)
+    )) {
+      return false;
+    }
+
+    if ((t.isMemberExpression(parent) || t.isOptionalMemberExpression(parent)) && key === 'property' && !parent.computed) {
+      return false;
+    }
+
+    if (t.isTSQualifiedName(parent) && key === 'right') return false;
+    if (t.isTSEnumMember(parent) && key === 'id') return false;
+    if (t.isExportSpecifier(parent) && key === 'exported') return false;
+    if (key === 'label') return false;
+
+    return true;
+  }
+
+  /**
+   * True inside type annotations, type declarations, `implements` clauses,
+   * type arguments and `export type { ... }`
+   */
+  private isTypePosition(path: any): boolean {
+    const parent = path.parent;
+    if (t.isExportSpecifier(parent)) {
+      const declaration = path.parentPath.parent;
+      return parent.exportKind === 'type' || declaration?.exportKind === 'type';
+    }
+
+    return !!path.findParent((p: any) => t.isTSType(p.node) || TYPE_CONTEXTS.has(p.node.type));
+  }
+
+  /**
+   * Root identifier of an `@jsx h` pragma comment, or React by default
+   */
+  private jsxPragma(code: string): string {
+    const match = code.match(/@jsx\s+([A-Za-z_$][\w$]*)/);
+    return match ? match[1] : 'React';
+  }
+
   /**
    * Find unused imports by comparing declared imports with used identifiers
    */
   private findUnusedImports(
     imports: ImportInfo[],
-    usedIdentifiers: Set<string>,
-    code: string
+    runtimeIdentifiers: Set<string>,
+    typeIdentifiers: Set<string>
   ): UnusedImport[] {
     const unused: UnusedImport[] = [];
 
@@ -139,21 +305,30 @@ export class ASTAnalyzer {
       }
 
       const unusedSpecifiers: string[] = [];
+      const typeOnlySpecifiers: string[] = [];
 
       for (const spec of imp.specifiers) {
         const localName = spec.local;
 
         // Check if this imported identifier is used anywhere
-        if (!usedIdentifiers.has(localName)) {
+        if (runtimeIdentifiers.has(localName)) {
+          continue;
+        }
+        if (!typeIdentifiers.has(localName)) {
           unusedSpecifiers.push(localName);
+        } else if (!spec.isTypeOnly) {
+          typeOnlySpecifiers.push(localName);
         }
       }
 
-      if (unusedSpecifiers.length > 0) {
+      if (unusedSpecifiers.length > 0 || typeOnlySpecifiers.length > 0) {
         unused.push({
+          kind: imp.kind,
           line: imp.line,
+          column: imp.column,
           source: imp.source,
           unusedSpecifiers,
+          typeOnlySpecifiers,
           allSpecifiers: imp.specifiers.map(s => s.local)
         });
       }
@@ -209,7 +384,9 @@ export class ASTAnalyzer {
     const grouped = new Map<string, ImportInfo>();
 
     for (const imp of imports) {
-      const existing = grouped.get(imp.source);
+      // `import type` declarations are merged separately from value imports
+      const key = `${imp.isTypeOnly ? 'type:' : ''}${imp.source}`;
+      const existing = grouped.get(key);
 
       if (existing) {
         // Merge specifiers
@@ -221,12 +398,12 @@ export class ASTAnalyzer {
           }
         }
 
-        grouped.set(imp.source, {
+        grouped.set(key, {
           ...existing,
           specifiers: mergedSpecifiers
         });
       } else {
-        grouped.set(imp.source, imp);
+        grouped.set(key, imp);
       }
     }
 
@@ -298,21 +475,27 @@ export class ASTAnalyzer {
       // Sort named imports alphabetically
       const sorted = namedSpecs.sort((a, b) => a.local.localeCompare(b.local));
       const namedStr = sorted
-        .map(s => s.imported === s.local ? s.local : `${s.imported} as ${s.local}`)
+        .map(s => {
+          const name = s.imported === s.local ? s.local : `${s.imported} as ${s.local}`;
+          return s.isTypeOnly && !imp.isTypeOnly ? `type ${name}` : name;
+        })
         .join(', ');
       parts.push(`{ ${namedStr} }`);
     }
 
-    return `import ${parts.join(', ')} from '${imp.source}';`;
+    return `import ${imp.isTypeOnly ? 'type ' : ''}${parts.join(', ')} from '${imp.source}';`;
   }
 }
 
 // Type definitions
 export interface ImportInfo {
+  kind: 'import' | 'require';
   source: string;
   line: number;
+  column: number;
   specifiers: ImportSpecifier[];
   isSideEffect: boolean;
+  isTypeOnly: boolean;  // `import type { ... }`
   raw: string;
 }
 
@@ -320,12 +503,16 @@ export interface ImportSpecifier {
   type: 'default' | 'namespace' | 'named';
   imported: string;  // Original name in module
   local: string;     // Local name in file
+  isTypeOnly: boolean;  // `import type` or inline `type` specifier
 }
 
 export interface UnusedImport {
+  kind: 'import' | 'require';
   line: number;
+  column: number;
   source: string;
-  unusedSpecifiers: string[];
+  unusedSpecifiers: string[];     // Not referenced at all
+  typeOnlySpecifiers: string[];   // Value imports referenced only by types
   allSpecifiers: string[];
 }
 
@@ -0,0 +1,162 @@
+import * as crypto from 'crypto';
+import * as fs from 'fs';
+import * as path from 'path';
+import { CLIArguments } from './ArgumentParser';
+import { UnusedImport } from './CLIAnalyzer';
+import { compareStrings } from './Report';
+import { toolVersion } from './Version';
+
+/** Default location of the cache, relative to the working directory */
+export const DEFAULT_CACHE_DIR = '.importlens-cache';
+
+const CACHE_FILE = 'results.json';
+
+// Oldest entries are dropped beyond this, so edits over time don't grow the file forever
+const MAX_ENTRIES = 50000;
+
+// Options that only affect file selection or what happens to the results, never the findings
+const NON_ANALYSIS_OPTIONS = new Set<keyof CLIArguments>([
+  'check', 'fix', 'dryRun', 'interactive', 'format', 'config', 'exclude', 'hidden', 'jobs', 'files',
+  'help', 'version', 'exitOnError', 'baseline', 'baselineGenerate', 'baselineUpdate',
+  'baselineCheck', 'analytics', 'analyticsOutput', 'cache', 'cacheDir', 'command', 'exec',
+  'stdin', 'stdinFilename', 'filesFrom', 'nullSeparated', 'expandGlobs', 'failOn', 'maxWarnings', 'output', 'outputPatch',
+  'diffBase', 'diff', 'diffScope', 'crateRoot', 'graphFormat', 'collapse', 'graphFilter',
+  'groupBy', 'top', 'verbose', 'color', 'quiet', 'staged', 'fixStaged', 'checkPrelude', 'edition', 'checkTestOnly', 'renameFrom', 'renameTo', 'allowDirty',
+  'maxFileSize', 'includeLargeFiles',
+]);
+
+/**
+ * Findings for one file, as stored in the cache
+ */
+export interface CachedResult {
+  language: string;
+  unusedImports: UnusedImport[];
+}
+
+/**
+ * Cache file format
+ */
+interface CacheFile {
+  /** ImportLens version that wrote the cache */
+  version: string;
+  /** Hash of the analysis options in effect */
+  config: string;
+  entries: Record<string, CachedResult>;
+}
+
+/**
+ * On-disk cache of per-file findings keyed by a hash of the file content, so
+ * unchanged files are not parsed again on the next run.
+ *
+ * The whole cache is dropped when the ImportLens version or the analysis options
+ * change. A cache file that can't be read or parsed is silently rebuilt.
+ */
+export class AnalysisCache {
+  private entries = new Map<string, CachedResult>();
+  private dirty = false;
+
+  constructor(private dir: string, private config: string, private version = toolVersion()) {
+    this.load();
+  }
+
+  /**
+   * Cache for a CLI run: `--cache-dir` (or the default directory), keyed on the analysis options
+   */
+  static forArgs(args: CLIArguments): AnalysisCache {
+    return new AnalysisCache(args.cacheDir ?? DEFAULT_CACHE_DIR, configHash(args));
+  }
+
+  /**
+   * Delete a cache directory. Returns false if there was nothing to delete.
+   */
+  static clear(dir: string = DEFAULT_CACHE_DIR): boolean {
+    if (!fs.existsSync(dir)) return false;
+    fs.rmSync(dir, { recursive: true, force: true });
+    return true;
+  }
+
+  get size(): number {
+    return this.entries.size;
+  }
+
+  get(content: string, language: string): CachedResult | undefined {
+    const key = cacheKey(content, language);
+    const entry = this.entries.get(key);
+    if (entry) {
+      // Move to the back so recently used entries survive eviction
+      this.entries.delete(key);
+      this.entries.set(key, entry);
+    }
+    return entry;
+  }
+
+  set(content: string, language: string, result: CachedResult): void {
+    this.entries.set(cacheKey(content, language), result);
+    this.dirty = true;
+  }
+
+  /**
+   * Write the cache back if anything was added. Failures are ignored: the cache is only an optimization.
+   */
+  save(): void {
+    if (!this.dirty) return;
+
+    for (const key of this.entries.keys()) {
+      if (this.entries.size <= MAX_ENTRIES) break;
+      this.entries.delete(key);
+    }
+
+    const file: CacheFile = {
+      version: this.version,
+      config: this.config,
+      entries: Object.fromEntries(this.entries),
+    };
+
+    try {
+      fs.mkdirSync(this.dir, { recursive: true });
+      // Write then rename, so an interrupted run never leaves a truncated cache behind
+      const target = path.join(this.dir, CACHE_FILE);
+      const temp = `${target}.${process.pid}.tmp`;
+      fs.writeFileSync(temp, JSON.stringify(file));
+      fs.renameSync(temp, target);
+      this.dirty = false;
+    } catch {
+      // Read-only checkout or similar; analysis results are unaffected
+    }
+  }
+
+  private load(): void {
+    let file: CacheFile;
+    try {
+      file = JSON.parse(fs.readFileSync(path.join(this.dir, CACHE_FILE), 'utf-8'));
+    } catch {
+      return;
+    }
+
+    if (!file || file.version !== this.version || file.config !== this.config ||
+        typeof file.entries !== 'object' || file.entries === null) {
+      return;
+    }
+
+    for (const [key, entry] of Object.entries(file.entries)) {
+      if (entry && typeof entry.language === 'string' && Array.isArray(entry.unusedImports)) {
+        this.entries.set(key, entry);
+      }
+    }
+  }
+}
+
+/**
+ * Hash of every option that can change the findings for a file
+ */
+export function configHash(args: CLIArguments): string {
+  const options = Object.entries(args)
+    .filter(([key]) => !NON_ANALYSIS_OPTIONS.has(key as keyof CLIArguments))
+    // Code-unit order: a locale-aware sort could hash the same options differently on another machine
+    .sort(([a], [b]) => compareStrings(a, b));
+  return crypto.createHash('sha256').update(JSON.stringify(options)).digest('hex');
+}
+
+function cacheKey(content: string, language: string): string {
+  return crypto.createHash('sha256').update(language).update('\0').update(content).digest('hex');
+}
@@ -0,0 +1,766 @@
+import * as fs from 'fs';
+import { ASTAnalyzer } from './ASTAnalyzer';
+import { TreeSitterAnalyzer } from './TreeSitterAnalyzer';
+import { GlobAnalysis, RustAnalyzer } from './RustAnalyzer';
+import { PythonAnalyzer } from './PythonAnalyzer';
+import { GoAnalyzer } from './GoAnalyzer';
+import { CppAnalyzer } from './CppAnalyzer';
+import { FixEngine } from './FixEngine';
+import { LineIndex } from './LineIndex';
+import { findManifest, RustCrate } from './RustCrate';
+import { parseCfgOption } from './RustCfg';
+import { isEdition, manifestEdition, RUST_EDITIONS, RustEdition } from './RustPrelude';
+import { recoverRustSyntax, SkippedRegion } from './RustRecovery';
+import { BannedImport, Config, ConfigResolver, EffectiveConfig, Severity } from './Config';
+import { LANGUAGE_NAMES, normalizeLanguage } from './Languages';
+import { RunOptions } from './Progress';
+import { buildReport, Report } from './Report';
+import { AnalysisResult, ConfidenceLevel, DEFAULT_CONFIDENCE_REASONS, DEFAULT_SEVERITIES, DiagnosticSink, FINDING_KINDS, FindingKind, FindingSeverity, isRuleKind, meetsConfidence, SourceSpan, UnusedImport } from './CLIAnalyzer';
+import { builtinRules, createFileContext, Rule, runRule } from './Rules';
+
+/** Offsets of an item in its source, as found in `Finding.byte_span` and suggested fixes */
+export type Span = SourceSpan;
+
+/** Languages the analyzer has a parser or heuristics for, including the JSX variants */
+const SUPPORTED_LANGUAGES = new Set([
+  'typescript', 'typescriptreact', 'javascript', 'javascriptreact', 'python', 'java', 'go', 'rust', 'cpp', 'c',
+]);
+
+/** File name reported for sources analyzed without a path */
+const SOURCE_NAME = '<source>';
+
+const KIND_ADJECTIVES: Record<FindingKind, string> = {
+  'unused-import': 'unused',
+  'duplicate-import': 'duplicate',
+  'redundant-import': 'redundant',
+  'type-only-import': 'runtime',
+  'useless-suppression': 'useless',
+  'unused-reexport': 'unused re-exported',
+  'unused-dependency': 'unused',
+  'shadowed-import': 'shadowed',
+  'redundant-alias': 'redundantly aliased',
+  'over-qualified-path': 'over-qualified',
+  'banned-import': 'banned',
+  'unnecessary-import': 'unnecessary',
+  'test-only-import': 'test-only',
+};
+
+/** Finding severity for each `importlens.toml` level that reports findings */
+const FINDING_SEVERITIES: Record<Exclude<Severity, 'off'>, FindingSeverity> = {
+  error: 'error',
+  warn: 'warning',
+  info: 'info',
+};
+
+/**
+ * Why an analysis failed:
+ * - `parse`: the source or an `importlens.toml` could not be parsed
+ * - `io`: a file could not be read
+ * - `unsupported-language`: the language is unknown or could not be detected
+ * - `cancelled`: the run's `CancellationToken` was cancelled
+ */
+export type ImportLensErrorKind = 'parse' | 'io' | 'unsupported-language' | 'cancelled';
+
+/**
+ * Error thrown by `Analyzer` and `AnalyzerBuilder`. Check `kind` rather than the message,
+ * which is meant for people and may change.
+ */
+export class ImportLensError extends Error {
+  readonly kind: ImportLensErrorKind;
+  /** File being analyzed, if any */
+  readonly filePath?: string;
+
+  constructor(kind: ImportLensErrorKind, message: string, filePath?: string) {
+    super(message);
+    this.name = 'ImportLensError';
+    this.kind = kind;
+    this.filePath = filePath;
+  }
+}
+
+/** The error a run cancelled after `done` of its `total` files rejects with */
+export function cancelledError(done: number, total: number): ImportLensError {
+  return new ImportLensError('cancelled', `Analysis cancelled after ${done} of ${total} files`);
+}
+
+/**
+ * Settings of an `Analyzer`; see the `AnalyzerBuilder` methods of the same names
+ */
+export interface AnalyzerOptions {
+  language?: string;
+  kinds?: FindingKind[];
+  config?: Config;
+  configResolver?: ConfigResolver;
+  minConfidence?: ConfidenceLevel;
+  strictMacros?: boolean;
+  reportUnusedSuppressions?: boolean;
+  cfg?: string[];
+  checkDoctests?: boolean;
+  qualifiedPathThreshold?: number;
+  checkPrelude?: boolean;
+  edition?: RustEdition;
+  checkTestOnly?: boolean;
+  rules?: Rule[];
+}
+
+/**
+ * Builds an `Analyzer`. Every setting is optional:
+ *
+ * ```ts
+ * const analyzer = Analyzer.builder()
+ *   .language('rust')
+ *   .kinds(['unused-import', 'duplicate-import'])
+ *   .build();
+ * const report = analyzer.analyzeSource('use std::io::Read;\nfn main() {}\n');
+ * ```
+ *
+ * Invalid values throw an `ImportLensError` right away rather than on first use.
+ */
+export class AnalyzerBuilder {
+  private options: AnalyzerOptions = {};
+
+  /**
+   * Analyze everything as this language (a `--language` name or alias such as `ts`
+   * or `rs`) instead of detecting it from file extensions. Required for
+   * `analyzeSource` without a file path.
+   */
+  language(name: string): this {
+    const language = normalizeLanguage(name);
+    if (!language) {
+      throw new ImportLensError('unsupported-language', `Invalid language: ${name}. Must be one of: ${LANGUAGE_NAMES.join(', ')}`);
+    }
+    this.options.language = language;
+    return this;
+  }
+
+  /** Only report these finding kinds, on top of any `kinds` set by config */
+  kinds(kinds: FindingKind[]): this {
+    const unknown = kinds.find(kind => !(FINDING_KINDS as readonly string[]).includes(kind));
+    if (unknown !== undefined) {
+      throw new ImportLensError('parse', `Unknown finding kind '${unknown}'. Must be one of: ${FINDING_KINDS.join(', ')}`);
+    }
+    this.options.kinds = [...kinds];
+    return this;
+  }
+
+  /**
+   * Settings applied on top of the `importlens.toml` files found above each analyzed
+   * path. Sources analyzed without a path only get these.
+   */
+  config(config: Config): this {
+    this.options.config = config;
+    return this;
+  }
+
+  /** Resolve per-file settings with an existing resolver, e.g. one shared with file discovery */
+  configResolver(resolver: ConfigResolver): this {
+    this.options.configResolver = resolver;
+    return this;
+  }
+
+  /** Drop findings below this confidence (default: `medium`) */
+  minConfidence(level: ConfidenceLevel): this {
+    this.options.minConfidence = level;
+    return this;
+  }
+
+  /** Rust: don't count identifiers inside macro bodies as uses */
+  strictMacros(enabled = true): this {
+    this.options.strictMacros = enabled;
+    return this;
+  }
+
+  /** Rust: report `allow(unused_imports)` and `importlens: ignore` markers that only cover used imports */
+  reportUnusedSuppressions(enabled = true): this {
+    this.options.reportUnusedSuppressions = enabled;
+    return this;
+  }
+
+  /** Rust: also report unused imports inside the code blocks of doc comments */
+  checkDoctests(enabled = true): this {
+    this.options.checkDoctests = enabled;
+    return this;
+  }
+
+  /**
+   * Rust: report paths written out in full `threshold` times or more in a module
+   * without an import, as `over-qualified-path` findings; `false` turns it off
+   */
+  checkQualifiedPaths(threshold: number | false = 3): this {
+    if (threshold !== false && (!Number.isInteger(threshold) || threshold < 1)) {
+      throw new ImportLensError('parse', `Invalid qualified path threshold: ${threshold}. Must be a positive integer`);
+    }
+    this.options.qualifiedPathThreshold = threshold === false ? undefined : threshold;
+    return this;
+  }
+
+  /** Rust: report imports of what the prelude already provides, as `unnecessary-import` findings */
+  checkPrelude(enabled = true): this {
+    this.options.checkPrelude = enabled;
+    return this;
+  }
+
+  /**
+   * Rust: the edition whose prelude `checkPrelude` checks against. By default, or
+   * with `undefined`, it is read from the nearest `Cargo.toml` of each file.
+   */
+  edition(edition: RustEdition | undefined): this {
+    if (edition !== undefined && !isEdition(edition)) {
+      throw new ImportLensError('parse', `Invalid edition: ${edition}. Must be one of: ${RUST_EDITIONS.join(', ')}`);
+    }
+    this.options.edition = edition;
+    return this;
+  }
+
+  /**
+   * Rust: report imports only test code uses, as `test-only-import` findings. Files
+   * whose config gives the kind a severity other than `off` are checked regardless.
+   */
+  checkTestOnly(enabled = true): this {
+    this.options.checkTestOnly = enabled;
+    return this;
+  }
+
+  /** Rust: pin cfg options like rustc's `--cfg`, e.g. `unix` or `feature="serde"` */
+  cfg(options: string[]): this {
+    try {
+      this.options.cfg = options.map(parseCfgOption);
+    } catch (error) {
+      throw new ImportLensError('parse', error instanceof Error ? error.message : String(error));
+    }
+    return this;
+  }
+
+  /**
+   * Run a custom rule on every file, after the built-in ones. Its `id` must be
+   * namespaced (`acme/banned-crate`) and not taken by another rule.
+   */
+  rule(rule: Rule): this {
+    if (!isRuleKind(rule.id)) {
+      throw new ImportLensError('parse', `Invalid rule id '${rule.id}'. Must be namespaced, like 'acme/banned-crate'`);
+    }
+    if (this.options.rules?.some(other => other.id === rule.id)) {
+      throw new ImportLensError('parse', `Duplicate rule id '${rule.id}'`);
+    }
+    this.options.rules = [...(this.options.rules ?? []), rule];
+    return this;
+  }
+
+  build(): Analyzer {
+    return new Analyzer({ ...this.options });
+  }
+}
+
+/**
+ * Library entry point: analyzes sources or files and returns reports in the same
+ * stable schema as `--format=json`. The CLI runs every file through an `Analyzer`,
+ * so both always agree.
+ *
+ * Every check is a `Rule` run on a `FileContext` of the file: the built-in
+ * `unused-imports` and `duplicate-imports` rules, then the builder's custom rules.
+ *
+ * Findings that need other files (`--check-reexports`, `--check-dependencies`) are
+ * only computed by the CLI.
+ */
+export class Analyzer {
+  private astAnalyzer = new ASTAnalyzer();
+  private treeSitterAnalyzer = new TreeSitterAnalyzer();
+  private rustAnalyzer: RustAnalyzer;
+  private pythonAnalyzer = new PythonAnalyzer();
+  private goAnalyzer = new GoAnalyzer();
+  private cppAnalyzer = new CppAnalyzer();
+  private fixEngine = new FixEngine();
+  private resolver: ConfigResolver;
+  /** The builder's custom rules, run after the built-in ones */
+  private rules: Rule[];
+  /** Edition of each `Cargo.toml` read for `checkPrelude` */
+  private editions = new Map<string, RustEdition | undefined>();
+
+  constructor(private options: AnalyzerOptions = {}) {
+    this.rustAnalyzer = new RustAnalyzer({
+      strictMacros: options.strictMacros,
+      reportUnusedSuppressions: options.reportUnusedSuppressions,
+      cfg: options.cfg,
+      checkDoctests: options.checkDoctests,
+      qualifiedPathThreshold: options.qualifiedPathThreshold,
+    });
+    this.resolver = options.configResolver ?? new ConfigResolver(options.config, options.language);
+    this.rules = options.rules ?? [];
+  }
+
+  static builder(): AnalyzerBuilder {
+    return new AnalyzerBuilder();
+  }
+
+  /**
+   * Analyze in-memory source. With `filePath`, it is analyzed as if it were that
+   * file: the language comes from its extension and the `importlens.toml` files
+   * above it apply. Without one, the builder's `language` is required and findings
+   * are reported for the file `<source>`.
+   */
+  analyzeSource(source: string, filePath?: string): Report {
+    return buildReport([this.analyzeResult(source, filePath)]);
+  }
+
+  /**
+   * Read and analyze a file. Rejects with an `ImportLensError` of kind `io` if the
+   * file can't be read.
+   */
+  async analyzePath(filePath: string): Promise<Report> {
+    return this.analyzeSource(await readSource(filePath), filePath);
+  }
+
+  /**
+   * Read and analyze several files into one report, calling `onProgress` after each.
+   * `cancellation` is checked between files; once it is cancelled the run rejects
+   * with an `ImportLensError` of kind `cancelled`, so an embedder can enforce its
+   * own timeout. Rejects with kind `io` if a file can't be read.
+   */
+  async analyzePaths(filePaths: string[], run: RunOptions = {}): Promise<Report> {
+    const results: AnalysisResult[] = [];
+    for (const filePath of filePaths) {
+      if (run.cancellation?.isCancelled) throw cancelledError(results.length, filePaths.length);
+      results.push(this.analyzeResult(await readSource(filePath), filePath));
+      run.onProgress?.({ done: results.length, total: filePaths.length, filePath });
+    }
+    if (run.cancellation?.isCancelled) throw cancelledError(results.length, filePaths.length);
+    return buildReport(results);
+  }
+
+  private analyzeResult(source: string, filePath?: string): AnalysisResult {
+    const config = this.effectiveConfig(filePath);
+    if (!SUPPORTED_LANGUAGES.has(config.language)) {
+      throw new ImportLensError(
+        'unsupported-language',
+        filePath ? `Unsupported language for ${filePath}` : 'No language set; call language() on the builder or pass a file path',
+        filePath,
+      );
+    }
+
+    let { unusedImports } = this.detect(source, config.language, filePath ?? SOURCE_NAME);
+    const needsRecovery = config.banned.length > 0 || this.options.checkPrelude || this.checksTestOnly(config);
+    const rust = config.language === 'rust' && needsRecovery ? recoverRustSyntax(source) : undefined;
+    if (rust && config.banned.length > 0) {
+      unusedImports.push(...this.findBannedImports(rust.source, config.banned, undefined, undefined, rust.skipped));
+      unusedImports.sort((a, b) => a.line - b.line);
+    }
+    if (rust && this.options.checkPrelude) {
+      unusedImports = this.addUnnecessaryImports(rust.source, unusedImports, filePath, rust.skipped);
+    }
+    if (rust && this.checksTestOnly(config)) {
+      unusedImports = this.addTestOnlyImports(rust.source, unusedImports, rust.skipped);
+    }
+    if (config.language === 'c' || config.language === 'cpp') {
+      unusedImports = this.applyHeaders(source, unusedImports, config.headers, filePath);
+    }
+    return {
+      filePath: filePath ?? SOURCE_NAME,
+      language: config.language,
+      unusedImports: this.filter(unusedImports, config),
+    };
+  }
+
+  /**
+   * Findings for a source before kinds, severities and `minConfidence` are applied,
+   * with byte offsets and suggested fixes filled in. What the analyzers have to say
+   * about the file, such as items skipped or a fallback to heuristics, is returned in
+   * `diagnostics` rather than written to stderr. Throws an
+   * `ImportLensError` of kind `parse` if an analyzer fails.
+   *
+   * With `filter`, this is what `analyzeSource` does; the CLI calls the two steps
+   * separately so it can cache the unfiltered findings.
+   */
+  detect(source: string, language: string, filePath: string): { unusedImports: UnusedImport[]; diagnostics: string[] } {
+    // Buffer analyzer messages so output from different files never interleaves
+    const diagnostics: string[] = [];
+    const diagnose: DiagnosticSink = message => diagnostics.push(message);
+    const rules = [
+      ...builtinRules(context => this.findUnusedImports(context.source, context.language, context.filePath, diagnose)),
+      ...this.rules,
+    ];
+
+    try {
+      const context = createFileContext(source, language, filePath);
+      const unusedImports = rules
+        .flatMap(rule => runRule(rule, context))
+        .map(unused => (unused.kind ? unused : { ...unused, kind: 'unused-import' as const }))
+        .sort((a, b) => a.line - b.line);
+      this.attachFixes(source, language, unusedImports);
+      return { unusedImports, diagnostics };
+    } catch (error) {
+      throw new ImportLensError('parse', error instanceof Error ? error.message : String(error), filePath);
+    }
+  }
+
+  /**
+   * Drop findings below `minConfidence` or of kinds the builder or the file's config
+   * disables, and mark each with its configured or default severity and its confidence.
+   * `kinds` lists only select built-in kinds; a custom rule's findings are dropped
+   * when its severity is `off`, and are warnings by default.
+   */
+  filter(unusedImports: UnusedImport[], config: EffectiveConfig): UnusedImport[] {
+    const minConfidence = this.options.minConfidence ?? 'medium';
+    const { kinds, severity } = config;
+
+    return unusedImports.flatMap(unused => {
+      const kind = unused.kind ?? 'unused-import';
+      const level = severity[kind];
+      const builtin = !isRuleKind(kind);
+      if (!meetsConfidence(unused, minConfidence)) return [];
+      if ((builtin && !kinds.includes(kind)) || level === 'off') return [];
+      if (builtin && this.options.kinds && !this.options.kinds.includes(kind)) return [];
+      return [{
+        ...unused,
+        severity: level ? FINDING_SEVERITIES[level] : builtin ? DEFAULT_SEVERITIES[kind] : 'warning',
+        confidence: unused.confidence ?? 'high',
+        confidenceReason: unused.confidenceReason ?? (builtin ? DEFAULT_CONFIDENCE_REASONS[kind] : 'syntactic'),
+      }];
+    });
+  }
+
+  /**
+   * Settings for `filePath` from its `importlens.toml` files, or from the builder's
+   * `config` alone for sources without a path. Throws an `ImportLensError` of kind
+   * `parse` for a malformed config file.
+   */
+  effectiveConfig(filePath?: string): EffectiveConfig {
+    if (filePath === undefined) {
+      const { config = {}, language = 'unknown' } = this.options;
+      return {
+        sources: [],
+        ignored: false,
+        language,
+        kinds: [...(config.kinds ?? FINDING_KINDS)],
+        severity: { ...config.severity },
+        banned: [...(config.banned ?? [])],
+        headers: { ...config.headers },
+        fix: { enabled: config.fix?.enabled ?? true, kinds: [...(config.fix?.kinds ?? FINDING_KINDS)] },
+      };
+    }
+
+    try {
+      return this.resolver.resolve(filePath);
+    } catch (error) {
+      throw new ImportLensError('parse', error instanceof Error ? error.message : String(error), filePath);
+    }
+  }
+
+  /**
+   * Rust: the unused-import `findings` of `content` that other modules of `crate` use
+   * through the module they are imported in
+   */
+  findCrateUsedImports(content: string, crate: RustCrate, modulePath: string[], findings: UnusedImport[]): UnusedImport[] {
+    return this.rustAnalyzer.findCrateUsedImports(content, crate, modulePath, findings);
+  }
+
+  /**
+   * Rust: imports and uses in `content` of paths that `bans` match, with `crate` and
+   * `modulePath` to resolve relative paths. Banned paths depend on each file's config,
+   * so the CLI finds them after the cache.
+   *
+   * This and the other Rust passes that run after `analyzeFile` take `content` as
+   * `recoverRustSyntax` returns it, with the regions it `skipped`; findings of a file
+   * with skipped regions get `low` confidence.
+   */
+  findBannedImports(content: string, bans: BannedImport[], crate?: RustCrate, modulePath?: string[], skipped?: SkippedRegion[]): UnusedImport[] {
+    const findings = this.rustAnalyzer.findBannedImports(content, bans, crate, modulePath, skipped);
+    this.attachFixes(content, 'rust', findings);
+    return findings;
+  }
+
+  /**
+   * Rust: imports in `content` of what the prelude already provides, in the builder's
+   * `edition` or else that of the nearest `Cargo.toml` of `filePath`. Without either,
+   * only what the prelude of every edition has is reported. Returns `findings` with
+   * them added, in line order. Where `findings` has one for the same import already,
+   * it is kept, unless it is a `low` confidence guess the prelude settles (a trait only
+   * its methods use). The edition may come from another file, so the CLI adds these
+   * after the cache.
+   */
+  addUnnecessaryImports(content: string, findings: UnusedImport[], filePath?: string, skipped?: SkippedRegion[]): UnusedImport[] {
+    let edition = this.options.edition;
+    const manifest = edition === undefined && filePath !== undefined ? findManifest(filePath) : undefined;
+    if (manifest !== undefined) {
+      if (!this.editions.has(manifest)) this.editions.set(manifest, manifestEdition(manifest));
+      edition = this.editions.get(manifest);
+    }
+
+    const unnecessary = this.rustAnalyzer.findUnnecessaryImports(content, edition, skipped);
+    if (unnecessary.length === 0) return findings;
+    const kept = new Set(findings.filter(finding => finding.confidence !== 'low').map(finding => finding.span?.start));
+    const added = unnecessary.filter(finding => !kept.has(finding.span?.start));
+    const replaced = new Set(added.map(finding => finding.span?.start));
+    this.attachFixes(content, 'rust', added);
+
+    return [...findings.filter(finding => finding.span === undefined || !replaced.has(finding.span.start)), ...added]
+      .sort((a, b) => a.line - b.line);
+  }
+
+  /**
+   * Whether `test-only-import` findings are wanted in a file: they are off unless the
+   * builder's `checkTestOnly` or the file's config, with a severity for the kind, turns
+   * them on
+   */
+  checksTestOnly(config: EffectiveConfig): boolean {
+    const level = config.severity['test-only-import'];
+    return this.options.checkTestOnly === true || (level !== undefined && level !== 'off');
+  }
+
+  /**
+   * Rust: imports in `content` that only test code uses, with suggested fixes that move
+   * them into the test module. Returns `findings` with them added, in line order;
+   * imports `findings` already reports are left to those findings. Whether to look
+   * depends on each file's config, so the CLI adds these after the cache.
+   */
+  addTestOnlyImports(content: string, findings: UnusedImport[], skipped?: SkippedRegion[]): UnusedImport[] {
+    const reported = new Set(findings.map(finding => finding.span?.start));
+    const added = this.rustAnalyzer.findTestOnlyImports(content, skipped).filter(finding => !reported.has(finding.span?.start));
+    if (added.length === 0) return findings;
+    this.attachFixes(content, 'rust', added);
+    return [...findings, ...added].sort((a, b) => a.line - b.line);
+  }
+
+  /**
+   * C/C++: `findings` with the includes of `content` checked again against what
+   * `headers`, from `importlens.toml`, adds to the identifiers each header provides.
+   * That depends on each file's config, so the CLI does it after the cache.
+   */
+  applyHeaders(content: string, findings: UnusedImport[], headers: Record<string, string[]>, filePath?: string): UnusedImport[] {
+    if (Object.keys(headers).length === 0) return findings;
+    const includes = this.cppAnalyzer.analyzeFile(content, filePath, headers);
+    this.attachFixes(content, 'cpp', includes);
+    return [...findings.filter(finding => finding.kind !== 'unused-import'), ...includes].sort((a, b) => a.line - b.line);
+  }
+
+  /**
+   * Rust: crate-visible re-exports in `content` that nothing in `crate` refers to,
+   * with suggested fixes
+   */
+  findUnusedReexports(content: string, crate: RustCrate, modulePath: string[], skipped?: SkippedRegion[]): UnusedImport[] {
+    const findings = this.rustAnalyzer.findUnusedReexports(content, crate, modulePath, skipped);
+    this.attachFixes(content, 'rust', findings);
+    return findings;
+  }
+
+  /**
+   * Rust: glob imports in `content` of modules of `crate`, with suggested fixes
+   * for the unused ones
+   */
+  analyzeGlobs(content: string, crate: RustCrate, modulePath: string[], skipped?: SkippedRegion[]): GlobAnalysis {
+    const analysis = this.rustAnalyzer.analyzeGlobs(content, crate, modulePath, skipped);
+    this.attachFixes(content, 'rust', analysis.unused);
+    return analysis;
+  }
+
+  /**
+   * Fill in byte offsets and a standalone suggested fix for findings that carry a span
+   */
+  private attachFixes(content: string, language: string, unusedImports: UnusedImport[]): void {
+    if (!unusedImports.some(u => u.span)) return;
+
+    const lineIndex = new LineIndex(content);
+    const toBytes = (span: SourceSpan): SourceSpan => ({
+      start: lineIndex.byteOffsetAt(span.start),
+      end: lineIndex.byteOffsetAt(span.end),
+    });
+
+    for (const unused of unusedImports) {
+      if (!unused.span) continue;
+      unused.byteSpan = toBytes(unused.span);
+      if (unused.original?.span) {
+        unused.original.byteSpan = toBytes(unused.original.span);
+      }
+
+      // Custom rules only report; what would fix their findings is up to them. Banned imports are used.
+      if (!this.fixEngine.canFix(language) || unused.kind === 'banned-import' || isRuleKind(unused.kind ?? 'unused-import')) continue;
+      const edits = this.fixEngine.computeEdits(content, language, [unused]);
+      if (edits.length === 0) continue;
+
+      unused.suggestedFix = {
+        description: unused.kind === 'redundant-alias'
+          ? `Remove redundant alias \`${content.slice(unused.span.start, unused.span.end)}\``
+          : unused.kind === 'over-qualified-path'
+          ? `Import \`${unused.importPath}\` and shorten its ${unused.qualifiedUses?.length ?? 1} uses`
+          : unused.kind === 'test-only-import'
+          ? `Move test-only import \`${unused.importPath}\` into \`mod ${unused.testModule?.name}\``
+          : `Remove ${KIND_ADJECTIVES[unused.kind ?? 'unused-import']} import \`${unused.importPath ?? unused.symbols.join(', ')}\``,
+        edits: edits.map(edit => ({ ...toBytes(edit), newText: edit.newText })),
+      };
+    }
+  }
+
+  /**
+   * Find unused imports in file content.
+   * Priority: dedicated parsers (Babel for TS/JS, Rust use trees, Python, Go and C/C++ tokenizers) > Tree-sitter > regex heuristics.
+   */
+  private findUnusedImports(
+    content: string,
+    language: string,
+    filePath: string,
+    diagnose: DiagnosticSink
+  ): UnusedImport[] {
+    const lines = content.split('\n');
+
+    switch (language) {
+      case 'typescript':
+      case 'typescriptreact':
+      case 'javascript':
+      case 'javascriptreact':
+        return this.analyzeTypeScriptWithAST(content, lines, filePath, diagnose);
+
+      case 'python':
+        return this.pythonAnalyzer.analyzeFile(content);
+
+      case 'java':
+        if (this.treeSitterAnalyzer.canHandle('java')) {
+          return this.treeSitterAnalyzer.analyzeFile(content, 'java', filePath, diagnose);
+        }
+        return this.analyzeJavaImports(lines, content);
+
+      case 'go':
+        return this.goAnalyzer.analyzeFile(content);
+
+      case 'rust':
+        return this.rustAnalyzer.analyzeFile(content, filePath, diagnose);
+
+      case 'cpp':
+      case 'c':
+        return this.cppAnalyzer.analyzeFile(content, filePath);
+
+      default:
+        return [];
+    }
+  }
+
+  /**
+   * Analyze TypeScript/JavaScript imports using AST parsing
+   * Provides 100% accuracy compared to regex-based approach
+   */
+  private analyzeTypeScriptWithAST(content: string, lines: string[], filePath: string, diagnose: DiagnosticSink): UnusedImport[] {
+    try {
+      const result = this.astAnalyzer.analyzeTypeScriptFile(content, filePath, diagnose);
+      // Babel counts columns in UTF-16 code units; findings report UTF-8 bytes
+      const lineIndex = new LineIndex(content);
+      const byteColumn = (line: number, column: number) =>
+        lineIndex.positionAt(lineIndex.offsetAtUtf16(line, column)).column;
+
+      return result.unusedImports.flatMap(astUnused => {
+        const importStatement = lines[astUnused.line - 1]?.trim() || '';
+        const from = astUnused.kind === 'require' ? `require('${astUnused.source}')` : `'${astUnused.source}'`;
+        const findings: UnusedImport[] = [];
+
+        if (astUnused.unusedSpecifiers.length > 0) {
+          findings.push({
+            line: astUnused.line,
+            column: byteColumn(astUnused.line, astUnused.column),
+            importStatement,
+            symbols: astUnused.unusedSpecifiers,
+            reason:
+              astUnused.unusedSpecifiers.length === astUnused.allSpecifiers.length
+                ? `All imports from ${from} are unused`
+                : `Unused: ${astUnused.unusedSpecifiers.join(', ')} from ${from}`,
+            kind: 'unused-import',
+            importPath: astUnused.source,
+          });
+        }
+
+        if (astUnused.typeOnlySpecifiers.length > 0) {
+          findings.push({
+            line: astUnused.line,
+            column: byteColumn(astUnused.line, astUnused.column),
+            importStatement,
+            symbols: astUnused.typeOnlySpecifiers,
+            reason: `Only used as a type: ${astUnused.typeOnlySpecifiers.join(', ')} from ${from} (use \`import type\`)`,
+            kind: 'type-only-import',
+            importPath: astUnused.source,
+          });
+        }
+
+        return findings;
+      });
+    } catch (error) {
+      diagnose(`AST parsing failed for ${filePath}, falling back to regex: ${error instanceof Error ? error.message : String(error)}`);
+      return this.analyzeTypeScriptImportsRegex(lines, content);
+    }
+  }
+
+  /**
+   * Fallback regex-based TypeScript/JavaScript analysis
+   */
+  private analyzeTypeScriptImportsRegex(lines: string[], content: string): UnusedImport[] {
+    const unused: UnusedImport[] = [];
+    const importRegex = /^import\s+(?:{([^}]+)}|(\w+))\s+from\s+['"]([^'"]+)['"]/;
+
+    lines.forEach((line, index) => {
+      const trimmed = line.trim();
+      const match = trimmed.match(importRegex);
+
+      if (match) {
+        const namedImports = match[1] ? match[1].split(',').map(s => s.trim()) : [];
+        const defaultImport = match[2];
+        const symbols = [...namedImports, defaultImport].filter(Boolean);
+
+        const unusedSymbols = symbols.filter(symbol => {
+          const codeWithoutImports = lines.filter((_, i) => i !== index).join('\n');
+          const symbolRegex = new RegExp(`\\b${symbol}\\b`, 'g');
+          const matches = codeWithoutImports.match(symbolRegex);
+          return !matches || matches.length === 0;
+        });
+
+        if (unusedSymbols.length > 0) {
+          unused.push({
+            line: index + 1,
+            importStatement: trimmed,
+            symbols: unusedSymbols,
+            reason: `Symbol(s) ${unusedSymbols.join(', ')} not used in code`,
+          });
+        }
+      }
+    });
+
+    return unused;
+  }
+
+  /**
+   * Regex-based Java import analysis (fallback when tree-sitter is unavailable)
+   */
+  private analyzeJavaImports(lines: string[], content: string): UnusedImport[] {
+    const unused: UnusedImport[] = [];
+    const importRegex = /^import\s+(?:static\s+)?([a-zA-Z0-9_.]+(?:\.\*)?);/;
+
+    lines.forEach((line, index) => {
+      const trimmed = line.trim();
+      const match = trimmed.match(importRegex);
+
+      if (match) {
+        const importPath = match[1];
+        const className = importPath.split('.').pop() || '';
+
+        if (className !== '*') {
+          const codeWithoutImports = lines.filter((_, i) => i !== index).join('\n');
+          const classRegex = new RegExp(`\\b${className}\\b`, 'g');
+          const matches = codeWithoutImports.match(classRegex);
+
+          if (!matches || matches.length === 0) {
+            unused.push({
+              line: index + 1,
+              importStatement: trimmed,
+              symbols: [className],
+              reason: `Class ${className} not used in code`,
+            });
+          }
+        }
+      }
+    });
+
+    return unused;
+  }
+}
+
+async function readSource(filePath: string): Promise<string> {
+  try {
+    return await fs.promises.readFile(filePath, 'utf-8');
+  } catch (error) {
+    throw new ImportLensError('io', `Failed to read ${filePath}: ${error instanceof Error ? error.message : String(error)}`, filePath);
+  }
+}
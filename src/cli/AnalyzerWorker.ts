@@ -1,20 +1,23 @@
 /**
  * Worker thread entry point for parallel file analysis.
- * Spawned by CLIAnalyzer when processing large file sets.
+ * Spawned by WorkerPool; analyzes one file per request until terminated.
  */
 import { workerData, parentPort } from 'worker_threads';
 import { CLIAnalyzer } from './CLIAnalyzer';
 import { CLIArguments } from './ArgumentParser';
+import { WorkerRequest, WorkerResponse } from './WorkerPool';
 
 interface WorkerInput {
-  files: string[];
   args: CLIArguments;
 }
 
-const { files, args } = workerData as WorkerInput;
+const { args } = workerData as WorkerInput;
 const analyzer = new CLIAnalyzer(args);
 
 // analyzeFiles runs sequentially here because isMainThread === false in worker context
-analyzer.analyzeFiles(files)
-  .then(results => parentPort!.postMessage(results))
-  .catch(() => parentPort!.postMessage([]));
+parentPort!.on('message', async ({ index, filePath }: WorkerRequest) => {
+  const [result] = await analyzer.analyzeFiles([filePath]);
+  parentPort!.postMessage({ index, result } as WorkerResponse);
+});
+
+parentPort!.postMessage({ ready: true } as WorkerResponse);
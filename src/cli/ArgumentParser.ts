@@ -1,5 +1,15 @@
 import * as fs from 'fs';
 import * as path from 'path';
+import { CONFIDENCE_LEVELS, ConfidenceLevel, FindingSeverity, SEVERITY_LEVELS } from './CLIAnalyzer';
+import { Config, parseSeverityList } from './Config';
+import { parseCfgOption } from './RustCfg';
+import { isEdition, RUST_EDITIONS, RustEdition } from './RustPrelude';
+import { LANGUAGE_NAMES, normalizeLanguage } from './Languages';
+import { DIFF_SCOPES, DiffScope } from './DiffFilter';
+import { GRAPH_FORMATS, GRAPH_GRANULARITIES, GraphFormat, GraphGranularity } from './ImportGraph';
+import { STATS_FORMATS } from './ImportStats';
+import { COLOR_MODES, ColorMode, TEXT_GROUPINGS, TextGrouping } from './OutputFormatter';
+import { isRenamePath } from './RustRename';
 
 /**
  * Configuration for the confidence scoring engine
@@ -12,14 +22,117 @@ export interface ConfidenceConfig {
   specificCodeMultiplier: number;  // Default: 1.2
 }
 
+/** Subcommands, e.g. `importlens-cli cache clear` or `importlens-cli config --print-effective <path>` */
+export type CLICommand = 'cache-clear' | 'print-config' | 'watch' | 'lsp' | 'baseline-generate' | 'baseline-update' | 'graph' | 'stats' | 'hook' | 'hook-install' | 'rename';
+
 export interface CLIArguments {
   check: boolean;
   fix: boolean;
+  dryRun: boolean;
+  /** With `--fix`: ask before removing each import */
+  interactive: boolean;
+  /** Rust: group and sort the remaining `use` items, alone or after `--fix` */
+  organize: boolean;
+  /** Rust: report runs of `use` items that `--organize` would change, without rewriting */
+  checkOrganized: boolean;
+  /** Rust: with `--fix`, replace used glob imports of crate modules with the items used */
+  expandGlobs: boolean;
   safeMode: boolean;
   aggressive: boolean;
-  format: 'text' | 'json' | 'github' | 'junit';
+  format: 'text' | 'json' | 'github' | 'junit' | 'sarif' | 'html';
+  /** With `graph`: DOT or GraphML; defaults to `dot` */
+  graphFormat?: GraphFormat;
+  /** With `graph`: a node per module (the default) or per crate */
+  collapse?: GraphGranularity;
+  /** With `graph`: only the nodes matching this pattern, e.g. `crate::services::*` */
+  graphFilter?: string;
+  /** Text output: what findings are grouped by first; defaults to `file` */
+  groupBy?: TextGrouping;
+  /** Text output: only show the files, kinds or crates with the most findings */
+  top?: number;
+  /** Text output: show every finding and the files without any */
+  verbose: boolean;
+  /** Color severities in text output: `auto` on a terminal without `NO_COLOR` */
+  color: ColorMode;
+  /** Don't draw the progress bar a terminal gets on stderr */
+  quiet: boolean;
+  /** Write the report to this file instead of stdout */
+  output?: string;
+  /** With `--fix` or `--organize`: write the changes to this patch file, or stdout for `-`, instead of the files */
+  outputPatch?: string;
   config?: string;
   exclude: string[];
+  hidden: boolean;
+  /** Files larger than this many bytes are skipped with a warning; defaults to 10 MB */
+  maxFileSize?: number;
+  /** Analyze files over `maxFileSize` too */
+  includeLargeFiles: boolean;
+  strictMacros: boolean;
+  reportUnusedSuppressions: boolean;
+  /**
+   * Findings below this confidence are not reported (default: `medium`), nor removed by
+   * `--fix`, which only removes `high` findings when this is unset
+   */
+  minConfidence?: ConfidenceLevel;
+  /** `--check` fails on findings of this severity and above */
+  failOn: FindingSeverity;
+  /** `--check` also fails when there are more warnings than this */
+  maxWarnings?: number;
+  /** Only report findings on lines changed since this git ref */
+  diffBase?: string;
+  /** Only report findings on lines changed in this unified diff file, or stdin for `-` */
+  diff?: string;
+  /** With `--diff-base` or `--diff`: what a finding must touch to be reported; defaults to `imports` */
+  diffScope?: DiffScope;
+  /** Rust: report `pub(crate)` re-exports that no module of the crate refers to */
+  checkReexports: boolean;
+  /** Rust: root file of the crate to resolve module paths in, instead of the crates `Cargo.toml` implies */
+  crateRoot?: string;
+  /** Rust: report `Cargo.toml` dependencies that no source file of the package refers to */
+  checkDependencies: boolean;
+  /** Rust: report unused imports inside doctests, each analyzed on its own */
+  checkDoctests: boolean;
+  /** Rust: report paths written out this many times in a module without an import */
+  qualifiedPathThreshold?: number;
+  /** Rust: report imports of what the prelude already provides */
+  checkPrelude: boolean;
+  /** Rust: edition of the prelude `--check-prelude` checks against, instead of each crate's `Cargo.toml` */
+  edition?: RustEdition;
+  /** Rust: report imports only test code uses, even where config doesn't turn them on */
+  checkTestOnly: boolean;
+  /** Rust: cfg options pinned with `--cfg`, e.g. `target_os="linux"` */
+  cfg: string[];
+  /** Analyze every file as this language instead of detecting it from the extension */
+  language?: string;
+  /** Number of worker threads; defaults to the number of CPU cores */
+  jobs?: number;
+  /** Reuse findings for unchanged files from the analysis cache */
+  cache: boolean;
+  cacheDir?: string;
+  /** With `hook`: analyze the staged content of the files to commit */
+  staged: boolean;
+  /** With `hook`: like `staged`, but first remove the fixable findings from the index and the working tree */
+  fixStaged: boolean;
+  /** With `rename`: the module path to rewrite, e.g. `crate::utils::parse` */
+  renameFrom?: string;
+  /** With `rename`: the path to rewrite it to */
+  renameTo?: string;
+  /** With `rename`: rewrite files even when the git working tree has uncommitted changes */
+  allowDirty: boolean;
+  /** Subcommand to run instead of analyzing files */
+  command?: CLICommand;
+  /** Analyze source read from stdin instead of files */
+  stdin: boolean;
+  /** With `--stdin`: path the source is analyzed and reported as */
+  stdinFilename?: string;
+  /** Also analyze the paths listed in this file, or in stdin for `-` */
+  filesFrom?: string;
+  /** With `--files-from`: paths are NUL-separated (`-0`) instead of one per line */
+  nullSeparated: boolean;
+  /** In watch mode: shell command to run after every analysis */
+  exec?: string;
+  /** Settings applied on top of every `importlens.toml`, for embedders */
+  configOverrides?: Config;
   files: string[];
   help: boolean;
   version: boolean;
@@ -48,10 +161,35 @@ export function parseArgs(argv: string[]): CLIArguments {
   const args: CLIArguments = {
     check: false,
     fix: false,
+    dryRun: false,
+    interactive: false,
+    organize: false,
+    checkOrganized: false,
+    expandGlobs: false,
     safeMode: true,
     aggressive: false,
     format: 'text',
+    verbose: false,
+    color: 'auto',
+    quiet: false,
     exclude: [],
+    hidden: false,
+    includeLargeFiles: false,
+    strictMacros: false,
+    reportUnusedSuppressions: false,
+    checkReexports: false,
+    checkDependencies: false,
+    checkDoctests: false,
+    checkPrelude: false,
+    checkTestOnly: false,
+    cfg: [],
+    failOn: 'error',
+    cache: true,
+    staged: false,
+    fixStaged: false,
+    allowDirty: false,
+    stdin: false,
+    nullSeparated: false,
     files: [],
     help: false,
     version: false,
@@ -62,38 +200,280 @@ export function parseArgs(argv: string[]): CLIArguments {
     analytics: false
   };
 
-  for (let i = 0; i < argv.length; i++) {
+  // Anything else starting with `cache` is a path, so a directory named cache can still be analyzed
+  let start = 0;
+  if (argv[0] === 'cache' && argv[1] === 'clear') {
+    args.command = 'cache-clear';
+    start = 2;
+  } else if (argv[0] === 'config' && argv[1] === '--print-effective') {
+    args.command = 'print-config';
+    start = 2;
+  } else if (argv[0] === 'watch') {
+    args.command = 'watch';
+    start = 1;
+  } else if (argv[0] === 'lsp') {
+    args.command = 'lsp';
+    start = 1;
+  } else if (argv[0] === 'baseline' && (argv[1] === 'generate' || argv[1] === 'update')) {
+    args.command = argv[1] === 'generate' ? 'baseline-generate' : 'baseline-update';
+    start = 2;
+  } else if (argv[0] === 'hook') {
+    args.command = argv[1] === 'install' ? 'hook-install' : 'hook';
+    start = argv[1] === 'install' ? 2 : 1;
+  } else if (argv[0] === 'graph') {
+    args.command = 'graph';
+    start = 1;
+  } else if (argv[0] === 'stats') {
+    args.command = 'stats';
+    start = 1;
+  } else if (argv[0] === 'rename') {
+    args.command = 'rename';
+    start = 1;
+  }
+
+  for (let i = start; i < argv.length; i++) {
     const arg = argv[i];
 
     if (arg === '--check') {
       args.check = true;
     } else if (arg === '--fix') {
       args.fix = true;
+    } else if (arg === '--dry-run') {
+      args.dryRun = true;
+    } else if (arg === '--interactive') {
+      args.interactive = true;
+    } else if (arg === '--organize') {
+      args.organize = true;
+    } else if (arg === '--check-organized') {
+      args.checkOrganized = true;
+    } else if (arg === '--expand-globs') {
+      args.expandGlobs = true;
     } else if (arg === '--safe-mode') {
       args.safeMode = true;
       args.aggressive = false;
     } else if (arg === '--aggressive') {
       args.aggressive = true;
       args.safeMode = false;
-    } else if (arg.startsWith('--format=')) {
-      const format = arg.split('=')[1] as any;
-      if (['text', 'json', 'github', 'junit'].includes(format)) {
+    } else if (arg.startsWith('--format=') || arg === '--format') {
+      const format = (arg === '--format' ? argv[++i] : arg.split('=')[1]) as any;
+      if (args.command === 'graph') {
+        if (!(GRAPH_FORMATS as readonly string[]).includes(format)) {
+          throw new Error(`Invalid graph format: ${format}. Must be one of: ${GRAPH_FORMATS.join(', ')}`);
+        }
+        args.graphFormat = format;
+      } else if (args.command === 'stats') {
+        if (!(STATS_FORMATS as readonly string[]).includes(format)) {
+          throw new Error(`Invalid stats format: ${format}. Must be one of: ${STATS_FORMATS.join(', ')}`);
+        }
+        args.format = format;
+      } else if (['text', 'json', 'github', 'junit', 'sarif', 'html'].includes(format)) {
         args.format = format;
       } else {
-        throw new Error(`Invalid format: ${format}. Must be one of: text, json, github, junit`);
+        throw new Error(`Invalid format: ${format}. Must be one of: text, json, github, junit, sarif, html`);
       }
     } else if (arg.startsWith('--config=')) {
       args.config = arg.split('=')[1];
     } else if (arg.startsWith('--exclude=')) {
       args.exclude.push(arg.split('=')[1]);
+    } else if (arg === '--hidden') {
+      args.hidden = true;
+    } else if (arg === '--strict-macros') {
+      args.strictMacros = true;
+    } else if (arg === '--report-unused-suppressions') {
+      args.reportUnusedSuppressions = true;
+    } else if (arg === '--check-reexports') {
+      args.checkReexports = true;
+    } else if (arg === '--check-dependencies') {
+      args.checkDependencies = true;
+    } else if (arg === '--check-doctests') {
+      args.checkDoctests = true;
+    } else if (arg === '--check-qualified-paths' || arg.startsWith('--check-qualified-paths=')) {
+      const value = arg === '--check-qualified-paths' ? '3' : arg.slice('--check-qualified-paths='.length);
+      const threshold = Number(value);
+      if (value === '' || !Number.isInteger(threshold) || threshold < 1) {
+        throw new Error(`Invalid --check-qualified-paths value: ${value}. Must be a positive integer`);
+      }
+      args.qualifiedPathThreshold = threshold;
+    } else if (arg === '--check-prelude') {
+      args.checkPrelude = true;
+    } else if (arg.startsWith('--edition=') || arg === '--edition') {
+      const value = arg === '--edition' ? argv[++i] ?? '' : arg.slice('--edition='.length);
+      if (!isEdition(value)) {
+        throw new Error(`Invalid --edition value: ${value}. Must be one of: ${RUST_EDITIONS.join(', ')}`);
+      }
+      args.edition = value;
+    } else if (arg === '--check-test-only') {
+      args.checkTestOnly = true;
+    } else if (arg.startsWith('--cfg=') || arg === '--cfg') {
+      args.cfg.push(parseCfgOption(arg === '--cfg' ? argv[++i] ?? '' : arg.slice('--cfg='.length)));
+    } else if (arg.startsWith('--min-confidence=') || arg === '--min-confidence') {
+      const value = arg === '--min-confidence' ? argv[++i] ?? '' : arg.split('=')[1];
+      // `uncertain` was the only level below `high` before `low` and `medium`
+      const level = value === 'uncertain' ? 'low' : value;
+      if (!(CONFIDENCE_LEVELS as readonly string[]).includes(level)) {
+        throw new Error(`Invalid confidence level: ${level}. Must be one of: ${CONFIDENCE_LEVELS.join(', ')}`);
+      }
+      args.minConfidence = level as ConfidenceLevel;
+    } else if (arg.startsWith('--severity=') || arg === '--severity') {
+      // Applied on top of every importlens.toml; repeated flags add up
+      const severity = parseSeverityList(arg === '--severity' ? argv[++i] ?? '' : arg.slice('--severity='.length));
+      const overrides = args.configOverrides ?? {};
+      args.configOverrides = { ...overrides, severity: { ...overrides.severity, ...severity } };
+    } else if (arg.startsWith('--fail-on=') || arg === '--fail-on') {
+      const value = arg === '--fail-on' ? argv[++i] ?? '' : arg.split('=')[1];
+      const level = value === 'warn' ? 'warning' : value;
+      if (!(SEVERITY_LEVELS as readonly string[]).includes(level)) {
+        throw new Error(`Invalid --fail-on level: ${value}. Must be one of: ${SEVERITY_LEVELS.join(', ')}`);
+      }
+      args.failOn = level as FindingSeverity;
+    } else if (arg.startsWith('--max-warnings=') || arg === '--max-warnings') {
+      const value = arg === '--max-warnings' ? argv[++i] ?? '' : arg.split('=')[1];
+      const maxWarnings = Number(value);
+      if (value === '' || !Number.isInteger(maxWarnings) || maxWarnings < 0) {
+        throw new Error(`Invalid --max-warnings value: ${value}. Must be a non-negative integer`);
+      }
+      args.maxWarnings = maxWarnings;
+    } else if (arg.startsWith('--group-by=') || arg === '--group-by') {
+      const value = arg === '--group-by' ? argv[++i] ?? '' : arg.slice('--group-by='.length);
+      if (!(TEXT_GROUPINGS as readonly string[]).includes(value)) {
+        throw new Error(`Invalid --group-by value: ${value}. Must be one of: ${TEXT_GROUPINGS.join(', ')}`);
+      }
+      args.groupBy = value as TextGrouping;
+    } else if (arg.startsWith('--top=') || arg === '--top') {
+      const value = arg === '--top' ? argv[++i] ?? '' : arg.slice('--top='.length);
+      const top = Number(value);
+      if (value === '' || !Number.isInteger(top) || top < 1) {
+        throw new Error(`Invalid --top value: ${value}. Must be a positive integer`);
+      }
+      args.top = top;
+    } else if (arg === '--verbose') {
+      args.verbose = true;
+    } else if (arg === '--quiet' || arg === '-q') {
+      args.quiet = true;
+    } else if (arg.startsWith('--color=') || arg === '--color') {
+      const value = arg === '--color' ? argv[++i] ?? '' : arg.slice('--color='.length);
+      if (!(COLOR_MODES as readonly string[]).includes(value)) {
+        throw new Error(`Invalid --color value: ${value}. Must be one of: ${COLOR_MODES.join(', ')}`);
+      }
+      args.color = value as ColorMode;
+    } else if (arg.startsWith('--max-file-size=') || arg === '--max-file-size') {
+      const value = arg === '--max-file-size' ? argv[++i] ?? '' : arg.slice('--max-file-size='.length);
+      const size = parseSize(value);
+      if (size === undefined) {
+        throw new Error(`Invalid --max-file-size value: ${value}. Must be a size such as 500KB or 20MB`);
+      }
+      args.maxFileSize = size;
+    } else if (arg === '--include-large-files') {
+      args.includeLargeFiles = true;
+    } else if (arg.startsWith('--crate-root=') || arg === '--crate-root') {
+      args.crateRoot = arg === '--crate-root' ? argv[++i] : arg.slice('--crate-root='.length);
+      if (!args.crateRoot) {
+        throw new Error('--crate-root requires the path of a crate root file, e.g. src/lib.rs');
+      }
+      if (!fs.existsSync(args.crateRoot) || !fs.statSync(args.crateRoot).isFile()) {
+        throw new Error(`Crate root not found: ${args.crateRoot}`);
+      }
+    } else if (arg.startsWith('--diff-base=') || arg === '--diff-base') {
+      args.diffBase = arg === '--diff-base' ? argv[++i] : arg.slice('--diff-base='.length);
+      if (!args.diffBase) {
+        throw new Error('--diff-base requires a git ref');
+      }
+    } else if (arg.startsWith('--diff=') || arg === '--diff') {
+      args.diff = arg === '--diff' ? argv[++i] : arg.slice('--diff='.length);
+      if (!args.diff) {
+        throw new Error('--diff requires a diff file, or - for stdin');
+      }
+    } else if (arg.startsWith('--diff-scope=') || arg === '--diff-scope') {
+      const scope = arg === '--diff-scope' ? argv[++i] ?? '' : arg.split('=')[1];
+      if (!(DIFF_SCOPES as readonly string[]).includes(scope)) {
+        throw new Error(`Invalid --diff-scope: ${scope}. Must be one of: ${DIFF_SCOPES.join(', ')}`);
+      }
+      args.diffScope = scope as DiffScope;
+    } else if (arg.startsWith('--collapse=') || arg === '--collapse') {
+      const granularity = arg === '--collapse' ? argv[++i] ?? '' : arg.split('=')[1];
+      if (!(GRAPH_GRANULARITIES as readonly string[]).includes(granularity)) {
+        throw new Error(`Invalid --collapse: ${granularity}. Must be one of: ${GRAPH_GRANULARITIES.join(', ')}`);
+      }
+      args.collapse = granularity as GraphGranularity;
+    } else if (arg.startsWith('--filter=') || arg === '--filter') {
+      args.graphFilter = arg === '--filter' ? argv[++i] : arg.slice('--filter='.length);
+      if (!args.graphFilter) {
+        throw new Error('--filter requires a pattern, e.g. crate::services::*');
+      }
+    } else if (arg.startsWith('--language=') || arg === '--language') {
+      const name = arg === '--language' ? argv[++i] ?? '' : arg.split('=')[1];
+      const language = normalizeLanguage(name);
+      if (!language) {
+        throw new Error(`Invalid language: ${name}. Must be one of: ${LANGUAGE_NAMES.join(', ')}`);
+      }
+      args.language = language;
+    } else if (arg.startsWith('--jobs=') || arg === '--jobs' || arg === '-j') {
+      const value = arg.startsWith('--jobs=') ? arg.split('=')[1] : argv[++i] ?? '';
+      const jobs = Number(value);
+      if (!Number.isInteger(jobs) || jobs < 1) {
+        throw new Error(`Invalid job count: ${value}. Must be a positive integer`);
+      }
+      args.jobs = jobs;
+    } else if (arg === '--no-cache') {
+      args.cache = false;
+    } else if (arg.startsWith('--cache-dir=')) {
+      args.cacheDir = arg.split('=')[1];
+    } else if (arg.startsWith('--exec=') || arg === '--exec') {
+      args.exec = arg === '--exec' ? argv[++i] : arg.slice('--exec='.length);
+      if (!args.exec) {
+        throw new Error('--exec requires a command');
+      }
+    } else if (arg === '--staged' && args.command === 'hook') {
+      args.staged = true;
+    } else if (arg === '--fix-staged' && args.command === 'hook') {
+      args.fixStaged = true;
+    } else if (arg.startsWith('--from=') || arg === '--from' || arg.startsWith('--to=') || arg === '--to') {
+      const option = arg.split('=')[0];
+      const value = arg.includes('=') ? arg.slice(option.length + 1) : argv[++i];
+      if (!value || !isRenamePath(value)) {
+        throw new Error(`Invalid ${option} path: ${value ?? ''}. Must be a path from the crate root or of a crate, e.g. crate::utils::parse`);
+      }
+      if (option === '--from') args.renameFrom = value;
+      else args.renameTo = value;
+    } else if (arg === '--allow-dirty') {
+      args.allowDirty = true;
+    } else if (arg === '--stdin') {
+      args.stdin = true;
+    } else if (arg.startsWith('--stdin-filename=') || arg === '--stdin-filename') {
+      args.stdinFilename = arg === '--stdin-filename' ? argv[++i] : arg.slice('--stdin-filename='.length);
+      if (!args.stdinFilename) {
+        throw new Error('--stdin-filename requires a file path');
+      }
+    } else if (arg.startsWith('--output=') || arg === '--output' || arg === '-o') {
+      args.output = arg.startsWith('--output=') ? arg.slice('--output='.length) : argv[++i];
+      if (!args.output) {
+        throw new Error('--output requires a file path');
+      }
+    } else if (arg.startsWith('--output-patch=') || arg === '--output-patch') {
+      args.outputPatch = arg === '--output-patch' ? argv[++i] : arg.slice('--output-patch='.length);
+      if (!args.outputPatch) {
+        throw new Error('--output-patch requires a file path, or - for stdout');
+      }
+    } else if (arg.startsWith('--files-from=') || arg === '--files-from') {
+      args.filesFrom = arg === '--files-from' ? argv[++i] : arg.slice('--files-from='.length);
+      if (!args.filesFrom) {
+        throw new Error('--files-from requires a file path, or - for stdin');
+      }
+    } else if (arg === '-0' || arg === '--null') {
+      args.nullSeparated = true;
+    } else if (arg === '--stdio' && args.command === 'lsp') {
+      // Passed by most LSP clients; stdio is the only transport
     } else if (arg === '--help' || arg === '-h') {
       args.help = true;
     } else if (arg === '--version' || arg === '-v') {
       args.version = true;
     } else if (arg === '--no-exit-on-error') {
       args.exitOnError = false;
-    } else if (arg.startsWith('--baseline=')) {
-      args.baseline = arg.split('=')[1];
+    } else if (arg.startsWith('--baseline=') || arg === '--baseline') {
+      args.baseline = arg === '--baseline' ? argv[++i] : arg.slice('--baseline='.length);
+      if (!args.baseline) {
+        throw new Error('--baseline requires a file path');
+      }
     } else if (arg === '--baseline-generate' || arg === '--generate-baseline') {
       args.baselineGenerate = true;
     } else if (arg === '--baseline-update' || arg === '--update-baseline') {
@@ -112,8 +492,134 @@ export function parseArgs(argv: string[]): CLIArguments {
     }
   }
 
+  if (args.dryRun && !args.fix && !args.organize && args.command !== 'rename') {
+    throw new Error('--dry-run can only be used together with --fix, --organize or rename');
+  }
+
+  if (args.outputPatch !== undefined && (!(args.fix || args.organize) || args.dryRun || args.stdin)) {
+    throw new Error('--output-patch can only be used together with --fix or --organize, and not with --dry-run or --stdin');
+  }
+
+  if (args.interactive && !args.fix) {
+    throw new Error('--interactive can only be used with --fix');
+  }
+
+  if (args.interactive && (args.stdin || args.command !== undefined || args.filesFrom === '-')) {
+    throw new Error('--interactive reads answers from stdin; it cannot be used with --stdin, --files-from - or subcommands');
+  }
+
+  if (args.checkOrganized && (args.fix || args.organize)) {
+    throw new Error('--check-organized cannot be used with --fix or --organize');
+  }
+
+  if (args.expandGlobs && !args.fix) {
+    throw new Error('--expand-globs can only be used with --fix');
+  }
+
+  if (args.expandGlobs && args.stdin) {
+    throw new Error('--expand-globs cannot be used with --stdin');
+  }
+
+  if (args.stdinFilename !== undefined && !args.stdin) {
+    throw new Error('--stdin-filename can only be used with --stdin');
+  }
+
+  if (args.stdin && (args.files.length > 0 || args.filesFrom !== undefined)) {
+    throw new Error('--stdin cannot be used with file arguments or --files-from');
+  }
+
+  if (args.stdin && (args.command !== undefined || args.analytics)) {
+    throw new Error('--stdin cannot be used with subcommands or --analytics');
+  }
+
+  if (args.stdin && args.stdinFilename === undefined && args.language === undefined) {
+    throw new Error('--stdin requires --stdin-filename or --language');
+  }
+
+  const diffMode = args.diffBase !== undefined || args.diff !== undefined;
+  if (args.diffBase !== undefined && args.diff !== undefined) {
+    throw new Error('--diff-base cannot be used with --diff');
+  }
+
+  if (args.diffScope !== undefined && !diffMode) {
+    throw new Error('--diff-scope can only be used with --diff-base or --diff');
+  }
+
+  if (diffMode && (args.fix || args.organize || args.checkOrganized || args.analytics || args.stdin || args.command !== undefined ||
+    args.baselineGenerate || args.baselineUpdate)) {
+    throw new Error('--diff-base and --diff only filter reports; they cannot be used with --fix, --organize, --check-organized, --stdin, --analytics or subcommands');
+  }
+
+  if (args.diff === '-' && args.filesFrom === '-') {
+    throw new Error('--diff - cannot be used with --files-from -');
+  }
+
+  if ((args.groupBy !== undefined || args.top !== undefined) && args.format !== 'text') {
+    throw new Error('--group-by and --top only apply to --format=text');
+  }
+
+  if ((args.collapse !== undefined || args.graphFilter !== undefined) && args.command !== 'graph') {
+    throw new Error('--collapse and --filter can only be used with graph');
+  }
+
+  if (args.command === 'hook' && !args.staged && !args.fixStaged) {
+    throw new Error('hook needs --staged or --fix-staged; run `hook install` to set up the pre-commit hook');
+  }
+
+  if (args.command === 'hook' && (args.fix || args.organize || args.checkOrganized || args.analytics || args.filesFrom !== undefined)) {
+    throw new Error('hook analyzes the staged files; it cannot be used with --fix, --organize, --check-organized, --analytics or --files-from');
+  }
+
+  if (args.command === 'graph' && (args.fix || args.organize || args.checkOrganized || args.analytics || args.baselineGenerate || args.baselineUpdate)) {
+    throw new Error('graph only reads files; it cannot be used with --fix, --organize, --check-organized, --analytics or baselines');
+  }
+
+  if ((args.renameFrom !== undefined || args.renameTo !== undefined || args.allowDirty) && args.command !== 'rename') {
+    throw new Error('--from, --to and --allow-dirty can only be used with rename');
+  }
+
+  if (args.command === 'rename') {
+    if (args.renameFrom === undefined || args.renameTo === undefined) {
+      throw new Error('rename needs --from and --to, e.g. rename --from crate::utils::parse --to crate::parsing');
+    }
+    if (args.renameFrom === args.renameTo) {
+      throw new Error('rename --from and --to are the same path');
+    }
+    if (args.fix || args.organize || args.checkOrganized || args.analytics || args.stdin || args.baselineGenerate || args.baselineUpdate) {
+      throw new Error('rename rewrites paths on its own; it cannot be used with --fix, --organize, --check-organized, --stdin, --analytics or baselines');
+    }
+  }
+
+  if (args.command === 'stats' && (args.fix || args.organize || args.checkOrganized || args.analytics || args.baselineGenerate || args.baselineUpdate)) {
+    throw new Error('stats only reports numbers; it cannot be used with --fix, --organize, --check-organized, --analytics or baselines');
+  }
+
+  if (args.nullSeparated && args.filesFrom === undefined) {
+    throw new Error('-0 can only be used with --files-from');
+  }
+
+  if (args.filesFrom !== undefined && args.command === 'lsp') {
+    throw new Error('--files-from cannot be used with lsp');
+  }
+
+  if (args.exec !== undefined && args.command !== 'watch') {
+    throw new Error('--exec can only be used with watch');
+  }
+
+  if (args.command === 'watch' && args.fix) {
+    throw new Error('--fix cannot be used with watch');
+  }
+
+  if (args.command === 'watch' && (args.organize || args.checkOrganized)) {
+    throw new Error('--organize and --check-organized cannot be used with watch');
+  }
+
+  if (args.command?.startsWith('baseline-') && (args.fix || args.organize)) {
+    throw new Error('baseline generate and update cannot be used with --fix or --organize');
+  }
+
   // Default to check mode if neither check nor fix specified (unless in analytics mode)
-  if (!args.check && !args.fix && !args.analytics) {
+  if (!args.check && !args.fix && !args.organize && !args.analytics) {
     args.check = true;
   }
 
@@ -126,6 +632,16 @@ export function parseArgs(argv: string[]): CLIArguments {
   return args;
 }
 
+/**
+ * Bytes in a size such as `1048576`, `512KB` or `20MB` (units of 1024, case-insensitive)
+ */
+function parseSize(value: string): number | undefined {
+  const match = /^(\d+(?:\.\d+)?)\s*(B|KB|MB|GB)?$/i.exec(value.trim());
+  if (!match) return undefined;
+  const units: Record<string, number> = { B: 1, KB: 1024, MB: 1024 ** 2, GB: 1024 ** 3 };
+  return Math.round(Number(match[1]) * units[(match[2] ?? 'B').toUpperCase()]);
+}
+
 /**
  * Find configuration file in current directory or parent directories
  */
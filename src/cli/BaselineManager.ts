@@ -1,15 +1,35 @@
+import { createHash } from 'crypto';
 import * as fs from 'fs';
 import * as path from 'path';
-import { AnalysisResult } from './CLIAnalyzer';
+import { AnalysisResult, FindingKind, UnusedImport } from './CLIAnalyzer';
+import { toPosixPath } from './Paths';
 
 /**
  * Baseline entry representing an accepted unused import
  */
 export interface BaselineEntry {
   filePath: string;
+  /** Line when the baseline was written; only used to match entries without a fingerprint */
   line: number;
   importStatement: string;
   symbols: string[];
+  kind?: FindingKind;
+  importPath?: string;
+  /** Hash of the finding's kind, import path and statement text; survives the import moving */
+  fingerprint?: string;
+}
+
+/**
+ * Fingerprint of a finding that doesn't depend on where it is in the file, so
+ * edits above an import don't invalidate its baseline entry
+ */
+export function findingFingerprint(unusedImport: UnusedImport): string {
+  const statement = unusedImport.importStatement.replace(/\s+/g, ' ').trim();
+  const subject = unusedImport.importPath ?? unusedImport.symbols.join(', ');
+  return createHash('sha256')
+    .update(`${unusedImport.kind ?? 'unused-import'}\n${subject}\n${statement}`)
+    .digest('hex')
+    .slice(0, 16);
 }
 
 /**
@@ -64,7 +84,10 @@ export class BaselineManager {
           filePath: this.normalizePath(result.filePath),
           line: unusedImport.line,
           importStatement: unusedImport.importStatement,
-          symbols: unusedImport.symbols
+          symbols: unusedImport.symbols,
+          kind: unusedImport.kind ?? 'unused-import',
+          importPath: unusedImport.importPath,
+          fingerprint: findingFingerprint(unusedImport)
         });
       }
     }
@@ -178,7 +201,7 @@ export class BaselineManager {
       filesWithNewIssues: number;
     };
   } {
-    const baselineSet = this.createBaselineSet(baseline);
+    const isBaselined = this.createMatcher(baseline);
     const newIssues: AnalysisResult[] = [];
     const baselineIssues: AnalysisResult[] = [];
 
@@ -187,13 +210,7 @@ export class BaselineManager {
       const baselineUnusedImports = [];
 
       for (const unusedImport of result.unusedImports) {
-        const key = this.createEntryKey(
-          result.filePath,
-          unusedImport.line,
-          unusedImport.importStatement
-        );
-
-        if (baselineSet.has(key)) {
+        if (isBaselined(result.filePath, unusedImport)) {
           baselineUnusedImports.push(unusedImport);
         } else {
           newUnusedImports.push(unusedImport);
@@ -229,6 +246,62 @@ export class BaselineManager {
     };
   }
 
+  /**
+   * Drop the findings recorded in the baseline from the results, keeping every file.
+   * Returns the filtered results and how many findings were left out.
+   */
+  filterResults(results: AnalysisResult[], baseline: BaselineFile): { results: AnalysisResult[]; baselined: number } {
+    const isBaselined = this.createMatcher(baseline);
+    let baselined = 0;
+
+    const filtered = results.map(result => {
+      const unusedImports = result.unusedImports.filter(unusedImport => {
+        const matched = isBaselined(result.filePath, unusedImport);
+        if (matched) baselined++;
+        return !matched;
+      });
+      return { ...result, unusedImports };
+    });
+
+    return { results: filtered, baselined };
+  }
+
+  /**
+   * Remove the baseline entries whose findings no longer exist, without adding new
+   * ones. Captures a historical snapshot first, like `updateBaseline`.
+   */
+  pruneBaseline(results: AnalysisResult[]): { kept: number; pruned: number } {
+    const baseline = this.loadBaseline();
+    if (!baseline) {
+      throw new Error(`No baseline found at ${this.baselinePath}`);
+    }
+
+    // Each current finding keeps the first entry it matches
+    const remaining = baseline.entries.map((entry, index) => ({ entry, index }));
+    const kept = new Set<number>();
+    for (const result of results) {
+      for (const unusedImport of result.unusedImports) {
+        const match = remaining.findIndex(({ entry }) => this.entryMatches(entry, result.filePath, unusedImport));
+        if (match === -1) continue;
+        kept.add(remaining[match].index);
+        remaining.splice(match, 1);
+      }
+    }
+
+    const pruned = baseline.entries.length - kept.size;
+    baseline.history = this.pruneHistory([...(baseline.history ?? []), this.captureSnapshot(baseline)], 30);
+    baseline.entries = baseline.entries.filter((_, index) => kept.has(index));
+    baseline.metadata = {
+      totalFiles: new Set(baseline.entries.map(entry => entry.filePath)).size,
+      totalUnusedImports: baseline.entries.length
+    };
+    baseline.updatedAt = new Date().toISOString();
+    baseline.version = '3.0.0';
+
+    this.saveBaseline(baseline);
+    return { kept: kept.size, pruned };
+  }
+
   /**
    * Update baseline with current results
    * Captures historical snapshot before updating
@@ -317,17 +390,39 @@ export class BaselineManager {
   }
 
   /**
-   * Create a set of baseline entry keys for fast lookup
+   * Returns a function telling whether a finding is in the baseline. Entries with a
+   * fingerprint match one finding each, wherever it moved in its file; older entries
+   * without one match by line and statement.
    */
-  private createBaselineSet(baseline: BaselineFile): Set<string> {
-    const set = new Set<string>();
+  private createMatcher(baseline: BaselineFile): (filePath: string, unusedImport: UnusedImport) => boolean {
+    const counts = new Map<string, number>();
+    const legacy = new Set<string>();
 
     for (const entry of baseline.entries) {
-      const key = this.createEntryKey(entry.filePath, entry.line, entry.importStatement);
-      set.add(key);
+      if (entry.fingerprint) {
+        const key = `${this.normalizePath(entry.filePath)}:${entry.fingerprint}`;
+        counts.set(key, (counts.get(key) ?? 0) + 1);
+      } else {
+        legacy.add(this.createEntryKey(entry.filePath, entry.line, entry.importStatement));
+      }
     }
 
-    return set;
+    return (filePath, unusedImport) => {
+      const key = `${this.normalizePath(filePath)}:${findingFingerprint(unusedImport)}`;
+      const count = counts.get(key) ?? 0;
+      if (count > 0) {
+        counts.set(key, count - 1);
+        return true;
+      }
+      return legacy.has(this.createEntryKey(filePath, unusedImport.line, unusedImport.importStatement));
+    };
+  }
+
+  private entryMatches(entry: BaselineEntry, filePath: string, unusedImport: UnusedImport): boolean {
+    if (this.normalizePath(entry.filePath) !== this.normalizePath(filePath)) return false;
+    return entry.fingerprint
+      ? entry.fingerprint === findingFingerprint(unusedImport)
+      : entry.line === unusedImport.line && entry.importStatement.trim() === unusedImport.importStatement.trim();
   }
 
   /**
@@ -344,7 +439,7 @@ export class BaselineManager {
   private normalizePath(filePath: string): string {
     // Convert to forward slashes and make relative to cwd
     const relativePath = path.relative(process.cwd(), filePath);
-    return relativePath.split(path.sep).join('/');
+    return toPosixPath(relativePath);
   }
 
   /**
@@ -1,45 +1,330 @@
 import * as fs from 'fs';
 import * as path from 'path';
 import * as os from 'os';
-import { isMainThread, Worker } from 'worker_threads';
+import { isMainThread } from 'worker_threads';
 import { CLIArguments } from './ArgumentParser';
-import { ASTAnalyzer } from './ASTAnalyzer';
-import { TreeSitterAnalyzer } from './TreeSitterAnalyzer';
+import { Analyzer, cancelledError } from './Analyzer';
+import { TextEdit } from './FixEngine';
+import { WorkerPool } from './WorkerPool';
+import { AnalysisCache } from './AnalysisCache';
+import { CrateFile, findManifest, RustCrate } from './RustCrate';
+import { recoverRustSyntax } from './RustRecovery';
+import { findUnusedDependencies } from './RustDependencies';
+import { ConfigResolver } from './Config';
+import { isHeaderPath } from './CppAnalyzer';
+import { CancellationToken, nextTurn, RunOptions } from './Progress';
 
 export interface AnalysisResult {
   filePath: string;
   language: string;
   unusedImports: UnusedImport[];
   error?: string;
+  /** Messages the analyzers printed for this file, replayed in file order after the run */
+  diagnostics?: string[];
+  /** `--expand-globs`: edits that replace used Rust glob imports with the items used from them */
+  globExpansions?: TextEdit[];
 }
 
+/**
+ * Receives what an analyzer has to say about a file besides its findings, such as
+ * items it skipped or a parser it fell back from
+ */
+export type DiagnosticSink = (message: string) => void;
+
+/** Sink of an analyzer used on its own: messages go to stderr */
+export const printDiagnostic: DiagnosticSink = message => console.error(message);
+
 export interface UnusedImport {
   line: number;
   importStatement: string;
   symbols: string[];
   reason: string;
+  /** 1-based column of the unused item, when the analyzer can pinpoint it */
+  column?: number;
+  /** Source offsets of the unused item, when the analyzer can pinpoint it */
+  span?: SourceSpan;
+  /** What kind of problem this is; defaults to 'unused-import' */
+  kind?: FindingKind;
+  /** Path of the imported item, e.g. `std::io::Write` */
+  importPath?: string;
+  /** `span` converted to UTF-8 byte offsets */
+  byteSpan?: SourceSpan;
+  /** How sure the analyzer is that the import can go; defaults to 'high' */
+  confidence?: ConfidenceLevel;
+  /** Why the finding has its confidence; defaults to the kind's `DEFAULT_CONFIDENCE_REASONS` entry */
+  confidenceReason?: ConfidenceReason;
+  /** The kind's default severity, or the one `importlens.toml` or `--severity` assigns; unset counts as `error` */
+  severity?: FindingSeverity;
+  /** Edit that resolves this finding on its own */
+  suggestedFix?: SuggestedFix;
+  /** For duplicate and redundant imports: where the name is already imported; for shadowed imports: the local definition shadowing it */
+  original?: ImportLocation;
+  /** Go: the `//go:build` constraint of the file, e.g. `linux && !race` */
+  buildConstraint?: string;
+  /** Rust, with `--check-doctests`: line of the fence that opens the doctest the import is in */
+  doctestLine?: number;
+  /** For over-qualified paths: the written-out path of every use, which the fix shortens to the imported name */
+  qualifiedUses?: SourceSpan[];
+  /** For test-only imports: the `#[cfg(test)]` module every use is in, braces included, which the fix moves the import to */
+  testModule?: { name: string; span: SourceSpan };
+}
+
+export interface ImportLocation {
+  line: number;
+  column?: number;
+  span?: SourceSpan;
+  byteSpan?: SourceSpan;
+}
+
+export const FINDING_KINDS = ['unused-import', 'duplicate-import', 'redundant-import', 'type-only-import', 'useless-suppression', 'unused-reexport', 'unused-dependency', 'shadowed-import', 'redundant-alias', 'over-qualified-path', 'banned-import', 'unnecessary-import', 'test-only-import'] as const;
+
+/** Kind of the findings of a custom rule: its namespaced id, e.g. `acme/banned-crate` */
+export type RuleKind = `${string}/${string}`;
+export type FindingKind = typeof FINDING_KINDS[number] | RuleKind;
+
+/** Whether `kind` is a custom rule's `namespace/name` rather than a built-in kind */
+export function isRuleKind(kind: string): kind is RuleKind {
+  return /^[a-z0-9][a-z0-9_-]*\/[a-z0-9][a-z0-9_-]*$/.test(kind);
+}
+
+/** Severities, lowest first; `--fail-on` fails `--check` on findings at its level and above */
+export const SEVERITY_LEVELS = ['info', 'warning', 'error'] as const;
+export type FindingSeverity = typeof SEVERITY_LEVELS[number];
+
+/** Severity of each kind unless an `importlens.toml` or `--severity` sets one */
+export const DEFAULT_SEVERITIES: Record<FindingKind, FindingSeverity> = {
+  'unused-import': 'error',
+  'duplicate-import': 'warning',
+  'redundant-import': 'warning',
+  'type-only-import': 'info',
+  'useless-suppression': 'warning',
+  'unused-reexport': 'error',
+  'unused-dependency': 'error',
+  'shadowed-import': 'error',
+  'redundant-alias': 'warning',
+  'over-qualified-path': 'info',
+  'banned-import': 'error',
+  'unnecessary-import': 'warning',
+  'test-only-import': 'warning',
+};
+
+/**
+ * Confidence levels, lowest first; `--min-confidence` hides findings below its level
+ * (default: `medium`), and `--fix` only removes `high` ones unless it is given
+ */
+export const CONFIDENCE_LEVELS = ['low', 'medium', 'high'] as const;
+export type ConfidenceLevel = typeof CONFIDENCE_LEVELS[number];
+
+/**
+ * Why a finding has its confidence:
+ * - `never-referenced`: the name never appears outside the import
+ * - `syntactic`: the imports themselves show it, e.g. the same item imported twice
+ * - `only-seen-in-macro`: with `--strict-macros`, the name only appears in macro bodies
+ * - `glob-items-listed`: none of the items listed for a glob's module is used; items
+ *   macros define there can't be listed
+ * - `trait-method-heuristic`: a trait that is never named, but methods it has are called
+ * - `package-name-guessed`: Go, the package name was guessed from the import path
+ * - `parse-degraded`: Rust, the file has syntax errors and parts of it were skipped
+ * - `header-identifiers-listed`: C/C++, none of the identifiers listed for the header
+ *   appears; what other headers' macros expand to can't be seen
+ * - `preprocessor-context`: C/C++, the include is in a header, inside `extern "C"` or
+ *   under `#if`, where includers or other configurations may need it
+ */
+export const CONFIDENCE_REASONS = [
+  'never-referenced', 'syntactic', 'only-seen-in-macro', 'glob-items-listed', 'trait-method-heuristic', 'package-name-guessed',
+  'parse-degraded', 'header-identifiers-listed', 'preprocessor-context',
+] as const;
+export type ConfidenceReason = typeof CONFIDENCE_REASONS[number];
+
+/** Reason of the findings of each kind whose analyzer gives none */
+export const DEFAULT_CONFIDENCE_REASONS: Record<FindingKind, ConfidenceReason> = {
+  'unused-import': 'never-referenced',
+  'duplicate-import': 'syntactic',
+  'redundant-import': 'syntactic',
+  'type-only-import': 'never-referenced',
+  'useless-suppression': 'never-referenced',
+  'unused-reexport': 'never-referenced',
+  'unused-dependency': 'never-referenced',
+  'shadowed-import': 'never-referenced',
+  'redundant-alias': 'syntactic',
+  'over-qualified-path': 'syntactic',
+  'banned-import': 'syntactic',
+  'unnecessary-import': 'syntactic',
+  'test-only-import': 'syntactic',
+};
+
+/** Whether a finding's confidence is at least `level` */
+export function meetsConfidence(finding: UnusedImport, level: ConfidenceLevel): boolean {
+  return CONFIDENCE_LEVELS.indexOf(finding.confidence ?? 'high') >= CONFIDENCE_LEVELS.indexOf(level);
+}
+
+/** Longest `importStatement` findings carry; longer ones are cut off with `…` */
+export const MAX_STATEMENT_LENGTH = 1000;
+
+/**
+ * The `importStatement` for the source from `start` to `end`: its whitespace runs
+ * replaced by `separator`, cut off at `MAX_STATEMENT_LENGTH`. Only about as much as
+ * is kept gets copied, so a generated `use` group megabytes long costs no more than
+ * a short one, however many of its leaves are reported.
+ */
+export function statementText(content: string, start: number, end: number, separator = ' '): string {
+  for (let window = MAX_STATEMENT_LENGTH; ; window *= 2) {
+    const whole = start + window >= end;
+    const text = content.slice(start, whole ? end : start + window).replace(/\s+/g, separator);
+    if (text.length > MAX_STATEMENT_LENGTH) return `${text.slice(0, MAX_STATEMENT_LENGTH)}…`;
+    if (whole) return text;
+  }
+}
+
+export interface SuggestedFix {
+  description: string;
+  /** Replacements in UTF-8 byte offsets, like `byteSpan` */
+  edits: TextEdit[];
+}
+
+export interface SourceSpan {
+  /** Offset of the first character */
+  start: number;
+  /** Offset one past the last character */
+  end: number;
 }
 
 // Minimum number of files to justify spawning worker threads
 const WORKER_THRESHOLD = 8;
 
-export class CLIAnalyzer {
-  private astAnalyzer: ASTAnalyzer;
-  private treeSitterAnalyzer: TreeSitterAnalyzer;
+/** Counts `count` more files of a run as done, `filePath` being the last of them */
+type Advance = (count: number, filePath?: string) => void;
 
-  constructor(private args: CLIArguments) {
-    this.astAnalyzer = new ASTAnalyzer();
-    this.treeSitterAnalyzer = new TreeSitterAnalyzer();
+/**
+ * Runs an `Analyzer` over the files of a CLI run, adding caching, worker threads
+ * and the findings that need more than one file
+ */
+export class CLIAnalyzer {
+  private analyzer: Analyzer;
+
+  constructor(private args: CLIArguments, private config = new ConfigResolver(args.configOverrides, args.language)) {
+    this.analyzer = Analyzer.builder()
+      .configResolver(config)
+      .minConfidence(args.minConfidence ?? 'medium')
+      .strictMacros(args.strictMacros)
+      .reportUnusedSuppressions(args.reportUnusedSuppressions)
+      .checkDoctests(args.checkDoctests)
+      .checkQualifiedPaths(args.qualifiedPathThreshold ?? false)
+      .checkPrelude(args.checkPrelude)
+      .edition(args.edition)
+      .checkTestOnly(args.checkTestOnly)
+      .cfg(args.cfg)
+      .build();
   }
 
   /**
    * Analyze multiple files for unused imports.
-   * Uses worker threads for large file sets when running in the main thread.
+   * In the main thread, unchanged files are answered from the analysis cache and
+   * large sets of the remaining files are spread over a pool of `--jobs` worker
+   * threads. Results are always in the order of `files`. Rust results also carry
+   * findings that need the whole crate: unused glob imports of crate modules, and
+   * with `--check-reexports` unused re-exports; with `--crate-root`, imports other
+   * modules use are no longer reported; with
+   * `--check-dependencies`, a result per `Cargo.toml` follows them. Banned imports
+   * of each file's `importlens.toml`, with `--check-prelude` unnecessary imports,
+   * test-only imports where enabled and C/C++ includes of headers the config lists
+   * identifiers for are found after the cache. Findings below
+   * `--min-confidence` are dropped, and the finding kinds and severities of each
+   * file's `importlens.toml` are applied last.
+   *
+   * `onProgress` is called as files finish, cache hits first. Once `cancellation` is
+   * cancelled no further file is started; the results so far are still cached, and
+   * the run rejects with an `ImportLensError` of kind `cancelled`.
    */
-  async analyzeFiles(files: string[]): Promise<AnalysisResult[]> {
-    if (isMainThread && files.length >= WORKER_THRESHOLD) {
+  async analyzeFiles(files: string[], run: RunOptions = {}): Promise<AnalysisResult[]> {
+    let done = 0;
+    const advance = (count: number, filePath?: string) => {
+      done += count;
+      run.onProgress?.({ done, total: files.length, ...(filePath !== undefined ? { filePath } : {}) });
+    };
+
+    if (!isMainThread) {
+      return this.analyzeUncached(files, advance, run.cancellation);
+    }
+
+    const results = this.args.cache
+      ? await this.analyzeCached(files, advance, run.cancellation)
+      : await this.analyzeUncached(files, advance, run.cancellation);
+    if (run.cancellation?.isCancelled) {
+      throw cancelledError(done, files.length);
+    }
+    const crates = new Map<string, RustCrate>();
+    if (this.args.crateRoot !== undefined) {
+      this.applyCrateUsage(results, crates);
+    }
+    this.addGlobFindings(results, crates);
+    if (this.args.checkReexports) {
+      this.addReexportFindings(results, crates);
+    }
+    this.addBannedFindings(results, crates);
+    if (this.args.checkPrelude) {
+      this.addPreludeFindings(results);
+    }
+    this.addTestOnlyFindings(results);
+    this.addHeaderFindings(results);
+    if (this.args.checkDependencies) {
+      results.push(...this.dependencyResults(results));
+    }
+    this.applyConfig(results);
+    return results;
+  }
+
+  private async analyzeCached(files: string[], advance: Advance, cancellation?: CancellationToken): Promise<AnalysisResult[]> {
+    const cache = AnalysisCache.forArgs(this.args);
+    const results = new Array<AnalysisResult>(files.length);
+    const misses: { index: number; content: string; language: string }[] = [];
+
+    files.forEach((filePath, index) => {
+      let content: string;
       try {
-        return await this.analyzeFilesParallel(files);
+        content = fs.readFileSync(filePath, 'utf-8');
+      } catch {
+        // Let the analysis report the read error
+        misses.push({ index, content: '', language: '' });
+        return;
+      }
+
+      const language = cacheLanguage(filePath, this.config.resolve(filePath).language);
+      const cached = cache.get(content, language);
+      if (cached) {
+        results[index] = { filePath, ...cached };
+      } else {
+        misses.push({ index, content, language });
+      }
+    });
+
+    if (misses.length < files.length) {
+      advance(files.length - misses.length);
+    }
+
+    // Holes are files a cancellation left unanalyzed
+    const analyzed = await this.analyzeUncached(misses.map(({ index }) => files[index]), advance, cancellation);
+    analyzed.forEach((result, i) => {
+      const { index, content, language } = misses[i];
+      results[index] = result;
+      // Files that failed or made an analyzer complain are retried every run
+      if (!result.error && !result.diagnostics) {
+        cache.set(content, language, { language: result.language, unusedImports: result.unusedImports });
+      }
+    });
+
+    cache.save();
+    return results;
+  }
+
+  private async analyzeUncached(files: string[], advance: Advance, cancellation?: CancellationToken): Promise<AnalysisResult[]> {
+    const jobs = this.args.jobs ?? os.cpus().length;
+
+    if (isMainThread && jobs > 1 && files.length >= WORKER_THRESHOLD) {
+      try {
+        // Resolve path relative to the compiled output directory
+        const pool = new WorkerPool(path.join(__dirname, 'AnalyzerWorker.js'), this.args, jobs);
+        return await pool.run(files, result => advance(1, result.filePath), cancellation);
       } catch (err) {
         // Graceful degradation: fall through to sequential processing
         if (this.args.format !== 'json') {
@@ -50,388 +335,262 @@ export class CLIAnalyzer {
 
     const results: AnalysisResult[] = [];
     for (const file of files) {
+      // Lets a SIGINT handler or an embedder's timer cancel between files
+      if (cancellation) await nextTurn();
+      if (cancellation?.isCancelled) break;
       results.push(await this.analyzeFile(file));
+      advance(1, file);
     }
     return results;
   }
 
   /**
-   * Distribute files across CPU-count worker threads for maximum throughput.
+   * Drop findings below `--min-confidence` or of kinds a file's config disables,
+   * and mark configured severities
    */
-  private async analyzeFilesParallel(files: string[]): Promise<AnalysisResult[]> {
-    const cpuCount = os.cpus().length;
-    const workerCount = Math.min(cpuCount, Math.ceil(files.length / 2));
-    const chunkSize = Math.ceil(files.length / workerCount);
-
-    const chunks: string[][] = [];
-    for (let i = 0; i < files.length; i += chunkSize) {
-      chunks.push(files.slice(i, i + chunkSize));
+  private applyConfig(results: AnalysisResult[]): void {
+    for (const result of results) {
+      if (result.unusedImports.length === 0) continue;
+      result.unusedImports = this.analyzer.filter(result.unusedImports, this.config.resolve(result.filePath));
     }
-
-    // Resolve path relative to the compiled output directory
-    const workerPath = path.join(__dirname, 'AnalyzerWorker.js');
-
-    const workerPromises = chunks.map(
-      chunk =>
-        new Promise<AnalysisResult[]>((resolve, reject) => {
-          const worker = new Worker(workerPath, {
-            workerData: { files: chunk, args: this.args },
-          });
-          worker.once('message', (results: AnalysisResult[]) => resolve(results));
-          worker.once('error', reject);
-          worker.once('exit', code => {
-            if (code !== 0) reject(new Error(`Worker exited with code ${code}`));
-          });
-        })
-    );
-
-    const results = await Promise.all(workerPromises);
-    return results.flat();
   }
 
   /**
-   * Analyze a single file for unused imports
+   * `--crate-root`: drop unused-import findings for Rust imports that other modules of
+   * the crate use through `super::` or `crate::` paths, and report problems with the
+   * crate's module tree (missing module files, files mounted twice) at the `mod` items
+   * that cause them. Depends on other files, so it is applied after the cache.
    */
-  private async analyzeFile(filePath: string): Promise<AnalysisResult> {
-    try {
-      const content = fs.readFileSync(filePath, 'utf-8');
-      const language = this.detectLanguage(filePath);
-      const unusedImports = this.findUnusedImports(content, language, filePath);
+  private applyCrateUsage(results: AnalysisResult[], crates: Map<string, RustCrate>): void {
+    const reported = new Set<RustCrate>();
 
-      return { filePath, language, unusedImports };
-    } catch (error) {
-      return {
-        filePath,
-        language: 'unknown',
-        unusedImports: [],
-        error: error instanceof Error ? error.message : String(error),
-      };
+    for (const result of results) {
+      if (result.language !== 'rust' || result.error) continue;
+
+      const crate = RustCrate.forFile(result.filePath, crates, this.args.crateRoot);
+      const file = crate.fileFor(result.filePath);
+      if (!file) continue;
+
+      if (!reported.has(crate)) {
+        reported.add(crate);
+        for (const diagnostic of crate.diagnostics) {
+          const target = results.find(r => path.resolve(r.filePath) === diagnostic.filePath) ?? result;
+          target.diagnostics = [...(target.diagnostics ?? []), `[WARNING] ${diagnostic.filePath}:${diagnostic.line}: ${diagnostic.message}`];
+        }
+      }
+
+      if (result.unusedImports.length === 0) continue;
+      const used = new Set(this.analyzer.findCrateUsedImports(file.content, crate, file.modulePath, result.unusedImports));
+      if (used.size > 0) result.unusedImports = result.unusedImports.filter(finding => !used.has(finding));
     }
   }
 
   /**
-   * Detect programming language from file extension
+   * `--check-reexports`: add findings for crate-visible Rust re-exports that nothing
+   * in their crate refers to. These depend on other files, so they are never cached.
    */
-  private detectLanguage(filePath: string): string {
-    const ext = path.extname(filePath).toLowerCase();
-    const languageMap: Record<string, string> = {
-      '.ts': 'typescript',
-      '.tsx': 'typescriptreact',
-      '.js': 'javascript',
-      '.jsx': 'javascriptreact',
-      '.py': 'python',
-      '.java': 'java',
-      '.go': 'go',
-      '.rs': 'rust',
-      '.cpp': 'cpp',
-      '.cc': 'cpp',
-      '.cxx': 'cpp',
-      '.c': 'c',
-      '.h': 'c',
-      '.hpp': 'cpp',
-    };
-    return languageMap[ext] || 'unknown';
+  private addReexportFindings(results: AnalysisResult[], crates: Map<string, RustCrate>): void {
+    for (const result of results) {
+      if (result.language !== 'rust' || result.error) continue;
+
+      const crate = RustCrate.forFile(result.filePath, crates, this.args.crateRoot);
+      const file = crate.fileFor(result.filePath);
+      if (!file) continue;
+
+      const findings = this.analyzer.findUnusedReexports(file.content, crate, file.modulePath, file.skipped);
+      if (findings.length === 0) continue;
+
+      result.unusedImports = [...result.unusedImports, ...findings].sort((a, b) => a.line - b.line);
+    }
   }
 
   /**
-   * Find unused imports in file content.
-   * Priority: Babel AST (TS/JS) > Tree-sitter > regex heuristics.
+   * Check Rust glob imports of modules in the same crate, for files that have any.
+   * Like re-export findings, these depend on other files and are never cached. With
+   * `--expand-globs`, used globs get the edits that expand them, and globs that can't
+   * be checked are noted.
    */
-  private findUnusedImports(
-    content: string,
-    language: string,
-    filePath: string
-  ): UnusedImport[] {
-    const lines = content.split('\n');
-
-    switch (language) {
-      case 'typescript':
-      case 'typescriptreact':
-      case 'javascript':
-      case 'javascriptreact':
-        return this.analyzeTypeScriptWithAST(content, lines, filePath);
-
-      case 'python':
-        if (this.treeSitterAnalyzer.canHandle('python')) {
-          return this.treeSitterAnalyzer.analyzeFile(content, 'python', filePath);
-        }
-        return this.analyzePythonImports(lines, content);
-
-      case 'java':
-        if (this.treeSitterAnalyzer.canHandle('java')) {
-          return this.treeSitterAnalyzer.analyzeFile(content, 'java', filePath);
-        }
-        return this.analyzeJavaImports(lines, content);
-
-      case 'go':
-        if (this.treeSitterAnalyzer.canHandle('go')) {
-          return this.treeSitterAnalyzer.analyzeFile(content, 'go', filePath);
-        }
-        return this.analyzeGoImports(lines, content);
+  private addGlobFindings(results: AnalysisResult[], crates: Map<string, RustCrate>): void {
+    for (const result of results) {
+      if (result.language !== 'rust' || result.error) continue;
+      try {
+        if (!/::\s*\*/.test(fs.readFileSync(result.filePath, 'utf-8'))) continue;
+      } catch {
+        continue;
+      }
 
-      case 'rust':
-        if (this.treeSitterAnalyzer.canHandle('rust')) {
-          return this.treeSitterAnalyzer.analyzeFile(content, 'rust', filePath);
-        }
-        return this.analyzeRustImports(lines, content);
+      const crate = RustCrate.forFile(result.filePath, crates, this.args.crateRoot);
+      const file = crate.fileFor(result.filePath);
+      if (!file) continue;
 
-      case 'cpp':
-      case 'c':
-        return this.analyzeCppImports(lines, content);
+      const { unused, expansions, skipped } = this.analyzer.analyzeGlobs(file.content, crate, file.modulePath, file.skipped);
+      if (unused.length > 0) {
+        result.unusedImports = [...result.unusedImports, ...unused].sort((a, b) => a.line - b.line);
+      }
+      if (!this.args.expandGlobs) continue;
 
-      default:
-        return [];
+      if (expansions.length > 0) result.globExpansions = expansions;
+      const notes = skipped.map(glob => `[NOTE] ${result.filePath}:${glob.line}: not expanding \`${glob.importPath}\`: ${glob.reason}`);
+      if (notes.length > 0) result.diagnostics = [...(result.diagnostics ?? []), ...notes];
     }
   }
 
   /**
-   * Analyze TypeScript/JavaScript imports using AST parsing
-   * Provides 100% accuracy compared to regex-based approach
+   * Add `banned-import` findings for Rust files whose config bans paths. Bans depend
+   * on the config files above each file, so they are never cached. `crates` resolves
+   * relative paths; without it (editor buffers), paths resolve as if in the crate root.
    */
-  private analyzeTypeScriptWithAST(content: string, lines: string[], filePath: string): UnusedImport[] {
-    try {
-      const result = this.astAnalyzer.analyzeTypeScriptFile(content, filePath);
-
-      return result.unusedImports.map(astUnused => {
-        const importStatement = lines[astUnused.line - 1]?.trim() || '';
-        return {
-          line: astUnused.line,
-          importStatement,
-          symbols: astUnused.unusedSpecifiers,
-          reason:
-            astUnused.unusedSpecifiers.length === astUnused.allSpecifiers.length
-              ? `All imports from '${astUnused.source}' are unused`
-              : `Unused: ${astUnused.unusedSpecifiers.join(', ')} from '${astUnused.source}'`,
-        };
-      });
-    } catch (error) {
-      console.error(`AST parsing failed for ${filePath}, falling back to regex:`, error);
-      return this.analyzeTypeScriptImportsRegex(lines, content);
+  private addBannedFindings(results: AnalysisResult[], crates?: Map<string, RustCrate>, content?: string): void {
+    for (const result of results) {
+      if (result.language !== 'rust' || result.error) continue;
+      const { banned } = this.config.resolve(result.filePath);
+      if (banned.length === 0) continue;
+
+      const crate = crates && RustCrate.forFile(result.filePath, crates, this.args.crateRoot);
+      const file = crate?.fileFor(result.filePath);
+      const recovered = content === undefined && file ? file : rustSource(result.filePath, content);
+      if (!recovered) continue;
+
+      const findings = this.analyzer.findBannedImports(recovered.content, banned, file && crate, file?.modulePath, recovered.skipped);
+      if (findings.length === 0) continue;
+      result.unusedImports = [...result.unusedImports, ...findings].sort((a, b) => a.line - b.line);
     }
   }
 
   /**
-   * Fallback regex-based TypeScript/JavaScript analysis
+   * `--check-prelude`: imports of what the prelude already provides. They depend on
+   * the edition in each file's `Cargo.toml`, so like banned imports they are found
+   * after the cache.
    */
-  private analyzeTypeScriptImportsRegex(lines: string[], content: string): UnusedImport[] {
-    const unused: UnusedImport[] = [];
-    const importRegex = /^import\s+(?:{([^}]+)}|(\w+))\s+from\s+['"]([^'"]+)['"]/;
-
-    lines.forEach((line, index) => {
-      const trimmed = line.trim();
-      const match = trimmed.match(importRegex);
-
-      if (match) {
-        const namedImports = match[1] ? match[1].split(',').map(s => s.trim()) : [];
-        const defaultImport = match[2];
-        const symbols = [...namedImports, defaultImport].filter(Boolean);
-
-        const unusedSymbols = symbols.filter(symbol => {
-          const codeWithoutImports = lines.filter((_, i) => i !== index).join('\n');
-          const symbolRegex = new RegExp(`\\b${symbol}\\b`, 'g');
-          const matches = codeWithoutImports.match(symbolRegex);
-          return !matches || matches.length === 0;
-        });
-
-        if (unusedSymbols.length > 0) {
-          unused.push({
-            line: index + 1,
-            importStatement: trimmed,
-            symbols: unusedSymbols,
-            reason: `Symbol(s) ${unusedSymbols.join(', ')} not used in code`,
-          });
-        }
-      }
-    });
+  private addPreludeFindings(results: AnalysisResult[], content?: string): void {
+    for (const result of results) {
+      if (result.language !== 'rust' || result.error) continue;
+      const recovered = rustSource(result.filePath, content);
+      if (!recovered) continue;
 
-    return unused;
+      result.unusedImports = this.analyzer.addUnnecessaryImports(recovered.content, result.unusedImports, result.filePath, recovered.skipped);
+    }
   }
 
   /**
-   * Regex-based Python import analysis (fallback when tree-sitter is unavailable)
+   * Imports only test code uses, in files where `--check-test-only` or their
+   * `importlens.toml` turns them on. Like banned imports, that depends on the config
+   * files above each file, so they are found after the cache.
    */
-  private analyzePythonImports(lines: string[], content: string): UnusedImport[] {
-    const unused: UnusedImport[] = [];
-    const importRegex = /^(?:from\s+\S+\s+)?import\s+(.+)/;
-
-    lines.forEach((line, index) => {
-      const trimmed = line.trim();
-      const match = trimmed.match(importRegex);
-
-      if (match) {
-        const imports = match[1].split(',').map(s => s.trim().split(' as ')[0]);
-
-        const unusedSymbols = imports.filter(symbol => {
-          const codeWithoutImports = lines.filter((_, i) => i !== index).join('\n');
-          const symbolRegex = new RegExp(`\\b${symbol}\\b`, 'g');
-          const matches = codeWithoutImports.match(symbolRegex);
-          return !matches || matches.length === 0;
-        });
-
-        if (unusedSymbols.length > 0) {
-          unused.push({
-            line: index + 1,
-            importStatement: trimmed,
-            symbols: unusedSymbols,
-            reason: `Symbol(s) ${unusedSymbols.join(', ')} not used in code`,
-          });
-        }
-      }
-    });
-
-    return unused;
+  private addTestOnlyFindings(results: AnalysisResult[], content?: string): void {
+    for (const result of results) {
+      if (result.language !== 'rust' || result.error) continue;
+      if (!this.analyzer.checksTestOnly(this.config.resolve(result.filePath))) continue;
+      const recovered = rustSource(result.filePath, content);
+      if (!recovered) continue;
+
+      result.unusedImports = this.analyzer.addTestOnlyImports(recovered.content, result.unusedImports, recovered.skipped);
+    }
   }
 
   /**
-   * Regex-based Java import analysis (fallback when tree-sitter is unavailable)
+   * C/C++ includes checked again against the identifiers the `headers` table of each
+   * file's `importlens.toml` adds. Like banned imports, they depend on the config files
+   * above each file, so they are found after the cache.
    */
-  private analyzeJavaImports(lines: string[], content: string): UnusedImport[] {
-    const unused: UnusedImport[] = [];
-    const importRegex = /^import\s+(?:static\s+)?([a-zA-Z0-9_.]+(?:\.\*)?);/;
-
-    lines.forEach((line, index) => {
-      const trimmed = line.trim();
-      const match = trimmed.match(importRegex);
-
-      if (match) {
-        const importPath = match[1];
-        const className = importPath.split('.').pop() || '';
-
-        if (className !== '*') {
-          const codeWithoutImports = lines.filter((_, i) => i !== index).join('\n');
-          const classRegex = new RegExp(`\\b${className}\\b`, 'g');
-          const matches = codeWithoutImports.match(classRegex);
-
-          if (!matches || matches.length === 0) {
-            unused.push({
-              line: index + 1,
-              importStatement: trimmed,
-              symbols: [className],
-              reason: `Class ${className} not used in code`,
-            });
-          }
-        }
+  private addHeaderFindings(results: AnalysisResult[], content?: string): void {
+    for (const result of results) {
+      if ((result.language !== 'c' && result.language !== 'cpp') || result.error) continue;
+      const { headers } = this.config.resolve(result.filePath);
+      if (Object.keys(headers).length === 0) continue;
+      let source = content;
+      try {
+        source ??= fs.readFileSync(result.filePath, 'utf-8');
+      } catch {
+        continue;
       }
-    });
 
-    return unused;
+      result.unusedImports = this.analyzer.applyHeaders(source, result.unusedImports, headers, result.filePath);
+    }
   }
 
   /**
-   * Regex-based Go import analysis (fallback when tree-sitter is unavailable)
+   * `--check-dependencies`: one result per `Cargo.toml` of the analyzed Rust files,
+   * listing the dependencies that no source file of its package refers to.
+   * Like re-export findings, these depend on other files and are never cached.
    */
-  private analyzeGoImports(lines: string[], content: string): UnusedImport[] {
-    const unused: UnusedImport[] = [];
-    const importRegex = /^\s*(?:"([^"]+)"|(\w+)\s+"([^"]+)")/;
-
-    let inImportBlock = false;
-
-    lines.forEach((line, index) => {
-      const trimmed = line.trim();
-
-      if (trimmed === 'import (') { inImportBlock = true; return; }
-      if (trimmed === ')' && inImportBlock) { inImportBlock = false; return; }
-
-      if (trimmed.startsWith('import ') || inImportBlock) {
-        const match = trimmed.match(importRegex);
-        if (match) {
-          const packagePath = match[1] || match[3];
-          const alias = match[2] || packagePath.split('/').pop();
-
-          if (alias) {
-            const codeWithoutImports = lines.filter((_, i) => i !== index).join('\n');
-            const pkgRegex = new RegExp(`\\b${alias}\\.`, 'g');
-            const matches = codeWithoutImports.match(pkgRegex);
-
-            if (!matches || matches.length === 0) {
-              unused.push({
-                line: index + 1,
-                importStatement: trimmed,
-                symbols: [alias],
-                reason: `Package ${alias} not used in code`,
-              });
-            }
-          }
-        }
+  private dependencyResults(results: AnalysisResult[]): AnalysisResult[] {
+    const manifests = new Set<string>();
+    for (const result of results) {
+      if (result.language !== 'rust') continue;
+      const manifest = findManifest(result.filePath);
+      if (manifest) manifests.add(manifest);
+    }
+
+    return [...manifests].sort().map(filePath => {
+      try {
+        return { filePath, language: 'toml', unusedImports: findUnusedDependencies(filePath) };
+      } catch (error) {
+        return { filePath, language: 'toml', unusedImports: [], error: error instanceof Error ? error.message : String(error) };
       }
     });
-
-    return unused;
   }
 
   /**
-   * Regex-based Rust import analysis (fallback when tree-sitter is unavailable)
+   * Analyze in-memory content as if it were the file at `filePath`, e.g. an editor
+   * buffer with unsaved changes. Crate-level findings (globs, `--check-reexports`) are not
+   * computed, since they need the other files on disk.
    */
-  private analyzeRustImports(lines: string[], content: string): UnusedImport[] {
-    const unused: UnusedImport[] = [];
-    const useRegex = /^use\s+([^;]+);/;
-
-    lines.forEach((line, index) => {
-      const trimmed = line.trim();
-      const match = trimmed.match(useRegex);
-
-      if (match) {
-        const importPath = match[1];
-        const symbols = importPath.includes('{')
-          ? importPath.match(/{([^}]+)}/)?.[1].split(',').map(s => s.trim()) || []
-          : [importPath.split('::').pop()?.trim() || ''];
-
-        const unusedSymbols = symbols.filter(symbol => {
-          const codeWithoutImports = lines.filter((_, i) => i !== index).join('\n');
-          const symbolRegex = new RegExp(`\\b${symbol}\\b`, 'g');
-          const matches = codeWithoutImports.match(symbolRegex);
-          return !matches || matches.length === 0;
-        });
-
-        if (unusedSymbols.length > 0) {
-          unused.push({
-            line: index + 1,
-            importStatement: trimmed,
-            symbols: unusedSymbols,
-            reason: `Symbol(s) ${unusedSymbols.join(', ')} not used in code`,
-          });
-        }
-      }
-    });
-
-    return unused;
+  analyzeContent(filePath: string, content: string): AnalysisResult {
+    const result = this.analyzeSource(filePath, () => content);
+    this.addBannedFindings([result], undefined, content);
+    if (this.args.checkPrelude) {
+      this.addPreludeFindings([result], content);
+    }
+    this.addTestOnlyFindings([result], content);
+    this.addHeaderFindings([result], content);
+    this.applyConfig([result]);
+    return result;
   }
 
   /**
-   * C/C++ include analysis
+   * Analyze a single file for unused imports
    */
-  private analyzeCppImports(lines: string[], content: string): UnusedImport[] {
-    const unused: UnusedImport[] = [];
-    const includeRegex = /^#include\s+[<"]([^>"]+)[>"]/;
-
-    lines.forEach((line, index) => {
-      const trimmed = line.trim();
-      const match = trimmed.match(includeRegex);
-
-      if (match) {
-        const header = match[1];
-        // Derive likely identifier from header name (e.g. stdio.h -> stdio, vector -> vector)
-        const symbol = header.replace(/\.h(pp)?$/, '').split('/').pop() || header;
-
-        // Skip common headers that are always used via macros/types without a symbol reference
-        const alwaysUsed = ['stdio', 'stdlib', 'string', 'stddef', 'stdint', 'stdbool'];
-        if (alwaysUsed.includes(symbol)) return;
-
-        const codeWithoutIncludes = lines.filter((_, i) => i !== index).join('\n');
-        const symbolRegex = new RegExp(`\\b${symbol}\\b`, 'g');
-        const matches = codeWithoutIncludes.match(symbolRegex);
-
-        if (!matches || matches.length === 0) {
-          unused.push({
-            line: index + 1,
-            importStatement: trimmed,
-            symbols: [symbol],
-            reason: `Header <${header}> may be unused`,
-          });
-        }
-      }
-    });
+  private async analyzeFile(filePath: string): Promise<AnalysisResult> {
+    return this.analyzeSource(filePath, () => fs.readFileSync(filePath, 'utf-8'));
+  }
+
+  private analyzeSource(filePath: string, read: () => string): AnalysisResult {
+    try {
+      const content = read();
+      const language = this.config.resolve(filePath).language;
+      const { unusedImports, diagnostics } = this.analyzer.detect(content, language, filePath);
+
+      return { filePath, language, unusedImports, diagnostics: diagnostics.length > 0 ? diagnostics : undefined };
+    } catch (error) {
+      return {
+        filePath,
+        language: 'unknown',
+        unusedImports: [],
+        error: error instanceof Error ? error.message : String(error),
+      };
+    }
+  }
+}
+
+/**
+ * Language a file's findings are cached under. A C/C++ header gets findings of lower
+ * confidence than a source file with the same text, so the two are kept apart.
+ */
+function cacheLanguage(filePath: string, language: string): string {
+  return (language === 'c' || language === 'cpp') && isHeaderPath(filePath) ? `${language}-header` : language;
+}
 
-    return unused;
+/**
+ * A Rust file as the passes after the cache take it: `content`, or else the file on
+ * disk, with syntax errors neutralized by `recoverRustSyntax`. Undefined when the file
+ * can't be read.
+ */
+function rustSource(filePath: string, content?: string): Pick<CrateFile, 'content' | 'skipped'> | undefined {
+  try {
+    const { source, skipped } = recoverRustSyntax(content ?? fs.readFileSync(filePath, 'utf-8'));
+    return { content: source, skipped };
+  } catch {
+    return undefined;
   }
 }
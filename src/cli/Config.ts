@@ -0,0 +1,371 @@
+import * as fs from 'fs';
+import * as path from 'path';
+import { FINDING_KINDS, FindingKind, isRuleKind } from './CLIAnalyzer';
+import { IgnoreRules } from './IgnoreRules';
+import { LANGUAGE_NAMES, normalizeLanguage, resolveLanguage } from './Languages';
+import { toPosixPath } from './Paths';
+import { isBannedPattern } from './RustBannedImports';
+import { parseToml, TomlTable, TomlValue } from './Toml';
+
+/** Name of the per-directory configuration file */
+export const CONFIG_FILE_NAME = 'importlens.toml';
+
+/**
+ * How a finding kind is treated: `error`, `warn` and `info` report it with that
+ * severity (only errors fail `--check`, unless `--fail-on` says otherwise), `off`
+ * doesn't report it at all
+ */
+export type Severity = 'error' | 'warn' | 'info' | 'off';
+
+const SEVERITIES: Severity[] = ['error', 'warn', 'info', 'off'];
+
+/**
+ * Analysis settings, as read from an `importlens.toml` or built programmatically.
+ * Every field is optional; unset fields inherit from farther config files.
+ */
+export interface Config {
+  /** Built-in finding kinds to report; every kind when unset */
+  kinds?: FindingKind[];
+  /** By built-in kind, or by a custom rule's `namespace/name` */
+  severity?: Partial<Record<FindingKind, Severity>>;
+  /** Gitignore-style patterns of files to skip, relative to the config's directory */
+  ignore?: string[];
+  /** Language to analyze matching files as, by gitignore-style pattern */
+  languages?: Record<string, string>;
+  /** Rust paths whose import or use is a `banned-import` finding */
+  banned?: BannedImport[];
+  /**
+   * C/C++: identifiers each header provides, by the name `#include` gives it, added to
+   * the built-in ones; an empty list keeps includes of the header from being reported
+   */
+  headers?: Record<string, string[]>;
+  fix?: FixConfig;
+}
+
+/**
+ * An entry of `banned`: `{ path = "chrono::*", message = "use time instead" }`
+ */
+export interface BannedImport {
+  /** Rust path pattern; see `bannedPathMatcher` for what it matches */
+  path: string;
+  /** Why the path is banned, added to each finding's reason */
+  message?: string;
+  /** Gitignore-style patterns of files, relative to the config's directory, that may use the path */
+  allowIn?: string[];
+}
+
+export interface FixConfig {
+  /** `false` keeps `--fix` from rewriting files under this directory */
+  enabled?: boolean;
+  /** Finding kinds `--fix` removes; every fixable kind when unset */
+  kinds?: FindingKind[];
+}
+
+/**
+ * The settings that apply to one file after merging every config file above it
+ */
+export interface EffectiveConfig {
+  /** Config files that contributed, farthest first */
+  sources: string[];
+  /** Whether the file is skipped when directories are walked */
+  ignored: boolean;
+  language: string;
+  kinds: FindingKind[];
+  /** Severities set by a config; unset kinds keep their default severity */
+  severity: Partial<Record<FindingKind, Severity>>;
+  /** Bans whose `allowIn` patterns don't match the file */
+  banned: BannedImport[];
+  /** `headers` entries of every config, the nearest one winning for each header */
+  headers: Record<string, string[]>;
+  fix: { enabled: boolean; kinds: FindingKind[] };
+}
+
+interface ConfigLayer {
+  /** Directory that relative patterns are matched against */
+  dir: string;
+  source?: string;
+  config: Config;
+  ignore?: IgnoreRules;
+  languages?: Array<{ rules: IgnoreRules; language: string }>;
+  banned?: Array<{ ban: BannedImport; allowIn?: IgnoreRules }>;
+}
+
+/**
+ * Parse and validate the contents of an `importlens.toml`
+ */
+export function parseConfig(source: string, fileName: string = CONFIG_FILE_NAME): Config {
+  const fail = (message: string): never => {
+    throw new Error(`${fileName}: ${message}`);
+  };
+
+  let table: TomlTable;
+  try {
+    table = parseToml(source);
+  } catch (error) {
+    return fail(error instanceof Error ? error.message : String(error));
+  }
+
+  const kinds = (value: TomlValue, key: string): FindingKind[] => stringList(value, key, fail).map(kind => {
+    if (!(FINDING_KINDS as readonly string[]).includes(kind)) {
+      fail(`Unknown finding kind '${kind}' in ${key}. Must be one of: ${FINDING_KINDS.join(', ')}`);
+    }
+    return kind as FindingKind;
+  });
+
+  const config: Config = {};
+  for (const [key, value] of Object.entries(table)) {
+    switch (key) {
+      case 'kinds':
+        config.kinds = kinds(value, key);
+        break;
+
+      case 'ignore':
+        config.ignore = stringList(value, key, fail);
+        break;
+
+      case 'severity':
+        config.severity = {};
+        for (const [kind, severity] of Object.entries(subTable(value, key, fail))) {
+          // Custom rules can be turned off by their namespaced kind
+          const [checked] = isRuleKind(kind) ? [kind] : kinds([kind], key);
+          if (!SEVERITIES.includes(severity as Severity)) {
+            fail(`Invalid severity for ${kind}: ${JSON.stringify(severity)}. Must be one of: ${SEVERITIES.join(', ')}`);
+          }
+          config.severity[checked] = severity as Severity;
+        }
+        break;
+
+      case 'languages':
+        config.languages = {};
+        for (const [pattern, name] of Object.entries(subTable(value, key, fail))) {
+          const language = typeof name === 'string' ? normalizeLanguage(name) : null;
+          if (!language) {
+            fail(`Invalid language for ${pattern}: ${JSON.stringify(name)}. Must be one of: ${LANGUAGE_NAMES.join(', ')}`);
+          }
+          config.languages[pattern] = language!;
+        }
+        break;
+
+      case 'banned':
+        if (!Array.isArray(value)) fail('banned must be an array of tables');
+        config.banned = (value as TomlValue[]).map((entry, k) => bannedImport(entry, `banned[${k}]`, fail));
+        break;
+
+      case 'headers':
+        config.headers = {};
+        for (const [header, identifiers] of Object.entries(subTable(value, key, fail))) {
+          config.headers[header] = stringList(identifiers, `headers."${header}"`, fail);
+        }
+        break;
+
+      case 'fix':
+        config.fix = {};
+        for (const [fixKey, fixValue] of Object.entries(subTable(value, key, fail))) {
+          if (fixKey === 'enabled') {
+            if (typeof fixValue !== 'boolean') fail('fix.enabled must be true or false');
+            config.fix.enabled = fixValue as boolean;
+          } else if (fixKey === 'kinds') {
+            config.fix.kinds = kinds(fixValue, 'fix.kinds');
+          } else {
+            fail(`Unknown key fix.${fixKey}`);
+          }
+        }
+        break;
+
+      default:
+        fail(`Unknown key ${key}`);
+    }
+  }
+
+  return config;
+}
+
+/**
+ * Parse a `--severity` value: comma-separated `kind=level` pairs, such as
+ * `unused-import=error,duplicate-import=warn`
+ */
+export function parseSeverityList(value: string): Partial<Record<FindingKind, Severity>> {
+  const severity: Partial<Record<FindingKind, Severity>> = {};
+
+  for (const entry of value.split(',')) {
+    const [kind, level, ...rest] = entry.split('=').map(part => part.trim());
+    if (!kind || level === undefined || rest.length > 0) {
+      throw new Error(`Invalid --severity entry: ${entry}. Expected kind=level`);
+    }
+    if (!(FINDING_KINDS as readonly string[]).includes(kind) && !isRuleKind(kind)) {
+      throw new Error(`Unknown finding kind '${kind}' in --severity. Must be one of: ${FINDING_KINDS.join(', ')}`);
+    }
+    if (!SEVERITIES.includes(level as Severity)) {
+      throw new Error(`Invalid severity for ${kind}: ${JSON.stringify(level)}. Must be one of: ${SEVERITIES.join(', ')}`);
+    }
+    severity[kind as FindingKind] = level as Severity;
+  }
+
+  return severity;
+}
+
+/**
+ * Read and validate an `importlens.toml`
+ */
+export function loadConfig(filePath: string): Config {
+  return parseConfig(fs.readFileSync(filePath, 'utf-8'), filePath);
+}
+
+/**
+ * Works out the settings for each file from the `importlens.toml` files in its
+ * directory and every parent directory. Nearer files win: lists (`banned` too) and
+ * `fix` values replace farther ones, `severity`, `languages` and `headers` entries
+ * are merged key by key, and `ignore` patterns are checked nearest first, so `!pattern` in a nested config
+ * re-includes files a parent config ignores.
+ *
+ * `overrides` (for instance built from CLI flags) apply on top of every config file,
+ * with patterns relative to `overridesDir`; `forcedLanguage` (`--language`) wins over
+ * any `languages` table.
+ */
+export class ConfigResolver {
+  private layers = new Map<string, ConfigLayer[]>();
+  private overridesLayer: ConfigLayer;
+
+  constructor(overrides: Config = {}, private forcedLanguage?: string, overridesDir: string = process.cwd()) {
+    this.overridesLayer = createLayer(path.resolve(overridesDir), overrides);
+  }
+
+  resolve(filePath: string): EffectiveConfig {
+    const resolved = path.resolve(filePath);
+    const layers = [...this.layersFor(path.dirname(resolved)), this.overridesLayer];
+
+    const effective: EffectiveConfig = {
+      sources: [],
+      ignored: false,
+      language: resolveLanguage(resolved, this.forcedLanguage),
+      kinds: [...FINDING_KINDS],
+      severity: {},
+      banned: [],
+      headers: {},
+      fix: { enabled: true, kinds: [...FINDING_KINDS] },
+    };
+
+    for (const layer of layers) {
+      const { config } = layer;
+      if (layer.source) effective.sources.push(layer.source);
+      if (config.kinds) effective.kinds = [...config.kinds];
+      if (config.severity) Object.assign(effective.severity, config.severity);
+      if (config.headers) Object.assign(effective.headers, config.headers);
+      if (config.fix?.enabled !== undefined) effective.fix.enabled = config.fix.enabled;
+      if (config.fix?.kinds) effective.fix.kinds = [...config.fix.kinds];
+
+      const relative = relativeTo(layer.dir, resolved);
+      if (layer.banned) {
+        effective.banned = layer.banned
+          .filter(({ allowIn }) => !allowIn || relative === undefined || !matchesPath(allowIn, relative))
+          .map(({ ban }) => ban);
+      }
+      if (relative === undefined) continue;
+
+      for (const { rules, language } of layer.languages ?? []) {
+        if (!this.forcedLanguage && matchesPath(rules, relative)) effective.language = resolveLanguage(resolved, language);
+      }
+    }
+
+    // Nearest verdict first, so nested configs can re-include what a parent ignores
+    for (let i = layers.length - 1; i >= 0; i--) {
+      const { ignore, dir } = layers[i];
+      const relative = relativeTo(dir, resolved);
+      if (!ignore || relative === undefined) continue;
+
+      const verdict = matchPath(ignore, relative);
+      if (verdict !== undefined) {
+        effective.ignored = verdict;
+        break;
+      }
+    }
+
+    return effective;
+  }
+
+  /**
+   * Config layers for a directory, farthest first. Cached, so sibling files share the work.
+   */
+  private layersFor(dir: string): ConfigLayer[] {
+    const cached = this.layers.get(dir);
+    if (cached) return cached;
+
+    const parent = path.dirname(dir);
+    const layers = parent === dir ? [] : [...this.layersFor(parent)];
+    const configPath = path.join(dir, CONFIG_FILE_NAME);
+    if (fs.existsSync(configPath)) {
+      layers.push(createLayer(dir, loadConfig(configPath), configPath));
+    }
+
+    this.layers.set(dir, layers);
+    return layers;
+  }
+}
+
+function createLayer(dir: string, config: Config, source?: string): ConfigLayer {
+  return {
+    dir,
+    source,
+    config,
+    ignore: config.ignore ? new IgnoreRules(config.ignore) : undefined,
+    languages: config.languages
+      ? Object.entries(config.languages).map(([pattern, language]) => ({ rules: new IgnoreRules([pattern]), language }))
+      : undefined,
+    banned: config.banned?.map(ban => ({ ban, allowIn: ban.allowIn ? new IgnoreRules(ban.allowIn) : undefined })),
+  };
+}
+
+/**
+ * Gitignore-style verdict for a file: a matching parent directory decides first,
+ * then the file itself. Undefined when no pattern applies.
+ */
+function matchPath(rules: IgnoreRules, relativePath: string): boolean | undefined {
+  const segments = relativePath.split('/');
+  for (let i = 1; i < segments.length; i++) {
+    if (rules.match(segments.slice(0, i).join('/'), true) === true) return true;
+  }
+  return rules.match(relativePath, false);
+}
+
+function matchesPath(rules: IgnoreRules, relativePath: string): boolean {
+  return matchPath(rules, relativePath) === true;
+}
+
+/** `/`-separated path of `filePath` inside `dir`, or undefined if it lies outside */
+function relativeTo(dir: string, filePath: string): string | undefined {
+  const relative = path.relative(dir, filePath);
+  if (relative.startsWith('..') || path.isAbsolute(relative)) return undefined;
+  return toPosixPath(relative);
+}
+
+function stringList(value: TomlValue, key: string, fail: (message: string) => never): string[] {
+  if (!Array.isArray(value) || !value.every(item => typeof item === 'string')) {
+    fail(`${key} must be an array of strings`);
+  }
+  return value as string[];
+}
+
+function subTable(value: TomlValue, key: string, fail: (message: string) => never): TomlTable {
+  if (typeof value !== 'object' || Array.isArray(value)) {
+    fail(`${key} must be a table`);
+  }
+  return value as TomlTable;
+}
+
+function bannedImport(value: TomlValue, key: string, fail: (message: string) => never): BannedImport {
+  const ban: Partial<BannedImport> = {};
+  for (const [field, fieldValue] of Object.entries(subTable(value, key, fail))) {
+    if (field === 'path' || field === 'message') {
+      if (typeof fieldValue !== 'string') fail(`${key}.${field} must be a string`);
+      ban[field] = fieldValue as string;
+    } else if (field === 'allow_in') {
+      ban.allowIn = stringList(fieldValue, `${key}.allow_in`, fail);
+    } else {
+      fail(`Unknown key ${key}.${field}`);
+    }
+  }
+
+  if (ban.path === undefined) fail(`${key} needs a path`);
+  if (!isBannedPattern(ban.path!)) fail(`Invalid path pattern in ${key}: ${JSON.stringify(ban.path)}. Expected a Rust path like chrono::*`);
+  return ban as BannedImport;
+}
@@ -0,0 +1,239 @@
+import * as path from 'path';
+import { UnusedImport } from './CLIAnalyzer';
+import { CPP_HEADER_IDENTIFIERS } from './CppHeaders';
+import { CppToken, tokenizeCpp } from './CppLexer';
+import { LineIndex } from './LineIndex';
+
+/**
+ * One `#include <header>` or `#include "header"` directive
+ */
+export interface CppInclude {
+  /** Header name without its delimiters, e.g. `vector` or `mylib/log.h` */
+  header: string;
+  /** Whether it is written `<header>` rather than `"header"` */
+  angled: boolean;
+  /** Offsets of the directive, `#` to the end of the header name */
+  start: number;
+  end: number;
+  /** The `#if` group it is in, other than an include guard, e.g. `` `#ifdef _WIN32` `` */
+  condition?: string;
+  /** Whether it is inside an `extern "C"` block */
+  externC: boolean;
+}
+
+interface Directive {
+  name: string;
+  /** Whitespace-normalized text, e.g. `#ifdef _WIN32` */
+  text: string;
+  /** Index of the `directive` token */
+  index: number;
+  /** Index of the token after its `newline` */
+  after: number;
+}
+
+const HEADER_EXTENSIONS = new Set(['.h', '.hh', '.hpp', '.hxx', '.h++', '.inl', '.ipp', '.tpp']);
+const GUARD = /^#(?:ifndef\s+(\w+)|if\s+!\s*defined\s*(?:\(\s*(\w+)\s*\)|(\w+)))$/;
+// Identifiers of a header listed in a reason
+const LISTED_IDENTIFIERS = 3;
+
+/**
+ * Heuristic `#include` analyzer for C and C++ files.
+ *
+ * Without a preprocessor and the headers themselves, an include can't be proven
+ * unused. Instead each header has a list of the identifiers it provides
+ * (`CPP_HEADER_IDENTIFIERS`, extended by the `headers` table of `importlens.toml`),
+ * and an include none of whose identifiers appears in the file is reported with
+ * `medium` confidence. Headers without a list, includes by macro and `#import`
+ * are never reported.
+ *
+ * - Identifiers count anywhere outside `#include` lines, macro definitions and `#if`
+ *   conditions included, but not as members (`x.size`)
+ * - Namespaced identifiers (`std::vector`) count when written out, or bare after
+ *   `using namespace std;`
+ * - Includes of a header file (by extension, include guard or `#pragma once`), inside
+ *   `extern "C"` or under `#if`/`#ifdef`, other than an include guard, have `low`
+ *   confidence: includers, other configurations or C code may rely on them
+ */
+/**
+ * Whether `filePath` has a header extension, which lowers the confidence of its findings
+ */
+export function isHeaderPath(filePath: string): boolean {
+  return HEADER_EXTENSIONS.has(path.extname(filePath).toLowerCase());
+}
+
+export class CppAnalyzer {
+  /**
+   * Findings for the includes of `content`; `headers` adds identifiers to what each
+   * header provides, and an empty list keeps a header from being reported
+   */
+  analyzeFile(content: string, filePath = '', headers: Record<string, string[]> = {}): UnusedImport[] {
+    const tokens = tokenizeCpp(content);
+    const { includes, guarded } = parseIncludes(tokens, content);
+    if (includes.length === 0) return [];
+
+    const used = usedNames(tokens);
+    const inHeader = guarded || isHeaderPath(filePath);
+    const lineIndex = new LineIndex(content);
+
+    const findings: UnusedImport[] = [];
+    for (const include of includes) {
+      const configured = headers[include.header];
+      const identifiers = configured === undefined
+        ? CPP_HEADER_IDENTIFIERS[include.header]
+        : configured.length === 0 ? [] : [...(CPP_HEADER_IDENTIFIERS[include.header] ?? []), ...configured];
+      if (!identifiers || identifiers.length === 0 || identifiers.some(name => used.has(name))) continue;
+
+      const contexts = [
+        ...(include.condition ? [`included under ${include.condition}`] : []),
+        ...(include.externC ? ['inside `extern "C"`'] : []),
+        ...(inHeader ? ['in a header, whose includers may rely on it'] : []),
+      ];
+      const listed = identifiers.length > LISTED_IDENTIFIERS
+        ? `${identifiers.slice(0, LISTED_IDENTIFIERS).join(', ')}, …`
+        : identifiers.join(', ');
+      const written = include.angled ? `<${include.header}>` : `"${include.header}"`;
+      const context = contexts.length > 0 ? ` (${contexts.join(', ')})` : '';
+      const position = lineIndex.positionAt(include.start);
+
+      findings.push({
+        line: position.line,
+        column: position.column,
+        importStatement: content.slice(include.start, include.end),
+        symbols: [include.header],
+        reason: `Header ${written} may be unused: none of ${listed} is used${context}`,
+        span: { start: include.start, end: include.end },
+        kind: 'unused-import',
+        importPath: include.header,
+        confidence: contexts.length > 0 ? 'low' : 'medium',
+        confidenceReason: contexts.length > 0 ? 'preprocessor-context' : 'header-identifiers-listed',
+      });
+    }
+
+    return findings;
+  }
+}
+
+/**
+ * The includes of a file, with the conditions and `extern "C"` blocks around them,
+ * and whether the file has an include guard or `#pragma once`
+ */
+export function parseIncludes(tokens: CppToken[], content: string): { includes: CppInclude[]; guarded: boolean } {
+  const directives = parseDirectives(tokens, content);
+  const guard = includeGuard(tokens, directives);
+  const byIndex = new Map(directives.map(directive => [directive.index, directive]));
+
+  const includes: CppInclude[] = [];
+  const conditions: Array<{ guard: boolean; opening: string; branch?: string }> = [];
+  // For each open brace, whether it opens an `extern "C"` block
+  const braces: boolean[] = [];
+
+  for (let i = 0; i < tokens.length; i++) {
+    const directive = byIndex.get(i);
+    if (directive) {
+      const { name, text } = directive;
+      if (name === 'if' || name === 'ifdef' || name === 'ifndef') {
+        conditions.push({ guard: directive === guard, opening: text });
+      } else if ((name === 'elif' || name === 'elifdef' || name === 'elifndef' || name === 'else') && conditions.length > 0) {
+        conditions[conditions.length - 1].branch = text;
+      } else if (name === 'endif') {
+        conditions.pop();
+      } else if (name === 'include' || name === 'include_next') {
+        const target = tokens[i + 1];
+        if (target && (target.kind === 'header' || (target.kind === 'string' && target.text.startsWith('"')))) {
+          const innermost = [...conditions].reverse().find(condition => !condition.guard);
+          includes.push({
+            header: target.text.slice(1, -1).trim(),
+            angled: target.kind === 'header',
+            start: tokens[i].start,
+            end: target.end,
+            ...(innermost ? { condition: innermost.branch ? `\`${innermost.branch}\` of \`${innermost.opening}\`` : `\`${innermost.opening}\`` } : {}),
+            externC: braces.includes(true),
+          });
+        }
+      }
+      i = directive.after - 1;
+      continue;
+    }
+
+    const token = tokens[i];
+    if (token.text === '{' && token.kind === 'op') {
+      braces.push(tokens[i - 1]?.text === '"C"' && tokens[i - 2]?.text === 'extern');
+    } else if (token.text === '}' && token.kind === 'op') {
+      braces.pop();
+    }
+  }
+
+  return { includes, guarded: guard !== undefined || directives.some(d => d.text === '#pragma once') };
+}
+
+function parseDirectives(tokens: CppToken[], content: string): Directive[] {
+  const directives: Directive[] = [];
+  for (let i = 0; i < tokens.length; i++) {
+    if (tokens[i].kind !== 'directive') continue;
+    let end = i + 1;
+    while (end < tokens.length && tokens[end].kind !== 'newline') end++;
+    const rest = end > i + 1 ? content.slice(tokens[i].end, tokens[end - 1].end).trim().replace(/\s+/g, ' ') : '';
+    const name = tokens[i].text;
+    directives.push({ name, text: rest ? `#${name} ${rest}` : `#${name}`, index: i, after: end + 1 });
+    i = end;
+  }
+  return directives;
+}
+
+/**
+ * The `#ifndef NAME` of an include guard: the file's first line of code, directly
+ * followed by `#define NAME`, and closed by the file's last line
+ */
+function includeGuard(tokens: CppToken[], directives: Directive[]): Directive | undefined {
+  const [first, second] = directives;
+  const match = first?.index === 0 ? GUARD.exec(first.text) : null;
+  const name = match && (match[1] ?? match[2] ?? match[3]);
+  if (!name || second?.index !== first.after || second.text !== `#define ${name}`) return undefined;
+
+  let depth = 0;
+  for (const directive of directives) {
+    if (directive.name === 'if' || directive.name === 'ifdef' || directive.name === 'ifndef') depth++;
+    else if (directive.name === 'endif' && --depth === 0) return directive.after >= tokens.length ? first : undefined;
+  }
+  return undefined;
+}
+
+/**
+ * Names used outside `#include` directives: every identifier that isn't a member
+ * (`x.size`, `p->size`), and the leading parts of qualified names (`std` and
+ * `std::chrono` as well as `std::chrono::seconds`). After `using namespace ns;`,
+ * each with `ns::` in front counts too.
+ */
+function usedNames(tokens: CppToken[]): Set<string> {
+  const used = new Set<string>();
+  const prefixes: string[] = [];
+  const namespaces: string[] = [];
+
+  for (let i = 0; i < tokens.length; i++) {
+    const token = tokens[i];
+    if (token.kind === 'directive' && (token.text === 'include' || token.text === 'include_next' || token.text === 'import')) {
+      while (i < tokens.length && tokens[i].kind !== 'newline') i++;
+      continue;
+    }
+    if (token.kind !== 'ident') continue;
+
+    const previous = tokens[i - 1];
+    if (previous?.text === '.' || previous?.text === '->') continue;
+    used.add(token.text);
+    // Later parts of a qualified name were counted with its first
+    if (previous?.text === '::' && tokens[i - 2]?.kind === 'ident') continue;
+
+    const parts = [token.text];
+    for (let j = i; tokens[j + 1]?.text === '::' && tokens[j + 2]?.kind === 'ident'; j += 2) {
+      parts.push(tokens[j + 2].text);
+    }
+    for (let k = 1; k <= parts.length; k++) prefixes.push(parts.slice(0, k).join('::'));
+    if (previous?.text === 'namespace' && tokens[i - 2]?.text === 'using') namespaces.push(parts.join('::'));
+  }
+
+  for (const prefix of prefixes) {
+    used.add(prefix);
+    for (const scope of namespaces) used.add(`${scope}::${prefix}`);
+  }
+  return used;
+}
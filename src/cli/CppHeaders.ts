@@ -0,0 +1,397 @@
+/**
+ * Identifiers of the C and C++ standard libraries (and a few POSIX headers) by the
+ * header that declares them, named as written in `#include`.
+ *
+ * Names with a namespace (`std::vector`) are used when written out, or written bare
+ * after `using namespace std;`; entries naming a namespace (`std::chrono`) cover
+ * everything in it. Names without one are used wherever they appear, which also covers
+ * the `std::` versions `<cstring>` and the other `<c...>` headers declare. The lists
+ * don't need to be complete: an include is only reported when none of them is used,
+ * so a missing identifier can only make an include look unused when it isn't.
+ */
+export const CPP_HEADER_IDENTIFIERS: Record<string, string[]> = {};
+
+/** Add `identifiers` for each of `headers` */
+function define(headers: string[], identifiers: string[]): void {
+  for (const header of headers) CPP_HEADER_IDENTIFIERS[header] = identifiers;
+}
+
+/** `std::` of each name */
+function std(list: string): string[] {
+  return names(list).map(name => `std::${name}`);
+}
+
+function names(list: string): string[] {
+  return list.trim().split(/\s+/);
+}
+
+const FIXED_WIDTHS = [8, 16, 32, 64].flatMap(width => [
+  `int${width}_t`, `uint${width}_t`, `int_least${width}_t`, `uint_least${width}_t`, `int_fast${width}_t`, `uint_fast${width}_t`,
+  `INT${width}_MIN`, `INT${width}_MAX`, `UINT${width}_MAX`, `INT${width}_C`, `UINT${width}_C`,
+]);
+const STDINT = [...FIXED_WIDTHS, ...names(`
+  intptr_t uintptr_t intmax_t uintmax_t INTPTR_MIN INTPTR_MAX UINTPTR_MAX INTMAX_MIN INTMAX_MAX UINTMAX_MAX
+  SIZE_MAX PTRDIFF_MIN PTRDIFF_MAX INTMAX_C UINTMAX_C
+`)];
+
+// C headers, which C++ also has as <cname>
+define(['assert.h', 'cassert'], names('assert static_assert NDEBUG'));
+define(['ctype.h', 'cctype'], names(`
+  isalnum isalpha isblank iscntrl isdigit isgraph islower isprint ispunct isspace isupper isxdigit tolower toupper
+`));
+define(['errno.h', 'cerrno'], names(`
+  errno EDOM ERANGE EILSEQ EAGAIN EWOULDBLOCK EINTR EINVAL ENOENT ENOMEM EEXIST EACCES EBADF EBUSY EPERM EPIPE EIO
+  ETIMEDOUT ENOSPC ENOTSUP ECONNREFUSED EINPROGRESS
+`));
+define(['fenv.h', 'cfenv'], names(`
+  fenv_t fexcept_t feclearexcept fetestexcept feraiseexcept fegetexceptflag fesetexceptflag fegetround fesetround
+  fegetenv fesetenv feholdexcept feupdateenv FE_TONEAREST FE_UPWARD FE_DOWNWARD FE_TOWARDZERO FE_ALL_EXCEPT
+  FE_DIVBYZERO FE_INEXACT FE_INVALID FE_OVERFLOW FE_UNDERFLOW FE_DFL_ENV
+`));
+define(['float.h', 'cfloat'], names(`
+  FLT_MAX FLT_MIN FLT_EPSILON FLT_DIG FLT_MANT_DIG FLT_TRUE_MIN DBL_MAX DBL_MIN DBL_EPSILON DBL_DIG DBL_MANT_DIG
+  DBL_TRUE_MIN LDBL_MAX LDBL_MIN LDBL_EPSILON LDBL_DIG DECIMAL_DIG FLT_RADIX FLT_EVAL_METHOD
+`));
+define(['inttypes.h', 'cinttypes'], [...STDINT, ...names(`
+  imaxabs imaxdiv imaxdiv_t strtoimax strtoumax wcstoimax wcstoumax
+  PRId8 PRId16 PRId32 PRId64 PRIi8 PRIi16 PRIi32 PRIi64 PRIu8 PRIu16 PRIu32 PRIu64 PRIx8 PRIx16 PRIx32 PRIx64
+  PRIX8 PRIX16 PRIX32 PRIX64 PRIo32 PRIo64 PRIdMAX PRIuMAX PRIxMAX PRIdPTR PRIuPTR PRIxPTR PRIXPTR
+  SCNd8 SCNd16 SCNd32 SCNd64 SCNu8 SCNu16 SCNu32 SCNu64 SCNx32 SCNx64 SCNdMAX SCNuMAX
+`)]);
+define(['iso646.h', 'ciso646'], names('and and_eq bitand bitor compl not not_eq or or_eq xor xor_eq'));
+define(['limits.h', 'climits'], names(`
+  CHAR_BIT CHAR_MIN CHAR_MAX SCHAR_MIN SCHAR_MAX UCHAR_MAX SHRT_MIN SHRT_MAX USHRT_MAX INT_MIN INT_MAX UINT_MAX
+  LONG_MIN LONG_MAX ULONG_MAX LLONG_MIN LLONG_MAX ULLONG_MAX MB_LEN_MAX PATH_MAX
+`));
+define(['locale.h', 'clocale'], names(`
+  setlocale localeconv lconv LC_ALL LC_COLLATE LC_CTYPE LC_MONETARY LC_NUMERIC LC_TIME newlocale uselocale freelocale
+`));
+define(['math.h', 'cmath'], names(`
+  abs fabs fabsf fabsl fmod fmodf remainder remquo fma fmax fmaxf fmin fminf fdim exp expf exp2 expm1 log logf log10
+  log2 log1p pow powf sqrt sqrtf cbrt hypot sin sinf cos cosf tan tanf asin acos atan atan2 atan2f sinh cosh tanh
+  asinh acosh atanh erf erfc tgamma lgamma ceil ceilf floor floorf trunc truncf round roundf lround llround nearbyint
+  rint lrint llrint frexp ldexp modf scalbn scalbln ilogb logb nextafter nexttoward copysign nan isnan isinf
+  isfinite isnormal signbit fpclassify isgreater isless isunordered HUGE_VAL HUGE_VALF INFINITY NAN FP_NAN
+  FP_INFINITE FP_ZERO FP_NORMAL FP_SUBNORMAL M_PI M_PI_2 M_PI_4 M_1_PI M_2_PI M_E M_LN2 M_LN10 M_LOG2E M_LOG10E
+  M_SQRT2 M_SQRT1_2 float_t double_t lerp
+`));
+define(['setjmp.h', 'csetjmp'], names('setjmp longjmp jmp_buf sigsetjmp siglongjmp sigjmp_buf'));
+define(['signal.h', 'csignal'], names(`
+  signal raise sig_atomic_t SIG_DFL SIG_IGN SIG_ERR SIGINT SIGTERM SIGSEGV SIGABRT SIGFPE SIGILL SIGKILL SIGHUP
+  SIGPIPE SIGQUIT SIGUSR1 SIGUSR2 SIGALRM SIGCHLD SIGCONT SIGSTOP SIGWINCH sigaction sigset_t sigemptyset
+  sigfillset sigaddset sigdelset sigismember sigprocmask siginfo_t kill SA_RESTART SA_SIGINFO
+`));
+define(['stdalign.h', 'cstdalign'], names('alignas alignof'));
+define(['stdarg.h', 'cstdarg'], names('va_list va_start va_end va_arg va_copy'));
+define(['stdatomic.h'], names(`
+  atomic_bool atomic_char atomic_int atomic_uint atomic_long atomic_ulong atomic_llong atomic_ullong atomic_size_t
+  atomic_intptr_t atomic_uintptr_t atomic_flag atomic_init atomic_load atomic_store atomic_exchange
+  atomic_compare_exchange_strong atomic_compare_exchange_weak atomic_fetch_add atomic_fetch_sub atomic_fetch_or
+  atomic_fetch_and atomic_fetch_xor atomic_load_explicit atomic_store_explicit atomic_fetch_add_explicit
+  atomic_flag_test_and_set atomic_flag_clear atomic_thread_fence atomic_signal_fence memory_order
+  memory_order_relaxed memory_order_consume memory_order_acquire memory_order_release memory_order_acq_rel
+  memory_order_seq_cst ATOMIC_FLAG_INIT ATOMIC_VAR_INIT
+`));
+define(['stdbool.h', 'cstdbool'], names('bool true false __bool_true_false_are_defined'));
+define(['stddef.h'], names('size_t ptrdiff_t NULL offsetof max_align_t wchar_t nullptr_t'));
+define(['cstddef'], [...names('size_t ptrdiff_t NULL offsetof max_align_t'), ...std('byte nullptr_t to_integer')]);
+define(['stdint.h', 'cstdint'], STDINT);
+define(['stdio.h', 'cstdio'], names(`
+  printf fprintf sprintf snprintf vprintf vfprintf vsprintf vsnprintf dprintf scanf fscanf sscanf vscanf vfscanf
+  vsscanf puts fputs putchar fputc putc getchar fgetc getc fgets ungetc fopen freopen fclose fflush fread fwrite
+  fseek fseeko ftell ftello rewind fgetpos fsetpos feof ferror clearerr perror remove rename tmpfile tmpnam setbuf
+  setvbuf fileno fdopen popen pclose getline getdelim FILE fpos_t EOF BUFSIZ FILENAME_MAX FOPEN_MAX L_tmpnam
+  TMP_MAX SEEK_SET SEEK_CUR SEEK_END _IOFBF _IOLBF _IONBF stdin stdout stderr size_t NULL
+`));
+define(['stdlib.h', 'cstdlib'], names(`
+  malloc calloc realloc free aligned_alloc posix_memalign abort exit atexit quick_exit at_quick_exit _Exit getenv
+  setenv unsetenv putenv system atoi atol atoll atof strtol strtoll strtoul strtoull strtod strtof strtold rand
+  srand rand_r RAND_MAX qsort bsearch abs labs llabs div ldiv lldiv div_t ldiv_t lldiv_t EXIT_SUCCESS EXIT_FAILURE
+  mblen mbtowc wctomb mbstowcs wcstombs MB_CUR_MAX realpath mkstemp mkdtemp size_t NULL
+`));
+define(['stdnoreturn.h'], names('noreturn'));
+define(['string.h', 'cstring'], names(`
+  memcpy memmove memset memcmp memchr memccpy strcpy strncpy strcat strncat strcmp strncmp strcoll strxfrm strlen
+  strnlen strchr strrchr strstr strspn strcspn strpbrk strtok strtok_r strerror strerror_r strdup strndup strsignal
+  strlcpy strlcat size_t NULL
+`));
+define(['threads.h'], names(`
+  thrd_t thrd_create thrd_join thrd_detach thrd_sleep thrd_yield thrd_exit thrd_current thrd_equal thrd_success
+  thrd_error thrd_busy thrd_nomem thrd_timedout thrd_start_t mtx_t mtx_init mtx_lock mtx_trylock mtx_timedlock
+  mtx_unlock mtx_destroy mtx_plain mtx_recursive mtx_timed cnd_t cnd_init cnd_wait cnd_timedwait cnd_signal
+  cnd_broadcast cnd_destroy tss_t tss_create tss_get tss_set tss_delete once_flag call_once ONCE_FLAG_INIT
+  thread_local
+`));
+define(['time.h', 'ctime'], names(`
+  time clock difftime mktime strftime gmtime gmtime_r localtime localtime_r asctime ctime timegm time_t clock_t tm
+  timespec timespec_get clock_gettime clock_settime clock_getres nanosleep CLOCKS_PER_SEC CLOCK_REALTIME
+  CLOCK_MONOTONIC CLOCK_PROCESS_CPUTIME_ID TIME_UTC tzset size_t NULL
+`));
+define(['uchar.h', 'cuchar'], names('char16_t char32_t char8_t mbrtoc16 c16rtomb mbrtoc32 c32rtomb mbrtoc8 c8rtomb mbstate_t'));
+define(['wchar.h', 'cwchar'], names(`
+  wchar_t wint_t mbstate_t WEOF WCHAR_MIN WCHAR_MAX wcslen wcscpy wcsncpy wcscat wcsncat wcscmp wcsncmp wcscoll
+  wcschr wcsrchr wcsstr wcstok wcstol wcstoll wcstoul wcstoull wcstod wcstof wcsftime wprintf fwprintf swprintf
+  vswprintf wscanf fwscanf swscanf fgetwc fgetws fputwc fputws getwc putwc getwchar putwchar mbrtowc wcrtomb
+  mbrlen mbsinit mbsrtowcs wcsrtombs btowc wctob wmemcpy wmemmove wmemset wmemcmp wmemchr fwide size_t NULL
+`));
+define(['wctype.h', 'cwctype'], names(`
+  iswalnum iswalpha iswblank iswcntrl iswdigit iswgraph iswlower iswprint iswpunct iswspace iswupper iswxdigit
+  towlower towupper wctype iswctype wctrans towctrans wctype_t wctrans_t wint_t WEOF
+`));
+define(['complex.h'], names('complex _Complex_I I CMPLX creal cimag cabs carg conj cproj cexp clog cpow csqrt csin ccos ctan'));
+
+// POSIX
+define(['unistd.h'], names(`
+  read write close lseek pread pwrite fork vfork execv execve execvp execl execlp execle pipe dup dup2 getpid getppid
+  getuid geteuid getgid getegid setuid setgid setsid getpgrp setpgid sleep usleep alarm pause access unlink rmdir
+  chdir fchdir getcwd isatty ttyname sysconf pathconf _exit fsync fdatasync ftruncate truncate link symlink
+  readlink chown fchown lchown gethostname getopt optarg optind opterr optopt nice sync ssize_t pid_t uid_t gid_t
+  off_t useconds_t STDIN_FILENO STDOUT_FILENO STDERR_FILENO R_OK W_OK X_OK F_OK _SC_PAGESIZE _SC_NPROCESSORS_ONLN
+`));
+define(['fcntl.h'], names(`
+  open openat creat fcntl posix_fadvise posix_fallocate O_RDONLY O_WRONLY O_RDWR O_CREAT O_EXCL O_TRUNC O_APPEND
+  O_NONBLOCK O_CLOEXEC O_DIRECTORY O_NOFOLLOW O_SYNC F_GETFL F_SETFL F_GETFD F_SETFD F_DUPFD F_DUPFD_CLOEXEC
+  F_GETLK F_SETLK F_SETLKW FD_CLOEXEC AT_FDCWD mode_t
+`));
+define(['pthread.h'], names(`
+  pthread_t pthread_create pthread_join pthread_detach pthread_self pthread_equal pthread_exit pthread_cancel
+  pthread_kill pthread_sigmask pthread_setname_np pthread_getname_np pthread_attr_t pthread_attr_init
+  pthread_attr_destroy pthread_attr_setdetachstate pthread_attr_setstacksize pthread_mutex_t pthread_mutex_init
+  pthread_mutex_lock pthread_mutex_trylock pthread_mutex_unlock pthread_mutex_destroy pthread_mutexattr_t
+  pthread_mutexattr_init pthread_mutexattr_settype PTHREAD_MUTEX_INITIALIZER PTHREAD_MUTEX_RECURSIVE
+  pthread_cond_t pthread_cond_init pthread_cond_wait pthread_cond_timedwait pthread_cond_signal
+  pthread_cond_broadcast pthread_cond_destroy PTHREAD_COND_INITIALIZER pthread_rwlock_t pthread_rwlock_init
+  pthread_rwlock_rdlock pthread_rwlock_wrlock pthread_rwlock_unlock pthread_rwlock_destroy pthread_once
+  pthread_once_t PTHREAD_ONCE_INIT pthread_key_t pthread_key_create pthread_key_delete pthread_setspecific
+  pthread_getspecific pthread_spinlock_t pthread_spin_lock pthread_spin_unlock pthread_barrier_t
+  PTHREAD_CREATE_DETACHED PTHREAD_CREATE_JOINABLE
+`));
+
+// C++ containers and utilities
+define(['vector'], std('vector'));
+define(['array'], std('array to_array'));
+define(['deque'], std('deque'));
+define(['list'], std('list'));
+define(['forward_list'], std('forward_list'));
+define(['map'], std('map multimap'));
+define(['set'], std('set multiset'));
+define(['unordered_map'], std('unordered_map unordered_multimap'));
+define(['unordered_set'], std('unordered_set unordered_multiset'));
+define(['queue'], std('queue priority_queue'));
+define(['stack'], std('stack'));
+define(['span'], std('span dynamic_extent as_bytes as_writable_bytes'));
+define(['bitset'], std('bitset'));
+define(['valarray'], std('valarray slice gslice slice_array gslice_array mask_array indirect_array'));
+define(['string'], std(`
+  string wstring u8string u16string u32string basic_string char_traits to_string to_wstring stoi stol stoll stoul
+  stoull stof stod stold getline literals::string_literals string_literals
+`));
+define(['string_view'], std('string_view wstring_view u8string_view u16string_view u32string_view basic_string_view literals::string_view_literals string_view_literals'));
+define(['tuple'], std('tuple make_tuple tie get tuple_size tuple_size_v tuple_element tuple_element_t apply forward_as_tuple tuple_cat ignore make_from_tuple'));
+define(['utility'], std(`
+  pair make_pair move forward swap exchange declval integer_sequence index_sequence make_index_sequence
+  make_integer_sequence index_sequence_for as_const in_place in_place_t in_place_type in_place_index
+  piecewise_construct piecewise_construct_t cmp_equal cmp_not_equal cmp_less cmp_greater cmp_less_equal
+  cmp_greater_equal in_range to_underlying unreachable forward_like get rel_ops
+`));
+define(['optional'], std('optional nullopt nullopt_t make_optional bad_optional_access'));
+define(['variant'], std('variant visit get get_if holds_alternative monostate bad_variant_access variant_size variant_size_v variant_alternative variant_alternative_t variant_npos'));
+define(['any'], std('any any_cast make_any bad_any_cast'));
+define(['expected'], std('expected unexpected unexpect unexpect_t bad_expected_access'));
+define(['initializer_list'], std('initializer_list'));
+define(['memory'], std(`
+  unique_ptr shared_ptr weak_ptr make_unique make_unique_for_overwrite make_shared make_shared_for_overwrite
+  allocate_shared enable_shared_from_this allocator allocator_traits addressof static_pointer_cast
+  dynamic_pointer_cast const_pointer_cast reinterpret_pointer_cast default_delete pointer_traits
+  uninitialized_copy uninitialized_copy_n uninitialized_fill uninitialized_fill_n uninitialized_move
+  uninitialized_default_construct destroy destroy_at destroy_n construct_at align assume_aligned to_address
+  owner_less bad_weak_ptr get_deleter atomic out_ptr inout_ptr
+`));
+define(['memory_resource'], std('pmr'));
+define(['scoped_allocator'], std('scoped_allocator_adaptor'));
+define(['functional'], std(`
+  function move_only_function copyable_function function_ref bind bind_front bind_back ref cref reference_wrapper
+  invoke invoke_r hash less greater less_equal greater_equal equal_to not_equal_to plus minus multiplies divides
+  modulus negate logical_and logical_or logical_not bit_and bit_or bit_xor bit_not not_fn mem_fn identity
+  placeholders bad_function_call is_bind_expression is_placeholder default_searcher boyer_moore_searcher
+  boyer_moore_horspool_searcher ranges
+`));
+define(['typeinfo'], ['typeid', ...std('type_info bad_cast bad_typeid')]);
+define(['typeindex'], std('type_index'));
+define(['limits'], std('numeric_limits float_round_style float_denorm_style round_to_nearest'));
+define(['compare'], std(`
+  strong_ordering weak_ordering partial_ordering compare_three_way compare_three_way_result
+  compare_three_way_result_t three_way_comparable three_way_comparable_with common_comparison_category is_eq is_neq
+  is_lt is_gt is_lteq is_gteq strong_order weak_order partial_order compare_strong_order_fallback
+`));
+define(['source_location'], std('source_location'));
+define(['bit'], std(`
+  bit_cast popcount countl_zero countr_zero countl_one countr_one has_single_bit bit_ceil bit_floor bit_width
+  rotl rotr endian byteswap
+`));
+define(['numbers'], std('numbers'));
+define(['ratio'], std(`
+  ratio ratio_add ratio_subtract ratio_multiply ratio_divide ratio_equal ratio_not_equal ratio_less
+  ratio_less_equal ratio_greater ratio_greater_equal atto femto pico nano micro milli centi deci deca hecto kilo
+  mega giga tera peta exa
+`));
+define(['chrono'], std('chrono literals::chrono_literals chrono_literals'));
+define(['charconv'], std('to_chars from_chars to_chars_result from_chars_result chars_format'));
+define(['format'], std(`
+  format format_to format_to_n formatted_size vformat vformat_to formatter format_context wformat_context
+  basic_format_context format_args wformat_args make_format_args make_wformat_args format_error format_string
+  wformat_string basic_format_string format_parse_context runtime_format range_formatter
+`));
+define(['print'], std('print println vprint_unicode vprint_nonunicode'));
+define(['concepts'], std(`
+  same_as derived_from convertible_to common_reference_with common_with integral signed_integral unsigned_integral
+  floating_point assignable_from swappable swappable_with destructible constructible_from default_initializable
+  move_constructible copy_constructible equality_comparable equality_comparable_with totally_ordered
+  totally_ordered_with movable copyable semiregular regular invocable regular_invocable predicate relation
+  equivalence_relation strict_weak_order
+`));
+define(['coroutine'], ['co_await', 'co_yield', 'co_return', ...std(`
+  coroutine_handle coroutine_traits suspend_always suspend_never noop_coroutine noop_coroutine_handle
+  noop_coroutine_promise
+`)]);
+
+const UNARY_TRAITS = names(`
+  is_void is_null_pointer is_integral is_floating_point is_array is_enum is_union is_class is_function is_pointer
+  is_lvalue_reference is_rvalue_reference is_member_pointer is_fundamental is_arithmetic is_scalar is_object
+  is_compound is_reference is_const is_volatile is_trivial is_trivially_copyable is_standard_layout is_empty
+  is_polymorphic is_abstract is_final is_aggregate is_signed is_unsigned is_bounded_array is_unbounded_array
+  is_scoped_enum is_constructible is_trivially_constructible is_nothrow_constructible is_default_constructible
+  is_nothrow_default_constructible is_copy_constructible is_nothrow_copy_constructible is_move_constructible
+  is_nothrow_move_constructible is_assignable is_copy_assignable is_move_assignable is_nothrow_move_assignable
+  is_destructible is_trivially_destructible is_nothrow_destructible has_virtual_destructor
+  has_unique_object_representations is_swappable is_nothrow_swappable is_same is_base_of is_convertible
+  is_nothrow_convertible is_invocable is_invocable_r is_nothrow_invocable is_layout_compatible alignment_of rank
+  extent conjunction disjunction negation
+`);
+const TYPE_TRANSFORMATIONS = names(`
+  remove_cv remove_const remove_volatile add_cv add_const add_volatile remove_reference add_lvalue_reference
+  add_rvalue_reference remove_pointer add_pointer make_signed make_unsigned remove_extent remove_all_extents
+  remove_cvref decay enable_if conditional common_type common_reference underlying_type invoke_result
+  type_identity aligned_storage aligned_union unwrap_reference unwrap_ref_decay
+`);
+define(['type_traits'], [
+  ...UNARY_TRAITS.flatMap(trait => std(`${trait} ${trait}_v`)),
+  ...TYPE_TRANSFORMATIONS.flatMap(trait => std(`${trait} ${trait}_t`)),
+  ...std('integral_constant bool_constant true_type false_type void_t is_constant_evaluated is_pointer_interconvertible_base_of declval'),
+]);
+
+// C++ algorithms and iterators
+define(['algorithm'], std(`
+  sort stable_sort partial_sort partial_sort_copy nth_element find find_if find_if_not find_end find_first_of
+  adjacent_find count count_if copy copy_if copy_n copy_backward move move_backward fill fill_n transform generate
+  generate_n remove remove_if remove_copy remove_copy_if replace replace_if replace_copy replace_copy_if reverse
+  reverse_copy rotate rotate_copy shuffle sample unique unique_copy min max minmax min_element max_element
+  minmax_element clamp all_of any_of none_of for_each for_each_n equal mismatch search search_n lower_bound
+  upper_bound equal_range binary_search merge inplace_merge includes set_union set_intersection set_difference
+  set_symmetric_difference make_heap push_heap pop_heap sort_heap is_heap is_heap_until is_sorted
+  is_sorted_until partition stable_partition partition_copy partition_point is_partitioned next_permutation
+  prev_permutation is_permutation lexicographical_compare lexicographical_compare_three_way swap_ranges iter_swap
+  swap shift_left shift_right ranges
+`));
+define(['numeric'], std(`
+  accumulate reduce inner_product transform_reduce partial_sum inclusive_scan exclusive_scan
+  transform_inclusive_scan transform_exclusive_scan adjacent_difference iota gcd lcm midpoint saturate_cast
+  add_sat sub_sat mul_sat div_sat
+`));
+define(['iterator'], std(`
+  begin end cbegin cend rbegin rend crbegin crend size ssize empty data next prev advance distance back_inserter
+  front_inserter inserter iterator_traits reverse_iterator move_iterator make_move_iterator make_reverse_iterator
+  back_insert_iterator front_insert_iterator insert_iterator istream_iterator ostream_iterator
+  istreambuf_iterator ostreambuf_iterator input_iterator_tag output_iterator_tag forward_iterator_tag
+  bidirectional_iterator_tag random_access_iterator_tag contiguous_iterator_tag iterator default_sentinel
+  default_sentinel_t unreachable_sentinel counted_iterator common_iterator input_iterator output_iterator
+  forward_iterator bidirectional_iterator random_access_iterator contiguous_iterator sentinel_for iter_value_t
+  iter_reference_t iter_difference_t indirectly_readable incrementable weakly_incrementable
+`));
+define(['ranges'], std('ranges views'));
+define(['execution'], std('execution'));
+define(['random'], std(`
+  mt19937 mt19937_64 random_device default_random_engine minstd_rand minstd_rand0 ranlux24 ranlux48 ranlux24_base
+  ranlux48_base knuth_b linear_congruential_engine mersenne_twister_engine subtract_with_carry_engine
+  discard_block_engine independent_bits_engine shuffle_order_engine uniform_int_distribution
+  uniform_real_distribution bernoulli_distribution binomial_distribution geometric_distribution
+  negative_binomial_distribution poisson_distribution exponential_distribution gamma_distribution
+  weibull_distribution extreme_value_distribution normal_distribution lognormal_distribution
+  chi_squared_distribution cauchy_distribution fisher_f_distribution student_t_distribution
+  discrete_distribution piecewise_constant_distribution piecewise_linear_distribution seed_seq
+  generate_canonical uniform_random_bit_generator
+`));
+define(['regex'], std(`
+  regex wregex basic_regex smatch cmatch wsmatch wcmatch match_results sub_match ssub_match csub_match regex_match
+  regex_search regex_replace regex_iterator sregex_iterator cregex_iterator regex_token_iterator
+  sregex_token_iterator cregex_token_iterator regex_error regex_constants regex_traits
+`));
+define(['complex'], std('complex literals::complex_literals complex_literals real imag arg norm conj polar proj'));
+define(['filesystem'], std('filesystem'));
+define(['locale'], std(`
+  locale use_facet has_facet ctype ctype_base codecvt codecvt_base codecvt_utf8 codecvt_utf16 numpunct
+  moneypunct money_get money_put time_get time_put num_get num_put collate messages wstring_convert
+  wbuffer_convert isspace isblank isalpha isdigit isalnum isupper islower ispunct isprint isxdigit toupper tolower
+`));
+define(['codecvt'], std('codecvt_utf8 codecvt_utf16 codecvt_utf8_utf16 codecvt_mode'));
+
+// C++ input and output
+const IOS_FLAGS = std(`
+  boolalpha noboolalpha showbase noshowbase showpoint noshowpoint showpos noshowpos skipws noskipws uppercase
+  nouppercase unitbuf nounitbuf internal left right dec hex oct fixed scientific hexfloat defaultfloat
+`);
+define(['ios'], [...IOS_FLAGS, ...std('ios wios ios_base basic_ios streamsize streamoff fpos streampos io_errc iostream_category')]);
+define(['iosfwd'], std(`
+  istream ostream iostream wistream wostream wiostream ifstream ofstream fstream stringstream istringstream
+  ostringstream streambuf filebuf stringbuf basic_istream basic_ostream basic_iostream basic_ios basic_streambuf
+  basic_ifstream basic_ofstream basic_fstream basic_stringstream char_traits allocator fpos streampos ios
+`));
+define(['streambuf'], std('streambuf wstreambuf basic_streambuf'));
+define(['istream'], [...IOS_FLAGS, ...std('istream wistream basic_istream iostream wiostream basic_iostream ws ios ios_base streamsize')]);
+define(['ostream'], [...IOS_FLAGS, ...std('ostream wostream basic_ostream endl ends flush emit_on_flush print println ios ios_base streamsize')]);
+define(['iostream'], [...IOS_FLAGS, ...std(`
+  cout cin cerr clog wcout wcin wcerr wclog istream ostream iostream wistream wostream basic_istream
+  basic_ostream streambuf endl ends flush ws ios ios_base streamsize
+`)]);
+define(['fstream'], std('ifstream ofstream fstream wifstream wofstream wfstream basic_ifstream basic_ofstream basic_fstream filebuf wfilebuf basic_filebuf'));
+define(['sstream'], std(`
+  stringstream istringstream ostringstream wstringstream wistringstream wostringstream stringbuf wstringbuf
+  basic_stringstream basic_istringstream basic_ostringstream basic_stringbuf
+`));
+define(['spanstream'], std('spanstream ispanstream ospanstream spanbuf basic_spanstream'));
+define(['syncstream'], std('osyncstream wosyncstream basic_osyncstream syncbuf basic_syncbuf'));
+define(['iomanip'], std('setw setprecision setfill setbase setiosflags resetiosflags put_time get_time put_money get_money quoted'));
+
+// C++ errors and concurrency
+define(['exception'], std(`
+  exception exception_ptr current_exception rethrow_exception make_exception_ptr terminate set_terminate
+  get_terminate terminate_handler uncaught_exceptions uncaught_exception throw_with_nested rethrow_if_nested
+  nested_exception bad_exception
+`));
+define(['stdexcept'], std('logic_error domain_error invalid_argument length_error out_of_range runtime_error range_error overflow_error underflow_error'));
+define(['system_error'], std(`
+  error_code error_condition error_category system_error errc generic_category system_category make_error_code
+  make_error_condition is_error_code_enum is_error_code_enum_v is_error_condition_enum is_error_condition_enum_v
+`));
+define(['thread'], std('thread jthread this_thread stop_token stop_source stop_callback'));
+define(['stop_token'], std('stop_token stop_source stop_callback nostopstate nostopstate_t'));
+define(['mutex'], std(`
+  mutex recursive_mutex timed_mutex recursive_timed_mutex lock_guard unique_lock scoped_lock lock try_lock call_once
+  once_flag defer_lock try_to_lock adopt_lock defer_lock_t try_to_lock_t adopt_lock_t
+`));
+define(['shared_mutex'], std('shared_mutex shared_timed_mutex shared_lock'));
+define(['condition_variable'], std('condition_variable condition_variable_any cv_status notify_all_at_thread_exit'));
+define(['atomic'], [...std(`
+  atomic atomic_ref atomic_flag memory_order memory_order_relaxed memory_order_consume memory_order_acquire
+  memory_order_release memory_order_acq_rel memory_order_seq_cst atomic_thread_fence atomic_signal_fence
+  atomic_bool atomic_char atomic_int atomic_uint atomic_long atomic_ulong atomic_llong atomic_ullong atomic_size_t
+  atomic_ptrdiff_t atomic_intptr_t atomic_uintptr_t atomic_int8_t atomic_uint8_t atomic_int16_t atomic_uint16_t
+  atomic_int32_t atomic_uint32_t atomic_int64_t atomic_uint64_t atomic_load atomic_store atomic_exchange
+  atomic_compare_exchange_weak atomic_compare_exchange_strong atomic_fetch_add atomic_fetch_sub atomic_fetch_and
+  atomic_fetch_or atomic_fetch_xor atomic_flag_test_and_set atomic_flag_clear atomic_is_lock_free
+  atomic_signed_lock_free atomic_unsigned_lock_free kill_dependency
+`), 'ATOMIC_FLAG_INIT', 'ATOMIC_VAR_INIT']);
+define(['future'], std('future shared_future promise packaged_task async launch future_status future_error future_errc future_category'));
+define(['semaphore'], std('counting_semaphore binary_semaphore'));
+define(['latch'], std('latch'));
+define(['barrier'], std('barrier'));
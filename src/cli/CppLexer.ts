@@ -0,0 +1,152 @@
+export type CppTokenKind = 'ident' | 'string' | 'char' | 'number' | 'op' | 'header' | 'directive' | 'newline';
+
+export interface CppToken {
+  kind: CppTokenKind;
+  /** For `directive` tokens, the directive's name: `include`, `ifdef`, ... */
+  text: string;
+  /** Offset of the first character; of the `#` for directives */
+  start: number;
+  /** Offset one past the last character */
+  end: number;
+}
+
+const IDENT_START = /[\p{L}_$]/u;
+const IDENT_PART = /[\p{L}\p{N}_$]/u;
+const RAW_STRING_PREFIX = /^(?:u8|[uUL])?R$/;
+const OPERATORS = ['<<=', '>>=', '<=>', '...', '->*', '::', '->', '++', '--', '<<', '>>', '<=', '>=', '==', '!=',
+  '&&', '||', '+=', '-=', '*=', '/=', '%=', '&=', '|=', '^=', '.*', '##'];
+
+/**
+ * Minimal C/C++ tokenizer: enough to find `#include` directives, the conditions
+ * around them and identifier uses.
+ *
+ * Comments are skipped, and so are `\` line continuations. Newlines only matter to
+ * preprocessor directives: a `directive` token (`#` and the name) starts each, and a
+ * `newline` token ends it. `<header>` names of `#include` and `#import` are one
+ * `header` token; string literals keep their quotes, raw strings included.
+ */
+export function tokenizeCpp(source: string): CppToken[] {
+  const tokens: CppToken[] = [];
+  let inDirective = false;
+  let headerAllowed = false;
+  let lineStart = true;
+  let i = 0;
+
+  const push = (kind: CppTokenKind, start: number, end: number, text = source.slice(start, end)) => {
+    tokens.push({ kind, text, start, end });
+    lineStart = false;
+  };
+
+  while (i < source.length) {
+    const char = source[i];
+
+    if (char === '\n') {
+      if (inDirective) push('newline', i, i + 1);
+      inDirective = false;
+      headerAllowed = false;
+      lineStart = true;
+      i++;
+      continue;
+    }
+
+    if (char === '\\' && (source[i + 1] === '\n' || (source[i + 1] === '\r' && source[i + 2] === '\n'))) {
+      i += source[i + 1] === '\r' ? 3 : 2;
+      continue;
+    }
+
+    if (char === ' ' || char === '\t' || char === '\r' || char === '\f' || char === '\v' || (char === '\uFEFF' && i === 0)) {
+      i++;
+      continue;
+    }
+
+    if (char === '/' && source[i + 1] === '/') {
+      // A continued line comment goes on on the next line
+      while (i < source.length && source[i] !== '\n') {
+        i += source[i] === '\\' && source[i + 1] === '\n' ? 2 : 1;
+      }
+      continue;
+    }
+
+    if (char === '/' && source[i + 1] === '*') {
+      const close = source.indexOf('*/', i + 2);
+      i = close === -1 ? source.length : close + 2;
+      continue;
+    }
+
+    if (char === '#' && lineStart) {
+      let end = i + 1;
+      while (source[end] === ' ' || source[end] === '\t') end++;
+      let nameEnd = end;
+      while (nameEnd < source.length && IDENT_PART.test(source[nameEnd])) nameEnd++;
+      const name = source.slice(end, nameEnd);
+      push('directive', i, nameEnd, name);
+      inDirective = true;
+      headerAllowed = name === 'include' || name === 'include_next' || name === 'import';
+      i = nameEnd;
+      continue;
+    }
+
+    if (char === '<' && headerAllowed) {
+      const close = source.indexOf('>', i + 1);
+      const lineEnd = source.indexOf('\n', i);
+      if (close !== -1 && (lineEnd === -1 || close < lineEnd)) {
+        push('header', i, close + 1);
+        headerAllowed = false;
+        i = close + 1;
+        continue;
+      }
+    }
+    headerAllowed = false;
+
+    if (char === '"' || char === '\'') {
+      let end = i + 1;
+      while (end < source.length && source[end] !== char && source[end] !== '\n') {
+        end += source[end] === '\\' ? 2 : 1;
+      }
+      end = Math.min(end + 1, source.length);
+      push(char === '"' ? 'string' : 'char', i, end);
+      i = end;
+      continue;
+    }
+
+    if (IDENT_START.test(char)) {
+      let end = i + 1;
+      while (end < source.length && IDENT_PART.test(source[end])) end++;
+
+      // R"delimiter( ... )delimiter", newlines included
+      if (source[end] === '"' && RAW_STRING_PREFIX.test(source.slice(i, end))) {
+        const open = source.indexOf('(', end);
+        const delimiter = open === -1 ? '' : source.slice(end + 1, open);
+        const close = open === -1 ? -1 : source.indexOf(`)${delimiter}"`, open);
+        const stringEnd = close === -1 ? source.length : close + delimiter.length + 2;
+        push('string', i, stringEnd);
+        i = stringEnd;
+        continue;
+      }
+
+      push('ident', i, end);
+      i = end;
+      continue;
+    }
+
+    // Digit separators (`1'000'000`) are part of the number
+    if (/\d/.test(char) || (char === '.' && /\d/.test(source[i + 1] ?? ''))) {
+      let end = i + 1;
+      while (end < source.length && (/[\w.]/.test(source[end]) || (source[end] === '\'' && /\w/.test(source[end + 1] ?? '')) ||
+        (/[eEpP]/.test(source[end - 1]) && /[+-]/.test(source[end])))) {
+        end++;
+      }
+      push('number', i, end);
+      i = end;
+      continue;
+    }
+
+    const operator = OPERATORS.find(op => source.startsWith(op, i));
+    const length = operator ? operator.length : 1;
+    push('op', i, i + length);
+    i += length;
+  }
+
+  if (inDirective) push('newline', source.length, source.length, '');
+  return tokens;
+}
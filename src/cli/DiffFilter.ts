@@ -0,0 +1,204 @@
+import { execFileSync } from 'child_process';
+import * as path from 'path';
+import { AnalysisResult, FindingKind, UnusedImport } from './CLIAnalyzer';
+import { toPosixPath } from './Paths';
+
+/**
+ * What a finding must touch to survive `--diff-base`/`--diff`:
+ * - `imports`: a changed line of its import statement
+ * - `any`: that, or a changed line (added or removed) that mentions the imported name,
+ *   such as the deleted last use of the import
+ */
+export const DIFF_SCOPES = ['imports', 'any'] as const;
+export type DiffScope = typeof DIFF_SCOPES[number];
+
+/**
+ * Changes to one file in a unified diff
+ */
+export interface FileChanges {
+  /** Path before the change, for renamed files */
+  oldPath?: string;
+  /** 1-based lines of the new file that were added or changed */
+  addedLines: Set<number>;
+  /** Text of the added and removed lines, without their `+`/`-` */
+  changedText: string[];
+}
+
+// Findings about names used anywhere in the crate or package, not just their own file
+const CROSS_FILE_KINDS = new Set<FindingKind>(['unused-reexport', 'unused-dependency']);
+
+const HUNK_HEADER = /^@@ -(\d+)(?:,(\d+))? \+(\d+)(?:,(\d+))? @@/;
+
+/**
+ * Parse a unified diff (`git diff` output or plain `diff -u`) into the changes of each
+ * file, by the file's path after the change. Renames are keyed by their new path;
+ * deleted files are left out.
+ */
+export function parseUnifiedDiff(diff: string): Map<string, FileChanges> {
+  const files = new Map<string, FileChanges>();
+  let current: FileChanges | undefined;
+  let currentPath: string | undefined;
+  let oldPath: string | undefined;
+  // Lines of the current hunk still to come, per side
+  let oldRemaining = 0;
+  let newRemaining = 0;
+  let newLine = 0;
+
+  const start = (newPath: string | undefined) => {
+    if (newPath === undefined) {
+      // Deleted file: nothing of it is left to report on
+      if (currentPath !== undefined && current?.addedLines.size === 0) files.delete(currentPath);
+      current = currentPath = undefined;
+      return;
+    }
+    currentPath = newPath;
+    current = files.get(newPath) ?? { addedLines: new Set(), changedText: [] };
+    if (oldPath !== undefined && oldPath !== newPath) current.oldPath = oldPath;
+    files.set(newPath, current);
+  };
+
+  for (const line of diff.split(/\r?\n/)) {
+    if (oldRemaining > 0 || newRemaining > 0) {
+      const marker = line[0];
+      if (marker === '+') {
+        current?.addedLines.add(newLine);
+        current?.changedText.push(line.slice(1));
+        newLine++;
+        newRemaining--;
+      } else if (marker === '-') {
+        current?.changedText.push(line.slice(1));
+        oldRemaining--;
+      } else if (marker === ' ' || line === '') {
+        newLine++;
+        oldRemaining--;
+        newRemaining--;
+      }
+      // `\ No newline at end of file` belongs to neither side
+      continue;
+    }
+
+    const git = /^diff --git (\S+|"[^"]*") (\S+|"[^"]*")$/.exec(line);
+    if (git) {
+      oldPath = diffPath(git[1]);
+      start(diffPath(git[2]));
+      continue;
+    }
+
+    if (line.startsWith('rename from ')) {
+      oldPath = unquote(line.slice('rename from '.length));
+    } else if (line.startsWith('rename to ')) {
+      start(unquote(line.slice('rename to '.length)));
+    } else if (line.startsWith('--- ')) {
+      oldPath = diffPath(line.slice(4));
+    } else if (line.startsWith('+++ ')) {
+      start(diffPath(line.slice(4)));
+    } else {
+      const hunk = HUNK_HEADER.exec(line);
+      if (hunk) {
+        oldRemaining = hunk[2] === undefined ? 1 : Number(hunk[2]);
+        newRemaining = hunk[4] === undefined ? 1 : Number(hunk[4]);
+        newLine = Number(hunk[3]);
+      }
+    }
+  }
+
+  return files;
+}
+
+/**
+ * A path from a `diff --git`, `---` or `+++` line without its `a/`/`b/` prefix and any
+ * trailing timestamp; undefined for `/dev/null`
+ */
+function diffPath(text: string): string | undefined {
+  const name = unquote(text.split('\t')[0]);
+  if (name === '/dev/null') return undefined;
+  return /^[ab]\//.test(name) ? name.slice(2) : name;
+}
+
+function unquote(text: string): string {
+  if (!text.startsWith('"') || !text.endsWith('"')) return text;
+  return text.slice(1, -1).replace(/\\(["\\])/g, '$1').replace(/\\t/g, '\t').replace(/\\n/g, '\n');
+}
+
+/**
+ * Keep only the findings the diff touches, as defined by `scope`; files the diff doesn't
+ * mention lose all of theirs. Diff paths are relative to `root`. Returns the filtered
+ * results and the number of findings left out.
+ */
+export function filterToDiff(
+  results: AnalysisResult[],
+  changes: Map<string, FileChanges>,
+  root: string,
+  scope: DiffScope
+): { results: AnalysisResult[]; hidden: number } {
+  const allChangedText = [...changes.values()].flatMap(file => file.changedText);
+  let hidden = 0;
+
+  const filtered = results.map(result => {
+    const relative = toPosixPath(path.relative(root, path.resolve(result.filePath)));
+    const file = changes.get(relative);
+
+    const unusedImports = result.unusedImports.filter(finding => {
+      if (!file) return false;
+      if (touchesImport(finding, file)) return true;
+      if (scope !== 'any') return false;
+      const text = CROSS_FILE_KINDS.has(finding.kind ?? 'unused-import') ? allChangedText : file.changedText;
+      return mentionsSymbol(finding, text);
+    });
+
+    hidden += result.unusedImports.length - unusedImports.length;
+    return { ...result, unusedImports };
+  });
+
+  return { results: filtered, hidden };
+}
+
+/** Whether an added or changed line falls within the finding's import statement */
+function touchesImport(finding: UnusedImport, file: FileChanges): boolean {
+  const lineCount = finding.importStatement.split('\n').length;
+  for (let line = finding.line; line < finding.line + lineCount; line++) {
+    if (file.addedLines.has(line)) return true;
+  }
+  return false;
+}
+
+function mentionsSymbol(finding: UnusedImport, changedText: string[]): boolean {
+  const names = finding.symbols
+    .map(symbol => symbol.split(/::|\./).pop() ?? symbol)
+    .filter(name => /^[\p{L}\p{N}_$-]+$/u.test(name));
+  if (names.length === 0) return false;
+
+  const escaped = names.map(name => name.replace(/\$/g, '\\$'));
+  const pattern = new RegExp(`(?<![\\p{L}\\p{N}_$])(?:${escaped.join('|')})(?![\\p{L}\\p{N}_$])`, 'u');
+  return changedText.some(text => pattern.test(text));
+}
+
+/**
+ * The directory diff paths are relative to: the root of the git repository containing
+ * `cwd`, or `cwd` outside of one
+ */
+export function diffRoot(cwd: string = process.cwd()): string {
+  try {
+    return execFileSync('git', ['rev-parse', '--show-toplevel'], { cwd, encoding: 'utf-8', stdio: ['ignore', 'pipe', 'ignore'] }).trim();
+  } catch {
+    return cwd;
+  }
+}
+
+/**
+ * `git diff` of the working tree against `base`, with renames detected and no
+ * context lines, run from the repository root
+ */
+export function gitDiff(base: string, root: string): string {
+  try {
+    return execFileSync('git', ['diff', '--no-color', '--no-ext-diff', '-M', '--unified=0', base, '--'], {
+      cwd: root,
+      encoding: 'utf-8',
+      maxBuffer: 256 * 1024 * 1024,
+      stdio: ['ignore', 'pipe', 'pipe'],
+    });
+  } catch (error) {
+    const stderr = (error as { stderr?: string }).stderr?.trim();
+    throw new Error(`git diff against ${base} failed${stderr ? `: ${stderr}` : ''}`);
+  }
+}
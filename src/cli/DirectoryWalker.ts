@@ -0,0 +1,167 @@
+import * as fs from 'fs';
+import * as path from 'path';
+import { IgnoreRules } from './IgnoreRules';
+import { toPosixPath } from './Paths';
+
+/** Ignore files read in every directory, lowest precedence first */
+export const IGNORE_FILE_NAMES = ['.gitignore', '.ignore', '.importlensignore'];
+
+/** Directories that are never worth descending into */
+export const DEFAULT_SKIPPED_DIRECTORIES = ['.git', 'node_modules', 'target', 'dist', 'out', 'build'];
+
+export interface WalkOptions {
+  /** File extensions to collect, e.g. ['.rs'] */
+  extensions: string[];
+  /** Include dotfiles and dot-directories */
+  hidden: boolean;
+  /** Extra gitignore-style patterns relative to `excludeBase`; these cannot be re-included */
+  exclude: string[];
+  excludeBase: string;
+}
+
+interface RuleScope {
+  base: string;
+  rules: IgnoreRules;
+}
+
+/**
+ * Recursively collects source files below a directory while honouring
+ * `.gitignore`, `.ignore` and `.importlensignore` files.
+ *
+ * Symlinks are followed; each real directory is visited at most once so
+ * symlink cycles terminate. Broken symlinks are reported in `warnings`.
+ */
+export class DirectoryWalker {
+  readonly warnings: string[] = [];
+  private readonly excludeRules: IgnoreRules;
+  private readonly skipped = new Set(DEFAULT_SKIPPED_DIRECTORIES);
+
+  constructor(private options: WalkOptions) {
+    this.excludeRules = new IgnoreRules(options.exclude);
+  }
+
+  walk(root: string): string[] {
+    const absoluteRoot = path.resolve(root);
+    const files: string[] = [];
+    const visited = new Set<string>();
+
+    this.walkDirectory(absoluteRoot, this.loadAncestorScopes(absoluteRoot), visited, files);
+
+    return files.sort();
+  }
+
+  private walkDirectory(directory: string, scopes: RuleScope[], visited: Set<string>, files: string[]): void {
+    let realPath: string;
+    try {
+      realPath = fs.realpathSync(directory);
+    } catch (error) {
+      this.warnings.push(`Cannot resolve directory ${directory}: ${errorMessage(error)}`);
+      return;
+    }
+    if (visited.has(realPath)) return;
+    visited.add(realPath);
+
+    let entries: fs.Dirent[];
+    try {
+      entries = fs.readdirSync(directory, { withFileTypes: true });
+    } catch (error) {
+      this.warnings.push(`Cannot read directory ${directory}: ${errorMessage(error)}`);
+      return;
+    }
+
+    const localRules = this.loadIgnoreFiles(directory);
+    const activeScopes = localRules ? [...scopes, { base: directory, rules: localRules }] : scopes;
+
+    entries.sort((a, b) => (a.name < b.name ? -1 : a.name > b.name ? 1 : 0));
+
+    for (const entry of entries) {
+      const entryPath = path.join(directory, entry.name);
+
+      if (entry.name.startsWith('.') && !this.options.hidden) continue;
+
+      let isDirectory = entry.isDirectory();
+      let isFile = entry.isFile();
+
+      if (entry.isSymbolicLink()) {
+        try {
+          const target = fs.statSync(entryPath);
+          isDirectory = target.isDirectory();
+          isFile = target.isFile();
+        } catch {
+          this.warnings.push(`Skipping broken symlink ${entryPath}`);
+          continue;
+        }
+      }
+
+      if (isDirectory) {
+        if (this.skipped.has(entry.name)) continue;
+        if (this.isIgnored(entryPath, true, activeScopes)) continue;
+        this.walkDirectory(entryPath, activeScopes, visited, files);
+      } else if (isFile) {
+        if (!this.options.extensions.includes(path.extname(entry.name))) continue;
+        if (this.isIgnored(entryPath, false, activeScopes)) continue;
+        files.push(entryPath);
+      }
+    }
+  }
+
+  /**
+   * Exclude patterns always win; otherwise the deepest ignore file with a matching pattern decides.
+   */
+  private isIgnored(entryPath: string, isDirectory: boolean, scopes: RuleScope[]): boolean {
+    if (!this.excludeRules.isEmpty && this.excludeRules.match(toPosixPath(path.relative(this.options.excludeBase, entryPath)), isDirectory)) {
+      return true;
+    }
+
+    for (let i = scopes.length - 1; i >= 0; i--) {
+      const verdict = scopes[i].rules.match(toPosixPath(path.relative(scopes[i].base, entryPath)), isDirectory);
+      if (verdict !== undefined) return verdict;
+    }
+
+    return false;
+  }
+
+  private loadIgnoreFiles(directory: string): IgnoreRules | null {
+    const rules = new IgnoreRules();
+
+    for (const name of IGNORE_FILE_NAMES) {
+      const filePath = path.join(directory, name);
+      if (!fs.existsSync(filePath)) continue;
+      try {
+        rules.add(fs.readFileSync(filePath, 'utf-8').split(/\r?\n/));
+      } catch (error) {
+        this.warnings.push(`Cannot read ${filePath}: ${errorMessage(error)}`);
+      }
+    }
+
+    return rules.isEmpty ? null : rules;
+  }
+
+  /**
+   * When the walk starts inside a git repository, ignore files between the
+   * repository root and the walk root apply too.
+   */
+  private loadAncestorScopes(root: string): RuleScope[] {
+    const ancestors: string[] = [];
+    let current = path.dirname(root);
+
+    if (fs.existsSync(path.join(root, '.git'))) return [];
+
+    while (current !== path.dirname(current)) {
+      ancestors.unshift(current);
+      if (fs.existsSync(path.join(current, '.git'))) {
+        return ancestors.flatMap(directory => {
+          const rules = this.loadIgnoreFiles(directory);
+          return rules ? [{ base: directory, rules }] : [];
+        });
+      }
+      current = path.dirname(current);
+    }
+
+    return [];
+  }
+}
+
+function errorMessage(error: unknown): string {
+  return error instanceof Error ? error.message : String(error);
+}
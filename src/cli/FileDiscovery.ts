@@ -1,83 +1,162 @@
+import * as fs from 'fs';
 import * as path from 'path';
 import fg from 'fast-glob';
 import { CLIArguments } from './ArgumentParser';
+import { ConfigResolver } from './Config';
+import { DEFAULT_SKIPPED_DIRECTORIES, DirectoryWalker } from './DirectoryWalker';
+import { EXTENSION_LANGUAGES, extensionsFor } from './Languages';
+
+/** Files larger than this are skipped unless `--include-large-files` is given */
+export const DEFAULT_MAX_FILE_SIZE = 10 * 1024 * 1024;
 
 export class FileDiscovery {
-  private readonly supportedExtensions = [
-    '.ts', '.tsx', '.js', '.jsx',
-    '.py',
-    '.java',
-    '.go',
-    '.rs',
-    '.cpp', '.cc', '.cxx', '.c', '.h', '.hpp'
-  ];
+  /** Problems found while discovering files (broken symlinks, missing paths, files over the size limit) */
+  readonly warnings: string[] = [];
 
-  constructor(private args: CLIArguments) {}
+  constructor(private args: CLIArguments, private config = new ConfigResolver(args.configOverrides, args.language)) {}
 
   /**
-   * Discover files to analyze based on patterns and exclusions
-   * Uses fast-glob for production-grade glob matching
+   * Discover files to analyze based on patterns and exclusions.
+   * Directories are walked recursively with .gitignore/.ignore/.importlensignore support,
+   * explicit files are always included, and glob patterns are expanded with fast-glob.
+   * With `--language`, walked directories only yield files of that language.
+   * Walked and globbed files ignored by an `importlens.toml` are dropped, and so
+   * are files over `--max-file-size`, with a warning, unless `--include-large-files`
+   * is given. Returns absolute paths in sorted order.
    */
   async discoverFiles(): Promise<string[]> {
-    // Default exclusions
-    const defaultExclusions = [
-      '**/node_modules/**',
-      '**/dist/**',
-      '**/out/**',
-      '**/build/**',
-      '**/.git/**',
-      '**/*.min.js',
-      '**/*.min.css'
-    ];
+    const inputs = this.args.files.length === 0 ? ['.'] : this.args.files;
+    const files = new Set<string>();
+    const globPatterns: string[] = [];
+
+    const walker = new DirectoryWalker({
+      extensions: this.args.language ? extensionsFor(this.args.language) : Object.keys(EXTENSION_LANGUAGES),
+      hidden: this.args.hidden,
+      exclude: ['*.min.js', '*.min.css', ...this.args.exclude],
+      excludeBase: process.cwd(),
+    });
 
-    const allExclusions = [...defaultExclusions, ...this.args.exclude];
+    for (const input of inputs) {
+      if (isGlobPattern(input)) {
+        globPatterns.push(input.replace(/\\/g, '/'));
+        continue;
+      }
 
-    // Build patterns to search
-    let searchPatterns: string[];
+      const resolved = path.resolve(process.cwd(), input);
+      let stat: fs.Stats;
+      try {
+        stat = fs.statSync(resolved);
+      } catch {
+        this.warnings.push(`No such file or directory: ${input}`);
+        continue;
+      }
 
-    if (this.args.files.length === 0) {
-      // Default: search current directory for all supported file types
-      searchPatterns = this.supportedExtensions.map(ext => `**/*${ext}`);
-    } else {
-      // User provided patterns - expand them
-      searchPatterns = this.expandUserPatterns(this.args.files);
+      if (stat.isDirectory()) {
+        walker.walk(resolved).filter(file => !this.config.resolve(file).ignored).forEach(file => files.add(file));
+      } else {
+        files.add(resolved);
+      }
     }
 
-    // Use fast-glob for efficient, reliable file discovery
-    const files = await fg(searchPatterns, {
-      ignore: allExclusions,
-      onlyFiles: true,
-      absolute: true,
-      dot: false,
-      cwd: process.cwd(),
-      suppressErrors: true // Don't throw on permission errors
-    });
+    if (globPatterns.length > 0) {
+      const matches = await this.expandGlobs(globPatterns);
+      matches
+        .map(file => path.resolve(file))
+        .filter(file => !this.config.resolve(file).ignored)
+        .forEach(file => files.add(file));
+    }
+
+    this.warnings.push(...walker.warnings);
 
-    return files;
+    return [...files].sort().filter(file => {
+      try {
+        return this.withinSizeLimit(file, fs.statSync(file).size);
+      } catch {
+        // Let the analysis report the read error
+        return true;
+      }
+    });
   }
 
   /**
-   * Expand user-provided patterns to handle directories and specific files
+   * `--files-from`: the listed paths that are files of a known language (or of
+   * `--language`) and not ignored by an `importlens.toml`. Paths are taken
+   * literally, relative to the working directory. Missing paths and directories are
+   * skipped without a warning, so `git diff --name-only` output can be piped in
+   * as is, deleted files included. Returns absolute paths in sorted order.
    */
-  private expandUserPatterns(userPatterns: string[]): string[] {
-    const expandedPatterns: string[] = [];
-
-    for (const pattern of userPatterns) {
-      const resolved = path.resolve(process.cwd(), pattern);
-
-      // If pattern is a directory, expand to all supported extensions
-      if (pattern.endsWith('/') || pattern.endsWith('\\') || !path.extname(pattern)) {
-        const normalized = pattern.replace(/\\/g, '/').replace(/\/$/, '');
-        this.supportedExtensions.forEach(ext => {
-          expandedPatterns.push(`${normalized}/**/*${ext}`);
-        });
-      } else {
-        // It's a file pattern or glob - use as-is
-        expandedPatterns.push(pattern.replace(/\\/g, '/'));
+  filterListedFiles(paths: string[]): string[] {
+    const files = new Set<string>();
+
+    for (const listed of paths) {
+      const resolved = path.resolve(process.cwd(), listed);
+      try {
+        if (!fs.statSync(resolved).isFile()) continue;
+      } catch {
+        continue;
       }
+
+      const effective = this.config.resolve(resolved);
+      if (!effective.ignored && effective.language !== 'unknown') files.add(resolved);
     }
 
-    return expandedPatterns;
+    return [...files].sort();
+  }
+
+  /**
+   * Whether a file of `size` bytes is to be analyzed; warns about one that isn't.
+   * Generated sources (bindgen output, protobuf code) can run to tens of megabytes,
+   * which take long to analyze and need a lot of memory.
+   */
+  private withinSizeLimit(filePath: string, size: number): boolean {
+    const limit = this.args.maxFileSize ?? DEFAULT_MAX_FILE_SIZE;
+    if (this.args.includeLargeFiles || size <= limit) return true;
+
+    this.warnings.push(`Skipping ${filePath}: ${formatSize(size)} is over the ${formatSize(limit)} --max-file-size; pass --include-large-files to analyze it`);
+    return false;
+  }
+
+  /**
+   * Expand user-provided glob patterns with fast-glob
+   */
+  private async expandGlobs(patterns: string[]): Promise<string[]> {
+    const defaultExclusions = [
+      ...DEFAULT_SKIPPED_DIRECTORIES.map(name => `**/${name}/**`),
+      '**/*.min.js',
+      '**/*.min.css'
+    ];
+
+    return fg(patterns, {
+      ignore: [...defaultExclusions, ...this.args.exclude],
+      onlyFiles: true,
+      absolute: true,
+      dot: this.args.hidden,
+      cwd: process.cwd(),
+      suppressErrors: true // Don't throw on permission errors
+    });
+  }
+}
+
+/**
+ * Split a `--files-from` list: one path per line, or NUL-separated with `-0`.
+ * Empty entries are dropped; with lines, so are `\r` line endings.
+ */
+export function parseFileList(text: string, nullSeparated: boolean): string[] {
+  const entries = nullSeparated ? text.split('\0') : text.split('\n').map(line => line.replace(/\r$/, ''));
+  return entries.filter(entry => entry.length > 0);
+}
+
+/** `size` bytes for messages: `512 B`, `64 KB`, `52.4 MB` */
+export function formatSize(size: number): string {
+  const units = ['B', 'KB', 'MB', 'GB'];
+  let unit = 0;
+  while (size >= 1024 && unit < units.length - 1) {
+    size /= 1024;
+    unit++;
   }
+  return `${unit > 0 && size < 100 && !Number.isInteger(size) ? size.toFixed(1) : Math.round(size)} ${units[unit]}`;
+}
 
+function isGlobPattern(input: string): boolean {
+  return /[*?[\]{}]/.test(input);
 }
@@ -0,0 +1,181 @@
+import * as fs from 'fs';
+import { AnalysisResult, isRuleKind, UnusedImport } from './CLIAnalyzer';
+import { displayPath } from './Paths';
+import { RustFixer } from './RustFixer';
+import { RustOrganizer } from './RustOrganizer';
+import { createUnifiedDiff } from './UnifiedDiff';
+
+/**
+ * Replacement of the source range [start, end) with newText
+ */
+export interface TextEdit {
+  start: number;
+  end: number;
+  newText: string;
+}
+
+/**
+ * Outcome of fixing a single file
+ */
+export interface FileFixResult {
+  filePath: string;
+  /** Number of unused imports removed */
+  fixedCount: number;
+  /** Number of glob imports `--expand-globs` replaced with explicit lists */
+  expandedCount: number;
+  /** Whether `--organize` reordered or merged the remaining imports */
+  organized: boolean;
+  /** Unified diff of the change; empty when the file is unchanged */
+  diff: string;
+  /** Set when the file could not be fixed */
+  error?: string;
+}
+
+/**
+ * Apply non-overlapping edits to a string
+ */
+export function applyEdits(content: string, edits: TextEdit[]): string {
+  const sorted = [...edits].sort((a, b) => b.start - a.start);
+  let result = content;
+  for (const edit of sorted) {
+    result = result.slice(0, edit.start) + edit.newText + result.slice(edit.end);
+  }
+  return result;
+}
+
+/**
+ * Replace the file at `filePath` with `content` by writing a temporary file next to
+ * it and renaming it over the original, so an interrupted fix leaves either the old
+ * file or the new one, never part of each. The file keeps its permissions.
+ */
+export function writeFileAtomic(filePath: string, content: string): void {
+  const temp = `${filePath}.${process.pid}.importlens.tmp`;
+  try {
+    const { mode } = fs.statSync(filePath);
+    fs.writeFileSync(temp, content, 'utf-8');
+    fs.chmodSync(temp, mode);
+    fs.renameSync(temp, filePath);
+  } catch (error) {
+    fs.rmSync(temp, { force: true });
+    throw error;
+  }
+}
+
+/**
+ * Whether `--fix` can remove a finding: it needs an exact span, and doctests live in
+ * comments, which `--fix` doesn't rewrite. Banned imports are used, and custom rules'
+ * findings are only reported.
+ */
+export function isFixable(finding: UnusedImport): boolean {
+  return finding.span !== undefined && finding.kind !== 'useless-suppression' && finding.kind !== 'banned-import' &&
+    finding.doctestLine === undefined && !isRuleKind(finding.kind ?? 'unused-import');
+}
+
+/**
+ * Rewrites source files to remove the unused imports reported by CLIAnalyzer.
+ * Only languages whose analyzer reports precise spans can be fixed.
+ */
+export class FixEngine {
+  private rustFixer = new RustFixer();
+  private rustOrganizer = new RustOrganizer();
+
+  canFix(language: string): boolean {
+    return language === 'rust';
+  }
+
+  canOrganize(language: string): boolean {
+    return language === 'rust';
+  }
+
+  /**
+   * Compute the edits that remove the given unused imports
+   */
+  computeEdits(content: string, language: string, unusedImports: UnusedImport[]): TextEdit[] {
+    switch (language) {
+      case 'rust':
+        return this.rustFixer.computeEdits(content, unusedImports);
+      default:
+        return [];
+    }
+  }
+
+  /**
+   * Return the content with the given unused imports removed
+   */
+  applyFixes(content: string, language: string, unusedImports: UnusedImport[]): string {
+    return applyEdits(content, this.computeEdits(content, language, unusedImports));
+  }
+
+  /**
+   * Compute the edits that group and sort the imports (`--organize`), one per block of imports
+   */
+  organizeEdits(content: string, language: string): TextEdit[] {
+    return language === 'rust' ? this.rustOrganizer.computeEdits(content) : [];
+  }
+
+  /**
+   * Return the content with its imports grouped and sorted
+   */
+  organizeImports(content: string, language: string): string {
+    return applyEdits(content, this.organizeEdits(content, language));
+  }
+
+  /**
+   * Remove the fixable findings of `result` from `content`, expand its glob imports
+   * (`globExpansions`) and, with `organize`, organize the imports that remain.
+   * Nothing is read or written.
+   */
+  fixSource(
+    content: string,
+    result: AnalysisResult,
+    organize = false
+  ): { fixed: string; fixedCount: number; expandedCount: number; organized: boolean } {
+    const fixable = result.unusedImports.filter(isFixable);
+    const removals = this.computeEdits(content, result.language, fixable);
+    // A glob whose group is rewritten by a removal is left for the next run
+    const expansions = (result.globExpansions ?? []).filter(expansion =>
+      !removals.some(removal => removal.start < expansion.end && expansion.start < removal.end));
+    const edited = applyEdits(content, [...removals, ...expansions]);
+    const fixed = organize ? this.organizeImports(edited, result.language) : edited;
+    // Findings no removal touches, such as stale spans or edits merged away, weren't fixed
+    const fixedCount = fixable.filter(finding =>
+      removals.some(removal => removal.start < finding.span!.end && finding.span!.start < removal.end)).length;
+
+    return {
+      fixed,
+      fixedCount,
+      expandedCount: expansions.length,
+      organized: fixed !== edited,
+    };
+  }
+
+  /**
+   * Fix one analyzed file, then organize its remaining imports when `organize` is set.
+   * With dryRun the file is left untouched and only the diff is returned. The diff
+   * names the file relative to `cwd`, so `git apply` run there accepts it.
+   */
+  fixFile(result: AnalysisResult, dryRun: boolean, organize = false, cwd: string = process.cwd()): FileFixResult {
+    try {
+      const content = fs.readFileSync(result.filePath, 'utf-8');
+      const { fixed, fixedCount, expandedCount, organized } = this.fixSource(content, result, organize);
+
+      const file = displayPath(result.filePath, cwd);
+      const diff = createUnifiedDiff(`a/${file}`, `b/${file}`, content, fixed);
+
+      if (!dryRun && fixed !== content) {
+        writeFileAtomic(result.filePath, fixed);
+      }
+
+      return { filePath: result.filePath, fixedCount, expandedCount, organized, diff };
+    } catch (error) {
+      return {
+        filePath: result.filePath,
+        fixedCount: 0,
+        expandedCount: 0,
+        organized: false,
+        diff: '',
+        error: error instanceof Error ? error.message : String(error),
+      };
+    }
+  }
+}
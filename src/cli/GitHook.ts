@@ -0,0 +1,110 @@
+import { execFileSync } from 'child_process';
+import * as fs from 'fs';
+import * as path from 'path';
+import { createUnifiedDiff } from './UnifiedDiff';
+
+// Marks hooks written by `hook install`, which it may overwrite
+const HOOK_MARKER = '# Installed by importlens-cli hook install';
+
+const HOOK_SCRIPT = `#!/bin/sh
+${HOOK_MARKER}
+# Checks the staged content of the files being committed; skip once with --no-verify
+exec importlens-cli hook --staged
+`;
+
+/**
+ * Root of the git repository containing `cwd`
+ */
+export function gitRoot(cwd: string = process.cwd()): string {
+  try {
+    return git(['rev-parse', '--show-toplevel'], cwd).trim();
+  } catch {
+    throw new Error(`${cwd} is not inside a git repository; hook needs one to read staged files from`);
+  }
+}
+
+/**
+ * Files with staged content to commit (added, copied, modified or renamed), relative
+ * to the repository root. Deleted files have nothing left to check.
+ */
+export function stagedFiles(root: string): string[] {
+  return git(['diff', '--cached', '--name-only', '--diff-filter=ACMR', '-z', '--no-renames'], root)
+    .split('\0')
+    .filter(file => file !== '');
+}
+
+/**
+ * The staged version of a file, which can differ from the working tree when only
+ * some of its changes were added
+ */
+export function stagedContent(root: string, file: string): string {
+  return git(['cat-file', 'blob', `:${file}`], root);
+}
+
+/**
+ * Files with uncommitted changes, untracked ones included, relative to the root of
+ * the repository containing `cwd`; undefined outside a git repository
+ */
+export function dirtyFiles(cwd: string = process.cwd()): string[] | undefined {
+  let status: string;
+  try {
+    status = git(['status', '--porcelain', '-z', '--untracked-files=all', '--no-renames'], cwd);
+  } catch {
+    return undefined;
+  }
+  return status
+    .split('\0')
+    .filter(entry => entry !== '')
+    .map(entry => entry.slice(3))
+    .sort();
+}
+
+/**
+ * `hook --fix-staged`: turn the staged version of `file` from `staged` into `fixed`
+ * in the index, and make the same change to the working tree, leaving unstaged
+ * changes alone. Nothing is changed when the fix doesn't apply to the working tree,
+ * e.g. because unstaged changes touch the same lines; an error says so.
+ */
+export function restageFix(root: string, file: string, staged: string, fixed: string): void {
+  // One line of context, so unstaged changes close by don't stop the patch from applying
+  const patch = createUnifiedDiff(`a/${file}`, `b/${file}`, staged, fixed, 1);
+  if (patch === '') return;
+
+  try {
+    git(['apply', '--check', '-'], root, patch);
+    git(['apply', '--check', '--cached', '-'], root, patch);
+  } catch {
+    throw new Error(`the fix for ${file} doesn't apply to the working tree; its unstaged changes overlap the removed imports`);
+  }
+  git(['apply', '-'], root, patch);
+  git(['apply', '--cached', '-'], root, patch);
+}
+
+/**
+ * Write a pre-commit hook that runs `importlens-cli hook --staged`, where git looks
+ * for hooks (`core.hooksPath` included). An existing hook is only replaced if
+ * `hook install` wrote it. Returns the hook's path.
+ */
+export function installHook(cwd: string = process.cwd()): string {
+  gitRoot(cwd);
+  const hookPath = path.resolve(cwd, git(['rev-parse', '--git-path', 'hooks/pre-commit'], cwd).trim());
+
+  if (fs.existsSync(hookPath) && !fs.readFileSync(hookPath, 'utf-8').includes(HOOK_MARKER)) {
+    throw new Error(`${hookPath} already exists; add \`importlens-cli hook --staged\` to it instead`);
+  }
+
+  fs.mkdirSync(path.dirname(hookPath), { recursive: true });
+  fs.writeFileSync(hookPath, HOOK_SCRIPT, { mode: 0o755 });
+  fs.chmodSync(hookPath, 0o755);
+  return hookPath;
+}
+
+function git(args: string[], cwd: string, input?: string): string {
+  return execFileSync('git', args, {
+    cwd,
+    input,
+    encoding: 'utf-8',
+    maxBuffer: 256 * 1024 * 1024,
+    stdio: ['pipe', 'pipe', 'pipe'],
+  });
+}
@@ -0,0 +1,199 @@
+import { UnusedImport } from './CLIAnalyzer';
+import { GoToken, stringValue, tokenizeGo } from './GoLexer';
+import { LineIndex } from './LineIndex';
+
+/**
+ * One import spec: `"fmt"`, `str "strings"`, `_ "net/http/pprof"` or `. "math"`
+ */
+export interface GoImportSpec {
+  /** Name written before the path: an alias, `_` or `.` */
+  name?: string;
+  path: string;
+  /** Name the import binds in the file; the assumed package name unless aliased */
+  binding: string;
+  /** Offsets of the spec, name included */
+  start: number;
+  end: number;
+  /** Offsets of the name, when there is one */
+  nameStart?: number;
+  nameEnd?: number;
+}
+
+const GO_BUILD = /^\/\/go:build\s+(.+?)\s*$/;
+const PLUS_BUILD = /^\/\/\s*\+build\s+(.+?)\s*$/;
+
+/**
+ * Import analyzer for Go files.
+ *
+ * The compiler already rejects unused imports, so this mostly serves the unified
+ * report, and files that aren't compiled on the machine running the build because
+ * of their build constraints.
+ *
+ * - A package counts as used when its name is the left side of a selector (`fmt.Println`)
+ * - Blank imports (`_ "net/http/pprof"`), dot imports and `import "C"` are never reported
+ * - Unaliased imports bind the name goimports assumes for the path: the last element,
+ *   skipping a `/vN` suffix, without a `go-` prefix and cut at the first character that
+ *   can't be in an identifier (`gopkg.in/yaml.v3` is `yaml`). Findings that rely on such
+ *   a guess have `low` confidence
+ * - An alias identical to the last path element (`fmt "fmt"`) is a `redundant-alias`
+ * - The file's `//go:build` (or legacy `// +build`) constraint is recorded in every finding
+ */
+export class GoAnalyzer {
+  analyzeFile(content: string): UnusedImport[] {
+    const tokens = tokenizeGo(content).filter(token => token.kind !== 'comment' || isConstraintComment(token));
+    const packageAt = tokens.findIndex(token => token.kind === 'ident' && token.text === 'package');
+    if (packageAt === -1) return [];
+
+    const constraint = buildConstraint(tokens.slice(0, packageAt));
+    const code = tokens.filter(token => token.kind !== 'comment');
+    const { specs, end } = parseImports(code, code.findIndex(token => token.text === 'package') + 2);
+    const selectors = collectSelectorNames(code, end);
+    const lineIndex = new LineIndex(content);
+    const context = constraint ? ` (file built only when \`${constraint}\`)` : '';
+
+    const findings: UnusedImport[] = [];
+    for (const spec of specs) {
+      if (spec.name === '_' || spec.name === '.' || spec.path === 'C') continue;
+      const importStatement = `import ${content.slice(spec.start, spec.end)}`;
+      const base = spec.path.split('/').pop() ?? spec.path;
+      const described = spec.binding === spec.path ? spec.path : `${spec.binding} (${spec.path})`;
+
+      if (!selectors.has(spec.binding)) {
+        const position = lineIndex.positionAt(spec.start);
+        findings.push({
+          line: position.line,
+          column: position.column,
+          importStatement,
+          symbols: [spec.binding],
+          reason: `Package ${described} not used in code${context}`,
+          span: { start: spec.start, end: spec.end },
+          kind: 'unused-import',
+          importPath: spec.path,
+          ...(spec.name === undefined && spec.binding !== base ? { confidence: 'low' as const, confidenceReason: 'package-name-guessed' as const } : {}),
+          ...(constraint ? { buildConstraint: constraint } : {}),
+        });
+      } else if (spec.name === base && spec.nameStart !== undefined && spec.nameEnd !== undefined) {
+        const position = lineIndex.positionAt(spec.nameStart);
+        findings.push({
+          line: position.line,
+          column: position.column,
+          importStatement,
+          symbols: [spec.name],
+          reason: `Alias ${spec.name} for package ${spec.path} repeats its name${context}`,
+          span: { start: spec.nameStart, end: spec.nameEnd },
+          kind: 'redundant-alias',
+          importPath: spec.path,
+          ...(constraint ? { buildConstraint: constraint } : {}),
+        });
+      }
+    }
+
+    return findings;
+  }
+}
+
+/**
+ * The package name goimports assumes for an import path, e.g. `yaml` for
+ * `gopkg.in/yaml.v3` and `chi` for `github.com/go-chi/chi/v5`
+ */
+export function assumedPackageName(importPath: string): string {
+  const elements = importPath.split('/');
+  let base = elements[elements.length - 1];
+  if (/^v\d+$/.test(base) && elements.length > 1) {
+    base = elements[elements.length - 2];
+  }
+  base = base.replace(/^go-/, '');
+  const cut = base.search(/[^\p{L}\p{N}_]/u);
+  return cut === -1 ? base : base.slice(0, cut);
+}
+
+/**
+ * The constraint from the comments before the package clause, as a `//go:build`
+ * expression; legacy `// +build` lines are converted (spaces are `||`, commas `&&`,
+ * several lines all apply)
+ */
+export function buildConstraint(comments: GoToken[]): string | undefined {
+  const goBuild = comments.map(comment => GO_BUILD.exec(comment.text)).find(match => match);
+  if (goBuild) return goBuild[1];
+
+  const lines = comments.map(comment => PLUS_BUILD.exec(comment.text)).filter((match): match is RegExpExecArray => match !== null);
+  if (lines.length === 0) return undefined;
+
+  const expressions = lines.map(match => {
+    const options = match[1].split(/\s+/).map(option => {
+      const terms = option.split(',');
+      return terms.length > 1 && match[1].includes(' ') ? `(${terms.join(' && ')})` : terms.join(' && ');
+    });
+    return options.length > 1 && lines.length > 1 ? `(${options.join(' || ')})` : options.join(' || ');
+  });
+  return expressions.join(' && ');
+}
+
+function isConstraintComment(token: GoToken): boolean {
+  return GO_BUILD.test(token.text) || PLUS_BUILD.test(token.text);
+}
+
+/**
+ * Parse the import declarations that follow the package clause, from token `from`;
+ * returns the specs and the index of the first token after them
+ */
+function parseImports(tokens: GoToken[], from: number): { specs: GoImportSpec[]; end: number } {
+  const specs: GoImportSpec[] = [];
+  let i = from;
+
+  while (i < tokens.length) {
+    if (tokens[i].text === ';') {
+      i++;
+      continue;
+    }
+    if (tokens[i].kind !== 'ident' || tokens[i].text !== 'import') break;
+    i++;
+
+    if (tokens[i]?.text === '(') {
+      i++;
+      while (i < tokens.length && tokens[i].text !== ')') {
+        const next = parseSpec(tokens, i, specs);
+        i = next === i ? i + 1 : next;
+      }
+      i++;
+    } else {
+      i = parseSpec(tokens, i, specs);
+    }
+  }
+
+  return { specs, end: i };
+}
+
+/** Parse one `[name] "path"` spec at `at` into `specs`; returns the index after it */
+function parseSpec(tokens: GoToken[], at: number, specs: GoImportSpec[]): number {
+  const first = tokens[at];
+  if (!first) return at;
+
+  const named = (first.kind === 'ident' || first.text === '.') && tokens[at + 1]?.kind === 'string';
+  const pathToken = named ? tokens[at + 1] : first;
+  if (pathToken.kind !== 'string') return at;
+
+  const path = stringValue(pathToken);
+  const name = named ? first.text : undefined;
+  specs.push({
+    name,
+    path,
+    binding: name ?? assumedPackageName(path),
+    start: first.start,
+    end: pathToken.end,
+    ...(named ? { nameStart: first.start, nameEnd: first.end } : {}),
+  });
+  return named ? at + 2 : at + 1;
+}
+
+/** Identifiers used as the left side of a selector (`fmt` in `fmt.Println`) from token `from` on */
+function collectSelectorNames(tokens: GoToken[], from: number): Set<string> {
+  const names = new Set<string>();
+  for (let i = from; i < tokens.length; i++) {
+    const token = tokens[i];
+    if (token.kind === 'ident' && tokens[i + 1]?.text === '.' && tokens[i - 1]?.text !== '.') {
+      names.add(token.text);
+    }
+  }
+  return names;
+}
@@ -0,0 +1,111 @@
+export type GoTokenKind = 'ident' | 'string' | 'char' | 'number' | 'op' | 'comment';
+
+export interface GoToken {
+  kind: GoTokenKind;
+  text: string;
+  /** Offset of the first character */
+  start: number;
+  /** Offset one past the last character */
+  end: number;
+}
+
+const IDENT_START = /[\p{L}_]/u;
+const IDENT_PART = /[\p{L}\p{N}_]/u;
+const NUMBER_PART = /[\w.]/;
+const OPERATORS = ['&^=', '<<=', '>>=', '...', '&&', '||', '<-', '++', '--', '==', '!=', '<=', '>=', ':=',
+  '+=', '-=', '*=', '/=', '%=', '&=', '|=', '^=', '<<', '>>', '&^', '~'];
+
+/**
+ * Minimal Go tokenizer: enough to find import declarations, package selectors
+ * and build constraint comments.
+ *
+ * Newlines are skipped rather than turned into the semicolons the Go spec inserts;
+ * import declarations don't need them. Comments are kept as tokens.
+ */
+export function tokenizeGo(source: string): GoToken[] {
+  const tokens: GoToken[] = [];
+  let i = 0;
+
+  const push = (kind: GoTokenKind, start: number, end: number) => {
+    tokens.push({ kind, text: source.slice(start, end), start, end });
+  };
+
+  while (i < source.length) {
+    const char = source[i];
+
+    // Go ignores a BOM at the start of a file
+    if (char === ' ' || char === '\t' || char === '\n' || char === '\r' || (char === '\uFEFF' && i === 0)) {
+      i++;
+      continue;
+    }
+
+    if (char === '/' && source[i + 1] === '/') {
+      let end = i;
+      while (end < source.length && source[end] !== '\n') end++;
+      push('comment', i, source[end - 1] === '\r' ? end - 1 : end);
+      i = end;
+      continue;
+    }
+
+    if (char === '/' && source[i + 1] === '*') {
+      const close = source.indexOf('*/', i + 2);
+      const end = close === -1 ? source.length : close + 2;
+      push('comment', i, end);
+      i = end;
+      continue;
+    }
+
+    // Raw strings run to the next backtick, newlines included
+    if (char === '`') {
+      const close = source.indexOf('`', i + 1);
+      const end = close === -1 ? source.length : close + 1;
+      push('string', i, end);
+      i = end;
+      continue;
+    }
+
+    if (char === '"' || char === '\'') {
+      let end = i + 1;
+      while (end < source.length && source[end] !== char && source[end] !== '\n') {
+        end += source[end] === '\\' ? 2 : 1;
+      }
+      end = Math.min(end + 1, source.length);
+      push(char === '"' ? 'string' : 'char', i, end);
+      i = end;
+      continue;
+    }
+
+    if (IDENT_START.test(char)) {
+      let end = i + 1;
+      while (end < source.length && IDENT_PART.test(source[end])) end++;
+      push('ident', i, end);
+      i = end;
+      continue;
+    }
+
+    if (/\d/.test(char) || (char === '.' && /\d/.test(source[i + 1] ?? ''))) {
+      let end = i + 1;
+      while (end < source.length && (NUMBER_PART.test(source[end]) || (/[eEpP]/.test(source[end - 1]) && /[+-]/.test(source[end])))) {
+        end++;
+      }
+      push('number', i, end);
+      i = end;
+      continue;
+    }
+
+    const operator = OPERATORS.find(op => source.startsWith(op, i));
+    const length = operator ? operator.length : 1;
+    push('op', i, i + length);
+    i += length;
+  }
+
+  return tokens;
+}
+
+/**
+ * The value of an interpreted or raw string literal token, e.g. `fmt` for `"fmt"`;
+ * escapes in import paths are left as written
+ */
+export function stringValue(token: GoToken): string {
+  return token.text.slice(1, token.text.length > 1 ? -1 : undefined);
+}
@@ -0,0 +1,225 @@
+import * as fs from 'fs';
+import * as path from 'path';
+import { AnalysisResult, FINDING_KINDS, FindingSeverity, isRuleKind, SEVERITY_LEVELS, UnusedImport } from './CLIAnalyzer';
+import { LineIndex } from './LineIndex';
+import { displayPath } from './Paths';
+import { compareStrings } from './Report';
+import { toolVersion } from './Version';
+
+/** Files listed in the summary's "worst files" table */
+const TOP_FILES = 20;
+/** Lines of context shown above and below each finding */
+const CONTEXT_LINES = 2;
+
+// Everything is inlined so the report can be mailed or kept as a CI artifact
+const STYLE = `
+body { font: 14px/1.5 -apple-system, BlinkMacSystemFont, "Segoe UI", Helvetica, Arial, sans-serif; margin: 2em auto; max-width: 1100px; padding: 0 1em; color: #1f2328; }
+h1, h2, h3 { line-height: 1.25; }
+h3 { margin-top: 2em; padding-top: 0.5em; border-top: 1px solid #d0d7de; word-break: break-all; }
+a { color: #0969da; text-decoration: none; }
+a:hover { text-decoration: underline; }
+table { border-collapse: collapse; margin: 1em 0; }
+th, td { border: 1px solid #d0d7de; padding: 4px 10px; text-align: left; }
+td.count { text-align: right; font-variant-numeric: tabular-nums; }
+.meta { color: #59636e; }
+.finding { margin: 1em 0 1.5em; }
+.severity { display: inline-block; min-width: 5em; padding: 0 6px; border-radius: 4px; font-size: 12px; font-weight: 600; text-align: center; color: #fff; }
+.severity.error { background: #cf222e; }
+.severity.warning { background: #9a6700; }
+.severity.info { background: #0969da; }
+pre { background: #f6f8fa; border: 1px solid #d0d7de; border-radius: 6px; padding: 8px 0; overflow-x: auto; font: 12px/1.45 ui-monospace, SFMono-Regular, Menlo, Consolas, monospace; }
+pre span.line { display: block; padding: 0 12px; }
+pre span.line.current { background: #fff8c5; }
+pre span.number { display: inline-block; width: 4em; color: #8c959f; user-select: none; }
+mark { background: #ffcecb; color: inherit; border-radius: 2px; }
+`;
+
+interface FileEntry {
+  /** Path relative to the working directory, with `/` separators */
+  display: string;
+  result: AnalysisResult;
+  findings: UnusedImport[];
+  anchor: string;
+}
+
+/**
+ * Build a self-contained HTML report, as written by `--format=html`: a summary
+ * (files analyzed, findings per kind and severity, the worst files), then a section per
+ * file with findings, most findings first, showing the source around each finding
+ * with the offending item highlighted. Files are read again for the excerpts; findings
+ * in files that can't be read show their import statement instead.
+ */
+export function buildHtmlReport(
+  results: AnalysisResult[],
+  cwd: string = process.cwd(),
+  version: string = toolVersion(),
+  generatedAt: Date = new Date()
+): string {
+  const files: FileEntry[] = results
+    .filter(result => result.unusedImports.length > 0)
+    .map(result => ({
+      display: displayPath(result.filePath, cwd),
+      result,
+      findings: [...result.unusedImports].sort((a, b) => (a.span?.start ?? 0) - (b.span?.start ?? 0) || a.line - b.line),
+      anchor: '',
+    }))
+    .sort((a, b) => b.findings.length - a.findings.length || compareStrings(a.display, b.display));
+  files.forEach((file, i) => { file.anchor = `file-${i + 1}`; });
+
+  const findings = files.flatMap(file => file.findings);
+  const errors = results.filter(result => result.error);
+
+  const lines: string[] = [];
+  lines.push('<!DOCTYPE html>');
+  lines.push('<html lang="en">');
+  lines.push('<head>');
+  lines.push('<meta charset="utf-8">');
+  lines.push('<meta name="viewport" content="width=device-width, initial-scale=1">');
+  lines.push('<title>ImportLens Report</title>');
+  lines.push(`<style>${STYLE}</style>`);
+  lines.push('</head>');
+  lines.push('<body>');
+  lines.push('<h1>ImportLens Report</h1>');
+  lines.push(`<p class="meta">Generated ${escapeHtml(generatedAt.toISOString())} by ImportLens ${escapeHtml(version)}</p>`);
+
+  lines.push('<h2 id="summary">Summary</h2>');
+  lines.push('<table>');
+  lines.push(`<tr><th>Files analyzed</th><td class="count">${results.length}</td></tr>`);
+  lines.push(`<tr><th>Files with findings</th><td class="count">${files.length}</td></tr>`);
+  lines.push(`<tr><th>Findings</th><td class="count">${findings.length}</td></tr>`);
+  for (const severity of [...SEVERITY_LEVELS].reverse()) {
+    const count = findings.filter(f => (f.severity ?? 'error') === severity).length;
+    lines.push(`<tr><th>${badge(severity)}</th><td class="count">${count}</td></tr>`);
+  }
+  if (errors.length > 0) {
+    lines.push(`<tr><th><a href="#errors">Analysis errors</a></th><td class="count">${errors.length}</td></tr>`);
+  }
+  lines.push('</table>');
+
+  lines.push('<h2 id="kinds">Findings per kind</h2>');
+  lines.push('<table>');
+  lines.push('<tr><th>Kind</th><th>Findings</th></tr>');
+  const ruleKinds = [...new Set(findings.map(f => f.kind ?? 'unused-import').filter(isRuleKind))].sort();
+  for (const kind of [...FINDING_KINDS, ...ruleKinds]) {
+    const count = findings.filter(f => (f.kind ?? 'unused-import') === kind).length;
+    if (count > 0) lines.push(`<tr><td>${kind}</td><td class="count">${count}</td></tr>`);
+  }
+  lines.push('</table>');
+
+  if (files.length > 0) {
+    lines.push(`<h2 id="top-files">Top ${Math.min(TOP_FILES, files.length)} files</h2>`);
+    lines.push(fileTable(files.slice(0, TOP_FILES)));
+
+    lines.push('<h2 id="files">All files with findings</h2>');
+    lines.push(fileTable(files));
+  }
+
+  for (const file of files) {
+    lines.push(...fileSection(file, cwd));
+  }
+
+  if (errors.length > 0) {
+    lines.push('<h2 id="errors">Analysis errors</h2>');
+    lines.push('<ul>');
+    for (const result of errors) {
+      lines.push(`<li><code>${escapeHtml(displayPath(result.filePath, cwd))}</code>: ${escapeHtml(result.error ?? '')}</li>`);
+    }
+    lines.push('</ul>');
+  }
+
+  lines.push('</body>');
+  lines.push('</html>');
+  return lines.join('\n') + '\n';
+}
+
+function fileTable(files: FileEntry[]): string {
+  const rows = files.map(file => {
+    const counts = SEVERITY_LEVELS.map(severity => file.findings.filter(f => (f.severity ?? 'error') === severity).length);
+    const [info, warning, error] = counts;
+    return `<tr><td><a href="#${file.anchor}">${escapeHtml(file.display)}</a></td>` +
+      `<td class="count">${file.findings.length}</td><td class="count">${error}</td>` +
+      `<td class="count">${warning}</td><td class="count">${info}</td></tr>`;
+  });
+  return ['<table>', '<tr><th>File</th><th>Findings</th><th>Errors</th><th>Warnings</th><th>Info</th></tr>', ...rows, '</table>'].join('\n');
+}
+
+function fileSection(file: FileEntry, cwd: string): string[] {
+  const source = readSource(file.result.filePath, cwd);
+  const lines: string[] = [];
+
+  lines.push(`<h3 id="${file.anchor}">${escapeHtml(file.display)} <span class="meta">(${file.findings.length})</span></h3>`);
+  lines.push('<ul>');
+  file.findings.forEach((finding, i) => {
+    lines.push(`<li><a href="#${file.anchor}-${i + 1}">Line ${finding.line}</a>: ${escapeHtml(finding.reason)}</li>`);
+  });
+  lines.push('</ul>');
+
+  file.findings.forEach((finding, i) => {
+    const severity = finding.severity ?? 'error';
+    const column = finding.column !== undefined ? `:${finding.column}` : '';
+    lines.push(`<div class="finding" id="${file.anchor}-${i + 1}">`);
+    lines.push(`<p>${badge(severity)} <strong>${finding.kind ?? 'unused-import'}</strong> ` +
+      `<a href="#${file.anchor}-${i + 1}" class="meta">line ${finding.line}${column}</a> ${escapeHtml(finding.reason)}</p>`);
+    lines.push(source ? excerpt(source, finding) : `<pre><span class="line">${escapeHtml(finding.importStatement)}</span></pre>`);
+    lines.push('</div>');
+  });
+
+  return lines;
+}
+
+/** The lines around a finding, with its span (or its whole line, without one) highlighted */
+function excerpt(source: { content: string; index: LineIndex; lineStarts: number[] }, finding: UnusedImport): string {
+  const { content, index, lineStarts } = source;
+  const span = finding.span;
+  const firstLine = span ? index.positionAt(span.start).line : finding.line;
+  const lastLine = span ? index.positionAt(Math.max(span.start, span.end - 1)).line : finding.line;
+  const from = Math.max(1, firstLine - CONTEXT_LINES);
+  const to = Math.min(lineStarts.length, lastLine + CONTEXT_LINES);
+
+  const rendered: string[] = [];
+  for (let line = from; line <= to; line++) {
+    const start = lineStarts[line - 1];
+    const end = line < lineStarts.length ? lineStarts[line] - 1 : content.length;
+    const text = content.slice(start, end).replace(/\r$/, '');
+    const current = line >= firstLine && line <= lastLine;
+
+    let html = escapeHtml(text);
+    if (current && span) {
+      const markStart = Math.max(span.start, start) - start;
+      const markEnd = Math.min(span.end, start + text.length) - start;
+      if (markEnd > markStart) {
+        html = escapeHtml(text.slice(0, markStart)) + `<mark>${escapeHtml(text.slice(markStart, markEnd))}</mark>` + escapeHtml(text.slice(markEnd));
+      }
+    }
+
+    rendered.push(`<span class="line${current ? ' current' : ''}"><span class="number">${line}</span>${html}</span>`);
+  }
+
+  return `<pre>${rendered.join('')}</pre>`;
+}
+
+function readSource(filePath: string, cwd: string): { content: string; index: LineIndex; lineStarts: number[] } | undefined {
+  try {
+    const content = fs.readFileSync(path.resolve(cwd, filePath), 'utf-8');
+    const lineStarts = [0];
+    for (let i = 0; i < content.length; i++) {
+      if (content[i] === '\n') lineStarts.push(i + 1);
+    }
+    return { content, index: new LineIndex(content), lineStarts };
+  } catch {
+    return undefined;
+  }
+}
+
+function badge(severity: FindingSeverity): string {
+  return `<span class="severity ${severity}">${severity}</span>`;
+}
+
+function escapeHtml(text: string): string {
+  return text
+    .replace(/&/g, '&amp;')
+    .replace(/</g, '&lt;')
+    .replace(/>/g, '&gt;')
+    .replace(/"/g, '&quot;')
+    .replace(/'/g, '&#39;');
+}
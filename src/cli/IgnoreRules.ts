@@ -0,0 +1,124 @@
+interface IgnoreRule {
+  regex: RegExp;
+  negated: boolean;
+  directoryOnly: boolean;
+}
+
+/**
+ * A parsed set of gitignore-style patterns (`.gitignore`, `.ignore`, `.importlensignore`).
+ * Paths are matched relative to the directory the patterns belong to, with `/` separators.
+ * As in git, the last matching pattern wins and `!pattern` re-includes a path.
+ */
+export class IgnoreRules {
+  private rules: IgnoreRule[] = [];
+
+  constructor(lines: string[] = []) {
+    this.add(lines);
+  }
+
+  static parse(content: string): IgnoreRules {
+    return new IgnoreRules(content.split(/\r?\n/));
+  }
+
+  get isEmpty(): boolean {
+    return this.rules.length === 0;
+  }
+
+  add(lines: string[]): void {
+    for (const line of lines) {
+      const rule = parseRule(line);
+      if (rule) this.rules.push(rule);
+    }
+  }
+
+  /**
+   * Returns true if the path is ignored, false if a negated pattern re-includes it,
+   * and undefined if no pattern matches.
+   */
+  match(relativePath: string, isDirectory: boolean): boolean | undefined {
+    for (let i = this.rules.length - 1; i >= 0; i--) {
+      const rule = this.rules[i];
+      if (rule.directoryOnly && !isDirectory) continue;
+      if (rule.regex.test(relativePath)) return !rule.negated;
+    }
+    return undefined;
+  }
+}
+
+function parseRule(line: string): IgnoreRule | null {
+  // Trailing spaces are ignored unless escaped
+  let pattern = line.replace(/(?<!\\)\s+$/, '');
+  if (pattern === '' || pattern.startsWith('#')) return null;
+
+  let negated = false;
+  if (pattern.startsWith('!')) {
+    negated = true;
+    pattern = pattern.slice(1);
+  } else if (pattern.startsWith('\\!') || pattern.startsWith('\\#')) {
+    pattern = pattern.slice(1);
+  }
+
+  let directoryOnly = false;
+  if (pattern.endsWith('/')) {
+    directoryOnly = true;
+    pattern = pattern.replace(/\/+$/, '');
+  }
+  if (pattern === '') return null;
+
+  // A slash anywhere but the end anchors the pattern to its directory
+  const anchored = pattern.includes('/');
+  pattern = pattern.replace(/^\/+/, '');
+
+  const body = globToRegex(pattern);
+  const regex = new RegExp(anchored ? `^${body}$` : `^(?:.*/)?${body}$`);
+  return { regex, negated, directoryOnly };
+}
+
+function globToRegex(glob: string): string {
+  let out = '';
+
+  for (let i = 0; i < glob.length; i++) {
+    const char = glob[i];
+
+    if (char === '*' && glob[i + 1] === '*') {
+      const atSegmentStart = i === 0 || glob[i - 1] === '/';
+      const next = glob[i + 2];
+      if (atSegmentStart && next === '/') {
+        out += '(?:.*/)?';             // `**/` matches zero or more directories
+        i += 2;
+        continue;
+      }
+      if (atSegmentStart && next === undefined) {
+        out += '.*';                   // trailing `/**` matches everything inside
+        i += 1;
+        continue;
+      }
+    }
+
+    if (char === '*') {
+      out += '[^/]*';
+    } else if (char === '?') {
+      out += '[^/]';
+    } else if (char === '[') {
+      const close = glob.indexOf(']', i + 2);
+      if (close === -1) {
+        out += '\\[';
+        continue;
+      }
+      let set = glob.slice(i + 1, close);
+      if (set.startsWith('!')) set = '^' + set.slice(1);
+      out += `[${set.replace(/\\/g, '\\\\')}]`;
+      i = close;
+    } else if (char === '\\' && i + 1 < glob.length) {
+      out += escapeRegex(glob[++i]);
+    } else {
+      out += escapeRegex(char);
+    }
+  }
+
+  return out;
+}
+
+function escapeRegex(text: string): string {
+  return text.replace(/[.*+?^${}()|[\]\\/]/g, '\\$&');
+}
@@ -0,0 +1,342 @@
+import * as fs from 'fs';
+import * as path from 'path';
+import { toPosixPath } from './Paths';
+import { compareStrings } from './Report';
+import { declarationModule, findManifest, packageName, RustCrate } from './RustCrate';
+import { tokenizeRust } from './RustLexer';
+import { parseExternCrates, parseUseDeclarations } from './RustUseTree';
+
+export const GRAPH_FORMATS = ['dot', 'graphml'] as const;
+export type GraphFormat = typeof GRAPH_FORMATS[number];
+
+/** `modules`: a node per module of each crate; `crates`: a node per crate */
+export const GRAPH_GRANULARITIES = ['modules', 'crates'] as const;
+export type GraphGranularity = typeof GRAPH_GRANULARITIES[number];
+
+export interface GraphNode {
+  /** Unique id: `demo::net::http` for modules of crate `demo`, the crate name for crates */
+  id: string;
+  /** `crate::net::http` for modules, the crate name for crates */
+  label: string;
+  /** Crate the node belongs to */
+  crate: string;
+  /** Crates outside the analyzed files, such as `std` or `serde` */
+  external: boolean;
+  /** File of a module node, relative to the working directory; inline modules have none */
+  file?: string;
+  /** Part of an import cycle */
+  cycle: boolean;
+}
+
+export interface GraphEdge {
+  from: string;
+  to: string;
+  /** Imported items, relative to the target node: `Client`, `de::Deserialize`, `*` for globs, `self` for the module itself */
+  items: string[];
+  /** Part of an import cycle */
+  cycle: boolean;
+}
+
+export interface ImportGraph {
+  nodes: GraphNode[];
+  edges: GraphEdge[];
+}
+
+export interface GraphOptions {
+  /** Root file of the crate, instead of the crates `Cargo.toml` implies (`--crate-root`) */
+  crateRoot?: string;
+  granularity?: GraphGranularity;
+  /**
+   * Keep only nodes whose label or id matches, and the edges between them;
+   * `*` matches anything, `::` included (`crate::services::*`)
+   */
+  filter?: string;
+  cwd?: string;
+}
+
+/** An import from one module: the module or crate it points at and the item path in it */
+interface ModuleImport {
+  from: GraphNode;
+  to: GraphNode;
+  /** Path of the item in the target crate, e.g. ['net', 'http', 'Client'] */
+  path: string[];
+  /** Length of the target module's path within `path` */
+  moduleLength: number;
+}
+
+/**
+ * The import graph of the Rust crates the given files belong to: a node per module
+ * (or per crate, with `granularity: 'crates'`) and an edge per importing module and
+ * imported module, labelled with the items imported. `use` items and `extern crate`
+ * items are edges; paths written in code are not. Imports of other crates point at a
+ * node for that crate, which is one of the analyzed crates when the name matches.
+ *
+ * Cycles are found before filtering, so an edge of a cycle that passes through nodes
+ * the filter hides is still marked.
+ */
+export function buildImportGraph(files: string[], options: GraphOptions = {}): ImportGraph {
+  const cwd = options.cwd ?? process.cwd();
+  const crates = new Map<string, RustCrate>();
+  for (const file of files) RustCrate.forFile(file, crates, options.crateRoot);
+
+  // One graph crate per distinct crate, named after its package
+  const named = [...new Set(crates.values())].filter(crate => crate.files.length > 0).map(crate => ({ crate, name: crateName(crate.files[0].filePath) }));
+  const byName = new Map(named.map(entry => [entry.name.replace(/-/g, '_'), entry.name]));
+
+  const nodes = new Map<string, GraphNode>();
+  const moduleNode = (crate: string, module: string[], file?: string): GraphNode => {
+    const id = [crate, ...module].join('::');
+    let node = nodes.get(id);
+    if (!node) {
+      node = { id, label: ['crate', ...module].join('::'), crate, external: false, cycle: false };
+      nodes.set(id, node);
+    }
+    if (file !== undefined && node.file === undefined) node.file = toPosixPath(path.relative(cwd, file));
+    return node;
+  };
+  const crateNode = (name: string): GraphNode => {
+    const internal = byName.get(name);
+    if (internal !== undefined) return moduleNode(internal, []);
+    let node = nodes.get(name);
+    if (!node) {
+      node = { id: name, label: name, crate: name, external: true, cycle: false };
+      nodes.set(name, node);
+    }
+    return node;
+  };
+
+  const imports: ModuleImport[] = [];
+  for (const { crate, name } of named) {
+    for (const file of crate.files) {
+      moduleNode(name, file.modulePath, file.filePath);
+      const tokens = tokenizeRust(file.content).filter(t => t.kind !== 'comment');
+
+      for (const declaration of parseUseDeclarations(tokens)) {
+        const module = declarationModule(file.modulePath, declaration);
+        const from = moduleNode(name, module);
+
+        for (const leaf of declaration.leaves) {
+          const leafPath = leaf.isSelf ? leaf.path.slice(0, -1) : leaf.path;
+          const written = leafPath[0] === '' ? leafPath.slice(1) : leafPath;
+          const resolved = leafPath[0] === '' ? undefined : crate.resolvePath(written, module);
+
+          if (resolved) {
+            let moduleLength = resolved.length;
+            while (moduleLength > 0 && !crate.isModule(resolved.slice(0, moduleLength))) moduleLength--;
+            imports.push({ from, to: moduleNode(name, resolved.slice(0, moduleLength)), path: resolved, moduleLength });
+          } else if (!['crate', 'self', 'super'].includes(written[0]) && written.length > 0) {
+            // `use serde::de::Deserialize;` or `use other_crate::x;`
+            const to = crateNode(written[0]);
+            imports.push({ from, to, path: written.slice(1), moduleLength: 0 });
+          }
+        }
+      }
+
+      for (const item of parseExternCrates(tokens)) {
+        if (item.name === 'self') continue;
+        imports.push({ from: moduleNode(name, file.modulePath), to: crateNode(item.name), path: [], moduleLength: 0 });
+      }
+    }
+  }
+
+  const collapse = options.granularity === 'crates';
+  const finalNodes = new Map<string, GraphNode>();
+  const nodeFor = (node: GraphNode): GraphNode => {
+    const target = collapse ? { id: node.crate, label: node.crate, crate: node.crate, external: node.external, cycle: false } : node;
+    const existing = finalNodes.get(target.id);
+    if (existing) return existing;
+    finalNodes.set(target.id, target);
+    return target;
+  };
+  for (const node of nodes.values()) nodeFor(node);
+
+  const edges = new Map<string, GraphEdge>();
+  for (const entry of imports) {
+    const from = nodeFor(entry.from);
+    const to = nodeFor(entry.to);
+    if (from === to) continue;
+
+    const itemPath = collapse ? entry.path : entry.path.slice(entry.moduleLength);
+    const item = itemPath.length === 0 ? 'self' : itemPath.join('::');
+    const key = `${from.id}\0${to.id}`;
+    const edge = edges.get(key) ?? { from: from.id, to: to.id, items: [], cycle: false };
+    if (!edge.items.includes(item)) edge.items.push(item);
+    edges.set(key, edge);
+  }
+
+  const graph: ImportGraph = {
+    nodes: [...finalNodes.values()].sort((a, b) => compareStrings(a.id, b.id)),
+    edges: [...edges.values()].sort((a, b) => compareStrings(a.from, b.from) || compareStrings(a.to, b.to)),
+  };
+  for (const edge of graph.edges) edge.items.sort(compareStrings);
+  markCycles(graph);
+
+  return options.filter !== undefined ? filterGraph(graph, options.filter) : graph;
+}
+
+/**
+ * Name of the crate rooted at `rootFile`: the package name for `src/lib.rs`, and for
+ * `src/main.rs` in packages without a library; other roots add their path, as in
+ * `demo(bin/tool)`. Files outside any package are named after themselves.
+ */
+export function crateName(rootFile: string): string {
+  const manifest = findManifest(rootFile);
+  if (!manifest) return path.basename(rootFile, '.rs');
+
+  const name = packageName(manifest);
+  const src = path.join(path.dirname(manifest), 'src');
+  const relative = toPosixPath(path.relative(src, rootFile));
+  if (relative === 'lib.rs' || (relative === 'main.rs' && !fs.existsSync(path.join(src, 'lib.rs')))) return name;
+  return `${name}(${relative.replace(/\.rs$/, '')})`;
+}
+
+/**
+ * Mark the nodes and edges of every strongly connected component with more
+ * than one node (Tarjan's algorithm)
+ */
+function markCycles(graph: ImportGraph): void {
+  const successors = new Map<string, string[]>(graph.nodes.map(node => [node.id, []]));
+  for (const edge of graph.edges) successors.get(edge.from)?.push(edge.to);
+
+  const index = new Map<string, number>();
+  const lowlink = new Map<string, number>();
+  const onStack = new Set<string>();
+  const stack: string[] = [];
+  const component = new Map<string, number>();
+  let counter = 0;
+  let components = 0;
+  const cyclic = new Set<number>();
+
+  const visit = (id: string) => {
+    index.set(id, counter);
+    lowlink.set(id, counter);
+    counter++;
+    stack.push(id);
+    onStack.add(id);
+
+    for (const next of successors.get(id) ?? []) {
+      if (!index.has(next)) {
+        visit(next);
+        lowlink.set(id, Math.min(lowlink.get(id)!, lowlink.get(next)!));
+      } else if (onStack.has(next)) {
+        lowlink.set(id, Math.min(lowlink.get(id)!, index.get(next)!));
+      }
+    }
+
+    if (lowlink.get(id) === index.get(id)) {
+      const members: string[] = [];
+      let member: string | undefined;
+      do {
+        member = stack.pop()!;
+        onStack.delete(member);
+        component.set(member, components);
+        members.push(member);
+      } while (member !== id);
+      if (members.length > 1) cyclic.add(components);
+      components++;
+    }
+  };
+
+  for (const node of graph.nodes) {
+    if (!index.has(node.id)) visit(node.id);
+  }
+
+  for (const node of graph.nodes) node.cycle = cyclic.has(component.get(node.id)!);
+  for (const edge of graph.edges) {
+    const group = component.get(edge.from);
+    edge.cycle = group !== undefined && group === component.get(edge.to) && cyclic.has(group);
+  }
+}
+
+function filterGraph(graph: ImportGraph, pattern: string): ImportGraph {
+  const regex = new RegExp(`^${pattern.split('*').map(part => part.replace(/[.+?^${}()|[\]\\]/g, '\\$&')).join('.*')}$`);
+  const nodes = graph.nodes.filter(node => regex.test(node.label) || regex.test(node.id));
+  const kept = new Set(nodes.map(node => node.id));
+  return { nodes, edges: graph.edges.filter(edge => kept.has(edge.from) && kept.has(edge.to)) };
+}
+
+/**
+ * Graphviz DOT: modules grouped in a cluster per crate, other crates dashed, and
+ * import cycles in red
+ */
+export function formatDot(graph: ImportGraph): string {
+  const lines = ['digraph imports {', '  rankdir=LR;', '  node [shape=box, fontname="Helvetica"];', '  edge [fontname="Helvetica", fontsize=10];'];
+  const nodeLine = (node: GraphNode, indent: string) => {
+    const attributes = [`label=${quoteDot(node.label)}`];
+    if (node.external) attributes.push('style=dashed');
+    if (node.cycle) attributes.push('color=red', 'fontcolor=red');
+    return `${indent}${quoteDot(node.id)} [${attributes.join(', ')}];`;
+  };
+
+  const clusters = new Map<string, GraphNode[]>();
+  for (const node of graph.nodes) {
+    // Crate nodes of a collapsed graph, labelled with their id, need no cluster
+    if (node.external || node.label === node.id) {
+      lines.push(nodeLine(node, '  '));
+      continue;
+    }
+    clusters.set(node.crate, [...(clusters.get(node.crate) ?? []), node]);
+  }
+
+  [...clusters].forEach(([crate, members], i) => {
+    lines.push(`  subgraph cluster_${i} {`, `    label=${quoteDot(crate)};`);
+    for (const node of members) lines.push(nodeLine(node, '    '));
+    lines.push('  }');
+  });
+
+  for (const edge of graph.edges) {
+    const attributes = [`label=${quoteDot(edge.items.join(', '))}`];
+    if (edge.cycle) attributes.push('color=red', 'fontcolor=red', 'penwidth=2');
+    lines.push(`  ${quoteDot(edge.from)} -> ${quoteDot(edge.to)} [${attributes.join(', ')}];`);
+  }
+
+  lines.push('}');
+  return lines.join('\n') + '\n';
+}
+
+/**
+ * GraphML, e.g. for yEd: labels, crates, files, imported items and cycle flags are
+ * node and edge data
+ */
+export function formatGraphML(graph: ImportGraph): string {
+  const ids = new Map(graph.nodes.map((node, i) => [node.id, `n${i}`]));
+  const data = (key: string, value: string | boolean) => `<data key="${key}">${escapeXml(String(value))}</data>`;
+
+  const lines = [
+    '<?xml version="1.0" encoding="UTF-8"?>',
+    '<graphml xmlns="http://graphml.graphdrawing.org/xmlns" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:schemaLocation="http://graphml.graphdrawing.org/xmlns http://graphml.graphdrawing.org/xmlns/1.0/graphml.xsd">',
+    '  <key id="label" for="node" attr.name="label" attr.type="string"/>',
+    '  <key id="crate" for="node" attr.name="crate" attr.type="string"/>',
+    '  <key id="file" for="node" attr.name="file" attr.type="string"/>',
+    '  <key id="external" for="node" attr.name="external" attr.type="boolean"/>',
+    '  <key id="nodeCycle" for="node" attr.name="cycle" attr.type="boolean"/>',
+    '  <key id="items" for="edge" attr.name="items" attr.type="string"/>',
+    '  <key id="edgeCycle" for="edge" attr.name="cycle" attr.type="boolean"/>',
+    '  <graph id="imports" edgedefault="directed">',
+  ];
+
+  for (const node of graph.nodes) {
+    const fields = [data('label', node.label), data('crate', node.crate), ...(node.file !== undefined ? [data('file', node.file)] : []),
+      data('external', node.external), data('nodeCycle', node.cycle)];
+    lines.push(`    <node id="${ids.get(node.id)}">${fields.join('')}</node>`);
+  }
+  graph.edges.forEach((edge, i) => {
+    lines.push(`    <edge id="e${i}" source="${ids.get(edge.from)}" target="${ids.get(edge.to)}">${data('items', edge.items.join(', '))}${data('edgeCycle', edge.cycle)}</edge>`);
+  });
+
+  lines.push('  </graph>', '</graphml>');
+  return lines.join('\n') + '\n';
+}
+
+function quoteDot(text: string): string {
+  return `"${text.replace(/\\/g, '\\\\').replace(/"/g, '\\"').replace(/\n/g, '\\n')}"`;
+}
+
+function escapeXml(text: string): string {
+  return text
+    .replace(/&/g, '&amp;')
+    .replace(/</g, '&lt;')
+    .replace(/>/g, '&gt;')
+    .replace(/"/g, '&quot;')
+    .replace(/'/g, '&apos;');
+}
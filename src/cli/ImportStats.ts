@@ -0,0 +1,208 @@
+import { AnalysisResult, FindingKind } from './CLIAnalyzer';
+import { crateName } from './ImportGraph';
+import { displayPath } from './Paths';
+import { compareStrings } from './Report';
+import { declarationModule, RustCrate } from './RustCrate';
+import { tokenizeRust } from './RustLexer';
+import { parseExternCrates, parseUseDeclarations } from './RustUseTree';
+import { toolVersion } from './Version';
+
+/** Bumped whenever a field is removed or changes meaning */
+export const STATS_SCHEMA_VERSION = '1.0';
+
+export const STATS_FORMATS = ['text', 'json'] as const;
+
+/** Findings that count as an unused import: each is an import that could be removed */
+const UNUSED_KINDS = new Set<FindingKind>(['unused-import', 'duplicate-import', 'redundant-import', 'shadowed-import', 'unused-reexport']);
+
+export interface ImportCounts {
+  files: number;
+  imports: number;
+  unused: number;
+  /** `unused` as a percentage of `imports`, to one decimal */
+  unusedPercent: number;
+}
+
+/**
+ * Import metrics of a set of files, as written by `stats --format=json`
+ */
+export interface ImportStats {
+  schemaVersion: string;
+  importlensVersion: string;
+  /** ISO 8601 time the numbers were taken */
+  generatedAt: string;
+  totals: ImportCounts & {
+    /** To two decimals */
+    averageImportsPerFile: number;
+  };
+  /** Every crate with analyzed files, by name */
+  crates: Array<ImportCounts & { name: string }>;
+  /** Crates outside the analyzed files (`std`, `serde`, ...), most imported first */
+  externalCrates: Array<{ name: string; imports: number; files: number }>;
+  /** Files with the most imports, most first */
+  topFiles: Array<{ file: string; crate: string; imports: number; unused: number }>;
+  /** How many `use` items import how many items: `use a::{b, c};` has size 2 */
+  groupSizes: Array<{ size: number; count: number }>;
+  /** Files that could not be analyzed, left out of the numbers */
+  errors: Array<{ file: string; message: string }>;
+}
+
+export interface StatsOptions {
+  /** Root file of the crate, instead of the crates `Cargo.toml` implies (`--crate-root`) */
+  crateRoot?: string;
+  /** Entries kept in `externalCrates` and `topFiles`; defaults to 10 */
+  limit?: number;
+  cwd?: string;
+  version?: string;
+  now?: Date;
+}
+
+/**
+ * Aggregate import metrics of analyzed Rust files. Imports are the items of `use`
+ * items (each leaf of a use tree, globs included) and `extern crate` items; unused
+ * imports are the findings of `results` that remove an import, so the numbers follow
+ * the same configuration, ignores and kind filters as `--check`.
+ */
+export function buildImportStats(results: AnalysisResult[], options: StatsOptions = {}): ImportStats {
+  const cwd = options.cwd ?? process.cwd();
+  const limit = options.limit ?? 10;
+  const crates = new Map<string, RustCrate>();
+
+  const analyzed = results.filter(result => result.language === 'rust' && result.error === undefined);
+  const owners = analyzed.map(result => RustCrate.forFile(result.filePath, crates, options.crateRoot));
+  const names = new Map([...new Set(owners)].map(crate => [crate, crateName(crate.files[0]?.filePath ?? '')]));
+  const workspace = new Set([...names.values()].map(name => name.replace(/-/g, '_')));
+
+  const files: ImportStats['topFiles'] = [];
+  const perCrate = new Map<string, ImportCounts>();
+  const external = new Map<string, { imports: number; files: Set<string> }>();
+  const groupSizes = new Map<number, number>();
+
+  analyzed.forEach((result, i) => {
+    const crate = owners[i];
+    const name = names.get(crate)!;
+    const file = crate.fileFor(result.filePath);
+    const modulePath = file?.modulePath ?? [];
+    const tokens = tokenizeRust(file?.content ?? '').filter(t => t.kind !== 'comment');
+    const importedCrates: string[] = [];
+
+    let imports = 0;
+    for (const declaration of parseUseDeclarations(tokens)) {
+      const module = declarationModule(modulePath, declaration);
+      imports += declaration.leaves.length;
+      groupSizes.set(declaration.leaves.length, (groupSizes.get(declaration.leaves.length) ?? 0) + 1);
+
+      for (const leaf of declaration.leaves) {
+        const leafPath = leaf.isSelf ? leaf.path.slice(0, -1) : leaf.path;
+        const written = leafPath[0] === '' ? leafPath.slice(1) : leafPath;
+        if (written.length === 0 || ['crate', 'self', 'super'].includes(written[0])) continue;
+        if (leafPath[0] === '' || !crate.resolvePath(written, module)) importedCrates.push(written[0]);
+      }
+    }
+    for (const item of parseExternCrates(tokens)) {
+      imports++;
+      if (item.name !== 'self') importedCrates.push(item.name);
+    }
+
+    const displayed = displayPath(result.filePath, cwd);
+    for (const imported of importedCrates) {
+      if (workspace.has(imported)) continue;
+      const entry = external.get(imported) ?? { imports: 0, files: new Set<string>() };
+      entry.imports++;
+      entry.files.add(displayed);
+      external.set(imported, entry);
+    }
+
+    const unused = result.unusedImports.filter(u => UNUSED_KINDS.has(u.kind ?? 'unused-import')).length;
+    files.push({ file: displayed, crate: name, imports, unused });
+
+    const counts = perCrate.get(name) ?? { files: 0, imports: 0, unused: 0, unusedPercent: 0 };
+    counts.files++;
+    counts.imports += imports;
+    counts.unused += unused;
+    perCrate.set(name, counts);
+  });
+
+  const imports = files.reduce((sum, file) => sum + file.imports, 0);
+  const unused = files.reduce((sum, file) => sum + file.unused, 0);
+
+  return {
+    schemaVersion: STATS_SCHEMA_VERSION,
+    importlensVersion: options.version ?? toolVersion(),
+    generatedAt: (options.now ?? new Date()).toISOString(),
+    totals: {
+      files: files.length,
+      imports,
+      unused,
+      unusedPercent: percent(unused, imports),
+      averageImportsPerFile: files.length === 0 ? 0 : Math.round(imports / files.length * 100) / 100,
+    },
+    crates: [...perCrate.entries()]
+      .sort(([a], [b]) => compareStrings(a, b))
+      .map(([name, counts]) => ({ name, ...counts, unusedPercent: percent(counts.unused, counts.imports) })),
+    externalCrates: [...external.entries()]
+      .map(([name, entry]) => ({ name, imports: entry.imports, files: entry.files.size }))
+      .sort((a, b) => b.imports - a.imports || compareStrings(a.name, b.name))
+      .slice(0, limit),
+    topFiles: files
+      .filter(file => file.imports > 0)
+      .sort((a, b) => b.imports - a.imports || compareStrings(a.file, b.file))
+      .slice(0, limit),
+    groupSizes: [...groupSizes.entries()].sort(([a], [b]) => a - b).map(([size, count]) => ({ size, count })),
+    errors: results
+      .filter(result => result.language === 'rust' && result.error !== undefined)
+      .map(result => ({ file: displayPath(result.filePath, cwd), message: result.error! })),
+  };
+}
+
+/**
+ * The stats as text tables, for reading in a terminal
+ */
+export function formatStatsText(stats: ImportStats): string {
+  const { totals } = stats;
+  const lines = [
+    `Import statistics (importlens ${stats.importlensVersion})`,
+    '',
+    `  Files:              ${totals.files}`,
+    `  Imports:            ${totals.imports}`,
+    `  Unused:             ${totals.unused} (${totals.unusedPercent}%)`,
+    `  Imports per file:   ${totals.averageImportsPerFile}`,
+  ];
+
+  const section = (title: string, header: string[], rows: string[][]) => {
+    if (rows.length === 0) return;
+    lines.push('', `${title}:`, ...table([header, ...rows]));
+  };
+
+  section('Crates', ['CRATE', 'FILES', 'IMPORTS', 'UNUSED'],
+    stats.crates.map(c => [c.name, `${c.files}`, `${c.imports}`, `${c.unused} (${c.unusedPercent}%)`]));
+  section('Most imported external crates', ['CRATE', 'IMPORTS', 'FILES'],
+    stats.externalCrates.map(c => [c.name, `${c.imports}`, `${c.files}`]));
+  section('Files with the most imports', ['FILE', 'IMPORTS', 'UNUSED'],
+    stats.topFiles.map(f => [f.file, `${f.imports}`, `${f.unused}`]));
+
+  if (stats.groupSizes.length > 0) {
+    // Bars scaled to the most common size
+    const most = Math.max(...stats.groupSizes.map(g => g.count));
+    const width = Math.max(...stats.groupSizes.map(g => `${g.size}`.length));
+    lines.push('', 'Import group sizes (items per use item):');
+    for (const group of stats.groupSizes) {
+      const bar = '#'.repeat(Math.max(1, Math.round(group.count / most * 40)));
+      lines.push(`  ${`${group.size}`.padStart(width)} | ${bar} ${group.count}`);
+    }
+  }
+
+  for (const error of stats.errors) lines.push('', `[WARNING] ${error.file}: not counted, ${error.message}`);
+
+  return lines.join('\n') + '\n';
+}
+
+// Columns padded to their widest cell; numbers right-aligned
+function table(rows: string[][]): string[] {
+  const widths = rows[0].map((_, k) => Math.max(...rows.map(row => row[k].length)));
+  return rows.map(row => '  ' + row.map((cell, k) => k === 0 ? cell.padEnd(widths[k]) : cell.padStart(widths[k])).join('  ').trimEnd());
+}
+
+function percent(part: number, whole: number): number {
+  return whole === 0 ? 0 : Math.round(part / whole * 1000) / 10;
+}
@@ -0,0 +1,218 @@
+import * as fs from 'fs';
+import * as readline from 'readline';
+import { AnalysisResult, FindingKind, UnusedImport } from './CLIAnalyzer';
+import { BOM, LineIndex } from './LineIndex';
+import { displayPath } from './Paths';
+
+/** Lines of context shown above and below each finding */
+const CONTEXT_LINES = 2;
+
+const HIGHLIGHT = '\x1b[7m';
+const RESET = '\x1b[0m';
+
+// Take no room in a terminal: combining marks, zero-width spaces and joiners, variation selectors
+const ZERO_WIDTH = /[\p{Mn}\p{Me}\u200B-\u200F\u2060\uFE00-\uFE0F]/u;
+const EMOJI = /\p{Emoji_Presentation}/u;
+
+const HELP = [
+  'y - remove this import',
+  'n - keep this import',
+  'a - remove this import and the remaining ones in this file',
+  'k - remove this import and every later one of the same kind',
+  's - keep this import and skip the rest of this file',
+  'q - keep this import and stop; imports already accepted are still removed',
+  '? - print help',
+].join('\n');
+
+/**
+ * Where `--fix --interactive` asks its questions
+ */
+export interface PromptIO {
+  /** Print `question` and resolve with the line typed, or undefined at end of input */
+  ask(question: string): Promise<string | undefined>;
+  write(text: string): void;
+}
+
+/**
+ * `--fix --interactive`: walk through the fixable findings one at a time, like
+ * `git add -p`, showing the source around each with the import highlighted. The
+ * answers only choose findings; the accepted ones of a file are still removed in one
+ * pass, so later removals land where they should.
+ */
+export class InteractiveReview {
+  /** Kinds answered with `k`, accepted without asking for the rest of the session */
+  private acceptedKinds = new Set<FindingKind>();
+  private stopped = false;
+
+  /**
+   * @param color Highlight the import with ANSI inverse video instead of a `^^^` line
+   */
+  constructor(private io: PromptIO, private color = false) {}
+
+  /**
+   * Ask about every finding `canFix` accepts and return the results with only the
+   * accepted findings left. Findings that can't be fixed are dropped without asking,
+   * since `--fix` would leave them anyway.
+   */
+  async review(results: AnalysisResult[], canFix: (result: AnalysisResult, finding: UnusedImport) => boolean): Promise<AnalysisResult[]> {
+    const reviewed: AnalysisResult[] = [];
+    const total = results.reduce((count, result) => count + result.unusedImports.filter(finding => canFix(result, finding)).length, 0);
+    let position = 0;
+
+    for (const result of results) {
+      const findings = result.unusedImports
+        .filter(finding => canFix(result, finding))
+        .sort((a, b) => (a.span?.start ?? 0) - (b.span?.start ?? 0));
+      const accepted: UnusedImport[] = [];
+      let content: string | undefined;
+      let acceptFile = false;
+      let skipFile = false;
+
+      for (const finding of findings) {
+        position++;
+        const kind = finding.kind ?? 'unused-import';
+        if (this.stopped || skipFile) continue;
+        if (acceptFile || this.acceptedKinds.has(kind)) {
+          accepted.push(finding);
+          continue;
+        }
+
+        content ??= readContent(result.filePath);
+        this.io.write(`\n${displayPath(result.filePath)}:${finding.line}: ${finding.reason} [${kind}]\n`);
+        if (content !== undefined) this.io.write(this.excerpt(content, finding));
+
+        const action = kind === 'over-qualified-path' ? 'Import this path' : 'Remove this import';
+        const answer = await this.ask(`(${position}/${total}) ${action} [y,n,a,k,s,q,?]? `);
+        if (answer === 'y' || answer === 'a' || answer === 'k') accepted.push(finding);
+        if (answer === 'a') acceptFile = true;
+        if (answer === 'k') this.acceptedKinds.add(kind);
+        if (answer === 's') skipFile = true;
+        if (answer === 'q') this.stopped = true;
+      }
+
+      reviewed.push({ ...result, unusedImports: accepted });
+    }
+
+    return reviewed;
+  }
+
+  /** Ask until the answer is one of the keys; the end of input counts as `q` */
+  private async ask(question: string): Promise<string> {
+    for (;;) {
+      const line = await this.io.ask(question);
+      if (line === undefined) return 'q';
+      const answer = line.trim().toLowerCase();
+      if (['y', 'n', 'a', 'k', 's', 'q'].includes(answer)) return answer;
+      this.io.write(`${HELP}\n`);
+    }
+  }
+
+  /** The lines around a finding, numbered, with its span highlighted */
+  private excerpt(content: string, finding: UnusedImport): string {
+    const index = new LineIndex(content);
+    const lines = content.split('\n');
+    const span = finding.span;
+    const first = span ? index.positionAt(span.start).line : finding.line;
+    const last = span ? index.positionAt(Math.max(span.start, span.end - 1)).line : finding.line;
+    const width = String(Math.min(lines.length, last + CONTEXT_LINES)).length;
+    const output: string[] = [];
+
+    let offset = lines.slice(0, Math.max(0, first - CONTEXT_LINES - 1)).reduce((sum, line) => sum + line.length + 1, 0);
+    for (let line = Math.max(1, first - CONTEXT_LINES); line <= Math.min(lines.length, last + CONTEXT_LINES); line++) {
+      // A BOM prints as nothing and would shift the carets
+      const bom = line === 1 && content.startsWith(BOM) ? 1 : 0;
+      const text = lines[line - 1].slice(bom).replace(/\r$/, '');
+      const start = offset + bom;
+      const current = line >= first && line <= last;
+      const gutter = `${current ? '>' : ' '} ${String(line).padStart(width)} | `;
+      const markStart = span ? Math.max(span.start, start) - start : 0;
+      const markEnd = span ? Math.min(span.end, start + text.length) - start : text.length;
+
+      if (current && markEnd > markStart && this.color) {
+        output.push(`${gutter}${text.slice(0, markStart)}${HIGHLIGHT}${text.slice(markStart, markEnd)}${RESET}${text.slice(markEnd)}`);
+      } else {
+        output.push(`${gutter}${text}`);
+        if (current && markEnd > markStart) {
+          output.push(`${' '.repeat(gutter.length)}${padding(text.slice(0, markStart))}${'^'.repeat(displayWidth(text.slice(markStart, markEnd)))}`);
+        }
+      }
+      offset += lines[line - 1].length + 1;
+    }
+
+    return output.join('\n') + '\n';
+  }
+}
+
+/**
+ * Prompts on the terminal: questions on stderr, so `--dry-run` diffs on stdout stay clean
+ */
+export function terminalPrompt(): PromptIO & { close(): void } {
+  const rl = readline.createInterface({ input: process.stdin, output: process.stderr });
+  // Lines typed ahead of their question are kept for it, as in `git add -p`
+  const lines: string[] = [];
+  let waiting: ((line: string | undefined) => void) | undefined;
+  let closed = false;
+
+  rl.on('line', line => {
+    if (waiting) {
+      const resolve = waiting;
+      waiting = undefined;
+      resolve(line);
+    } else {
+      lines.push(line);
+    }
+  });
+  rl.on('close', () => {
+    closed = true;
+    waiting?.(undefined);
+    waiting = undefined;
+  });
+
+  return {
+    ask: question => {
+      if (lines.length > 0) {
+        process.stderr.write(`${question}${lines[0]}\n`);
+        return Promise.resolve(lines.shift());
+      }
+      if (closed) return Promise.resolve(undefined);
+      rl.setPrompt(question);
+      rl.prompt();
+      return new Promise(resolve => { waiting = resolve; });
+    },
+    write: text => { process.stderr.write(text); },
+    close: () => rl.close(),
+  };
+}
+
+function readContent(filePath: string): string | undefined {
+  try {
+    return fs.readFileSync(filePath, 'utf-8');
+  } catch {
+    return undefined;
+  }
+}
+
+/**
+ * Columns `text` takes up in a terminal: two for wide East Asian characters and
+ * emoji, none for combining marks, so carets line up under the highlighted import
+ */
+function displayWidth(text: string): number {
+  let width = 0;
+  for (const char of text) {
+    if (ZERO_WIDTH.test(char)) continue;
+    width += EMOJI.test(char) || isWide(char.codePointAt(0)!) ? 2 : 1;
+  }
+  return width;
+}
+
+/** Blanks as wide as `text`, keeping its tabs so they stop where the text's do */
+function padding(text: string): string {
+  return [...text].map(char => (char === '\t' ? char : ' '.repeat(displayWidth(char)))).join('');
+}
+
+// East Asian Wide and Fullwidth ranges: Hangul, CJK, kana, fullwidth forms
+function isWide(code: number): boolean {
+  return (code >= 0x1100 && code <= 0x115f) || (code >= 0x2e80 && code <= 0xa4cf && code !== 0x303f) ||
+    (code >= 0xac00 && code <= 0xd7a3) || (code >= 0xf900 && code <= 0xfaff) || (code >= 0xfe30 && code <= 0xfe4f) ||
+    (code >= 0xff00 && code <= 0xff60) || (code >= 0xffe0 && code <= 0xffe6) || (code >= 0x20000 && code <= 0x3fffd);
+}
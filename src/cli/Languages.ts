@@ -0,0 +1,71 @@
+import * as path from 'path';
+
+/**
+ * Language identifiers by file extension
+ */
+export const EXTENSION_LANGUAGES: Record<string, string> = {
+  '.ts': 'typescript',
+  '.tsx': 'typescriptreact',
+  '.js': 'javascript',
+  '.jsx': 'javascriptreact',
+  '.mjs': 'javascript',
+  '.cjs': 'javascript',
+  '.py': 'python',
+  '.java': 'java',
+  '.go': 'go',
+  '.rs': 'rust',
+  '.cpp': 'cpp',
+  '.cc': 'cpp',
+  '.cxx': 'cpp',
+  '.c': 'c',
+  '.h': 'c',
+  '.hpp': 'cpp',
+};
+
+/** Values accepted by `--language` */
+export const LANGUAGE_NAMES = ['typescript', 'javascript', 'python', 'java', 'go', 'rust', 'cpp', 'c'];
+
+const LANGUAGE_ALIASES: Record<string, string> = {
+  ts: 'typescript',
+  js: 'javascript',
+  py: 'python',
+  rs: 'rust',
+  'c++': 'cpp',
+};
+
+/**
+ * Detect the language of a file from its extension
+ */
+export function detectLanguage(filePath: string): string {
+  return EXTENSION_LANGUAGES[path.extname(filePath).toLowerCase()] || 'unknown';
+}
+
+/**
+ * Resolve a `--language` value (name or alias) to a language identifier, or null if unknown
+ */
+export function normalizeLanguage(name: string): string | null {
+  const lower = name.toLowerCase();
+  const language = LANGUAGE_ALIASES[lower] ?? lower;
+  return LANGUAGE_NAMES.includes(language) ? language : null;
+}
+
+/**
+ * The language a file is analyzed as. A forced language wins over the extension,
+ * except that `.tsx`/`.jsx` files keep their JSX flavour under `typescript`/`javascript`.
+ */
+export function resolveLanguage(filePath: string, forced?: string): string {
+  const detected = detectLanguage(filePath);
+  if (!forced) return detected;
+  return languageFamily(detected) === forced ? detected : forced;
+}
+
+/**
+ * File extensions that belong to a language
+ */
+export function extensionsFor(language: string): string[] {
+  return Object.keys(EXTENSION_LANGUAGES).filter(ext => languageFamily(EXTENSION_LANGUAGES[ext]) === language);
+}
+
+function languageFamily(language: string): string {
+  return language.replace(/react$/, '');
+}
@@ -0,0 +1,167 @@
+/** Byte order mark, which some editors on Windows write at the start of files */
+export const BOM = '\uFEFF';
+
+/**
+ * Maps source offsets to 1-based line/column positions.
+ *
+ * Lines end at `\n`, so a `\r\n` terminator counts once and its `\r` is the last
+ * character of its line. Columns are in UTF-8 bytes, not counting a leading BOM,
+ * like rustc and gopls report them; `utf16PositionAt` gives the UTF-16 code unit
+ * columns LSP and SARIF use.
+ */
+export class LineIndex {
+  private readonly lineStarts: number[] = [0];
+  private lineByteStarts?: number[];
+  // Whether every character is ASCII, so UTF-8 byte and string offsets are the same
+  private asciiOnly?: boolean;
+
+  constructor(private readonly content: string) {
+    for (let i = content.indexOf('\n'); i !== -1; i = content.indexOf('\n', i + 1)) {
+      this.lineStarts.push(i + 1);
+    }
+  }
+
+  /**
+   * Convert an offset into a 1-based line and a 1-based UTF-8 byte column
+   */
+  positionAt(offset: number): { line: number; column: number } {
+    const line = this.lineOf(offset);
+    const start = this.columnStart(line);
+    return { line: line + 1, column: this.byteLength(start, Math.max(start, offset)) + 1 };
+  }
+
+  /**
+   * Convert an offset into a 1-based line and a 1-based UTF-16 code unit column,
+   * counting every character of the text, a BOM included
+   */
+  utf16PositionAt(offset: number): { line: number; column: number } {
+    const line = this.lineOf(offset);
+    return { line: line + 1, column: offset - this.lineStarts[line] + 1 };
+  }
+
+  /**
+   * Convert a 1-based line and UTF-8 byte column, as `positionAt` returns them,
+   * back into an offset
+   */
+  offsetAt(line: number, column: number): number {
+    const index = Math.min(Math.max(line - 1, 0), this.lineStarts.length - 1);
+    const end = index + 1 < this.lineStarts.length ? this.lineStarts[index + 1] : this.content.length;
+    const offset = this.offsetAtByte(this.byteOffsetAt(this.columnStart(index)) + column - 1);
+    return Math.min(offset, end);
+  }
+
+  /**
+   * Convert a 1-based line and UTF-16 code unit column, as `utf16PositionAt`
+   * returns them, back into an offset
+   */
+  offsetAtUtf16(line: number, column: number): number {
+    const index = Math.min(Math.max(line - 1, 0), this.lineStarts.length - 1);
+    return Math.min(this.lineStarts[index] + column - 1, this.content.length);
+  }
+
+  /**
+   * Convert a string (UTF-16) offset into a UTF-8 byte offset
+   */
+  byteOffsetAt(offset: number): number {
+    const byteStarts = this.byteStarts();
+    const line = this.lineOf(offset);
+    return byteStarts[line] + this.byteLength(this.lineStarts[line], offset);
+  }
+
+  /**
+   * Convert a UTF-8 byte offset back into a string (UTF-16) offset
+   */
+  offsetAtByte(byteOffset: number): number {
+    if (this.isAscii()) return Math.min(Math.max(byteOffset, 0), this.content.length);
+    const byteStarts = this.byteStarts();
+    const line = findLine(byteStarts, byteOffset);
+
+    let offset = this.lineStarts[line];
+    let bytes = byteStarts[line];
+    while (bytes < byteOffset && offset < this.content.length) {
+      const code = this.content.codePointAt(offset)!;
+      bytes += code < 0x80 ? 1 : code < 0x800 ? 2 : code < 0x10000 ? 3 : 4;
+      offset += code >= 0x10000 ? 2 : 1;
+    }
+    return offset;
+  }
+
+  /** UTF-8 byte offset of every line start, computed on first use */
+  private byteStarts(): number[] {
+    if (!this.lineByteStarts) {
+      this.lineByteStarts = [0];
+      for (let i = 1; i < this.lineStarts.length; i++) {
+        this.lineByteStarts.push(this.lineByteStarts[i - 1] + this.byteLength(this.lineStarts[i - 1], this.lineStarts[i]));
+      }
+    }
+    return this.lineByteStarts;
+  }
+
+  /** UTF-8 length of the text from `start` to `end`; long generated lines make this worth a fast path */
+  private byteLength(start: number, end: number): number {
+    return this.isAscii() ? end - start : utf8Length(this.content, start, end);
+  }
+
+  private isAscii(): boolean {
+    this.asciiOnly ??= !/[^\x00-\x7f]/.test(this.content);
+    return this.asciiOnly;
+  }
+
+  /** Offset at which columns of the 0-based `line` start counting: past a leading BOM */
+  private columnStart(line: number): number {
+    return line === 0 && this.content.startsWith(BOM) ? 1 : this.lineStarts[line];
+  }
+
+  /** 0-based index of the line containing offset */
+  private lineOf(offset: number): number {
+    return findLine(this.lineStarts, offset);
+  }
+}
+
+/**
+ * Offset at which the line containing `offset` starts, past a BOM on the first
+ * line, so edits from there leave the BOM in place
+ */
+export function startOfLine(content: string, offset: number): number {
+  const start = content.lastIndexOf('\n', offset - 1) + 1;
+  return start === 0 && offset > 0 && content.startsWith(BOM) ? 1 : start;
+}
+
+/**
+ * UTF-8 length of `content.slice(start, end)`, counted without copying it out. A
+ * lone surrogate counts as the 3 bytes of the U+FFFD it is encoded as.
+ */
+function utf8Length(content: string, start: number, end: number): number {
+  let bytes = 0;
+  for (let i = start; i < end; i++) {
+    const code = content.charCodeAt(i);
+    if (code < 0x80) {
+      bytes += 1;
+    } else if (code < 0x800) {
+      bytes += 2;
+    } else if (code >= 0xd800 && code <= 0xdbff && i + 1 < end && (content.charCodeAt(i + 1) & 0xfc00) === 0xdc00) {
+      bytes += 4;
+      i++;
+    } else {
+      bytes += 3;
+    }
+  }
+  return bytes;
+}
+
+/** Index of the last line start at or before offset */
+function findLine(starts: number[], offset: number): number {
+  let low = 0;
+  let high = starts.length - 1;
+
+  while (low < high) {
+    const mid = (low + high + 1) >> 1;
+    if (starts[mid] <= offset) {
+      low = mid;
+    } else {
+      high = mid - 1;
+    }
+  }
+
+  return low;
+}
@@ -0,0 +1,320 @@
+import { fileURLToPath } from 'url';
+import { CLIArguments } from './ArgumentParser';
+import { AnalysisResult, CLIAnalyzer, FindingKind, FindingSeverity, UnusedImport } from './CLIAnalyzer';
+import { LineIndex } from './LineIndex';
+import { hasBalancedDelimiters, tokenizeRust } from './RustLexer';
+import { toolVersion } from './Version';
+
+/** JSON-RPC request, response or notification */
+export interface LspMessage {
+  jsonrpc: '2.0';
+  id?: number | string | null;
+  method?: string;
+  params?: any;
+  result?: unknown;
+  error?: { code: number; message: string };
+}
+
+export interface Position {
+  /** 0-based */
+  line: number;
+  /** 0-based, in UTF-16 code units */
+  character: number;
+}
+
+export interface Range {
+  start: Position;
+  end: Position;
+}
+
+export interface Diagnostic {
+  range: Range;
+  severity: number;
+  code: FindingKind;
+  source: 'importlens';
+  message: string;
+  tags?: number[];
+}
+
+// https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/
+const ErrorCodes = {
+  InvalidRequest: -32600,
+  MethodNotFound: -32601,
+  InternalError: -32603,
+  ServerNotInitialized: -32002,
+};
+
+const DiagnosticSeverity = { Error: 1, Warning: 2, Information: 3 };
+const DiagnosticTag = { Unnecessary: 1 };
+const DIAGNOSTIC_SEVERITIES: Record<FindingSeverity, number> = {
+  error: DiagnosticSeverity.Error,
+  warning: DiagnosticSeverity.Warning,
+  info: DiagnosticSeverity.Information,
+};
+const TextDocumentSyncKind = { Full: 1 };
+
+// Kinds whose import can simply go, which editors render faded out
+const UNNECESSARY_KINDS = new Set<FindingKind>(['unused-import', 'duplicate-import', 'redundant-import', 'useless-suppression', 'unused-reexport']);
+
+interface OpenDocument {
+  uri: string;
+  filePath: string;
+  text: string;
+  /** Analysis of the last text that parsed, and that text */
+  analyzed?: { text: string; result: AnalysisResult };
+}
+
+/**
+ * `importlens-cli lsp`: a minimal Language Server Protocol server over stdio.
+ *
+ * Open documents are analyzed from their in-memory text on every change and the
+ * findings are published as diagnostics; findings with a suggested fix get a
+ * quick fix code action. When the current text fails to parse (the analyzer
+ * complains, or Rust brackets don't balance yet), the last good diagnostics are
+ * kept rather than replaced.
+ */
+export class LspServer {
+  private analyzer: CLIAnalyzer;
+  private documents = new Map<string, OpenDocument>();
+  private buffer = Buffer.alloc(0);
+  private initialized = false;
+  private shuttingDown = false;
+
+  constructor(
+    args: CLIArguments,
+    private output: NodeJS.WritableStream,
+    private onExit: (code: number) => void = code => process.exit(code)
+  ) {
+    this.analyzer = new CLIAnalyzer(args);
+  }
+
+  listen(input: NodeJS.ReadableStream): void {
+    input.on('data', (chunk: Buffer) => this.receive(chunk));
+    input.on('end', () => this.onExit(this.shuttingDown ? 0 : 1));
+  }
+
+  /**
+   * Feed raw bytes from the client; complete messages are handled as they arrive
+   */
+  receive(chunk: Buffer): void {
+    this.buffer = Buffer.concat([this.buffer, chunk]);
+
+    for (;;) {
+      const headerEnd = this.buffer.indexOf('\r\n\r\n');
+      if (headerEnd === -1) return;
+
+      const header = this.buffer.subarray(0, headerEnd).toString('ascii');
+      const length = /Content-Length: *(\d+)/i.exec(header);
+      if (!length) {
+        // Unrecoverable framing; drop what we have and wait for the next header
+        this.buffer = this.buffer.subarray(headerEnd + 4);
+        continue;
+      }
+
+      const bodyStart = headerEnd + 4;
+      const bodyEnd = bodyStart + Number(length[1]);
+      if (this.buffer.length < bodyEnd) return;
+
+      const body = this.buffer.subarray(bodyStart, bodyEnd).toString('utf8');
+      this.buffer = this.buffer.subarray(bodyEnd);
+
+      let message: LspMessage;
+      try {
+        message = JSON.parse(body);
+      } catch {
+        continue;
+      }
+      this.handle(message);
+    }
+  }
+
+  private handle(message: LspMessage): void {
+    const { id, method, params } = message;
+    if (!method) return;
+    const isRequest = id !== undefined && id !== null;
+
+    if (!this.initialized && method !== 'initialize' && method !== 'exit') {
+      if (isRequest) this.respondError(id, ErrorCodes.ServerNotInitialized, 'Server not initialized');
+      return;
+    }
+    if (this.shuttingDown && method !== 'exit') {
+      if (isRequest) this.respondError(id, ErrorCodes.InvalidRequest, 'Server is shutting down');
+      return;
+    }
+
+    try {
+      switch (method) {
+        case 'initialize':
+          this.initialized = true;
+          this.respond(id, {
+            capabilities: {
+              textDocumentSync: { openClose: true, change: TextDocumentSyncKind.Full },
+              codeActionProvider: { codeActionKinds: ['quickfix'] },
+            },
+            serverInfo: { name: 'importlens', version: toolVersion() },
+          });
+          break;
+
+        case 'shutdown':
+          this.shuttingDown = true;
+          this.respond(id, null);
+          break;
+
+        case 'exit':
+          this.onExit(this.shuttingDown ? 0 : 1);
+          break;
+
+        case 'textDocument/didOpen': {
+          const { uri, text } = params.textDocument;
+          this.documents.set(uri, { uri, filePath: toFilePath(uri), text });
+          this.analyze(uri);
+          break;
+        }
+
+        case 'textDocument/didChange': {
+          const document = this.documents.get(params.textDocument.uri);
+          // Full sync: the last change carries the whole text
+          const changes = params.contentChanges as Array<{ text: string }>;
+          if (document && changes.length > 0) {
+            document.text = changes[changes.length - 1].text;
+            this.analyze(document.uri);
+          }
+          break;
+        }
+
+        case 'textDocument/didClose':
+          this.documents.delete(params.textDocument.uri);
+          this.publish(params.textDocument.uri, []);
+          break;
+
+        case 'textDocument/codeAction':
+          this.respond(id, this.codeActions(params.textDocument.uri, params.range));
+          break;
+
+        default:
+          if (isRequest) this.respondError(id, ErrorCodes.MethodNotFound, `Unhandled method ${method}`);
+      }
+    } catch (error) {
+      if (isRequest) this.respondError(id, ErrorCodes.InternalError, error instanceof Error ? error.message : String(error));
+    }
+  }
+
+  private analyze(uri: string): void {
+    const document = this.documents.get(uri)!;
+    const result = this.analyzer.analyzeContent(document.filePath, document.text);
+
+    // Half-typed code: keep showing what was found before rather than parse noise
+    if (result.error || result.diagnostics || !parses(result.language, document.text)) return;
+
+    document.analyzed = { text: document.text, result };
+    this.publish(uri, this.diagnostics(document));
+  }
+
+  private diagnostics(document: OpenDocument): Diagnostic[] {
+    if (!document.analyzed) return [];
+    const { text, result } = document.analyzed;
+    const index = new LineIndex(text);
+    return result.unusedImports.map(unused => toDiagnostic(unused, text, index));
+  }
+
+  /**
+   * Quick fixes for the findings overlapping `range`. Only offered while the
+   * diagnostics match the current text, since the edits are computed against it.
+   */
+  private codeActions(uri: string, range: Range): unknown[] {
+    const document = this.documents.get(uri);
+    if (!document?.analyzed || document.analyzed.text !== document.text) return [];
+
+    const { text, result } = document.analyzed;
+    const index = new LineIndex(text);
+
+    return result.unusedImports.flatMap(unused => {
+      const diagnostic = toDiagnostic(unused, text, index);
+      if (!unused.suggestedFix || !overlaps(diagnostic.range, range)) return [];
+
+      const edits = unused.suggestedFix.edits.map(edit => ({
+        range: {
+          start: toPosition(index, index.offsetAtByte(edit.start)),
+          end: toPosition(index, index.offsetAtByte(edit.end)),
+        },
+        newText: edit.newText,
+      }));
+
+      return [{
+        title: unused.suggestedFix.description,
+        kind: 'quickfix',
+        diagnostics: [diagnostic],
+        isPreferred: true,
+        edit: { changes: { [uri]: edits } },
+      }];
+    });
+  }
+
+  private publish(uri: string, diagnostics: Diagnostic[]): void {
+    this.send({ jsonrpc: '2.0', method: 'textDocument/publishDiagnostics', params: { uri, diagnostics } });
+  }
+
+  private respond(id: LspMessage['id'], result: unknown): void {
+    this.send({ jsonrpc: '2.0', id, result });
+  }
+
+  private respondError(id: LspMessage['id'], code: number, message: string): void {
+    this.send({ jsonrpc: '2.0', id, error: { code, message } });
+  }
+
+  private send(message: LspMessage): void {
+    const body = JSON.stringify(message);
+    this.output.write(`Content-Length: ${Buffer.byteLength(body, 'utf8')}\r\n\r\n${body}`);
+  }
+}
+
+function toDiagnostic(unused: UnusedImport, text: string, index: LineIndex): Diagnostic {
+  const kind = unused.kind ?? 'unused-import';
+  const diagnostic: Diagnostic = {
+    range: unused.span
+      ? { start: toPosition(index, unused.span.start), end: toPosition(index, unused.span.end) }
+      : lineRange(text, index, unused.line, unused.column),
+    severity: DIAGNOSTIC_SEVERITIES[unused.severity ?? 'error'],
+    code: kind,
+    source: 'importlens',
+    message: unused.reason,
+  };
+  if (UNNECESSARY_KINDS.has(kind)) diagnostic.tags = [DiagnosticTag.Unnecessary];
+  return diagnostic;
+}
+
+/** LSP positions count UTF-16 code units, the protocol's default encoding */
+function toPosition(index: LineIndex, offset: number): Position {
+  const { line, column } = index.utf16PositionAt(offset);
+  return { line: line - 1, character: column - 1 };
+}
+
+/** From the byte column (or the start of the line) to the end of the 1-based `line` */
+function lineRange(text: string, index: LineIndex, line: number, column?: number): Range {
+  const content = (text.split('\n')[line - 1] ?? '').replace(/\r$/, '');
+  return {
+    start: column !== undefined ? toPosition(index, index.offsetAt(line, column)) : { line: line - 1, character: 0 },
+    end: { line: line - 1, character: content.length },
+  };
+}
+
+function overlaps(a: Range, b: Range): boolean {
+  return !(before(a.end, b.start) || before(b.end, a.start));
+}
+
+function before(a: Position, b: Position): boolean {
+  return a.line < b.line || (a.line === b.line && a.character < b.character);
+}
+
+function parses(language: string, text: string): boolean {
+  if (language === 'rust') return hasBalancedDelimiters(tokenizeRust(text));
+  return true;
+}
+
+function toFilePath(uri: string): string {
+  try {
+    return uri.startsWith('file:') ? fileURLToPath(uri) : uri;
+  } catch {
+    return uri;
+  }
+}
@@ -1,7 +1,96 @@
-import { AnalysisResult } from './CLIAnalyzer';
+import * as path from 'path';
+import { CLIArguments } from './ArgumentParser';
+import { AnalysisResult, FINDING_KINDS, FindingKind, FindingSeverity, UnusedImport } from './CLIAnalyzer';
+import { buildHtmlReport } from './HtmlReport';
+import { toPosixPath } from './Paths';
+import { buildReport, compareStrings } from './Report';
+import { findManifest, packageName } from './RustCrate';
+import { buildSarif } from './Sarif';
+
+const KIND_TITLES: Record<FindingKind, string> = {
+  'unused-import': 'Unused import',
+  'duplicate-import': 'Duplicate import',
+  'redundant-import': 'Redundant import',
+  'type-only-import': 'Type-only import',
+  'useless-suppression': 'Useless suppression',
+  'unused-reexport': 'Unused re-export',
+  'unused-dependency': 'Unused dependency',
+  'shadowed-import': 'Shadowed import',
+  'redundant-alias': 'Redundant alias',
+  'over-qualified-path': 'Over-qualified path',
+  'banned-import': 'Banned import',
+  'unnecessary-import': 'Unnecessary import',
+  'test-only-import': 'Test-only import',
+};
+
+// ANSI colors for severities in text output: red, yellow, cyan
+const SEVERITY_COLORS: Record<FindingSeverity, string> = {
+  error: '\x1b[31m',
+  warning: '\x1b[33m',
+  info: '\x1b[36m',
+};
+const RESET = '\x1b[0m';
+
+// Findings of one kind in a file shown in text output before the rest are only counted
+const COLLAPSED_RUN = 3;
+
+const NO_CRATE = '(no crate)';
+
+export const TEXT_GROUPINGS = ['file', 'kind', 'crate'] as const;
+export type TextGrouping = typeof TEXT_GROUPINGS[number];
+
+export const COLOR_MODES = ['auto', 'always', 'never'] as const;
+export type ColorMode = typeof COLOR_MODES[number];
+
+/** Layout of text output */
+export interface TextOptions {
+  /** What findings are grouped by first; defaults to `file` */
+  groupBy?: TextGrouping;
+  /** Only show this many groups, those with the most findings */
+  top?: number;
+  /** Show every finding of a run of one kind, and the files without findings */
+  verbose?: boolean;
+}
+
+/** A finding in text output, with what it is grouped by */
+interface TextFinding {
+  filePath: string;
+  crate: string;
+  kind: FindingKind;
+  severity: FindingSeverity;
+  finding: UnusedImport;
+}
+
+interface FindingGroup {
+  key: string;
+  findings: TextFinding[];
+}
+
+// Annotation commands GitHub Actions has for each severity
+const GITHUB_LEVELS: Record<FindingSeverity, string> = {
+  error: 'error',
+  warning: 'warning',
+  info: 'notice',
+};
 
 export class OutputFormatter {
-  constructor(private outputFormat: 'text' | 'json' | 'github' | 'junit') {}
+  /** Package name of each `Cargo.toml` read for text output */
+  private crates = new Map<string, string>();
+
+  /**
+   * @param color Color-code findings by severity in text output
+   * @param text How text output is grouped
+   */
+  constructor(
+    private outputFormat: 'text' | 'json' | 'github' | 'junit' | 'sarif' | 'html',
+    private color = false,
+    private text: TextOptions = {}
+  ) {}
+
+  /** Formatter for the report `args` ask for, colored by `--color` when written to `stream` */
+  static forArgs(args: CLIArguments, stream: { isTTY?: boolean } = process.stdout): OutputFormatter {
+    return new OutputFormatter(args.format, useColor(args.color, stream), { groupBy: args.groupBy, top: args.top, verbose: args.verbose });
+  }
 
   /**
    * Format analysis results according to specified format
@@ -14,6 +103,10 @@ export class OutputFormatter {
         return this.formatGitHub(results);
       case 'junit':
         return this.formatJUnit(results);
+      case 'sarif':
+        return this.formatSARIF(results);
+      case 'html':
+        return buildHtmlReport(results);
       case 'text':
       default:
         return this.formatText(results);
@@ -21,63 +114,139 @@ export class OutputFormatter {
   }
 
   /**
-   * Format as human-readable text
+   * Format as human-readable text: findings grouped by file, kind or crate, each
+   * file's findings by kind with long runs of one kind collapsed, then totals by
+   * kind and, for workspaces, by crate. Groups and findings are sorted, so the same
+   * results always give the same text.
    */
   private formatText(results: AnalysisResult[]): string {
+    const { groupBy = 'file', top, verbose = false } = this.text;
     const lines: string[] = [];
-    let totalIssues = 0;
+    const sorted = [...results].sort((a, b) => compareStrings(a.filePath, b.filePath));
+    const findings = this.textFindings(sorted);
 
     lines.push('ImportLens Analysis Results');
     lines.push('===========================\n');
 
-    for (const result of results) {
+    for (const result of sorted) {
       if (result.error) {
         lines.push(`[ERROR] ${result.filePath}`);
         lines.push(`        ${result.error}\n`);
-        continue;
       }
+    }
+    const clean = verbose ? sorted.filter(result => !result.error && result.unusedImports.length === 0) : [];
+    if (clean.length > 0) {
+      lines.push(...clean.map(result => `[OK] ${result.filePath} - No findings`), '');
+    }
 
-      if (result.unusedImports.length === 0) {
-        lines.push(`[OK] ${result.filePath} - No unused imports`);
-        continue;
+    const groups = groupFindings(findings, groupBy === 'file' ? f => f.filePath : groupBy === 'kind' ? f => f.kind : f => f.crate,
+      groupBy === 'kind' ? compareKinds : compareStrings);
+    // The worst offenders first; the sort is stable, so ties keep their order
+    const shown = top === undefined ? groups : [...groups].sort((a, b) => b.findings.length - a.findings.length).slice(0, top);
+
+    for (const group of shown) {
+      if (groupBy === 'file') {
+        lines.push(`${group.key}: ${countOf(group.findings.length, 'finding')} (${breakdown(group.findings)})`);
+        lines.push(...this.formatFile(group.findings, '  '));
+      } else {
+        const files = groupFindings(group.findings, f => f.filePath, compareStrings);
+        const title = groupBy === 'kind' ? kindTitle(group.key) : `Crate ${group.key}`;
+        lines.push(`${title}: ${countOf(group.findings.length, 'finding')} in ${countOf(files.length, 'file')} (${breakdown(group.findings)})`);
+        for (const file of files) {
+          lines.push(`  ${file.key}`);
+          lines.push(...this.formatFile(file.findings, '    ', groupBy !== 'kind'));
+        }
       }
+      lines.push('');
+    }
+    if (shown.length < groups.length) {
+      const noun = { file: 'files', kind: 'kinds', crate: 'crates' }[groupBy];
+      lines.push(`Showing the top ${shown.length} of ${groups.length} ${noun} by number of findings\n`);
+    }
 
-      totalIssues += result.unusedImports.length;
+    lines.push('='.repeat(50));
+    const kinds = groupFindings(findings, f => f.kind, compareKinds);
+    if (kinds.length > 0) {
+      lines.push('By kind:');
+      const width = Math.max(...kinds.map(kind => kindTitle(kind.key).length));
+      lines.push(...kinds.map(kind => `  ${kindTitle(kind.key).padEnd(width)}  ${kind.findings.length}`));
+    }
+    const crates = groupFindings(findings, f => f.crate, compareStrings);
+    if (crates.length > 1) {
+      lines.push('By crate:');
+      const width = Math.max(...crates.map(crate => crate.key.length));
+      lines.push(...crates.map(crate => {
+        const files = new Set(crate.findings.map(f => f.filePath)).size;
+        return `  ${crate.key.padEnd(width)}  ${crate.findings.length} in ${countOf(files, 'file')}`;
+      }));
+    }
+    const totals = findings.length === 0 ? '' : ` (${breakdown(findings)})`;
+    const files = new Set(results.map(result => path.resolve(result.filePath))).size;
+    lines.push(`Total: ${countOf(findings.length, 'finding')} in ${countOf(files, 'file')}${totals}`);
 
-      lines.push(`\n[WARN] ${result.filePath}`);
-      lines.push(`   Found ${result.unusedImports.length} unused import(s):\n`);
+    return lines.join('\n');
+  }
 
-      for (const unused of result.unusedImports) {
-        lines.push(`   Line ${unused.line}: ${unused.importStatement}`);
-        lines.push(`   → ${unused.reason}`);
-        lines.push('');
+  /**
+   * One file's findings, by kind and then line, each kind under a heading unless the
+   * group is of one kind already. Past the first few of a kind the rest are counted
+   * instead of shown, unless `verbose`.
+   */
+  private formatFile(findings: TextFinding[], indent: string, showKinds = true): string[] {
+    const lines: string[] = [];
+    for (const run of groupFindings(findings, f => f.kind, compareKinds)) {
+      const shown = this.text.verbose ? run.findings : run.findings.slice(0, COLLAPSED_RUN);
+      const inner = showKinds ? `${indent}  ` : indent;
+      if (showKinds) lines.push(`${indent}${kindTitle(run.key)} (${run.findings.length})`);
+
+      for (const { finding, severity } of shown) {
+        lines.push(`${inner}${this.paint(severity, `[${severity}]`)} Line ${finding.line}: ${finding.importStatement}`);
+        lines.push(`${inner}→ ${finding.reason}`);
+        if (finding.confidence && finding.confidence !== 'high') {
+          lines.push(`${inner}Confidence: ${finding.confidence}${finding.confidenceReason ? ` (${finding.confidenceReason})` : ''}`);
+        }
+      }
+      if (shown.length < run.findings.length) {
+        const title = kindTitle(run.key).toLowerCase();
+        lines.push(`${inner}${run.findings.length} ${plural(title)} in this file, showing first ${shown.length}; pass --verbose for all`);
       }
     }
+    return lines;
+  }
 
-    lines.push('\n' + '='.repeat(50));
-    lines.push(`Total: ${totalIssues} unused import(s) in ${results.length} file(s)`);
+  /**
+   * The findings of every analyzed file, by line. A finding that is reported twice,
+   * e.g. for a file reached through two of the paths given, is listed once.
+   */
+  private textFindings(results: AnalysisResult[]): TextFinding[] {
+    const seen = new Set<string>();
+    const findings: TextFinding[] = [];
 
-    return lines.join('\n');
+    for (const result of results) {
+      if (result.error) continue;
+      const crate = this.crateOf(result.filePath);
+      for (const finding of [...result.unusedImports].sort((a, b) => a.line - b.line || (a.column ?? 0) - (b.column ?? 0))) {
+        const kind = finding.kind ?? 'unused-import';
+        const key = [path.resolve(result.filePath), kind, finding.line, finding.column, finding.importStatement, finding.reason].join('\0');
+        if (seen.has(key)) continue;
+        seen.add(key);
+        findings.push({ filePath: result.filePath, crate, kind, severity: finding.severity ?? 'error', finding });
+      }
+    }
+    return findings;
+  }
+
+  /** Package name from the nearest `Cargo.toml`, or its directory's name; `(no crate)` outside any package */
+  private crateOf(filePath: string): string {
+    const manifest = findManifest(filePath);
+    return manifest ? packageName(manifest, this.crates) : NO_CRATE;
   }
 
   /**
-   * Format as JSON
+   * Format as JSON (see Report for the schema)
    */
   private formatJSON(results: AnalysisResult[]): string {
-    const summary = {
-      totalFiles: results.length,
-      filesWithIssues: results.filter(r => r.unusedImports.length > 0).length,
-      totalUnusedImports: results.reduce((sum, r) => sum + r.unusedImports.length, 0),
-      results: results.map(r => ({
-        filePath: r.filePath,
-        language: r.language,
-        unusedImportCount: r.unusedImports.length,
-        unusedImports: r.unusedImports,
-        error: r.error
-      }))
-    };
-
-    return JSON.stringify(summary, null, 2);
+    return JSON.stringify(buildReport(results), null, 2);
   }
 
   /**
@@ -90,15 +259,18 @@ export class OutputFormatter {
     for (const result of results) {
       if (result.error) {
         lines.push(
-          `::error file=${result.filePath}::Analysis error: ${result.error}`
+          `::error file=${toPosixPath(result.filePath)}::Analysis error: ${result.error}`
         );
         continue;
       }
 
       for (const unused of result.unusedImports) {
-        const message = `Unused import: ${unused.symbols.join(', ')} - ${unused.reason}`;
+        const kind = unused.kind ?? 'unused-import';
+        const message = `${KIND_TITLES[kind] ?? kind}: ${unused.symbols.join(', ')} - ${unused.reason}`;
+        const column = unused.column !== undefined ? `,col=${unused.column}` : '';
+        const level = GITHUB_LEVELS[unused.severity ?? 'error'];
         lines.push(
-          `::warning file=${result.filePath},line=${unused.line}::${message}`
+          `::${level} file=${toPosixPath(result.filePath)},line=${unused.line}${column}::${message}`
         );
       }
     }
@@ -114,6 +286,13 @@ export class OutputFormatter {
     return lines.join('\n');
   }
 
+  /**
+   * Format as SARIF 2.1.0 (for GitHub code scanning and other SARIF viewers)
+   */
+  private formatSARIF(results: AnalysisResult[]): string {
+    return JSON.stringify(buildSarif(results), null, 2);
+  }
+
   /**
    * Format as JUnit XML (for CI systems that support it)
    */
@@ -157,6 +336,10 @@ export class OutputFormatter {
     return lines.join('\n');
   }
 
+  private paint(severity: FindingSeverity, text: string): string {
+    return this.color ? `${SEVERITY_COLORS[severity]}${text}${RESET}` : text;
+  }
+
   /**
    * Escape special XML characters
    */
@@ -169,3 +352,51 @@ export class OutputFormatter {
       .replace(/'/g, '&apos;');
   }
 }
+
+/**
+ * Whether to color output for `--color`: always, never, or on a terminal unless the
+ * `NO_COLOR` environment variable is set
+ */
+export function useColor(mode: ColorMode, stream: { isTTY?: boolean }): boolean {
+  if (mode !== 'auto') return mode === 'always';
+  return Boolean(stream.isTTY) && !process.env.NO_COLOR;
+}
+
+/** Findings split by `key`, groups in `compare` order and findings in the order given */
+function groupFindings(findings: TextFinding[], key: (finding: TextFinding) => string, compare: (a: string, b: string) => number): FindingGroup[] {
+  const groups = new Map<string, TextFinding[]>();
+  for (const finding of findings) {
+    const group = groups.get(key(finding)) ?? [];
+    group.push(finding);
+    groups.set(key(finding), group);
+  }
+  return [...groups.keys()].sort(compare).map(name => ({ key: name, findings: groups.get(name) ?? [] }));
+}
+
+/** Built-in kinds in the order of `FINDING_KINDS`, then custom rules by id */
+function compareKinds(a: string, b: string): number {
+  const rank = (kind: string) => {
+    const index = (FINDING_KINDS as readonly string[]).indexOf(kind);
+    return index === -1 ? FINDING_KINDS.length : index;
+  };
+  return rank(a) - rank(b) || compareStrings(a, b);
+}
+
+function kindTitle(kind: string): string {
+  return KIND_TITLES[kind as FindingKind] ?? kind;
+}
+
+function breakdown(findings: TextFinding[]): string {
+  const count = (severity: FindingSeverity) => findings.filter(f => f.severity === severity).length;
+  return `${count('error')} error(s), ${count('warning')} warning(s), ${count('info')} info`;
+}
+
+function countOf(count: number, noun: string): string {
+  return `${count} ${count === 1 ? noun : plural(noun)}`;
+}
+
+/** `unused imports`, `redundant aliases`, `unused dependencies` */
+function plural(noun: string): string {
+  if (/[^aeiou]y$/.test(noun)) return `${noun.slice(0, -1)}ies`;
+  return /(s|x|ch|sh)$/.test(noun) ? `${noun}es` : `${noun}s`;
+}
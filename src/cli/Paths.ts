@@ -0,0 +1,18 @@
+import * as path from 'path';
+
+/**
+ * `filePath` with `/` separators, as files are named in reports. Only the platform's
+ * own separator is replaced: on POSIX `\` is an ordinary file name character.
+ */
+export function toPosixPath(filePath: string, platformPath: path.PlatformPath = path): string {
+  return platformPath.sep === '/' ? filePath : filePath.split(platformPath.sep).join('/');
+}
+
+/**
+ * How a file is shown in output: relative to `cwd` when absolute, with `/` separators.
+ * Reading and writing the file keeps using its native path.
+ */
+export function displayPath(filePath: string, cwd: string = process.cwd(), platformPath: path.PlatformPath = path): string {
+  const relative = platformPath.isAbsolute(filePath) ? platformPath.relative(cwd, filePath) : filePath;
+  return toPosixPath(relative, platformPath);
+}
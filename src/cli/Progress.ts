@@ -0,0 +1,152 @@
+import { CLIArguments } from './ArgumentParser';
+import { displayPath } from './Paths';
+
+/**
+ * Progress of a multi-file run: `done` of its `total` files are finished
+ */
+export interface ProgressEvent {
+  done: number;
+  total: number;
+  /** File that was just analyzed; unset for files answered from the analysis cache */
+  filePath?: string;
+}
+
+/** Called after each file of a run, and once for all the files answered from the cache */
+export type ProgressCallback = (event: ProgressEvent) => void;
+
+type Listener = () => void;
+
+/**
+ * Asks a running analysis to stop. It is checked between files, so the file being
+ * analyzed or fixed is finished first and nothing is left half-written; the run then
+ * rejects with an `ImportLensError` of kind `cancelled`.
+ *
+ * ```ts
+ * const cancellation = new CancellationToken();
+ * setTimeout(() => cancellation.cancel(), 60_000);
+ * const report = await analyzer.analyzePaths(files, { cancellation });
+ * ```
+ */
+export class CancellationToken {
+  private cancelled = false;
+  private listeners = new Set<Listener>();
+
+  get isCancelled(): boolean {
+    return this.cancelled;
+  }
+
+  /** Request cancellation; listeners run on the first call only */
+  cancel(): void {
+    if (this.cancelled) return;
+    this.cancelled = true;
+    const listeners = [...this.listeners];
+    this.listeners.clear();
+    listeners.forEach(listener => listener());
+  }
+
+  /**
+   * Call `listener` on cancellation, at once if the token is already cancelled.
+   * Returns a function that unregisters it.
+   */
+  onCancel(listener: Listener): () => void {
+    if (this.cancelled) {
+      listener();
+      return () => undefined;
+    }
+    this.listeners.add(listener);
+    return () => {
+      this.listeners.delete(listener);
+    };
+  }
+}
+
+/**
+ * Hooks into a multi-file run
+ */
+export interface RunOptions {
+  onProgress?: ProgressCallback;
+  cancellation?: CancellationToken;
+}
+
+/**
+ * Resolves on a later turn of the event loop, so signal handlers and timers get to
+ * run between files of an otherwise synchronous loop
+ */
+export function nextTurn(): Promise<void> {
+  return new Promise(resolve => setImmediate(resolve));
+}
+
+/** Stream a `ProgressBar` draws on */
+export interface ProgressStream {
+  write(text: string): unknown;
+  /** Width of the terminal; 80 when unset or 0 */
+  columns?: number;
+}
+
+// Fewest milliseconds between redraws, so drawing never slows down a fast run
+const REDRAW_INTERVAL = 100;
+const BAR_WIDTH = 20;
+
+/**
+ * One-line progress bar, redrawn in place:
+ * `[########------------] 180/450 files  ETA 12s  src/net/client.rs`.
+ *
+ * The ETA assumes the remaining files go at the rate of those done since the first
+ * update, which leaves out the files answered from the cache at the start. The path
+ * is shortened from the left to fit the terminal.
+ */
+export class ProgressBar {
+  private start?: { time: number; done: number };
+  private lastDraw = 0;
+  private drawn = false;
+
+  constructor(private stream: ProgressStream, private now: () => number = Date.now, private cwd = process.cwd()) {}
+
+  /** A bar on stderr, unless stderr isn't a terminal or `--quiet` is given */
+  static forArgs(args: CLIArguments, stream: ProgressStream & { isTTY?: boolean } = process.stderr): ProgressBar | undefined {
+    return stream.isTTY && !args.quiet ? new ProgressBar(stream) : undefined;
+  }
+
+  update(event: ProgressEvent): void {
+    const time = this.now();
+    if (!this.start) this.start = { time, done: event.done };
+    if (this.drawn && event.done < event.total && time - this.lastDraw < REDRAW_INTERVAL) return;
+
+    this.lastDraw = time;
+    this.drawn = true;
+    this.stream.write(`\r${this.render(event, time)}\x1b[K`);
+  }
+
+  /** Clear the bar, so what is printed next starts on an empty line */
+  clear(): void {
+    if (!this.drawn) return;
+    this.stream.write('\r\x1b[K');
+    this.drawn = false;
+  }
+
+  render(event: ProgressEvent, time = this.now()): string {
+    const { done, total } = event;
+    const filled = total === 0 ? BAR_WIDTH : Math.floor(BAR_WIDTH * done / total);
+    let line = `[${'#'.repeat(filled)}${'-'.repeat(BAR_WIDTH - filled)}] ${done}/${total} files`;
+
+    const start = this.start ?? { time, done };
+    if (done > start.done && done < total && time > start.time) {
+      const perFile = (time - start.time) / (done - start.done);
+      line += `  ETA ${formatDuration(perFile * (total - done))}`;
+    }
+
+    if (event.filePath !== undefined) {
+      // One column is left free, so the cursor never wraps to the next line
+      const room = (this.stream.columns || 80) - line.length - 3;
+      const file = displayPath(event.filePath, this.cwd);
+      if (room >= 8) line += `  ${file.length > room ? `…${file.slice(file.length - room + 1)}` : file}`;
+    }
+    return line;
+  }
+}
+
+/** `12s`, or `3m05s` from a minute up */
+function formatDuration(milliseconds: number): string {
+  const seconds = Math.ceil(milliseconds / 1000);
+  return seconds < 60 ? `${seconds}s` : `${Math.floor(seconds / 60)}m${String(seconds % 60).padStart(2, '0')}s`;
+}
@@ -0,0 +1,386 @@
+import { statementText, UnusedImport } from './CLIAnalyzer';
+import { LineIndex } from './LineIndex';
+import { PythonToken, tokenizePython } from './PythonLexer';
+
+/**
+ * A single name bound by an import statement
+ */
+export interface PythonImportBinding {
+  /** Name the import binds in the module, e.g. `np` for `import numpy as np` */
+  binding: string;
+  /** Dotted path of the imported item, e.g. `os.path` or `typing.List` */
+  path: string;
+  isStar: boolean;
+  /** Offsets of `name` or `name as alias` */
+  start: number;
+  end: number;
+}
+
+/**
+ * An `import ...` or `from ... import ...` statement
+ */
+export interface PythonImportStatement {
+  start: number;
+  end: number;
+  bindings: PythonImportBinding[];
+  /** `from __future__ import ...` */
+  isFuture: boolean;
+  /** Inside an `if TYPE_CHECKING:` block */
+  typeCheckingOnly: boolean;
+}
+
+interface LogicalLine {
+  tokens: PythonToken[];
+  /** Leading whitespace width of the physical line the statement starts on */
+  indent: number;
+}
+
+// Type-like strings: `"Foo"`, `"List[mod.Bar]"`, `"Foo | None"`
+const TYPE_STRING = /^[\p{L}\p{N}_.[\], |]+$/u;
+const IDENTIFIER = /[\p{L}_][\p{L}\p{N}_]*/gu;
+const NOQA = /#\s*noqa\b(?::\s*([\w\s,]+))?/i;
+
+/**
+ * Import analyzer for Python modules.
+ *
+ * - Names listed in `__all__` count as used
+ * - `# noqa` or `# noqa: F401` on the import line suppresses the finding
+ * - String annotations (`x: "Foo"`, `List["Foo"]`) and `# type:` comments count as uses,
+ *   and imports under `if TYPE_CHECKING:` also count any type-like string
+ * - `from x import *` and `from __future__ import ...` are never reported
+ */
+export class PythonAnalyzer {
+  analyzeFile(content: string): UnusedImport[] {
+    const tokens = tokenizePython(content);
+    const lines = splitLogicalLines(content, tokens);
+    const statements = parseImportStatements(lines);
+    const lineIndex = new LineIndex(content);
+
+    const used = collectUsedNames(tokens, statements);
+    const exported = collectDunderAll(lines);
+    const typeStrings = collectTypeStringNames(tokens);
+    const suppressedLines = collectNoqaLines(tokens, lineIndex);
+
+    const unused: UnusedImport[] = [];
+
+    for (const statement of statements) {
+      if (statement.isFuture) continue;
+      const statementLine = lineIndex.positionAt(statement.start).line;
+
+      for (const binding of statement.bindings) {
+        if (binding.isStar) continue;
+        if (used.has(binding.binding) || exported.has(binding.binding)) continue;
+        if (statement.typeCheckingOnly && typeStrings.has(binding.binding)) continue;
+
+        const position = lineIndex.positionAt(binding.start);
+        if (suppressedLines.has(statementLine) || suppressedLines.has(position.line)) continue;
+
+        unused.push({
+          line: position.line,
+          column: position.column,
+          importStatement: statementText(content, statement.start, statement.end),
+          symbols: [binding.binding],
+          reason: `Symbol ${binding.binding} (${binding.path}) not used in code`,
+          span: { start: binding.start, end: binding.end },
+          kind: 'unused-import',
+          importPath: binding.path,
+        });
+      }
+    }
+
+    return unused;
+  }
+}
+
+/**
+ * Group tokens into logical lines (comments dropped), splitting simple statements on `;`
+ */
+function splitLogicalLines(content: string, tokens: PythonToken[]): LogicalLine[] {
+  const lines: LogicalLine[] = [];
+  let current: PythonToken[] = [];
+  let depth = 0;
+
+  const flush = () => {
+    if (current.length === 0) return;
+    const lineStart = content.lastIndexOf('\n', current[0].start - 1) + 1;
+    const indent = /^[ \t]*/.exec(content.slice(lineStart))![0].length;
+    lines.push({ tokens: current, indent });
+    current = [];
+  };
+
+  for (const token of tokens) {
+    if (token.kind === 'comment') continue;
+
+    if (token.kind === 'newline' || (token.text === ';' && depth === 0)) {
+      flush();
+      continue;
+    }
+
+    if (token.text === '(' || token.text === '[' || token.text === '{') depth++;
+    else if (token.text === ')' || token.text === ']' || token.text === '}') depth = Math.max(0, depth - 1);
+
+    current.push(token);
+  }
+
+  flush();
+  return lines;
+}
+
+function parseImportStatements(lines: LogicalLine[]): PythonImportStatement[] {
+  const statements: PythonImportStatement[] = [];
+  // Indentation of the enclosing `if TYPE_CHECKING:` headers
+  const typeCheckingBlocks: number[] = [];
+
+  for (const line of lines) {
+    while (typeCheckingBlocks.length > 0 && line.indent <= typeCheckingBlocks[typeCheckingBlocks.length - 1]) {
+      typeCheckingBlocks.pop();
+    }
+
+    if (isTypeCheckingHeader(line.tokens)) {
+      typeCheckingBlocks.push(line.indent);
+      continue;
+    }
+
+    const statement = parseImportLine(line.tokens);
+    if (statement) {
+      statement.typeCheckingOnly = typeCheckingBlocks.length > 0;
+      statements.push(statement);
+    }
+  }
+
+  return statements;
+}
+
+/**
+ * `if TYPE_CHECKING:` / `if typing.TYPE_CHECKING:` (any module alias)
+ */
+function isTypeCheckingHeader(tokens: PythonToken[]): boolean {
+  const texts = tokens.map(t => t.text);
+  if (texts[0] !== 'if' || texts[texts.length - 1] !== ':') return false;
+  const condition = texts.slice(1, -1);
+  return (condition.length === 1 && condition[0] === 'TYPE_CHECKING') ||
+    (condition.length === 3 && condition[1] === '.' && condition[2] === 'TYPE_CHECKING');
+}
+
+function parseImportLine(tokens: PythonToken[]): PythonImportStatement | null {
+  const first = tokens[0];
+  if (first?.kind !== 'name' || (first.text !== 'import' && first.text !== 'from')) return null;
+
+  const last = tokens[tokens.length - 1];
+  const statement: PythonImportStatement = {
+    start: first.start,
+    end: last.end,
+    bindings: [],
+    isFuture: false,
+    typeCheckingOnly: false,
+  };
+
+  let i = 1;
+
+  if (first.text === 'import') {
+    // import a.b, c as d
+    while (i < tokens.length) {
+      const nameStart = i;
+      const dotted = readDottedName(tokens, i);
+      if (!dotted) return null;
+      i = dotted.next;
+
+      let binding = dotted.name.split('.')[0];
+      if (tokens[i]?.text === 'as' && tokens[i + 1]?.kind === 'name') {
+        binding = tokens[i + 1].text;
+        i += 2;
+      }
+      statement.bindings.push({
+        binding,
+        path: dotted.name,
+        isStar: false,
+        start: tokens[nameStart].start,
+        end: tokens[i - 1].end,
+      });
+
+      if (tokens[i]?.text !== ',') break;
+      i++;
+    }
+    return statement;
+  }
+
+  // from .pkg.mod import x, y as z   |   from pkg import (x, y)   |   from pkg import *
+  let module = '';
+  while (tokens[i]?.text === '.' || tokens[i]?.text === '...') {
+    module += tokens[i].text;
+    i++;
+  }
+  if (tokens[i]?.kind === 'name' && tokens[i].text !== 'import') {
+    const dotted = readDottedName(tokens, i);
+    if (!dotted) return null;
+    module += dotted.name;
+    i = dotted.next;
+  }
+  if (tokens[i]?.text !== 'import') return null;
+  i++;
+
+  statement.isFuture = module === '__future__';
+  if (tokens[i]?.text === '(') i++;
+
+  while (i < tokens.length && tokens[i].text !== ')') {
+    const token = tokens[i];
+
+    if (token.text === '*') {
+      statement.bindings.push({ binding: '*', path: `${module}.*`, isStar: true, start: token.start, end: token.end });
+      i++;
+    } else if (token.kind === 'name') {
+      let binding = token.text;
+      let end = token.end;
+      if (tokens[i + 1]?.text === 'as' && tokens[i + 2]?.kind === 'name') {
+        binding = tokens[i + 2].text;
+        end = tokens[i + 2].end;
+        i += 2;
+      }
+      const separator = module.endsWith('.') ? '' : '.';
+      statement.bindings.push({ binding, path: `${module}${separator}${token.text}`, isStar: false, start: token.start, end });
+      i++;
+    } else {
+      return null;
+    }
+
+    if (tokens[i]?.text === ',') i++;
+  }
+
+  return statement;
+}
+
+function readDottedName(tokens: PythonToken[], start: number): { name: string; next: number } | null {
+  if (tokens[start]?.kind !== 'name') return null;
+  let name = tokens[start].text;
+  let i = start + 1;
+  while (tokens[i]?.text === '.' && tokens[i + 1]?.kind === 'name') {
+    name += '.' + tokens[i + 1].text;
+    i += 2;
+  }
+  return { name, next: i };
+}
+
+/**
+ * Names referenced outside import statements. Attribute names (`x.name`) are
+ * skipped, f-string fields are included, and so are type-like strings in
+ * annotation positions: after `:` or `->`, or inside a generic subscript such as `List["Foo"]`.
+ */
+function collectUsedNames(tokens: PythonToken[], statements: PythonImportStatement[]): Set<string> {
+  const used = new Set<string>();
+  const subscripts: boolean[] = [];
+  let statementIndex = 0;
+
+  const addNames = (nameTokens: PythonToken[]) => {
+    nameTokens.forEach((token, index) => {
+      if (token.kind === 'name' && nameTokens[index - 1]?.text !== '.') used.add(token.text);
+    });
+  };
+
+  for (let i = 0; i < tokens.length; i++) {
+    const token = tokens[i];
+
+    const statement = statements[statementIndex];
+    if (statement && token.start >= statement.start) {
+      if (token.end <= statement.end) continue;
+      statementIndex++;
+    }
+
+    const previous = tokens[i - 1];
+
+    if (token.text === '[') {
+      subscripts.push(previous?.kind === 'name' && isGenericTypeName(previous.text));
+    } else if (token.text === ']') {
+      subscripts.pop();
+    } else if (token.kind === 'comment') {
+      const typeComment = token.text.match(/^#\s*type:\s*(.+)$/);
+      if (typeComment && !/^ignore\b/.test(typeComment[1])) {
+        (typeComment[1].match(IDENTIFIER) ?? []).forEach(name => used.add(name));
+      }
+    } else if (token.kind === 'string') {
+      if (token.embedded) addNames(token.embedded);
+
+      const inAnnotation = previous?.text === ':' || previous?.text === '->' ||
+        (subscripts[subscripts.length - 1] === true && (previous?.text === '[' || previous?.text === ','));
+      const value = stringValue(token.text);
+      if (inAnnotation && value !== null && TYPE_STRING.test(value)) {
+        (value.match(IDENTIFIER) ?? []).forEach(name => used.add(name));
+      }
+    } else if (token.kind === 'name' && previous?.text !== '.') {
+      used.add(token.text);
+    }
+  }
+
+  return used;
+}
+
+/**
+ * `List`, `Optional`, `list`, `dict`, ... but not `config` in `config["key"]`
+ */
+function isGenericTypeName(name: string): boolean {
+  return /^[A-Z]/.test(name) || ['list', 'dict', 'set', 'frozenset', 'tuple', 'type'].includes(name);
+}
+
+/**
+ * Names mentioned in any type-like string, for imports that only exist for type checkers
+ */
+function collectTypeStringNames(tokens: PythonToken[]): Set<string> {
+  const names = new Set<string>();
+  for (const token of tokens) {
+    if (token.kind !== 'string') continue;
+    const value = stringValue(token.text);
+    if (value !== null && TYPE_STRING.test(value)) {
+      (value.match(IDENTIFIER) ?? []).forEach(name => names.add(name));
+    }
+  }
+  return names;
+}
+
+/**
+ * Names listed in `__all__ = [...]`, `__all__ += [...]` or `__all__.extend([...])`
+ */
+function collectDunderAll(lines: LogicalLine[]): Set<string> {
+  const names = new Set<string>();
+
+  for (const { tokens } of lines) {
+    if (tokens[0]?.text !== '__all__') continue;
+    const operator = tokens[1]?.text;
+    const isAssignment = operator === '=' || operator === '+=' || operator === ':';
+    const isMethod = operator === '.' && ['extend', 'append'].includes(tokens[2]?.text);
+    if (!isAssignment && !isMethod) continue;
+
+    for (const token of tokens) {
+      const value = token.kind === 'string' ? stringValue(token.text) : null;
+      if (value) names.add(value);
+    }
+  }
+
+  return names;
+}
+
+/**
+ * Lines carrying a `# noqa` comment that covers unused imports (bare, or listing F401)
+ */
+function collectNoqaLines(tokens: PythonToken[], lineIndex: LineIndex): Set<number> {
+  const lines = new Set<number>();
+
+  for (const token of tokens) {
+    if (token.kind !== 'comment') continue;
+    const match = token.text.match(NOQA);
+    if (!match) continue;
+
+    const codes = match[1]?.split(/[\s,]+/).filter(Boolean) ?? [];
+    if (codes.length === 0 || codes.some(code => code.toUpperCase() === 'F401')) {
+      lines.add(lineIndex.positionAt(token.start).line);
+    }
+  }
+
+  return lines;
+}
+
+/**
+ * Contents of a plain (non-f, non-bytes) string literal, or null
+ */
+function stringValue(text: string): string | null {
+  const match = text.match(/^([rRuU]?)('''|"""|'|")([\s\S]*)\2$/);
+  return match ? match[3] : null;
+}
@@ -0,0 +1,196 @@
+export type PythonTokenKind = 'name' | 'string' | 'number' | 'op' | 'comment' | 'newline';
+
+export interface PythonToken {
+  kind: PythonTokenKind;
+  text: string;
+  /** Offset of the first character */
+  start: number;
+  /** Offset one past the last character */
+  end: number;
+  /** For f-string tokens: tokens of the embedded `{expression}` parts */
+  embedded?: PythonToken[];
+}
+
+const STRING_PREFIX = /^(?:[rRbBuUfF]|[rR][bBfF]|[bBfF][rR])(?=['"])/;
+const NAME_START = /[\p{L}_]/u;
+const NAME_PART = /[\p{L}\p{N}_]/u;
+const OPERATORS = ['...', '**=', '//=', '>>=', '<<=', '->', ':=', '**', '//', '==', '!=', '<=', '>=', '<<', '>>',
+  '+=', '-=', '*=', '/=', '%=', '&=', '|=', '^=', '@='];
+
+/**
+ * Minimal Python tokenizer: enough to find import statements and identifier uses.
+ *
+ * A `newline` token ends each logical line, so statements spanning several
+ * physical lines inside brackets or after a `\` continuation stay together.
+ * f-string replacement fields are tokenized into `embedded`.
+ */
+export function tokenizePython(source: string, offset = 0): PythonToken[] {
+  const tokens: PythonToken[] = [];
+  let depth = 0;
+  let i = 0;
+
+  const push = (kind: PythonTokenKind, start: number, end: number) => {
+    tokens.push({ kind, text: source.slice(start, end), start: start + offset, end: end + offset });
+  };
+
+  while (i < source.length) {
+    const char = source[i];
+
+    if (char === '\n' || (char === '\r' && source[i + 1] === '\n')) {
+      const length = char === '\r' ? 2 : 1;
+      const last = tokens[tokens.length - 1];
+      if (depth === 0 && last && last.kind !== 'newline' && !(last.kind === 'comment' && isLineStartComment(tokens))) {
+        push('newline', i, i + length);
+      }
+      i += length;
+      continue;
+    }
+
+    // A leading BOM is whitespace, as for the Python tokenizer
+    if (char === ' ' || char === '\t' || char === '\f' || char === '\r' || (char === '\uFEFF' && i === 0)) {
+      i++;
+      continue;
+    }
+
+    // Explicit line continuation
+    if (char === '\\' && (source[i + 1] === '\n' || (source[i + 1] === '\r' && source[i + 2] === '\n'))) {
+      i += source[i + 1] === '\r' ? 3 : 2;
+      continue;
+    }
+
+    if (char === '#') {
+      let end = i;
+      while (end < source.length && source[end] !== '\n' && source[end] !== '\r') end++;
+      push('comment', i, end);
+      i = end;
+      continue;
+    }
+
+    const prefix = source.slice(i, i + 3).match(STRING_PREFIX);
+    if (char === '"' || char === "'" || prefix) {
+      const prefixLength = prefix ? prefix[0].length : 0;
+      const end = readString(source, i + prefixLength);
+      push('string', i, end);
+      if (prefix && /f/i.test(prefix[0])) {
+        tokens[tokens.length - 1].embedded = tokenizeFStringFields(source, i + prefixLength, end, offset);
+      }
+      i = end;
+      continue;
+    }
+
+    if (NAME_START.test(char)) {
+      let end = i + 1;
+      while (end < source.length && NAME_PART.test(source[end])) end++;
+      push('name', i, end);
+      i = end;
+      continue;
+    }
+
+    if (/[0-9]/.test(char) || (char === '.' && /[0-9]/.test(source[i + 1] ?? ''))) {
+      let end = i + 1;
+      while (end < source.length && (/[\w.]/.test(source[end]) || (/[eE]/.test(source[end - 1]) && /[+-]/.test(source[end])))) end++;
+      push('number', i, end);
+      i = end;
+      continue;
+    }
+
+    const operator = OPERATORS.find(op => source.startsWith(op, i)) ?? char;
+    if ('([{'.includes(operator)) depth++;
+    else if (')]}'.includes(operator)) depth = Math.max(0, depth - 1);
+    push('op', i, i + operator.length);
+    i += operator.length;
+  }
+
+  const last = tokens[tokens.length - 1];
+  if (last && last.kind !== 'newline') {
+    tokens.push({ kind: 'newline', text: '', start: source.length + offset, end: source.length + offset });
+  }
+
+  return tokens;
+}
+
+/**
+ * True when the last token is a comment alone on its line (no statement to end)
+ */
+function isLineStartComment(tokens: PythonToken[]): boolean {
+  const previous = tokens[tokens.length - 2];
+  return !previous || previous.kind === 'newline';
+}
+
+/**
+ * Return the offset one past the closing quote of the string whose opening quote is at `quoteStart`
+ */
+function readString(source: string, quoteStart: number): number {
+  const quote = source[quoteStart];
+  const triple = source.startsWith(quote.repeat(3), quoteStart);
+  const delimiter = triple ? quote.repeat(3) : quote;
+  let i = quoteStart + delimiter.length;
+
+  while (i < source.length) {
+    if (source[i] === '\\') {
+      i += 2;
+      continue;
+    }
+    if (source.startsWith(delimiter, i)) {
+      return i + delimiter.length;
+    }
+    // An unterminated single-quoted string ends at the line break
+    if (!triple && source[i] === '\n') {
+      return i;
+    }
+    i++;
+  }
+
+  return source.length;
+}
+
+/**
+ * Tokenize the expressions of an f-string's `{...}` fields, skipping
+ * `{{` escapes, `!r` conversions and the literal part of format specs.
+ */
+function tokenizeFStringFields(source: string, quoteStart: number, end: number, offset: number): PythonToken[] {
+  const quote = source[quoteStart];
+  const delimiterLength = source.startsWith(quote.repeat(3), quoteStart) ? 3 : 1;
+  const bodyEnd = Math.max(quoteStart + delimiterLength, end - delimiterLength);
+  const tokens: PythonToken[] = [];
+
+  const scanFields = (from: number, to: number) => {
+    let i = from;
+    while (i < to) {
+      if (source[i] === '{' && source[i + 1] === '{') {
+        i += 2;
+        continue;
+      }
+      if (source[i] !== '{') {
+        i++;
+        continue;
+      }
+
+      // Find the end of the expression part and of the whole field
+      let depth = 0;
+      let expressionEnd = -1;
+      let j = i + 1;
+      for (; j < to; j++) {
+        const c = source[j];
+        if ('([{'.includes(c)) depth++;
+        else if (')]}'.includes(c)) {
+          if (depth === 0) break;
+          depth--;
+        } else if (depth === 0 && expressionEnd === -1 && (c === ':' || (c === '!' && source[j + 1] !== '='))) {
+          expressionEnd = j;
+        }
+      }
+
+      const fieldEnd = j;
+      const exprEnd = expressionEnd === -1 ? fieldEnd : expressionEnd;
+      tokens.push(...tokenizePython(source.slice(i + 1, exprEnd), offset + i + 1).filter(t => t.kind !== 'newline'));
+
+      // Format specs may nest fields: f"{value:{width}}"
+      if (expressionEnd !== -1) scanFields(expressionEnd + 1, fieldEnd);
+      i = fieldEnd + 1;
+    }
+  };
+
+  scanFields(quoteStart + delimiterLength, bodyEnd);
+  return tokens;
+}
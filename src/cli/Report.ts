@@ -0,0 +1,166 @@
+import { AnalysisResult, ConfidenceLevel, ConfidenceReason, DEFAULT_CONFIDENCE_REASONS, FINDING_KINDS, FindingKind, FindingSeverity, SEVERITY_LEVELS, SourceSpan, SuggestedFix, UnusedImport } from './CLIAnalyzer';
+import { displayPath, toPosixPath } from './Paths';
+
+/** Bumped whenever a field is removed or changes meaning */
+export const REPORT_SCHEMA_VERSION = '3.0';
+
+/**
+ * One problem found in one file, in the stable JSON schema. Keys are snake_case, like
+ * the rest of the report apart from the fields kept from the original format.
+ */
+export interface Finding {
+  /** Path relative to the working directory, with `/` separators */
+  file: string;
+  line: number;
+  column: number | null;
+  /** UTF-8 byte offsets of the offending item */
+  byte_span: SourceSpan | null;
+  import_path: string;
+  kind: FindingKind;
+  /** Only `error` findings fail `--check`, unless `--fail-on` sets another level */
+  severity: FindingSeverity;
+  /** `high` unless the finding relies on a heuristic; `confidence_reason` says which */
+  confidence: ConfidenceLevel;
+  confidence_reason: ConfidenceReason;
+  symbols: string[];
+  message: string;
+  suggested_fix: ReportFix | null;
+  /** For duplicate and redundant imports: the import that already provides the name */
+  original: { line: number; column: number | null; byte_span: SourceSpan | null } | null;
+  /** Go: build constraint of the file, when it has one */
+  build_constraint: string | null;
+}
+
+/**
+ * `SuggestedFix` of a finding as reported: replacements in UTF-8 byte offsets, each
+ * applicable on its own
+ */
+export interface ReportFix {
+  description: string;
+  edits: Array<{ start: number; end: number; new_text: string }>;
+}
+
+export interface ReportSummary {
+  total_findings: number;
+  /** Count for every built-in kind, including zeroes, and every custom rule kind found */
+  by_kind: Record<FindingKind, number>;
+  /** Count for every severity, including zeroes */
+  by_severity: Record<FindingSeverity, number>;
+  /** Count per file that has findings */
+  by_file: Record<string, number>;
+}
+
+/**
+ * Machine-readable analysis report, as written by `--format=json`
+ */
+export interface Report {
+  schema_version: string;
+  findings: Finding[];
+  summary: ReportSummary;
+  /** Files that could not be analyzed */
+  errors: Array<{ file: string; message: string }>;
+
+  // Fields of the original JSON format, frozen under their original names for the scripts that read them
+  totalFiles: number;
+  filesWithIssues: number;
+  totalUnusedImports: number;
+  /**
+   * @deprecated Repeats `findings` per file in the original shape; it will be removed
+   * in the next major release
+   */
+  results: Array<{
+    filePath: string;
+    language: string;
+    unusedImportCount: number;
+    unusedImports: UnusedImport[];
+    error?: string;
+  }>;
+}
+
+/**
+ * Build a report from analysis results. Findings are sorted by file, then position.
+ */
+export function buildReport(results: AnalysisResult[], cwd: string = process.cwd()): Report {
+  const findings = results
+    .flatMap(result => result.unusedImports.map(unused => toFinding(displayPath(result.filePath, cwd), unused)))
+    .sort(compareFindings);
+
+  const byKind = Object.fromEntries(FINDING_KINDS.map(kind => [kind, 0])) as Record<FindingKind, number>;
+  const bySeverity = Object.fromEntries(SEVERITY_LEVELS.map(severity => [severity, 0])) as Record<FindingSeverity, number>;
+  const byFile: Record<string, number> = {};
+  for (const finding of findings) {
+    byKind[finding.kind] = (byKind[finding.kind] ?? 0) + 1;
+    bySeverity[finding.severity]++;
+    byFile[finding.file] = (byFile[finding.file] ?? 0) + 1;
+  }
+
+  return {
+    schema_version: REPORT_SCHEMA_VERSION,
+    findings,
+    summary: { total_findings: findings.length, by_kind: byKind, by_severity: bySeverity, by_file: byFile },
+    errors: results
+      .filter(r => r.error)
+      .map(r => ({ file: displayPath(r.filePath, cwd), message: r.error! }))
+      .sort((a, b) => compareStrings(a.file, b.file)),
+
+    totalFiles: results.length,
+    filesWithIssues: results.filter(r => r.unusedImports.length > 0).length,
+    totalUnusedImports: findings.length,
+    results: results.map(r => ({
+      filePath: toPosixPath(r.filePath),
+      language: r.language,
+      unusedImportCount: r.unusedImports.length,
+      unusedImports: r.unusedImports,
+      error: r.error,
+    })),
+  };
+}
+
+/**
+ * Order findings by file, then byte offset (falling back to line/column)
+ */
+export function compareFindings(a: Finding, b: Finding): number {
+  const byFile = compareStrings(a.file, b.file);
+  if (byFile !== 0) return byFile;
+
+  if (a.byte_span && b.byte_span && a.byte_span.start !== b.byte_span.start) {
+    return a.byte_span.start - b.byte_span.start;
+  }
+
+  return a.line - b.line || (a.column ?? 0) - (b.column ?? 0) || compareStrings(a.kind, b.kind);
+}
+
+function toFinding(file: string, unused: UnusedImport): Finding {
+  return {
+    file,
+    line: unused.line,
+    column: unused.column ?? null,
+    byte_span: unused.byteSpan ?? null,
+    import_path: unused.importPath ?? unused.importStatement,
+    kind: unused.kind ?? 'unused-import',
+    severity: unused.severity ?? 'error',
+    confidence: unused.confidence ?? 'high',
+    confidence_reason: unused.confidenceReason ?? DEFAULT_CONFIDENCE_REASONS[unused.kind ?? 'unused-import'] ?? 'syntactic',
+    symbols: unused.symbols,
+    message: unused.reason,
+    suggested_fix: unused.suggestedFix ? toReportFix(unused.suggestedFix) : null,
+    original: unused.original
+      ? { line: unused.original.line, column: unused.original.column ?? null, byte_span: unused.original.byteSpan ?? null }
+      : null,
+    build_constraint: unused.buildConstraint ?? null,
+  };
+}
+
+function toReportFix(fix: SuggestedFix): ReportFix {
+  return {
+    description: fix.description,
+    edits: fix.edits.map(edit => ({ start: edit.start, end: edit.end, new_text: edit.newText })),
+  };
+}
+
+/**
+ * Locale-independent string order, so sorted output is identical on every machine
+ */
+export function compareStrings(a: string, b: string): number {
+  return a < b ? -1 : a > b ? 1 : 0;
+}
@@ -0,0 +1,178 @@
+import { ConfidenceLevel, FindingKind, isRuleKind, SourceSpan, statementText, UnusedImport } from './CLIAnalyzer';
+import { LineIndex } from './LineIndex';
+import { declarationModule } from './RustCrate';
+import { identifierName, tokenizeRust } from './RustLexer';
+import { parseExternCrates, parseUseDeclarations, reexportScope } from './RustUseTree';
+
+/**
+ * One imported item of a file, as custom rules see it
+ */
+export interface ImportItem {
+  /**
+   * Path as written: ['chrono', 'Utc'] for `use chrono::Utc;`, ['crate', 'internal', '*']
+   * for a glob, ['serde'] for `extern crate serde;`
+   */
+  path: string[];
+  /** Name the import binds: the alias, the last segment, `*` for globs */
+  binding: string;
+  /** `pub use`, `pub(crate) use`, ... */
+  reexport: boolean;
+  externCrate: boolean;
+  /** Inline module the import is in, relative to the file: ['tests'] in `mod tests { ... }` */
+  module: string[];
+  /** The leaf in the use tree, `Utc` of `use chrono::{Utc, Duration};`, or the whole `extern crate` item */
+  span: SourceSpan;
+  line: number;
+  /** 1-based UTF-8 byte column */
+  column: number;
+}
+
+/**
+ * What a rule gets to look at for one file
+ */
+export interface FileContext {
+  /** Path of the file, or `<source>` for sources analyzed without one */
+  readonly filePath: string;
+  readonly language: string;
+  readonly source: string;
+  /**
+   * Rust: every leaf of every `use` tree and every `extern crate` item, in file order.
+   * Empty for other languages.
+   */
+  readonly imports: readonly ImportItem[];
+  /**
+   * Rust: how often each name appears outside `use` and `extern crate` items, raw
+   * identifiers by their plain name. Empty for other languages.
+   */
+  readonly usages: ReadonlyMap<string, number>;
+  /** 1-based line and UTF-8 byte column of an offset into `source` */
+  positionAt(offset: number): { line: number; column: number };
+  /**
+   * A finding for `span`, with its line, column and statement filled in; `reason` is
+   * its message. The kind is set from the rule that returns it.
+   */
+  findingAt(span: SourceSpan, reason: string, symbols?: string[], confidence?: ConfidenceLevel): UnusedImport;
+}
+
+/**
+ * A check run on every analyzed file. Built-in rules report built-in kinds; a custom
+ * rule's `id` must be namespaced, `namespace/name`, and is the kind of every finding
+ * it returns, so `[severity]` in `importlens.toml` can turn it off:
+ *
+ * ```ts
+ * const noChrono: Rule = {
+ *   id: 'acme/banned-crate',
+ *   check: context => context.imports
+ *     .filter(item => item.path[0] === 'chrono')
+ *     .map(item => context.findingAt(item.span, '`chrono` is banned; use `time`', [item.binding])),
+ * };
+ * const analyzer = Analyzer.builder().rule(noChrono).build();
+ * ```
+ */
+export interface Rule {
+  readonly id: string;
+  check(context: FileContext): UnusedImport[];
+}
+
+// Built-in kinds reported by the duplicate-imports rule; the unused-imports rule reports the rest
+const REPETITION_KINDS = new Set<FindingKind>(['duplicate-import', 'redundant-import']);
+
+/**
+ * The built-in checks as rules: `unused-imports` and `duplicate-imports`. Both come
+ * from one pass of the language's analyzer (`analyze`), run once per file.
+ */
+export function builtinRules(analyze: (context: FileContext) => UnusedImport[]): Rule[] {
+  const found = new WeakMap<FileContext, UnusedImport[]>();
+  const findings = (context: FileContext) => {
+    let result = found.get(context);
+    if (!result) {
+      result = analyze(context);
+      found.set(context, result);
+    }
+    return result;
+  };
+
+  return [
+    { id: 'unused-imports', check: context => findings(context).filter(u => !REPETITION_KINDS.has(u.kind ?? 'unused-import')) },
+    { id: 'duplicate-imports', check: context => findings(context).filter(u => REPETITION_KINDS.has(u.kind ?? 'unused-import')) },
+  ];
+}
+
+/**
+ * Run `rule` on `context`. A custom rule's findings all get its id as their kind.
+ */
+export function runRule(rule: Rule, context: FileContext): UnusedImport[] {
+  const findings = rule.check(context);
+  return isRuleKind(rule.id) ? findings.map(finding => ({ ...finding, kind: rule.id as FindingKind })) : findings;
+}
+
+/**
+ * The context of a file, parsing imports and usages only when a rule asks for them
+ */
+export function createFileContext(source: string, language: string, filePath: string): FileContext {
+  let lineIndex: LineIndex | undefined;
+  let parsed: { imports: ImportItem[]; usages: Map<string, number> } | undefined;
+  const index = () => (lineIndex ??= new LineIndex(source));
+  const parse = () => (parsed ??= language === 'rust' ? parseRust(source, index()) : { imports: [], usages: new Map() });
+
+  return {
+    filePath,
+    language,
+    source,
+    get imports() {
+      return parse().imports;
+    },
+    get usages() {
+      return parse().usages;
+    },
+    positionAt: offset => index().positionAt(offset),
+    findingAt: (span, reason, symbols = [], confidence) => {
+      const { line, column } = index().positionAt(span.start);
+      const lineStart = source.lastIndexOf('\n', span.start - 1) + 1;
+      const lineEnd = source.indexOf('\n', span.start);
+      return {
+        line,
+        column,
+        span,
+        importStatement: statementText(source, lineStart, lineEnd === -1 ? source.length : lineEnd).trim(),
+        symbols,
+        reason,
+        ...(confidence ? { confidence } : {}),
+      };
+    },
+  };
+}
+
+function parseRust(source: string, lineIndex: LineIndex): { imports: ImportItem[]; usages: Map<string, number> } {
+  const tokens = tokenizeRust(source).filter(t => t.kind !== 'comment');
+  const declarations = parseUseDeclarations(tokens);
+  const externs = parseExternCrates(tokens);
+
+  const imports: ImportItem[] = [];
+  const item = (path: string[], binding: string, reexport: boolean, externCrate: boolean, module: string[], span: SourceSpan) =>
+    imports.push({ path, binding, reexport, externCrate, module, span, ...lineIndex.positionAt(span.start) });
+
+  for (const declaration of declarations) {
+    const module = declarationModule([], declaration);
+    for (const leaf of declaration.leaves) {
+      item(leaf.path, leaf.binding, reexportScope(declaration) !== 'private', false, module, { start: leaf.tree.start, end: leaf.tree.end });
+    }
+  }
+  for (const crate of externs) {
+    item([crate.name], crate.binding, crate.visibility !== '', true, [], { start: crate.start, end: crate.end });
+  }
+  imports.sort((a, b) => a.span.start - b.span.start);
+
+  const inItem = new Array<boolean>(tokens.length).fill(false);
+  for (const { tokenRange } of [...declarations, ...externs]) {
+    for (let i = tokenRange[0]; i <= tokenRange[1]; i++) inItem[i] = true;
+  }
+  const usages = new Map<string, number>();
+  tokens.forEach((token, i) => {
+    if (inItem[i] || token.kind !== 'ident') return;
+    const name = identifierName(token.text);
+    usages.set(name, (usages.get(name) ?? 0) + 1);
+  });
+
+  return { imports, usages };
+}
@@ -0,0 +1,1061 @@
+import { DiagnosticSink, FindingKind, printDiagnostic, statementText, UnusedImport } from './CLIAnalyzer';
+import { BannedImport } from './Config';
+import { TextEdit } from './FixEngine';
+import { LineIndex } from './LineIndex';
+import { CfgCondition, cfgConditions, cfgMayOverlap } from './RustCfg';
+import { findClosingDelimiter, identifierName, RustToken, tokenizeRust } from './RustLexer';
+import { LocalDefinition, RustShadowing } from './RustShadowing';
+import { RustSuppressions, Suppression } from './RustSuppressions';
+import { collectMethodCalls, collectTraitDefinitions, traitMethods } from './RustTraitMethods';
+import { declarationModule, RustCrate } from './RustCrate';
+import { doctestSource, extractDoctests } from './RustDoctests';
+import { BannedUse, findBannedUses } from './RustBannedImports';
+import { findQualifiedPaths, QualifiedPath } from './RustQualifiedPaths';
+import { findPreludeImports, PreludeImport, RustEdition } from './RustPrelude';
+import { recoverRustSyntax, SkippedRegion } from './RustRecovery';
+import { isImportedFromChild, RustTestCode, TestModule } from './RustTestOnly';
+import { ExternCrate, findMisplacedUses, parseExternCrates, parseUseDeclarations, reexportScope, UseDeclaration, UseLeaf, UseScope } from './RustUseTree';
+
+interface ImportedLeaf {
+  declaration: UseDeclaration;
+  leaf: UseLeaf;
+}
+
+interface Repetition {
+  kind: FindingKind;
+  original: ImportedLeaf;
+}
+
+/** Where an identifier that may refer to an import appears, and the distinct cfg conditions it appears under */
+interface IdentifierUses {
+  conditions: CfgCondition[];
+  positions: number[];
+}
+
+type UsedIdentifiers = Map<string, IdentifierUses>;
+
+/** Labels of local definitions in `shadowed-import` reasons */
+const DEFINITION_LABELS: Partial<Record<LocalDefinition['kind'], string>> = {
+  let: 'let binding',
+  parameter: 'parameter',
+};
+
+/** Method calls in a file and the traits it defines, for trait imports that are never named */
+interface MethodUsage {
+  calls: Set<string>;
+  traits: Map<string, string[]>;
+}
+
+/** A glob import `analyzeGlobs` couldn't check, and why */
+export interface SkippedGlob {
+  line: number;
+  importPath: string;
+  reason: string;
+}
+
+export interface GlobAnalysis {
+  /** Globs of crate modules that bring in nothing used in their scope */
+  unused: UnusedImport[];
+  /** One edit per used glob, replacing the `*` with the items used from it */
+  expansions: TextEdit[];
+  skipped: SkippedGlob[];
+}
+
+export interface RustAnalyzerOptions {
+  /**
+   * Ignore identifiers inside macro invocation bodies, `macro_rules!` definitions
+   * and attribute arguments when deciding whether an import is used
+   */
+  strictMacros?: boolean;
+  /** Report `#[allow(unused_imports)]` and `// importlens: ignore` markers that cover only used imports */
+  reportUnusedSuppressions?: boolean;
+  /** cfg options pinned with `--cfg`, normalized by `parseCfgOption`; other options may take any value */
+  cfg?: string[];
+  /** Also analyze the code blocks of doc comments, each as a file of its own */
+  checkDoctests?: boolean;
+  /** Report paths written out in full this many times or more in a module without an import */
+  qualifiedPathThreshold?: number;
+}
+
+/**
+ * Use-tree aware analyzer for Rust.
+ * Every leaf of a `use` tree is checked on its own, so in
+ * `use std::io::{self, Write, BufReader};` an unused `BufReader` is reported
+ * with the span of that leaf rather than the whole statement.
+ *
+ * A leaf that repeats an import already in scope is reported as a
+ * `duplicate-import` (same path twice in one scope) or `redundant-import`
+ * (covered by a glob, or re-imported in a nested block) instead.
+ *
+ * Leaves covered by `#[allow(unused_imports)]` / `#[expect(unused_imports)]` (on the
+ * item, an enclosing module or the crate root) or by an `// importlens: ignore`
+ * comment are not reported. With `reportUnusedSuppressions`, a marker that covers
+ * only used imports is reported as a `useless-suppression`.
+ *
+ * Traits are often imported only for their methods (`use std::io::Write;` for
+ * `file.write_all(..)` or `writeln!(..)`). An unnamed trait import whose methods
+ * are called, for standard library traits and traits defined in the same file, is
+ * still reported but with `low` confidence.
+ *
+ * `extern crate name;` items are reported when `name` is never used, unless they
+ * carry `#[macro_use]` or `#[allow(unused_extern_crates)]`, bind `_` or are re-exported.
+ *
+ * An import whose every use resolves to a local definition instead (a `fn`, `struct`,
+ * `let` binding or parameter of the same name in a nested scope) is reported as a
+ * `shadowed-import`, pointing at the definition. It is still used if any use reaches it.
+ *
+ * A `use` item in a block is only used by names inside that block. Raw identifiers
+ * match their plain spelling (`r#foo` is `foo`). `use` items in `impl`, `trait` and
+ * `extern` blocks aren't valid Rust; they are skipped with a warning on stderr.
+ *
+ * Renamed imports (`use std::collections::HashMap as Map;`) are used through their
+ * alias, and reasons name both. An alias that repeats the name it renames
+ * (`use foo::Bar as Bar;`) is a `redundant-alias`; `as _` imports always count as used.
+ *
+ * Re-exports (`pub use`, `pub(crate) use`, ...) are part of a module's interface and
+ * are never reported as unused by `analyzeFile`. `findUnusedReexports` checks the
+ * crate-visible ones against references from the rest of the crate.
+ *
+ * Glob imports can't be checked from one file. `analyzeGlobs` lists the items of the
+ * crate module a glob points at and checks those instead.
+ *
+ * Imports and uses behind `#[cfg(..)]` are matched by condition: an import gated on
+ * `cfg(unix)` is only used by code that can be compiled together with it, so a use
+ * behind `cfg(windows)` doesn't count. Conditions are compared conservatively (only
+ * clearly exclusive ones, like `unix` and `windows` or `x` and `not(x)`, never
+ * overlap), with the options pinned by `cfg` fixed. Imports that can't be compiled
+ * under the pinned options are not reported.
+ *
+ * Macros are not expanded. By default any identifier in a macro body counts as
+ * a potential use (`lazy_static! { static ref M: HashMap<..> = HashMap::new(); }`
+ * keeps `HashMap`); `strictMacros` turns that off, and imports named only in macro
+ * bodies get `medium` confidence. So do unused globs, whose modules may hold items
+ * that macros define.
+ *
+ * Doc comments are not code: a name in a doctest never uses an import of the file.
+ * With `checkDoctests`, the imports of each doctest are checked against its own code.
+ *
+ * With `qualifiedPathThreshold`, the inverse is reported too: a path written out in
+ * full that many times in a module (`std::collections::HashMap::new()`) without an
+ * import is an `over-qualified-path` (see `findQualifiedPaths`).
+ *
+ * `findUnnecessaryImports` reports imports the prelude makes unnecessary. It depends
+ * on the crate's edition, so it is not part of `analyzeFile`. Neither is
+ * `findTestOnlyImports`, which config can turn on per file.
+ *
+ * The passes besides `analyzeFile` take a file as `recoverRustSyntax` returns it:
+ * its source with syntax errors neutralized and the regions it skipped. Like those
+ * of `analyzeFile`, their findings in a file with skipped regions get `low` confidence.
+ */
+export class RustAnalyzer {
+  constructor(private options: RustAnalyzerOptions = {}) {}
+
+  /**
+   * Findings for one file. Warnings about items the analyzer skips go to `diagnose`;
+   * `filePath` only names the file in them.
+   *
+   * A file with syntax errors is analyzed without the items they are in (see
+   * `recoverRustSyntax`), with a note listing the lines skipped; its findings get
+   * `low` confidence, since the skipped code may use more than it seems to.
+   */
+  analyzeFile(content: string, filePath?: string, diagnose: DiagnosticSink = printDiagnostic): UnusedImport[] {
+    const { source, skipped, tokens } = recoverRustSyntax(content);
+    if (skipped.length === 0) return this.analyzeContent(content, filePath, diagnose, tokens);
+
+    const regions = skipped.map(region => {
+      const lines = region.startLine === region.endLine ? `line ${region.startLine}` : `lines ${region.startLine}-${region.endLine}`;
+      return `${lines} (${region.problems.join(', ')})`;
+    });
+    diagnose(`[NOTE] ${filePath ?? 'source'}: syntax errors, skipped ${regions.join('; ')}; findings in this file have low confidence`);
+    return degraded(this.analyzeContent(source, filePath, diagnose), skipped);
+  }
+
+  private analyzeContent(content: string, filePath: string | undefined, diagnose: DiagnosticSink, allTokens = tokenizeRust(content)): UnusedImport[] {
+    const tokens = allTokens.filter(t => t.kind !== 'comment');
+    const declarations = parseUseDeclarations(tokens);
+    const externs = parseExternCrates(tokens);
+    const misplaced = findMisplacedUses(tokens);
+    const conditions = cfgConditions(tokens);
+    const shadowing = new RustShadowing(tokens, conditions);
+    const usedIdentifiers = this.collectUsedIdentifiers(tokens, [...declarations, ...externs, ...misplaced], conditions, shadowing.names);
+    const overlaps = this.overlapCheck();
+    const suppressions = new RustSuppressions(content, allTokens, declarations);
+    const lineIndex = new LineIndex(content);
+
+    // Not valid Rust, so there is no telling what the item would import; the rest of the file is still checked
+    for (const item of misplaced) {
+      const line = lineIndex.positionAt(item.start).line;
+      const location = filePath ? `${filePath}:${line}` : `line ${line}`;
+      diagnose(`[WARNING] ${location}: skipping \`use\` item, which is not allowed in \`${item.owner}\` blocks`);
+    }
+    const methods: MethodUsage = { calls: collectMethodCalls(tokens), traits: collectTraitDefinitions(tokens) };
+    const macroNames = this.options.strictMacros ? macroIdentifiers(tokens) : new Set<string>();
+
+    const repetitions = this.findRepetitions(declarations, conditions, overlaps);
+
+    const unused: UnusedImport[] = [];
+    // Leaves each suppression covers, and whether it hid any finding
+    const coverage = new Map<Suppression, { leaves: UseLeaf[]; suppressed: boolean }>();
+
+    for (const declaration of declarations) {
+      const condition = conditions[declaration.tokenRange[0]];
+      // Not compiled with the pinned options, so nothing can be said about it
+      if (!overlaps(condition, [])) continue;
+
+      for (const leaf of declaration.leaves) {
+        const repetition = repetitions.get(leaf);
+        const isReexport = reexportScope(declaration) !== 'private';
+        const neverUsed = !isReexport && this.isUnused(declaration, leaf, usedIdentifiers, tokens, conditions, overlaps);
+        const shadowedBy = repetition || isReexport || neverUsed
+          ? undefined
+          : this.findShadowingDefinition(declaration, leaf, usedIdentifiers, tokens, shadowing, conditions, overlaps);
+        const finding = repetition
+          ? this.createRepetitionFinding(content, lineIndex, { declaration, leaf }, repetition)
+          : neverUsed ? this.createUnusedFinding(content, lineIndex, declaration, leaf, methods, macroNames)
+          : shadowedBy ? this.createShadowedFinding(content, lineIndex, tokens, declaration, leaf, shadowedBy) : undefined;
+
+        // Only reported for otherwise fine imports, and never what makes a suppression useful
+        const redundantAlias = finding ? undefined : this.createRedundantAliasFinding(content, lineIndex, declaration, leaf);
+
+        const suppression = suppressions.suppressionFor(leaf);
+        if (!suppression) {
+          if (finding) unused.push(finding);
+          if (redundantAlias) unused.push(redundantAlias);
+          continue;
+        }
+
+        // Globs, `as _` imports and re-exports can't be shown to be used here, so they don't make a suppression useless
+        if (!finding && (leaf.isGlob || leaf.binding === '_' || isReexport)) continue;
+        const entry = coverage.get(suppression) ?? { leaves: [], suppressed: false };
+        entry.leaves.push(leaf);
+        entry.suppressed = entry.suppressed || finding !== undefined;
+        coverage.set(suppression, entry);
+      }
+    }
+
+    if (this.options.reportUnusedSuppressions) {
+      for (const [suppression, { leaves, suppressed }] of coverage) {
+        if (!suppressed) unused.push(this.createSuppressionFinding(lineIndex, suppression, leaves));
+      }
+    }
+
+    for (const item of externs) {
+      if (this.isUsedExternCrate(item, usedIdentifiers, declarations)) continue;
+      unused.push(this.createExternCrateFinding(content, lineIndex, item));
+    }
+
+    const threshold = this.options.qualifiedPathThreshold;
+    if (threshold !== undefined) {
+      const qualified = findQualifiedPaths(tokens, declarations, [...declarations, ...externs], markMacroTokens(tokens), conditions, threshold);
+      unused.push(...qualified.map(path => this.createQualifiedPathFinding(content, lineIndex, path)));
+    }
+
+    if (this.options.checkDoctests) {
+      unused.push(...this.analyzeDoctests(content, allTokens, lineIndex, filePath, diagnose));
+    }
+
+    return unused;
+  }
+
+  /**
+   * Findings in the doctests of a file. Each doctest is analyzed as its own file, but
+   * with the rest of the file blanked out rather than removed, so positions need no mapping.
+   */
+  private analyzeDoctests(content: string, tokens: RustToken[], lineIndex: LineIndex, filePath: string | undefined, diagnose: DiagnosticSink): UnusedImport[] {
+    // Examples often spell paths out on purpose
+    const analyzer = new RustAnalyzer({ ...this.options, checkDoctests: false, qualifiedPathThreshold: undefined });
+
+    return extractDoctests(content, tokens).flatMap(doctest => {
+      const doctestLine = lineIndex.positionAt(doctest.start).line;
+      return analyzer.analyzeContent(doctestSource(content, doctest), filePath, diagnose).map(finding => ({
+        ...finding,
+        reason: `Doctest at line ${doctestLine}: ${finding.reason}`,
+        doctestLine,
+      }));
+    });
+  }
+
+  /**
+   * The `unused-import` findings of one file of a crate that other modules do use: a
+   * module's private imports are visible to its descendants, which can name them as
+   * `super::Name`, `crate::module::Name` or through `use super::*;`.
+   */
+  findCrateUsedImports(content: string, crate: RustCrate, modulePath: string[], findings: UnusedImport[]): UnusedImport[] {
+    const tokens = tokenizeRust(content).filter(t => t.kind !== 'comment');
+    const leaves = new Map<number, { leaf: UseLeaf; module: string[] }>();
+    for (const declaration of parseUseDeclarations(tokens)) {
+      const module = declarationModule(modulePath, declaration);
+      for (const leaf of declaration.leaves) leaves.set(leaf.tree.start, { leaf, module });
+    }
+
+    return findings.filter(finding => {
+      if ((finding.kind ?? 'unused-import') !== 'unused-import' || !finding.span) return false;
+      const match = leaves.get(finding.span.start);
+      return match !== undefined && crate.isReferenced(match.module, match.leaf.binding);
+    });
+  }
+
+  /**
+   * `banned-import` findings: imports and `extern crate` items that one of `bans`
+   * matches, and with `qualifiedPathThreshold` the paths written out in code that
+   * one does (see `findBannedUses`). `crate` and `modulePath` resolve relative paths.
+   */
+  findBannedImports(content: string, bans: BannedImport[], crate?: RustCrate, modulePath: string[] = [], skipped: SkippedRegion[] = []): UnusedImport[] {
+    if (bans.length === 0) return [];
+    const tokens = tokenizeRust(content).filter(t => t.kind !== 'comment');
+    const lineIndex = new LineIndex(content);
+    const uses = findBannedUses(tokens, parseUseDeclarations(tokens), parseExternCrates(tokens), bans, {
+      modulePath,
+      crate,
+      qualified: this.options.qualifiedPathThreshold !== undefined,
+    });
+    return degraded(uses.map(use => this.createBannedFinding(content, lineIndex, use)), skipped);
+  }
+
+  /**
+   * `unnecessary-import` findings: imports of what the prelude of `edition` already
+   * provides (see `findPreludeImports`). Without an edition, only what every
+   * edition's prelude has is reported.
+   */
+  findUnnecessaryImports(content: string, edition?: RustEdition, skipped: SkippedRegion[] = []): UnusedImport[] {
+    const allTokens = tokenizeRust(content);
+    const tokens = allTokens.filter(t => t.kind !== 'comment');
+    const declarations = parseUseDeclarations(tokens);
+    const suppressions = new RustSuppressions(content, allTokens, declarations);
+    const lineIndex = new LineIndex(content);
+
+    const unnecessary = findPreludeImports(tokens, declarations, edition ?? '2015')
+      .filter(({ leaf }) => !suppressions.suppressionFor(leaf))
+      .map(use => this.createPreludeFinding(content, lineIndex, use, edition));
+    return degraded(unnecessary, skipped);
+  }
+
+  /**
+   * `test-only-import` findings: private imports of a module, outside test code, that
+   * only code compiled for tests uses (`#[cfg(test)]` items and `#[test]` functions).
+   * When every use is in one `#[cfg(test)]` child module, the finding names it and its
+   * fix moves the import there. Imports a child module names as `super::Name` stay.
+   */
+  findTestOnlyImports(content: string, skipped: SkippedRegion[] = []): UnusedImport[] {
+    const allTokens = tokenizeRust(content);
+    const tokens = allTokens.filter(t => t.kind !== 'comment');
+    const declarations = parseUseDeclarations(tokens);
+    const conditions = cfgConditions(tokens);
+    const testCode = new RustTestCode(tokens, conditions);
+    const shadowing = new RustShadowing(tokens, conditions);
+    const usedIdentifiers = this.collectUsedIdentifiers(tokens, [...declarations, ...parseExternCrates(tokens)], conditions, shadowing.names);
+    const overlaps = this.overlapCheck();
+    const suppressions = new RustSuppressions(content, allTokens, declarations);
+    const lineIndex = new LineIndex(content);
+    const unused: UnusedImport[] = [];
+
+    for (const declaration of declarations) {
+      const importAt = declaration.tokenRange[0];
+      const condition = conditions[importAt];
+      // Gated imports already say when they are needed; block imports have nowhere to move to
+      if (reexportScope(declaration) !== 'private' || condition.length > 0) continue;
+      if (declaration.scopes.some(scope => scope.kind === 'block') || testCode.contains(importAt)) continue;
+
+      for (const leaf of declaration.leaves) {
+        if (leaf.isGlob || leaf.binding === '_' || suppressions.suppressionFor(leaf) || isImportedFromChild(declarations, leaf)) continue;
+        const uses = this.usesInScope(declaration, leaf, usedIdentifiers, tokens)
+          .filter(use => overlaps(condition, conditions[use]) && !shadowing.shadowing(use, importAt));
+        if (uses.length === 0 || !uses.every(use => testCode.contains(use))) continue;
+
+        const module = testCode.moduleOf(uses, testCode.enclosing[importAt]);
+        unused.push(this.createTestOnlyFinding(content, lineIndex, tokens, declaration, leaf, module));
+      }
+    }
+
+    return degraded(unused, skipped);
+  }
+
+  /**
+   * Find `pub(crate)`, `pub(super)` and `pub(in path)` re-exports in one file of a
+   * crate that are neither used in the file nor referenced from anywhere in the crate.
+   * Plain `pub use` items are public API and never reported.
+   */
+  findUnusedReexports(content: string, crate: RustCrate, modulePath: string[], skipped: SkippedRegion[] = []): UnusedImport[] {
+    const allTokens = tokenizeRust(content);
+    const tokens = allTokens.filter(t => t.kind !== 'comment');
+    const declarations = parseUseDeclarations(tokens);
+    const conditions = cfgConditions(tokens);
+    const { names } = new RustShadowing(tokens, conditions);
+    const usedIdentifiers = this.collectUsedIdentifiers(tokens, [...declarations, ...parseExternCrates(tokens)], conditions, names);
+    const overlaps = this.overlapCheck();
+    const suppressions = new RustSuppressions(content, allTokens, declarations);
+    const lineIndex = new LineIndex(content);
+    const unused: UnusedImport[] = [];
+
+    for (const declaration of declarations) {
+      if (reexportScope(declaration) !== 'restricted') continue;
+      const module = declarationModule(modulePath, declaration);
+      const condition = conditions[declaration.tokenRange[0]];
+      if (!overlaps(condition, [])) continue;
+
+      for (const leaf of declaration.leaves) {
+        if (!this.isUnused(declaration, leaf, usedIdentifiers, tokens, conditions, overlaps) || crate.isReferenced(module, leaf.binding)) continue;
+        if (suppressions.suppressionFor(leaf)) continue;
+
+        const finding = this.createFinding(content, lineIndex, declaration, leaf);
+        unused.push({
+          ...finding,
+          kind: 'unused-reexport',
+          reason: `Re-export ${describeLeaf(leaf)} is not used anywhere in the crate`,
+        });
+      }
+    }
+
+    return degraded(unused, skipped);
+  }
+
+  /**
+   * Check the private glob imports of one file of a crate that point at modules of the
+   * crate. A glob none of whose items is used in its scope is reported as unused; a
+   * used one gets an edit that lists just the items used (`use shapes::{Circle, area};`).
+   * Globs of other crates, of enums and of modules with glob imports of their own are
+   * skipped, and so is expanding any glob of a file with skipped regions.
+   */
+  analyzeGlobs(content: string, crate: RustCrate, modulePath: string[], skipped: SkippedRegion[] = []): GlobAnalysis {
+    const allTokens = tokenizeRust(content);
+    const tokens = allTokens.filter(t => t.kind !== 'comment');
+    const declarations = parseUseDeclarations(tokens);
+    const conditions = cfgConditions(tokens);
+    const shadowing = new RustShadowing(tokens, conditions);
+    const usedIdentifiers = this.collectUsedIdentifiers(tokens, [...declarations, ...parseExternCrates(tokens)], conditions, shadowing.names);
+    const overlaps = this.overlapCheck();
+    const suppressions = new RustSuppressions(content, allTokens, declarations);
+    const lineIndex = new LineIndex(content);
+    const analysis: GlobAnalysis = { unused: [], expansions: [], skipped: [] };
+
+    for (const declaration of declarations) {
+      const condition = conditions[declaration.tokenRange[0]];
+      if (reexportScope(declaration) !== 'private' || !overlaps(condition, [])) continue;
+      const module = declarationModule(modulePath, declaration);
+
+      for (const leaf of declaration.leaves) {
+        if (!leaf.isGlob) continue;
+        const importPath = leaf.path.join('::');
+        const line = lineIndex.positionAt(leaf.tree.start).line;
+
+        const target = crate.resolvePath(leaf.path.slice(0, -1), module);
+        if (!target) {
+          analysis.skipped.push({ line, importPath, reason: 'it points outside the crate' });
+          continue;
+        }
+        const items = crate.moduleItems(target);
+        if (!items) {
+          const reason = crate.isModule(target) ? 'its module has glob imports of its own' : 'it does not point at a module';
+          analysis.skipped.push({ line, importPath, reason });
+          continue;
+        }
+
+        // Private items are only visible from the module itself and its descendants
+        const inside = target.every((segment, i) => module[i] === segment);
+        const names = new Set(items.filter(item => item.isPublic || inside).map(item => item.name));
+        const used = this.usedGlobItems(tokens, declarations, declaration, names, usedIdentifiers, shadowing, conditions, overlaps);
+
+        if (used.length > 0 && skipped.length > 0) {
+          analysis.skipped.push({ line, importPath, reason: 'the file has syntax errors' });
+        } else if (used.length > 0) {
+          analysis.expansions.push({
+            start: leaf.tree.end - 1,
+            end: leaf.tree.end,
+            newText: used.length === 1 ? used[0] : `{${used.join(', ')}}`,
+          });
+        } else if (!suppressions.suppressionFor(leaf)) {
+          analysis.unused.push({
+            ...this.createFinding(content, lineIndex, declaration, leaf),
+            reason: `Glob ${importPath} not used in code: none of the items it imports is used`,
+            confidence: 'medium',
+            confidenceReason: 'glob-items-listed',
+          });
+        }
+      }
+    }
+
+    analysis.unused = degraded(analysis.unused, skipped);
+    return analysis;
+  }
+
+  /**
+   * Sorted names from a glob's module that are used in the glob's scope and not
+   * brought in there by another import or a local definition
+   */
+  private usedGlobItems(
+    tokens: RustToken[],
+    declarations: UseDeclaration[],
+    declaration: UseDeclaration,
+    names: Set<string>,
+    usedIdentifiers: UsedIdentifiers,
+    shadowing: RustShadowing,
+    conditions: CfgCondition[],
+    overlaps: (a: CfgCondition, b: CfgCondition) => boolean
+  ): string[] {
+    const importAt = declaration.tokenRange[0];
+    const condition = conditions[importAt];
+    const scope = declaration.scopes[declaration.scopes.length - 1];
+    const open = scope ? tokens.findIndex(token => token.start === scope.start) : -1;
+    const close = open === -1 ? tokens.length : findClosingDelimiter(tokens, open);
+
+    const explicit = new Set(declarations
+      .filter(other => sameScope(other.scopes, declaration.scopes))
+      .flatMap(other => other.leaves.filter(leaf => !leaf.isGlob).map(leaf => leaf.binding)));
+
+    return [...names].filter(name => !explicit.has(name) && (usedIdentifiers.get(name)?.positions ?? []).some(use =>
+      use > open && use < close && overlaps(condition, conditions[use]) && !shadowing.shadowing(use, importAt)
+    )).sort();
+  }
+
+  /**
+   * Find leaves that import something already in scope. The later copy of a
+   * duplicate is the one reported, so the fix removes it and keeps the original.
+   */
+  private findRepetitions(
+    declarations: UseDeclaration[],
+    conditions: CfgCondition[],
+    overlaps: (a: CfgCondition, b: CfgCondition) => boolean
+  ): Map<UseLeaf, Repetition> {
+    const imports: ImportedLeaf[] = declarations.flatMap(declaration =>
+      declaration.leaves
+        .filter(leaf => leaf.binding !== '_')
+        .map(leaf => ({ declaration, leaf }))
+    );
+    const repetitions = new Map<UseLeaf, Repetition>();
+
+    // `#[cfg(unix)] use a::X;` and `#[cfg(windows)] use a::X;` are never compiled together
+    const together = (a: ImportedLeaf, b: ImportedLeaf) =>
+      overlaps(conditions[a.declaration.tokenRange[0]], conditions[b.declaration.tokenRange[0]]);
+
+    // Only globs and imports of the same name can repeat an import, so a group of
+    // thousands of generated names isn't compared pairwise
+    const globs: number[] = [];
+    const byName = new Map<string, number[]>();
+    imports.forEach(({ leaf }, index) => {
+      if (leaf.isGlob) {
+        globs.push(index);
+      } else {
+        const name = importName(leaf);
+        const indices = byName.get(name) ?? [];
+        indices.push(index);
+        byName.set(name, indices);
+      }
+    });
+
+    imports.forEach((current, index) => {
+      if (current.leaf.isGlob) return;
+      const scopes = current.declaration.scopes;
+      const sameName = byName.get(importName(current.leaf)) ?? [];
+
+      for (const other of sameName.filter(i => i < index).map(i => imports[i])) {
+        if (sameScope(other.declaration.scopes, scopes) && together(other, current)) {
+          repetitions.set(current.leaf, { kind: 'duplicate-import', original: other });
+          return;
+        }
+      }
+
+      for (const other of [...globs, ...sameName].sort((a, b) => a - b).map(i => imports[i])) {
+        if (other === current || !isVisibleFrom(other.declaration.scopes, scopes) || !together(other, current)) continue;
+
+        const coveredByGlob = other.leaf.isGlob && coversLeaf(other.leaf, current.leaf);
+        const importedOutside = !other.leaf.isGlob && !sameScope(other.declaration.scopes, scopes);
+        if (coveredByGlob || importedOutside) {
+          repetitions.set(current.leaf, { kind: 'redundant-import', original: other });
+          return;
+        }
+      }
+    });
+
+    return repetitions;
+  }
+
+  /**
+   * Glob imports can't be checked without resolving the target module,
+   * and `as _` imports exist only to bring a trait into scope.
+   */
+  private isUnused(
+    declaration: UseDeclaration,
+    leaf: UseLeaf,
+    usedIdentifiers: UsedIdentifiers,
+    tokens: RustToken[],
+    conditions: CfgCondition[],
+    overlaps: (a: CfgCondition, b: CfgCondition) => boolean
+  ): boolean {
+    if (leaf.isGlob || leaf.binding === '_') {
+      return false;
+    }
+    const condition = conditions[declaration.tokenRange[0]];
+    if (declaration.scopes.length === 0) {
+      return !usedIdentifiers.get(identifierName(leaf.binding))?.conditions.some(use => overlaps(condition, use));
+    }
+    return !this.usesInScope(declaration, leaf, usedIdentifiers, tokens).some(i => overlaps(condition, conditions[i]));
+  }
+
+  /**
+   * Token indexes of the identifiers that may name `leaf`: anywhere in the file for a
+   * file-level import, and only inside the braces of its block or module for others,
+   * so `{ use std::io::Read; } let _: &dyn Read;` leaves the import unused
+   */
+  private usesInScope(declaration: UseDeclaration, leaf: UseLeaf, usedIdentifiers: UsedIdentifiers, tokens: RustToken[]): number[] {
+    const positions = usedIdentifiers.get(identifierName(leaf.binding))?.positions ?? [];
+    const scope = declaration.scopes[declaration.scopes.length - 1];
+    if (!scope) return positions;
+    return positions.filter(i => tokens[i].start > scope.start && (scope.end === undefined || tokens[i].start < scope.end));
+  }
+
+  /**
+   * The local definition that every use of a used leaf, compiled together with it,
+   * resolves to instead of the import; undefined if any use may reach the import
+   */
+  private findShadowingDefinition(
+    declaration: UseDeclaration,
+    leaf: UseLeaf,
+    usedIdentifiers: UsedIdentifiers,
+    tokens: RustToken[],
+    shadowing: RustShadowing,
+    conditions: CfgCondition[],
+    overlaps: (a: CfgCondition, b: CfgCondition) => boolean
+  ): LocalDefinition | undefined {
+    if (leaf.isGlob || leaf.binding === '_') return undefined;
+    const condition = conditions[declaration.tokenRange[0]];
+    const uses = this.usesInScope(declaration, leaf, usedIdentifiers, tokens).filter(i => overlaps(condition, conditions[i]));
+
+    let first: LocalDefinition | undefined;
+    for (const use of uses) {
+      const definition = shadowing.shadowing(use, declaration.tokenRange[0]);
+      if (!definition) return undefined;
+      first = first ?? definition;
+    }
+    return first;
+  }
+
+  /**
+   * `cfgMayOverlap` with the pinned options, remembered per pair of conditions for one file
+   */
+  private overlapCheck(): (a: CfgCondition, b: CfgCondition) => boolean {
+    const pinned = this.options.cfg ?? [];
+    const cache = new Map<CfgCondition, Map<CfgCondition, boolean>>();
+
+    return (a, b) => {
+      if (a.length === 0 && b.length === 0 && pinned.length === 0) return true;
+      const row = cache.get(a) ?? new Map<CfgCondition, boolean>();
+      cache.set(a, row);
+      if (!row.has(b)) row.set(b, cfgMayOverlap(a, b, pinned));
+      return row.get(b)!;
+    };
+  }
+
+  /**
+   * Whether anything refers to the crate an `extern crate` item binds, or the item
+   * is kept for its side effects (`#[macro_use]`, `as _`) or as a re-export
+   */
+  private isUsedExternCrate(item: ExternCrate, usedIdentifiers: UsedIdentifiers, declarations: UseDeclaration[]): boolean {
+    if (item.visibility !== '' && item.visibility !== 'pub(self)') return true;
+    if (item.binding === '_' || item.name === 'self') return true;
+    if (/#\[macro_use\]|unused_extern_crates/.test(item.attributes)) return true;
+    if (usedIdentifiers.has(identifierName(item.binding))) return true;
+
+    // `use name::...` paths start at the crate too, and aren't counted as identifier uses
+    return declarations.some(declaration => declaration.leaves.some(leaf => {
+      const first = leaf.path[0] === '' ? leaf.path[1] : leaf.path[0];
+      return first === item.binding;
+    }));
+  }
+
+  /**
+   * Collect identifiers that can refer to an imported name: everything outside
+   * `use` and `extern crate` items except field/method names (`x.name`), path
+   * continuations (`a::name`) and the names of local definitions (`fn name`,
+   * `let name`), each with the cfg conditions it appears under.
+   */
+  private collectUsedIdentifiers(
+    tokens: RustToken[],
+    items: Array<{ tokenRange: [number, number] }>,
+    conditions: CfgCondition[],
+    definitionNames: Set<number>
+  ): UsedIdentifiers {
+    const used: UsedIdentifiers = new Map();
+    const inMacro = this.options.strictMacros ? markMacroTokens(tokens) : undefined;
+    const skipped = [...items].sort((a, b) => a.tokenRange[0] - b.tokenRange[0]);
+    let itemIndex = 0;
+
+    for (let i = 0; i < tokens.length; i++) {
+      const current = skipped[itemIndex];
+      if (current && i >= current.tokenRange[0]) {
+        i = current.tokenRange[1];
+        itemIndex++;
+        continue;
+      }
+
+      const token = tokens[i];
+      if (token.kind !== 'ident' || inMacro?.[i] || definitionNames.has(i)) continue;
+
+      const previous = tokens[i - 1]?.text;
+      if (previous === '.' || previous === '::' || previous === 'trait') continue;
+
+      // `r#foo` and `foo` are the same name
+      const name = identifierName(token.text);
+      const seen = used.get(name);
+      if (!seen) {
+        used.set(name, { conditions: [conditions[i]], positions: [i] });
+        continue;
+      }
+      if (!seen.conditions.includes(conditions[i])) seen.conditions.push(conditions[i]);
+      seen.positions.push(i);
+    }
+
+    return used;
+  }
+
+  private createRepetitionFinding(
+    content: string,
+    lineIndex: LineIndex,
+    current: ImportedLeaf,
+    repetition: Repetition
+  ): UnusedImport {
+    const { leaf } = repetition.original;
+    const position = lineIndex.positionAt(leaf.tree.start);
+    const finding = this.createFinding(content, lineIndex, current.declaration, current.leaf);
+    const path = current.leaf.path.join('::');
+
+    return {
+      ...finding,
+      kind: repetition.kind,
+      reason: leaf.isGlob
+        ? `\`${path}\` is already imported by \`${leaf.path.join('::')}\` at line ${position.line}`
+        : `\`${path}\` is already imported at line ${position.line}`,
+      original: {
+        line: position.line,
+        column: position.column,
+        span: { start: leaf.tree.start, end: leaf.tree.end },
+      },
+    };
+  }
+
+  /**
+   * Finding for a leaf that is never named, downgraded to `low` confidence when it is a
+   * trait whose methods are called, and to `medium` when it is only named in macro bodies
+   * (`macroNames`, with `strictMacros`)
+   */
+  private createUnusedFinding(
+    content: string,
+    lineIndex: LineIndex,
+    declaration: UseDeclaration,
+    leaf: UseLeaf,
+    methods: MethodUsage,
+    macroNames: Set<string>
+  ): UnusedImport {
+    const finding = this.createFinding(content, lineIndex, declaration, leaf);
+    const called = traitMethods(leaf.path, methods.traits)?.find(method => methods.calls.has(method));
+    if (!called) {
+      if (!macroNames.has(leaf.binding)) return finding;
+      return {
+        ...finding,
+        confidence: 'medium',
+        confidenceReason: 'only-seen-in-macro',
+        reason: `${finding.reason} outside macro bodies`,
+      };
+    }
+
+    return {
+      ...finding,
+      confidence: 'low',
+      confidenceReason: 'trait-method-heuristic',
+      reason: `Trait ${describeLeaf(leaf)} is never named, but \`${called}\` calls may need it in scope`,
+    };
+  }
+
+  private createShadowedFinding(
+    content: string,
+    lineIndex: LineIndex,
+    tokens: RustToken[],
+    declaration: UseDeclaration,
+    leaf: UseLeaf,
+    definition: LocalDefinition
+  ): UnusedImport {
+    const finding = this.createFinding(content, lineIndex, declaration, leaf);
+    const name = tokens[definition.index];
+    const position = lineIndex.positionAt(name.start);
+    const label = DEFINITION_LABELS[definition.kind] ?? definition.kind;
+
+    return {
+      ...finding,
+      kind: 'shadowed-import',
+      reason: `Symbol ${describeLeaf(leaf)} is shadowed by ${label} \`${definition.name}\` at line ${position.line}`,
+      original: {
+        line: position.line,
+        column: position.column,
+        span: { start: name.start, end: name.end },
+      },
+    };
+  }
+
+  /**
+   * Finding for `use foo::Bar as Bar;`, whose alias is the name it would bind anyway;
+   * undefined for other leaves. Its span is the `as` clause, which the fix drops.
+   */
+  private createRedundantAliasFinding(
+    content: string,
+    lineIndex: LineIndex,
+    declaration: UseDeclaration,
+    leaf: UseLeaf
+  ): UnusedImport | undefined {
+    const { alias, aliasStart } = leaf.tree;
+    if (alias === undefined || alias === '_' || aliasStart === undefined) return undefined;
+    const name = leaf.isSelf ? leaf.path[leaf.path.length - 2] : leaf.path[leaf.path.length - 1];
+    if (alias !== name) return undefined;
+
+    const position = lineIndex.positionAt(aliasStart);
+    const path = leaf.path.join('::');
+    return {
+      line: position.line,
+      column: position.column,
+      importStatement: statementText(content, declaration.start, declaration.end),
+      symbols: [alias],
+      reason: `Alias ${alias} for ${path} repeats its name`,
+      span: { start: aliasStart, end: leaf.tree.end },
+      kind: 'redundant-alias',
+      importPath: path,
+    };
+  }
+
+  /**
+   * Finding for a path written out too often, at its first use. The fix imports the
+   * path and shortens every use in `qualifiedUses`.
+   */
+  private createQualifiedPathFinding(content: string, lineIndex: LineIndex, qualified: QualifiedPath): UnusedImport {
+    const [first] = qualified.uses;
+    const position = lineIndex.positionAt(first.start);
+    const path = qualified.path.join('::');
+
+    return {
+      line: position.line,
+      column: position.column,
+      importStatement: statementText(content, first.start, first.end, ''),
+      symbols: [qualified.binding],
+      reason: `Path ${path} is written out ${qualified.uses.length} times without an import; \`use ${path};\` shortens it to ${qualified.binding}`,
+      span: { ...first },
+      kind: 'over-qualified-path',
+      importPath: path,
+      qualifiedUses: qualified.uses,
+    };
+  }
+
+  private createPreludeFinding(content: string, lineIndex: LineIndex, use: PreludeImport, edition?: RustEdition): UnusedImport {
+    const { declaration, leaf } = use;
+    const path = leaf.path.join('::');
+    const prelude = edition ? `the Rust ${edition} prelude` : 'the prelude';
+    const reason = {
+      'prelude-item': `${leaf.binding} is already in scope from ${prelude}`,
+      'prelude-glob': `\`${path}\` imports ${prelude}, which every module already has`,
+      'derive-macro': `${path} is only used by \`#[derive(${leaf.binding})]\`, and the derive macro is in the prelude`,
+    }[use.reason];
+
+    return { ...this.createFinding(content, lineIndex, declaration, leaf), reason, kind: 'unnecessary-import' };
+  }
+
+  private createTestOnlyFinding(
+    content: string,
+    lineIndex: LineIndex,
+    tokens: RustToken[],
+    declaration: UseDeclaration,
+    leaf: UseLeaf,
+    module: TestModule | undefined
+  ): UnusedImport {
+    const finding = this.createFinding(content, lineIndex, declaration, leaf);
+    const reason = `Symbol ${describeLeaf(leaf)} is only used by tests`;
+    if (!module) return { ...finding, reason, kind: 'test-only-import' };
+
+    const [open, close] = module.body;
+    return {
+      ...finding,
+      reason: `${reason}; it belongs in \`mod ${module.name}\``,
+      kind: 'test-only-import',
+      testModule: { name: module.name, span: { start: tokens[open].start, end: tokens[close].end } },
+    };
+  }
+
+  private createBannedFinding(content: string, lineIndex: LineIndex, use: BannedUse): UnusedImport {
+    const path = use.path[0] === '' ? `::${use.path.slice(1).join('::')}` : use.path.join('::');
+    const banned = `\`${path}\` is banned by \`${use.ban.path}\``;
+    const base = use.declaration && use.leaf
+      ? this.createFinding(content, lineIndex, use.declaration, use.leaf)
+      : use.item ? this.createExternCrateFinding(content, lineIndex, use.item)
+      : {
+        ...lineIndex.positionAt(use.span.start),
+        importStatement: statementText(content, use.span.start, use.span.end, ''),
+        symbols: [use.path[use.path.length - 1]],
+        reason: '',
+        span: { ...use.span },
+      };
+
+    return {
+      ...base,
+      reason: use.ban.message ? `${banned}: ${use.ban.message}` : banned,
+      kind: 'banned-import',
+      importPath: path,
+    };
+  }
+
+  private createExternCrateFinding(content: string, lineIndex: LineIndex, item: ExternCrate): UnusedImport {
+    const position = lineIndex.positionAt(item.start);
+
+    return {
+      line: position.line,
+      column: position.column,
+      importStatement: statementText(content, item.start, item.end),
+      symbols: [item.binding],
+      reason: `Extern crate ${item.name} not used in code`,
+      span: { start: item.start, end: item.end },
+      kind: 'unused-import',
+      importPath: item.name,
+    };
+  }
+
+  private createSuppressionFinding(lineIndex: LineIndex, suppression: Suppression, leaves: UseLeaf[]): UnusedImport {
+    const position = lineIndex.positionAt(suppression.start);
+    const paths = leaves.map(leaf => leaf.path.join('::'));
+
+    return {
+      line: position.line,
+      column: position.column,
+      importStatement: suppression.text,
+      symbols: leaves.map(leaf => leaf.binding),
+      reason: `\`${suppression.text}\` is not needed: ${paths.map(p => `\`${p}\``).join(', ')} ${paths.length === 1 ? 'is' : 'are'} used`,
+      span: { start: suppression.start, end: suppression.end },
+      kind: 'useless-suppression',
+      importPath: paths.join(', '),
+    };
+  }
+
+  private createFinding(
+    content: string,
+    lineIndex: LineIndex,
+    declaration: UseDeclaration,
+    leaf: UseLeaf
+  ): UnusedImport {
+    const position = lineIndex.positionAt(leaf.tree.start);
+
+    return {
+      line: position.line,
+      column: position.column,
+      importStatement: statementText(content, declaration.start, declaration.end),
+      symbols: [leaf.binding],
+      reason: `Symbol ${describeLeaf(leaf)} not used in code`,
+      span: { start: leaf.tree.start, end: leaf.tree.end },
+      kind: 'unused-import',
+      importPath: leaf.path.join('::'),
+    };
+  }
+}
+
+/**
+ * `findings` with `low` confidence if their file had regions skipped: the skipped
+ * code may use more than it seems to
+ */
+function degraded(findings: UnusedImport[], skipped: SkippedRegion[]): UnusedImport[] {
+  if (skipped.length === 0) return findings;
+  return findings.map(finding => ({ ...finding, confidence: 'low' as const, confidenceReason: 'parse-degraded' as const }));
+}
+
+/**
+ * How reasons name an import: `HashMap (std::collections::HashMap)`, or
+ * `std::collections::HashMap (as Map)` when it is renamed
+ */
+function describeLeaf(leaf: UseLeaf): string {
+  const path = leaf.path.join('::');
+  return leaf.alias !== undefined && leaf.alias !== '_' ? `${path} (as ${leaf.alias})` : `${leaf.binding} (${path})`;
+}
+
+const CLOSING_DELIMITERS: Record<string, string> = { '(': ')', '[': ']', '{': '}' };
+// Strict and reserved keywords: before `!` they start an expression, as in `return !(done)`
+const KEYWORDS = new Set([
+  'as', 'async', 'await', 'break', 'const', 'continue', 'crate', 'dyn', 'else', 'enum', 'extern', 'false',
+  'fn', 'for', 'if', 'impl', 'in', 'let', 'loop', 'match', 'mod', 'move', 'mut', 'pub', 'ref', 'return',
+  'self', 'Self', 'static', 'struct', 'super', 'trait', 'true', 'type', 'unsafe', 'use', 'where', 'while',
+  'abstract', 'become', 'box', 'do', 'final', 'gen', 'macro', 'override', 'priv', 'try', 'typeof', 'unsized',
+  'virtual', 'yield',
+]);
+
+/**
+ * Identifiers that appear in macro bodies or attribute arguments, the tokens
+ * `strictMacros` doesn't count as uses
+ */
+function macroIdentifiers(tokens: RustToken[]): Set<string> {
+  const flags = markMacroTokens(tokens);
+  return new Set(tokens.filter((token, i) => flags[i] && token.kind === 'ident').map(token => token.text));
+}
+
+/**
+ * Flag tokens whose meaning depends on macro expansion: bodies of `name!(...)`
+ * invocations and `macro_rules!` definitions, and attribute arguments such as
+ * `#[serde(with = "...")]`. Macro names, attribute paths and `#[derive(...)]`
+ * lists are real name references and stay unflagged.
+ */
+function markMacroTokens(tokens: RustToken[]): boolean[] {
+  const flags = new Array<boolean>(tokens.length).fill(false);
+  const markBody = (open: number) => {
+    const close = findClosingDelimiter(tokens, open);
+    for (let j = open + 1; j < close; j++) flags[j] = true;
+  };
+
+  for (let i = 0; i < tokens.length; i++) {
+    const token = tokens[i];
+
+    // name!(...), name![...], name!{...} and macro_rules! name { ... }; `r#return!` is a macro too
+    if (token.kind === 'ident' && !KEYWORDS.has(token.text) && tokens[i + 1]?.text === '!') {
+      let open = i + 2;
+      if (token.text === 'macro_rules' && tokens[open]?.kind === 'ident') open++;
+      if (tokens[open] && CLOSING_DELIMITERS[tokens[open].text]) {
+        markBody(open);
+      }
+      continue;
+    }
+
+    // #[path(args)], #[path = value] and inner #![...] attributes
+    if (token.text === '#') {
+      let open = i + 1;
+      if (tokens[open]?.text === '!') open++;
+      if (tokens[open]?.text !== '[') continue;
+
+      const close = findClosingDelimiter(tokens, open);
+      let j = open + 1;
+      while (j < close && (tokens[j].kind === 'ident' || tokens[j].text === '::')) j++;
+
+      const isDerive = j === open + 2 && tokens[open + 1].text === 'derive';
+      if (!isDerive) {
+        for (; j < close; j++) flags[j] = true;
+      }
+    }
+  }
+
+  return flags;
+}
+
+// Leaves with the same name import the same item under the same binding
+function importName(leaf: UseLeaf): string {
+  return [leaf.binding, ...leaf.path].map(identifierName).join('::');
+}
+
+function sameScope(a: UseScope[], b: UseScope[]): boolean {
+  return a.length === b.length && a.every((scope, i) => scope.start === b[i].start);
+}
+
+/**
+ * Imports of the `outer` scope apply in `inner` when `outer` encloses it
+ * without a `mod` boundary in between.
+ */
+function isVisibleFrom(outer: UseScope[], inner: UseScope[]): boolean {
+  if (outer.length > inner.length) return false;
+  if (!outer.every((scope, i) => scope.start === inner[i].start)) return false;
+  return inner.slice(outer.length).every(scope => scope.kind === 'block');
+}
+
+/**
+ * `use foo::*;` already brings in `foo::Bar` (but not a renamed `foo::Bar as Baz`)
+ */
+function coversLeaf(glob: UseLeaf, leaf: UseLeaf): boolean {
+  if (leaf.isSelf || leaf.binding !== leaf.path[leaf.path.length - 1]) return false;
+  const module = glob.path.slice(0, -1);
+  return leaf.path.length === module.length + 1 && module.every((segment, i) => segment === leaf.path[i]);
+}
@@ -0,0 +1,183 @@
+import { SourceSpan } from './CLIAnalyzer';
+import { BannedImport } from './Config';
+import { declarationModule, RustCrate } from './RustCrate';
+import { identifierName, RustToken } from './RustLexer';
+import { enclosingModules } from './RustQualifiedPaths';
+import { ExternCrate, UseDeclaration, UseLeaf } from './RustUseTree';
+
+/**
+ * An import, or a path written out in code, that a `banned` pattern matches
+ */
+export interface BannedUse {
+  /** Path as written, e.g. ['super', 'raw', 'Conn'], or ['', 'chrono', 'Utc'] for `::chrono::Utc` */
+  path: string[];
+  ban: BannedImport;
+  span: SourceSpan;
+  /** For a leaf of a use tree */
+  declaration?: UseDeclaration;
+  leaf?: UseLeaf;
+  /** For an `extern crate` item */
+  item?: ExternCrate;
+}
+
+export interface BannedUseOptions {
+  /** Module path of the file in its crate; empty for the crate root or a file on its own */
+  modulePath?: string[];
+  /** The file's crate, to resolve paths through its modules */
+  crate?: RustCrate;
+  /** Also check paths written out in code (`chrono::Utc::now()`) */
+  qualified?: boolean;
+}
+
+/** Whether a `banned` pattern is well-formed: `::`-separated names, `*` or names with `*` in them */
+export function isBannedPattern(pattern: string): boolean {
+  return /^(::)?(r#)?[A-Za-z0-9_*]+(::(r#)?[A-Za-z0-9_*]+)*$/.test(pattern.trim());
+}
+
+/**
+ * A test for the paths `pattern` bans: the paths it matches and every path inside
+ * them. `*` stands for any one segment, or any part of one (`chrono::Naive*`); a
+ * trailing `::*` changes nothing, so `chrono::*` bans `chrono` itself,
+ * `chrono::NaiveDate` and `chrono::prelude::Utc` alike.
+ */
+export function bannedPathMatcher(pattern: string): (path: string[]) => boolean {
+  const segments = pattern.trim().replace(/^::/, '').split('::').map(identifierName);
+  while (segments.length > 0 && segments[segments.length - 1] === '*') segments.pop();
+  const tests = segments.map(segment => new RegExp(`^${segment.split('*').map(escapeRegex).join('.*')}$`));
+
+  return path => {
+    const names = path[0] === '' ? path.slice(1) : path;
+    return names.length >= tests.length && tests.every((test, k) => test.test(names[k]));
+  };
+}
+
+/**
+ * Imports and `extern crate` items whose path one of `bans` matches, in file order,
+ * and with `qualified` the paths written out in code that do. A path is checked as
+ * written and as resolved from the crate root, so `super::raw::Conn` in `crate::db`
+ * is caught by `crate::db::raw::*`; a written-out path starting with an imported
+ * name is checked as the path it imports (`db::raw::query()` after `use crate::db;`),
+ * unless the import itself is already banned. Paths in macro bodies count too.
+ */
+export function findBannedUses(
+  tokens: RustToken[],
+  declarations: UseDeclaration[],
+  externs: ExternCrate[],
+  bans: BannedImport[],
+  options: BannedUseOptions = {}
+): BannedUse[] {
+  const { modulePath = [], crate, qualified = false } = options;
+  const matchers = bans.map(ban => ({ ban, matches: bannedPathMatcher(ban.path) }));
+  const banFor = (written: string[], resolved: string[]) =>
+    matchers.find(({ matches }) => matches(written) || matches(resolved))?.ban;
+
+  const found: BannedUse[] = [];
+  // Per module: the path each imported name stands for, and whether that import is banned
+  const bindings = new Map<string, Map<string, { path: string[]; banned: boolean }>>();
+  const bind = (module: string[], name: string, path: string[], banned: boolean) => {
+    const key = module.join('::');
+    const names = bindings.get(key) ?? new Map<string, { path: string[]; banned: boolean }>();
+    names.set(name, { path, banned });
+    bindings.set(key, names);
+  };
+
+  for (const declaration of declarations) {
+    const module = declarationModule(modulePath, declaration);
+    for (const leaf of declaration.leaves) {
+      const written = (leaf.isGlob || leaf.isSelf ? leaf.path.slice(0, -1) : leaf.path).map(identifierName);
+      if (written.length === 0) continue;
+
+      const resolved = resolvePath(written, module, crate);
+      const ban = banFor(written, resolved);
+      if (ban) found.push({ path: written, ban, span: { start: leaf.tree.start, end: leaf.tree.end }, declaration, leaf });
+      if (!leaf.isGlob && leaf.binding !== '_') bind(module, leaf.binding, resolved, ban !== undefined);
+    }
+  }
+
+  for (const item of externs) {
+    if (item.name === 'self') continue;
+    const ban = banFor([item.name], [item.name]);
+    if (ban) found.push({ path: [item.name], ban, span: { start: item.start, end: item.end }, item });
+    bind(modulePath, item.binding, [item.name], ban !== undefined);
+  }
+
+  if (qualified) found.push(...findBannedPaths(tokens, [...declarations, ...externs], modulePath, crate, bindings, banFor));
+
+  return found.sort((a, b) => a.span.start - b.span.start);
+}
+
+function findBannedPaths(
+  tokens: RustToken[],
+  items: Array<{ tokenRange: [number, number] }>,
+  modulePath: string[],
+  crate: RustCrate | undefined,
+  bindings: Map<string, Map<string, { path: string[]; banned: boolean }>>,
+  banFor: (written: string[], resolved: string[]) => BannedImport | undefined
+): BannedUse[] {
+  const inItem = new Array<boolean>(tokens.length).fill(false);
+  for (const item of items) {
+    for (let j = item.tokenRange[0]; j <= item.tokenRange[1]; j++) inItem[j] = true;
+  }
+
+  // Module path of each inline `mod name { ... }`, by the index of its `{`
+  const modules = enclosingModules(tokens);
+  const inlinePaths = new Map<number, string[]>([[-1, modulePath]]);
+  const inlinePath = (open: number): string[] => {
+    let path = inlinePaths.get(open);
+    if (!path) {
+      path = [...inlinePath(modules[open - 2] ?? -1), identifierName(tokens[open - 1].text)];
+      inlinePaths.set(open, path);
+    }
+    return path;
+  };
+
+  const found: BannedUse[] = [];
+  for (let i = 0; i < tokens.length; i++) {
+    if (inItem[i] || tokens[i].kind !== 'ident' || tokens[i + 1]?.text !== '::') continue;
+    const previous = tokens[i - 1];
+    if (previous?.text === '.') continue;
+    // `::chrono::Utc`, but not the `new` of `Vec::<u8>::new`
+    const rooted = previous?.text === '::' && tokens[i - 2]?.kind !== 'ident' && tokens[i - 2]?.text !== '>';
+    if (previous?.text === '::' && !rooted) continue;
+
+    let last = i;
+    while (tokens[last + 1]?.text === '::' && tokens[last + 2]?.kind === 'ident') last += 2;
+    const names: string[] = [];
+    for (let j = i; j <= last; j += 2) names.push(identifierName(tokens[j].text));
+    const start = rooted ? i - 1 : i;
+    i = last;
+
+    const module = inlinePath(modules[start]);
+    const imported = rooted ? undefined : bindings.get(module.join('::'))?.get(names[0]);
+    if (imported?.banned) continue;
+
+    const written = rooted ? ['', ...names] : names;
+    const resolved = imported ? [...imported.path, ...names.slice(1)] : rooted ? names : resolvePath(names, module, crate);
+    const ban = banFor(written, resolved);
+    if (ban) found.push({ path: written, ban, span: { start: tokens[start].start, end: tokens[last].end } });
+  }
+
+  return found;
+}
+
+/**
+ * `written`, a path used in `module`, from the crate root (`crate::db::raw::Conn`)
+ * when it points into the crate, or else as written
+ */
+export function resolvePath(written: string[], module: string[], crate: RustCrate | undefined): string[] {
+  if (written[0] === '') return written.slice(1);
+  const inCrate = crate?.resolvePath(written, module);
+  if (inCrate) return ['crate', ...inCrate];
+
+  if (written[0] === 'self') return ['crate', ...module, ...written.slice(1)];
+  if (written[0] === 'super') {
+    let up = 0;
+    while (written[up] === 'super') up++;
+    if (up <= module.length) return ['crate', ...module.slice(0, module.length - up), ...written.slice(up)];
+  }
+  return written;
+}
+
+function escapeRegex(text: string): string {
+  return text.replace(/[.+?^${}()|[\]\\]/g, '\\$&');
+}
@@ -0,0 +1,217 @@
+import { findClosingDelimiter, RustToken } from './RustLexer';
+
+/**
+ * A `cfg(...)` predicate: `unix`, `feature = "x"`, `all(..)`, `any(..)` or `not(..)`
+ */
+export type CfgPredicate =
+  | { kind: 'option'; option: string }
+  | { kind: 'all' | 'any'; predicates: CfgPredicate[] }
+  | { kind: 'not'; predicate: CfgPredicate };
+
+/**
+ * Predicates that must all hold for a piece of code to be compiled; empty when
+ * the code is not gated at all
+ */
+export type CfgCondition = CfgPredicate[];
+
+/** Options that take one value per target, so `target_os = "linux"` rules out `target_os = "macos"` */
+const SINGLE_VALUED = new Set([
+  'target_os', 'target_family', 'target_arch', 'target_env', 'target_vendor',
+  'target_pointer_width', 'target_endian', 'panic',
+]);
+
+/** `unix` and `windows` are shorthands for the target family */
+const ALIASES: Record<string, string> = {
+  unix: 'target_family="unix"',
+  windows: 'target_family="windows"',
+};
+
+const ITEM_MODIFIERS = new Set(['pub', 'unsafe', 'async', 'const', 'extern', 'default']);
+const ITEM_KEYWORDS = new Set([
+  'fn', 'struct', 'enum', 'union', 'impl', 'trait', 'mod', 'type', 'static', 'use', 'macro_rules', 'crate',
+]);
+
+// Beyond this many distinct options, overlap is assumed rather than searched for
+const MAX_OPTIONS = 16;
+
+/**
+ * Normalize a `--cfg` value to the form options take in predicates:
+ * `unix`, `feature=foo` or `feature="foo"` become `target_family="unix"` and `feature="foo"`
+ */
+export function parseCfgOption(value: string): string {
+  const match = /^\s*([A-Za-z_][A-Za-z0-9_]*)\s*(?:=\s*(.*?)\s*)?$/.exec(value);
+  if (!match) {
+    throw new Error(`Invalid --cfg value: ${value}. Expected name or key=value`);
+  }
+  const [, name, rawValue] = match;
+  if (rawValue === undefined) return ALIASES[name] ?? name;
+  const unquoted = /^"(.*)"$/.exec(rawValue)?.[1] ?? rawValue;
+  return `${name}="${unquoted}"`;
+}
+
+/**
+ * The condition each token is compiled under. Outer `#[cfg(..)]` attributes gate
+ * the item (or statement, field, arm) they are attached to; an inner `#![cfg(..)]`
+ * gates the rest of its module or file. Nested gates add up.
+ */
+export function cfgConditions(tokens: RustToken[]): CfgCondition[] {
+  const ungated: CfgCondition = [];
+  const conditions = new Array<CfgCondition>(tokens.length).fill(ungated);
+  const open: number[] = [];
+
+  for (let i = 0; i < tokens.length; i++) {
+    const text = tokens[i].text;
+    if (text === '{' || text === '(' || text === '[') open.push(i);
+    else if (text === '}' || text === ')' || text === ']') open.pop();
+
+    if (text !== '#') continue;
+    const inner = tokens[i + 1]?.text === '!';
+    const bracket = inner ? i + 2 : i + 1;
+    if (tokens[bracket]?.text !== '[' || tokens[bracket + 1]?.text !== 'cfg' || tokens[bracket + 2]?.text !== '(') continue;
+
+    const close = findClosingDelimiter(tokens, bracket + 2);
+    const predicate = parsePredicate(tokens, bracket + 3, close);
+    if (!predicate) continue;
+
+    let end: number;
+    if (inner) {
+      const enclosing = open[open.length - 1];
+      end = enclosing === undefined ? tokens.length - 1 : findClosingDelimiter(tokens, enclosing) - 1;
+    } else {
+      end = itemEnd(tokens, findClosingDelimiter(tokens, bracket) + 1);
+    }
+
+    // Tokens that shared a condition keep sharing one, so callers can compare by identity
+    const extended = new Map<CfgCondition, CfgCondition>();
+    for (let j = i; j <= end && j < tokens.length; j++) {
+      const current = conditions[j];
+      if (!extended.has(current)) extended.set(current, [...current, predicate]);
+      conditions[j] = extended.get(current)!;
+    }
+  }
+
+  return conditions;
+}
+
+/**
+ * Whether some configuration compiles code under both conditions, given options
+ * pinned with `--cfg`. Unknown options may take any value, so this only answers
+ * false for conditions that clearly exclude each other, like `unix` and
+ * `windows`, or `feature = "a"` and `not(feature = "a")`.
+ */
+export function cfgMayOverlap(a: CfgCondition, b: CfgCondition, pinned: string[] = []): boolean {
+  const predicates = [...a, ...b];
+  if (predicates.length === 0) return true;
+
+  const options = new Set<string>(pinned);
+  predicates.forEach(predicate => collectOptions(predicate, options));
+  const names = [...options];
+  if (names.length > MAX_OPTIONS) return true;
+
+  const forced = new Set(pinned);
+  for (let mask = 0; mask < 1 << names.length; mask++) {
+    const enabled = new Set(names.filter((_, i) => mask & (1 << i)));
+    if ([...forced].some(name => !enabled.has(name))) continue;
+    if (!isConsistent(enabled)) continue;
+    if (predicates.every(predicate => evaluate(predicate, enabled))) return true;
+  }
+  return false;
+}
+
+/**
+ * Whether code under `condition` is compiled at all, given the pinned options
+ */
+export function cfgMayHold(condition: CfgCondition, pinned: string[] = []): boolean {
+  return cfgMayOverlap(condition, [], pinned);
+}
+
+function parsePredicate(tokens: RustToken[], start: number, end: number): CfgPredicate | undefined {
+  const token = tokens[start];
+  if (!token || token.kind !== 'ident') return undefined;
+
+  if ((token.text === 'all' || token.text === 'any' || token.text === 'not') && tokens[start + 1]?.text === '(') {
+    const close = findClosingDelimiter(tokens, start + 1);
+    const predicates: CfgPredicate[] = [];
+    let itemStart = start + 2;
+    let depth = 0;
+    for (let j = start + 2; j <= close; j++) {
+      const text = tokens[j].text;
+      if (text === '(') depth++;
+      else if (text === ')' && j !== close) depth--;
+      if ((text === ',' && depth === 0) || j === close) {
+        if (j > itemStart) {
+          const predicate = parsePredicate(tokens, itemStart, j);
+          if (!predicate) return undefined;
+          predicates.push(predicate);
+        }
+        itemStart = j + 1;
+      }
+    }
+
+    if (token.text === 'not') {
+      return predicates.length === 1 ? { kind: 'not', predicate: predicates[0] } : undefined;
+    }
+    return token.text === 'all' ? { kind: 'all', predicates } : { kind: 'any', predicates };
+  }
+
+  if (tokens[start + 1]?.text === '=' && tokens[start + 2]?.kind === 'literal' && start + 3 >= end) {
+    return { kind: 'option', option: `${token.text}=${tokens[start + 2].text}` };
+  }
+  return start + 1 >= end ? { kind: 'option', option: ALIASES[token.text] ?? token.text } : undefined;
+}
+
+/**
+ * Last token of the item starting at `start` (after its attributes): up to a `;`
+ * or the end of its first `{...}` body. Fields, arguments and match arms also end
+ * at a `,` on their level; items don't, since `Result<T, E>` has one.
+ */
+function itemEnd(tokens: RustToken[], start: number): number {
+  let j = start;
+  while (tokens[j] && ITEM_MODIFIERS.has(tokens[j].text)) {
+    j = tokens[j + 1]?.text === '(' ? findClosingDelimiter(tokens, j + 1) + 1 : j + 1;
+  }
+  // `extern "C" fn`
+  if (tokens[j]?.kind === 'literal' && tokens[j - 1]?.text === 'extern') j++;
+  const isItem = ITEM_KEYWORDS.has(tokens[j]?.text ?? '') || tokens[j - 1]?.text === 'const';
+
+  while (j < tokens.length) {
+    const text = tokens[j].text;
+    if (text === ';') return j;
+    if (text === ',' && !isItem) return j - 1;
+    if (text === '}' || text === ')' || text === ']') return j - 1;
+    if (text === '{') return findClosingDelimiter(tokens, j);
+    j = text === '(' || text === '[' ? findClosingDelimiter(tokens, j) + 1 : j + 1;
+  }
+  return tokens.length - 1;
+}
+
+function collectOptions(predicate: CfgPredicate, options: Set<string>): void {
+  if (predicate.kind === 'option') options.add(predicate.option);
+  else if (predicate.kind === 'not') collectOptions(predicate.predicate, options);
+  else predicate.predicates.forEach(p => collectOptions(p, options));
+}
+
+function evaluate(predicate: CfgPredicate, enabled: Set<string>): boolean {
+  switch (predicate.kind) {
+    case 'option':
+      return enabled.has(predicate.option);
+    case 'not':
+      return !evaluate(predicate.predicate, enabled);
+    case 'all':
+      return predicate.predicates.every(p => evaluate(p, enabled));
+    case 'any':
+      return predicate.predicates.some(p => evaluate(p, enabled));
+  }
+}
+
+/** At most one value per single-valued option */
+function isConsistent(enabled: Set<string>): boolean {
+  const keys = new Set<string>();
+  for (const option of enabled) {
+    const key = option.split('=')[0];
+    if (!option.includes('=') || !SINGLE_VALUED.has(key)) continue;
+    if (keys.has(key)) return false;
+    keys.add(key);
+  }
+  return true;
+}
@@ -0,0 +1,473 @@
+import * as fs from 'fs';
+import * as path from 'path';
+import { LineIndex } from './LineIndex';
+import { toPosixPath } from './Paths';
+import { findClosingDelimiter, RustToken, tokenizeRust } from './RustLexer';
+import { RecoveredSource, recoverRustSyntax, SkippedRegion } from './RustRecovery';
+import { itemNameIndex } from './RustShadowing';
+import { parseToml } from './Toml';
+import { parseUseDeclarations, reexportScope, UseDeclaration } from './RustUseTree';
+
+/**
+ * A source file of a crate with the module path it is mounted at
+ */
+export interface CrateFile {
+  filePath: string;
+  /** The source with its syntax errors neutralized (see `recoverRustSyntax`) */
+  content: string;
+  /** The regions `recoverRustSyntax` skipped, if the file has syntax errors */
+  skipped: SkippedRegion[];
+  /** Module path from the crate root, e.g. ['net', 'http'] for `src/net/http.rs` */
+  modulePath: string[];
+}
+
+/**
+ * A name a module defines or imports, which a glob import of the module can bring in
+ */
+export interface ModuleItem {
+  name: string;
+  /** Private items are only visible to the module itself and its descendants */
+  isPublic: boolean;
+}
+
+/**
+ * A problem with the module tree, reported at the `mod` item that caused it
+ */
+export interface CrateDiagnostic {
+  filePath: string;
+  line: number;
+  message: string;
+}
+
+/**
+ * The files of one crate, found by following `mod name;` declarations from its root
+ * file (`src/lib.rs`, `src/main.rs`, `src/bin/*.rs` or the `--crate-root`), and the
+ * item paths they refer to. Module files are looked up like rustc does: `name.rs` or
+ * `name/mod.rs`, or the file a `#[path = "..."]` attribute names.
+ *
+ * Paths are resolved without expanding macros: `crate::`, `self::` and `super::`
+ * prefixes, child modules of the current module and names brought in by `use` items
+ * of the same module are understood. Anything else is assumed to come from another crate.
+ * Files with syntax errors are read as `recoverRustSyntax` recovers them, so their
+ * brackets can't put later items in the wrong module, and names in the items it skips
+ * are still references.
+ */
+export class RustCrate {
+  readonly files: CrateFile[] = [];
+  /** Module files that don't exist, and files mounted twice, e.g. by a `#[path]` cycle */
+  readonly diagnostics: CrateDiagnostic[] = [];
+  private modules = new Set<string>();
+  /** Resolved paths referenced anywhere in the crate, and every prefix of them */
+  private references = new Set<string>();
+  /** Modules whose contents are imported with a glob */
+  private globs = new Set<string>();
+
+  private constructor(rootFile: string) {
+    this.loadModule(rootFile, [], path.dirname(rootFile), undefined);
+    this.collectReferences();
+  }
+
+  /**
+   * The crate a file belongs to: the one rooted at `crateRoot` when given, otherwise
+   * one of the crates of its `Cargo.toml`. Crates are cached in `crates` by root file,
+   * so files of the same crate share one instance.
+   */
+  static forFile(filePath: string, crates: Map<string, RustCrate>, crateRoot?: string): RustCrate {
+    const resolved = path.resolve(filePath);
+    const load = (root: string) => {
+      let crate = crates.get(root);
+      if (!crate) {
+        crate = new RustCrate(root);
+        crates.set(root, crate);
+      }
+      return crate;
+    };
+
+    for (const root of crateRoot !== undefined ? [path.resolve(crateRoot)] : candidateRoots(resolved)) {
+      const crate = load(root);
+      if (crate.fileFor(resolved)) return crate;
+    }
+
+    // Not reachable from a crate root: treat the file as a crate of its own
+    return load(resolved);
+  }
+
+  fileFor(filePath: string): CrateFile | undefined {
+    const resolved = path.resolve(filePath);
+    return this.files.find(file => file.filePath === resolved);
+  }
+
+  /**
+   * Whether any file of the crate refers to the item `name` of `module`,
+   * e.g. as `crate::module::name`, `super::name` or through a glob import of `module`
+   */
+  isReferenced(module: string[], name: string): boolean {
+    const key = module.join('::');
+    return this.references.has(key === '' ? name : `${key}::${name}`) || this.globs.has(key);
+  }
+
+  /**
+   * Resolve a path written in `module` to a path from the crate root, or undefined
+   * if it points outside the crate (or through an import)
+   */
+  resolvePath(segments: string[], module: string[]): string[] | undefined {
+    return this.resolve(segments, module, undefined);
+  }
+
+  isModule(module: string[]): boolean {
+    return this.modules.has(module.join('::'));
+  }
+
+  /**
+   * The items of a module of the crate: its items and the names its `use` items bind.
+   * Undefined when `module` is not a module of the crate, or when it has glob imports
+   * of its own, whose items can't be listed without resolving them too.
+   */
+  moduleItems(module: string[]): ModuleItem[] | undefined {
+    const key = module.join('::');
+    if (!this.isModule(module)) return undefined;
+    const items: ModuleItem[] = [];
+
+    for (const file of this.files) {
+      const isAncestor = file.modulePath.length <= module.length && file.modulePath.every((segment, i) => module[i] === segment);
+      if (!isAncestor) continue;
+
+      const tokens = tokenizeRust(file.content).filter(t => t.kind !== 'comment');
+      const moduleAt = inlineModules(tokens, file.modulePath);
+      const atModuleLevel = moduleLevelTokens(tokens);
+      for (let i = 0; i < tokens.length; i++) {
+        if (!atModuleLevel[i] || moduleAt[i].join('::') !== key) continue;
+        const name = itemNameIndex(tokens, i);
+        if (name !== undefined) items.push({ name: tokens[name].text, isPublic: isPublicItem(tokens, i) });
+      }
+
+      for (const declaration of parseUseDeclarations(tokens)) {
+        if (declaration.scopes.some(scope => scope.kind === 'block') || declarationModule(file.modulePath, declaration).join('::') !== key) continue;
+        for (const leaf of declaration.leaves) {
+          if (leaf.isGlob) return undefined;
+          if (leaf.binding !== '_') items.push({ name: leaf.binding, isPublic: reexportScope(declaration) !== 'private' });
+        }
+      }
+    }
+
+    return items;
+  }
+
+  /**
+   * Load the file of a module and, through its `mod name;` items, its descendants.
+   * `moduleDir` is where the module's children live; `declaredAt` is the `mod` item
+   * that led here, for diagnostics.
+   */
+  private loadModule(filePath: string, modulePath: string[], moduleDir: string, declaredAt: Omit<CrateDiagnostic, 'message'> | undefined): void {
+    const loaded = this.files.find(file => file.filePath === filePath);
+    if (loaded) {
+      if (declaredAt) {
+        const existing = loaded.modulePath.length === 0 ? 'the crate root' : `module \`${loaded.modulePath.join('::')}\``;
+        this.diagnostics.push({ ...declaredAt, message: `module \`${modulePath.join('::')}\` points at ${filePath}, which is already ${existing}; not loading it again` });
+      }
+      return;
+    }
+
+    let recovered: RecoveredSource;
+    try {
+      recovered = recoverRustSyntax(fs.readFileSync(filePath, 'utf-8'));
+    } catch {
+      return;
+    }
+
+    const { source: content, skipped } = recovered;
+    this.files.push({ filePath, content, skipped, modulePath });
+    this.modules.add(modulePath.join('::'));
+
+    const tokens = tokenizeRust(content).filter(t => t.kind !== 'comment');
+    let lineIndex: LineIndex | undefined;
+    forEachModuleItem(tokens, (inline, name, isFile, at) => {
+      const childPath = [...modulePath, ...inline, name];
+      this.modules.add(childPath.join('::'));
+      if (!isFile) return;
+
+      const dir = path.join(moduleDir, ...inline);
+      const attribute = pathAttribute(tokens, at);
+      // `#[path]` is relative to the file's directory, or inside inline modules to theirs
+      const candidates = attribute !== undefined
+        ? [path.resolve(inline.length > 0 ? dir : path.dirname(filePath), attribute)]
+        : [path.join(dir, `${name}.rs`), path.join(dir, name, 'mod.rs')];
+      const childFile = candidates.find(candidate => fs.existsSync(candidate) && fs.statSync(candidate).isFile());
+
+      lineIndex = lineIndex ?? new LineIndex(content);
+      const declaration = { filePath, line: lineIndex.positionAt(tokens[at].start).line };
+      if (!childFile) {
+        const looked = candidates.map(candidate => toPosixPath(path.relative(path.dirname(filePath), candidate)));
+        this.diagnostics.push({ ...declaration, message: `file not found for module \`${childPath.join('::')}\` (looked for ${looked.join(', ')})` });
+        return;
+      }
+
+      // Files named by `#[path]` own their directory, like `mod.rs` files
+      this.loadModule(childFile, childPath, attribute !== undefined ? path.dirname(childFile) : path.join(dir, name), declaration);
+    });
+  }
+
+  private collectReferences(): void {
+    for (const file of this.files) {
+      const tokens = tokenizeRust(file.content).filter(t => t.kind !== 'comment');
+      const declarations = parseUseDeclarations(tokens);
+      const moduleAt = inlineModules(tokens, file.modulePath);
+
+      // Names imported by `use` items, per module
+      const imports = new Map<string, Map<string, string[]>>();
+      for (const declaration of declarations) {
+        const module = declarationModule(file.modulePath, declaration);
+        const key = module.join('::');
+        const bindings = imports.get(key) ?? new Map<string, string[]>();
+        imports.set(key, bindings);
+
+        for (const leaf of declaration.leaves) {
+          const leafPath = leaf.isSelf ? leaf.path.slice(0, -1) : leaf.path;
+          if (leaf.isGlob) {
+            const target = this.resolve(leafPath.slice(0, -1), module, undefined);
+            if (target) this.globs.add(target.join('::'));
+          } else if (leaf.binding !== '_') {
+            bindings.set(leaf.binding, leafPath);
+          }
+        }
+      }
+
+      const addReference = (segments: string[], module: string[]) => {
+        const resolved = this.resolve(segments, module, imports.get(module.join('::')));
+        for (let i = 1; resolved && i <= resolved.length; i++) {
+          this.references.add(resolved.slice(0, i).join('::'));
+        }
+      };
+
+      for (const declaration of declarations) {
+        const module = declarationModule(file.modulePath, declaration);
+        for (const leaf of declaration.leaves) {
+          if (!leaf.isGlob) addReference(leaf.isSelf ? leaf.path.slice(0, -1) : leaf.path, module);
+        }
+      }
+
+      // Paths in code: `a::b::c` sequences outside `use` items
+      let declarationIndex = 0;
+      for (let i = 0; i < tokens.length; i++) {
+        const current = declarations[declarationIndex];
+        if (current && i >= current.tokenRange[0]) {
+          i = current.tokenRange[1];
+          declarationIndex++;
+          continue;
+        }
+        if (tokens[i].kind !== 'ident' || tokens[i - 1]?.text === '::' || tokens[i + 1]?.text !== '::') continue;
+
+        const segments = [tokens[i].text];
+        let j = i;
+        while (tokens[j + 1]?.text === '::' && tokens[j + 2]?.kind === 'ident') {
+          segments.push(tokens[j + 2].text);
+          j += 2;
+        }
+        addReference(segments, moduleAt[i]);
+        i = j;
+      }
+    }
+  }
+
+  /**
+   * Resolve a path written in `module` to a path from the crate root, or undefined
+   * if it points outside the crate
+   */
+  private resolve(segments: string[], module: string[], imports: Map<string, string[]> | undefined, depth = 0): string[] | undefined {
+    const [first, ...rest] = segments;
+    if (first === undefined || depth > 8) return undefined;
+
+    if (first === 'crate') return rest;
+    if (first === 'self') return [...module, ...rest];
+    if (first === 'super') {
+      let i = 0;
+      while (segments[i] === 'super') i++;
+      if (i > module.length) return undefined;
+      return [...module.slice(0, module.length - i), ...segments.slice(i)];
+    }
+    if (this.modules.has([...module, first].join('::'))) return [...module, ...segments];
+
+    const imported = imports?.get(first);
+    if (imported && imported !== segments) {
+      const target = this.resolve(imported, module, imports, depth + 1);
+      return target && [...target, ...rest];
+    }
+
+    return undefined;
+  }
+}
+
+/**
+ * Module path of the scope a `use` item is declared in
+ */
+export function declarationModule(fileModule: string[], declaration: UseDeclaration): string[] {
+  return [...fileModule, ...declaration.scopes.filter(scope => scope.kind === 'module').map(scope => scope.name!)];
+}
+
+/**
+ * Call `visit` for every `mod name;` and `mod name { ... }` item, with the inline
+ * modules enclosing it and the index of its `mod` token
+ */
+function forEachModuleItem(tokens: RustToken[], visit: (inline: string[], name: string, isFile: boolean, at: number) => void): void {
+  const scopes: (string | undefined)[] = [];
+
+  for (let i = 0; i < tokens.length; i++) {
+    const text = tokens[i].text;
+    if (text === '{') {
+      scopes.push(tokens[i - 2]?.text === 'mod' && tokens[i - 1]?.kind === 'ident' ? tokens[i - 1].text : undefined);
+    } else if (text === '}') {
+      scopes.pop();
+    } else if (text === 'mod' && tokens[i + 1]?.kind === 'ident') {
+      const next = tokens[i + 2]?.text;
+      // Modules inside function bodies can't be reached by path from elsewhere
+      if ((next === ';' || next === '{') && scopes.every(scope => scope !== undefined)) {
+        visit(scopes as string[], tokens[i + 1].text, next === ';', i);
+      }
+    }
+  }
+}
+
+/**
+ * The value of a `#[path = "..."]` attribute of the `mod` item whose keyword is at `at`,
+ * looking back over its visibility and other attributes
+ */
+function pathAttribute(tokens: RustToken[], at: number): string | undefined {
+  let i = at - 1;
+  if (tokens[i]?.text === ')') {
+    while (i > 0 && tokens[i].text !== '(') i--;
+    i--;
+  }
+  if (tokens[i]?.text === 'pub') i--;
+
+  while (tokens[i]?.text === ']') {
+    let open = i;
+    while (open > 0 && (tokens[open].text !== '[' || findClosingDelimiter(tokens, open) !== i)) open--;
+    if (tokens[open - 1]?.text !== '#') return undefined;
+
+    const [name, equals, value] = [tokens[open + 1], tokens[open + 2], tokens[open + 3]];
+    if (name?.text === 'path' && equals?.text === '=' && value?.kind === 'literal' && open + 4 === i) {
+      return literalValue(value.text);
+    }
+    i = open - 2;
+  }
+  return undefined;
+}
+
+/** The contents of a string literal token: `"a\\b.rs"` or `r"a\b.rs"` */
+function literalValue(text: string): string | undefined {
+  const raw = /^r(#*)"([\s\S]*)"\1$/.exec(text);
+  if (raw) return raw[2];
+  if (!text.startsWith('"') || !text.endsWith('"')) return undefined;
+  return text.slice(1, -1).replace(/\\(.)/g, (_, char: string) => (char === 'n' ? '\n' : char === 't' ? '\t' : char));
+}
+
+/**
+ * Whether each token is directly in a module, rather than in a function body,
+ * `impl` block or other item
+ */
+function moduleLevelTokens(tokens: RustToken[]): boolean[] {
+  const braces: boolean[] = [];
+  let other = 0;
+
+  return tokens.map((token, i) => {
+    if (token.text === '}') {
+      if (braces.pop() === false) other--;
+      return other === 0;
+    }
+    const level = other === 0;
+    if (token.text === '{') {
+      const isModule = tokens[i - 2]?.text === 'mod' && tokens[i - 1]?.kind === 'ident';
+      braces.push(isModule);
+      if (!isModule) other++;
+    }
+    return level;
+  });
+}
+
+const QUALIFIERS = new Set(['const', 'async', 'unsafe', 'extern', 'default']);
+
+/**
+ * Whether the item whose keyword is at `at` has a `pub` visibility, looking back
+ * over qualifiers such as `const`, `async`, `unsafe` and `extern "C"`
+ */
+function isPublicItem(tokens: RustToken[], at: number): boolean {
+  let i = at - 1;
+  while (i >= 0 && (QUALIFIERS.has(tokens[i].text) || tokens[i].kind === 'literal')) i--;
+  if (tokens[i]?.text === ')') {
+    let open = i;
+    while (open > 0 && (tokens[open].text !== '(' || findClosingDelimiter(tokens, open) !== i)) open--;
+    i = open - 1;
+  }
+  return tokens[i]?.text === 'pub';
+}
+
+/**
+ * Module path in effect at every token: the file's module plus enclosing inline modules
+ */
+function inlineModules(tokens: RustToken[], fileModule: string[]): string[][] {
+  const stack: string[][] = [fileModule];
+  const braces: boolean[] = [];
+
+  return tokens.map((token, i) => {
+    if (token.text === '{') {
+      const isModule = tokens[i - 2]?.text === 'mod' && tokens[i - 1]?.kind === 'ident';
+      braces.push(isModule);
+      if (isModule) stack.push([...stack[stack.length - 1], tokens[i - 1].text]);
+    } else if (token.text === '}') {
+      if (braces.pop()) stack.pop();
+    }
+    return stack[stack.length - 1];
+  });
+}
+
+/**
+ * The `Cargo.toml` nearest to a file, looking in its directory and every parent
+ */
+export function findManifest(filePath: string): string | undefined {
+  let dir = path.dirname(path.resolve(filePath));
+  while (!fs.existsSync(path.join(dir, 'Cargo.toml'))) {
+    const parent = path.dirname(dir);
+    if (parent === dir) return undefined;
+    dir = parent;
+  }
+  return path.join(dir, 'Cargo.toml');
+}
+
+/**
+ * Package name of a `Cargo.toml`, or the name of its directory when the manifest has
+ * none or can't be read. Names are kept in `cache`, keyed by manifest, when one is given.
+ */
+export function packageName(manifest: string, cache?: Map<string, string>): string {
+  const cached = cache?.get(manifest);
+  if (cached !== undefined) return cached;
+
+  let name = path.basename(path.dirname(manifest));
+  try {
+    const table = parseToml(fs.readFileSync(manifest, 'utf-8')).package;
+    if (table && typeof table === 'object' && !Array.isArray(table) && typeof table.name === 'string') name = table.name;
+  } catch {
+    // A malformed manifest still names the crate after its directory
+  }
+  cache?.set(manifest, name);
+  return name;
+}
+
+/**
+ * Root files of the crates a file may belong to, from its nearest `Cargo.toml`
+ */
+function candidateRoots(filePath: string): string[] {
+  const manifest = findManifest(filePath);
+  if (!manifest) return [];
+
+  const src = path.join(path.dirname(manifest), 'src');
+  const roots = [path.join(src, 'lib.rs'), path.join(src, 'main.rs')];
+  const binDir = path.join(src, 'bin');
+  if (fs.existsSync(binDir)) {
+    for (const entry of fs.readdirSync(binDir, { withFileTypes: true })) {
+      if (entry.isFile() && entry.name.endsWith('.rs')) roots.push(path.join(binDir, entry.name));
+      else if (entry.isDirectory()) roots.push(path.join(binDir, entry.name, 'main.rs'));
+    }
+  }
+
+  return roots.filter(root => fs.existsSync(root));
+}
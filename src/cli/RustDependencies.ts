@@ -0,0 +1,271 @@
+import * as fs from 'fs';
+import * as path from 'path';
+import { UnusedImport } from './CLIAnalyzer';
+import { DirectoryWalker } from './DirectoryWalker';
+import { LineIndex } from './LineIndex';
+import { findClosingDelimiter, RustToken, tokenizeRust } from './RustLexer';
+import { parseExternCrates, parseUseDeclarations } from './RustUseTree';
+import { parseToml, TomlTable, TomlValue } from './Toml';
+
+/** Which `Cargo.toml` table a dependency is declared in */
+export type DependencyKind = 'normal' | 'dev' | 'build';
+
+const DEPENDENCY_TABLES: Record<string, DependencyKind> = {
+  'dependencies': 'normal',
+  'dev-dependencies': 'dev',
+  'build-dependencies': 'build',
+};
+
+/**
+ * Derive macros that imply a package even when nothing names it in a path,
+ * e.g. `#[derive(Serialize)]` with `#[macro_use] extern crate serde_derive`
+ */
+const DERIVE_PACKAGES: Record<string, string[]> = {
+  Serialize: ['serde', 'serde_derive'],
+  Deserialize: ['serde', 'serde_derive'],
+};
+
+/**
+ * A dependency declared in a `Cargo.toml`
+ */
+export interface Dependency {
+  /** Key in the manifest; code refers to the crate by this name, with `-` written as `_` */
+  key: string;
+  /** Package name: `package = "..."` for renamed dependencies, otherwise the key */
+  packageName: string;
+  kind: DependencyKind;
+  /** Offsets of the key in the manifest, if it could be located */
+  span?: { start: number; end: number };
+}
+
+/**
+ * Crates a set of source files refers to
+ */
+interface References {
+  /** Crate names used as the first segment of a path, in `use` items or in `extern crate` */
+  crates: Set<string>;
+  /** Packages implied by derive macros and their helper attributes */
+  packages: Set<string>;
+}
+
+/**
+ * Read the dependencies of a manifest: `[dependencies]`, `[dev-dependencies]`,
+ * `[build-dependencies]`, their `[target.'cfg(..)'.*]` variants and
+ * `[dependencies.name]` tables. Throws on malformed TOML.
+ */
+export function parseDependencies(manifest: string): Dependency[] {
+  const table = parseToml(manifest);
+  const dependencies: Dependency[] = [];
+  const seen = new Set<string>();
+
+  const collect = (parent: TomlTable) => {
+    for (const [tableName, kind] of Object.entries(DEPENDENCY_TABLES)) {
+      const entries = parent[tableName];
+      if (!isTable(entries)) continue;
+
+      for (const [key, value] of Object.entries(entries)) {
+        if (seen.has(`${kind}:${key}`)) continue;
+        seen.add(`${kind}:${key}`);
+        const packageName = isTable(value) && typeof value.package === 'string' ? value.package : key;
+        dependencies.push({ key, packageName, kind, span: locateKey(manifest, tableName, key) });
+      }
+    }
+  };
+
+  collect(table);
+  if (isTable(table.target)) {
+    Object.values(table.target).filter(isTable).forEach(collect);
+  }
+
+  return dependencies;
+}
+
+/**
+ * Report dependencies of the package at `manifestPath` that no source file refers to.
+ * Normal dependencies are looked for in `src/`, `tests/`, `benches/` and `examples/`;
+ * dev-dependencies only in `tests/`, `benches/`, `examples/` and `#[cfg(test)]` code
+ * under `src/`; build-dependencies only in the build script.
+ */
+export function findUnusedDependencies(manifestPath: string): UnusedImport[] {
+  const manifest = fs.readFileSync(manifestPath, 'utf-8');
+  const dependencies = parseDependencies(manifest);
+  if (dependencies.length === 0) return [];
+
+  const dir = path.dirname(manifestPath);
+  const walker = new DirectoryWalker({ extensions: ['.rs'], hidden: false, exclude: [], excludeBase: dir });
+  const walk = (name: string) => fs.existsSync(path.join(dir, name)) ? walker.walk(path.join(dir, name)) : [];
+
+  const sources = walk('src').map(read);
+  const targets = [...walk('tests'), ...walk('benches'), ...walk('examples')].map(read);
+  const buildScript = buildScriptPath(manifest, dir);
+
+  const normal = collectReferences([...sources, ...targets], false);
+  const dev = mergeReferences(collectReferences(targets, false), collectReferences(sources, true));
+  const build = collectReferences(fs.existsSync(buildScript) ? [read(buildScript)] : [], false);
+
+  const lineIndex = new LineIndex(manifest);
+  const lines = manifest.split('\n');
+
+  return dependencies
+    .filter(dependency => !isReferenced(dependency, { normal, dev, build }[dependency.kind]))
+    .map(dependency => {
+      const position = dependency.span ? lineIndex.positionAt(dependency.span.start) : { line: 1, column: 1 };
+      const table = { normal: 'Dependency', dev: 'Dev-dependency', build: 'Build-dependency' }[dependency.kind];
+      const where = {
+        normal: 'any source file',
+        dev: 'tests, benches, examples or #[cfg(test)] code',
+        build: 'the build script',
+      }[dependency.kind];
+
+      return {
+        line: position.line,
+        column: position.column,
+        importStatement: (lines[position.line - 1] ?? '').trim(),
+        symbols: [dependency.key],
+        reason: `${table} ${dependency.key} is not used by ${where}`,
+        span: dependency.span,
+        kind: 'unused-dependency' as const,
+        importPath: dependency.packageName,
+      };
+    });
+}
+
+function isReferenced(dependency: Dependency, references: References): boolean {
+  return references.crates.has(crateName(dependency.key)) || references.packages.has(crateName(dependency.packageName));
+}
+
+/** Name a package is referred to by in code */
+function crateName(name: string): string {
+  return name.replace(/-/g, '_');
+}
+
+function collectReferences(contents: string[], testOnly: boolean): References {
+  const references: References = { crates: new Set(), packages: new Set() };
+
+  for (const content of contents) {
+    let tokens = tokenizeRust(content).filter(t => t.kind !== 'comment');
+    if (testOnly) tokens = testTokens(tokens);
+
+    for (const declaration of parseUseDeclarations(tokens)) {
+      for (const leaf of declaration.leaves) {
+        references.crates.add(leaf.path[0] === '' ? leaf.path[1] : leaf.path[0]);
+      }
+    }
+    for (const item of parseExternCrates(tokens)) {
+      references.crates.add(item.name);
+    }
+
+    tokens.forEach((token, i) => {
+      if (token.kind !== 'ident') return;
+      const previous = tokens[i - 1]?.text;
+
+      // First segment of `name::...`, including `::name::...`
+      if (tokens[i + 1]?.text === '::' && (previous !== '::' || tokens[i - 2]?.kind !== 'ident')) {
+        references.crates.add(token.text);
+      }
+
+      // `#[derive(Serialize)]` and helper attributes such as `#[serde(rename = "..")]`
+      if (previous === '[' && tokens[i - 2]?.text === '#') {
+        if (token.text === 'derive' && tokens[i + 1]?.text === '(') {
+          const close = findClosingDelimiter(tokens, i + 1);
+          for (let j = i + 2; j < close; j++) {
+            if (tokens[j - 1].text === '::') continue;
+            DERIVE_PACKAGES[tokens[j].text]?.forEach(name => references.packages.add(name));
+          }
+        } else {
+          references.packages.add(token.text);
+        }
+      }
+    });
+  }
+
+  return references;
+}
+
+function mergeReferences(a: References, b: References): References {
+  return { crates: new Set([...a.crates, ...b.crates]), packages: new Set([...a.packages, ...b.packages]) };
+}
+
+/**
+ * Tokens of the items marked `#[cfg(test)]` (every token, for a file starting with `#![cfg(test)]`)
+ */
+function testTokens(tokens: RustToken[]): RustToken[] {
+  if (tokens[0]?.text === '#' && tokens[1]?.text === '!' && isCfgTest(tokens, 2)) return tokens;
+
+  const selected: RustToken[] = [];
+  for (let i = 0; i < tokens.length; i++) {
+    if (tokens[i].text !== '#' || !isCfgTest(tokens, i + 1)) continue;
+
+    // The item runs to its body's closing brace, or to a `;` before any body
+    let j = findClosingDelimiter(tokens, i + 1) + 1;
+    while (j < tokens.length && tokens[j].text !== ';' && tokens[j].text !== '{') {
+      j = tokens[j].text === '(' || tokens[j].text === '[' ? findClosingDelimiter(tokens, j) + 1 : j + 1;
+    }
+    const end = tokens[j]?.text === '{' ? findClosingDelimiter(tokens, j) : j;
+
+    selected.push(...tokens.slice(i, end + 1));
+    i = end;
+  }
+  return selected;
+}
+
+/** Whether the attribute whose `[` is at `open` is `cfg(test)` or `cfg(all/any(.., test, ..))` */
+function isCfgTest(tokens: RustToken[], open: number): boolean {
+  if (tokens[open]?.text !== '[' || tokens[open + 1]?.text !== 'cfg') return false;
+  const inner = tokens.slice(open + 2, findClosingDelimiter(tokens, open)).map(t => t.text);
+  return inner.includes('test') && !inner.includes('not');
+}
+
+function buildScriptPath(manifest: string, dir: string): string {
+  try {
+    const build = (parseToml(manifest).package as TomlTable | undefined)?.build;
+    if (typeof build === 'string') return path.join(dir, build);
+  } catch {
+    // Reported by parseDependencies
+  }
+  return path.join(dir, 'build.rs');
+}
+
+/**
+ * Offsets of `key` in `[table]` (also `[target.*.table]`), or of `[table.key]`
+ */
+function locateKey(manifest: string, tableName: string, key: string): { start: number; end: number } | undefined {
+  const quoted = `["']?${key.replace(/[.*+?^${}()|[\]\\]/g, '\\$&')}["']?`;
+  const keyLine = new RegExp(`^[ \\t]*(${quoted})[ \\t]*[=.]`);
+  const tableHeader = new RegExp(`^[ \\t]*\\[(?:target\\..+\\.)?${tableName}\\.(${quoted})\\][ \\t]*(?:#.*)?$`);
+  const sectionHeader = new RegExp(`^[ \\t]*\\[(?:target\\..+\\.)?${tableName}\\][ \\t]*(?:#.*)?$`);
+
+  let offset = 0;
+  let inSection = false;
+  for (const line of manifest.split('\n')) {
+    const header = tableHeader.exec(line);
+    if (header) {
+      const start = offset + line.indexOf(header[1]);
+      return { start, end: start + header[1].length };
+    }
+    if (/^[ \t]*\[/.test(line)) {
+      inSection = sectionHeader.test(line);
+    } else if (inSection) {
+      const match = keyLine.exec(line);
+      if (match) {
+        const start = offset + line.indexOf(match[1]);
+        return { start, end: start + match[1].length };
+      }
+    }
+    offset += line.length + 1;
+  }
+
+  return undefined;
+}
+
+function read(filePath: string): string {
+  try {
+    return fs.readFileSync(filePath, 'utf-8');
+  } catch {
+    return '';
+  }
+}
+
+function isTable(value: TomlValue | undefined): value is TomlTable {
+  return typeof value === 'object' && !Array.isArray(value);
+}
@@ -0,0 +1,168 @@
+import { SourceSpan } from './CLIAnalyzer';
+import { RustToken } from './RustLexer';
+
+/**
+ * A fenced Rust code block from a doc comment, as the source rustdoc would compile
+ */
+export interface Doctest {
+  /** Offset of the opening fence in the file */
+  start: number;
+  /** Range of the code on each line, without the comment prefix */
+  lines: SourceSpan[];
+}
+
+/** A doc comment line without its `///`, `//!` or `*` prefix */
+interface DocLine {
+  text: string;
+  /** Offset of `text` in the file */
+  start: number;
+}
+
+const FENCE = /^\s*(`{3,}|~{3,})\s*(.*)$/;
+// Attributes of compiled Rust blocks; any other word is another language, or `ignore` or `compile_fail`
+const RUST_ATTRIBUTES = /^(?:rust|should_panic|no_run|test_harness|standalone_crate|edition\d{4})$/;
+
+/**
+ * Extract the Rust code blocks of the file's doc comments: `///` and `//!` lines and
+ * `/**` and `/*!` blocks. Consecutive doc comments of the same kind form one doc
+ * block, and a fence left open ends with its block.
+ *
+ * Blocks whose info string names another language (`text`, `toml`, ...) are left out,
+ * as are `ignore` and `compile_fail` blocks. Hidden lines (`# use std::fmt;`) are kept
+ * without their `# `, since rustdoc still compiles them.
+ */
+export function extractDoctests(content: string, tokens: RustToken[]): Doctest[] {
+  const doctests: Doctest[] = [];
+  for (const block of docBlocks(content, tokens)) {
+    doctests.push(...codeBlocks(block));
+  }
+  return doctests;
+}
+
+/** Lines of each run of doc comments that document the same item */
+function docBlocks(content: string, tokens: RustToken[]): DocLine[][] {
+  const blocks: DocLine[][] = [];
+  let current: DocLine[] = [];
+  let currentStyle: string | undefined;
+  let previousEnd = -1;
+
+  for (const token of tokens) {
+    const style = docStyle(token);
+    const adjacent = previousEnd !== -1 && content.slice(previousEnd, token.start).trim() === '';
+    if (!style || style !== currentStyle || !adjacent) {
+      if (current.length > 0) blocks.push(current);
+      current = [];
+      currentStyle = undefined;
+    }
+    if (!style) continue;
+
+    current.push(...docLines(token));
+    currentStyle = style;
+    previousEnd = token.end;
+  }
+
+  if (current.length > 0) blocks.push(current);
+  return blocks;
+}
+
+/**
+ * `outer` for `///` and `/**` comments, `inner` for `//!` and `/*!`; undefined for
+ * other comments and tokens. `////` and `/***` are plain comments, as in rustc.
+ */
+function docStyle(token: RustToken): string | undefined {
+  if (token.kind !== 'comment') return undefined;
+  const text = token.text;
+  if (text.startsWith('//!') || text.startsWith('/*!')) return 'inner';
+  if (text.startsWith('///') && !text.startsWith('////')) return 'outer';
+  if (text.startsWith('/**') && !text.startsWith('/***') && text !== '/**/') return 'outer';
+  return undefined;
+}
+
+function docLines(token: RustToken): DocLine[] {
+  if (token.text.startsWith('//')) {
+    return [{ text: token.text.slice(3), start: token.start + 3 }];
+  }
+
+  // Block doc comment: one line per source line, without the ` * ` decoration
+  // when every line after the first has it
+  const body = token.text.slice(3, -2);
+  const lines: DocLine[] = [];
+  let offset = token.start + 3;
+  for (const text of body.split('\n')) {
+    lines.push({ text: text.replace(/\r$/, ''), start: offset });
+    offset += text.length + 1;
+  }
+
+  const decorated = lines.slice(1).filter(line => line.text.trim() !== '');
+  if (decorated.length > 0 && decorated.every(line => /^\s*\*/.test(line.text))) {
+    for (const line of lines.slice(1)) {
+      const prefix = /^\s*\*?/.exec(line.text)![0].length;
+      line.text = line.text.slice(prefix);
+      line.start += prefix;
+    }
+  }
+  return lines;
+}
+
+function codeBlocks(lines: DocLine[]): Doctest[] {
+  const doctests: Doctest[] = [];
+  let fence: { marker: string; start: number; checked: boolean } | undefined;
+  let code: DocLine[] = [];
+
+  const close = () => {
+    if (fence?.checked && code.length > 0) doctests.push(toDoctest(fence.start, code));
+    fence = undefined;
+    code = [];
+  };
+
+  for (const line of lines) {
+    const match = FENCE.exec(line.text);
+    if (!fence) {
+      if (match) fence = { marker: match[1], start: line.start, checked: isCheckedRust(match[2]) };
+      continue;
+    }
+
+    const marker = fence.marker;
+    if (match && match[1][0] === marker[0] && match[1].length >= marker.length && match[2].trim() === '') {
+      close();
+    } else {
+      code.push(line);
+    }
+  }
+
+  close();
+  return doctests;
+}
+
+/** Whether a block with this info string is Rust that rustdoc compiles */
+function isCheckedRust(info: string): boolean {
+  return info.split(/[\s,]+/).filter(word => word !== '').every(word => RUST_ATTRIBUTES.test(word));
+}
+
+function toDoctest(start: number, code: DocLine[]): Doctest {
+  const lines = code.map(line => {
+    // `# ` lines are hidden from the rendered docs but still compiled
+    const indent = /^\s*/.exec(line.text)![0].length;
+    const hidden = /^#(?: |$)/.exec(line.text.slice(indent));
+    // `##` escapes a line that really starts with `#`
+    const skipped = hidden ? indent + hidden[0].length : line.text.slice(indent).startsWith('##') ? indent + 1 : 0;
+    return { start: line.start + skipped, end: line.start + line.text.length };
+  });
+  return { start, lines };
+}
+
+/**
+ * The file with everything but the doctest's code blanked out, so that offsets,
+ * lines and columns found in it are those of the file
+ */
+export function doctestSource(content: string, doctest: Doctest): string {
+  const blank = (text: string) => text.replace(/[^\r\n]/g, ' ');
+  let source = '';
+  let offset = 0;
+
+  for (const line of doctest.lines) {
+    source += blank(content.slice(offset, line.start)) + content.slice(line.start, line.end);
+    offset = line.end;
+  }
+  return source + blank(content.slice(offset));
+}
@@ -0,0 +1,376 @@
+import { UnusedImport } from './CLIAnalyzer';
+import { TextEdit } from './FixEngine';
+import { startOfLine } from './LineIndex';
+import { RustToken, tokenizeRust } from './RustLexer';
+import { blockOf, comparePaths } from './RustOrganizer';
+import { enclosingModules, moduleBodyStart } from './RustQualifiedPaths';
+import { childModulePath } from './RustTestOnly';
+import { ExternCrate, parseExternCrates, parseUseDeclarations, UseDeclaration, UseTree } from './RustUseTree';
+
+/** A file's tokens and the items the fixer edits */
+interface ParsedFile {
+  content: string;
+  allTokens: RustToken[];
+  tokens: RustToken[];
+  declarations: UseDeclaration[];
+  externs: ExternCrate[];
+}
+
+/** `use` lines to add at one offset, with what goes around them */
+interface Insertion {
+  offset: number;
+  indent: string;
+  paths: string[];
+  before: string;
+  after: string;
+}
+
+/**
+ * Computes source edits that remove unused leaves from Rust `use` trees.
+ *
+ * - Removing one leaf of a group keeps the rest: `use std::io::{self, Write, File};` → `use std::io::{self, Write};`
+ * - A group left with a single plain item is unwrapped: `use std::io::{Read, Write};` → `use std::io::Write;`
+ * - Removing every leaf drops the whole item, its attributes, trailing comment and newline
+ * - Unused globs (`use crate::shapes::*;`) are removed like any other leaf
+ * - Items written one per line in a multi-line group are removed line by line together with their comments
+ * - Unused `extern crate` items are removed like a whole `use` item
+ * - Redundant aliases lose their `as` clause: `use foo::Bar as Bar;` → `use foo::Bar;`
+ * - Over-qualified paths are imported and every use shortened:
+ *   `std::collections::HashMap::new()` → `HashMap::new()` with `use std::collections::HashMap;`
+ * - Test-only imports move into their `#[cfg(test)]` module, after its imports
+ */
+export class RustFixer {
+  // The file parsed last: suggested fixes are computed one finding at a time on the same content
+  private parsed?: ParsedFile;
+
+  computeEdits(content: string, unusedImports: UnusedImport[]): TextEdit[] {
+    const aliasEdits = unusedImports
+      .filter(u => u.kind === 'redundant-alias' && u.span !== undefined)
+      .map(u => this.removeAlias(content, u.span!.start, u.span!.end));
+    const qualified = unusedImports.filter(u => u.kind === 'over-qualified-path' && u.qualifiedUses !== undefined);
+    const moved = unusedImports.filter(u => u.kind === 'test-only-import' && u.testModule !== undefined && u.span !== undefined);
+    const removeStarts = new Set(
+      unusedImports
+        .filter(u => u.span !== undefined && u.kind !== 'redundant-alias' && u.kind !== 'over-qualified-path')
+        // Used by tests, so only removed if there is a test module to move them to
+        .filter(u => u.kind !== 'test-only-import' || u.testModule !== undefined)
+        .map(u => u.span!.start)
+    );
+    // Insertions go last, so a removal starting at the same offset is applied before them
+    const importEdits = [
+      ...(qualified.length > 0 ? this.importPaths(content, qualified) : []),
+      ...(moved.length > 0 ? this.moveToTestModules(content, moved) : []),
+    ];
+    if (removeStarts.size === 0) return [...aliasEdits, ...importEdits];
+
+    const { declarations, externs } = this.parse(content);
+    const edits: TextEdit[] = [];
+
+    for (const declaration of declarations) {
+      const isRemoved = (tree: UseTree) => tree.kind !== 'group' && removeStarts.has(tree.start);
+      if (!declaration.leaves.some(leaf => isRemoved(leaf.tree))) continue;
+
+      if (this.becomesEmpty(declaration.tree, isRemoved)) {
+        edits.push(this.removeDeclaration(content, declaration));
+      } else {
+        edits.push(...this.editGroup(content, declaration.tree, isRemoved));
+      }
+    }
+
+    for (const item of externs) {
+      if (removeStarts.has(item.start)) edits.push(this.removeDeclaration(content, item));
+    }
+
+    // An alias of a removed leaf goes with it
+    const kept = aliasEdits.filter(alias => !edits.some(edit => edit.start <= alias.start && alias.end <= edit.end));
+    return [...mergeDeletions([...edits, ...kept]), ...importEdits];
+  }
+
+  private parse(content: string): ParsedFile {
+    if (this.parsed?.content !== content) {
+      const allTokens = tokenizeRust(content);
+      const tokens = allTokens.filter(t => t.kind !== 'comment');
+      this.parsed = { content, allTokens, tokens, declarations: parseUseDeclarations(tokens), externs: parseExternCrates(tokens) };
+    }
+    return this.parsed;
+  }
+
+  /**
+   * Shorten the uses of over-qualified paths, and add a `use` for each to the module
+   * the uses are in: among the module's imports of the same block (standard library,
+   * external crates, the crate itself) where it sorts, else as a block of its own
+   * after the blocks before it or ahead of the others, or at the top of a module
+   * without imports.
+   */
+  private importPaths(content: string, findings: UnusedImport[]): TextEdit[] {
+    const { allTokens, tokens, declarations } = this.parse(content);
+    const modules = enclosingModules(tokens);
+    const newline = content.includes('\r\n') ? '\r\n' : '\n';
+    const edits: TextEdit[] = [];
+    const insertions = new Map<number, Insertion>();
+
+    for (const finding of findings) {
+      const binding = finding.symbols[0];
+      const path = finding.importPath!.split('::');
+      const first = tokens.findIndex(token => token.start === finding.qualifiedUses![0].start);
+      if (first === -1) continue;
+      const module = modules[first];
+
+      const insertion = this.importInsertion(content, allTokens, tokens, declarations, module, path, newline);
+      const existing = insertions.get(insertion.offset);
+      if (existing) existing.paths.push(...insertion.paths);
+      else insertions.set(insertion.offset, insertion);
+
+      edits.push(...finding.qualifiedUses!.map(use => ({ start: use.start, end: use.end, newText: binding })));
+    }
+
+    for (const { offset, indent, paths, before, after } of insertions.values()) {
+      const lines = [...paths].sort(comparePaths).map(path => `${indent}use ${path};`);
+      edits.push({ start: offset, end: offset, newText: before + lines.join(newline) + after });
+    }
+    return edits;
+  }
+
+  /**
+   * Add a `use` for each test-only import to the end of its test module's imports, or
+   * ahead of the module's first item. A module that imports the name already keeps its
+   * own import, and the one outside is only removed.
+   */
+  private moveToTestModules(content: string, findings: UnusedImport[]): TextEdit[] {
+    const { allTokens, tokens, declarations } = this.parse(content);
+    const modules = enclosingModules(tokens);
+    const newline = content.includes('\r\n') ? '\r\n' : '\n';
+    const insertions = new Map<number, Insertion>();
+
+    for (const finding of findings) {
+      const declaration = declarations.find(d => d.leaves.some(leaf => leaf.tree.start === finding.span!.start));
+      const leaf = declaration?.leaves.find(l => l.tree.start === finding.span!.start);
+      const module = tokens.findIndex(token => token.start === finding.testModule!.span.start);
+      if (!declaration || !leaf || module === -1) continue;
+
+      const imports = declarations.filter(other => other.scopes[other.scopes.length - 1]?.start === tokens[module].start);
+      if (imports.some(other => other.leaves.some(l => l.binding === leaf.binding))) continue;
+
+      const text = childModulePath(tokens, modules, modules[declaration.tokenRange[0]], leaf);
+      const last = imports[imports.length - 1];
+      const insertion: Insertion = last
+        ? { offset: lineAfter(content, last.end), indent: indentOf(content, last.itemStart), paths: [text], before: '', after: newline }
+        : this.bodyInsertion(content, allTokens, tokens, module, text, newline);
+      const existing = insertions.get(insertion.offset);
+      if (existing) existing.paths.push(...insertion.paths);
+      else insertions.set(insertion.offset, insertion);
+    }
+
+    return [...insertions.values()].map(({ offset, indent, paths, before, after }) => ({
+      start: offset,
+      end: offset,
+      newText: before + paths.map(path => `${indent}use ${path};`).join(newline) + after,
+    }));
+  }
+
+  /** Where `use path;` goes among the imports of `module` (the index of its `{`, or -1) */
+  private importInsertion(
+    content: string,
+    allTokens: RustToken[],
+    tokens: RustToken[],
+    declarations: UseDeclaration[],
+    module: number,
+    path: string[],
+    newline: string
+  ): Insertion {
+    const text = path.join('::');
+    const block = blockOf(path);
+    const moduleStart = module === -1 ? undefined : tokens[module].start;
+    const imports = declarations.filter(declaration => {
+      const last = declaration.scopes[declaration.scopes.length - 1];
+      return moduleStart === undefined ? last === undefined : last?.kind === 'module' && last.start === moduleStart;
+    });
+    const blockOfImport = (declaration: UseDeclaration) => blockOf(declaration.leaves[0]?.path ?? []);
+
+    const same = imports.filter(declaration => blockOfImport(declaration) === block);
+    if (same.length > 0) {
+      const next = same.find(declaration =>
+        comparePaths(content.slice(declaration.tree.start, declaration.tree.end).replace(/\s+/g, ''), text) > 0);
+      if (next) {
+        return { offset: startOfLine(content, next.itemStart), indent: indentOf(content, next.itemStart), paths: [text], before: '', after: newline };
+      }
+      const last = same[same.length - 1];
+      const offset = lineAfter(content, last.end);
+      const atEnd = offset === content.length && !content.endsWith('\n');
+      return { offset, indent: indentOf(content, last.itemStart), paths: [text], before: atEnd ? newline : '', after: atEnd ? '' : newline };
+    }
+
+    const earlier = imports.filter(declaration => blockOfImport(declaration) < block);
+    if (earlier.length > 0) {
+      const last = earlier[earlier.length - 1];
+      return { offset: lineAfter(content, last.end), indent: indentOf(content, last.itemStart), paths: [text], before: newline, after: newline };
+    }
+    if (imports.length > 0) {
+      return { offset: startOfLine(content, imports[0].itemStart), indent: indentOf(content, imports[0].itemStart), paths: [text], before: '', after: newline + newline };
+    }
+
+    return this.bodyInsertion(content, allTokens, tokens, module, text, newline);
+  }
+
+  /** Ahead of the first item of `module`, which has no imports yet */
+  private bodyInsertion(content: string, allTokens: RustToken[], tokens: RustToken[], module: number, text: string, newline: string): Insertion {
+    const moduleStart = module === -1 ? undefined : tokens[module].start;
+    const open = module === -1 ? -1 : allTokens.findIndex(token => token.start === moduleStart);
+    const bodyStart = moduleBodyStart(allTokens, open);
+    if (module !== -1 && startOfLine(content, bodyStart) <= tokens[module].start) {
+      // `mod name { ... }` on one line
+      return { offset: tokens[module].end, indent: '', paths: [text], before: ' ', after: '' };
+    }
+    return { offset: startOfLine(content, bodyStart), indent: indentOf(content, bodyStart), paths: [text], before: '', after: newline + newline };
+  }
+
+  /** Drop an `as` clause from `start` (the `as`) to `end`, with the spaces before it */
+  private removeAlias(content: string, start: number, end: number): TextEdit {
+    let from = start;
+    while (from > 0 && /\s/.test(content[from - 1])) from--;
+    return { start: from, end, newText: '' };
+  }
+
+  private becomesEmpty(tree: UseTree, isRemoved: (tree: UseTree) => boolean): boolean {
+    switch (tree.kind) {
+      case 'simple':
+      case 'glob':
+        return isRemoved(tree);
+      case 'group':
+        return tree.children.length > 0 && tree.children.every(child => this.becomesEmpty(child, isRemoved));
+    }
+  }
+
+  /**
+   * Remove a whole item. When it is the only thing on its line(s) the lines go too,
+   * including a trailing `// comment`.
+   */
+  private removeDeclaration(content: string, declaration: Pick<UseDeclaration, 'itemStart' | 'end'>): TextEdit {
+    const start = declaration.itemStart;
+
+    let afterSpaces = declaration.end;
+    while (content[afterSpaces] === ' ' || content[afterSpaces] === '\t') afterSpaces++;
+
+    let lineEnd = afterSpaces;
+    if (content.startsWith('//', lineEnd)) {
+      lineEnd = content.indexOf('\n', lineEnd);
+      if (lineEnd === -1) lineEnd = content.length;
+      if (content[lineEnd - 1] === '\r') lineEnd--;
+    }
+
+    const lineStart = startOfLine(content, start);
+    const onlyWhitespaceBefore = content.slice(lineStart, start).trim() === '';
+    const newlineLength = newlineLengthAt(content, lineEnd);
+
+    if (onlyWhitespaceBefore && (newlineLength > 0 || lineEnd === content.length)) {
+      return { start: lineStart, end: lineEnd + newlineLength, newText: '' };
+    }
+
+    // Other code shares the line: drop the item and the spaces that follow it
+    return { start, end: afterSpaces, newText: '' };
+  }
+
+  /**
+   * Remove unused children from a group that keeps at least one item.
+   */
+  private editGroup(content: string, tree: UseTree, isRemoved: (tree: UseTree) => boolean): TextEdit[] {
+    if (tree.kind !== 'group') return [];
+
+    const children = tree.children;
+    const kept = children.filter(child => !this.becomesEmpty(child, isRemoved));
+    if (kept.length === children.length) {
+      return children.flatMap(child => this.editGroup(content, child, isRemoved));
+    }
+
+    // `{Read, Write}` minus `Read` becomes plain `Write` (but `{self}` must keep its braces)
+    const only = kept.length === 1 ? kept[0] : undefined;
+    if (only && only.kind !== 'group' && !(only.kind === 'simple' && only.segments[only.segments.length - 1] === 'self')) {
+      return [{
+        start: tree.braceStart!,
+        end: tree.braceEnd!,
+        newText: content.slice(only.start, only.end),
+      }];
+    }
+
+    const edits: TextEdit[] = [];
+    const lastKeptIndex = children.lastIndexOf(kept[kept.length - 1]);
+
+    children.forEach((child, index) => {
+      if (!this.becomesEmpty(child, isRemoved)) {
+        edits.push(...this.editGroup(content, child, isRemoved));
+        return;
+      }
+
+      const ownLine = this.ownLineRange(content, child);
+      if (ownLine) {
+        edits.push({ ...ownLine, newText: '' });
+      } else if (index < lastKeptIndex) {
+        // `A, B` → `B`: remove the item, its comma and the space up to the next item
+        edits.push({ start: child.start, end: children[index + 1].start, newText: '' });
+      } else {
+        // Trailing item: `A, B` → `A`: remove from the end of the previous item
+        edits.push({ start: children[index - 1].end, end: child.end, newText: '' });
+      }
+    });
+
+    return edits;
+  }
+
+  /**
+   * If a group item sits alone on its line (`    Foo, // note`), return the range of the whole line.
+   */
+  private ownLineRange(content: string, child: UseTree): { start: number; end: number } | null {
+    const lineStart = startOfLine(content, child.start);
+    if (content.slice(lineStart, child.start).trim() !== '') return null;
+
+    let after = child.end;
+    while (content[after] === ' ' || content[after] === '\t') after++;
+    if (content[after] === ',') after++;
+    while (content[after] === ' ' || content[after] === '\t') after++;
+    if (content.startsWith('//', after)) {
+      after = content.indexOf('\n', after);
+      if (after === -1) return null;
+      if (content[after - 1] === '\r') after--;
+    }
+
+    const newlineLength = newlineLengthAt(content, after);
+    if (newlineLength === 0) return null;
+
+    return { start: lineStart, end: after + newlineLength };
+  }
+}
+
+function newlineLengthAt(content: string, offset: number): number {
+  if (content[offset] === '\n') return 1;
+  if (content[offset] === '\r' && content[offset + 1] === '\n') return 2;
+  return 0;
+}
+
+/**
+ * Adjacent trailing-item deletions can overlap (`A, B, C` minus `B` and `C`); fold them together.
+ */
+function mergeDeletions(edits: TextEdit[]): TextEdit[] {
+  const sorted = [...edits].sort((a, b) => a.start - b.start);
+  const merged: TextEdit[] = [];
+
+  for (const edit of sorted) {
+    const previous = merged[merged.length - 1];
+    if (previous && previous.newText === '' && edit.newText === '' && edit.start <= previous.end) {
+      previous.end = Math.max(previous.end, edit.end);
+    } else {
+      merged.push({ ...edit });
+    }
+  }
+
+  return merged;
+}
+
+function indentOf(content: string, offset: number): string {
+  return /^[ \t]*/.exec(content.slice(startOfLine(content, offset)))![0];
+}
+
+/** Offset of the line after the one `offset` is on, or the end of `content` */
+function lineAfter(content: string, offset: number): number {
+  const end = content.indexOf('\n', offset);
+  return end === -1 ? content.length : end + 1;
+}
@@ -0,0 +1,281 @@
+/**
+ * Minimal Rust tokenizer used by the CLI's Rust analyzer.
+ * Understands enough of the lexical grammar (nested block comments, raw strings,
+ * char literals vs lifetimes, raw identifiers) that identifiers inside strings and
+ * comments are never mistaken for code.
+ */
+
+export type RustTokenKind = 'ident' | 'lifetime' | 'literal' | 'punct' | 'comment';
+
+export interface RustToken {
+  kind: RustTokenKind;
+  /** Raw source text of the token */
+  text: string;
+  /** Offset of the first character */
+  start: number;
+  /** Offset one past the last character */
+  end: number;
+}
+
+const IDENT_START = /[\p{L}_]/u;
+const IDENT_CONTINUE = /[\p{L}\p{N}_]/u;
+const DIGIT = /[0-9]/;
+const NUMBER_CONTINUE = /[0-9A-Za-z_]/;
+const WHITESPACE = /\s/;
+
+/**
+ * Split Rust source into tokens. Whitespace is dropped; comments are kept as
+ * `comment` tokens so callers can inspect doc comments and marker comments.
+ */
+export function tokenizeRust(source: string): RustToken[] {
+  const tokens: RustToken[] = [];
+  const length = source.length;
+  let i = 0;
+
+  const push = (kind: RustTokenKind, start: number, end: number) => {
+    tokens.push({ kind, text: source.slice(start, end), start, end });
+  };
+
+  while (i < length) {
+    const ch = source[i];
+    const next = source[i + 1];
+
+    if (isWhitespace(ch)) {
+      i++;
+      continue;
+    }
+
+    // Line comment (including `///` and `//!` doc comments)
+    if (ch === '/' && next === '/') {
+      let end = source.indexOf('\n', i);
+      if (end === -1) end = length;
+      // Keep a trailing `\r` out of the comment text
+      const textEnd = source[end - 1] === '\r' ? end - 1 : end;
+      push('comment', i, textEnd);
+      i = end;
+      continue;
+    }
+
+    // Block comment — Rust block comments nest
+    if (ch === '/' && next === '*') {
+      let depth = 1;
+      let j = i + 2;
+      while (j < length && depth > 0) {
+        if (source[j] === '/' && source[j + 1] === '*') {
+          depth++;
+          j += 2;
+        } else if (source[j] === '*' && source[j + 1] === '/') {
+          depth--;
+          j += 2;
+        } else {
+          j++;
+        }
+      }
+      push('comment', i, j);
+      i = j;
+      continue;
+    }
+
+    // Raw strings: r"...", r#"..."#, br"...", cr"..."
+    const rawEnd = scanRawString(source, i);
+    if (rawEnd !== -1) {
+      push('literal', i, rawEnd);
+      i = rawEnd;
+      continue;
+    }
+
+    // Byte / C string and byte char prefixes: b"...", c"...", b'x'
+    if ((ch === 'b' || ch === 'c') && next === '"') {
+      const end = scanQuoted(source, i + 1, '"');
+      push('literal', i, end);
+      i = end;
+      continue;
+    }
+    if (ch === 'b' && next === '\'') {
+      const end = scanQuoted(source, i + 1, '\'');
+      push('literal', i, end);
+      i = end;
+      continue;
+    }
+
+    if (ch === '"') {
+      const end = scanQuoted(source, i, '"');
+      push('literal', i, end);
+      i = end;
+      continue;
+    }
+
+    // Char literal or lifetime
+    if (ch === '\'') {
+      if (next === '\\') {
+        const end = scanQuoted(source, i, '\'');
+        push('literal', i, end);
+        i = end;
+        continue;
+      }
+      const codePoint = source.codePointAt(i + 1);
+      const width = codePoint !== undefined && codePoint > 0xffff ? 2 : 1;
+      if (codePoint !== undefined && source[i + 1 + width] === '\'') {
+        push('literal', i, i + 2 + width);
+        i += 2 + width;
+        continue;
+      }
+      let j = i + 1;
+      while (j < length && isIdentContinue(source[j])) j++;
+      if (j === i + 1) {
+        push('punct', i, i + 1);
+        i++;
+      } else {
+        push('lifetime', i, j);
+        i = j;
+      }
+      continue;
+    }
+
+    // Raw identifier: r#type
+    if (ch === 'r' && next === '#' && i + 2 < length && isIdentStart(source[i + 2])) {
+      let j = i + 2;
+      while (j < length && isIdentContinue(source[j])) j++;
+      push('ident', i, j);
+      i = j;
+      continue;
+    }
+
+    if (isIdentStart(ch)) {
+      let j = i + 1;
+      while (j < length && isIdentContinue(source[j])) j++;
+      push('ident', i, j);
+      i = j;
+      continue;
+    }
+
+    if (DIGIT.test(ch)) {
+      let j = i + 1;
+      while (j < length) {
+        if (NUMBER_CONTINUE.test(source[j])) {
+          j++;
+        } else if (source[j] === '.' && DIGIT.test(source[j + 1] || '') && source[j - 1] !== '.') {
+          j++;
+        } else {
+          break;
+        }
+      }
+      push('literal', i, j);
+      i = j;
+      continue;
+    }
+
+    if (ch === ':' && next === ':') {
+      push('punct', i, i + 2);
+      i += 2;
+      continue;
+    }
+
+    push('punct', i, i + 1);
+    i++;
+  }
+
+  return tokens;
+}
+
+// ASCII characters, most of any source, are told apart by code: testing the
+// Unicode-aware regexes on every character dominates the time on large files
+function isIdentStart(ch: string): boolean {
+  const code = ch.charCodeAt(0);
+  if (code < 0x80) return (code >= 0x61 && code <= 0x7a) || (code >= 0x41 && code <= 0x5a) || code === 0x5f;
+  return IDENT_START.test(ch);
+}
+
+function isIdentContinue(ch: string): boolean {
+  const code = ch.charCodeAt(0);
+  if (code < 0x80) return (code >= 0x61 && code <= 0x7a) || (code >= 0x41 && code <= 0x5a) || (code >= 0x30 && code <= 0x39) || code === 0x5f;
+  return IDENT_CONTINUE.test(ch);
+}
+
+function isWhitespace(ch: string): boolean {
+  const code = ch.charCodeAt(0);
+  if (code < 0x80) return code === 0x20 || (code >= 0x09 && code <= 0x0d);
+  return WHITESPACE.test(ch);
+}
+
+/**
+ * If a raw string literal starts at `start`, return the offset just past it; otherwise -1.
+ */
+function scanRawString(source: string, start: number): number {
+  let i = start;
+  if (source[i] === 'b' || source[i] === 'c') i++;
+  if (source[i] !== 'r') return -1;
+  i++;
+
+  let hashes = 0;
+  while (source[i] === '#') {
+    hashes++;
+    i++;
+  }
+  if (source[i] !== '"') return -1;
+
+  const terminator = '"' + '#'.repeat(hashes);
+  const close = source.indexOf(terminator, i + 1);
+  return close === -1 ? source.length : close + terminator.length;
+}
+
+/**
+ * Scan a quoted literal starting at the opening quote, honouring backslash escapes.
+ * Returns the offset just past the closing quote (or end of input when unterminated).
+ */
+function scanQuoted(source: string, start: number, quote: string): number {
+  let i = start + 1;
+  while (i < source.length) {
+    if (source[i] === '\\') {
+      i += 2;
+      continue;
+    }
+    if (source[i] === quote) {
+      return i + 1;
+    }
+    i++;
+  }
+  return source.length;
+}
+
+/**
+ * Whether every bracket is closed by its counterpart; false for half-typed code
+ * such as an unfinished `use a::{b,` group
+ */
+export function hasBalancedDelimiters(tokens: RustToken[]): boolean {
+  const pairs: Record<string, string> = { ')': '(', ']': '[', '}': '{' };
+  const open: string[] = [];
+  for (const token of tokens) {
+    if (token.kind !== 'punct') continue;
+    if (token.text === '(' || token.text === '[' || token.text === '{') {
+      open.push(token.text);
+    } else if (pairs[token.text] && open.pop() !== pairs[token.text]) {
+      return false;
+    }
+  }
+  return open.length === 0;
+}
+
+/**
+ * Index of the token closing the bracket opened at `open`, or `tokens.length` if unbalanced
+ */
+export function findClosingDelimiter(tokens: RustToken[], open: number): number {
+  let depth = 0;
+  for (let j = open; j < tokens.length; j++) {
+    const text = tokens[j].text;
+    if (text === '(' || text === '[' || text === '{') depth++;
+    else if (text === ')' || text === ']' || text === '}') {
+      depth--;
+      if (depth === 0) return j;
+    }
+  }
+  return tokens.length;
+}
+
+/**
+ * The name an identifier token stands for, without the `r#` of a raw identifier:
+ * `r#foo` and `foo` name the same item
+ */
+export function identifierName(text: string): string {
+  return text.startsWith('r#') ? text.slice(2) : text;
+}
@@ -0,0 +1,242 @@
+import { TextEdit } from './FixEngine';
+import { startOfLine } from './LineIndex';
+import { RustToken, tokenizeRust } from './RustLexer';
+import { parseUseDeclarations, UseDeclaration } from './RustUseTree';
+
+/** Crates of the standard library, placed in the first block */
+const STD_CRATES = new Set(['std', 'core', 'alloc']);
+/** Path roots inside the current crate, placed in the last block */
+const LOCAL_ROOTS = new Set(['crate', 'self', 'super']);
+
+/**
+ * A `use` item with the comments that travel with it
+ */
+interface Unit {
+  declaration: UseDeclaration;
+  /** Offset of the first attached comment, else of the first attribute or the item */
+  start: number;
+  /** Offset one past the `;`, or past a comment following it on the same line */
+  end: number;
+  /** Without attributes and comments, the item may be merged with others */
+  mergeable: boolean;
+}
+
+/**
+ * An imported item after its module path: `File`, `Read as R`, `self` or `*`
+ */
+interface Entry {
+  name: string;
+  alias?: string;
+}
+
+/**
+ * Sorts and groups the `use` items of Rust files for `--organize`.
+ *
+ * Each run of `use` items with nothing but whitespace and comments between them
+ * is rewritten on its own, so imports never move across other items:
+ * - items are split into blocks (`std`/`core`/`alloc`, external crates,
+ *   `crate`/`self`/`super`) separated by a blank line, and sorted within a block
+ * - items importing from the same module with the same visibility are merged into
+ *   one group, `use foo::a; use foo::b;` becoming `use foo::{a, b};`
+ * - items with attributes or comments are moved as they are, together with
+ *   comments on the lines directly above and after them on the same line
+ *
+ * A comment followed by a blank line ends a run. Organizing is idempotent.
+ */
+export class RustOrganizer {
+  /**
+   * One edit per run of `use` items that is not organized yet
+   */
+  computeEdits(content: string): TextEdit[] {
+    const allTokens = tokenizeRust(content);
+    const comments = allTokens.filter(t => t.kind === 'comment');
+    const tokens = allTokens.filter(t => t.kind !== 'comment');
+    const declarations = parseUseDeclarations(tokens);
+    const newline = content.includes('\r\n') ? '\r\n' : '\n';
+    const edits: TextEdit[] = [];
+
+    for (const run of this.findRuns(content, tokens, comments, declarations)) {
+      const start = run[0].start;
+      const end = run[run.length - 1].end;
+      const lineStart = startOfLine(content, start);
+      const indent = content.slice(lineStart, start);
+
+      const organized = this.render(content, run, indent, newline);
+      if (organized !== content.slice(start, end)) {
+        edits.push({ start, end, newText: organized });
+      }
+    }
+
+    return edits;
+  }
+
+  private findRuns(content: string, tokens: RustToken[], comments: RustToken[], declarations: UseDeclaration[]): Unit[][] {
+    const runs: Unit[][] = [];
+    let run: Unit[] = [];
+    let previousEnd = -1;
+    let previousLast = -1;
+
+    const close = () => {
+      // Items sharing a line with other code can't be moved line by line
+      if (run.length > 0 && run.every(unit => ownsLines(content, unit))) runs.push(run);
+      run = [];
+    };
+
+    for (const declaration of declarations) {
+      const firstIndex = tokens.findIndex(t => t.start === declaration.itemStart);
+      const [, lastIndex] = declaration.tokenRange;
+      const between = comments.filter(c => c.start >= previousEnd && c.end <= declaration.itemStart);
+
+      // A following comment on the `;` line belongs to the previous item
+      const trailing = run.length > 0 && between[0] && !content.slice(previousEnd, between[0].start).includes('\n')
+        ? between.shift()
+        : undefined;
+      if (trailing) run[run.length - 1].end = trailing.end;
+
+      // Comments directly above the item, without blank lines, move with it
+      let start = declaration.itemStart;
+      while (between.length > 0 && isLineAbove(content, between[between.length - 1], start) && !isInnerDoc(between[between.length - 1])) {
+        start = between.pop()!.start;
+      }
+
+      const contiguous = firstIndex === previousLast + 1 && between.length === 0;
+      if (!contiguous) close();
+
+      const inner = comments.some(c => c.start > declaration.itemStart && c.end < declaration.end);
+      run.push({
+        declaration,
+        start,
+        end: declaration.end,
+        mergeable: start === declaration.start && !inner,
+      });
+      previousEnd = declaration.end;
+      previousLast = lastIndex;
+    }
+
+    // A comment after the last item of a run, on its line
+    if (run.length > 0) {
+      const last = run[run.length - 1];
+      const trailing = comments.find(c => c.start >= last.end);
+      if (trailing && !content.slice(last.end, trailing.start).includes('\n')) last.end = trailing.end;
+    }
+    close();
+
+    runs.forEach(units => units.forEach(unit => {
+      if (unit.end !== unit.declaration.end) unit.mergeable = false;
+    }));
+    return runs;
+  }
+
+  private render(content: string, run: Unit[], indent: string, newline: string): string {
+    const blocks: Array<Array<{ key: string; visibility: string; text: string }>> = [[], [], []];
+
+    // Merge items from the same module, keyed by visibility and module path.
+    // A bare `use name;` has no module to merge into and stays on its own.
+    const groups = new Map<string, { visibility: string; prefix: string[]; entries: Entry[] }>();
+    const groupKey = (visibility: string, prefix: string[], entry: Entry) =>
+      prefix.length > 0 ? `${visibility} ${prefix.join('::')}` : `${visibility} ${entry.name} as ${entry.alias ?? ''}`;
+
+    for (const unit of run.filter(u => u.mergeable)) {
+      const { visibility } = unit.declaration;
+      for (const leaf of unit.declaration.leaves) {
+        const prefix = leaf.path.slice(0, -1);
+        const entry: Entry = { name: leaf.path[leaf.path.length - 1], alias: leaf.alias };
+        const key = groupKey(visibility, prefix, entry);
+        if (!groups.has(key)) groups.set(key, { visibility, prefix, entries: [] });
+        const entries = groups.get(key)!.entries;
+        if (!entries.some(e => e.name === entry.name && e.alias === entry.alias)) entries.push(entry);
+      }
+    }
+
+    // `use a::b; use a::b::C;` becomes `use a::b::{self, C};`
+    for (const group of groups.values()) {
+      if (group.prefix.length === 0) continue;
+      const name = group.prefix[group.prefix.length - 1];
+      const parent = groups.get(groupKey(group.visibility, group.prefix.slice(0, -1), { name }));
+      const index = parent?.entries.findIndex(e => e.name === name && e.alias === undefined) ?? -1;
+      if (parent && index !== -1) {
+        parent.entries.splice(index, 1);
+        if (!group.entries.some(e => e.name === 'self' && e.alias === undefined)) group.entries.push({ name: 'self' });
+      }
+    }
+
+    for (const { visibility, prefix, entries } of groups.values()) {
+      if (entries.length === 0) continue;
+      const sorted = [...entries].sort(compareEntries);
+      const items = sorted.map(e => (e.alias ? `${e.name} as ${e.alias}` : e.name));
+      const path = sorted.length === 1 && sorted[0].name !== 'self' && prefix.length > 0
+        ? `${prefix.join('::')}::${items[0]}`
+        : prefix.length > 0 ? `${prefix.join('::')}::{${items.join(', ')}}` : items[0];
+      const text = `${visibility ? `${visibility} ` : ''}use ${path};`;
+      blocks[blockOf(prefix.length > 0 ? prefix : [sorted[0].name])].push({ key: path, visibility, text });
+    }
+
+    for (const unit of run.filter(u => !u.mergeable)) {
+      const { declaration } = unit;
+      const path = content.slice(declaration.tree.start, declaration.tree.end).replace(/\s+/g, '');
+      blocks[blockOf(declaration.leaves[0]?.path ?? [])].push({
+        key: path,
+        visibility: declaration.visibility,
+        text: content.slice(unit.start, unit.end),
+      });
+    }
+
+    return blocks
+      .filter(block => block.length > 0)
+      .map(block => block
+        .sort((a, b) => comparePaths(a.key, b.key) || compareText(a.visibility, b.visibility) || compareText(a.text, b.text))
+        .map(item => item.text)
+        .join(newline + indent))
+      .join(newline + newline + indent);
+  }
+}
+
+/** 0 for the standard library, 1 for external crates, 2 for the current crate */
+export function blockOf(path: string[]): number {
+  const root = path[0] === '' ? path[1] : path[0];
+  if (path[0] !== '' && LOCAL_ROOTS.has(root)) return 2;
+  return STD_CRATES.has(root) ? 0 : 1;
+}
+
+/** `self` first and `*` last, the rest alphabetically */
+function compareEntries(a: Entry, b: Entry): number {
+  const rank = (e: Entry) => (e.name === 'self' ? 0 : e.name === '*' ? 2 : 1);
+  return rank(a) - rank(b) || compareText(a.name, b.name) || compareText(a.alias ?? '', b.alias ?? '');
+}
+
+/** Segment by segment, so `a::b` sorts before `a_c` and `a::{...}` groups after `a::z` */
+export function comparePaths(a: string, b: string): number {
+  const left = a.split('::');
+  const right = b.split('::');
+  for (let i = 0; i < Math.min(left.length, right.length); i++) {
+    const order = compareText(left[i], right[i]);
+    if (order !== 0) return order;
+  }
+  return left.length - right.length;
+}
+
+/** Alphabetical ignoring case, then by code unit so the order is total */
+function compareText(a: string, b: string): number {
+  const lower = a.toLowerCase() < b.toLowerCase() ? -1 : a.toLowerCase() > b.toLowerCase() ? 1 : 0;
+  return lower || (a < b ? -1 : a > b ? 1 : 0);
+}
+
+/** Whether `comment` sits alone on the line directly above `offset` */
+function isLineAbove(content: string, comment: RustToken, offset: number): boolean {
+  const gap = content.slice(comment.end, offset);
+  const before = content.slice(content.lastIndexOf('\n', comment.start - 1) + 1, comment.start);
+  return /^[ \t]*\r?\n[ \t]*$/.test(gap) && /^[ \t]*$/.test(before);
+}
+
+/** `//!` and `/*!` document the enclosing item, not the next one */
+function isInnerDoc(comment: RustToken): boolean {
+  return comment.text.startsWith('//!') || comment.text.startsWith('/*!');
+}
+
+/** Whether nothing but whitespace shares the unit's first and last lines */
+function ownsLines(content: string, unit: Unit): boolean {
+  const before = content.slice(content.lastIndexOf('\n', unit.start - 1) + 1, unit.start);
+  const lineEnd = content.indexOf('\n', unit.end);
+  const after = content.slice(unit.end, lineEnd === -1 ? content.length : lineEnd);
+  return /^[ \t]*$/.test(before) && /^[ \t]*\r?$/.test(after);
+}
@@ -0,0 +1,198 @@
+import * as fs from 'fs';
+import * as path from 'path';
+import { findClosingDelimiter, RustToken } from './RustLexer';
+import { itemNameIndex } from './RustShadowing';
+import { reexportScope, UseDeclaration, UseLeaf } from './RustUseTree';
+import { parseToml, TomlTable, TomlValue } from './Toml';
+
+export const RUST_EDITIONS = ['2015', '2018', '2021', '2024'] as const;
+export type RustEdition = typeof RUST_EDITIONS[number];
+
+/** Items of the `v1` prelude, which every edition has, as paths below `std` */
+const PRELUDE_V1 = [
+  'marker::Copy', 'marker::Send', 'marker::Sized', 'marker::Sync', 'marker::Unpin',
+  'ops::Drop', 'ops::Fn', 'ops::FnMut', 'ops::FnOnce', 'mem::drop',
+  'boxed::Box', 'borrow::ToOwned', 'clone::Clone',
+  'cmp::PartialEq', 'cmp::PartialOrd', 'cmp::Eq', 'cmp::Ord',
+  'convert::AsRef', 'convert::AsMut', 'convert::Into', 'convert::From', 'default::Default',
+  'iter::Iterator', 'iter::Extend', 'iter::IntoIterator', 'iter::DoubleEndedIterator', 'iter::ExactSizeIterator',
+  'option::Option', 'option::Option::Some', 'option::Option::None',
+  'result::Result', 'result::Result::Ok', 'result::Result::Err',
+  'string::String', 'string::ToString', 'vec::Vec',
+];
+
+/** What the prelude of each edition adds to the one before */
+const EDITION_ADDITIONS: Record<RustEdition, string[]> = {
+  '2015': PRELUDE_V1,
+  '2018': [],
+  '2021': ['convert::TryFrom', 'convert::TryInto', 'iter::FromIterator'],
+  '2024': ['future::Future', 'future::IntoFuture'],
+};
+
+// Only in `std` and `alloc`; `alloc::` imports are needed by `no_std` crates and never reported
+const STD_ONLY = new Set(['boxed', 'borrow', 'string', 'vec']);
+
+/**
+ * Traits that are not in the prelude, but whose derive macro of the same name is:
+ * an import only `#[derive(..)]` uses is unnecessary
+ */
+const DERIVE_ONLY = new Set(['std::fmt::Debug', 'core::fmt::Debug', 'std::hash::Hash', 'core::hash::Hash']);
+
+/** Why the prelude makes an import unnecessary */
+export type PreludeReason = 'prelude-item' | 'prelude-glob' | 'derive-macro';
+
+export interface PreludeImport {
+  declaration: UseDeclaration;
+  leaf: UseLeaf;
+  reason: PreludeReason;
+}
+
+/**
+ * Paths of the items the prelude of `edition` brings into every module, through
+ * `std::` and, for those `core` has, `core::`
+ */
+export function preludePaths(edition: RustEdition): Set<string> {
+  const paths = new Set<string>();
+  for (const year of RUST_EDITIONS.filter(year => year <= edition)) {
+    for (const item of EDITION_ADDITIONS[year]) {
+      paths.add(`std::${item}`);
+      if (!STD_ONLY.has(item.split('::')[0])) paths.add(`core::${item}`);
+    }
+  }
+  return paths;
+}
+
+/**
+ * Find imports of what the prelude of `edition` already provides: its items
+ * (`use std::vec::Vec;`, `use std::convert::TryFrom;` from 2021 on), imports of a
+ * prelude module (`use std::prelude::v1::*;`, or `rust_2018` in a 2021 crate) and
+ * `fmt::Debug` or `hash::Hash` imports that only `#[derive(..)]` uses.
+ *
+ * Renamed imports, re-exports and `alloc::` paths are left alone, and so is every
+ * import in a file with `no_implicit_prelude`. So is an import of a name the file
+ * also defines, or one in a module with other glob imports: either may bring in a
+ * different item of the same name, which the explicit import is there to override.
+ */
+export function findPreludeImports(tokens: RustToken[], declarations: UseDeclaration[], edition: RustEdition): PreludeImport[] {
+  if (tokens.some(token => token.text === 'no_implicit_prelude')) return [];
+
+  const items = preludePaths(edition);
+  const names = new Set([...items].map(item => item.split('::').pop()!));
+  // Derive macros of the `core` prelude that no prelude trait imports
+  names.add('Debug');
+  names.add('Hash');
+
+  const defined = new Set<string>();
+  tokens.forEach((_, i) => {
+    const name = itemNameIndex(tokens, i);
+    if (name !== undefined) defined.add(tokens[name].text);
+  });
+
+  // Modules with glob imports other than a prelude's
+  const globModules = new Set<number>();
+  for (const declaration of declarations) {
+    if (declaration.leaves.some(leaf => leaf.isGlob && preludeModule(leaf.path.filter(segment => segment !== '')) === undefined)) {
+      globModules.add(moduleOf(declaration));
+    }
+  }
+
+  const found: PreludeImport[] = [];
+  for (const declaration of declarations) {
+    if (reexportScope(declaration) !== 'private' || globModules.has(moduleOf(declaration))) continue;
+
+    for (const leaf of declaration.leaves) {
+      if (leaf.alias !== undefined || (!leaf.isGlob && defined.has(leaf.binding))) continue;
+      const segments = leaf.path.filter((segment, k) => segment !== '' && !(k === leaf.path.length - 1 && segment === 'self'));
+      const module = preludeModule(segments);
+
+      let reason: PreludeReason | undefined;
+      if (module !== undefined) {
+        // A later edition's prelude has items this one lacks
+        if (leaf.isGlob ? module <= edition : names.has(leaf.binding)) reason = leaf.isGlob ? 'prelude-glob' : 'prelude-item';
+      } else if (!leaf.isGlob && items.has(segments.join('::'))) {
+        reason = 'prelude-item';
+      } else if (!leaf.isGlob && DERIVE_ONLY.has(segments.join('::')) && onlyDerived(tokens, declarations, leaf.binding)) {
+        reason = 'derive-macro';
+      }
+      if (reason) found.push({ declaration, leaf, reason });
+    }
+  }
+
+  return found;
+}
+
+/**
+ * The edition `Cargo.toml` at `manifestPath` sets for its package: `edition`, or
+ * the workspace's with `edition.workspace = true`. A package without one is 2015.
+ * Undefined when the manifest can't be read or names no edition ImportLens knows.
+ */
+export function manifestEdition(manifestPath: string): RustEdition | undefined {
+  const manifest = readManifest(manifestPath);
+  const pkg = manifest?.package;
+  if (!isTable(pkg)) return undefined;
+
+  const edition = isTable(pkg.edition) && pkg.edition.workspace === true
+    ? workspaceEdition(path.dirname(path.dirname(manifestPath)))
+    : pkg.edition ?? '2015';
+  return isEdition(edition) ? edition : undefined;
+}
+
+export function isEdition(value: unknown): value is RustEdition {
+  return (RUST_EDITIONS as readonly unknown[]).includes(value);
+}
+
+/** `[workspace.package] edition` of the nearest manifest with a `[workspace]` at or above `dir` */
+function workspaceEdition(dir: string): TomlValue | undefined {
+  for (let current = dir; ; current = path.dirname(current)) {
+    const workspace = readManifest(path.join(current, 'Cargo.toml'))?.workspace;
+    if (isTable(workspace)) return isTable(workspace.package) ? workspace.package.edition : undefined;
+    if (path.dirname(current) === current) return undefined;
+  }
+}
+
+function readManifest(manifestPath: string): TomlTable | undefined {
+  try {
+    return parseToml(fs.readFileSync(manifestPath, 'utf-8'));
+  } catch {
+    return undefined;
+  }
+}
+
+/**
+ * The edition of the prelude module a `std::prelude::..` or `core::prelude::..` path
+ * is in, `v1` being every edition's; undefined for other paths
+ */
+function preludeModule(segments: string[]): RustEdition | undefined {
+  if ((segments[0] !== 'std' && segments[0] !== 'core') || segments[1] !== 'prelude' || segments.length < 3) return undefined;
+  const year = segments[2] === 'v1' ? '2015' : segments[2].replace(/^rust_/, '');
+  return isEdition(year) ? year : undefined;
+}
+
+/** Offset of the innermost `mod { .. }` a `use` item is in, or -1 at the top of the file */
+function moduleOf(declaration: UseDeclaration): number {
+  const modules = declaration.scopes.filter(scope => scope.kind === 'module');
+  return modules.length > 0 ? modules[modules.length - 1].start : -1;
+}
+
+/** Whether every use of `name` outside `use` items is in the arguments of a `derive(..)` */
+function onlyDerived(tokens: RustToken[], declarations: UseDeclaration[], name: string): boolean {
+  const inDerive = new Array<boolean>(tokens.length).fill(false);
+  for (let i = 0; i < tokens.length - 1; i++) {
+    if (tokens[i].text !== 'derive' || tokens[i + 1].text !== '(') continue;
+    const close = findClosingDelimiter(tokens, i + 1);
+    for (let k = i + 1; k <= close && k < tokens.length; k++) inDerive[k] = true;
+  }
+  const inUse = (i: number) => declarations.some(({ tokenRange }) => i >= tokenRange[0] && i <= tokenRange[1]);
+
+  let uses = 0;
+  for (let i = 0; i < tokens.length; i++) {
+    if (tokens[i].kind !== 'ident' || tokens[i].text !== name || tokens[i - 1]?.text === '::' || inUse(i)) continue;
+    if (!inDerive[i]) return false;
+    uses++;
+  }
+  return uses > 0;
+}
+
+function isTable(value: TomlValue | undefined): value is TomlTable {
+  return typeof value === 'object' && !Array.isArray(value);
+}
@@ -0,0 +1,184 @@
+import { SourceSpan } from './CLIAnalyzer';
+import { CfgCondition } from './RustCfg';
+import { findClosingDelimiter, RustToken } from './RustLexer';
+import { UseDeclaration } from './RustUseTree';
+
+/** Names the prelude brings into every module, which an import would shadow */
+const PRELUDE_NAMES = new Set([
+  'Option', 'Some', 'None', 'Result', 'Ok', 'Err', 'Vec', 'String', 'Box', 'ToString', 'ToOwned',
+  'Clone', 'Copy', 'Send', 'Sync', 'Sized', 'Unpin', 'Drop', 'Fn', 'FnMut', 'FnOnce', 'Iterator',
+  'IntoIterator', 'DoubleEndedIterator', 'ExactSizeIterator', 'Extend', 'Default', 'Eq', 'PartialEq',
+  'Ord', 'PartialOrd', 'AsRef', 'AsMut', 'Into', 'From', 'TryFrom', 'TryInto', 'FromIterator', 'Debug', 'Hash',
+]);
+
+// Path roots that are not names an import could bind
+const PATH_KEYWORDS = new Set(['crate', 'self', 'super', 'Self']);
+
+/**
+ * A path written out in full often enough in one module to be worth importing
+ */
+export interface QualifiedPath {
+  /** What to import, e.g. ['std', 'collections', 'HashMap'] */
+  path: string[];
+  /** The name the import binds, which every use is shortened to */
+  binding: string;
+  /** The written-out part of each use: `std::collections::HashMap` of `std::collections::HashMap::new()` */
+  uses: SourceSpan[];
+}
+
+/**
+ * Index of the `{` of the innermost `mod name { ... }` around each token, or -1 at
+ * the top of the file. Blocks inside a module belong to the module.
+ */
+export function enclosingModules(tokens: RustToken[]): number[] {
+  const modules: number[] = [];
+  const opened: number[] = [];
+  let current = -1;
+
+  tokens.forEach((token, i) => {
+    if (token.text === '{') {
+      opened.push(current);
+      if (tokens[i - 1]?.kind === 'ident' && tokens[i - 2]?.text === 'mod') current = i;
+    }
+    modules.push(current);
+    if (token.text === '}') current = opened.pop() ?? -1;
+  });
+
+  return modules;
+}
+
+/**
+ * How many segments of a written-out path to import: up to the first type-like
+ * (capitalized) segment, `std::collections::HashMap` of `std::collections::HashMap::new`,
+ * or else the module of the function or constant, `std::fs` of `std::fs::read`.
+ * 0 for paths starting at a type, and for paths whose import would be a single segment.
+ */
+function importedLength(names: string[]): number {
+  if (/^[A-Z]/.test(names[0])) return 0;
+  const type = names.findIndex((name, k) => k > 0 && /^[A-Z]/.test(name));
+  const length = type === -1 ? names.length - 1 : type + 1;
+  return length >= 2 && !PATH_KEYWORDS.has(names[length - 1]) ? length : 0;
+}
+
+/**
+ * Find paths that are written out `threshold` or more times in one module
+ * (`std::collections::HashMap::new()`, ...) although an import could shorten them.
+ *
+ * Paths in `use` and `extern crate` items (`items`), attributes and macro bodies
+ * (`inMacro`) don't count, nor do uses compiled under other cfg conditions than
+ * their module, or paths starting with a name the module imports (`io::Error` after
+ * `use std::io;`). A path is left alone when the name its import would bind is
+ * already taken in the module: imported, defined or written on its own anywhere in
+ * it, or in the prelude. Of several paths binding the same name, only the one
+ * written out most often is reported.
+ */
+export function findQualifiedPaths(
+  tokens: RustToken[],
+  declarations: UseDeclaration[],
+  items: Array<{ tokenRange: [number, number] }>,
+  inMacro: boolean[],
+  conditions: CfgCondition[],
+  threshold: number
+): QualifiedPath[] {
+  const modules = enclosingModules(tokens);
+  const inItem = new Array<boolean>(tokens.length).fill(false);
+  for (const item of items) {
+    for (let j = item.tokenRange[0]; j <= item.tokenRange[1]; j++) inItem[j] = true;
+  }
+
+  // Names bound by imports, and every name taken in each module
+  const imported = new Map<number, Set<string>>();
+  const taken = new Map<number, Set<string>>();
+  const add = (names: Map<number, Set<string>>, module: number, name: string) => {
+    const set = names.get(module) ?? new Set<string>();
+    set.add(name);
+    names.set(module, set);
+  };
+  for (const declaration of declarations) {
+    const scope = declaration.scopes.filter(s => s.kind === 'module').pop();
+    const module = scope ? tokens.findIndex(token => token.start === scope.start) : -1;
+    for (const leaf of declaration.leaves) {
+      if (leaf.isGlob) continue;
+      add(imported, module, leaf.binding);
+      add(taken, module, leaf.binding);
+    }
+  }
+
+  const conditionKeys = new Map<CfgCondition, string>();
+  const conditionKey = (condition: CfgCondition | undefined) => {
+    if (condition === undefined) return '[]';
+    if (!conditionKeys.has(condition)) conditionKeys.set(condition, JSON.stringify(condition));
+    return conditionKeys.get(condition)!;
+  };
+
+  const found = new Map<string, QualifiedPath & { module: number }>();
+  for (let i = 0; i < tokens.length; i++) {
+    const token = tokens[i];
+    if (inItem[i] || token.kind !== 'ident') continue;
+    const previous = tokens[i - 1]?.text;
+    if (previous === '::' || previous === '.') continue;
+    if (tokens[i + 1]?.text !== '::') {
+      add(taken, modules[i], token.text);
+      continue;
+    }
+
+    let last = i;
+    while (tokens[last + 1]?.text === '::' && tokens[last + 2]?.kind === 'ident') last += 2;
+    const start = i;
+    i = last;
+
+    // Macro paths (`log::info!`) and attribute paths (`#[tokio::main]`)
+    if (tokens[last + 1]?.text === '!' || inMacro[start]) continue;
+    if (previous === '[' && (tokens[start - 2]?.text === '#' || tokens[start - 2]?.text === '!')) continue;
+
+    const names: string[] = [];
+    for (let j = start; j <= last; j += 2) names.push(tokens[j].text);
+    const length = importedLength(names);
+    const module = modules[start];
+    if (length === 0 || imported.get(module)?.has(names[0])) continue;
+
+    const moduleCondition = module === -1 ? undefined : conditions[module];
+    if (conditionKey(conditions[start]) !== conditionKey(moduleCondition)) continue;
+
+    const path = names.slice(0, length);
+    const key = `${module}\0${path.join('::')}`;
+    const entry = found.get(key) ?? { path, binding: path[length - 1], uses: [], module };
+    entry.uses.push({ start: token.start, end: tokens[start + 2 * (length - 1)].end });
+    found.set(key, entry);
+  }
+
+  const candidates = [...found.values()].filter(entry =>
+    entry.uses.length >= threshold && !PRELUDE_NAMES.has(entry.binding) && !taken.get(entry.module)?.has(entry.binding));
+
+  // `std::fmt::Error` and `std::io::Error` can't both be imported as `Error`
+  const chosen = new Map<string, QualifiedPath & { module: number }>();
+  for (const entry of candidates) {
+    const key = `${entry.module}\0${entry.binding}`;
+    const other = chosen.get(key);
+    if (!other || entry.uses.length > other.uses.length) chosen.set(key, entry);
+  }
+
+  return [...chosen.values()]
+    .sort((a, b) => a.uses[0].start - b.uses[0].start)
+    .map(({ path, binding, uses }) => ({ path, binding, uses }));
+}
+
+/**
+ * Offset at which the first item of a module, or of the file for `open` -1, starts:
+ * past the `{`, inner attributes (`#![...]`) and inner doc comments. `tokens` must
+ * include comments.
+ */
+export function moduleBodyStart(tokens: RustToken[], open: number): number {
+  let i = open + 1;
+  while (i < tokens.length) {
+    const token = tokens[i];
+    if (token.kind === 'comment' && (token.text.startsWith('//!') || token.text.startsWith('/*!'))) {
+      i++;
+    } else if (token.text === '#' && tokens[i + 1]?.text === '!' && tokens[i + 2]?.text === '[') {
+      i = findClosingDelimiter(tokens, i + 2) + 1;
+    } else {
+      break;
+    }
+  }
+  return tokens[i]?.start ?? tokens[tokens.length - 1]?.end ?? 0;
+}
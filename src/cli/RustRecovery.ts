@@ -0,0 +1,172 @@
+import { LineIndex } from './LineIndex';
+import { hasBalancedDelimiters, RustToken, tokenizeRust } from './RustLexer';
+
+/**
+ * Part of a Rust file left out of the analysis because it doesn't parse
+ */
+export interface SkippedRegion {
+  /** Offset of the first character */
+  start: number;
+  /** Offset one past the last character */
+  end: number;
+  /** 1-based lines the region spans */
+  startLine: number;
+  endLine: number;
+  /** What is wrong in it, e.g. "unclosed `(`" */
+  problems: string[];
+}
+
+export interface RecoveredSource {
+  /** The source with the skipped regions neutralized; same length and line breaks as the original */
+  source: string;
+  skipped: SkippedRegion[];
+  /** Tokens of the original source, comments included, for callers to reuse when nothing was skipped */
+  tokens: RustToken[];
+}
+
+const OPENING: Record<string, string> = { '(': ')', '[': ']', '{': '}' };
+const CLOSING: Record<string, string> = { ')': '(', ']': '[', '}': '{' };
+
+/** Tokens that start an item when written at the start of a line, unindented */
+const ITEM_KEYWORDS = new Set([
+  'use', 'pub', 'fn', 'mod', 'struct', 'enum', 'union', 'impl', 'trait', 'type', 'const', 'static',
+  'extern', 'unsafe', 'async', 'macro_rules', '#',
+]);
+
+/**
+ * Make a Rust file with syntax errors analyzable. Tokenizing never fails, but
+ * brackets that don't pair up would put every later item in the wrong scope, so
+ * recovery happens at item boundaries: items written unindented at the start of a
+ * line. An item that holds an unclosed or unmatched bracket, or an unterminated
+ * string or block comment, is skipped up to the next such item: its brackets are
+ * blanked, so it no longer nests anything, and its `use` items are blanked whole,
+ * since there is no telling what they import. Names in it still count as uses.
+ *
+ * Files without such problems are returned as they are.
+ */
+export function recoverRustSyntax(content: string): RecoveredSource {
+  const allTokens = tokenizeRust(content);
+  const tokens = allTokens.filter(t => t.kind !== 'comment');
+  const problems = findProblems(content, allTokens, tokens);
+  if (problems.length === 0) return { source: content, skipped: [], tokens: allTokens };
+
+  const itemStarts = tokens
+    .map((token, i) => (isItemStart(content, token) ? i : -1))
+    .filter(i => i !== -1);
+  const lineIndex = new LineIndex(content);
+
+  // Each problem takes out the item it is in, merged with overlapping ones
+  const regions: SkippedRegion[] = [];
+  for (const { offset, problem } of problems.sort((a, b) => a.offset - b.offset)) {
+    const before = itemStarts.filter(i => tokens[i].start <= offset).pop();
+    const after = itemStarts.find(i => tokens[i].start > offset);
+    const start = before === undefined ? 0 : tokens[before].start;
+    const end = after === undefined ? content.length : tokens[after].start;
+
+    const last = regions[regions.length - 1];
+    if (last && start < last.end) {
+      last.end = Math.max(last.end, end);
+      if (!last.problems.includes(problem)) last.problems.push(problem);
+    } else {
+      regions.push({ start, end, startLine: 0, endLine: 0, problems: [problem] });
+    }
+  }
+
+  const blanked: Array<[number, number]> = [];
+  const blank = (start: number, end: number) => blanked.push([start, end]);
+  for (const region of regions) {
+    const inRegion = tokens.filter(t => t.start >= region.start && t.start < region.end);
+    for (let i = 0; i < inRegion.length; i++) {
+      const token = inRegion[i];
+      if (token.kind === 'punct' && (OPENING[token.text] || CLOSING[token.text])) {
+        blank(token.start, token.end);
+      } else if (token.kind === 'ident' && token.text === 'use') {
+        let j = i;
+        while (j < inRegion.length - 1 && inRegion[j].text !== ';') j++;
+        blank(token.start, inRegion[j].end);
+        i = j;
+      }
+    }
+
+    region.startLine = lineIndex.positionAt(region.start).line;
+    region.endLine = lineIndex.positionAt(Math.max(region.start, trimmedEnd(content, region.end))).line;
+  }
+
+  let source = '';
+  let copied = 0;
+  for (const [start, end] of blanked) {
+    // ASCII only, so byte columns of the rest of the line stay the same
+    source += content.slice(copied, start) + content.slice(start, end).replace(/[\x21-\x7e]/g, ' ');
+    copied = end;
+  }
+  source += content.slice(copied);
+
+  return { source, skipped: regions, tokens: allTokens };
+}
+
+/**
+ * Unterminated literals and, in files whose brackets don't balance, unclosed and
+ * unmatched brackets, with the offset each is reported at. A bracket still open
+ * when an item starts is unclosed; a closing bracket that matches one further down
+ * the stack closes it and leaves the ones above it unclosed.
+ */
+function findProblems(content: string, allTokens: RustToken[], tokens: RustToken[]): Array<{ offset: number; problem: string }> {
+  const problems: Array<{ offset: number; problem: string }> = [];
+  const unclosed = (token: RustToken) => problems.push({ offset: token.start, problem: `unclosed \`${token.text}\`` });
+  const open: RustToken[] = [];
+
+  for (const token of allTokens) {
+    if (token.kind === 'comment' && token.text.startsWith('/*') && (token.text.length < 4 || !token.text.endsWith('*/'))) {
+      problems.push({ offset: token.start, problem: 'unterminated block comment' });
+    } else if (token.kind === 'literal' && isUnterminatedString(token.text)) {
+      problems.push({ offset: token.start, problem: 'unterminated string' });
+    }
+  }
+  // Unindented items inside a block are fine as long as every bracket pairs up
+  if (hasBalancedDelimiters(tokens)) return problems;
+
+  for (const token of tokens) {
+    if (open.length > 0 && isItemStart(content, token)) {
+      open.forEach(unclosed);
+      open.length = 0;
+    }
+    if (token.kind !== 'punct') continue;
+
+    if (OPENING[token.text]) {
+      open.push(token);
+    } else if (CLOSING[token.text]) {
+      const match = open.map(t => t.text).lastIndexOf(CLOSING[token.text]);
+      if (match === -1) {
+        problems.push({ offset: token.start, problem: `unmatched \`${token.text}\`` });
+      } else {
+        open.splice(match + 1).forEach(unclosed);
+        open.pop();
+      }
+    }
+  }
+  open.forEach(unclosed);
+
+  return problems;
+}
+
+function isItemStart(content: string, token: RustToken): boolean {
+  const lineStart = token.start === 0 || content[token.start - 1] === '\n' || (token.start === 1 && content[0] === '\uFEFF');
+  return lineStart && token.kind !== 'comment' && ITEM_KEYWORDS.has(token.text);
+}
+
+// `"...`, `b"...`, `c"...` running to the end of the file; raw strings end with their `#`s
+function isUnterminatedString(text: string): boolean {
+  const quoted = text.replace(/^[bc]/, '');
+  if (!quoted.startsWith('"')) return false;
+  if (quoted.length < 2 || !quoted.endsWith('"')) return true;
+  // A closing quote that is escaped: `"abc\"`
+  const backslashes = quoted.slice(1, -1).match(/\\*$/)![0].length;
+  return backslashes % 2 === 1;
+}
+
+// Offset of the last non-whitespace character before `end`, so regions end on their last line of code
+function trimmedEnd(content: string, end: number): number {
+  let k = end - 1;
+  while (k > 0 && /\s/.test(content[k])) k--;
+  return k;
+}
@@ -0,0 +1,292 @@
+import * as fs from 'fs';
+import { SourceSpan } from './CLIAnalyzer';
+import { applyEdits, TextEdit } from './FixEngine';
+import { resolvePath } from './RustBannedImports';
+import { declarationModule, RustCrate } from './RustCrate';
+import { RustFixer } from './RustFixer';
+import { identifierName, RustToken, tokenizeRust } from './RustLexer';
+import { enclosingModules } from './RustQualifiedPaths';
+import { parseExternCrates, parseUseDeclarations, UseTree } from './RustUseTree';
+
+export interface RenameOptions {
+  /** Module path of the file in its crate; empty for the crate root or a file on its own */
+  modulePath?: string[];
+  /** The file's crate, to resolve paths through its modules */
+  crate?: RustCrate;
+}
+
+export interface RenameEdits {
+  edits: TextEdit[];
+  /** How many import leaves and written-out paths the edits rewrite */
+  paths: number;
+}
+
+export interface RenamedFile {
+  filePath: string;
+  before: string;
+  after: string;
+  paths: number;
+}
+
+/** Whether `text` can be renamed from or to: a `crate::` or crate-name path of at least two segments */
+export function isRenamePath(text: string): boolean {
+  return /^(r#)?[A-Za-z_][A-Za-z0-9_]*(::(r#)?[A-Za-z_][A-Za-z0-9_]*)+$/.test(text) && !/^(self|super)::/.test(text);
+}
+
+/** A terminal node of a use tree, with the trees from the root down to it */
+interface UsePath {
+  chain: UseTree[];
+  /** Every segment written along the chain */
+  written: string[];
+}
+
+/** Where a path matching `from` ends in a use tree */
+interface Match {
+  /** Tree holding the last segment of the match */
+  node: UseTree;
+  /** Index of that segment in `node.segments` */
+  segment: number;
+  /** Segments written in the trees above `node` */
+  above: string[];
+}
+
+/**
+ * Rewrite every path to the module (or item) `from` in one Rust file so it points at
+ * `to`, both given from the crate root (`crate::utils::parse`) or as paths of another
+ * crate. Paths are matched after resolving them from the file's module, so
+ * `super::parse::Config` and `self::parse` are found too, and names brought in by a
+ * `use` item are followed in written-out paths (`utils::parse::run()` after
+ * `use crate::utils;`).
+ *
+ * - A path whose tree can keep its shape is edited in place:
+ *   `use crate::utils::{parse::Config, Other};` → `use crate::utils::{parsing::Config, Other};` for `crate::utils::parsing`
+ * - Otherwise the matched parts of a group move to a `use` item of their own after it,
+ *   with the same attributes and visibility: `use crate::utils::{parse::Config, Other};` →
+ *   `use crate::utils::Other;` and `use crate::parsing::Config;`
+ * - An import of `from` itself whose name changes gets an alias, so the name it binds,
+ *   or re-exports with `pub use`, stays the same: `use crate::utils::parse;` → `use crate::parsing as parse;`
+ * - Written-out paths in code are replaced up to the end of the match:
+ *   `crate::utils::parse::run()` → `crate::parsing::run()`, and `to` is written as given
+ *   even where the original was relative
+ *
+ * `mod` items are left alone, and so are paths inside the module being moved that
+ * are relative to it (`super::`), which still point where they did.
+ */
+export function renameRustPaths(content: string, from: string[], to: string[], options: RenameOptions = {}): RenameEdits {
+  const { modulePath = [], crate } = options;
+  const tokens = tokenizeRust(content).filter(t => t.kind !== 'comment');
+  const declarations = parseUseDeclarations(tokens);
+  const target = to.join('::');
+  const oldName = identifierName(from[from.length - 1]);
+  const renamesItem = oldName !== identifierName(to[to.length - 1]);
+
+  const tokenAt = new Map(tokens.map((token, i) => [token.start, i]));
+  // Offset one past segment `k` of a tree
+  const segmentEnd = (tree: UseTree, k: number) => {
+    let i = tokenAt.get(tree.start)!;
+    for (let s = 0; s < k; s++) i += tree.segments[s] === '' ? 1 : 2;
+    return tokens[i].end;
+  };
+  // A leading `::` stays in front of another crate's path
+  const keepRoot = (start: number) => (to[0] !== 'crate' && content.startsWith('::', start) ? start + 2 : start);
+
+  const edits: TextEdit[] = [];
+  const removed: SourceSpan[] = [];
+  let paths = 0;
+  const bindings = new Map<string, Map<string, Binding>>();
+
+  for (const declaration of declarations) {
+    const module = declarationModule(modulePath, declaration);
+    const matches: Match[] = [];
+    // Offsets to give an alias of the old name at
+    const aliases = new Map<number, string>();
+
+    for (const { chain, written } of usePaths(declaration.tree)) {
+      const leaf = chain[chain.length - 1];
+      const named = leaf.kind === 'simple' && written.length > 1 && written[written.length - 1] === 'self' ? written.slice(0, -1) : written;
+      const w = leaf.kind === 'glob' && named.length === 0 ? 0 : matchLength(named, module, from, crate);
+      if (leaf.kind === 'simple' && leaf.alias !== '_') {
+        const key = module.join('::');
+        const names = bindings.get(key) ?? new Map<string, Binding>();
+        names.set(leaf.alias ?? identifierName(named[named.length - 1]), { path: resolvePath(named.map(identifierName), module, crate), renamed: w > 0 });
+        bindings.set(key, names);
+      }
+      if (w === 0) continue;
+      paths++;
+
+      let count = 0;
+      const n = chain.findIndex(tree => (count += tree.segments.length) >= w);
+      const node = chain[n];
+      const segment = w - 1 - (count - node.segments.length);
+      if (!matches.some(match => match.node === node)) {
+        matches.push({ node, segment, above: chain.slice(0, n).flatMap(tree => tree.segments) });
+      }
+      if (leaf.kind === 'simple' && leaf.alias === undefined && w === named.length && renamesItem) {
+        aliases.set(leaf.end, ` as ${from[from.length - 1]}`);
+      }
+    }
+    if (matches.length === 0) continue;
+
+    // The part of `to` to write over a match, when the trees above it already lead to the rest
+    const inPlace = (match: Match) => {
+      if (match.node === declaration.tree) return target;
+      const above = resolvePath(match.above.map(identifierName), module, crate);
+      return above.length < to.length && equalPaths(above, to.slice(0, above.length)) ? to.slice(above.length).join('::') : undefined;
+    };
+    if (matches.every(match => inPlace(match) !== undefined)) {
+      for (const match of matches) {
+        edits.push({ start: keepRoot(match.node.start), end: segmentEnd(match.node, match.segment), newText: inPlace(match)! });
+      }
+      for (const [at, alias] of aliases) edits.push({ start: at, end: at, newText: alias });
+      continue;
+    }
+
+    // Text of a matched tree after the match, with the aliases it gets
+    const rest = (match: Match) => withInserts(content, segmentEnd(match.node, match.segment), match.node.end, aliases);
+    const moved = matches.length === 1
+      ? target + rest(matches[0])
+      : `${target}::{${matches.map(rest).map(text => (text.startsWith('::') ? text.slice(2) : `self${text}`)).join(', ')}}`;
+    const isMoved = (tree: UseTree) => matches.some(({ node }) => node.start <= tree.start && tree.end <= node.end);
+    if (declaration.leaves.every(leaf => isMoved(leaf.tree))) {
+      edits.push({ start: declaration.tree.start, end: declaration.tree.end, newText: moved });
+      continue;
+    }
+
+    // The rest of the group stays; the moved part follows as an item of its own
+    removed.push(...declaration.leaves.filter(leaf => isMoved(leaf.tree)).map(leaf => ({ start: leaf.tree.start, end: leaf.tree.end })));
+    const lineStart = content.lastIndexOf('\n', declaration.itemStart - 1) + 1;
+    const indent = content.slice(lineStart, declaration.itemStart).match(/^[ \t]*/)![0];
+    const useToken = tokens.slice(declaration.tokenRange[0]).find(token => token.text === 'use')!;
+    const prefix = content.slice(declaration.itemStart, useToken.start);
+    edits.push({ start: declaration.end, end: declaration.end, newText: `\n${indent}${prefix}use ${moved};` });
+  }
+
+  if (removed.length > 0) {
+    const leaves = removed.map(span => ({ line: 0, importStatement: '', symbols: [], reason: '', span }));
+    edits.push(...new RustFixer().computeEdits(content, leaves));
+  }
+
+  for (const span of findCodePaths(tokens, [...declarations, ...parseExternCrates(tokens)], modulePath, crate, from, bindings)) {
+    edits.push({ start: keepRoot(span.start), end: span.end, newText: target });
+    paths++;
+  }
+
+  return { edits, paths };
+}
+
+/**
+ * Rewrite the paths to `from` in each of `files` (see `renameRustPaths`), resolved
+ * in the module its crate mounts it at. Only files with paths to rewrite are
+ * returned; nothing is written.
+ */
+export function renameModulePath(files: string[], from: string, to: string, options: { crateRoot?: string } = {}): RenamedFile[] {
+  const fromPath = from.split('::');
+  const toPath = to.split('::');
+  const crates = new Map<string, RustCrate>();
+  const renamed: RenamedFile[] = [];
+
+  for (const filePath of files) {
+    const crate = RustCrate.forFile(filePath, crates, options.crateRoot);
+    const before = fs.readFileSync(filePath, 'utf-8');
+    const { edits, paths } = renameRustPaths(before, fromPath, toPath, { modulePath: crate.fileFor(filePath)?.modulePath, crate });
+    if (paths > 0) renamed.push({ filePath, before, after: applyEdits(before, edits), paths });
+  }
+
+  return renamed;
+}
+
+/** What an imported name stands for, and whether its import is one of the renamed paths */
+interface Binding {
+  path: string[];
+  renamed: boolean;
+}
+
+/** Length of the shortest prefix of `written` that resolves to `from` in `module`, 0 for none */
+function matchLength(written: string[], module: string[], from: string[], crate: RustCrate | undefined): number {
+  for (let w = 1; w <= written.length; w++) {
+    if (equalPaths(resolvePath(written.slice(0, w).map(identifierName), module, crate), from)) return w;
+  }
+  return 0;
+}
+
+/**
+ * Spans of the written-out paths to `from` in code, outside `use` and `extern crate`
+ * items, up to the end of the match. Paths starting with a name whose import is
+ * renamed are skipped: the import keeps the name.
+ */
+function findCodePaths(
+  tokens: RustToken[],
+  items: Array<{ tokenRange: [number, number] }>,
+  modulePath: string[],
+  crate: RustCrate | undefined,
+  from: string[],
+  bindings: Map<string, Map<string, Binding>>
+): SourceSpan[] {
+  const inItem = new Array<boolean>(tokens.length).fill(false);
+  for (const item of items) {
+    for (let j = item.tokenRange[0]; j <= item.tokenRange[1]; j++) inItem[j] = true;
+  }
+
+  const modules = enclosingModules(tokens);
+  const inlinePaths = new Map<number, string[]>([[-1, modulePath]]);
+  const inlinePath = (open: number): string[] => {
+    let path = inlinePaths.get(open);
+    if (!path) {
+      path = [...inlinePath(modules[open - 2] ?? -1), identifierName(tokens[open - 1].text)];
+      inlinePaths.set(open, path);
+    }
+    return path;
+  };
+
+  const found: SourceSpan[] = [];
+  for (let i = 0; i < tokens.length; i++) {
+    if (inItem[i] || tokens[i].kind !== 'ident' || tokens[i + 1]?.text !== '::') continue;
+    const previous = tokens[i - 1];
+    if (previous?.text === '.') continue;
+    const rooted = previous?.text === '::' && tokens[i - 2]?.kind !== 'ident' && tokens[i - 2]?.text !== '>';
+    if (previous?.text === '::' && !rooted) continue;
+
+    let last = i;
+    while (tokens[last + 1]?.text === '::' && tokens[last + 2]?.kind === 'ident') last += 2;
+    const names: string[] = [];
+    for (let j = i; j <= last; j += 2) names.push(identifierName(tokens[j].text));
+    const start = rooted ? i - 1 : i;
+    i = last;
+
+    const module = inlinePath(modules[start]);
+    const imported = rooted ? undefined : bindings.get(module.join('::'))?.get(names[0]);
+    if (imported?.renamed) continue;
+
+    let w = 0;
+    if (imported) {
+      for (let k = 2; k <= names.length && w === 0; k++) {
+        if (equalPaths([...imported.path, ...names.slice(1, k)], from)) w = k;
+      }
+    } else {
+      w = rooted ? matchLength(['', ...names], module, from, crate) - 1 : matchLength(names, module, from, crate);
+    }
+    if (w > 0) found.push({ start: tokens[start].start, end: tokens[i - 2 * (names.length - w)].end });
+  }
+
+  return found;
+}
+
+/** `content` from `start` to `end`, with `inserts` at the offsets in that range, `end` included */
+function withInserts(content: string, start: number, end: number, inserts: Map<number, string>): string {
+  let text = '';
+  let copied = start;
+  for (const at of [...inserts.keys()].filter(at => at >= start && at <= end).sort((a, b) => a - b)) {
+    text += content.slice(copied, at) + inserts.get(at);
+    copied = at;
+  }
+  return text + content.slice(copied, end);
+}
+
+function equalPaths(a: string[], b: string[]): boolean {
+  return a.length === b.length && a.every((segment, k) => segment === b[k]);
+}
+
+function usePaths(tree: UseTree, chain: UseTree[] = [], written: string[] = []): UsePath[] {
+  const path: UsePath = { chain: [...chain, tree], written: [...written, ...tree.segments] };
+  return tree.kind === 'group' ? tree.children.flatMap(child => usePaths(child, path.chain, path.written)) : [path];
+}
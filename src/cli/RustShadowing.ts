@@ -0,0 +1,225 @@
+import { CfgCondition } from './RustCfg';
+import { findClosingDelimiter, RustToken } from './RustLexer';
+
+/** The keyword that introduces a local definition; `let` bindings and fn parameters included */
+export type DefinitionKind =
+  | 'fn' | 'const' | 'static' | 'struct' | 'enum' | 'union' | 'type' | 'trait' | 'mod'
+  | 'macro_rules!' | 'let' | 'parameter';
+
+export interface LocalDefinition {
+  name: string;
+  kind: DefinitionKind;
+  /** Index of the token that names it */
+  index: number;
+  /** Index of the `{` of the block it is defined in, or -1 at the top of the file */
+  block: number;
+  /** First and last token index it can be referred to from */
+  visible: [number, number];
+}
+
+const ITEM_KEYWORDS = new Set<string>(['fn', 'const', 'static', 'struct', 'enum', 'union', 'type', 'trait', 'mod']);
+// Items that live in the type namespace: `struct Parser` also hides a `Parser::new` import
+const TYPE_KINDS = new Set<DefinitionKind>(['struct', 'enum', 'union', 'type', 'trait', 'mod']);
+// Words that follow a definition keyword without being the defined name (`const fn`, `static mut`)
+const NOT_NAMES = new Set(['fn', 'unsafe', 'async', 'extern', 'mut', 'ref', 'self', 'Self', 'crate', 'super', '_']);
+
+/**
+ * Index of the name an item keyword (`fn`, `struct`, `const`, `mod`, ...) at `at` defines,
+ * or undefined when the token doesn't start an item
+ */
+export function itemNameIndex(tokens: RustToken[], at: number): number | undefined {
+  const keyword = tokens[at].text;
+  const previous = tokens[at - 1]?.text;
+  if (tokens[at].kind !== 'ident' || !ITEM_KEYWORDS.has(keyword) || previous === '.' || previous === '::') return undefined;
+  // `<const N: usize>` is a generic parameter, `*const T` and `&raw const x` are pointers
+  if (keyword === 'const' && (previous === '<' || previous === ',' || previous === '*' || previous === 'raw')) return undefined;
+
+  let i = at + 1;
+  while (keyword === 'static' && (tokens[i]?.text === 'mut' || tokens[i]?.text === 'ref')) i++;
+  const name = tokens[i];
+  return name?.kind === 'ident' && !NOT_NAMES.has(name.text) ? i : undefined;
+}
+
+/**
+ * Local definitions of a file and the part of the file each one is visible in, to
+ * tell when a name refers to a local item or binding rather than an import.
+ *
+ * Items (`fn`, `struct`, `const`, `mod`, ...) are visible throughout their enclosing
+ * block, or the whole file; `let` bindings from the end of their statement to the
+ * end of the block; fn parameters in the fn body; `macro_rules!` macros after their
+ * definition. Members of `impl` and `trait` bodies are not in scope by name and are
+ * left out; so are bindings made by patterns (`if let`, `match` arms, closures).
+ */
+export class RustShadowing {
+  private definitions: LocalDefinition[] = [];
+  /** Innermost `{` enclosing each token, or -1 */
+  private enclosing: number[];
+  /** Matching `}` of each `{` */
+  private closing = new Map<number, number>();
+  /** Indices of the tokens that name a definition, which are not uses of anything */
+  readonly names = new Set<number>();
+
+  constructor(private tokens: RustToken[], private conditions: CfgCondition[]) {
+    this.enclosing = this.findBlocks();
+    this.collectDefinitions();
+    this.definitions.forEach(definition => this.names.add(definition.index));
+  }
+
+  /**
+   * The local definition the identifier at `use` refers to instead of an import whose
+   * `use` item starts at token `importAt`. Definitions in an enclosing block of the
+   * import don't hide it, and a definition only counts when it is compiled whenever
+   * the use is.
+   */
+  shadowing(use: number, importAt: number): LocalDefinition | undefined {
+    const name = this.tokens[use].text;
+    const importBlock = this.enclosing[importAt];
+    let found: LocalDefinition | undefined;
+
+    for (const definition of this.definitions) {
+      if (definition.name !== name || definition.index === use) continue;
+      if (use < definition.visible[0] || use > definition.visible[1]) continue;
+      if (!this.contains(importBlock, definition.block) || !this.inNamespace(definition, use)) continue;
+      if (!this.conditions[definition.index].every(predicate => this.conditions[use].includes(predicate))) continue;
+
+      // The innermost, latest definition is the one the name resolves to
+      if (!found || definition.block > found.block || (definition.block === found.block && definition.index > found.index)) {
+        found = definition;
+      }
+    }
+
+    return found;
+  }
+
+  /**
+   * Whether `name!` or `name::` at `use` can refer to the definition: values and
+   * functions don't start paths, and only `macro_rules!` macros are invoked with `!`
+   */
+  private inNamespace(definition: LocalDefinition, use: number): boolean {
+    const next = this.tokens[use + 1]?.text;
+    if (definition.kind === 'macro_rules!') return next === '!';
+    if (next === '!') return false;
+    return TYPE_KINDS.has(definition.kind) || next !== '::';
+  }
+
+  /** Whether block `inner` is `outer` or nested inside it */
+  private contains(outer: number, inner: number): boolean {
+    if (outer === -1) return true;
+    if (inner === -1) return false;
+    return inner >= outer && inner <= this.closeOf(outer);
+  }
+
+  private closeOf(block: number): number {
+    return block === -1 ? this.tokens.length - 1 : this.closing.get(block) ?? this.tokens.length - 1;
+  }
+
+  private findBlocks(): number[] {
+    const enclosing = new Array<number>(this.tokens.length).fill(-1);
+    const open: number[] = [];
+
+    this.tokens.forEach((token, i) => {
+      if (token.text === '}' && open.length > 0) this.closing.set(open.pop()!, i);
+      enclosing[i] = open.length > 0 ? open[open.length - 1] : -1;
+      if (token.text === '{') open.push(i);
+    });
+
+    return enclosing;
+  }
+
+  private collectDefinitions(): void {
+    const tokens = this.tokens;
+
+    for (let i = 0; i < tokens.length; i++) {
+      const token = tokens[i];
+      if (token.kind !== 'ident') continue;
+      const previous = tokens[i - 1]?.text;
+      if (previous === '.' || previous === '::') continue;
+
+      const block = this.enclosing[i];
+      if (token.text === 'let') {
+        this.addLetBinding(i, block);
+      } else if (token.text === 'macro_rules' && tokens[i + 1]?.text === '!' && tokens[i + 2]?.kind === 'ident') {
+        this.add(tokens[i + 2].text, 'macro_rules!', i + 2, block, [i + 2, this.closeOf(block)]);
+      } else {
+        const at = itemNameIndex(tokens, i);
+        if (at === undefined) continue;
+
+        if (!this.isMemberBlock(block)) {
+          this.add(tokens[at].text, token.text as DefinitionKind, at, block, [block === -1 ? 0 : block, this.closeOf(block)]);
+        }
+        if (token.text === 'fn') this.addParameters(at + 1);
+      }
+    }
+  }
+
+  /** `let [ref] [mut] name ...;` statements; other patterns are skipped */
+  private addLetBinding(at: number, block: number): void {
+    const tokens = this.tokens;
+    const previous = tokens[at - 1]?.text;
+    if (previous !== undefined && previous !== ';' && previous !== '{' && previous !== '}' && previous !== ']') return;
+
+    let i = at + 1;
+    while (tokens[i]?.text === 'ref' || tokens[i]?.text === 'mut') i++;
+    const name = tokens[i];
+    const next = tokens[i + 1]?.text;
+    if (name?.kind !== 'ident' || NOT_NAMES.has(name.text) || (next !== '=' && next !== ':' && next !== ';')) return;
+
+    // Visible after the statement: `let x = x + 1;` still reads the outer `x`
+    let end = i + 1;
+    while (end < tokens.length && tokens[end].text !== ';') {
+      if (tokens[end].text === '(' || tokens[end].text === '[' || tokens[end].text === '{') {
+        end = findClosingDelimiter(tokens, end);
+      }
+      if (tokens[end]?.text === '}' && end === this.closeOf(block)) break;
+      end++;
+    }
+    this.add(name.text, 'let', i, block, [end, this.closeOf(block)]);
+  }
+
+  /** `name: Type` parameters of the fn whose generics or parameter list start at `from`, visible in its body */
+  private addParameters(from: number): void {
+    const tokens = this.tokens;
+    let open = from;
+    while (open < tokens.length && tokens[open].text !== '(' && tokens[open].text !== '{' && tokens[open].text !== ';') open++;
+    if (tokens[open]?.text !== '(') return;
+
+    const close = findClosingDelimiter(tokens, open);
+    let body = close + 1;
+    while (body < tokens.length && tokens[body].text !== '{' && tokens[body].text !== ';') body++;
+    if (tokens[body]?.text !== '{') return;
+
+    for (let i = open + 1; i < close; i++) {
+      const text = tokens[i].text;
+      if (text === '(' || text === '[' || text === '{') {
+        i = findClosingDelimiter(tokens, i);
+        continue;
+      }
+
+      const previous = tokens[i - 1].text;
+      const startsParameter = previous === '(' || previous === ',' || previous === 'mut';
+      if (tokens[i].kind === 'ident' && !NOT_NAMES.has(text) && startsParameter && tokens[i + 1]?.text === ':') {
+        this.add(text, 'parameter', i, body, [body, this.closeOf(body)]);
+      }
+    }
+  }
+
+  /**
+   * Whether the block opened at `block` is an `impl` or `trait` body, whose items
+   * are reached through a type (`Self::NAME`) or a receiver rather than by name
+   */
+  private isMemberBlock(block: number): boolean {
+    let sawMemberKeyword = false;
+    for (let i = block - 1; i >= 0; i--) {
+      const text = this.tokens[i].text;
+      if (text === ';' || text === '{' || text === '}') break;
+      // `fn f() -> impl Trait {` is a function body
+      if (text === 'fn') return false;
+      if (text === 'impl' || text === 'trait') sawMemberKeyword = true;
+    }
+    return block !== -1 && sawMemberKeyword;
+  }
+
+  private add(name: string, kind: DefinitionKind, index: number, block: number, visible: [number, number]): void {
+    this.definitions.push({ name, kind, index, block, visible });
+  }
+}
@@ -0,0 +1,226 @@
+import { LineIndex } from './LineIndex';
+import { findClosingDelimiter, RustToken } from './RustLexer';
+import { UseDeclaration, UseLeaf } from './RustUseTree';
+
+/**
+ * An `#[allow(unused_imports)]`-style attribute or an `// importlens: ignore` comment
+ */
+export interface Suppression {
+  /** Source text, e.g. `#[allow(unused_imports)]` */
+  text: string;
+  start: number;
+  end: number;
+}
+
+/**
+ * Source range governed by a lint attribute. Inner attributes (`#![...]`) cover
+ * their enclosing block or the whole file; outer attributes cover the item they precede.
+ */
+interface LintRegion {
+  start: number;
+  end: number;
+  /** `allow`/`expect` suppress; `warn`/`deny`/`forbid` re-enable the lint */
+  suppresses: boolean;
+  attribute: Suppression;
+}
+
+type LintLevel = 'suppress' | 'report';
+
+// `unused` is the lint group that contains `unused_imports`
+const LINT_NAMES = new Set(['unused_imports', 'unused']);
+const LEVELS: Record<string, LintLevel> = {
+  allow: 'suppress',
+  expect: 'suppress',
+  warn: 'report',
+  deny: 'report',
+  forbid: 'report',
+};
+const IGNORE_MARKER = /^\/[/*]\s*importlens:\s*ignore\b/;
+
+/**
+ * Decides which `use` leaves are exempt from unused-import findings.
+ *
+ * A leaf is covered by the nearest of: an `// importlens: ignore` comment on the
+ * line above its item (or the leaf itself) or at the end of its line; an outer
+ * attribute on its `use` item or an enclosing item such as a `mod`; an inner
+ * attribute of an enclosing block or of the crate root.
+ */
+export class RustSuppressions {
+  private regions: LintRegion[];
+  private comments = new Map<UseLeaf, Suppression>();
+
+  /**
+   * @param tokens All tokens of the file, including comments
+   */
+  constructor(content: string, tokens: RustToken[], declarations: UseDeclaration[]) {
+    const code = tokens.filter(t => t.kind !== 'comment');
+    this.regions = findLintRegions(content, code);
+    this.findIgnoreComments(tokens, declarations, new LineIndex(content));
+  }
+
+  /**
+   * The suppression that applies to a leaf, or undefined when findings for it are reported
+   */
+  suppressionFor(leaf: UseLeaf): Suppression | undefined {
+    const comment = this.comments.get(leaf);
+    if (comment) return comment;
+
+    let innermost: LintRegion | undefined;
+    for (const region of this.regions) {
+      if (region.start <= leaf.tree.start && leaf.tree.end <= region.end &&
+          (!innermost || region.start > innermost.start)) {
+        innermost = region;
+      }
+    }
+
+    return innermost?.suppresses ? innermost.attribute : undefined;
+  }
+
+  private findIgnoreComments(tokens: RustToken[], declarations: UseDeclaration[], lineIndex: LineIndex): void {
+    const lineOf = (offset: number) => lineIndex.positionAt(offset).line;
+    const leaves = declarations.flatMap(declaration => declaration.leaves.map(leaf => ({ declaration, leaf })));
+
+    tokens.forEach((token, index) => {
+      if (token.kind !== 'comment' || !IGNORE_MARKER.test(token.text)) return;
+
+      const suppression = { text: token.text, start: token.start, end: token.end };
+      const line = lineOf(token.start);
+      const previous = tokens[index - 1];
+      const trailing = previous !== undefined && previous.kind !== 'comment' && lineOf(previous.end) === line;
+
+      let covered: UseLeaf[];
+      if (trailing) {
+        // At the end of an import line: the leaves on that line, or the item that starts there
+        covered = leaves.filter(({ leaf }) => lineOf(leaf.tree.start) === line).map(({ leaf }) => leaf);
+        if (covered.length === 0) {
+          covered = leaves.filter(({ declaration }) => lineOf(declaration.start) === line).map(({ leaf }) => leaf);
+        }
+      } else {
+        // On the line above: the item (or the group member) on the next line
+        covered = leaves
+          .filter(({ declaration, leaf }) => lineOf(declaration.itemStart) === line + 1 || lineOf(leaf.tree.start) === line + 1)
+          .map(({ leaf }) => leaf);
+      }
+
+      for (const leaf of covered) {
+        if (!this.comments.has(leaf)) this.comments.set(leaf, suppression);
+      }
+    });
+  }
+}
+
+/**
+ * Find every attribute that sets the level of `unused_imports` and the range it governs
+ */
+function findLintRegions(content: string, tokens: RustToken[]): LintRegion[] {
+  const regions: LintRegion[] = [];
+  // Indices of the currently open `{` tokens
+  const blocks: number[] = [];
+
+  for (let i = 0; i < tokens.length; i++) {
+    const text = tokens[i].text;
+    if (text === '{') {
+      blocks.push(i);
+      continue;
+    }
+    if (text === '}') {
+      blocks.pop();
+      continue;
+    }
+    if (text !== '#') continue;
+
+    const isInner = tokens[i + 1]?.text === '!';
+    const open = isInner ? i + 2 : i + 1;
+    if (tokens[open]?.text !== '[') continue;
+
+    const close = findClosingDelimiter(tokens, open);
+    const level = lintLevel(tokens.slice(open + 1, close));
+    const start = tokens[i].start;
+    const end = tokens[Math.min(close, tokens.length - 1)].end;
+    const attribute = { text: content.slice(start, end).replace(/\s+/g, ' '), start, end };
+
+    if (level) {
+      const suppresses = level === 'suppress';
+      if (isInner) {
+        const block = blocks[blocks.length - 1];
+        regions.push(block === undefined
+          ? { start: 0, end: content.length, suppresses, attribute }
+          : { start: tokens[block].start, end: tokens[Math.min(findClosingDelimiter(tokens, block), tokens.length - 1)].end, suppresses, attribute });
+      } else {
+        regions.push({ start, end: tokens[Math.min(findItemEnd(tokens, close + 1), tokens.length - 1)].end, suppresses, attribute });
+      }
+    }
+
+    i = close;
+  }
+
+  return regions;
+}
+
+/**
+ * Level given to `unused_imports` by the contents of one attribute, such as
+ * `allow(unused_imports)`, `expect(unused, reason = "...")` or `cfg_attr(test, allow(unused_imports))`
+ */
+function lintLevel(tokens: RustToken[]): LintLevel | undefined {
+  const name = tokens[0]?.text;
+  if (tokens[1]?.text !== '(') return undefined;
+  const args = splitArguments(tokens.slice(2, findClosingDelimiter(tokens, 1)));
+
+  if (name === 'cfg_attr') {
+    for (const attribute of args.slice(1)) {
+      const level = lintLevel(attribute);
+      if (level) return level;
+    }
+    return undefined;
+  }
+
+  const level = LEVELS[name];
+  if (!level) return undefined;
+  return args.some(arg => arg.length === 1 && LINT_NAMES.has(arg[0].text)) ? level : undefined;
+}
+
+/**
+ * Split attribute arguments on top-level commas
+ */
+function splitArguments(tokens: RustToken[]): RustToken[][] {
+  const args: RustToken[][] = [[]];
+  let depth = 0;
+
+  for (const token of tokens) {
+    if (token.text === '(' || token.text === '[' || token.text === '{') depth++;
+    else if (token.text === ')' || token.text === ']' || token.text === '}') depth--;
+
+    if (token.text === ',' && depth === 0) {
+      args.push([]);
+    } else {
+      args[args.length - 1].push(token);
+    }
+  }
+
+  return args.filter(arg => arg.length > 0);
+}
+
+/**
+ * Index of the last token of the item starting at `start` (after any further
+ * attributes): its terminating `;`, or the `}` closing its body
+ */
+function findItemEnd(tokens: RustToken[], start: number): number {
+  let depth = 0;
+
+  for (let j = start; j < tokens.length; j++) {
+    const text = tokens[j].text;
+    if (text === '#' && depth === 0 && tokens[j + 1]?.text === '[') {
+      j = findClosingDelimiter(tokens, j + 1);
+    } else if (text === '{' && depth === 0) {
+      return findClosingDelimiter(tokens, j);
+    } else if (text === '(' || text === '[') {
+      depth++;
+    } else if (text === ')' || text === ']') {
+      depth--;
+    } else if ((text === ';' || text === ',') && depth === 0) {
+      return j;
+    }
+  }
+
+  return tokens.length - 1;
+}
@@ -0,0 +1,117 @@
+import { CfgCondition, CfgPredicate } from './RustCfg';
+import { findClosingDelimiter, RustToken } from './RustLexer';
+import { enclosingModules } from './RustQualifiedPaths';
+import { itemNameIndex } from './RustShadowing';
+import { UseDeclaration, UseLeaf } from './RustUseTree';
+
+// Crates every module can name, so paths starting at them mean the same in a child module
+const ROOT_SEGMENTS = new Set(['', 'crate', 'std', 'core', 'alloc']);
+
+/** A `#[cfg(test)] mod name { ... }` module */
+export interface TestModule {
+  name: string;
+  /** Index of the `mod` keyword */
+  keyword: number;
+  /** Indices of the `{` and `}` of its body */
+  body: [number, number];
+}
+
+/**
+ * Code compiled only for tests: `#[cfg(test)]` items (modules above all) and the
+ * bodies of `#[test]` functions, `#[tokio::test]` and other `::test` attributes included
+ */
+export class RustTestCode {
+  readonly modules: TestModule[] = [];
+  /** Token ranges of `#[test]` functions, attributes to closing brace */
+  private functions: Array<[number, number]> = [];
+  /** Index of the `{` of the module around each token, or -1 */
+  readonly enclosing: number[];
+
+  constructor(tokens: RustToken[], private conditions: CfgCondition[]) {
+    this.enclosing = enclosingModules(tokens);
+
+    for (let i = 0; i < tokens.length; i++) {
+      const text = tokens[i].text;
+      if (text === 'mod' && tokens[i + 1]?.kind === 'ident' && tokens[i + 2]?.text === '{' && requiresTest(conditions[i])) {
+        const open = i + 2;
+        // Nested test modules are part of the outermost one
+        if (!this.modules.some(module => module.body[0] < i && i < module.body[1])) {
+          this.modules.push({ name: tokens[i + 1].text, keyword: i, body: [open, findClosingDelimiter(tokens, open)] });
+        }
+      } else if (text === '#' && tokens[i + 1]?.text === '[') {
+        const close = findClosingDelimiter(tokens, i + 1);
+        if (isTestAttribute(tokens, i + 2, close)) {
+          const body = functionBody(tokens, close + 1);
+          if (body !== undefined) this.functions.push([i, findClosingDelimiter(tokens, body)]);
+        }
+      }
+    }
+  }
+
+  /** Whether the token at `index` is only compiled for tests */
+  contains(index: number): boolean {
+    return requiresTest(this.conditions[index]) || this.functions.some(([start, end]) => start <= index && index <= end);
+  }
+
+  /** The test module directly inside the module `{` at `module` (or -1) that holds every token of `indices` */
+  moduleOf(indices: number[], module: number): TestModule | undefined {
+    const found = this.modules.find(candidate => indices.every(i => candidate.body[0] < i && i < candidate.body[1]));
+    return found && this.enclosing[found.keyword] === module ? found : undefined;
+  }
+}
+
+/**
+ * What `leaf`, imported in the module `{` at `module` (or -1), is written as in a
+ * child module: `self::` and `super::` paths go up one more level, and paths that
+ * start at an item of the module itself, which 2018 paths can name, start at `super::`
+ */
+export function childModulePath(tokens: RustToken[], enclosing: number[], module: number, leaf: UseLeaf): string {
+  const path = leaf.isSelf ? leaf.path.slice(0, -1) : [...leaf.path];
+  const [first] = path;
+  const local = !ROOT_SEGMENTS.has(first) && first !== 'self' && first !== 'super' && tokens.some((token, i) => {
+    const name = enclosing[i] === module ? itemNameIndex(tokens, i) : undefined;
+    return name !== undefined && tokens[name].text === first;
+  });
+
+  const moved = first === 'self' ? ['super', ...path.slice(1)] : first === 'super' || local ? ['super', ...path] : path;
+  const { alias } = leaf;
+  return alias !== undefined && alias !== moved[moved.length - 1] ? `${moved.join('::')} as ${alias}` : moved.join('::');
+}
+
+/** Whether a child module names the import of `leaf` through its parent, as `use super::Name;` */
+export function isImportedFromChild(declarations: UseDeclaration[], leaf: UseLeaf): boolean {
+  return declarations.some(declaration => declaration.scopes.length > 0 && declaration.leaves.some(other =>
+    other.path[0] === 'super' && other.path[1] === leaf.binding
+  ));
+}
+
+/** Whether every configuration compiling code under `condition` has `test` set */
+function requiresTest(condition: CfgCondition): boolean {
+  const implies = (predicate: CfgPredicate): boolean =>
+    predicate.kind === 'option' ? predicate.option === 'test' : predicate.kind === 'all' && predicate.predicates.some(implies);
+  return condition.some(implies);
+}
+
+/** `test`, `tokio::test`, `test_case::test(..)`: the attribute between `start` and `close` */
+function isTestAttribute(tokens: RustToken[], start: number, close: number): boolean {
+  let i = start;
+  while (tokens[i]?.kind === 'ident' && tokens[i + 1]?.text === '::') i += 2;
+  return tokens[i]?.text === 'test' && (i + 1 === close || tokens[i + 1]?.text === '(');
+}
+
+/** Index of the `{` of the function a `#[test]` attribute ending before `start` is on */
+function functionBody(tokens: RustToken[], start: number): number | undefined {
+  let i = start;
+  while (i < tokens.length && tokens[i].text !== 'fn') {
+    if (tokens[i].text === '#' && tokens[i + 1]?.text === '[') i = findClosingDelimiter(tokens, i + 1);
+    else if (tokens[i].text === ';' || tokens[i].text === '{' || tokens[i].text === '}') return undefined;
+    i++;
+  }
+  for (; i < tokens.length; i++) {
+    const text = tokens[i].text;
+    if (text === '{') return i;
+    if (text === ';' || text === '}') return undefined;
+    if (text === '(' || text === '[') i = findClosingDelimiter(tokens, i);
+  }
+  return undefined;
+}
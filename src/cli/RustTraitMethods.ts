@@ -0,0 +1,93 @@
+import { findClosingDelimiter, RustToken } from './RustLexer';
+
+/**
+ * Methods of standard library traits that are commonly imported only so their
+ * methods can be called. Keyed by path with `core::`/`alloc::` spelled as `std::`.
+ */
+export const STD_TRAIT_METHODS: Record<string, string[]> = {
+  'std::io::Write': ['write', 'write_all', 'write_fmt', 'write_vectored', 'flush', 'by_ref'],
+  'std::io::Read': ['read', 'read_to_end', 'read_to_string', 'read_exact', 'read_vectored', 'bytes', 'chain', 'take', 'by_ref'],
+  'std::io::BufRead': ['fill_buf', 'consume', 'read_until', 'read_line', 'lines', 'split'],
+  'std::io::Seek': ['seek', 'rewind', 'stream_position'],
+  'std::fmt::Write': ['write_str', 'write_char', 'write_fmt'],
+  'std::hash::Hash': ['hash', 'hash_slice'],
+  'std::hash::Hasher': ['finish', 'write', 'write_u8', 'write_u32', 'write_u64', 'write_usize'],
+  'std::str::FromStr': ['from_str'],
+  'std::borrow::Borrow': ['borrow'],
+  'std::borrow::BorrowMut': ['borrow_mut'],
+  'std::ops::Deref': ['deref'],
+  'std::ops::DerefMut': ['deref_mut'],
+  'std::convert::TryFrom': ['try_from'],
+  'std::convert::TryInto': ['try_into'],
+  'std::iter::FromIterator': ['from_iter'],
+  'std::os::unix::fs::PermissionsExt': ['mode', 'set_mode', 'from_mode'],
+  'std::os::unix::fs::MetadataExt': ['dev', 'ino', 'mode', 'nlink', 'uid', 'gid', 'size', 'mtime'],
+  'std::os::unix::process::CommandExt': ['exec', 'uid', 'gid', 'process_group', 'arg0'],
+  'std::os::unix::ffi::OsStrExt': ['as_bytes', 'from_bytes'],
+};
+
+// `write!`/`writeln!` expand to a `.write_fmt(...)` call on their first argument
+const MACRO_METHODS: Record<string, string> = {
+  write: 'write_fmt',
+  writeln: 'write_fmt',
+};
+
+/**
+ * Names of methods called in the file: `x.name(...)`, `x.name::<T>(...)`,
+ * and the methods implied by `write!`/`writeln!`
+ */
+export function collectMethodCalls(tokens: RustToken[]): Set<string> {
+  const calls = new Set<string>();
+
+  tokens.forEach((token, i) => {
+    if (token.kind !== 'ident') return;
+
+    const next = tokens[i + 1]?.text;
+    if (tokens[i - 1]?.text === '.' && (next === '(' || next === '::')) {
+      calls.add(token.text);
+    } else if (next === '!' && MACRO_METHODS[token.text]) {
+      calls.add(MACRO_METHODS[token.text]);
+    }
+  });
+
+  return calls;
+}
+
+/**
+ * Method names of the traits defined in the file, by trait name
+ */
+export function collectTraitDefinitions(tokens: RustToken[]): Map<string, string[]> {
+  const traits = new Map<string, string[]>();
+
+  for (let i = 0; i < tokens.length; i++) {
+    if (tokens[i].text !== 'trait' || tokens[i + 1]?.kind !== 'ident') continue;
+
+    // Skip generics, supertraits and where clauses up to the body
+    let open = i + 2;
+    while (open < tokens.length && tokens[open].text !== '{' && tokens[open].text !== ';') open++;
+    if (tokens[open]?.text !== '{') continue;
+
+    const close = findClosingDelimiter(tokens, open);
+    const methods: string[] = [];
+    let depth = 0;
+    for (let j = open + 1; j < close; j++) {
+      const text = tokens[j].text;
+      if (text === '{') depth++;
+      else if (text === '}') depth--;
+      else if (depth === 0 && text === 'fn' && tokens[j + 1]?.kind === 'ident') methods.push(tokens[j + 1].text);
+    }
+
+    traits.set(tokens[i + 1].text, methods);
+    i = close;
+  }
+
+  return traits;
+}
+
+/**
+ * Methods of the trait at `path` (a standard library trait, or one defined in the file)
+ */
+export function traitMethods(path: string[], definitions: Map<string, string[]>): string[] | undefined {
+  const normalized = ['core', 'alloc'].includes(path[0]) ? ['std', ...path.slice(1)] : path;
+  return STD_TRAIT_METHODS[normalized.join('::')] ?? definitions.get(path[path.length - 1]);
+}
@@ -0,0 +1,419 @@
+import { RustToken } from './RustLexer';
+
+/**
+ * A node of a Rust `use` tree.
+ *
+ *   use std::io::{self, Read as R, prelude::*};
+ *       ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ group  (segments: std, io)
+ *                 ^^^^                          simple (segments: self)
+ *                       ^^^^^^^^^               simple (segments: Read, alias: R)
+ *                                  ^^^^^^^^^^   glob   (segments: prelude)
+ */
+export interface UseTree {
+  kind: 'simple' | 'glob' | 'group';
+  /** Path segments written in this tree itself, relative to the enclosing group */
+  segments: string[];
+  /** Name given with `as` (simple trees only); `_` for anonymous imports */
+  alias?: string;
+  /** Offset of the `as` keyword, when there is an alias */
+  aliasStart?: number;
+  /** Child trees of a `{...}` group */
+  children: UseTree[];
+  /** Offset of the first character of this tree */
+  start: number;
+  /** Offset one past the last character of this tree */
+  end: number;
+  /** Offset of the `{` for group trees */
+  braceStart?: number;
+  /** Offset one past the `}` for group trees */
+  braceEnd?: number;
+}
+
+/**
+ * A single imported item: the leaf of a use tree resolved to its full path.
+ */
+export interface UseLeaf {
+  /** Full path from the declaration root, e.g. ['std', 'io', 'Write'] */
+  path: string[];
+  /** Name the import binds in scope: the alias, or the last path segment (`self` resolves to its parent) */
+  binding: string;
+  alias?: string;
+  isSelf: boolean;
+  isGlob: boolean;
+  tree: UseTree;
+}
+
+/**
+ * A `{ ... }` region enclosing a `use` item. Imports of a block are visible in
+ * nested blocks, but a `mod name { ... }` starts a fresh scope.
+ */
+export interface UseScope {
+  kind: 'module' | 'block';
+  /** Offset of the opening brace */
+  start: number;
+  /** Offset one past the closing brace; unset while the brace is unclosed */
+  end?: number;
+  /** Module name for `mod` scopes */
+  name?: string;
+}
+
+/**
+ * A `use` item in the body of an `impl`, `trait` or `extern` block, where Rust
+ * doesn't allow one. `parseUseDeclarations` skips these.
+ */
+export interface MisplacedUse {
+  /** Keyword of the block the item is in: `impl`, `trait` or `extern` */
+  owner: string;
+  /** Offset of the `use` keyword */
+  start: number;
+  /** Index range [first, last] of the item's tokens; just the `use` when it doesn't parse */
+  tokenRange: [number, number];
+}
+
+// Items whose `{ ... }` bodies hold associated or foreign items, never `use`
+const NO_USE_ITEMS = new Set(['impl', 'trait', 'extern']);
+
+/**
+ * A complete `use` item, from its visibility modifier to the terminating semicolon.
+ */
+export interface UseDeclaration {
+  /** Visibility written before `use`, e.g. `pub` or `pub(crate)`; empty for private imports */
+  visibility: string;
+  /** Offset of the first token of the item (visibility or `use`) */
+  start: number;
+  /** Offset of the first outer attribute (`#[...]`) attached to the item, or `start` when there is none */
+  itemStart: number;
+  /** Offset one past the terminating `;` */
+  end: number;
+  tree: UseTree;
+  leaves: UseLeaf[];
+  /** Index range [first, last] of the item's tokens in the token list passed to the parser */
+  tokenRange: [number, number];
+  /** Enclosing brace scopes, outermost first; empty at file level */
+  scopes: UseScope[];
+}
+
+/**
+ * An `extern crate name;` or `extern crate name as alias;` item
+ */
+export interface ExternCrate {
+  /** Name of the crate, e.g. `serde` */
+  name: string;
+  /** Name the item binds: the alias if present */
+  binding: string;
+  visibility: string;
+  /** Offset of the first token of the item (visibility or `extern`) */
+  start: number;
+  /** Offset of the first outer attribute attached to the item, or `start` */
+  itemStart: number;
+  /** Offset one past the terminating `;` */
+  end: number;
+  /** Text of the outer attributes, e.g. `#[macro_use]` */
+  attributes: string;
+  /** Index range [first, last] of the item's tokens */
+  tokenRange: [number, number];
+}
+
+/**
+ * How far a `use` item is re-exported: `private` for plain `use` and `pub(self) use`,
+ * `public` for `pub use`, `restricted` for `pub(crate)`, `pub(super)` and `pub(in path)`
+ */
+export type ReexportScope = 'private' | 'public' | 'restricted';
+
+export function reexportScope(declaration: UseDeclaration): ReexportScope {
+  const { visibility } = declaration;
+  if (visibility === '' || visibility === 'pub(self)') return 'private';
+  return visibility === 'pub' ? 'public' : 'restricted';
+}
+
+/**
+ * Find and parse every `use` declaration in a token stream.
+ * Comment tokens must already be filtered out. Malformed declarations are skipped,
+ * and so are misplaced ones (see `findMisplacedUses`).
+ */
+export function parseUseDeclarations(tokens: RustToken[]): UseDeclaration[] {
+  const declarations: UseDeclaration[] = [];
+  const scopes: UseScope[] = [];
+  // Whether each open brace is the body of an item that can't hold `use` items
+  const noUse: boolean[] = [];
+
+  for (let i = 0; i < tokens.length; i++) {
+    const token = tokens[i];
+
+    if (token.text === '{') {
+      const isModule = tokens[i - 1]?.kind === 'ident' && tokens[i - 2]?.text === 'mod';
+      scopes.push(isModule
+        ? { kind: 'module', start: token.start, name: tokens[i - 1].text }
+        : { kind: 'block', start: token.start });
+      noUse.push(bodyOwner(tokens, i) !== undefined);
+      continue;
+    }
+    if (token.text === '}') {
+      const scope = scopes.pop();
+      if (scope) scope.end = token.end;
+      noUse.pop();
+      continue;
+    }
+
+    if (token.kind !== 'ident' || token.text !== 'use') continue;
+    if (noUse[noUse.length - 1]) continue;
+
+    const parser = new UseTreeParser(tokens, i + 1);
+    const tree = parser.parseTree();
+    if (!tree || tokens[parser.pos]?.text !== ';') continue;
+
+    const { visibility, firstIndex } = readVisibility(tokens, i);
+    const semicolon = tokens[parser.pos];
+
+    declarations.push({
+      visibility,
+      start: tokens[firstIndex].start,
+      itemStart: tokens[readOuterAttributes(tokens, firstIndex)].start,
+      end: semicolon.end,
+      tree,
+      leaves: flattenUseTree(tree, []),
+      tokenRange: [firstIndex, parser.pos],
+      scopes: [...scopes],
+    });
+
+    i = parser.pos;
+  }
+
+  return declarations;
+}
+
+/**
+ * Find the `use` items in `impl`, `trait` and `extern` block bodies, which would
+ * otherwise be taken for imports of the enclosing scope. Comment tokens must
+ * already be filtered out.
+ */
+export function findMisplacedUses(tokens: RustToken[]): MisplacedUse[] {
+  const misplaced: MisplacedUse[] = [];
+  const owners: Array<string | undefined> = [];
+
+  for (let i = 0; i < tokens.length; i++) {
+    const token = tokens[i];
+    if (token.text === '{') {
+      owners.push(bodyOwner(tokens, i));
+    } else if (token.text === '}') {
+      owners.pop();
+    } else if (token.kind === 'ident' && token.text === 'use' && owners[owners.length - 1] !== undefined) {
+      const parser = new UseTreeParser(tokens, i + 1);
+      const last = parser.parseTree() && tokens[parser.pos]?.text === ';' ? parser.pos : i;
+      misplaced.push({ owner: owners[owners.length - 1]!, start: token.start, tokenRange: [readVisibility(tokens, i).firstIndex, last] });
+      i = last;
+    }
+  }
+
+  return misplaced;
+}
+
+/**
+ * `impl`, `trait` or `extern` when the brace at `open` starts the body of such an
+ * item, judging by the keywords since the previous `;` or brace; undefined for
+ * other blocks, including function bodies (`fn f() -> impl Trait { ... }`)
+ */
+function bodyOwner(tokens: RustToken[], open: number): string | undefined {
+  let first = open;
+  while (first > 0 && !['{', '}', ';'].includes(tokens[first - 1].text)) first--;
+  const keywords = tokens.slice(first, open).map(token => token.text);
+  if (keywords.includes('fn')) return undefined;
+  return keywords.find(keyword => NO_USE_ITEMS.has(keyword));
+}
+
+/**
+ * Find every `extern crate` item in a token stream. Comment tokens must already be filtered out.
+ */
+export function parseExternCrates(tokens: RustToken[]): ExternCrate[] {
+  const items: ExternCrate[] = [];
+
+  for (let i = 0; i < tokens.length; i++) {
+    if (tokens[i].text !== 'extern' || tokens[i + 1]?.text !== 'crate' || tokens[i + 2]?.kind !== 'ident') continue;
+
+    const name = tokens[i + 2].text;
+    let binding = name;
+    let last = i + 3;
+    if (tokens[last]?.text === 'as' && (tokens[last + 1]?.kind === 'ident' || tokens[last + 1]?.text === '_')) {
+      binding = tokens[last + 1].text;
+      last += 2;
+    }
+    if (tokens[last]?.text !== ';') continue;
+
+    const { visibility, firstIndex } = readVisibility(tokens, i);
+    const attributeIndex = readOuterAttributes(tokens, firstIndex);
+    items.push({
+      name,
+      binding,
+      visibility,
+      start: tokens[firstIndex].start,
+      itemStart: tokens[attributeIndex].start,
+      end: tokens[last].end,
+      attributes: tokens.slice(attributeIndex, firstIndex).map(t => t.text).join(''),
+      tokenRange: [firstIndex, last],
+    });
+    i = last;
+  }
+
+  return items;
+}
+
+/**
+ * List the leaves of a use tree with their paths resolved against `prefix`.
+ */
+export function flattenUseTree(tree: UseTree, prefix: string[]): UseLeaf[] {
+  const path = [...prefix, ...tree.segments];
+
+  switch (tree.kind) {
+    case 'group':
+      return tree.children.flatMap(child => flattenUseTree(child, path));
+
+    case 'glob':
+      return [{ path: [...path, '*'], binding: '*', isSelf: false, isGlob: true, tree }];
+
+    case 'simple': {
+      const last = path[path.length - 1];
+      const isSelf = last === 'self' && path.length > 1;
+      const binding = tree.alias ?? (isSelf ? path[path.length - 2] : last);
+      return [{ path, binding, alias: tree.alias, isSelf, isGlob: false, tree }];
+    }
+  }
+}
+
+/**
+ * Walk back from the `use` keyword over `pub`, `pub(crate)`, `pub(in path)` etc.
+ */
+function readVisibility(tokens: RustToken[], useIndex: number): { visibility: string; firstIndex: number } {
+  const previous = tokens[useIndex - 1];
+
+  if (previous?.text === 'pub') {
+    return { visibility: 'pub', firstIndex: useIndex - 1 };
+  }
+
+  if (previous?.text === ')') {
+    let depth = 0;
+    for (let j = useIndex - 1; j >= 0; j--) {
+      if (tokens[j].text === ')') depth++;
+      else if (tokens[j].text === '(') depth--;
+      if (depth === 0) {
+        if (tokens[j - 1]?.text === 'pub') {
+          const inner = tokens.slice(j + 1, useIndex - 1).map(t => t.text).join(' ');
+          return { visibility: `pub(${inner})`, firstIndex: j - 1 };
+        }
+        break;
+      }
+    }
+  }
+
+  return { visibility: '', firstIndex: useIndex };
+}
+
+/**
+ * Walk back from the first token of an item over its outer attributes (`#[...]`).
+ * Returns the index of the earliest attribute's `#`, or `itemIndex` when there are none.
+ */
+function readOuterAttributes(tokens: RustToken[], itemIndex: number): number {
+  let first = itemIndex;
+
+  while (tokens[first - 1]?.text === ']') {
+    let depth = 0;
+    let j = first - 1;
+    for (; j >= 0; j--) {
+      if (tokens[j].text === ']') depth++;
+      else if (tokens[j].text === '[') depth--;
+      if (depth === 0) break;
+    }
+    if (j <= 0 || tokens[j - 1].text !== '#') break;
+    first = j - 1;
+  }
+
+  return first;
+}
+
+class UseTreeParser {
+  constructor(private tokens: RustToken[], public pos: number) {}
+
+  parseTree(): UseTree | null {
+    const first = this.tokens[this.pos];
+    if (!first) return null;
+
+    const segments: string[] = [];
+    const start = first.start;
+    let end = first.end;
+
+    // Leading `::` (2015-style absolute path)
+    if (first.text === '::') {
+      segments.push('');
+      this.pos++;
+    }
+
+    for (;;) {
+      const token = this.tokens[this.pos];
+      if (!token) return null;
+
+      if (token.text === '{') {
+        return this.parseGroup(segments, start);
+      }
+
+      if (token.text === '*') {
+        this.pos++;
+        return { kind: 'glob', segments, children: [], start, end: token.end };
+      }
+
+      if (token.kind !== 'ident') return null;
+
+      segments.push(token.text);
+      end = token.end;
+      this.pos++;
+
+      if (this.tokens[this.pos]?.text === '::') {
+        this.pos++;
+        continue;
+      }
+
+      let alias: string | undefined;
+      let aliasStart: number | undefined;
+      if (this.tokens[this.pos]?.text === 'as') {
+        const aliasToken = this.tokens[this.pos + 1];
+        if (!aliasToken || (aliasToken.kind !== 'ident' && aliasToken.text !== '_')) return null;
+        alias = aliasToken.text;
+        aliasStart = this.tokens[this.pos].start;
+        end = aliasToken.end;
+        this.pos += 2;
+      }
+
+      return { kind: 'simple', segments, alias, ...(aliasStart !== undefined ? { aliasStart } : {}), children: [], start, end };
+    }
+  }
+
+  private parseGroup(segments: string[], start: number): UseTree | null {
+    const open = this.tokens[this.pos];
+    this.pos++;
+
+    const children: UseTree[] = [];
+    while (this.tokens[this.pos] && this.tokens[this.pos].text !== '}') {
+      const child = this.parseTree();
+      if (!child) return null;
+      children.push(child);
+
+      if (this.tokens[this.pos]?.text === ',') {
+        this.pos++;
+      } else if (this.tokens[this.pos]?.text !== '}') {
+        return null;
+      }
+    }
+
+    const close = this.tokens[this.pos];
+    if (!close) return null;
+    this.pos++;
+
+    return {
+      kind: 'group',
+      segments,
+      children,
+      start,
+      end: close.end,
+      braceStart: open.start,
+      braceEnd: close.end,
+    };
+  }
+}
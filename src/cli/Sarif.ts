@@ -0,0 +1,274 @@
+import * as fs from 'fs';
+import * as path from 'path';
+import { pathToFileURL } from 'url';
+import { AnalysisResult, ConfidenceLevel, ConfidenceReason, DEFAULT_CONFIDENCE_REASONS, FINDING_KINDS, FindingKind, FindingSeverity, isRuleKind, UnusedImport } from './CLIAnalyzer';
+import { LineIndex } from './LineIndex';
+import { displayPath } from './Paths';
+import { compareStrings } from './Report';
+import { toolVersion } from './Version';
+
+export const SARIF_SCHEMA = 'https://json.schemastore.org/sarif-2.1.0.json';
+
+// Artifact URIs are relative to this base, which points at the scan directory
+const SRCROOT = 'SRCROOT';
+
+const RULES: Record<FindingKind, { name: string; description: string }> = {
+  'unused-import': {
+    name: 'UnusedImport',
+    description: 'An imported name is never used in the file.',
+  },
+  'duplicate-import': {
+    name: 'DuplicateImport',
+    description: 'The same path is imported more than once in one scope.',
+  },
+  'redundant-import': {
+    name: 'RedundantImport',
+    description: 'The name is already in scope through a glob import or an import in an enclosing scope.',
+  },
+  'type-only-import': {
+    name: 'TypeOnlyImport',
+    description: 'A value import is only used as a type and can be written as `import type`.',
+  },
+  'useless-suppression': {
+    name: 'UselessSuppression',
+    description: 'An `#[allow(unused_imports)]` attribute does not suppress any finding.',
+  },
+  'unused-reexport': {
+    name: 'UnusedReexport',
+    description: 'A restricted re-export is not used anywhere in the crate.',
+  },
+  'unused-dependency': {
+    name: 'UnusedDependency',
+    description: 'A Cargo.toml dependency is not referenced by any source file of the package.',
+  },
+  'shadowed-import': {
+    name: 'ShadowedImport',
+    description: 'Every use of an imported name resolves to a local definition of the same name instead.',
+  },
+  'redundant-alias': {
+    name: 'RedundantAlias',
+    description: 'An import is aliased to the name it would have without the alias.',
+  },
+  'over-qualified-path': {
+    name: 'OverQualifiedPath',
+    description: 'A path is written out in full several times in a module instead of being imported.',
+  },
+  'banned-import': {
+    name: 'BannedImport',
+    description: 'A path that a banned pattern of importlens.toml matches is imported or used.',
+  },
+  'unnecessary-import': {
+    name: 'UnnecessaryImport',
+    description: 'An import brings in what the prelude already provides in the edition of the crate.',
+  },
+  'test-only-import': {
+    name: 'TestOnlyImport',
+    description: 'An import outside test code is only used by #[cfg(test)] modules and #[test] functions.',
+  },
+};
+
+const LEVELS: Record<FindingSeverity, 'error' | 'warning' | 'note'> = {
+  error: 'error',
+  warning: 'warning',
+  info: 'note',
+};
+
+export interface SarifRegion {
+  startLine?: number;
+  startColumn?: number;
+  endLine?: number;
+  endColumn?: number;
+  byteOffset?: number;
+  byteLength?: number;
+}
+
+export interface SarifArtifactLocation {
+  uri: string;
+  uriBaseId?: string;
+}
+
+export interface SarifLocation {
+  physicalLocation: {
+    artifactLocation: SarifArtifactLocation;
+    region?: SarifRegion;
+  };
+}
+
+export interface SarifResult {
+  ruleId: string;
+  ruleIndex: number;
+  level: 'error' | 'warning';
+  message: { text: string };
+  locations: SarifLocation[];
+  relatedLocations?: Array<SarifLocation & { id: number; message: { text: string } }>;
+  fixes?: Array<{
+    description: { text: string };
+    artifactChanges: Array<{
+      artifactLocation: SarifArtifactLocation;
+      replacements: Array<{ deletedRegion: SarifRegion; insertedContent: { text: string } }>;
+    }>;
+  }>;
+  properties: { confidence: ConfidenceLevel; confidenceReason: ConfidenceReason };
+}
+
+/**
+ * SARIF 2.1.0 log with a single run, as written by `--format=sarif`
+ * See: https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+ */
+export interface SarifLog {
+  $schema: string;
+  version: '2.1.0';
+  runs: Array<{
+    tool: {
+      driver: {
+        name: string;
+        version: string;
+        informationUri: string;
+        rules: Array<{
+          id: string;
+          name: string;
+          shortDescription: { text: string };
+          defaultConfiguration: { level: 'error' | 'warning' };
+        }>;
+      };
+    };
+    originalUriBaseIds: Record<string, { uri: string }>;
+    columnKind: 'utf16CodeUnits';
+    results: SarifResult[];
+    invocations: Array<{
+      executionSuccessful: boolean;
+      toolExecutionNotifications: Array<{ level: 'error'; message: { text: string }; locations: SarifLocation[] }>;
+    }>;
+  }>;
+}
+
+/**
+ * Build a SARIF log from analysis results, with one rule per built-in finding kind
+ * followed by one per custom rule kind found. Artifact URIs are relative to `cwd`; the files are read again to turn spans
+ * into end positions, and findings in files that can't be read keep their start only.
+ */
+export function buildSarif(results: AnalysisResult[], cwd: string = process.cwd(), version: string = toolVersion()): SarifLog {
+  const sorted = [...results].sort((a, b) => compareStrings(artifactUri(a.filePath, cwd), artifactUri(b.filePath, cwd)));
+  const ruleKinds = [...new Set(results.flatMap(result => result.unusedImports.map(u => u.kind ?? 'unused-import')).filter(isRuleKind))].sort(compareStrings);
+  const ruleIds: FindingKind[] = [...FINDING_KINDS, ...ruleKinds];
+
+  const sarifResults: SarifResult[] = [];
+  for (const result of sorted) {
+    const artifact = { uri: artifactUri(result.filePath, cwd), uriBaseId: SRCROOT };
+    const index = result.unusedImports.some(unused => unused.span) ? lineIndexFor(result.filePath, cwd) : undefined;
+
+    for (const unused of result.unusedImports) {
+      sarifResults.push(toResult(unused, artifact, index, ruleIds));
+    }
+  }
+
+  return {
+    $schema: SARIF_SCHEMA,
+    version: '2.1.0',
+    runs: [{
+      tool: {
+        driver: {
+          name: 'ImportLens',
+          version,
+          informationUri: 'https://github.com/DEADSERPENT/importlens',
+          rules: ruleIds.map(kind => ({
+            id: kind,
+            name: RULES[kind]?.name ?? kind,
+            shortDescription: { text: RULES[kind]?.description ?? `Custom rule ${kind}.` },
+            defaultConfiguration: { level: 'warning' as const },
+          })),
+        },
+      },
+      originalUriBaseIds: { [SRCROOT]: { uri: pathToFileURL(path.resolve(cwd) + path.sep).href } },
+      columnKind: 'utf16CodeUnits',
+      results: sarifResults,
+      invocations: [{
+        executionSuccessful: true,
+        toolExecutionNotifications: sorted.filter(r => r.error).map(r => ({
+          level: 'error' as const,
+          message: { text: `Analysis error: ${r.error}` },
+          locations: [{ physicalLocation: { artifactLocation: { uri: artifactUri(r.filePath, cwd), uriBaseId: SRCROOT } } }],
+        })),
+      }],
+    }],
+  };
+}
+
+function toResult(unused: UnusedImport, artifact: SarifArtifactLocation, index: LineIndex | undefined, ruleIds: FindingKind[]): SarifResult {
+  const kind = unused.kind ?? 'unused-import';
+
+  const region: SarifRegion = unused.span && index
+    ? regionOf(index, unused.span.start, unused.span.end)
+    : lineRegion(index, unused.line, unused.column);
+  if (unused.byteSpan) {
+    region.byteOffset = unused.byteSpan.start;
+    region.byteLength = unused.byteSpan.end - unused.byteSpan.start;
+  }
+
+  const sarif: SarifResult = {
+    ruleId: kind,
+    ruleIndex: ruleIds.indexOf(kind),
+    level: LEVELS[unused.severity ?? 'error'],
+    message: { text: unused.reason },
+    locations: [{ physicalLocation: { artifactLocation: artifact, region } }],
+    properties: {
+      confidence: unused.confidence ?? 'high',
+      confidenceReason: unused.confidenceReason ?? DEFAULT_CONFIDENCE_REASONS[kind] ?? 'syntactic',
+    },
+  };
+
+  if (unused.original) {
+    const { original } = unused;
+    sarif.relatedLocations = [{
+      id: 1,
+      message: { text: 'Already imported here' },
+      physicalLocation: {
+        artifactLocation: artifact,
+        region: original.span && index
+          ? regionOf(index, original.span.start, original.span.end)
+          : lineRegion(index, original.line, original.column),
+      },
+    }];
+  }
+
+  if (unused.suggestedFix) {
+    // Fix edits are kept in UTF-8 bytes, which SARIF regions can express directly
+    sarif.fixes = [{
+      description: { text: unused.suggestedFix.description },
+      artifactChanges: [{
+        artifactLocation: artifact,
+        replacements: unused.suggestedFix.edits.map(edit => ({
+          deletedRegion: { byteOffset: edit.start, byteLength: edit.end - edit.start },
+          insertedContent: { text: edit.newText },
+        })),
+      }],
+    }];
+  }
+
+  return sarif;
+}
+
+function regionOf(index: LineIndex, start: number, end: number): SarifRegion {
+  const from = index.utf16PositionAt(start);
+  const to = index.utf16PositionAt(end);
+  return { startLine: from.line, startColumn: from.column, endLine: to.line, endColumn: to.column };
+}
+
+/** Region of a finding without a span, its UTF-8 byte column converted when the file could be read */
+function lineRegion(index: LineIndex | undefined, line: number, column: number | undefined): SarifRegion {
+  if (column === undefined) return { startLine: line };
+  const startColumn = index ? index.utf16PositionAt(index.offsetAt(line, column)).column : column;
+  return { startLine: line, startColumn };
+}
+
+function lineIndexFor(filePath: string, cwd: string): LineIndex | undefined {
+  try {
+    return new LineIndex(fs.readFileSync(path.resolve(cwd, filePath), 'utf-8'));
+  } catch {
+    return undefined;
+  }
+}
+
+function artifactUri(filePath: string, cwd: string): string {
+  return displayPath(filePath, cwd).split('/').map(encodeURIComponent).join('/');
+}
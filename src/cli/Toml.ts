@@ -0,0 +1,242 @@
+/**
+ * Value of a TOML key. Dates and times are not supported.
+ */
+export type TomlValue = string | number | boolean | TomlValue[] | TomlTable;
+
+export interface TomlTable {
+  [key: string]: TomlValue;
+}
+
+/**
+ * Parse the subset of TOML used by configuration files: tables (`[a.b]`),
+ * dotted and quoted keys, basic and literal strings, numbers, booleans,
+ * arrays (which may span lines) and inline tables.
+ * Throws with a line number on malformed or unsupported input.
+ */
+export function parseToml(source: string): TomlTable {
+  return new TomlParser(source).parse();
+}
+
+const BARE_KEY = /[A-Za-z0-9_-]/;
+const ESCAPES: Record<string, string> = { b: '\b', t: '\t', n: '\n', f: '\f', r: '\r', '"': '"', '\\': '\\' };
+
+class TomlParser {
+  private pos = 0;
+  private root: TomlTable = {};
+  /** Tables opened with a `[header]`, which may not be opened twice */
+  private defined = new Set<TomlTable>();
+
+  constructor(private source: string) {}
+
+  parse(): TomlTable {
+    let table = this.root;
+
+    for (;;) {
+      this.skipTrivia();
+      if (this.pos >= this.source.length) return this.root;
+
+      if (this.peek() === '[') {
+        if (this.source.startsWith('[[', this.pos)) this.fail('Arrays of tables are not supported');
+        this.pos++;
+        this.skipWhitespace();
+        const keys = this.parseKey();
+        this.skipWhitespace();
+        this.expect(']');
+        table = this.descend(this.root, keys);
+        if (this.defined.has(table)) this.fail(`Table [${keys.join('.')}] is defined twice`);
+        this.defined.add(table);
+      } else {
+        this.parseKeyValue(table);
+      }
+
+      this.skipWhitespace();
+      this.skipComment();
+      if (this.pos < this.source.length && !this.atNewline()) this.fail('Expected a newline');
+    }
+  }
+
+  private parseKeyValue(table: TomlTable): void {
+    const keys = this.parseKey();
+    this.skipWhitespace();
+    this.expect('=');
+    this.skipWhitespace();
+
+    const parent = this.descend(table, keys.slice(0, -1));
+    const last = keys[keys.length - 1];
+    if (last in parent) this.fail(`Key ${keys.join('.')} is defined twice`);
+    parent[last] = this.parseValue();
+  }
+
+  private parseKey(): string[] {
+    const keys: string[] = [];
+    for (;;) {
+      const char = this.peek();
+      if (char === '"') {
+        keys.push(this.parseBasicString());
+      } else if (char === "'") {
+        keys.push(this.parseLiteralString());
+      } else {
+        const start = this.pos;
+        while (this.pos < this.source.length && BARE_KEY.test(this.peek())) this.pos++;
+        if (this.pos === start) this.fail('Expected a key');
+        keys.push(this.source.slice(start, this.pos));
+      }
+
+      this.skipWhitespace();
+      if (this.peek() !== '.') return keys;
+      this.pos++;
+      this.skipWhitespace();
+    }
+  }
+
+  private parseValue(): TomlValue {
+    const char = this.peek();
+
+    if (char === '"') {
+      if (this.source.startsWith('"""', this.pos)) this.fail('Multi-line strings are not supported');
+      return this.parseBasicString();
+    }
+    if (char === "'") {
+      if (this.source.startsWith("'''", this.pos)) this.fail('Multi-line strings are not supported');
+      return this.parseLiteralString();
+    }
+    if (char === '[') return this.parseArray();
+    if (char === '{') return this.parseInlineTable();
+
+    const match = /^(true|false|[+-]?(?:inf|nan)|[+-]?[0-9][0-9_]*(?:\.[0-9_]+)?(?:[eE][+-]?[0-9_]+)?)(?=[\s,\]}#]|$)/
+      .exec(this.source.slice(this.pos));
+    if (!match) this.fail('Expected a value');
+
+    this.pos += match[0].length;
+    if (match[0] === 'true') return true;
+    if (match[0] === 'false') return false;
+    return Number(match[0].replace(/_/g, '').replace(/inf$/, 'Infinity').replace(/nan$/, 'NaN'));
+  }
+
+  private parseArray(): TomlValue[] {
+    const items: TomlValue[] = [];
+    this.pos++;
+
+    for (;;) {
+      this.skipTrivia();
+      if (this.peek() === ']') break;
+      items.push(this.parseValue());
+      this.skipTrivia();
+      if (this.peek() !== ',') break;
+      this.pos++;
+    }
+
+    this.skipTrivia();
+    this.expect(']');
+    return items;
+  }
+
+  private parseInlineTable(): TomlTable {
+    const table: TomlTable = {};
+    this.pos++;
+    this.skipWhitespace();
+
+    if (this.peek() !== '}') {
+      for (;;) {
+        this.parseKeyValue(table);
+        this.skipWhitespace();
+        if (this.peek() !== ',') break;
+        this.pos++;
+        this.skipWhitespace();
+      }
+    }
+
+    this.expect('}');
+    return table;
+  }
+
+  private parseBasicString(): string {
+    let value = '';
+    this.pos++;
+
+    for (;;) {
+      const char = this.peek();
+      if (char === '' || char === '\n') this.fail('Unterminated string');
+      this.pos++;
+      if (char === '"') return value;
+      if (char !== '\\') {
+        value += char;
+        continue;
+      }
+
+      const escape = this.peek();
+      this.pos++;
+      if (escape in ESCAPES) {
+        value += ESCAPES[escape];
+      } else if (escape === 'u' || escape === 'U') {
+        const length = escape === 'u' ? 4 : 8;
+        const hex = this.source.slice(this.pos, this.pos + length);
+        if (!/^[0-9A-Fa-f]+$/.test(hex) || hex.length !== length) this.fail('Invalid unicode escape');
+        value += String.fromCodePoint(parseInt(hex, 16));
+        this.pos += length;
+      } else {
+        this.fail(`Invalid escape \\${escape}`);
+      }
+    }
+  }
+
+  private parseLiteralString(): string {
+    const end = this.source.indexOf("'", this.pos + 1);
+    const newline = this.source.indexOf('\n', this.pos + 1);
+    if (end === -1 || (newline !== -1 && newline < end)) this.fail('Unterminated string');
+
+    const value = this.source.slice(this.pos + 1, end);
+    this.pos = end + 1;
+    return value;
+  }
+
+  /**
+   * The table at `keys` below `table`, creating missing tables on the way
+   */
+  private descend(table: TomlTable, keys: string[]): TomlTable {
+    let current = table;
+    for (const key of keys) {
+      const next = current[key] ?? (current[key] = {});
+      if (typeof next !== 'object' || Array.isArray(next)) this.fail(`Key ${key} is not a table`);
+      current = next as TomlTable;
+    }
+    return current;
+  }
+
+  private skipWhitespace(): void {
+    while (this.peek() === ' ' || this.peek() === '\t') this.pos++;
+  }
+
+  private skipComment(): void {
+    if (this.peek() !== '#') return;
+    while (this.pos < this.source.length && !this.atNewline()) this.pos++;
+  }
+
+  /** Skip whitespace, comments and newlines */
+  private skipTrivia(): void {
+    for (;;) {
+      this.skipWhitespace();
+      this.skipComment();
+      if (!this.atNewline()) return;
+      this.pos += this.peek() === '\r' ? 2 : 1;
+    }
+  }
+
+  private atNewline(): boolean {
+    return this.peek() === '\n' || this.source.startsWith('\r\n', this.pos);
+  }
+
+  private peek(): string {
+    return this.source.charAt(this.pos);
+  }
+
+  private expect(char: string): void {
+    if (this.peek() !== char) this.fail(`Expected '${char}'`);
+    this.pos++;
+  }
+
+  private fail(message: string): never {
+    const line = this.source.slice(0, this.pos).split('\n').length;
+    throw new Error(`line ${line}: ${message}`);
+  }
+}
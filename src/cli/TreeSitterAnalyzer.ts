@@ -1,4 +1,4 @@
-import { UnusedImport } from './CLIAnalyzer';
+import { DiagnosticSink, printDiagnostic, UnusedImport } from './CLIAnalyzer';
 
 interface ParsedImport {
   line: number;
@@ -7,7 +7,7 @@ interface ParsedImport {
 }
 
 /**
- * Tree-sitter based AST analyzer for Python, Java, Go, and Rust.
+ * Tree-sitter based AST analyzer for Java.
  * Loads grammars lazily; falls back gracefully when packages are absent.
  */
 export class TreeSitterAnalyzer {
@@ -26,10 +26,7 @@ export class TreeSitterAnalyzer {
     }
 
     const grammars: Record<string, string> = {
-      python: 'tree-sitter-python',
       java: 'tree-sitter-java',
-      go: 'tree-sitter-go',
-      rust: 'tree-sitter-rust',
     };
 
     for (const [lang, pkg] of Object.entries(grammars)) {
@@ -48,7 +45,7 @@ export class TreeSitterAnalyzer {
     return this.parsers.has(language);
   }
 
-  analyzeFile(content: string, language: string, filePath: string): UnusedImport[] {
+  analyzeFile(content: string, language: string, filePath: string, diagnose: DiagnosticSink = printDiagnostic): UnusedImport[] {
     const parser = this.parsers.get(language);
     if (!parser) return [];
 
@@ -74,17 +71,14 @@ export class TreeSitterAnalyzer {
         }];
       });
     } catch (err) {
-      console.error(`[TreeSitter] Analysis failed for ${filePath}:`, err);
+      diagnose(`[TreeSitter] Analysis failed for ${filePath}: ${err instanceof Error ? err.message : String(err)}`);
       return [];
     }
   }
 
   private getImportNodeTypes(language: string): string[] {
     const typeMap: Record<string, string[]> = {
-      python: ['import_statement', 'import_from_statement'],
       java: ['import_declaration'],
-      go: ['import_declaration'],
-      rust: ['use_declaration'],
     };
     return typeMap[language] || [];
   }
@@ -131,10 +125,7 @@ export class TreeSitterAnalyzer {
 
     let symbols: string[] = [];
     switch (language) {
-      case 'python': symbols = this.extractPythonSymbols(rawText); break;
       case 'java':   symbols = this.extractJavaSymbols(rawText);   break;
-      case 'go':     symbols = this.extractGoSymbols(node);         break;
-      case 'rust':   symbols = this.extractRustSymbols(rawText);    break;
     }
 
     if (symbols.length === 0) return null;
@@ -143,28 +134,6 @@ export class TreeSitterAnalyzer {
 
   // ── Language-specific symbol extraction ─────────────────────────────────
 
-  private extractPythonSymbols(text: string): string[] {
-    const fromMatch = text.match(/^from\s+[\w.]+\s+import\s+(.+)$/);
-    if (fromMatch) {
-      const importPart = fromMatch[1].replace(/[()]/g, '').trim();
-      if (importPart === '*') return ['*'];
-      return importPart.split(',').map(s => {
-        const aliasMatch = s.trim().match(/\S+\s+as\s+(\S+)/);
-        return aliasMatch ? aliasMatch[1] : s.trim().split('.')[0];
-      }).filter(Boolean);
-    }
-
-    const importMatch = text.match(/^import\s+(.+)$/);
-    if (importMatch) {
-      return importMatch[1].split(',').map(s => {
-        const aliasMatch = s.trim().match(/\S+\s+as\s+(\S+)/);
-        return aliasMatch ? aliasMatch[1] : s.trim().split('.')[0];
-      }).filter(Boolean);
-    }
-
-    return [];
-  }
-
   private extractJavaSymbols(text: string): string[] {
     const match = text.match(/^import\s+(?:static\s+)?([\w.*]+)\s*;?$/);
     if (!match) return [];
@@ -172,63 +141,4 @@ export class TreeSitterAnalyzer {
     if (importPath.endsWith('.*')) return ['*'];
     return [importPath.split('.').pop() || importPath];
   }
-
-  private extractGoSymbols(node: any): string[] {
-    const symbols: string[] = [];
-    const nodeText = node.text;
-
-    if (!nodeText.includes('(')) {
-      // Single import: import [alias] "path"
-      if (/import\s+_\s+"/.test(nodeText)) return []; // blank import
-      const m = nodeText.match(/import\s+(?:(\w+|\.|_)\s+)?"([^"]+)"/);
-      if (!m) return [];
-      const alias = m[1];
-      const pkgPath = m[2];
-      if (alias === '.' || alias === '_') return [];
-      symbols.push(alias || pkgPath.split('/').pop() || pkgPath);
-      return symbols;
-    }
-
-    // Grouped: import ( ... )
-    for (let i = 0; i < node.childCount; i++) {
-      const child = node.child(i);
-      if (child.type !== 'import_spec') continue;
-      const spec = child.text.trim();
-      if (spec.startsWith('_') || spec.startsWith('.')) continue;
-      const aliasMatch = spec.match(/^(\w+)\s+"[^"]+"/);
-      if (aliasMatch) { symbols.push(aliasMatch[1]); continue; }
-      const pathMatch = spec.match(/"([^"]+)"/);
-      if (pathMatch) symbols.push(pathMatch[1].split('/').pop() || pathMatch[1]);
-    }
-    return symbols;
-  }
-
-  private extractRustSymbols(text: string): string[] {
-    const match = text.match(/^use\s+([^;]+);?$/);
-    if (!match) return [];
-    const path = match[1].trim();
-
-    if (path.endsWith('*')) return ['*'];
-
-    // use std::{io, fs, collections::HashMap};
-    if (path.includes('{')) {
-      const braceMatch = path.match(/\{([^}]+)\}/);
-      if (braceMatch) {
-        return braceMatch[1].split(',').map(s => {
-          const part = s.trim();
-          // handle `OldName as NewName`
-          const asMatch = part.match(/\s+as\s+(\w+)/);
-          if (asMatch) return asMatch[1];
-          return part.split('::').pop()?.trim() || part;
-        }).filter(Boolean);
-      }
-    }
-
-    // use std::io::Write as IoWrite;
-    const asMatch = path.match(/\s+as\s+(\w+)$/);
-    if (asMatch) return [asMatch[1]];
-
-    // use std::io::Write;
-    return [path.split('::').pop()?.trim() || path].filter(Boolean);
-  }
 }
@@ -0,0 +1,168 @@
+interface DiffOp {
+  type: ' ' | '-' | '+';
+  /** Line text including its terminator (the last line of a file may lack one) */
+  line: string;
+}
+
+// Above this many cells the LCS table is skipped and the changed region is replaced wholesale
+const MAX_LCS_CELLS = 4_000_000;
+
+/**
+ * Build a unified diff between two versions of a file.
+ * Paths are written as given, so pass `a/...` and `b/...` for `git apply` compatibility.
+ * Returns an empty string when the texts are identical.
+ */
+export function createUnifiedDiff(
+  oldPath: string,
+  newPath: string,
+  oldText: string,
+  newText: string,
+  contextLines = 3
+): string {
+  if (oldText === newText) return '';
+
+  const ops = diffLines(splitLines(oldText), splitLines(newText));
+  const output: string[] = [`--- ${oldPath}`, `+++ ${newPath}`];
+
+  for (const [start, end] of groupHunks(ops, contextLines)) {
+    let oldLine = 0;
+    let newLine = 0;
+    for (let i = 0; i < start; i++) {
+      if (ops[i].type !== '+') oldLine++;
+      if (ops[i].type !== '-') newLine++;
+    }
+
+    const hunk = ops.slice(start, end);
+    const oldCount = hunk.filter(op => op.type !== '+').length;
+    const newCount = hunk.filter(op => op.type !== '-').length;
+
+    output.push(`@@ -${formatRange(oldLine, oldCount)} +${formatRange(newLine, newCount)} @@`);
+    for (const op of hunk) {
+      if (op.line.endsWith('\n')) {
+        output.push(op.type + op.line.slice(0, -1));
+      } else {
+        output.push(op.type + op.line);
+        output.push('\\ No newline at end of file');
+      }
+    }
+  }
+
+  return output.join('\n') + '\n';
+}
+
+function formatRange(linesBefore: number, count: number): string {
+  const start = count === 0 ? linesBefore : linesBefore + 1;
+  return count === 1 ? `${start}` : `${start},${count}`;
+}
+
+function splitLines(text: string): string[] {
+  const lines: string[] = [];
+  let start = 0;
+  for (let i = 0; i < text.length; i++) {
+    if (text[i] === '\n') {
+      lines.push(text.slice(start, i + 1));
+      start = i + 1;
+    }
+  }
+  if (start < text.length) {
+    lines.push(text.slice(start));
+  }
+  return lines;
+}
+
+/**
+ * Line-level edit script: common prefix/suffix are trimmed and the rest is diffed with LCS.
+ */
+function diffLines(a: string[], b: string[]): DiffOp[] {
+  let prefix = 0;
+  while (prefix < a.length && prefix < b.length && a[prefix] === b[prefix]) prefix++;
+
+  let suffix = 0;
+  while (
+    suffix < a.length - prefix &&
+    suffix < b.length - prefix &&
+    a[a.length - 1 - suffix] === b[b.length - 1 - suffix]
+  ) {
+    suffix++;
+  }
+
+  const oldMiddle = a.slice(prefix, a.length - suffix);
+  const newMiddle = b.slice(prefix, b.length - suffix);
+
+  const ops: DiffOp[] = a.slice(0, prefix).map(line => ({ type: ' ' as const, line }));
+  ops.push(...diffMiddle(oldMiddle, newMiddle));
+  ops.push(...a.slice(a.length - suffix).map(line => ({ type: ' ' as const, line })));
+  return ops;
+}
+
+function diffMiddle(a: string[], b: string[]): DiffOp[] {
+  if ((a.length + 1) * (b.length + 1) > MAX_LCS_CELLS) {
+    return [
+      ...a.map(line => ({ type: '-' as const, line })),
+      ...b.map(line => ({ type: '+' as const, line })),
+    ];
+  }
+
+  // lengths[i][j] = LCS length of a[i..] and b[j..]
+  const lengths: number[][] = Array.from({ length: a.length + 1 }, () => new Array(b.length + 1).fill(0));
+  for (let i = a.length - 1; i >= 0; i--) {
+    for (let j = b.length - 1; j >= 0; j--) {
+      lengths[i][j] = a[i] === b[j]
+        ? lengths[i + 1][j + 1] + 1
+        : Math.max(lengths[i + 1][j], lengths[i][j + 1]);
+    }
+  }
+
+  const ops: DiffOp[] = [];
+  let i = 0;
+  let j = 0;
+  while (i < a.length && j < b.length) {
+    if (a[i] === b[j]) {
+      ops.push({ type: ' ', line: a[i] });
+      i++;
+      j++;
+    } else if (lengths[i + 1][j] >= lengths[i][j + 1]) {
+      ops.push({ type: '-', line: a[i++] });
+    } else {
+      ops.push({ type: '+', line: b[j++] });
+    }
+  }
+  while (i < a.length) ops.push({ type: '-', line: a[i++] });
+  while (j < b.length) ops.push({ type: '+', line: b[j++] });
+  return ops;
+}
+
+/**
+ * Group changed lines into [start, end) op ranges, merging changes whose context would overlap.
+ */
+function groupHunks(ops: DiffOp[], contextLines: number): Array<[number, number]> {
+  const hunks: Array<[number, number]> = [];
+  let i = 0;
+
+  while (i < ops.length) {
+    if (ops[i].type === ' ') {
+      i++;
+      continue;
+    }
+
+    const previousEnd = hunks.length > 0 ? hunks[hunks.length - 1][1] : 0;
+    const start = Math.max(previousEnd, i - contextLines);
+
+    let lastChange = i;
+    let j = i + 1;
+    while (j < ops.length) {
+      if (ops[j].type !== ' ') {
+        lastChange = j;
+      } else if (j - lastChange > 2 * contextLines) {
+        break;
+      }
+      j++;
+    }
+
+    const end = Math.min(ops.length, lastChange + 1 + contextLines);
+    hunks.push([start, end]);
+    i = end;
+  }
+
+  return hunks;
+}
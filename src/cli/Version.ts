@@ -0,0 +1,19 @@
+import * as fs from 'fs';
+import * as path from 'path';
+
+let cached: string | undefined;
+
+/**
+ * Version of ImportLens from package.json, or 'unknown' if it can't be read
+ */
+export function toolVersion(): string {
+  if (cached === undefined) {
+    try {
+      // Resolve path relative to the compiled output directory
+      cached = JSON.parse(fs.readFileSync(path.join(__dirname, '../../../package.json'), 'utf-8')).version as string;
+    } catch {
+      cached = 'unknown';
+    }
+  }
+  return cached;
+}
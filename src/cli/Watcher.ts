@@ -0,0 +1,229 @@
+import { spawn } from 'child_process';
+import * as fs from 'fs';
+import * as path from 'path';
+import { CLIArguments } from './ArgumentParser';
+import { DEFAULT_CACHE_DIR } from './AnalysisCache';
+import { AnalysisResult, CLIAnalyzer } from './CLIAnalyzer';
+import { CONFIG_FILE_NAME, ConfigResolver } from './Config';
+import { DEFAULT_SKIPPED_DIRECTORIES, IGNORE_FILE_NAMES } from './DirectoryWalker';
+import { FileDiscovery } from './FileDiscovery';
+import { OutputFormatter } from './OutputFormatter';
+
+// Editors often write a file several times per save (temp file, rename, touch)
+const DEFAULT_DEBOUNCE_MS = 100;
+
+// Clear the screen and move the cursor home
+const CLEAR_SCREEN = '\x1b[2J\x1b[H';
+
+export interface WatchOptions {
+  /** Quiet period after the last change before re-analyzing */
+  debounceMs?: number;
+  /** Where the summary is printed; defaults to stdout */
+  write?: (text: string) => void;
+  /** Redraw instead of appending; defaults to whether stdout is a terminal */
+  clearScreen?: boolean;
+  /** Called after every analysis with the current results, in file order */
+  onUpdate?: (results: AnalysisResult[]) => void;
+}
+
+/**
+ * `importlens-cli watch`: analyzes the inputs once, then re-analyzes files as
+ * they change and redraws the summary.
+ *
+ * Changes are debounced, and only changed files are analyzed again (through the
+ * analysis cache when it is enabled). Deleted files drop out of the summary and
+ * new files are picked up by re-running discovery whenever an unknown path changes.
+ * A change to an `importlens.toml` or ignore file reloads the configuration and
+ * analyzes everything again. With `--exec`, the command runs after every analysis.
+ */
+export class Watcher {
+  private results = new Map<string, AnalysisResult>();
+  private config: ConfigResolver;
+  private analyzer: CLIAnalyzer;
+  private watchers: fs.FSWatcher[] = [];
+  private pending = new Set<string>();
+  private reload = false;
+  private timer?: NodeJS.Timeout;
+  /** The analysis in progress; changes arriving meanwhile wait for it */
+  private running?: Promise<void>;
+  private cacheDir: string;
+  private closed = false;
+
+  constructor(private args: CLIArguments, private options: WatchOptions = {}) {
+    this.config = new ConfigResolver(args.configOverrides, args.language);
+    this.analyzer = new CLIAnalyzer(args, this.config);
+    this.cacheDir = path.resolve(args.cacheDir ?? DEFAULT_CACHE_DIR) + path.sep;
+  }
+
+  /**
+   * Analyze every input and start watching. Resolves once the first summary is printed.
+   */
+  async start(): Promise<void> {
+    this.running = this.refresh();
+    await this.running;
+    this.running = undefined;
+    if (this.closed) return;
+
+    for (const input of this.args.files.length === 0 ? ['.'] : this.args.files) {
+      this.watch(input);
+    }
+  }
+
+  /** Current results, in file order */
+  get current(): AnalysisResult[] {
+    return [...this.results.keys()].sort().map(file => this.results.get(file)!);
+  }
+
+  close(): void {
+    this.closed = true;
+    clearTimeout(this.timer);
+    this.watchers.forEach(watcher => watcher.close());
+    this.watchers = [];
+  }
+
+  private watch(input: string): void {
+    // Globs are matched by discovery; watch the working directory for them
+    const resolved = /[*?[\]{}]/.test(input) ? process.cwd() : path.resolve(input);
+    let directory = resolved;
+    let recursive = true;
+    try {
+      if (!fs.statSync(resolved).isDirectory()) {
+        directory = path.dirname(resolved);
+        recursive = false;
+      }
+    } catch {
+      return;
+    }
+
+    const watcher = fs.watch(directory, { recursive }, (_event, fileName) => {
+      if (!fileName) return;
+      // Build output and the cache's own writes would otherwise trigger endless re-runs
+      const segments = fileName.toString().split(/[\\/]/);
+      if (segments.some(segment => DEFAULT_SKIPPED_DIRECTORIES.includes(segment))) return;
+
+      const filePath = path.join(directory, fileName.toString());
+      if (!filePath.startsWith(this.cacheDir)) this.changed(filePath);
+    });
+    watcher.on('error', error => this.write(`[WARNING] Watching ${directory} failed: ${error.message}\n`));
+    this.watchers.push(watcher);
+  }
+
+  private changed(filePath: string): void {
+    const name = path.basename(filePath);
+    // Dependencies are only re-checked when every file is analyzed again
+    if (name === CONFIG_FILE_NAME || IGNORE_FILE_NAMES.includes(name) || (name === 'Cargo.toml' && this.args.checkDependencies)) {
+      this.reload = true;
+    }
+    this.pending.add(filePath);
+
+    clearTimeout(this.timer);
+    this.timer = setTimeout(() => this.flush(), this.options.debounceMs ?? DEFAULT_DEBOUNCE_MS);
+  }
+
+  private async flush(): Promise<void> {
+    // Changes arriving during an analysis are picked up when it finishes
+    if (this.running) return;
+    if (this.closed || (this.pending.size === 0 && !this.reload)) return;
+
+    const changed = [...this.pending];
+    const reload = this.reload;
+    this.pending.clear();
+    this.reload = false;
+
+    this.running = reload ? this.refresh(true) : this.update(changed);
+    try {
+      await this.running;
+    } catch (error) {
+      this.write(`Error: ${error instanceof Error ? error.message : String(error)}\n`);
+    } finally {
+      this.running = undefined;
+    }
+
+    if (this.pending.size > 0 || this.reload) {
+      await this.flush();
+    }
+  }
+
+  /**
+   * Discover and analyze every file; with `reload`, re-read the configuration first
+   */
+  private async refresh(reload = false): Promise<void> {
+    if (reload) {
+      this.config = new ConfigResolver(this.args.configOverrides, this.args.language);
+      this.analyzer = new CLIAnalyzer(this.args, this.config);
+    }
+
+    const started = Date.now();
+    const files = await this.discover();
+    this.results.clear();
+    await this.analyze(files);
+    this.render(started);
+  }
+
+  private async update(changed: string[]): Promise<void> {
+    const started = Date.now();
+    const known = changed.filter(file => this.results.has(file));
+    let removed = known.filter(file => !fs.existsSync(file));
+    const modified = known.filter(file => !removed.includes(file));
+
+    // Unknown paths may be new files or whole directories coming or going, which only discovery can vet
+    let added: string[] = [];
+    if (known.length < changed.length) {
+      const files = new Set(await this.discover());
+      added = [...files].filter(file => !this.results.has(file));
+      removed = [...this.results.keys()].filter(file => !files.has(file));
+    }
+
+    if (removed.length === 0 && modified.length === 0 && added.length === 0) return;
+
+    removed.forEach(file => this.results.delete(file));
+    // Crate-level findings depend on every file of the crate
+    await this.analyze(this.args.checkReexports ? [...this.results.keys(), ...added] : [...modified, ...added]);
+    this.render(started);
+  }
+
+  private async discover(): Promise<string[]> {
+    const discovery = new FileDiscovery(this.args, this.config);
+    const files = await discovery.discoverFiles();
+    discovery.warnings.forEach(warning => this.write(`[WARNING] ${warning}\n`));
+    return files;
+  }
+
+  private async analyze(files: string[]): Promise<void> {
+    if (files.length === 0) return;
+    for (const result of await this.analyzer.analyzeFiles(files)) {
+      this.results.set(result.filePath, result);
+    }
+  }
+
+  private render(started: number): void {
+    if (this.closed) return;
+    const results = this.current;
+
+    const clear = this.options.clearScreen ?? process.stdout.isTTY;
+    const elapsed = Date.now() - started;
+    const time = new Date().toLocaleTimeString();
+    this.write(
+      (clear ? CLEAR_SCREEN : '') +
+      OutputFormatter.forArgs(this.args).format(results) +
+      `\n\n[${time}] Analyzed in ${elapsed}ms. Watching ${results.length} file(s) for changes (Ctrl+C to stop)\n`
+    );
+
+    this.options.onUpdate?.(results);
+    if (this.args.exec) this.runHook(results);
+  }
+
+  private runHook(results: AnalysisResult[]): void {
+    const findings = results.reduce((sum, r) => sum + r.unusedImports.length, 0);
+    const child = spawn(this.args.exec!, {
+      shell: true,
+      stdio: 'inherit',
+      env: { ...process.env, IMPORTLENS_FINDINGS: String(findings), IMPORTLENS_FILES: String(results.length) },
+    });
+    child.on('error', error => this.write(`[WARNING] --exec failed: ${error.message}\n`));
+  }
+
+  private write(text: string): void {
+    (this.options.write ?? (chunk => process.stdout.write(chunk)))(text);
+  }
+}
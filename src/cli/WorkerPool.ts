@@ -0,0 +1,132 @@
+import { Worker } from 'worker_threads';
+import { CLIArguments } from './ArgumentParser';
+import { AnalysisResult } from './CLIAnalyzer';
+import { CancellationToken } from './Progress';
+
+/** Message sent to a worker: analyze one file */
+export interface WorkerRequest {
+  index: number;
+  filePath: string;
+}
+
+/** Message sent back by a worker: `ready` once loaded, then one result per request */
+export type WorkerResponse = { ready: true } | { index: number; result: AnalysisResult };
+
+/**
+ * Fixed-size pool of analysis workers fed from one shared queue, so a slow file
+ * never holds up a whole chunk. Results are stored by input index, which keeps the
+ * output order independent of completion order.
+ *
+ * A worker that dies while analyzing a file (out of memory, native crash) turns
+ * that file into an error result and is replaced; the run carries on.
+ *
+ * Cancelling `cancellation` terminates the workers and resolves with the results
+ * finished so far; the files still queued or being analyzed are left as holes.
+ */
+export class WorkerPool {
+  constructor(
+    private workerPath: string,
+    private args: CLIArguments,
+    private size: number
+  ) {}
+
+  /** Analyze `files`, calling `onResult` as each one finishes */
+  run(files: string[], onResult?: (result: AnalysisResult) => void, cancellation?: CancellationToken): Promise<AnalysisResult[]> {
+    return new Promise((resolve, reject) => {
+      const results = new Array<AnalysisResult>(files.length);
+      const workers = new Set<Worker>();
+      let next = 0;
+      let completed = 0;
+      let settled = false;
+      let stopListening: (() => void) | undefined;
+
+      const settle = (error?: Error) => {
+        if (settled) return;
+        settled = true;
+        stopListening?.();
+        workers.forEach(worker => worker.terminate());
+        if (error) reject(error);
+        else resolve(results);
+      };
+
+      const spawn = () => {
+        const worker = new Worker(this.workerPath, { workerData: { args: this.args } });
+        workers.add(worker);
+        let current: number | undefined;
+        let started = false;
+        let lastError: Error | undefined;
+
+        const dispatch = () => {
+          if (next >= files.length) {
+            current = undefined;
+            worker.terminate();
+            return;
+          }
+          current = next++;
+          worker.postMessage({ index: current, filePath: files[current] } as WorkerRequest);
+        };
+
+        worker.on('message', (response: WorkerResponse) => {
+          if ('ready' in response) {
+            started = true;
+            return;
+          }
+          // Answers that were already on their way when the run was cancelled
+          if (settled) return;
+
+          results[response.index] = response.result;
+          completed++;
+          onResult?.(response.result);
+          if (settled) return;
+          if (completed === files.length) {
+            settle();
+          } else {
+            dispatch();
+          }
+        });
+
+        worker.on('error', error => {
+          lastError = error;
+        });
+
+        worker.on('exit', code => {
+          workers.delete(worker);
+          if (settled || current === undefined) return;
+
+          // A worker that never loaded means workers are unusable here, not that the file is bad
+          if (!started) {
+            settle(lastError ?? new Error(`Worker exited with code ${code}`));
+            return;
+          }
+
+          results[current] = {
+            filePath: files[current],
+            language: 'unknown',
+            unusedImports: [],
+            error: `Analysis crashed: ${lastError?.message ?? `worker exited with code ${code}`}`,
+          };
+          completed++;
+          onResult?.(results[current]);
+          if (settled) return;
+          if (completed === files.length) {
+            settle();
+          } else if (next < files.length) {
+            spawn();
+          }
+        });
+
+        dispatch();
+      };
+
+      stopListening = cancellation?.onCancel(() => settle());
+      if (files.length === 0) {
+        settle();
+      }
+      if (settled) return;
+
+      for (let i = 0; i < Math.min(this.size, files.length); i++) {
+        spawn();
+      }
+    });
+  }
+}
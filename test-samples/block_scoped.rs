@@ -0,0 +1,32 @@
+// Rust Block-Scoped Import Test File
+use std::collections::HashMap;
+use std::io::Write;
+
+// Used: HashMap, Write (file level), File (in its function), BufRead (nested block)
+// Unused: Read (only named after its block ends), Cursor (only named in another function)
+
+fn open() -> std::io::Result<()> {
+    use std::fs::File;
+    let mut file = File::create("out.txt")?;
+    Write::write_all(&mut file, b"x")
+}
+
+fn read() {
+    {
+        use std::io::Read;
+        use std::io::BufRead;
+        let _ = {
+            let _: &dyn BufRead;
+        };
+    }
+    let _: Option<&dyn Read> = None;
+    let _ = HashMap::<u8, u8>::new();
+}
+
+fn cursor() {
+    use std::io::Cursor;
+}
+
+fn other() {
+    let _ = Cursor::new(Vec::<u8>::new());
+}
@@ -0,0 +1,23 @@
+// C++ header with an include guard
+#ifndef IMPORTLENS_GUARDED_HPP
+#define IMPORTLENS_GUARDED_HPP
+
+#include <string>
+#include <optional>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+}
+#endif
+
+// Using: string
+// Unused, with low confidence: optional (includers may rely on it), stddef.h (also inside extern "C" and #ifdef)
+
+std::string greeting();
+
+#endif // IMPORTLENS_GUARDED_HPP
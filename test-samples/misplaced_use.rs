@@ -0,0 +1,31 @@
+// Rust Misplaced Use Test File
+use std::fmt;
+use std::env;
+
+// `use` isn't allowed in impl, trait and extern blocks: those items are skipped with a warning
+// Used: fmt; Unused: env
+
+struct Wrapper;
+
+impl fmt::Display for Wrapper {
+    use std::io::Write;
+
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "wrapper")
+    }
+}
+
+trait Named {
+    use std::fs::{self, File};
+
+    fn name(&self) -> String;
+}
+
+extern "C" {
+    use std::os::raw::c_int;
+}
+
+fn main() -> impl fmt::Debug {
+    use std::path::Path;
+    Path::new("x").to_path_buf()
+}
@@ -0,0 +1,19 @@
+// Rust Raw Identifier Test File
+use r#async::r#Future;
+use protocol::r#match;
+use protocol::r#dyn::Frame;
+use protocol::r#try;
+use protocol::parse;
+use protocol::r#loop;
+
+// Used: r#Future (as Future), r#match, Frame, parse (as r#parse)
+// Unused: r#try, r#loop (only `r#loop` in a string and a comment)
+
+fn poll(future: impl Future) -> Frame {
+    r#match(r#parse(future))
+}
+
+fn describe() -> &'static str {
+    // r#loop
+    "r#loop"
+}
@@ -0,0 +1,40 @@
+// Rust Shadowing Test File
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::io::Result;
+use crate::util::parse;
+use crate::util::render;
+
+// Shadowed: Result, parse (every use refers to a local definition)
+// Used: HashMap, Display, render (shadowed in report only)
+
+mod util {
+    pub fn parse(input: &str) -> u32 { input.len() as u32 }
+    pub fn render(value: u32) -> String { value.to_string() }
+}
+
+fn show(value: impl Display) -> String {
+    format!("{}", value)
+}
+
+fn report(render: &dyn Fn(u32) -> String) -> String {
+    render(1)
+}
+
+fn count(input: &str) -> usize {
+    fn parse(input: &str) -> usize { input.trim().len() }
+    parse(input)
+}
+
+fn check() -> bool {
+    type Result = std::result::Result<(), String>;
+    let outcome: Result = Ok(());
+    outcome.is_ok()
+}
+
+fn main() {
+    let mut counts = HashMap::new();
+    counts.insert("a", count("a"));
+    println!("{} {} {}", show(1), render(2), check());
+    report(&|v| v.to_string());
+}
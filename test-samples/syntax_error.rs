@@ -0,0 +1,27 @@
+// Rust Syntax Error Test File
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{Read, Write;
+
+// `broken` is missing a `)` and `parse` a `}`: both are skipped, and the rest is analyzed
+// Used: HashMap, fmt; Unused: Read, Write (skipped), env
+
+use std::env;
+
+fn broken(map: HashMap<String, u32> {
+    map.len()
+}
+
+fn parse(input: &str) -> u32 {
+    match input.parse() {
+        Ok(n) => n,
+        Err(_) => 0,
+    }
+
+struct Wrapper;
+
+impl fmt::Display for Wrapper {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "wrapper")
+    }
+}
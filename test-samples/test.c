@@ -0,0 +1,19 @@
+/* C Test File */
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <math.h>
+#include <stdint.h>
+
+#define COPY(dst, src) memcpy((dst), (src), sizeof(src))
+
+/* Using: stdio (printf), stdlib (EXIT_SUCCESS), string (memcpy in a macro), stdint */
+/* Unused: math (sqrt is only mentioned in a comment) */
+
+int main(void) {
+    uint8_t buffer[4];
+    uint8_t source[4] = {1, 2, 3, 4};
+    COPY(buffer, source); /* sqrt(2) */
+    printf("%d\n", buffer[0]);
+    return EXIT_SUCCESS;
+}
@@ -0,0 +1,35 @@
+// C++ Test File
+#include <iostream>
+#include <vector>
+#include <map>
+#include <algorithm>
+#include <cstring>
+#include <memory>
+#include "config.h"
+
+#ifdef _WIN32
+#include <windows.h>
+#include <thread>
+#endif
+
+// Using: iostream (std::cout), vector (bare, after using namespace std), algorithm, memory, thread
+// Unused: map, cstring (memcpy only in a comment and a string)
+// Never reported: config.h and windows.h (no identifier list)
+
+using namespace std;
+
+int main() {
+    vector<int> values = {3, 1, 2};
+    std::sort(values.begin(), values.end());
+    auto owner = std::make_unique<int>(values.size());
+
+    // memcpy(dst, src, n);
+    const char *message = "std::map and memcpy are only mentioned here";
+    std::cout << message << *owner << std::endl;
+
+#ifdef _WIN32
+    std::thread worker([] {});
+    worker.join();
+#endif
+    return 0;
+}
@@ -5,14 +5,25 @@ import (
 	"fmt"
 	"strings"
 	"time"
+
 	_ "database/sql"
+	str "strings"
+	log "log"
+	. "math"
+
+	yaml "gopkg.in/yaml.v3"
+	"github.com/go-chi/chi/v5"
 )
 
-// Using: fmt, strings, database/sql (blank import)
+// Using: fmt, strings, str, log (redundant alias), math (dot import), yaml, chi
 // Unused: time
+// Never reported: database/sql (blank import)
 
 func main() {
 	message := "Hello, World!"
 	upper := strings.ToUpper(message)
-	fmt.Println(upper)
+	fmt.Println(upper, str.ToLower(message), Sqrt(2))
+	log.Print("time.Now() in a string is not a use")
+	_ = yaml.Marshal
+	_ = chi.NewRouter
 }
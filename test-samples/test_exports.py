@@ -0,0 +1,18 @@
+# Python Test File: __all__ and noqa
+import os.path
+import logging as log
+from collections import OrderedDict, defaultdict
+from .registry import register  # noqa: F401
+from .helpers import *
+
+# Using: os.path, defaultdict, OrderedDict (exported via __all__), register (noqa)
+# Unused: log
+
+__all__ = ["OrderedDict", "load"]
+
+
+def load(directory):
+    counts = defaultdict(int)
+    for name in os.listdir(directory):
+        counts[os.path.splitext(name)[1]] += 1
+    return counts
@@ -0,0 +1,17 @@
+# Python Test File: TYPE_CHECKING imports
+from __future__ import annotations
+
+from typing import TYPE_CHECKING
+
+if TYPE_CHECKING:
+    from pathlib import Path
+    from decimal import Decimal
+    from fractions import Fraction
+
+# Using: TYPE_CHECKING, Path (annotation), Decimal (string annotation)
+# Unused: Fraction
+
+
+def read_total(path: Path) -> "Decimal":
+    with open(path) as handle:
+        return sum(float(line) for line in handle)
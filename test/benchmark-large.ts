@@ -0,0 +1,78 @@
+/**
+ * Large-file benchmark: time and peak memory of analyzing one generated Rust file.
+ *
+ * Writes a synthetic bindgen-style file (default 50 MB): a `use` group of a few
+ * thousand generated names, half of them unused, followed by `#[repr(C)]` structs
+ * and `extern "C"` functions. The file is analyzed on the main thread with
+ * --include-large-files, sampling the resident set size while it runs.
+ *
+ *   npm run compile && npm run bench:large -- [megabytes]
+ */
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { parseArgs } from '../src/cli/ArgumentParser';
+import { CLIAnalyzer } from '../src/cli/CLIAnalyzer';
+import { formatSize } from '../src/cli/FileDiscovery';
+
+const GROUP_SIZE = 5000;
+
+function generate(filePath: string, bytes: number): void {
+  const names = Array.from({ length: GROUP_SIZE }, (_, i) => `bindgen_ty_${i}`);
+  const chunks = [
+    'use std::os::raw::{c_char, c_int, c_void};\n',
+    `use crate::ffi::{${names.join(', ')}};\n\n`,
+  ];
+  let size = chunks.reduce((sum, chunk) => sum + chunk.length, 0);
+
+  for (let i = 0; size < bytes; i++) {
+    // Every other generated name is used, so half the group is reported
+    const used = i < GROUP_SIZE && i % 2 === 0 ? `    pub inner: bindgen_ty_${i},\n` : '';
+    const chunk = `#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct bindgen_struct_${i} {
+    pub field_a: c_int,
+    pub field_b: *mut c_char,
+${used}    pub field_c: [u8; 16usize],
+}
+extern "C" {
+    pub fn bindgen_fn_${i}(arg: c_int, data: *const c_void) -> c_int;
+}
+`;
+    chunks.push(chunk);
+    size += chunk.length;
+  }
+  fs.writeFileSync(filePath, chunks.join(''));
+}
+
+async function main() {
+  const megabytes = Number(process.argv[2] ?? 50);
+  const root = fs.mkdtempSync(path.join(os.tmpdir(), 'importlens-bench-large-'));
+  const filePath = path.join(root, 'bindings.rs');
+
+  try {
+    generate(filePath, megabytes * 1024 * 1024);
+    const analyzer = new CLIAnalyzer(parseArgs(['--include-large-files', '--jobs=1', '--no-cache', '--format=json']));
+
+    const baseline = process.memoryUsage().rss;
+    let peak = baseline;
+    const sampler = setInterval(() => {
+      peak = Math.max(peak, process.memoryUsage().rss);
+    }, 10);
+
+    const start = process.hrtime.bigint();
+    const [result] = await analyzer.analyzeFiles([filePath]);
+    const ms = Number(process.hrtime.bigint() - start) / 1e6;
+    clearInterval(sampler);
+    peak = Math.max(peak, process.memoryUsage().rss);
+
+    console.log(`file         ${formatSize(fs.statSync(filePath).size)}`);
+    console.log(`time         ${ms.toFixed(0)} ms`);
+    console.log(`peak rss     ${formatSize(peak)} (${formatSize(peak - baseline)} over the ${formatSize(baseline)} at start)`);
+    console.log(`findings     ${result.unusedImports.length}${result.error ? ` (error: ${result.error})` : ''}`);
+  } finally {
+    fs.rmSync(root, { recursive: true, force: true });
+  }
+}
+
+main();
@@ -0,0 +1,61 @@
+/**
+ * Serial vs parallel vs cached analysis benchmark.
+ *
+ * Copies test-samples/ into a temporary tree (default 500 copies) and times
+ * CLIAnalyzer with --jobs=1 against the default worker pool and against a
+ * second run over a warm analysis cache.
+ *
+ *   npm run compile && npm run bench -- [copies]
+ */
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { parseArgs } from '../src/cli/ArgumentParser';
+import { CLIAnalyzer } from '../src/cli/CLIAnalyzer';
+
+async function time(label: string, argv: string[], files: string[]): Promise<number> {
+  const analyzer = new CLIAnalyzer(parseArgs([...argv, '--format=json']));
+  const start = process.hrtime.bigint();
+  const results = await analyzer.analyzeFiles(files);
+  const ms = Number(process.hrtime.bigint() - start) / 1e6;
+
+  const findings = results.reduce((sum, r) => sum + r.unusedImports.length, 0);
+  console.log(`${label.padEnd(12)} ${ms.toFixed(0).padStart(7)} ms  (${files.length} files, ${findings} findings)`);
+  return ms;
+}
+
+async function main() {
+  const copies = Number(process.argv[2] ?? 500);
+  const samplesDir = path.join(__dirname, '../../test-samples');
+  const samples = fs.readdirSync(samplesDir);
+  const root = fs.mkdtempSync(path.join(os.tmpdir(), 'importlens-bench-'));
+
+  try {
+    const files: string[] = [];
+    for (let i = 0; i < copies; i++) {
+      const dir = path.join(root, `copy${i}`);
+      fs.mkdirSync(dir);
+      for (const sample of samples) {
+        const target = path.join(dir, sample);
+        fs.copyFileSync(path.join(samplesDir, sample), target);
+        files.push(target);
+      }
+    }
+
+    // Warm up the JIT so the first timed run isn't penalized
+    await new CLIAnalyzer(parseArgs(['--jobs=1', '--no-cache', '--format=json'])).analyzeFiles(files);
+
+    const jobs = os.cpus().length;
+    const serial = await time('serial', ['--jobs=1', '--no-cache'], files);
+    const parallel = await time(`parallel x${jobs}`, [`--jobs=${jobs}`, '--no-cache'], files);
+    console.log(`speedup      ${(serial / parallel).toFixed(2)}x`);
+
+    const cacheDir = `--cache-dir=${path.join(root, 'cache')}`;
+    await time('cold cache', [cacheDir], files);
+    await time('warm cache', [cacheDir], files);
+  } finally {
+    fs.rmSync(root, { recursive: true, force: true });
+  }
+}
+
+main();
@@ -0,0 +1,4 @@
+﻿use std::io::Read;
+use std::fs::File;
+
+pub fn open() -> File { File::open("a").unwrap() }
@@ -0,0 +1,5 @@
+import os
+import sys, json
+
+# größe
+print(sys.argv)
@@ -0,0 +1,7 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+
+/// Größe in Bytes
+pub fn open() -> File { File::open("a").unwrap() }
+
+pub fn size() -> usize { /* größe */ use std::mem; 0 }
@@ -0,0 +1,23 @@
+//! 設定ファイルを読み込むモジュール 📦
+//!
+//! 🦀 で書かれています。
+
+mod données {
+    pub struct Entrée;
+    pub struct Sortie;
+    pub fn naïve_impl() {}
+}
+
+use std::collections::{HashMap, BTreeMap}; // 🗺️ 地図
+use données::{naïve_impl, Entrée, Sortie}; /* 未使用 🚫 */ use std::fmt::Write;
+use std::io::{self, Read};
+
+/// 値を数える — naïve implementation 🎯
+pub fn 数える(入力: &str) -> HashMap<char, usize> {
+    naïve_impl();
+    let _e: Entrée = Entrée;
+    let mut 結果 = HashMap::new(); // 🧮
+    for c in 入力.chars() { *結果.entry(c).or_insert(0) += 1; }
+    io::stdout();
+    結果
+}
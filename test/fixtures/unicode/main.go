@@ -0,0 +1,10 @@
+// Package main は設定を読み込む 📦
+package main
+
+import (
+	"fmt" // 🖨️ 出力
+	données "os"
+	"strings"
+)
+
+func main() { fmt.Println("naïve 🎉", strings.ToUpper("é")) }
@@ -0,0 +1,5 @@
+"""設定を読み込む 📦"""
+résumé = "naïve 🎉"; import os, sys  # 未使用
+from collections import OrderedDict, Counter  # 🧮 数える
+
+print(résumé, sys, Counter)
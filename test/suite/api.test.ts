@@ -0,0 +1,97 @@
+import * as assert from 'assert';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { Analyzer, ImportLensError } from '../../src/cli/Analyzer';
+import { parseArgs } from '../../src/cli/ArgumentParser';
+import { CLIAnalyzer } from '../../src/cli/CLIAnalyzer';
+import { buildReport } from '../../src/cli/Report';
+import { Rule } from '../../src/cli/Rules';
+import { buildSarif } from '../../src/cli/Sarif';
+
+suite('Library API Tests', () => {
+	const source = 'use std::fs::File;\nuse std::io::Read;\nuse std::io::Read;\n\nfn main() {\n    let _ = File::open("x");\n}\n';
+	let root: string;
+
+	setup(() => {
+		root = fs.mkdtempSync(path.join(os.tmpdir(), 'importlens-api-'));
+	});
+
+	teardown(() => {
+		fs.rmSync(root, { recursive: true, force: true });
+	});
+
+	test('Should analyze in-memory source with the configured language and kinds', () => {
+		const all = Analyzer.builder().language('rs').build().analyzeSource(source);
+		const unusedOnly = Analyzer.builder().language('rust').kinds(['unused-import']).build().analyzeSource(source);
+
+		assert.deepStrictEqual(all.findings.map(f => [f.kind, f.line]), [['unused-import', 2], ['duplicate-import', 3]]);
+		assert.deepStrictEqual(unusedOnly.findings.map(f => [f.kind, f.line]), [['unused-import', 2]]);
+		assert.strictEqual(all.findings[0].file, '<source>');
+		assert.deepStrictEqual(JSON.parse(JSON.stringify(all.findings)), all.findings, 'findings should survive a JSON round trip');
+	});
+
+	test('Should report the same findings as the CLI for a path', async () => {
+		const filePath = path.join(root, 'main.rs');
+		fs.writeFileSync(filePath, source);
+
+		const report = await Analyzer.builder().build().analyzePath(filePath);
+		const results = await new CLIAnalyzer(parseArgs(['--no-cache', filePath])).analyzeFiles([filePath]);
+
+		assert.deepStrictEqual(report.findings, buildReport(results).findings);
+	});
+
+	test('Should run custom rules next to the built-in ones', () => {
+		const noChrono: Rule = {
+			id: 'acme/banned-crate',
+			check: context => context.imports
+				.filter(item => item.path[0] === 'chrono')
+				.map(item => context.findingAt(item.span, '`chrono` is banned', [item.binding])),
+		};
+		const code = 'use chrono::Utc;\nuse std::io::Read;\n\nfn now() -> Utc { Utc }\n';
+		const analyzer = Analyzer.builder().language('rust').rule(noChrono).build();
+
+		const report = analyzer.analyzeSource(code);
+		assert.deepStrictEqual(report.findings.map(f => [f.kind, f.line, f.column, f.severity]), [
+			['acme/banned-crate', 1, 5, 'warning'],
+			['unused-import', 2, 5, 'error'],
+		]);
+		assert.strictEqual(report.summary.by_kind['acme/banned-crate'], 1);
+		assert.deepStrictEqual(JSON.parse(JSON.stringify(report.findings)), report.findings);
+
+		const filePath = path.join(root, 'main.rs');
+		const results = [{ filePath, language: 'rust', unusedImports: analyzer.detect(code, 'rust', filePath).unusedImports }];
+		const run = buildSarif(results, root).runs[0];
+		assert.ok(run.tool.driver.rules.some(r => r.id === 'acme/banned-crate'));
+		assert.strictEqual(run.results[0].ruleId, 'acme/banned-crate');
+
+		// Turned off like any built-in kind
+		fs.writeFileSync(path.join(root, 'importlens.toml'), '[severity]\n"acme/banned-crate" = "off"\n');
+		assert.deepStrictEqual(analyzer.analyzeSource(code, filePath).findings.map(f => f.kind), ['unused-import']);
+
+		assert.throws(() => Analyzer.builder().rule({ ...noChrono, id: 'banned-crate' }), ImportLensError);
+		assert.throws(() => Analyzer.builder().rule(noChrono).rule(noChrono), /Duplicate rule id/);
+	});
+
+	test('Should throw typed errors', async () => {
+		const kindOf = (run: () => unknown) => {
+			try {
+				run();
+			} catch (error) {
+				return error instanceof ImportLensError ? error.kind : 'untyped';
+			}
+			return 'none';
+		};
+
+		assert.strictEqual(kindOf(() => Analyzer.builder().build().analyzeSource(source)), 'unsupported-language');
+		assert.strictEqual(kindOf(() => Analyzer.builder().build().analyzeSource(source, 'notes.txt')), 'unsupported-language');
+		assert.strictEqual(kindOf(() => Analyzer.builder().language('cobol')), 'unsupported-language');
+		assert.strictEqual(kindOf(() => Analyzer.builder().cfg(['=x'])), 'parse');
+
+		fs.writeFileSync(path.join(root, 'importlens.toml'), 'kinds = [');
+		assert.strictEqual(kindOf(() => Analyzer.builder().build().analyzeSource(source, path.join(root, 'main.rs'))), 'parse');
+
+		await assert.rejects(Analyzer.builder().build().analyzePath(path.join(root, 'missing.rs')), (error: unknown) =>
+			error instanceof ImportLensError && error.kind === 'io');
+	});
+});
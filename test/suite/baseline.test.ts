@@ -306,6 +306,49 @@ suite('Baseline CLI Tests', () => {
 			assert.strictEqual(comparison.summary.totalNew, 0, 'Should have no new issues');
 			assert.strictEqual(comparison.summary.totalBaseline, 3, 'Should have 3 baseline issues');
 		});
+
+		test('Should keep matching a finding after it moves to another line', () => {
+			const baseline = baselineManager.generateBaseline(createMockResults(1, 2));
+			const moved = createMockResults(1, 2);
+			moved[0].unusedImports.forEach(unusedImport => unusedImport.line += 7);
+
+			const comparison = baselineManager.compareWithBaseline(moved, baseline);
+
+			assert.strictEqual(comparison.summary.totalNew, 0);
+			assert.strictEqual(comparison.summary.totalBaseline, 2);
+		});
+
+		test('Should match entries without a fingerprint by line', () => {
+			const baseline = baselineManager.generateBaseline(createMockResults(1, 1));
+			delete baseline.entries[0].fingerprint;
+			const moved = createMockResults(1, 1);
+			moved[0].unusedImports[0].line = 5;
+
+			assert.strictEqual(baselineManager.compareWithBaseline(createMockResults(1, 1), baseline).summary.totalNew, 0);
+			assert.strictEqual(baselineManager.compareWithBaseline(moved, baseline).summary.totalNew, 1);
+		});
+
+		test('Should filter baselined findings out of the results', () => {
+			const baseline = baselineManager.generateBaseline(createMockResults(3, 3));
+
+			const filtered = baselineManager.filterResults(createMockResults(5, 5), baseline);
+
+			assert.strictEqual(filtered.baselined, 3);
+			assert.strictEqual(filtered.results.length, 5, 'Files without new findings should be kept');
+			assert.strictEqual(filtered.results.reduce((sum, r) => sum + r.unusedImports.length, 0), 2);
+		});
+
+		test('Should prune entries whose findings are gone', () => {
+			baselineManager.updateBaseline(createMockResults(3, 3));
+
+			const { kept, pruned } = baselineManager.pruneBaseline(createMockResults(2, 2));
+
+			assert.strictEqual(kept, 2);
+			assert.strictEqual(pruned, 1);
+			const baseline = baselineManager.loadBaseline()!;
+			assert.strictEqual(baseline.entries.length, 2);
+			assert.strictEqual(baseline.history!.length, 1, 'Should capture a snapshot before pruning');
+		});
 	});
 
 	suite('Edge Cases', () => {
@@ -0,0 +1,104 @@
+import * as assert from 'assert';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { parseArgs } from '../../src/cli/ArgumentParser';
+import { AnalysisCache, configHash } from '../../src/cli/AnalysisCache';
+import { CLIAnalyzer } from '../../src/cli/CLIAnalyzer';
+
+suite('Analysis Cache Tests', () => {
+	let root: string;
+	let cacheDir: string;
+
+	setup(() => {
+		root = fs.mkdtempSync(path.join(os.tmpdir(), 'importlens-cache-'));
+		cacheDir = path.join(root, 'cache');
+	});
+
+	teardown(() => {
+		fs.rmSync(root, { recursive: true, force: true });
+	});
+
+	const result = { language: 'rust', unusedImports: [{ line: 1, importStatement: 'use a::b;', symbols: ['b'], reason: 'unused' }] };
+
+	test('Should return stored results for identical content only', () => {
+		const cache = new AnalysisCache(cacheDir, 'config', '1.0.0');
+		cache.set('use a::b;', 'rust', result);
+		cache.save();
+
+		const reloaded = new AnalysisCache(cacheDir, 'config', '1.0.0');
+		assert.deepStrictEqual(reloaded.get('use a::b;', 'rust'), result);
+		assert.strictEqual(reloaded.get('use a::b; ', 'rust'), undefined);
+		assert.strictEqual(reloaded.get('use a::b;', 'python'), undefined);
+	});
+
+	test('Should discard the cache when the version or configuration changes', () => {
+		const cache = new AnalysisCache(cacheDir, 'config', '1.0.0');
+		cache.set('use a::b;', 'rust', result);
+		cache.save();
+
+		assert.strictEqual(new AnalysisCache(cacheDir, 'config', '1.0.1').size, 0);
+		assert.strictEqual(new AnalysisCache(cacheDir, 'other', '1.0.0').size, 0);
+	});
+
+	test('Should hash only options that affect findings', () => {
+		const base = configHash(parseArgs(['--no-cache', 'src']));
+
+		assert.strictEqual(configHash(parseArgs(['--jobs=2', '--format=json', 'lib'])), base);
+		assert.strictEqual(configHash(parseArgs(['--max-file-size=20MB', '--include-large-files'])), base);
+		assert.notStrictEqual(configHash(parseArgs(['--aggressive'])), base);
+		assert.notStrictEqual(configHash(parseArgs(['--language=rust'])), base);
+	});
+
+	test('Should silently rebuild a corrupted cache file', async () => {
+		const filePath = path.join(root, 'lib.rs');
+		fs.writeFileSync(filePath, 'use std::fs::File;\n');
+		fs.mkdirSync(cacheDir);
+		fs.writeFileSync(path.join(cacheDir, 'results.json'), '{"version": ');
+
+		const args = parseArgs([`--cache-dir=${cacheDir}`, filePath]);
+		const [analyzed] = await new CLIAnalyzer(args).analyzeFiles([filePath]);
+
+		assert.strictEqual(analyzed.unusedImports.length, 1);
+		assert.strictEqual(AnalysisCache.forArgs(args).size, 1);
+	});
+
+	test('Should reuse results for unchanged files and re-analyze changed ones', async () => {
+		const unchanged = path.join(root, 'a.rs');
+		const changed = path.join(root, 'b.rs');
+		fs.writeFileSync(unchanged, 'use std::fs::File;\n');
+		fs.writeFileSync(changed, 'use std::io::Write;\n');
+		const args = parseArgs([`--cache-dir=${cacheDir}`, '--jobs=1', root]);
+
+		const cold = await new CLIAnalyzer(args).analyzeFiles([unchanged, changed]);
+		fs.writeFileSync(changed, 'use std::io::Write;\nfn f() { Write::flush; }\n');
+		const warm = await new CLIAnalyzer(args).analyzeFiles([unchanged, changed]);
+
+		assert.deepStrictEqual(warm[0].unusedImports, cold[0].unusedImports);
+		assert.deepStrictEqual(warm[1].unusedImports, []);
+		assert.strictEqual(AnalysisCache.forArgs(args).size, 3);
+	});
+
+	test('Should keep C headers and sources with the same content apart', async () => {
+		const source = path.join(root, 'util.c');
+		const header = path.join(root, 'util.h');
+		fs.writeFileSync(source, '#include <stdio.h>\nint f(void);\n');
+		fs.copyFileSync(source, header);
+		const args = parseArgs([`--cache-dir=${cacheDir}`, '--jobs=1', '--min-confidence=low', root]);
+
+		const cold = await new CLIAnalyzer(args).analyzeFiles([source, header]);
+		const warm = await new CLIAnalyzer(args).analyzeFiles([header, source]);
+
+		assert.deepStrictEqual(cold.map(r => r.unusedImports.map(u => u.confidence)), [['medium'], ['low']]);
+		assert.deepStrictEqual(warm.map(r => r.unusedImports), [cold[1].unusedImports, cold[0].unusedImports]);
+		assert.strictEqual(AnalysisCache.forArgs(args).size, 2);
+	});
+
+	test('Should parse the cache subcommand and flags', () => {
+		assert.strictEqual(parseArgs(['cache', 'clear']).command, 'cache-clear');
+		assert.strictEqual(parseArgs(['cache', 'src']).command, undefined);
+		assert.deepStrictEqual(parseArgs(['cache', 'src']).files, ['cache', 'src']);
+		assert.strictEqual(parseArgs(['--no-cache']).cache, false);
+		assert.strictEqual(AnalysisCache.clear(cacheDir), false);
+	});
+});
@@ -0,0 +1,183 @@
+import * as assert from 'assert';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { parseArgs } from '../../src/cli/ArgumentParser';
+import { CLIAnalyzer } from '../../src/cli/CLIAnalyzer';
+import { ConfigResolver, parseConfig } from '../../src/cli/Config';
+import { FileDiscovery } from '../../src/cli/FileDiscovery';
+import { parseToml } from '../../src/cli/Toml';
+
+suite('Configuration Tests', () => {
+	let root: string;
+
+	setup(() => {
+		root = fs.mkdtempSync(path.join(os.tmpdir(), 'importlens-config-'));
+	});
+
+	teardown(() => {
+		fs.rmSync(root, { recursive: true, force: true });
+	});
+
+	// Helper to write files below the temporary root: { 'a/importlens.toml': '...', ... }
+	function writeTree(files: Record<string, string>): void {
+		for (const [name, content] of Object.entries(files)) {
+			fs.mkdirSync(path.dirname(path.join(root, name)), { recursive: true });
+			fs.writeFileSync(path.join(root, name), content);
+		}
+	}
+
+	suite('TOML', () => {
+		test('Should parse tables, dotted keys, strings, arrays and inline tables', () => {
+			const source = `# comment
+title = "a \\"b\\"" # trailing comment
+count = 1_000
+[fix]
+kinds = [
+    'unused-import', # first
+    "redundant-import",
+]
+"quoted.key".nested = { on = true, ratio = 0.5 }
+`;
+			assert.deepStrictEqual(parseToml(source), {
+				title: 'a "b"',
+				count: 1000,
+				fix: {
+					kinds: ['unused-import', 'redundant-import'],
+					'quoted.key': { nested: { on: true, ratio: 0.5 } },
+				},
+			});
+		});
+
+		test('Should report the line of malformed input', () => {
+			assert.throws(() => parseToml('a = 1\nb = \n'), /line 2: Expected a value/);
+			assert.throws(() => parseToml('a = 1\na = 2\n'), /line 2: Key a is defined twice/);
+		});
+	});
+
+	test('Should validate keys, finding kinds, severities and languages', () => {
+		assert.deepStrictEqual(parseConfig('kinds = ["unused-import"]\n[severity]\nredundant-import = "warn"\n[languages]\n"*.h" = "c++"\n'), {
+			kinds: ['unused-import'],
+			severity: { 'redundant-import': 'warn' },
+			languages: { '*.h': 'cpp' },
+		});
+		assert.throws(() => parseConfig('kind = []'), /importlens.toml: Unknown key kind/);
+		assert.throws(() => parseConfig('kinds = ["unused"]'), /Unknown finding kind 'unused' in kinds/);
+		assert.throws(() => parseConfig('[severity]\nunused-import = "fatal"'), /Invalid severity for unused-import/);
+		assert.throws(() => parseConfig('[fix]\nenabled = "yes"'), /fix.enabled must be true or false/);
+	});
+
+	test('Should merge nested config files with the nearest winning', () => {
+		writeTree({
+			'importlens.toml': 'kinds = ["unused-import", "duplicate-import"]\n[severity]\nunused-import = "warn"\nduplicate-import = "warn"\n[fix]\nenabled = false\n',
+			'team/importlens.toml': '[severity]\nduplicate-import = "off"\n[fix]\nenabled = true\nkinds = ["unused-import"]\n',
+		});
+
+		const effective = new ConfigResolver({}, undefined, root).resolve(path.join(root, 'team/src/lib.rs'));
+
+		assert.deepStrictEqual(effective.sources, [path.join(root, 'importlens.toml'), path.join(root, 'team/importlens.toml')]);
+		assert.deepStrictEqual(effective.kinds, ['unused-import', 'duplicate-import']);
+		assert.deepStrictEqual(effective.severity, { 'unused-import': 'warn', 'duplicate-import': 'off' });
+		assert.deepStrictEqual(effective.fix, { enabled: true, kinds: ['unused-import'] });
+	});
+
+	test('Should ban paths outside the files allow_in names', async () => {
+		writeTree({
+			'importlens.toml': 'banned = [\n  { path = "chrono::*", message = "use time instead" },\n  { path = "crate::db::raw::*", allow_in = ["src/db/**"] },\n]\n',
+			'Cargo.toml': '[package]\nname = "app"\n',
+			'src/lib.rs': 'mod db;\nmod api;\n',
+			'src/db/mod.rs': 'pub mod raw;\nuse self::raw::Conn;\npub fn open() -> Conn { Conn }\n',
+			'src/db/raw.rs': 'pub struct Conn;\n',
+			'src/api.rs': 'use super::db::raw::Conn;\npub fn now(c: Conn) -> i64 { chrono::Utc::now().timestamp() }\n',
+		});
+		const files = ['src/db/mod.rs', 'src/api.rs'].map(name => path.join(root, name));
+		const banned = async (...flags: string[]) => (await new CLIAnalyzer(parseArgs(['--no-cache', ...flags, root])).analyzeFiles(files))
+			.map(result => result.unusedImports.filter(u => u.kind === 'banned-import').map(u => [u.line, u.reason]));
+
+		assert.deepStrictEqual(await banned(), [[], [[1, '`super::db::raw::Conn` is banned by `crate::db::raw::*`']]]);
+		assert.deepStrictEqual((await banned('--check-qualified-paths'))[1][1], [2, '`chrono::Utc::now` is banned by `chrono::*`: use time instead']);
+
+		assert.throws(() => parseConfig('banned = [{ message = "no" }]'), /banned\[0\] needs a path/);
+		assert.throws(() => parseConfig('banned = [{ path = "chrono.*" }]'), /Invalid path pattern in banned\[0\]/);
+		assert.throws(() => parseConfig('banned = [{ path = "a", allowed = [] }]'), /Unknown key banned\[0\].allowed/);
+	});
+
+	test('Should let overrides and --language win over config files', () => {
+		writeTree({ 'importlens.toml': 'kinds = ["unused-import"]\n[languages]\n"*.h" = "cpp"\n' });
+		const header = path.join(root, 'include/a.h');
+
+		assert.strictEqual(new ConfigResolver({}, undefined, root).resolve(header).language, 'cpp');
+		assert.strictEqual(new ConfigResolver({}, 'c', root).resolve(header).language, 'c');
+		assert.deepStrictEqual(new ConfigResolver({ kinds: ['redundant-import'] }, undefined, root).resolve(header).kinds, ['redundant-import']);
+	});
+
+	test('Should skip ignored files when walking, unless a nearer config re-includes them', async () => {
+		writeTree({
+			'importlens.toml': 'ignore = ["generated/", "*.pb.rs"]\n',
+			'keep/importlens.toml': 'ignore = ["!*.pb.rs"]\n',
+			'src/main.rs': '',
+			'src/api.pb.rs': '',
+			'generated/out.rs': '',
+			'keep/api.pb.rs': '',
+		});
+
+		const args = parseArgs(['--no-cache', root]);
+		const files = await new FileDiscovery(args, new ConfigResolver({}, undefined, root)).discoverFiles();
+
+		assert.deepStrictEqual(files.map(file => path.relative(root, file).split(path.sep).join('/')), ['keep/api.pb.rs', 'src/main.rs']);
+	});
+
+	test('Should drop disabled kinds and mark configured severities', async () => {
+		writeTree({
+			'importlens.toml': 'kinds = ["unused-import", "redundant-import"]\n[severity]\nredundant-import = "warn"\n',
+			'lib.rs': 'use std::io::*;\nuse std::io::Read;\nuse std::fs::File;\nuse std::fs::File;\n',
+		});
+		const filePath = path.join(root, 'lib.rs');
+
+		const args = parseArgs(['--no-cache', filePath]);
+		const [result] = await new CLIAnalyzer(args, new ConfigResolver({}, undefined, root)).analyzeFiles([filePath]);
+
+		assert.deepStrictEqual(result.unusedImports.map(u => [u.kind, u.severity]), [
+			['redundant-import', 'warning'],
+			['unused-import', 'error'],
+		]);
+	});
+
+	test('Should give unconfigured kinds their default severity and apply --severity', async () => {
+		writeTree({
+			'importlens.toml': '[severity]\nunused-import = "warn"\n',
+			'lib.rs': 'use std::fs::File;\nuse std::io::Read;\nuse std::io::Read;\n\nfn main() {\n    let _ = File::open("x");\n}\n',
+		});
+		const filePath = path.join(root, 'lib.rs');
+		const analyze = async (argv: string[]) => {
+			const args = parseArgs(['--no-cache', ...argv, filePath]);
+			const [result] = await new CLIAnalyzer(args, new ConfigResolver(args.configOverrides, undefined, root)).analyzeFiles([filePath]);
+			return result.unusedImports.map(u => [u.kind, u.severity]);
+		};
+
+		assert.deepStrictEqual(await analyze([]), [['unused-import', 'warning'], ['duplicate-import', 'warning']]);
+		assert.deepStrictEqual(await analyze(['--severity', 'unused-import=info,duplicate-import=error']), [['unused-import', 'info'], ['duplicate-import', 'error']]);
+		assert.deepStrictEqual(await analyze(['--severity=duplicate-import=off']), [['unused-import', 'warning']]);
+	});
+
+	test('Should parse --severity, --fail-on and --max-warnings', () => {
+		assert.strictEqual(parseArgs([]).failOn, 'error');
+		assert.strictEqual(parseArgs(['--fail-on', 'warn']).failOn, 'warning');
+		assert.strictEqual(parseArgs(['--fail-on=info', '--max-warnings=0']).maxWarnings, 0);
+		assert.deepStrictEqual(parseArgs(['--severity', 'unused-import=warn', '--severity', 'type-only-import=off']).configOverrides?.severity,
+			{ 'unused-import': 'warn', 'type-only-import': 'off' });
+
+		assert.throws(() => parseArgs(['--fail-on', 'fatal']), /Invalid --fail-on level: fatal/);
+		assert.throws(() => parseArgs(['--max-warnings', '-1']), /Invalid --max-warnings value/);
+		assert.throws(() => parseArgs(['--severity', 'unused-import']), /Expected kind=level/);
+		assert.throws(() => parseArgs(['--severity', 'unused=error']), /Unknown finding kind 'unused'/);
+		assert.throws(() => parseArgs(['--severity', 'unused-import=fatal']), /Invalid severity for unused-import/);
+	});
+
+	test('Should parse config --print-effective', () => {
+		const args = parseArgs(['config', '--print-effective', 'src/lib.rs']);
+
+		assert.strictEqual(args.command, 'print-config');
+		assert.deepStrictEqual(args.files, ['src/lib.rs']);
+	});
+});
@@ -0,0 +1,112 @@
+import * as assert from 'assert';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { parseArgs } from '../../src/cli/ArgumentParser';
+import { CLIAnalyzer } from '../../src/cli/CLIAnalyzer';
+import { parseConfig } from '../../src/cli/Config';
+import { CppAnalyzer } from '../../src/cli/CppAnalyzer';
+import { tokenizeCpp } from '../../src/cli/CppLexer';
+
+suite('C/C++ Analyzer Tests', () => {
+	const analyzer = new CppAnalyzer();
+	const sample = (name: string) => path.join(__dirname, '../../../test-samples', name);
+
+	// Helper: header, line and confidence of every finding
+	function findings(source: string, filePath = 'main.cpp', headers?: Record<string, string[]>): Array<[string, number, string | undefined]> {
+		return analyzer.analyzeFile(source, filePath, headers).map(u => [u.symbols[0], u.line, u.confidence]);
+	}
+
+	suite('Identifier lists', () => {
+		test('Should report includes none of whose identifiers is used', () => {
+			const source = '#include <vector>\n#include <map>\n#include "config.h"\n\nstd::vector<int> values;\n';
+			const [unused] = analyzer.analyzeFile(source, 'main.cpp');
+
+			assert.deepStrictEqual(findings(source), [['map', 2, 'medium']]);
+			assert.strictEqual(unused.reason, 'Header <map> may be unused: none of std::map, std::multimap is used');
+			assert.strictEqual(unused.confidenceReason, 'header-identifiers-listed');
+			assert.strictEqual(unused.importStatement, '#include <map>');
+			assert.deepStrictEqual(unused.span, { start: source.indexOf('#include <map>'), end: source.indexOf('#include <map>') + 14 });
+		});
+
+		test('Should count bare names after using namespace, and namespaces as a whole', () => {
+			assert.deepStrictEqual(findings('#include <vector>\n#include <chrono>\nusing namespace std;\nvector<int> v;\nauto t = chrono::steady_clock::now();\n'), []);
+			assert.deepStrictEqual(findings('#include <vector>\nnamespace fs = std::filesystem;\nvector<int> v;\n'), [['vector', 1, 'medium']]);
+			// `<cstring>` declares both `memcpy` and `std::memcpy`
+			assert.deepStrictEqual(findings('#include <cstring>\nvoid f(char *a, char *b) { std::memcpy(a, b, 1); }\n'), []);
+		});
+
+		test('Should not count comments, strings, members or include lines', () => {
+			const source = '#include <string.h>\n#include <stdio.h>\n// strlen(s)\nconst char *s = "printf";\nint f(struct t *p) { return p->strlen + p.puts; }\n';
+			assert.deepStrictEqual(findings(source, 'main.c'), [['string.h', 1, 'medium'], ['stdio.h', 2, 'medium']]);
+			// Macro bodies are code
+			assert.deepStrictEqual(findings('#include <string.h>\n#define COPY(a, b) memcpy(a, b, sizeof b)\n', 'main.c'), []);
+			assert.deepStrictEqual(
+				tokenizeCpp('#include <a.h> // c\nR"x(")x" 1\'000').map(t => [t.kind, t.text]),
+				[['directive', 'include'], ['header', '<a.h>'], ['newline', '\n'], ['string', 'R"x(")x"'], ['number', '1\'000']]
+			);
+		});
+
+		test('Should extend the identifier lists with the headers table', () => {
+			const source = '#include "mylib/log.h"\n#include <vector>\nint main() { return 0; }\n';
+			assert.deepStrictEqual(findings(source), [['vector', 2, 'medium']]);
+			assert.deepStrictEqual(findings(source, 'main.cpp', { 'mylib/log.h': ['log_info'], vector: [] }), [['mylib/log.h', 1, 'medium']]);
+			assert.deepStrictEqual(findings(source + 'void f() { log_info(); }\n', 'main.cpp', { 'mylib/log.h': ['log_info'] }), [['vector', 2, 'medium']]);
+
+			assert.deepStrictEqual(parseConfig('[headers]\n"mylib/log.h" = ["log_info", "mylib::Logger"]\n'), {
+				headers: { 'mylib/log.h': ['log_info', 'mylib::Logger'] },
+			});
+			assert.throws(() => parseConfig('[headers]\n"mylib/log.h" = "log_info"\n'), /headers."mylib\/log.h" must be an array of strings/);
+		});
+	});
+
+	suite('Preprocessor context', () => {
+		test('Should give low confidence to includes under conditions or inside extern "C"', () => {
+			const source = '#ifdef _WIN32\n#include <thread>\n#else\n#include <mutex>\n#endif\nextern "C" {\n#include <stdint.h>\n}\n';
+			const unused = analyzer.analyzeFile(source, 'main.cpp');
+
+			assert.deepStrictEqual(findings(source), [['thread', 2, 'low'], ['mutex', 4, 'low'], ['stdint.h', 7, 'low']]);
+			assert.ok(unused[0].reason.endsWith('(included under `#ifdef _WIN32`)'));
+			assert.ok(unused[1].reason.endsWith('(included under `#else` of `#ifdef _WIN32`)'));
+			assert.ok(unused[2].reason.endsWith('(inside `extern "C"`)'));
+			assert.ok(unused.every(u => u.confidenceReason === 'preprocessor-context'));
+		});
+
+		test('Should treat include guards as unconditional and their files as headers', () => {
+			const guarded = '#ifndef A_H\n#define A_H\n#include <vector>\n#endif\n';
+			assert.deepStrictEqual(findings(guarded, 'a.inc'), [['vector', 3, 'low']]);
+			assert.ok(analyzer.analyzeFile(guarded, 'a.inc')[0].reason.endsWith('(in a header, whose includers may rely on it)'));
+			assert.deepStrictEqual(findings('#pragma once\n#include <vector>\n', 'a.inc'), [['vector', 2, 'low']]);
+			assert.deepStrictEqual(findings('#include <vector>\n', 'a.hpp'), [['vector', 1, 'low']]);
+
+			// Code after the `#endif` makes it an ordinary condition
+			const notGuard = '#ifndef A_H\n#define A_H\n#include <vector>\n#endif\nint x;\n';
+			assert.ok(analyzer.analyzeFile(notGuard, 'a.cpp')[0].reason.endsWith('(included under `#ifndef A_H`)'));
+		});
+
+		test('Should analyze the sample files', () => {
+			const analyze = (name: string) => findings(fs.readFileSync(sample(name), 'utf8'), name);
+
+			assert.deepStrictEqual(analyze('test.cpp'), [['map', 4, 'medium'], ['cstring', 6, 'medium']]);
+			assert.deepStrictEqual(analyze('test.c'), [['math.h', 5, 'medium']]);
+			assert.deepStrictEqual(analyze('guarded.hpp'), [['optional', 6, 'low'], ['stddef.h', 12, 'low']]);
+		});
+	});
+
+	test('Should route C and C++ files through the CLI and apply importlens.toml headers', async () => {
+		const root = fs.mkdtempSync(path.join(os.tmpdir(), 'importlens-cpp-'));
+		try {
+			fs.copyFileSync(sample('test.cpp'), path.join(root, 'main.cc'));
+			fs.copyFileSync(sample('test.c'), path.join(root, 'util.c'));
+			const analyze = async () => (await new CLIAnalyzer(parseArgs(['--no-cache', root]))
+				.analyzeFiles([path.join(root, 'main.cc'), path.join(root, 'util.c')]))
+				.map(result => [result.language, result.unusedImports.map(u => u.symbols[0])]);
+
+			assert.deepStrictEqual(await analyze(), [['cpp', ['map', 'cstring']], ['c', ['math.h']]]);
+			fs.writeFileSync(path.join(root, 'importlens.toml'), '[headers]\n"config.h" = ["APP_NAME"]\nmap = []\n');
+			assert.deepStrictEqual(await analyze(), [['cpp', ['cstring', 'config.h']], ['c', ['math.h']]]);
+		} finally {
+			fs.rmSync(root, { recursive: true, force: true });
+		}
+	});
+});
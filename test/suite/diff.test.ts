@@ -0,0 +1,103 @@
+import * as assert from 'assert';
+import { execFileSync } from 'child_process';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { parseArgs } from '../../src/cli/ArgumentParser';
+import { AnalysisResult, CLIAnalyzer } from '../../src/cli/CLIAnalyzer';
+import { filterToDiff, gitDiff, parseUnifiedDiff } from '../../src/cli/DiffFilter';
+
+suite('Diff Filter Tests', () => {
+	let root: string;
+
+	setup(() => {
+		root = fs.mkdtempSync(path.join(os.tmpdir(), 'importlens-diff-'));
+	});
+
+	teardown(() => {
+		fs.rmSync(root, { recursive: true, force: true });
+	});
+
+	async function analyze(name: string, content: string): Promise<AnalysisResult[]> {
+		const filePath = path.join(root, name);
+		fs.mkdirSync(path.dirname(filePath), { recursive: true });
+		fs.writeFileSync(filePath, content);
+		return new CLIAnalyzer(parseArgs(['--no-cache', filePath])).analyzeFiles([filePath]);
+	}
+
+	// Helper: the reported names after filtering to the diff
+	function reported(results: AnalysisResult[], diff: string, scope: 'imports' | 'any' = 'imports'): string[] {
+		return filterToDiff(results, parseUnifiedDiff(diff), root, scope).results.flatMap(r => r.unusedImports.map(u => u.symbols[0]));
+	}
+
+	test('Should parse changed lines of renamed, new and deleted files', () => {
+		const diff = [
+			'diff --git a/src/old.rs b/src/new.rs',
+			'similarity index 90%',
+			'rename from src/old.rs',
+			'rename to src/new.rs',
+			'--- a/src/old.rs',
+			'+++ b/src/new.rs',
+			'@@ -2 +2,2 @@',
+			'-use std::fs;',
+			'+use std::io;',
+			'+use std::fmt;',
+			'@@ -10,0 +12 @@ fn main() {',
+			'+--x;',
+			'diff --git a/gone.rs b/gone.rs',
+			'deleted file mode 100644',
+			'--- a/gone.rs',
+			'+++ /dev/null',
+			'@@ -1 +0,0 @@',
+			'-use std::fs;',
+			'diff --git a/added.rs b/added.rs',
+			'new file mode 100644',
+			'--- /dev/null',
+			'+++ b/added.rs',
+			'@@ -0,0 +1 @@',
+			'+use std::env;',
+			'',
+		].join('\n');
+
+		const files = parseUnifiedDiff(diff);
+
+		assert.deepStrictEqual([...files.keys()], ['src/new.rs', 'added.rs']);
+		assert.strictEqual(files.get('src/new.rs')!.oldPath, 'src/old.rs');
+		assert.deepStrictEqual([...files.get('src/new.rs')!.addedLines], [2, 3, 12]);
+		assert.deepStrictEqual(files.get('src/new.rs')!.changedText, ['use std::fs;', 'use std::io;', 'use std::fmt;', '--x;']);
+		assert.deepStrictEqual([...files.get('added.rs')!.addedLines], [1]);
+	});
+
+	test('Should only keep findings whose import line changed', async () => {
+		const results = await analyze('src/lib.rs', 'use std::fs::File;\nuse std::io::Read;\n\npub fn f() {}\n');
+		const diff = '--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -2 +2 @@\n-use std::io::Write;\n+use std::io::Read;\n';
+
+		assert.deepStrictEqual(reported(results, diff), ['Read']);
+		assert.deepStrictEqual(reported(results, diff.replace(/src\/lib\.rs/g, 'src/other.rs')), []);
+	});
+
+	test('Should keep imports whose last use was deleted with the any scope', async () => {
+		const results = await analyze('lib.rs', 'use std::fs::File;\nuse std::io::Read;\n\npub fn f() {}\n');
+		const diff = '--- a/lib.rs\n+++ b/lib.rs\n@@ -5,3 +4,0 @@\n-pub fn g() {\n-    let _ = File::open("x");\n-}\n';
+
+		assert.deepStrictEqual(reported(results, diff, 'imports'), []);
+		assert.deepStrictEqual(reported(results, diff, 'any'), ['File']);
+	});
+
+	test('Should diff the working tree against a git ref', async () => {
+		const git = (...args: string[]) => execFileSync('git', ['-c', 'user.name=t', '-c', 'user.email=t@t', ...args], { cwd: root, stdio: 'pipe' });
+		git('init', '-q');
+		fs.writeFileSync(path.join(root, 'old.rs'), 'use std::fs::File;\n\npub fn f() {}\n');
+		git('add', '.');
+		git('commit', '-qm', 'base');
+		git('mv', 'old.rs', 'new.rs');
+		fs.writeFileSync(path.join(root, 'new.rs'), 'use std::fs::File;\nuse std::io::Read;\n\npub fn f() {}\n');
+
+		const results = await analyze('new.rs', fs.readFileSync(path.join(root, 'new.rs'), 'utf-8'));
+
+		assert.deepStrictEqual(reported(results, gitDiff('HEAD', root)), ['Read']);
+		assert.throws(() => gitDiff('no-such-ref', root), /git diff against no-such-ref failed/);
+		assert.throws(() => parseArgs(['--diff-base', 'main', '--fix', '.']), /cannot be used with --fix/);
+		assert.throws(() => parseArgs(['--diff-scope=any', '.']), /--diff-scope can only be used with --diff-base or --diff/);
+	});
+});
@@ -0,0 +1,187 @@
+import * as assert from 'assert';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { parseArgs } from '../../src/cli/ArgumentParser';
+import { ConfigResolver } from '../../src/cli/Config';
+import { DirectoryWalker } from '../../src/cli/DirectoryWalker';
+import { FileDiscovery, formatSize, parseFileList } from '../../src/cli/FileDiscovery';
+import { IgnoreRules } from '../../src/cli/IgnoreRules';
+
+suite('File Discovery Tests', () => {
+	suite('Ignore patterns', () => {
+		test('Should match unanchored names at any depth', () => {
+			const rules = IgnoreRules.parse('generated.rs\n*.bak\n');
+
+			assert.strictEqual(rules.match('generated.rs', false), true);
+			assert.strictEqual(rules.match('src/deep/generated.rs', false), true);
+			assert.strictEqual(rules.match('src/main.rs.bak', false), true);
+			assert.strictEqual(rules.match('src/main.rs', false), undefined);
+		});
+
+		test('Should anchor patterns containing a slash', () => {
+			const rules = IgnoreRules.parse('/vendor\nsrc/gen/*.rs\n');
+
+			assert.strictEqual(rules.match('vendor', true), true);
+			assert.strictEqual(rules.match('crates/vendor', true), undefined);
+			assert.strictEqual(rules.match('src/gen/a.rs', false), true);
+			assert.strictEqual(rules.match('src/gen/sub/a.rs', false), undefined);
+		});
+
+		test('Should support double-star, directory-only and negated patterns', () => {
+			const rules = IgnoreRules.parse('# comment\n**/fixtures/**\nlogs/\n*.rs\n!keep.rs\n');
+
+			assert.strictEqual(rules.match('a/fixtures/b/c.txt', false), true);
+			assert.strictEqual(rules.match('logs', true), true);
+			assert.strictEqual(rules.match('logs', false), undefined);
+			assert.strictEqual(rules.match('src/lib.rs', false), true);
+			assert.strictEqual(rules.match('src/keep.rs', false), false);
+		});
+	});
+
+	suite('Directory walking', () => {
+		let root: string;
+
+		// Helper to create a file (and its parent directories) below the temp root
+		function write(relativePath: string, content = ''): void {
+			const filePath = path.join(root, relativePath);
+			fs.mkdirSync(path.dirname(filePath), { recursive: true });
+			fs.writeFileSync(filePath, content);
+		}
+
+		function walk(options: { hidden?: boolean; exclude?: string[] } = {}): { files: string[]; warnings: string[] } {
+			const walker = new DirectoryWalker({
+				extensions: ['.rs'],
+				hidden: options.hidden ?? false,
+				exclude: options.exclude ?? [],
+				excludeBase: root,
+			});
+			const files = walker.walk(root).map(file => path.relative(root, file).split(path.sep).join('/'));
+			return { files, warnings: walker.warnings };
+		}
+
+		setup(() => {
+			root = fs.mkdtempSync(path.join(os.tmpdir(), 'importlens-walk-'));
+		});
+
+		teardown(() => {
+			fs.rmSync(root, { recursive: true, force: true });
+		});
+
+		test('Should find source files recursively and skip target/', () => {
+			write('src/main.rs');
+			write('crates/core/src/lib.rs');
+			write('target/debug/build/out.rs');
+			write('README.md');
+
+			assert.deepStrictEqual(walk().files, ['crates/core/src/lib.rs', 'src/main.rs']);
+		});
+
+		test('Should honour .gitignore, .ignore and .importlensignore', () => {
+			write('.gitignore', 'generated/\n');
+			write('.ignore', '*.gen.rs\n');
+			write('src/.importlensignore', 'legacy.rs\n');
+			write('generated/a.rs');
+			write('src/schema.gen.rs');
+			write('src/legacy.rs');
+			write('src/lib.rs');
+
+			assert.deepStrictEqual(walk().files, ['src/lib.rs']);
+		});
+
+		test('Should let a nested ignore file re-include a path', () => {
+			write('.gitignore', '*.rs\n');
+			write('keep/.importlensignore', '!*.rs\n');
+			write('drop/a.rs');
+			write('keep/b.rs');
+
+			assert.deepStrictEqual(walk().files, ['keep/b.rs']);
+		});
+
+		test('Should include dot-directories only with hidden', () => {
+			write('.cargo/config.rs');
+			write('src/lib.rs');
+
+			assert.deepStrictEqual(walk().files, ['src/lib.rs']);
+			assert.deepStrictEqual(walk({ hidden: true }).files, ['.cargo/config.rs', 'src/lib.rs']);
+		});
+
+		test('Should apply exclude patterns', () => {
+			write('src/lib.rs');
+			write('src/bindings/ffi.rs');
+
+			assert.deepStrictEqual(walk({ exclude: ['src/bindings/**'] }).files, ['src/lib.rs']);
+		});
+
+		test('Should survive symlink cycles and warn about broken symlinks', function () {
+			write('src/lib.rs');
+			try {
+				fs.symlinkSync(root, path.join(root, 'src', 'loop'), 'dir');
+				fs.symlinkSync(path.join(root, 'missing.rs'), path.join(root, 'dangling.rs'));
+			} catch {
+				this.skip(); // Symlinks need extra privileges on some platforms
+			}
+
+			const { files, warnings } = walk();
+
+			assert.deepStrictEqual(files, ['src/lib.rs']);
+			assert.strictEqual(warnings.length, 1);
+			assert.ok(warnings[0].includes('dangling.rs'));
+		});
+	});
+
+	suite('File lists', () => {
+		let root: string;
+
+		setup(() => {
+			root = fs.mkdtempSync(path.join(os.tmpdir(), 'importlens-list-'));
+		});
+
+		teardown(() => {
+			fs.rmSync(root, { recursive: true, force: true });
+		});
+
+		test('Should split newline and NUL separated lists', () => {
+			assert.deepStrictEqual(parseFileList('src/a.rs\r\nsrc/b c.rs\n\n', false), ['src/a.rs', 'src/b c.rs']);
+			assert.deepStrictEqual(parseFileList('src/a.rs\0src/new\nline.rs\0', true), ['src/a.rs', 'src/new\nline.rs']);
+		});
+
+		test('Should keep only existing source files that are not ignored', () => {
+			for (const name of ['lib.rs', 'gen.rs', 'README.md']) fs.writeFileSync(path.join(root, name), '');
+			fs.mkdirSync(path.join(root, 'src'));
+			fs.writeFileSync(path.join(root, 'importlens.toml'), 'ignore = ["gen.rs"]\n');
+
+			const discovery = new FileDiscovery(parseArgs([]), new ConfigResolver({}, undefined, root));
+			const listed = ['lib.rs', 'gen.rs', 'README.md', 'src', 'deleted.rs', 'lib.rs'].map(name => path.join(root, name));
+
+			assert.deepStrictEqual(discovery.filterListedFiles(listed), [path.join(root, 'lib.rs')]);
+		});
+
+		test('Should skip files over --max-file-size unless large files are included', async () => {
+			fs.writeFileSync(path.join(root, 'lib.rs'), 'use std::fmt;\n');
+			fs.writeFileSync(path.join(root, 'bindings.rs'), 'x'.repeat(3 * 1024));
+			const discover = async (argv: string[]) => {
+				const discovery = new FileDiscovery(parseArgs([...argv, root]), new ConfigResolver({}, undefined, root));
+				return { files: (await discovery.discoverFiles()).map(file => path.basename(file)), warnings: discovery.warnings };
+			};
+
+			assert.deepStrictEqual(await discover(['--max-file-size=2KB']), {
+				files: ['lib.rs'],
+				warnings: [`Skipping ${path.join(root, 'bindings.rs')}: 3 KB is over the 2 KB --max-file-size; pass --include-large-files to analyze it`],
+			});
+			assert.deepStrictEqual((await discover(['--max-file-size=2KB', '--include-large-files'])).files, ['bindings.rs', 'lib.rs']);
+			assert.deepStrictEqual((await discover([])).files, ['bindings.rs', 'lib.rs']);
+			assert.strictEqual(parseArgs(['--max-file-size', '1.5MB']).maxFileSize, 1572864);
+			assert.throws(() => parseArgs(['--max-file-size=big']), /Invalid --max-file-size value: big/);
+			assert.deepStrictEqual([512, 2560, 52428800].map(formatSize), ['512 B', '2.5 KB', '50 MB']);
+		});
+
+		test('Should validate stdin and file list options', () => {
+			assert.strictEqual(parseArgs(['--stdin', '--stdin-filename', 'src/lib.rs']).stdinFilename, 'src/lib.rs');
+			assert.throws(() => parseArgs(['--stdin']), /--stdin requires --stdin-filename or --language/);
+			assert.throws(() => parseArgs(['--stdin', '--language=rust', 'src']), /--stdin cannot be used with file arguments/);
+			assert.throws(() => parseArgs(['--stdin-filename=a.rs']), /--stdin-filename can only be used with --stdin/);
+			assert.throws(() => parseArgs(['-0', 'src']), /-0 can only be used with --files-from/);
+		});
+	});
+});
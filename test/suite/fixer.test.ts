@@ -0,0 +1,311 @@
+import * as assert from 'assert';
+import { execFileSync } from 'child_process';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { parseArgs } from '../../src/cli/ArgumentParser';
+import { CLIAnalyzer } from '../../src/cli/CLIAnalyzer';
+import { FixEngine, isFixable } from '../../src/cli/FixEngine';
+import { RustAnalyzer } from '../../src/cli/RustAnalyzer';
+import { createUnifiedDiff } from '../../src/cli/UnifiedDiff';
+
+suite('Fix Engine Tests', () => {
+	const analyzer = new RustAnalyzer();
+	const engine = new FixEngine();
+
+	// Helper: analyze a Rust source and apply every suggested fix
+	function fixRust(source: string): string {
+		return engine.applyFixes(source, 'rust', analyzer.analyzeFile(source));
+	}
+
+	suite('Rust use-tree removal', () => {
+		test('Should remove a single-item use statement with its newline', () => {
+			const source = `use std::fs::File;\nuse std::io::Write;\n\nfn main() { Write::flush; }\n`;
+
+			assert.strictEqual(fixRust(source), `use std::io::Write;\n\nfn main() { Write::flush; }\n`);
+		});
+
+		test('Should remove an item from the middle of a group', () => {
+			const source = `use std::io::{self, BufReader, Write};\nfn main() { io::stdout(); Write::flush; }\n`;
+
+			assert.strictEqual(fixRust(source), `use std::io::{self, Write};\nfn main() { io::stdout(); Write::flush; }\n`);
+		});
+
+		test('Should remove the trailing item of a group', () => {
+			const source = `use std::io::{self, Read, Write};\nfn main() { io::stdout(); Read::read; }\n`;
+
+			assert.strictEqual(fixRust(source), `use std::io::{self, Read};\nfn main() { io::stdout(); Read::read; }\n`);
+		});
+
+		test('Should unwrap a group left with one item', () => {
+			const source = `use std::io::{Read, Write};\nfn main() { Write::flush; }\n`;
+
+			assert.strictEqual(fixRust(source), `use std::io::Write;\nfn main() { Write::flush; }\n`);
+		});
+
+		test('Should keep braces around a lone self', () => {
+			const source = `use std::io::{self, Write};\nfn main() { io::stdout(); }\n`;
+
+			assert.strictEqual(fixRust(source), `use std::io::{self};\nfn main() { io::stdout(); }\n`);
+		});
+
+		test('Should drop the whole statement when the last remaining item goes', () => {
+			const source = `fn a() {}\nuse std::{fmt, io::{Read, Write}};\nfn main() {}\n`;
+
+			assert.strictEqual(fixRust(source), `fn a() {}\nfn main() {}\n`);
+		});
+
+		test('Should remove an emptied nested group', () => {
+			const source = `use std::{fmt, io::{Read, Write}};\nfn main() { fmt::format; }\n`;
+
+			assert.strictEqual(fixRust(source), `use std::fmt;\nfn main() { fmt::format; }\n`);
+		});
+
+		test('Should remove lines of a multi-line group with their comments', () => {
+			const source = `use std::{
+    fmt, // formatting
+    collections::HashMap, // unused
+    sync::Arc,
+};
+
+fn main() { fmt::format; Arc::new; }
+`;
+			const expected = `use std::{
+    fmt, // formatting
+    sync::Arc,
+};
+
+fn main() { fmt::format; Arc::new; }
+`;
+			assert.strictEqual(fixRust(source), expected);
+		});
+
+		test('Should remove the trailing comment and attributes of a removed statement', () => {
+			const source = `#[cfg(feature = "x")]\nuse std::fs::File; // for later\nfn main() {}\n`;
+
+			assert.strictEqual(fixRust(source), `fn main() {}\n`);
+		});
+
+		test('Should preserve CRLF line endings', () => {
+			const source = `use std::fs::File;\r\nuse std::io::{Read, Write};\r\nfn main() { Read::read; }\r\n`;
+
+			assert.strictEqual(fixRust(source), `use std::io::Read;\r\nfn main() { Read::read; }\r\n`);
+		});
+
+		test('Should remove the later copy of a duplicate import', () => {
+			const source = `use std::fmt;\nuse std::{fmt, io};\nfn f(_: &dyn fmt::Debug) { io::stdout(); }\n`;
+
+			assert.strictEqual(fixRust(source), `use std::fmt;\nuse std::io;\nfn f(_: &dyn fmt::Debug) { io::stdout(); }\n`);
+		});
+
+		test('Should remove an unused extern crate item with its attributes', () => {
+			const source = `#[cfg(feature = "log")]\nextern crate log;\nextern crate serde as sd;\nfn f(_: sd::de::IgnoredAny) {}\n`;
+
+			assert.strictEqual(fixRust(source), `extern crate serde as sd;\nfn f(_: sd::de::IgnoredAny) {}\n`);
+		});
+
+		test('Should drop redundant aliases, and remove unused ones with their import', () => {
+			const source = `use foo::Bar as Bar;\nuse std::io::{self  as io, Read as Read, Write};\nfn main() { Bar::new(); io::stdout(); Write::flush; }\n`;
+
+			assert.strictEqual(fixRust(source), `use foo::Bar;\nuse std::io::{self, Write};\nfn main() { Bar::new(); io::stdout(); Write::flush; }\n`);
+		});
+
+		test('Should count only the findings a removal was applied to', () => {
+			const source = `use std::fs::File;\nuse std::io::{Read, Write};\nfn main() { Read::read; }\n`;
+			const unused = analyzer.analyzeFile(source);
+			// A finding whose span no longer points at a use tree leaf gets no edit
+			const stale = { ...unused[0], span: { start: source.indexOf('main'), end: source.indexOf('main') + 4 } };
+
+			const fix = engine.fixSource(source, { filePath: 'lib.rs', language: 'rust', unusedImports: [...unused, stale] });
+			assert.strictEqual(unused.length, 2);
+			assert.strictEqual(fix.fixedCount, 2);
+			assert.strictEqual(fix.fixed, 'use std::io::Read;\nfn main() { Read::read; }\n');
+		});
+
+		test('Should leave a file without unused imports unchanged', () => {
+			const source = `use std::io::Write;\nfn main() { Write::flush; }\n`;
+
+			assert.strictEqual(fixRust(source), source);
+		});
+	});
+
+	suite('Rust import organizing', () => {
+		function organize(source: string): string {
+			return engine.organizeImports(source, 'rust');
+		}
+
+		test('Should split imports into std, external and crate blocks and merge by module', () => {
+			const source = `use crate::config::Config;
+use serde::Serialize;
+use std::io::Write;
+use std::fs::File;
+use serde::Deserialize;
+use super::util;
+
+fn main() {}
+`;
+
+			assert.strictEqual(organize(source), `use std::fs::File;
+use std::io::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use super::util;
+
+fn main() {}
+`);
+		});
+
+		test('Should keep attributes and comments with their item and never cross other items', () => {
+			const source = `// Copyright notice
+
+use std::io::Write;
+// Needed for the trait impls
+use std::fmt;
+#[cfg(test)]
+use crate::tests::Helper;
+use anyhow::Result; // error type
+
+struct S;
+
+use b::B;
+use a::A;
+`;
+
+			assert.strictEqual(organize(source), `// Copyright notice
+
+// Needed for the trait impls
+use std::fmt;
+use std::io::Write;
+
+use anyhow::Result; // error type
+
+#[cfg(test)]
+use crate::tests::Helper;
+
+struct S;
+
+use a::A;
+use b::B;
+`);
+		});
+
+		test('Should fold a module import into its group as self and be idempotent', () => {
+			const source = `mod inner {
+    use std::io::Read;
+    pub use std::io::BufRead;
+    use std::io;
+    use std::io::{Write, Read as R};
+}
+`;
+			const organized = organize(source);
+
+			assert.strictEqual(organized, `mod inner {
+    pub use std::io::BufRead;
+    use std::io::{self, Read, Read as R, Write};
+}
+`);
+			assert.strictEqual(organize(organized), organized);
+			assert.deepStrictEqual(engine.organizeEdits(organized, 'rust'), []);
+		});
+
+		test('Should parse --organize and --check-organized', () => {
+			assert.strictEqual(parseArgs(['--organize', '--dry-run', 'src']).organize, true);
+			assert.strictEqual(parseArgs(['--check-organized', 'src']).checkOrganized, true);
+			assert.throws(() => parseArgs(['--check-organized', '--organize', 'src']), /--check-organized cannot be used with --fix or --organize/);
+		});
+	});
+
+	suite('Unified diff', () => {
+		test('Should produce an empty diff for identical content', () => {
+			assert.strictEqual(createUnifiedDiff('a/x', 'b/x', 'same\n', 'same\n'), '');
+		});
+
+		test('Should produce a hunk with context lines', () => {
+			const before = 'use a;\nuse b;\n\nfn main() {}\n';
+			const after = 'use a;\n\nfn main() {}\n';
+
+			assert.strictEqual(
+				createUnifiedDiff('a/src/lib.rs', 'b/src/lib.rs', before, after),
+				'--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1,4 +1,3 @@\n use a;\n-use b;\n \n fn main() {}\n'
+			);
+		});
+
+		test('Should split distant changes into separate hunks', () => {
+			const lines = Array.from({ length: 20 }, (_, i) => `line ${i + 1}`);
+			const before = lines.join('\n') + '\n';
+			const after = lines.filter(line => line !== 'line 2' && line !== 'line 19').join('\n') + '\n';
+			const diff = createUnifiedDiff('a/f', 'b/f', before, after);
+
+			assert.deepStrictEqual(diff.match(/^@@.*@@$/gm), ['@@ -1,5 +1,4 @@', '@@ -16,5 +15,4 @@']);
+		});
+
+		test('Should mark a missing final newline', () => {
+			const diff = createUnifiedDiff('a/f', 'b/f', 'one\ntwo', 'one\n');
+
+			assert.ok(diff.endsWith('-two\n\\ No newline at end of file\n'));
+		});
+	});
+
+	suite('Fix patches', () => {
+		let root: string;
+
+		setup(() => {
+			root = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'importlens-patch-')));
+		});
+
+		teardown(() => {
+			fs.rmSync(root, { recursive: true, force: true });
+		});
+
+		function git(...args: string[]): string {
+			return execFileSync('git', ['-c', 'user.name=t', '-c', 'user.email=t@t', ...args], { cwd: root, encoding: 'utf-8', stdio: 'pipe' });
+		}
+
+		async function analyze(files: string[]) {
+			return new CLIAnalyzer(parseArgs(['--no-cache', root])).analyzeFiles(files.map(file => path.join(root, file)));
+		}
+
+		test('Should write a patch git apply accepts, after which nothing is left to fix', async () => {
+			const body = Array.from({ length: 12 }, (_, i) => `pub fn f${i}() {}`).join('\n');
+			const files: Record<string, string> = {
+				// Two adjacent removals share a hunk; the one in `inner` gets its own
+				'src/lib.rs': `mod clean;\nmod util;\nuse std::fs::File;\nuse std::io::Read;\nuse std::io::{self, BufRead, Write};\n\npub fn open() -> File { File::open("a").unwrap() }\npub fn out() { io::stdout().flush().unwrap(); }\n${body}\n\nmod inner {\n    use std::fmt;\n    pub fn g() {}\n}\n`,
+				'src/clean.rs': 'use std::env;\n\npub fn home() -> Option<String> { env::var("HOME").ok() }\n',
+				// CRLF and no newline at the end
+				'src/util/mod.rs': 'use std::mem;\r\nuse std::ptr;\r\n\r\npub fn size() -> usize { mem::size_of::<u8>() }',
+			};
+			for (const [name, content] of Object.entries(files)) {
+				fs.mkdirSync(path.dirname(path.join(root, name)), { recursive: true });
+				fs.writeFileSync(path.join(root, name), content);
+			}
+			git('init', '-q');
+			git('add', '.');
+			git('commit', '-qm', 'base');
+
+			const results = await analyze(Object.keys(files));
+			const patch = results.map(result => engine.fixFile(result, true, false, root).diff).join('');
+
+			assert.strictEqual(git('status', '--porcelain'), '', 'a dry run changed files');
+			assert.deepStrictEqual(patch.match(/^(---|\+\+\+) .*$/gm), ['--- a/src/lib.rs', '+++ b/src/lib.rs', '--- a/src/util/mod.rs', '+++ b/src/util/mod.rs']);
+			assert.deepStrictEqual(patch.match(/^@@.*@@$/gm), ['@@ -1,8 +1,7 @@', '@@ -20,6 +19,5 @@', '@@ -1,4 +1,3 @@']);
+
+			fs.writeFileSync(path.join(root, 'fixes.patch'), patch);
+			git('apply', '--check', 'fixes.patch');
+			git('apply', 'fixes.patch');
+
+			assert.deepStrictEqual(git('diff', '--name-only').trim().split('\n'), ['src/lib.rs', 'src/util/mod.rs']);
+			assert.strictEqual(fs.readFileSync(path.join(root, 'src/util/mod.rs'), 'utf-8'), 'use std::mem;\r\n\r\npub fn size() -> usize { mem::size_of::<u8>() }');
+			const remaining = (await analyze(Object.keys(files))).flatMap(result => result.unusedImports.filter(isFixable));
+			assert.deepStrictEqual(remaining, []);
+		});
+
+		test('Should only accept --output-patch with fixes', () => {
+			assert.strictEqual(parseArgs(['--fix', '--output-patch', 'fixes.patch', 'src']).outputPatch, 'fixes.patch');
+			assert.strictEqual(parseArgs(['--organize', '--output-patch=-', 'src']).outputPatch, '-');
+			assert.throws(() => parseArgs(['--check', '--output-patch', 'fixes.patch', 'src']), /--output-patch can only be used together with --fix or --organize/);
+			assert.throws(() => parseArgs(['--fix', '--dry-run', '--output-patch', 'fixes.patch', 'src']), /not with --dry-run/);
+		});
+	});
+});
@@ -0,0 +1,82 @@
+import * as assert from 'assert';
+import * as fs from 'fs';
+import * as path from 'path';
+import { assumedPackageName, GoAnalyzer } from '../../src/cli/GoAnalyzer';
+
+suite('Go Analyzer Tests', () => {
+	const analyzer = new GoAnalyzer();
+
+	// Helper: kind and name of every finding
+	function findings(source: string): Array<[string | undefined, string]> {
+		return analyzer.analyzeFile(source).map(u => [u.kind, u.symbols[0]]);
+	}
+
+	suite('Import forms', () => {
+		test('Should report unused packages of single and grouped imports', () => {
+			const source = `package main\n\nimport "os"\nimport (\n\t"fmt"\n\tstr "strings"\n\n\t"net/http"\n)\n\nfunc main() { fmt.Println(str.ToUpper("x")) }\n`;
+			const unused = analyzer.analyzeFile(source);
+
+			assert.deepStrictEqual(unused.map(u => [u.symbols[0], u.importPath, u.line]), [['os', 'os', 3], ['http', 'net/http', 8]]);
+			assert.deepStrictEqual(unused[1].span, { start: source.indexOf('"net/http"'), end: source.indexOf('"net/http"') + 10 });
+			assert.strictEqual(unused[1].importStatement, 'import "net/http"');
+		});
+
+		test('Should never report blank, dot and cgo imports', () => {
+			const source = `package main\n\n// #include <stdio.h>\nimport "C"\n\nimport (\n\t_ "net/http/pprof"\n\t. "math"\n)\n`;
+
+			assert.deepStrictEqual(findings(source), []);
+		});
+
+		test('Should assume the package name goimports would', () => {
+			assert.strictEqual(assumedPackageName('gopkg.in/yaml.v3'), 'yaml');
+			assert.strictEqual(assumedPackageName('github.com/go-chi/chi/v5'), 'chi');
+			assert.strictEqual(assumedPackageName('github.com/mattn/go-sqlite3'), 'sqlite3');
+
+			const [unused] = analyzer.analyzeFile(`package main\n\nimport "gopkg.in/yaml.v3"\n`);
+			assert.strictEqual(unused.confidence, 'low');
+			assert.strictEqual(unused.confidenceReason, 'package-name-guessed');
+		});
+
+		test('Should report aliases identical to the package name', () => {
+			const source = `package main\n\nimport (\n\tfmt "fmt"\n\tyaml "gopkg.in/yaml.v3"\n\tio "os"\n)\n\nvar _ = fmt.Sprint(yaml.Marshal, io.Args)\n`;
+			const [redundant] = analyzer.analyzeFile(source);
+
+			assert.deepStrictEqual(findings(source), [['redundant-alias', 'fmt']]);
+			assert.deepStrictEqual(redundant.span, { start: source.indexOf('fmt "'), end: source.indexOf('fmt "') + 3 });
+		});
+	});
+
+	suite('Usage detection', () => {
+		test('Should only count package selectors outside strings and comments', () => {
+			const source = 'package main\n\nimport (\n\t"fmt"\n\t"os"\n\t"time"\n)\n\n// os.Exit(1)\nfunc main() {\n\tfmt.Println("time.Now()", `os.Args`, cfg.time.Second)\n}\n';
+
+			assert.deepStrictEqual(findings(source), [['unused-import', 'os'], ['unused-import', 'time']]);
+		});
+
+		test('Should analyze the sample file', () => {
+			const sample = fs.readFileSync(path.join(__dirname, '../../../test-samples/test.go'), 'utf8');
+
+			assert.deepStrictEqual(findings(sample), [['unused-import', 'time'], ['redundant-alias', 'log']]);
+		});
+	});
+
+	suite('Build constraints', () => {
+		test('Should record the go:build constraint in findings', () => {
+			const sample = fs.readFileSync(path.join(__dirname, '../../../test-samples/build_constraints.go'), 'utf8');
+			const [unused] = analyzer.analyzeFile(sample);
+
+			assert.strictEqual(unused.importPath, 'os');
+			assert.strictEqual(unused.buildConstraint, 'linux && !race');
+			assert.match(unused.reason, /file built only when `linux && !race`/);
+		});
+
+		test('Should convert legacy +build lines', () => {
+			const constraintOf = (header: string) => analyzer.analyzeFile(`${header}\n\npackage p\n\nimport "os"\n`)[0].buildConstraint;
+
+			assert.strictEqual(constraintOf('// +build linux darwin'), 'linux || darwin');
+			assert.strictEqual(constraintOf('// +build linux,386 darwin,!cgo'), '(linux && 386) || (darwin && !cgo)');
+			assert.strictEqual(constraintOf('// +build linux darwin\n// +build amd64'), '(linux || darwin) && amd64');
+			assert.strictEqual(constraintOf('// Package p does things'), undefined);
+		});
+	});
+});
@@ -0,0 +1,106 @@
+import * as assert from 'assert';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { parseArgs } from '../../src/cli/ArgumentParser';
+import { buildImportGraph, formatDot, formatGraphML, GraphOptions, ImportGraph } from '../../src/cli/ImportGraph';
+
+suite('Import Graph Tests', () => {
+	let root: string;
+
+	setup(() => {
+		root = fs.mkdtempSync(path.join(os.tmpdir(), 'importlens-graph-'));
+	});
+
+	teardown(() => {
+		fs.rmSync(root, { recursive: true, force: true });
+	});
+
+	function write(files: Record<string, string>): string[] {
+		return Object.entries(files).map(([name, content]) => {
+			const filePath = path.join(root, name);
+			fs.mkdirSync(path.dirname(filePath), { recursive: true });
+			fs.writeFileSync(filePath, content);
+			return filePath;
+		});
+	}
+
+	function graphOf(files: Record<string, string>, options: GraphOptions = {}): ImportGraph {
+		return buildImportGraph(write(files).filter(file => file.endsWith('.rs')), { cwd: root, ...options });
+	}
+
+	// Helper: edges as `from -> to [items]`, cycle edges marked with `!`
+	function edges(graph: ImportGraph): string[] {
+		return graph.edges.map(e => `${e.from} -> ${e.to} [${e.items.join(', ')}]${e.cycle ? '!' : ''}`);
+	}
+
+	const app = {
+		'Cargo.toml': '[package]\nname = "app"\n',
+		'src/lib.rs': 'mod services;\nmod model;\nuse std::collections::HashMap;\nuse serde::{de::Deserialize, Serialize};\n',
+		'src/services/mod.rs': 'pub mod users;\nuse crate::model::{User, Group};\n',
+		'src/services/users.rs': 'use super::super::model::*;\nuse crate::model;\n',
+		'src/model.rs': 'use crate::services::users::find;\npub struct User;\n',
+	};
+
+	test('Should add an edge per importing and imported module, with the items as labels', () => {
+		const graph = graphOf(app);
+
+		assert.deepStrictEqual(graph.nodes.map(n => n.label), ['crate', 'crate::model', 'crate::services', 'crate::services::users', 'serde', 'std']);
+		assert.deepStrictEqual(edges(graph), [
+			'app -> serde [Serialize, de::Deserialize]',
+			'app -> std [collections::HashMap]',
+			'app::model -> app::services::users [find]!',
+			'app::services -> app::model [Group, User]',
+			'app::services::users -> app::model [*, self]!',
+		]);
+		assert.strictEqual(graph.nodes.find(n => n.id === 'app::services')!.file, 'src/services/mod.rs');
+		assert.ok(graph.nodes.find(n => n.id === 'std')!.external);
+	});
+
+	test('Should collapse modules to crates and link workspace crates', () => {
+		const graph = graphOf({
+			'core/Cargo.toml': '[package]\nname = "my-core"\n',
+			'core/src/lib.rs': 'pub mod db;\n',
+			'core/src/db.rs': 'use anyhow::Result;\n',
+			'cli/Cargo.toml': '[package]\nname = "cli"\n',
+			'cli/src/main.rs': 'use my_core::db::connect;\nextern crate my_core;\n',
+		}, { granularity: 'crates' });
+
+		assert.deepStrictEqual(graph.nodes.map(n => `${n.id}${n.external ? ' (external)' : ''}`), ['anyhow (external)', 'cli', 'my-core']);
+		assert.deepStrictEqual(edges(graph), ['cli -> my-core [db::connect, self]', 'my-core -> anyhow [Result]']);
+	});
+
+	test('Should keep the subgraph matching a filter, with cycles found on the whole graph', () => {
+		const graph = graphOf(app, { filter: 'crate::services::*' });
+
+		assert.deepStrictEqual(graph.nodes.map(n => n.label), ['crate::services::users']);
+		assert.deepStrictEqual(edges(graph), []);
+		assert.ok(graph.nodes[0].cycle);
+
+		const withModel = graphOf(app, { filter: 'crate::*' });
+		assert.deepStrictEqual(edges(withModel).filter(e => e.endsWith('!')), [
+			'app::model -> app::services::users [find]!',
+			'app::services::users -> app::model [*, self]!',
+		]);
+	});
+
+	test('Should write DOT with cycles in red and GraphML for yEd', () => {
+		const graph = graphOf(app);
+
+		const dot = formatDot(graph);
+		assert.ok(dot.startsWith('digraph imports {'));
+		assert.match(dot, /subgraph cluster_0 \{\n {4}label="app";/);
+		assert.match(dot, / {2}"std" \[label="std", style=dashed\];/);
+		assert.match(dot, /"app::model" -> "app::services::users" \[label="find", color=red, fontcolor=red, penwidth=2\];/);
+		assert.match(dot, /"app::services" -> "app::model" \[label="Group, User"\];/);
+
+		const graphml = formatGraphML(graph);
+		assert.match(graphml, /<graph id="imports" edgedefault="directed">/);
+		assert.match(graphml, /<node id="n1"><data key="label">crate::model<\/data><data key="crate">app<\/data><data key="file">src\/model\.rs<\/data>/);
+		assert.match(graphml, /<data key="items">\*, self<\/data><data key="edgeCycle">true<\/data>/);
+
+		assert.strictEqual(parseArgs(['graph', '--format', 'graphml', '.']).graphFormat, 'graphml');
+		assert.throws(() => parseArgs(['graph', '--format', 'json', '.']), /Invalid graph format: json/);
+		assert.throws(() => parseArgs(['--collapse', 'crates', '.']), /can only be used with graph/);
+	});
+});
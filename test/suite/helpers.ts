@@ -0,0 +1,14 @@
+import * as fs from 'fs';
+import * as path from 'path';
+
+/**
+ * Create `count` Rust files in `root`, each with one unused import, named so they
+ * sort in creation order: `file00.rs`, `file01.rs`, ...
+ */
+export function writeRustFiles(root: string, count: number): string[] {
+	return Array.from({ length: count }, (_, i) => {
+		const filePath = path.join(root, `file${String(i).padStart(2, '0')}.rs`);
+		fs.writeFileSync(filePath, `use std::fs::File;\nuse std::io::Write;\nfn f${i}() { Write::flush; }\n`);
+		return filePath;
+	});
+}
@@ -0,0 +1,93 @@
+import * as assert from 'assert';
+import { execFileSync } from 'child_process';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { parseArgs } from '../../src/cli/ArgumentParser';
+import { CLIAnalyzer } from '../../src/cli/CLIAnalyzer';
+import { gitRoot, installHook, restageFix, stagedContent, stagedFiles } from '../../src/cli/GitHook';
+
+suite('Git Hook Tests', () => {
+	let root: string;
+
+	setup(() => {
+		root = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'importlens-hook-')));
+		git('init', '-q');
+	});
+
+	teardown(() => {
+		fs.rmSync(root, { recursive: true, force: true });
+	});
+
+	function git(...args: string[]): string {
+		return execFileSync('git', ['-c', 'user.name=t', '-c', 'user.email=t@t', ...args], { cwd: root, encoding: 'utf-8', stdio: 'pipe' });
+	}
+
+	function write(name: string, content: string): void {
+		fs.mkdirSync(path.dirname(path.join(root, name)), { recursive: true });
+		fs.writeFileSync(path.join(root, name), content);
+	}
+
+	test('Should analyze the staged content of partially staged files', () => {
+		write('src/lib.rs', 'use std::fs::File;\n\npub fn f() { File::open("x"); }\n');
+		write('gone.rs', 'fn main() {}\n');
+		git('add', '.');
+		git('commit', '-qm', 'base');
+
+		// Staged: an unused import on line 2; unstaged: a use of it and a line above
+		write('src/lib.rs', 'use std::fs::File;\nuse std::io::Read;\n\npub fn f() { File::open("x"); }\n');
+		git('add', 'src/lib.rs');
+		git('rm', '-q', 'gone.rs');
+		write('src/lib.rs', '// unstaged\nuse std::fs::File;\nuse std::io::Read;\n\npub fn f() { File::open("x").unwrap().read(&mut []); }\n');
+		write('untracked.rs', 'use std::env;\n');
+
+		assert.deepStrictEqual(stagedFiles(root), ['src/lib.rs']);
+		const content = stagedContent(root, 'src/lib.rs');
+		const result = new CLIAnalyzer(parseArgs(['--no-cache', '.'])).analyzeContent(path.join(root, 'src/lib.rs'), content);
+
+		assert.deepStrictEqual(result.unusedImports.map(u => `${u.line}: ${u.symbols[0]}`), ['2: Read']);
+	});
+
+	test('Should re-stage fixes and apply them to the working tree around unstaged changes', () => {
+		write('lib.rs', 'use std::fs::File;\n\npub fn f() {}\n\n\n\n\npub fn g() {}\n');
+		git('add', '.');
+		git('commit', '-qm', 'base');
+
+		const staged = 'use std::fs::File;\nuse std::io::Read;\n\npub fn f() {}\n\n\n\n\npub fn g() {}\n';
+		write('lib.rs', staged);
+		git('add', 'lib.rs');
+		write('lib.rs', staged.replace('pub fn g() {}', 'pub fn g() { todo!() }'));
+
+		restageFix(root, 'lib.rs', staged, staged.replace('use std::io::Read;\n', ''));
+
+		assert.strictEqual(stagedContent(root, 'lib.rs'), 'use std::fs::File;\n\npub fn f() {}\n\n\n\n\npub fn g() {}\n');
+		assert.strictEqual(fs.readFileSync(path.join(root, 'lib.rs'), 'utf-8'), 'use std::fs::File;\n\npub fn f() {}\n\n\n\n\npub fn g() { todo!() }\n');
+
+		// Unstaged changes on the same lines: nothing is touched
+		write('lib.rs', 'use std::fs::{File, Metadata};\n\npub fn f() {}\n\n\n\n\npub fn g() {}\n');
+		const current = stagedContent(root, 'lib.rs');
+		assert.throws(() => restageFix(root, 'lib.rs', current, current.replace('use std::fs::File;\n', '')), /unstaged changes overlap/);
+		assert.strictEqual(stagedContent(root, 'lib.rs'), current);
+	});
+
+	test('Should install a pre-commit hook without replacing a hook of its own', () => {
+		const hookPath = installHook(root);
+
+		assert.strictEqual(hookPath, path.join(root, '.git', 'hooks', 'pre-commit'));
+		assert.match(fs.readFileSync(hookPath, 'utf-8'), /^#!\/bin\/sh\n[\s\S]*exec importlens-cli hook --staged\n$/);
+		assert.ok((fs.statSync(hookPath).mode & 0o111) !== 0, 'hook must be executable');
+		assert.strictEqual(installHook(root), hookPath);
+
+		fs.writeFileSync(hookPath, '#!/bin/sh\nmake lint\n');
+		assert.throws(() => installHook(root), /already exists/);
+
+		const outside = fs.mkdtempSync(path.join(os.tmpdir(), 'importlens-nogit-'));
+		try {
+			assert.throws(() => gitRoot(outside), /is not inside a git repository/);
+		} finally {
+			fs.rmSync(outside, { recursive: true, force: true });
+		}
+		assert.throws(() => parseArgs(['hook', '.']), /hook needs --staged or --fix-staged/);
+		assert.throws(() => parseArgs(['--staged', '.']), /Unknown argument: --staged/);
+	});
+});
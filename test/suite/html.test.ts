@@ -0,0 +1,76 @@
+import * as assert from 'assert';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { parseArgs } from '../../src/cli/ArgumentParser';
+import { AnalysisResult, CLIAnalyzer } from '../../src/cli/CLIAnalyzer';
+import { buildHtmlReport } from '../../src/cli/HtmlReport';
+
+suite('HTML Report Tests', () => {
+	let root: string;
+
+	setup(() => {
+		root = fs.mkdtempSync(path.join(os.tmpdir(), 'importlens-html-'));
+	});
+
+	teardown(() => {
+		fs.rmSync(root, { recursive: true, force: true });
+	});
+
+	async function analyze(files: Record<string, string>): Promise<AnalysisResult[]> {
+		const paths = Object.entries(files).map(([name, content]) => {
+			fs.writeFileSync(path.join(root, name), content);
+			return path.join(root, name);
+		});
+		return new CLIAnalyzer(parseArgs(['--no-cache', ...paths])).analyzeFiles(paths);
+	}
+
+	test('Should summarize findings and list files with the most findings first', async () => {
+		const results = await analyze({
+			'a.rs': 'use std::fs::File;\n\nfn main() {}\n',
+			'b.rs': 'use std::fs::File;\nuse std::io::Read;\n\nfn main() {}\n',
+			'c.rs': 'fn main() {}\n',
+		});
+
+		const html = buildHtmlReport(results, root, '1.0.0', new Date('2026-01-02T03:04:05Z'));
+
+		assert.ok(html.startsWith('<!DOCTYPE html>'));
+		assert.ok(!/<script|<link|src=/.test(html), 'report must not load external resources');
+		assert.match(html, /Generated 2026-01-02T03:04:05\.000Z by ImportLens 1\.0\.0/);
+		assert.match(html, /<th>Files analyzed<\/th><td class="count">3<\/td>/);
+		assert.match(html, /<tr><td>unused-import<\/td><td class="count">3<\/td><\/tr>/);
+		assert.ok(html.indexOf('<h3 id="file-1">b.rs') < html.indexOf('<h3 id="file-2">a.rs'));
+		assert.ok(!html.includes('c.rs'));
+	});
+
+	test('Should link every finding to an excerpt with the import highlighted', async () => {
+		const results = await analyze({ 'lib.rs': '// first\nuse std::fs::File;\nuse std::io::Read;\n\nfn main() {\n    let _ = File::open("x");\n}\n' });
+
+		const html = buildHtmlReport(results, root, '1.0.0');
+
+		assert.match(html, /<a href="#file-1-1">Line 3<\/a>/);
+		assert.match(html, /<div class="finding" id="file-1-1">/);
+		assert.match(html, /<span class="line current"><span class="number">3<\/span>use <mark>std::io::Read<\/mark>;<\/span>/);
+		// Two lines of context on each side
+		assert.match(html, /<span class="number">1<\/span>\/\/ first/);
+		assert.match(html, /<span class="number">5<\/span>fn main/);
+		assert.ok(!html.includes('<span class="number">6</span>'));
+	});
+
+	test('Should escape source and paths', () => {
+		const filePath = path.join(root, '<x>.ts');
+		fs.writeFileSync(filePath, 'import { a } from "<b>";\n');
+		const results: AnalysisResult[] = [{
+			filePath,
+			language: 'typescript',
+			unusedImports: [{ line: 1, importStatement: 'import { a } from "<b>";', symbols: ['a'], reason: 'Symbol a not used & <gone>' }],
+		}];
+
+		const html = buildHtmlReport(results, root, '1.0.0');
+
+		assert.ok(html.includes('&lt;x&gt;.ts'));
+		assert.ok(html.includes('Symbol a not used &amp; &lt;gone&gt;'));
+		assert.ok(html.includes('import { a } from &quot;&lt;b&gt;&quot;;'));
+		assert.ok(!html.includes('<b>'));
+	});
+});
@@ -0,0 +1,106 @@
+import * as assert from 'assert';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { parseArgs } from '../../src/cli/ArgumentParser';
+import { AnalysisResult, CLIAnalyzer } from '../../src/cli/CLIAnalyzer';
+import { FixEngine, isFixable } from '../../src/cli/FixEngine';
+import { InteractiveReview, PromptIO } from '../../src/cli/InteractiveReview';
+
+suite('Interactive Fix Tests', () => {
+	let root: string;
+
+	setup(() => {
+		root = fs.mkdtempSync(path.join(os.tmpdir(), 'importlens-interactive-'));
+	});
+
+	teardown(() => {
+		fs.rmSync(root, { recursive: true, force: true });
+	});
+
+	async function analyze(files: Record<string, string>): Promise<AnalysisResult[]> {
+		const paths = Object.entries(files).map(([name, content]) => {
+			fs.writeFileSync(path.join(root, name), content);
+			return path.join(root, name);
+		});
+		return new CLIAnalyzer(parseArgs(['--no-cache', ...paths])).analyzeFiles(paths);
+	}
+
+	// Helper: prompts answered from a script; everything written is kept in `output`
+	function scripted(answers: string[]): PromptIO & { output: string; questions: number } {
+		const io = {
+			output: '',
+			questions: 0,
+			ask: async (question: string) => {
+				io.output += question;
+				io.questions++;
+				return answers.shift();
+			},
+			write: (text: string) => { io.output += text; },
+		};
+		return io;
+	}
+
+	function fixed(result: AnalysisResult): string {
+		return new FixEngine().fixSource(fs.readFileSync(result.filePath, 'utf-8'), result).fixed;
+	}
+
+	test('Should remove only the accepted imports of a group', async () => {
+		const [result] = await analyze({ 'lib.rs': 'use std::io::{Read, Write, BufRead};\nuse std::fs::File;\n\npub fn f() {}\n' });
+		const io = scripted(['n', 'y', 'maybe', 'n', 'y']);
+
+		const [reviewed] = await new InteractiveReview(io).review([result], (_, finding) => isFixable(finding));
+
+		assert.strictEqual(io.questions, 5);
+		assert.deepStrictEqual(reviewed.unusedImports.map(u => u.symbols[0]), ['Write', 'File']);
+		assert.strictEqual(fixed(reviewed), 'use std::io::{Read, BufRead};\n\npub fn f() {}\n');
+		// The excerpt numbers lines and underlines the import; unknown answers print the help
+		assert.match(io.output, /> 1 \| use std::io::\{Read, Write, BufRead\};\n {26}\^{5}\n/);
+		assert.match(io.output, /\(2\/4\) Remove this import \[y,n,a,k,s,q,\?\]\? /);
+		assert.match(io.output, /k - remove this import and every later one of the same kind/);
+	});
+
+	test('Should line the carets up under wide characters and emoji', async () => {
+		const [result] = await analyze({ 'lib.rs': 'pub struct 値; /* 🦀 */ use std::io::Read;\n' });
+		const io = scripted(['n']);
+
+		await new InteractiveReview(io).review([result], (_, finding) => isFixable(finding));
+
+		// `値` and `🦀` take two columns each in a terminal
+		assert.match(io.output, /> 1 \| pub struct 値; \/\* 🦀 \*\/ use std::io::Read;\n {34}\^{13}\n/u);
+	});
+
+	test('Should accept the rest of a file, or of a kind, without asking again', async () => {
+		const results = await analyze({
+			'a.rs': 'use std::fs::File;\nuse std::io::Read;\n\npub fn f() {}\n',
+			'b.rs': 'use std::fs::File;\nuse std::fs::File;\n\npub fn f() { File::open("x"); }\n',
+			'c.rs': 'use std::env;\nuse std::fmt;\n\npub fn f() {}\n',
+		});
+		const io = scripted(['a', 'n', 'k']);
+
+		const reviewed = await new InteractiveReview(io).review(results, (_, finding) => isFixable(finding));
+
+		assert.strictEqual(io.questions, 3);
+		assert.deepStrictEqual(reviewed.map(r => r.unusedImports.length), [2, 0, 2]);
+		assert.strictEqual(fixed(reviewed[2]), '\npub fn f() {}\n');
+	});
+
+	test('Should stop at q or the end of input, keeping earlier answers', async () => {
+		const results = await analyze({
+			'a.rs': 'use std::fs::File;\nuse std::io::Read;\n\npub fn f() {}\n',
+			'b.rs': 'use std::env;\n\npub fn f() {}\n',
+		});
+
+		const quit = await new InteractiveReview(scripted(['y', 'q'])).review(results, (_, finding) => isFixable(finding));
+		assert.deepStrictEqual(quit.map(r => r.unusedImports.map(u => u.symbols[0])), [['File'], []]);
+
+		const skipped = await new InteractiveReview(scripted(['s', 'y'])).review(results, (_, finding) => isFixable(finding));
+		assert.deepStrictEqual(skipped.map(r => r.unusedImports.map(u => u.symbols[0])), [[], ['env']]);
+
+		const ended = await new InteractiveReview(scripted(['y'])).review(results, (_, finding) => isFixable(finding));
+		assert.deepStrictEqual(ended.map(r => r.unusedImports.length), [1, 0]);
+
+		assert.throws(() => parseArgs(['--interactive', '.']), /--interactive can only be used with --fix/);
+		assert.throws(() => parseArgs(['--fix', '--interactive', '--files-from', '-']), /cannot be used with --stdin, --files-from -/);
+	});
+});
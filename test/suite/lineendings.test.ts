@@ -0,0 +1,98 @@
+import * as assert from 'assert';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { parseArgs } from '../../src/cli/ArgumentParser';
+import { AnalysisResult, CLIAnalyzer } from '../../src/cli/CLIAnalyzer';
+import { FixEngine } from '../../src/cli/FixEngine';
+import { LineIndex } from '../../src/cli/LineIndex';
+import { displayPath, toPosixPath } from '../../src/cli/Paths';
+import { buildReport } from '../../src/cli/Report';
+import { buildSarif } from '../../src/cli/Sarif';
+
+suite('Line Ending Tests', () => {
+	// Checked in with -text, so git keeps their CRLFs and BOM
+	const fixtures = path.join(__dirname, '../../../test/fixtures/line-endings');
+
+	let root: string;
+
+	setup(() => {
+		root = fs.mkdtempSync(path.join(os.tmpdir(), 'importlens-eol-'));
+		for (const name of fs.readdirSync(fixtures)) fs.copyFileSync(path.join(fixtures, name), path.join(root, name));
+	});
+
+	teardown(() => {
+		fs.rmSync(root, { recursive: true, force: true });
+	});
+
+	async function analyze(name: string): Promise<AnalysisResult> {
+		const [result] = await new CLIAnalyzer(parseArgs(['--no-cache', root])).analyzeFiles([path.join(root, name)]);
+		return result;
+	}
+
+	// Helper: line, UTF-8 byte column and name of every finding
+	function positions(result: AnalysisResult): Array<[number, number | undefined, string]> {
+		return result.unusedImports.map(u => [u.line, u.column, u.symbols[0]]);
+	}
+
+	test('Should count CRLF as one line terminator and report columns in UTF-8 bytes', async () => {
+		const rust = await analyze('crlf.rs');
+		assert.ok(fs.readFileSync(path.join(root, 'crlf.rs'), 'utf-8').includes('\r\n'), 'fixture lost its CRLFs');
+
+		// `size` has a 5-character, 7-byte word before its import
+		assert.deepStrictEqual(positions(rust), [[1, 5, 'HashMap'], [2, 15, 'fs'], [7, 44, 'mem']]);
+		assert.deepStrictEqual(positions(await analyze('crlf.py')), [[1, 8, 'os'], [2, 13, 'json']]);
+		assert.deepStrictEqual(positions(await analyze('crlf.go')), [[5, 2, 'os']]);
+
+		const content = fs.readFileSync(path.join(root, 'crlf.rs'), 'utf-8');
+		const index = new LineIndex(content);
+		const mem = rust.unusedImports[2];
+		assert.deepStrictEqual(index.utf16PositionAt(mem.span!.start), { line: 7, column: 42 });
+		assert.strictEqual(index.offsetAt(7, 44), mem.span!.start);
+		assert.deepStrictEqual(mem.byteSpan, { start: Buffer.byteLength(content.slice(0, mem.span!.start)), end: Buffer.byteLength(content.slice(0, mem.span!.end)) });
+
+		// SARIF columns count UTF-16 code units
+		const region = buildSarif([rust], root).runs[0].results[2].locations[0].physicalLocation.region;
+		assert.deepStrictEqual([region.startLine, region.startColumn, region.endColumn], [7, 42, 50]);
+	});
+
+	test('Should keep CRLF line endings when fixing', async () => {
+		const fix = new FixEngine().fixFile(await analyze('crlf.rs'), false);
+
+		assert.strictEqual(fix.error, undefined);
+		assert.strictEqual(
+			fs.readFileSync(path.join(root, 'crlf.rs'), 'utf-8'),
+			'use std::fs::File;\r\n\r\n/// Größe in Bytes\r\npub fn open() -> File { File::open("a").unwrap() }\r\n\r\npub fn size() -> usize { /* größe */ 0 }\r\n'
+		);
+	});
+
+	test('Should keep a BOM out of columns and in fixed files', async () => {
+		const result = await analyze('bom.rs');
+
+		assert.deepStrictEqual(positions(result), [[1, 5, 'Read']]);
+		assert.deepStrictEqual(result.unusedImports[0].byteSpan, { start: 7, end: 20 });
+
+		new FixEngine().fixFile(result, false);
+		assert.deepStrictEqual(
+			fs.readFileSync(path.join(root, 'bom.rs')),
+			Buffer.from('\uFEFFuse std::fs::File;\r\n\r\npub fn open() -> File { File::open("a").unwrap() }\r\n', 'utf-8')
+		);
+
+		// Python and Go allow a BOM before the first statement
+		const analyzer = new CLIAnalyzer(parseArgs(['--no-cache', root]));
+		assert.deepStrictEqual(positions(analyzer.analyzeContent(path.join(root, 'bom.py'), '\uFEFFimport os\r\n')), [[1, 8, 'os']]);
+		assert.deepStrictEqual(positions(analyzer.analyzeContent(path.join(root, 'bom.go'), '\uFEFFpackage main\r\n\r\nimport "os"\r\n')), [[3, 8, 'os']]);
+	});
+
+	test('Should name files with / separators whatever the platform', async () => {
+		assert.strictEqual(displayPath('C:\\work\\src\\lib.rs', 'C:\\work', path.win32), 'src/lib.rs');
+		assert.strictEqual(displayPath('src\\util\\mod.rs', 'C:\\work', path.win32), 'src/util/mod.rs');
+		assert.strictEqual(toPosixPath('C:\\work\\lib.rs', path.win32), 'C:/work/lib.rs');
+		// On POSIX a backslash is part of the file name
+		assert.strictEqual(toPosixPath('odd\\name.rs', path.posix), 'odd\\name.rs');
+
+		const report = buildReport([await analyze('crlf.rs')], root);
+		assert.strictEqual(report.findings[0].file, 'crlf.rs');
+		assert.strictEqual(report.results[0].filePath, toPosixPath(path.join(root, 'crlf.rs')));
+	});
+});
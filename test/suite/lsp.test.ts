@@ -0,0 +1,123 @@
+import * as assert from 'assert';
+import { pathToFileURL } from 'url';
+import * as os from 'os';
+import * as path from 'path';
+import { Writable } from 'stream';
+import { parseArgs } from '../../src/cli/ArgumentParser';
+import { LspMessage, LspServer, Range } from '../../src/cli/LspServer';
+
+suite('LSP Server Tests', () => {
+	// Never written to disk: the server must analyze the in-memory text
+	const uri = pathToFileURL(path.join(os.tmpdir(), 'importlens-lsp-unsaved', 'lib.rs')).href;
+
+	let server: LspServer;
+	let received: LspMessage[];
+	let exitCode: number | undefined;
+
+	setup(() => {
+		received = [];
+		exitCode = undefined;
+		let pending = '';
+		const output = new Writable({
+			write(chunk: Buffer, _encoding, callback) {
+				pending += chunk.toString('utf8');
+				let match: RegExpExecArray | null;
+				while ((match = /^Content-Length: (\d+)\r\n\r\n/.exec(pending))) {
+					const start = match[0].length;
+					const body = Buffer.from(pending.slice(start), 'utf8').subarray(0, Number(match[1])).toString('utf8');
+					received.push(JSON.parse(body));
+					pending = pending.slice(start + body.length);
+				}
+				callback();
+			},
+		});
+		server = new LspServer(parseArgs(['lsp', '--stdio']), output, code => { exitCode = code; });
+	});
+
+	function frame(message: object): Buffer {
+		const body = JSON.stringify({ jsonrpc: '2.0', ...message });
+		return Buffer.from(`Content-Length: ${Buffer.byteLength(body, 'utf8')}\r\n\r\n${body}`, 'utf8');
+	}
+
+	function send(message: object): void {
+		server.receive(frame(message));
+	}
+
+	function open(text: string): void {
+		send({ id: 1, method: 'initialize', params: { capabilities: {} } });
+		send({ method: 'initialized', params: {} });
+		send({ method: 'textDocument/didOpen', params: { textDocument: { uri, languageId: 'rust', version: 1, text } } });
+	}
+
+	function published(): Array<{ message: string; range: Range }>[] {
+		return received.filter(m => m.method === 'textDocument/publishDiagnostics').map(m => m.params.diagnostics);
+	}
+
+	// Apply LSP text edits, last one first so earlier positions stay valid
+	function applyEdits(text: string, edits: Array<{ range: Range; newText: string }>): string {
+		const lines = text.split('\n');
+		const offset = (line: number, character: number) => lines.slice(0, line).reduce((sum, l) => sum + l.length + 1, 0) + character;
+		return [...edits]
+			.sort((a, b) => offset(b.range.start.line, b.range.start.character) - offset(a.range.start.line, a.range.start.character))
+			.reduce((result, edit) => result.slice(0, offset(edit.range.start.line, edit.range.start.character)) +
+				edit.newText + result.slice(offset(edit.range.end.line, edit.range.end.character)), text);
+	}
+
+	test('Should answer initialize and publish diagnostics with ranges for the open text', () => {
+		open('use std::fs::File;\nuse std::io::Read;\n\nfn main() { File::open("x"); }\n');
+
+		const initialize = received.find(m => m.id === 1)!.result as any;
+		assert.deepStrictEqual(initialize.capabilities.codeActionProvider, { codeActionKinds: ['quickfix'] });
+		assert.strictEqual(initialize.capabilities.textDocumentSync.change, 1);
+
+		const [diagnostics] = published();
+		assert.deepStrictEqual(diagnostics.map(d => [d.message, d.range]), [
+			['Symbol Read (std::io::Read) not used in code', { start: { line: 1, character: 4 }, end: { line: 1, character: 17 } }],
+		]);
+	});
+
+	test('Should offer a quick fix that removes the import, in UTF-16 positions', () => {
+		const text = 'fn greet() -> &\'static str { "héllo 👋" }\nuse std::io::{Read, Write};\n\nfn main() { std::io::stdout().flush().ok(); let _: &dyn Write; }\n';
+		open(text);
+
+		send({
+			id: 2,
+			method: 'textDocument/codeAction',
+			params: { textDocument: { uri }, range: { start: { line: 1, character: 15 }, end: { line: 1, character: 15 } }, context: { diagnostics: [] } },
+		});
+
+		const actions = received.find(m => m.id === 2)!.result as any[];
+		assert.deepStrictEqual(actions.map(a => [a.title, a.kind]), [['Remove unused import `std::io::Read`', 'quickfix']]);
+		assert.strictEqual(
+			applyEdits(text, actions[0].edit.changes[uri]),
+			text.replace('use std::io::{Read, Write};', 'use std::io::Write;')
+		);
+	});
+
+	test('Should keep the last good diagnostics while the text does not parse', () => {
+		open('use std::io::Read;\n');
+		assert.strictEqual(published().length, 1);
+
+		// Split across chunks, as a client may write it
+		const change = frame({ method: 'textDocument/didChange', params: { textDocument: { uri, version: 2 }, contentChanges: [{ text: 'use std::io::{Read, \n' }] } });
+		server.receive(change.subarray(0, 10));
+		server.receive(change.subarray(10));
+		assert.strictEqual(published().length, 1);
+
+		send({ method: 'textDocument/didChange', params: { textDocument: { uri, version: 3 }, contentChanges: [{ text: 'use std::io::{Read, Write};\n' }] } });
+		assert.deepStrictEqual(published().map(d => d.length), [1, 2]);
+
+		send({ method: 'textDocument/didClose', params: { textDocument: { uri } } });
+		assert.deepStrictEqual(published()[2], []);
+	});
+
+	test('Should reject requests before initialize and exit cleanly after shutdown', () => {
+		send({ id: 1, method: 'textDocument/codeAction', params: {} });
+		assert.strictEqual(received[0].error!.code, -32002);
+
+		send({ id: 2, method: 'initialize', params: {} });
+		send({ id: 3, method: 'shutdown' });
+		send({ method: 'exit' });
+		assert.strictEqual(exitCode, 0);
+	});
+});
@@ -0,0 +1,63 @@
+import * as assert from 'assert';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { parseArgs } from '../../src/cli/ArgumentParser';
+import { CLIAnalyzer } from '../../src/cli/CLIAnalyzer';
+import { WorkerPool } from '../../src/cli/WorkerPool';
+import { writeRustFiles } from './helpers';
+
+suite('Parallel Analysis Tests', () => {
+	let root: string;
+
+	setup(() => {
+		root = fs.mkdtempSync(path.join(os.tmpdir(), 'importlens-parallel-'));
+	});
+
+	teardown(() => {
+		fs.rmSync(root, { recursive: true, force: true });
+	});
+
+	test('Should return the same results in input order for any job count', async () => {
+		const files = writeRustFiles(root, 12).reverse();
+
+		const serial = await new CLIAnalyzer(parseArgs(['--jobs=1', '--no-cache', root])).analyzeFiles(files);
+		const parallel = await new CLIAnalyzer(parseArgs(['--jobs=4', '--no-cache', root])).analyzeFiles(files);
+
+		assert.deepStrictEqual(serial.map(r => r.filePath), files);
+		assert.deepStrictEqual(parallel, serial);
+	});
+
+	test('Should report a crashing file as an error and keep analyzing the rest', async () => {
+		// Stand-in worker that dies on one file and answers the others
+		const workerPath = path.join(root, 'worker.js');
+		fs.writeFileSync(workerPath, `
+const { parentPort } = require('worker_threads');
+parentPort.on('message', ({ index, filePath }) => {
+	if (filePath.includes('crash')) process.exit(3);
+	parentPort.postMessage({ index, result: { filePath, language: 'rust', unusedImports: [] } });
+});
+parentPort.postMessage({ ready: true });
+`);
+		const files = ['a.rs', 'crash.rs', 'b.rs', 'c.rs', 'd.rs'];
+
+		const results = await new WorkerPool(workerPath, parseArgs([]), 2).run(files);
+
+		assert.deepStrictEqual(results.map(r => r.filePath), files);
+		assert.strictEqual(results[1].error, 'Analysis crashed: worker exited with code 3');
+		assert.ok(results.filter(r => r !== results[1]).every(r => r.error === undefined));
+	});
+
+	test('Should reject when workers cannot start', async () => {
+		const pool = new WorkerPool(path.join(root, 'missing.js'), parseArgs([]), 2);
+
+		await assert.rejects(pool.run(['a.rs']));
+	});
+
+	test('Should validate --jobs', () => {
+		assert.strictEqual(parseArgs(['--jobs=8']).jobs, 8);
+		assert.strictEqual(parseArgs(['-j', '2']).jobs, 2);
+		assert.throws(() => parseArgs(['--jobs=0']), /Invalid job count: 0/);
+		assert.throws(() => parseArgs(['--jobs', 'many']), /Invalid job count: many/);
+	});
+});
@@ -0,0 +1,167 @@
+import * as assert from 'assert';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { AnalysisCache, configHash } from '../../src/cli/AnalysisCache';
+import { Analyzer, ImportLensError } from '../../src/cli/Analyzer';
+import { parseArgs } from '../../src/cli/ArgumentParser';
+import { CLIAnalyzer } from '../../src/cli/CLIAnalyzer';
+import { writeFileAtomic } from '../../src/cli/FixEngine';
+import { CancellationToken, ProgressBar, ProgressEvent } from '../../src/cli/Progress';
+import { WorkerPool } from '../../src/cli/WorkerPool';
+import { writeRustFiles } from './helpers';
+
+suite('Progress and Cancellation Tests', () => {
+	let root: string;
+
+	setup(() => {
+		root = fs.mkdtempSync(path.join(os.tmpdir(), 'importlens-progress-'));
+	});
+
+	teardown(() => {
+		fs.rmSync(root, { recursive: true, force: true });
+	});
+
+	// Helper: the kind of the error `promise` rejects with
+	async function rejection(promise: Promise<unknown>): Promise<[string, string]> {
+		try {
+			await promise;
+		} catch (error) {
+			assert.ok(error instanceof ImportLensError);
+			return [error.kind, error.message];
+		}
+		assert.fail('expected a rejection');
+	}
+
+	test('Should report progress after each file, cache hits first', async () => {
+		const files = writeRustFiles(root, 3);
+		const args = parseArgs(['--jobs=1', `--cache-dir=${path.join(root, 'cache')}`, root]);
+		const events: ProgressEvent[] = [];
+
+		await new CLIAnalyzer(args).analyzeFiles(files, { onProgress: event => events.push(event) });
+		assert.deepStrictEqual(events, files.map((filePath, i) => ({ done: i + 1, total: 3, filePath })));
+
+		events.length = 0;
+		fs.appendFileSync(files[1], '// changed\n');
+		await new CLIAnalyzer(args).analyzeFiles(files, { onProgress: event => events.push(event) });
+		assert.deepStrictEqual(events, [{ done: 2, total: 3 }, { done: 3, total: 3, filePath: files[1] }]);
+	});
+
+	test('Should stop between files once cancelled, keeping the finished files cached', async () => {
+		const files = writeRustFiles(root, 4);
+		const args = parseArgs(['--jobs=1', `--cache-dir=${path.join(root, 'cache')}`, root]);
+		const cancellation = new CancellationToken();
+		const analyzed: string[] = [];
+
+		const run = new CLIAnalyzer(args).analyzeFiles(files, {
+			cancellation,
+			onProgress: event => {
+				analyzed.push(event.filePath ?? '');
+				if (event.done === 2) cancellation.cancel();
+			},
+		});
+		assert.deepStrictEqual(await rejection(run), ['cancelled', 'Analysis cancelled after 2 of 4 files']);
+		assert.deepStrictEqual(analyzed, files.slice(0, 2));
+		assert.strictEqual(new AnalysisCache(path.join(root, 'cache'), configHash(args)).size, 2);
+
+		// Listeners run once, at once when added after the cancellation
+		let calls = 0;
+		const token = new CancellationToken();
+		const stop = token.onCancel(() => calls++);
+		token.cancel();
+		token.cancel();
+		token.onCancel(() => calls++);
+		stop();
+		assert.strictEqual(calls, 2);
+	});
+
+	test('Should terminate workers on cancellation and resolve with the finished files', async () => {
+		// Stand-in worker that answers every file
+		const workerPath = path.join(root, 'worker.js');
+		fs.writeFileSync(workerPath, `
+const { parentPort } = require('worker_threads');
+parentPort.on('message', ({ index, filePath }) => {
+	parentPort.postMessage({ index, result: { filePath, language: 'rust', unusedImports: [] } });
+});
+parentPort.postMessage({ ready: true });
+`);
+		const files = ['a.rs', 'b.rs', 'c.rs', 'd.rs', 'e.rs', 'f.rs'];
+		const cancellation = new CancellationToken();
+		const finished: string[] = [];
+
+		const results = await new WorkerPool(workerPath, parseArgs([]), 2).run(files, result => {
+			finished.push(result.filePath);
+			cancellation.cancel();
+		}, cancellation);
+
+		assert.strictEqual(finished.length, 1);
+		assert.deepStrictEqual(results.filter(result => result !== undefined).map(result => result.filePath), finished);
+		// A token cancelled before the run starts no worker
+		assert.strictEqual((await new WorkerPool(path.join(root, 'missing.js'), parseArgs([]), 2).run(files, undefined, cancellation)).length, 6);
+	});
+
+	test('Should analyze paths into one report with progress and cancellation in library mode', async () => {
+		const files = writeRustFiles(root, 2);
+		const analyzer = Analyzer.builder().build();
+		const events: ProgressEvent[] = [];
+
+		const report = await analyzer.analyzePaths(files, { onProgress: event => events.push(event) });
+		assert.deepStrictEqual(report.findings.map(f => [path.basename(f.file), f.line]), [['file00.rs', 1], ['file01.rs', 1]]);
+		assert.deepStrictEqual(events.map(event => event.done), [1, 2]);
+
+		const cancellation = new CancellationToken();
+		const run = analyzer.analyzePaths(files, { cancellation, onProgress: () => cancellation.cancel() });
+		assert.deepStrictEqual(await rejection(run), ['cancelled', 'Analysis cancelled after 1 of 2 files']);
+		assert.deepStrictEqual(await rejection(analyzer.analyzePaths(files, { cancellation })), ['cancelled', 'Analysis cancelled after 0 of 2 files']);
+	});
+
+	test('Should draw a throttled progress bar with an ETA and the current file', () => {
+		const writes: string[] = [];
+		let time = 0;
+		const bar = new ProgressBar({ write: text => writes.push(text), columns: 60 }, () => time, root);
+		const file = (name: string) => path.join(root, name);
+
+		bar.update({ done: 0, total: 4 });
+		time = 50;
+		bar.update({ done: 1, total: 4, filePath: file('a.rs') });
+		time = 1000;
+		bar.update({ done: 2, total: 4, filePath: file('src/b.rs') });
+		time = 1010;
+		bar.update({ done: 4, total: 4, filePath: file('src/c.rs') });
+		bar.clear();
+
+		assert.deepStrictEqual(writes, [
+			'\r[--------------------] 0/4 files\x1b[K',
+			'\r[##########----------] 2/4 files  ETA 1s  src/b.rs\x1b[K',
+			'\r[####################] 4/4 files  src/c.rs\x1b[K',
+			'\r\x1b[K',
+		]);
+
+		// Long paths lose their start to fit the terminal
+		const long = bar.render({ done: 2, total: 4, filePath: file('src/services/billing/invoices/export.rs') }, 1000);
+		assert.strictEqual(long, '[##########----------] 2/4 files  ETA 1s  …voices/export.rs');
+		assert.strictEqual(long.length, 59);
+		assert.strictEqual(bar.render({ done: 1, total: 400 }, 65000), '[--------------------] 1/400 files  ETA 432m15s');
+
+		assert.ok(ProgressBar.forArgs(parseArgs(['.']), { write: () => true, isTTY: true }) instanceof ProgressBar);
+		assert.strictEqual(ProgressBar.forArgs(parseArgs(['.']), { write: () => true, isTTY: false }), undefined);
+		assert.strictEqual(ProgressBar.forArgs(parseArgs(['-q', '.']), { write: () => true, isTTY: true }), undefined);
+		assert.strictEqual(parseArgs(['--quiet', '.']).quiet, true);
+	});
+
+	test('Should replace fixed files whole, keeping their permissions', () => {
+		const filePath = path.join(root, 'main.rs');
+		fs.writeFileSync(filePath, 'use std::fs::File;\nfn main() {}\n');
+		fs.chmodSync(filePath, 0o755);
+
+		writeFileAtomic(filePath, 'fn main() {}\n');
+
+		assert.strictEqual(fs.readFileSync(filePath, 'utf-8'), 'fn main() {}\n');
+		assert.deepStrictEqual(fs.readdirSync(root), ['main.rs']);
+		if (process.platform !== 'win32') {
+			assert.strictEqual(fs.statSync(filePath).mode & 0o777, 0o755);
+		}
+		assert.throws(() => writeFileAtomic(path.join(root, 'missing', 'main.rs'), ''));
+		assert.deepStrictEqual(fs.readdirSync(root), ['main.rs']);
+	});
+});
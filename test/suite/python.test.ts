@@ -0,0 +1,107 @@
+import * as assert from 'assert';
+import { parseArgs } from '../../src/cli/ArgumentParser';
+import { resolveLanguage } from '../../src/cli/Languages';
+import { PythonAnalyzer } from '../../src/cli/PythonAnalyzer';
+
+suite('Python Analyzer Tests', () => {
+	const analyzer = new PythonAnalyzer();
+
+	// Helper: names reported as unused
+	function unusedNames(source: string): string[] {
+		return analyzer.analyzeFile(source).flatMap(u => u.symbols);
+	}
+
+	suite('Import forms', () => {
+		test('Should report unused plain and aliased imports', () => {
+			const source = `import os\nimport numpy as np\nimport sys\n\nprint(sys.argv)\n`;
+
+			assert.deepStrictEqual(unusedNames(source), ['os', 'np']);
+		});
+
+		test('Should bind the top-level package of a dotted import', () => {
+			const source = `import os.path\nimport xml.etree.ElementTree\n\nos.path.join("a", "b")\n`;
+			const findings = analyzer.analyzeFile(source);
+
+			assert.deepStrictEqual(findings.map(f => [f.symbols[0], f.importPath]), [['xml', 'xml.etree.ElementTree']]);
+		});
+
+		test('Should report each unused name of a from-import with its span', () => {
+			const source = `from typing import List, Dict as D, Optional\n\nx: List[int] = []\n`;
+			const findings = analyzer.analyzeFile(source);
+
+			assert.deepStrictEqual(findings.map(f => f.importPath), ['typing.Dict', 'typing.Optional']);
+			assert.deepStrictEqual(findings[0].span, { start: source.indexOf('Dict'), end: source.indexOf('D,') + 1 });
+			assert.strictEqual(findings[0].column, source.indexOf('Dict') + 1);
+			assert.strictEqual(findings[0].kind, 'unused-import');
+		});
+
+		test('Should handle parenthesized multi-line imports', () => {
+			const source = `from .models import (\n    User,\n    Group,  # groups\n)\n\nUser()\n`;
+			const [finding] = analyzer.analyzeFile(source);
+
+			assert.deepStrictEqual(finding.symbols, ['Group']);
+			assert.strictEqual(finding.line, 3);
+			assert.strictEqual(finding.importPath, '.models.Group');
+		});
+
+		test('Should never report star and __future__ imports', () => {
+			const source = `from __future__ import annotations\nfrom os.path import *\n`;
+
+			assert.deepStrictEqual(unusedNames(source), []);
+		});
+	});
+
+	suite('Usage detection', () => {
+		test('Should not count attribute names, strings or comments as uses', () => {
+			const source = `import json\nimport re\n\nconfig.json = "re"  # re\n`;
+
+			assert.deepStrictEqual(unusedNames(source), ['json', 're']);
+		});
+
+		test('Should count names inside f-string fields', () => {
+			const source = `import math\nimport time\n\nprint(f"{math.pi:.{time}f} {{math}}")\n`;
+
+			assert.deepStrictEqual(unusedNames(source), []);
+		});
+
+		test('Should count string annotations and type comments', () => {
+			const source = `from a import Foo\nfrom b import Bar\nfrom c import Baz\n\ndef f(x: "Foo") -> List["Bar"]:\n    y = []  # type: Baz\n`;
+
+			assert.deepStrictEqual(unusedNames(source), []);
+		});
+	});
+
+	suite('Suppressions', () => {
+		test('Should treat names listed in __all__ as used', () => {
+			const source = `from .core import Engine, Helper\nfrom .util import tool\n\n__all__ = ["Engine"]\n__all__ += ["tool"]\n`;
+
+			assert.deepStrictEqual(unusedNames(source), ['Helper']);
+		});
+
+		test('Should honour noqa comments that cover F401', () => {
+			const source = `import os  # noqa\nimport sys  # noqa: F401\nimport re  # noqa: E501\nfrom x import (  # noqa\n    a,\n)\n`;
+
+			assert.deepStrictEqual(unusedNames(source), ['re']);
+		});
+
+		test('Should resolve TYPE_CHECKING imports through type-like strings', () => {
+			const source = `from typing import TYPE_CHECKING\n\nif TYPE_CHECKING:\n    from pathlib import Path\n    from decimal import Decimal\n\nimport os\n\nALIAS = "Path"\n`;
+
+			assert.deepStrictEqual(unusedNames(source), ['Decimal', 'os']);
+		});
+	});
+
+	suite('Language selection', () => {
+		test('Should detect languages from the extension unless overridden', () => {
+			assert.strictEqual(resolveLanguage('tool.py'), 'python');
+			assert.strictEqual(resolveLanguage('bin/tool', 'python'), 'python');
+			assert.strictEqual(resolveLanguage('App.tsx', 'typescript'), 'typescriptreact');
+		});
+
+		test('Should accept language aliases and reject unknown languages', () => {
+			assert.strictEqual(parseArgs(['--language=py', '.']).language, 'python');
+			assert.strictEqual(parseArgs(['--language', 'rust', '.']).language, 'rust');
+			assert.throws(() => parseArgs(['--language=cobol', '.']), /Invalid language: cobol/);
+		});
+	});
+});
@@ -0,0 +1,135 @@
+import * as assert from 'assert';
+import { execFileSync } from 'child_process';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { parseArgs } from '../../src/cli/ArgumentParser';
+import { dirtyFiles } from '../../src/cli/GitHook';
+import { renameModulePath, renameRustPaths } from '../../src/cli/RustRename';
+
+suite('Module Rename Tests', () => {
+	let root: string;
+
+	setup(() => {
+		root = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'importlens-rename-')));
+	});
+
+	teardown(() => {
+		fs.rmSync(root, { recursive: true, force: true });
+	});
+
+	function write(files: Record<string, string>): string[] {
+		return Object.entries(files).map(([name, content]) => {
+			const filePath = path.join(root, name);
+			fs.mkdirSync(path.dirname(filePath), { recursive: true });
+			fs.writeFileSync(filePath, content);
+			return filePath;
+		});
+	}
+
+	// Helper: the renamed content of every file that changes, by name
+	function rename(files: Record<string, string>, from = 'crate::utils::parse', to = 'crate::parsing'): Record<string, string> {
+		const renamed = renameModulePath(write(files).filter(file => file.endsWith('.rs')), from, to);
+		return Object.fromEntries(renamed.map(file => [path.relative(root, file.filePath).split(path.sep).join('/'), file.after]));
+	}
+
+	function renamed(content: string, from = 'crate::utils::parse', to = 'crate::parsing'): string {
+		const { edits } = renameRustPaths(content, from.split('::'), to.split('::'));
+		return [...edits].sort((a, b) => b.start - a.start).reduce((text, e) => text.slice(0, e.start) + e.newText + text.slice(e.end), content);
+	}
+
+	const crate = {
+		'Cargo.toml': '[package]\nname = "app"\n',
+		'src/lib.rs': 'mod utils;\nmod parsing;\nmod cli;\n',
+		'src/utils/mod.rs': 'pub mod parse;\npub mod fmt;\n',
+		'src/utils/parse.rs': 'pub struct Config;\npub fn run() {}\n',
+		'src/utils/fmt.rs': 'use super::parse::Config;\n\npub fn show(_: &Config) { super::parse::run(); }\n',
+		'src/parsing.rs': '',
+	};
+
+	test('Should rewrite imports resolved from every module of the crate', () => {
+		const files = rename({
+			...crate,
+			'src/cli.rs': 'use crate::utils::parse::{run, Config};\nuse crate::utils::fmt;\n\nfn main() { run(); }\n',
+		});
+
+		assert.deepStrictEqual(Object.keys(files).sort(), ['src/cli.rs', 'src/utils/fmt.rs']);
+		assert.strictEqual(files['src/cli.rs'], 'use crate::parsing::{run, Config};\nuse crate::utils::fmt;\n\nfn main() { run(); }\n');
+		assert.strictEqual(files['src/utils/fmt.rs'], 'use crate::parsing::Config;\n\npub fn show(_: &Config) { crate::parsing::run(); }\n');
+	});
+
+	test('Should split a group when only part of it moves', () => {
+		assert.strictEqual(
+			renamed('use crate::utils::{parse::{run, Config}, fmt::show};\n'),
+			'use crate::utils::fmt::show;\nuse crate::parsing::{run, Config};\n'
+		);
+		assert.strictEqual(
+			renamed('    #[cfg(test)]\n    pub(crate) use crate::utils::{parse::run, fmt};\n'),
+			'    #[cfg(test)]\n    pub(crate) use crate::utils::fmt;\n    #[cfg(test)]\n    pub(crate) use crate::parsing::run;\n'
+		);
+		// Every leaf moves: the group is rewritten whole
+		assert.strictEqual(renamed('use crate::utils::{parse::run, parse::Config};\n'), 'use crate::parsing::{run, Config};\n');
+	});
+
+	test('Should edit a group in place when it still leads to the new path', () => {
+		assert.strictEqual(
+			renamed('use crate::utils::{parse::Config, fmt};\n', 'crate::utils::parse', 'crate::utils::parsing'),
+			'use crate::utils::{parsing::Config, fmt};\n'
+		);
+	});
+
+	test('Should keep the name an import of the module binds or re-exports', () => {
+		assert.strictEqual(
+			renamed('pub use crate::utils::parse;\nuse crate::utils::{parse::{self}, fmt};\n\nfn f() { parse::run(); }\n'),
+			'pub use crate::parsing as parse;\nuse crate::utils::fmt;\nuse crate::parsing::{self as parse};\n\nfn f() { parse::run(); }\n'
+		);
+		assert.strictEqual(renamed('use crate::utils::parse as p;\n'), 'use crate::parsing as p;\n');
+		// Same last segment: no alias needed
+		assert.strictEqual(renamed('pub use crate::utils::parse;\n', 'crate::utils::parse', 'crate::text::parse'), 'pub use crate::text::parse;\n');
+	});
+
+	test('Should rewrite written-out paths through imports of their parents', () => {
+		assert.strictEqual(
+			renamed('use crate::utils;\n\nfn f() {\n    utils::parse::run();\n    let _ = crate::utils::parse::Config;\n    ::std::mem::drop(utils::fmt::show);\n}\n'),
+			'use crate::utils;\n\nfn f() {\n    crate::parsing::run();\n    let _ = crate::parsing::Config;\n    ::std::mem::drop(utils::fmt::show);\n}\n'
+		);
+		// `parse_more` and `utils::parser` are other paths
+		const unrelated = 'use crate::utils::parser::Config;\n\nfn f() { crate::utils::parse_more(); }\n';
+		assert.strictEqual(renamed(unrelated), unrelated);
+	});
+
+	test('Should rename paths of another crate', () => {
+		assert.strictEqual(
+			renamed('use ::app::utils::parse::Config;\n\nfn f() { ::app::utils::parse::run(); }\n', 'app::utils::parse', 'app::parsing'),
+			'use ::app::parsing::Config;\n\nfn f() { ::app::parsing::run(); }\n'
+		);
+	});
+
+	test('Should list modified and untracked files of the git repository', () => {
+		write(crate);
+		assert.strictEqual(dirtyFiles(root), undefined);
+
+		const git = (...args: string[]) => execFileSync('git', ['-c', 'user.name=t', '-c', 'user.email=t@t', ...args], { cwd: root, stdio: 'pipe' });
+		git('init', '-q');
+		git('add', '.');
+		git('commit', '-qm', 'base');
+		assert.deepStrictEqual(dirtyFiles(root), []);
+
+		write({ 'src/parsing.rs': 'pub fn new() {}\n', 'src/extra.rs': '' });
+		assert.deepStrictEqual(dirtyFiles(root), ['src/extra.rs', 'src/parsing.rs']);
+	});
+
+	test('Should parse the rename command and validate its paths', () => {
+		const args = parseArgs(['rename', '--from', 'crate::utils::parse', '--to=crate::parsing', '--allow-dirty', 'src']);
+		assert.strictEqual(args.command, 'rename');
+		assert.strictEqual(args.renameFrom, 'crate::utils::parse');
+		assert.strictEqual(args.renameTo, 'crate::parsing');
+		assert.ok(args.allowDirty);
+		assert.deepStrictEqual(args.files, ['src']);
+
+		assert.throws(() => parseArgs(['rename', '--from', 'crate::utils::parse']), /--to/);
+		assert.throws(() => parseArgs(['rename', '--from', 'super::parse', '--to', 'crate::parsing']), /Invalid --from/);
+		assert.throws(() => parseArgs(['rename', '--from', 'crate::a', '--to', 'crate::a']), /same/);
+		assert.throws(() => parseArgs(['--from', 'crate::a', '--to', 'crate::b']), /rename/);
+	});
+});
@@ -0,0 +1,109 @@
+import * as assert from 'assert';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { parseArgs } from '../../src/cli/ArgumentParser';
+import { AnalysisResult, CLIAnalyzer } from '../../src/cli/CLIAnalyzer';
+import { OutputFormatter } from '../../src/cli/OutputFormatter';
+import { buildReport } from '../../src/cli/Report';
+
+suite('JSON Report Tests', () => {
+	const cwd = path.resolve('/project');
+
+	// Helper to create a result with findings at the given byte offsets
+	function resultWith(filePath: string, offsets: number[]): AnalysisResult {
+		return {
+			filePath: path.join(cwd, filePath),
+			language: 'rust',
+			unusedImports: offsets.map(offset => ({
+				line: 1,
+				column: offset + 1,
+				importStatement: 'use x::y;',
+				symbols: ['y'],
+				reason: 'Symbol y (x::y) not used in code',
+				byteSpan: { start: offset, end: offset + 1 },
+				importPath: 'x::y',
+			})),
+		};
+	}
+
+	test('Should sort findings by file, then byte offset', () => {
+		const report = buildReport([resultWith('src/b.rs', [30, 4]), resultWith('src/a.rs', [9])], cwd);
+
+		assert.deepStrictEqual(
+			report.findings.map(f => [f.file, f.byte_span!.start]),
+			[['src/a.rs', 9], ['src/b.rs', 4], ['src/b.rs', 30]]
+		);
+	});
+
+	test('Should summarize counts per kind, severity and file', () => {
+		const report = buildReport([resultWith('src/b.rs', [1, 2]), resultWith('src/a.rs', [3]), resultWith('src/c.rs', [])], cwd);
+
+		assert.strictEqual(report.summary.total_findings, 3);
+		assert.deepStrictEqual(report.summary.by_kind, { 'unused-import': 3, 'duplicate-import': 0, 'redundant-import': 0, 'type-only-import': 0, 'useless-suppression': 0, 'unused-reexport': 0, 'unused-dependency': 0, 'shadowed-import': 0, 'redundant-alias': 0, 'over-qualified-path': 0, 'banned-import': 0, 'unnecessary-import': 0, 'test-only-import': 0 });
+		assert.deepStrictEqual(report.summary.by_severity, { info: 0, warning: 0, error: 3 });
+		assert.deepStrictEqual(report.summary.by_file, { 'src/a.rs': 1, 'src/b.rs': 2 });
+	});
+
+	test('Should fill defaults for findings from analyzers without spans', () => {
+		const report = buildReport([{
+			filePath: path.join(cwd, 'app.py'),
+			language: 'python',
+			unusedImports: [{ line: 2, importStatement: 'import os', symbols: ['os'], reason: 'Module os not used in code' }],
+		}], cwd);
+
+		assert.deepStrictEqual(report.findings[0], {
+			file: 'app.py',
+			line: 2,
+			column: null,
+			byte_span: null,
+			import_path: 'import os',
+			kind: 'unused-import',
+			severity: 'error',
+			confidence: 'high',
+			confidence_reason: 'never-referenced',
+			symbols: ['os'],
+			message: 'Module os not used in code',
+			suggested_fix: null,
+			original: null,
+			build_constraint: null,
+		});
+	});
+
+	test('Should keep the original top-level fields', () => {
+		const output = new OutputFormatter('json').format([resultWith('src/a.rs', [0]), resultWith('src/b.rs', [])]);
+		const parsed = JSON.parse(output);
+
+		assert.strictEqual(parsed.schema_version, '3.0');
+		assert.strictEqual(parsed.totalFiles, 2);
+		assert.strictEqual(parsed.filesWithIssues, 1);
+		assert.strictEqual(parsed.totalUnusedImports, 1);
+		assert.deepStrictEqual(parsed.results.map((r: { unusedImportCount: number }) => r.unusedImportCount), [1, 0]);
+		assert.ok(Array.isArray(parsed.findings));
+	});
+
+	test('Should report UTF-8 byte spans and a standalone fix for Rust findings', async () => {
+		const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'importlens-report-'));
+		const filePath = path.join(dir, 'lib.rs');
+		const source = '// é\nuse std::io::{Read, Write};\nfn f() { Read::read; }\n';
+		fs.writeFileSync(filePath, source);
+
+		try {
+			const analyzer = new CLIAnalyzer(parseArgs(['--format=json', '--no-cache', filePath]));
+			const [result] = await analyzer.analyzeFiles([filePath]);
+			const [finding] = buildReport([result], dir).findings;
+
+			assert.strictEqual(finding.file, 'lib.rs');
+			assert.strictEqual(finding.import_path, 'std::io::Write');
+			// "é" is two bytes in UTF-8, so byte offsets are one past string offsets
+			assert.deepStrictEqual(finding.byte_span, { start: source.indexOf('Write') + 1, end: source.indexOf('Write') + 6 });
+			assert.deepStrictEqual(finding.suggested_fix!.edits, [{
+				start: source.indexOf('{') + 1,
+				end: source.indexOf('}') + 2,
+				new_text: 'Read',
+			}]);
+		} finally {
+			fs.rmSync(dir, { recursive: true, force: true });
+		}
+	});
+});
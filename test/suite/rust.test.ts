@@ -0,0 +1,1283 @@
+import * as assert from 'assert';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { parseArgs } from '../../src/cli/ArgumentParser';
+import { CLIAnalyzer, MAX_STATEMENT_LENGTH, meetsConfidence } from '../../src/cli/CLIAnalyzer';
+import { applyEdits, FixEngine, isFixable } from '../../src/cli/FixEngine';
+import { RustAnalyzer } from '../../src/cli/RustAnalyzer';
+import { RustCrate } from '../../src/cli/RustCrate';
+import { parseDependencies } from '../../src/cli/RustDependencies';
+
+suite('Rust Analyzer Tests', () => {
+	const analyzer = new RustAnalyzer();
+
+	// Helper returning the source text covered by each finding's span
+	function unusedSpans(source: string): string[] {
+		return analyzer.analyzeFile(source).map(u => source.slice(u.span!.start, u.span!.end));
+	}
+
+	suite('Use-tree groups', () => {
+		test('Should report each unused leaf of a group independently', () => {
+			const source = `use std::io::{self, Write, BufReader};
+
+fn main() {
+    let mut out = io::stdout();
+    Write::flush(&mut out).unwrap();
+}
+`;
+			const unused = analyzer.analyzeFile(source);
+
+			assert.strictEqual(unused.length, 1);
+			assert.deepStrictEqual(unused[0].symbols, ['BufReader']);
+			assert.strictEqual(unused[0].line, 1);
+			assert.strictEqual(unused[0].column, 28);
+		});
+
+		test('Should descend into arbitrarily nested groups', () => {
+			const source = `use foo::{bar::{Baz, Qux}, quux};
+
+fn main() {
+    let _ = Baz::new();
+    quux();
+}
+`;
+			assert.deepStrictEqual(unusedSpans(source), ['Qux']);
+		});
+
+		test('Should track renames inside groups by their alias', () => {
+			const source = `use std::collections::{HashMap as Map, BTreeMap as Ordered};
+
+fn main() {
+    let _m: Map<u8, u8> = Map::new();
+}
+`;
+			const unused = analyzer.analyzeFile(source);
+
+			assert.strictEqual(unused.length, 1);
+			assert.deepStrictEqual(unused[0].symbols, ['Ordered']);
+			assert.strictEqual(source.slice(unused[0].span!.start, unused[0].span!.end), 'BTreeMap as Ordered');
+		});
+
+		test('Should resolve self to the parent module name', () => {
+			const used = `use std::io::{self};\nfn main() { io::stdout(); }\n`;
+			const unused = `use std::io::{self};\nfn main() {}\n`;
+
+			assert.strictEqual(analyzer.analyzeFile(used).length, 0);
+			assert.deepStrictEqual(analyzer.analyzeFile(unused)[0].symbols, ['io']);
+		});
+
+		test('Should span multi-line groups precisely', () => {
+			const source = `use std::{
+    fmt,
+    collections::HashMap,
+};
+
+fn main() { let _ = fmt::format; }
+`;
+			const unused = analyzer.analyzeFile(source);
+
+			assert.strictEqual(unused.length, 1);
+			assert.strictEqual(unused[0].line, 3);
+			assert.strictEqual(unused[0].column, 5);
+			assert.strictEqual(source.slice(unused[0].span!.start, unused[0].span!.end), 'collections::HashMap');
+		});
+	});
+
+	suite('Usage detection', () => {
+		test('Should ignore identifiers inside strings and comments', () => {
+			const source = `use std::fs::File;\n// File is mentioned here\nfn main() { println!("File"); }\n`;
+
+			assert.deepStrictEqual(unusedSpans(source), ['std::fs::File']);
+		});
+
+		test('Should not count field access or path continuations as usage', () => {
+			const source = `use std::fs::File;\nfn main() { let _ = config.File; let _ = other::File; }\n`;
+
+			assert.deepStrictEqual(unusedSpans(source), ['std::fs::File']);
+		});
+
+		test('Should never flag glob or anonymous imports', () => {
+			const source = `use std::io::prelude::*;\nuse std::fmt::Write as _;\nfn main() {}\n`;
+
+			assert.strictEqual(analyzer.analyzeFile(source).length, 0);
+		});
+
+		test('Should name both the path and the alias of renamed imports', () => {
+			const used = `use std::collections::HashMap as Map;\nfn main() { let _m: Map<u8, u8> = Map::new(); }\n`;
+			const unused = `use std::collections::HashMap as Map;\nfn main() { let _m = HashMap::new(); }\n`;
+
+			assert.strictEqual(analyzer.analyzeFile(used).length, 0);
+			assert.strictEqual(analyzer.analyzeFile(unused)[0].reason, 'Symbol std::collections::HashMap (as Map) not used in code');
+		});
+
+		test('Should report aliases that repeat the imported name', () => {
+			const source = `use foo::Bar as Bar;\nuse std::io::{self as io, Write as W};\nfn main() { Bar::new(); io::stdout(); W::flush; }\n`;
+			const unused = analyzer.analyzeFile(source);
+
+			assert.deepStrictEqual(unused.map(u => [u.kind, u.symbols[0], u.line, u.column]), [['redundant-alias', 'Bar', 1, 14], ['redundant-alias', 'io', 2, 20]]);
+			assert.strictEqual(source.slice(unused[0].span!.start, unused[0].span!.end), 'as Bar');
+			assert.strictEqual(unused[0].reason, 'Alias Bar for foo::Bar repeats its name');
+		});
+	});
+
+	suite('Macros', () => {
+		const strict = new RustAnalyzer({ strictMacros: true });
+
+		test('Should count identifiers inside macro bodies as uses', () => {
+			const source = `use std::collections::HashMap;
+lazy_static! {
+    static ref M: HashMap<u32, u32> = HashMap::new();
+}
+`;
+			assert.strictEqual(analyzer.analyzeFile(source).length, 0);
+			const [unused] = strict.analyzeFile(source);
+			assert.deepStrictEqual([unused.symbols[0], unused.confidence, unused.confidenceReason], ['HashMap', 'medium', 'only-seen-in-macro']);
+			assert.strictEqual(unused.reason, 'Symbol HashMap (std::collections::HashMap) not used in code outside macro bodies');
+		});
+
+		test('Should cover macro_rules definitions and attribute arguments', () => {
+			const source = `use std::fmt::Display;
+use crate::codec::Hex;
+
+macro_rules! show { ($x:expr) => { <dyn Display>::to_string(&$x) }; }
+
+#[component(codec = Hex)]
+struct Bytes;
+`;
+			assert.strictEqual(analyzer.analyzeFile(source).length, 0);
+			assert.deepStrictEqual(strict.analyzeFile(source).map(u => u.symbols[0]), ['Display', 'Hex']);
+		});
+
+		test('Should treat macro names and derive lists as uses in strict mode', () => {
+			const source = `use log::info;
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct S;
+
+fn main() { info!("started"); }
+`;
+			assert.strictEqual(strict.analyzeFile(source).length, 0);
+		});
+
+		test('Should not take a negation after a keyword for a macro call', () => {
+			const source = `use std::collections::HashMap;
+use std::env;
+
+fn f() -> bool {
+    if !(env::args().count() > 1) { return false; }
+    return !(HashMap::<u32, u32>::new().is_empty());
+}
+`;
+			assert.deepStrictEqual(strict.analyzeFile(source), []);
+		});
+	});
+
+	suite('Duplicate and redundant imports', () => {
+		test('Should report a repeated path as a duplicate of the first import', () => {
+			const source = `use std::fmt;
+use std::io;
+use std::fmt;
+
+fn f(_: &dyn fmt::Debug) { io::stdout(); }
+`;
+			const unused = analyzer.analyzeFile(source);
+
+			assert.strictEqual(unused.length, 1);
+			assert.strictEqual(unused[0].kind, 'duplicate-import');
+			assert.strictEqual(unused[0].line, 3);
+			assert.strictEqual(unused[0].original!.line, 1);
+			assert.strictEqual(unused[0].reason, '`std::fmt` is already imported at line 1');
+		});
+
+		test('Should report imports already covered by a glob', () => {
+			const source = `use std::collections::*;
+use std::collections::HashMap;
+use std::collections::BTreeMap as Tree;
+
+fn f(_: HashMap<u8, u8>, _: Tree<u8, u8>) {}
+`;
+			const unused = analyzer.analyzeFile(source);
+
+			assert.strictEqual(unused.length, 1);
+			assert.strictEqual(unused[0].kind, 'redundant-import');
+			assert.deepStrictEqual(unused[0].symbols, ['HashMap']);
+			assert.strictEqual(unused[0].original!.line, 1);
+		});
+
+		test('Should report re-imports in nested blocks but not in nested modules', () => {
+			const source = `use std::fmt;
+
+fn show() {
+    use std::fmt;
+    let _ = fmt::format;
+}
+
+mod inner {
+    use std::fmt;
+    pub fn f(_: &dyn fmt::Debug) {}
+}
+`;
+			const unused = analyzer.analyzeFile(source);
+
+			assert.strictEqual(unused.length, 1);
+			assert.strictEqual(unused[0].kind, 'redundant-import');
+			assert.strictEqual(unused[0].line, 4);
+			assert.strictEqual(unused[0].original!.line, 1);
+		});
+	});
+	suite('Shadowed imports', () => {
+		test('Should report imports whose every use refers to a local definition', () => {
+			const sample = path.join(__dirname, '../../../test-samples/shadowing.rs');
+			const unused = analyzer.analyzeFile(fs.readFileSync(sample, 'utf-8'));
+
+			assert.deepStrictEqual(unused.map(u => [u.kind, u.line, u.symbols[0], u.original!.line]), [
+				['shadowed-import', 4, 'Result', 30],
+				['shadowed-import', 5, 'parse', 25],
+			]);
+			assert.strictEqual(unused[1].reason, 'Symbol parse (crate::util::parse) is shadowed by fn `parse` at line 25');
+		});
+
+		test('Should not report partly shadowed or differently namespaced uses', () => {
+			const source = `use a::value;
+use a::total;
+use a::config;
+use a::helper;
+
+fn f(config: &config::Config) -> u32 {
+    let value = 1;
+    let total = total(value);
+    config.size + value + total
+}
+
+impl S {
+    fn helper(&self) {}
+    fn g(&self) { helper(); }
+}
+
+#[cfg(test)]
+fn value() {}
+fn h() { value(); }
+`;
+			assert.deepStrictEqual(analyzer.analyzeFile(source).map(u => u.symbols[0]), []);
+		});
+
+		test('Should not take the const of raw pointers for const items', () => {
+			assert.deepStrictEqual(analyzer.analyzeFile('use std::ffi::c_void;\nextern "C" {\n    fn free(data: *const c_void);\n}\n'), []);
+			assert.deepStrictEqual(analyzer.analyzeFile('use crate::state::COUNTER;\nfn f() -> *const u32 {\n    &raw const COUNTER\n}\n'), []);
+			// A real const item still shadows
+			assert.deepStrictEqual(analyzer.analyzeFile('use crate::state::COUNTER;\nconst COUNTER: u32 = 1;\nfn f() -> u32 { COUNTER }\n').map(u => u.kind), ['shadowed-import']);
+		});
+	});
+
+	suite('Suppressions', () => {
+		test('Should honour allow and expect attributes on use items', () => {
+			const source = `#[allow(unused_imports)]
+use std::fs::File;
+#[expect(unused_imports, reason = "needed by doctests")]
+use std::io::Read;
+#[cfg_attr(test, allow(unused_imports))]
+use std::fmt;
+use std::env;
+`;
+			assert.deepStrictEqual(unusedSpans(source), ['std::env']);
+		});
+
+		test('Should honour attributes on modules and the crate root', () => {
+			const source = `#![allow(unused)]
+use std::fs::File;
+
+#[deny(unused_imports)]
+mod strict {
+    use std::io::Read;
+
+    mod relaxed {
+        #![allow(unused_imports)]
+        use std::io::Write;
+    }
+}
+`;
+			assert.deepStrictEqual(unusedSpans(source), ['std::io::Read']);
+		});
+
+		test('Should honour importlens: ignore above or after an import', () => {
+			const source = `// importlens: ignore
+use std::fs::File;
+use std::io::Read; // importlens: ignore
+use std::{
+    // importlens: ignore
+    fmt,
+    env,
+};
+`;
+			assert.deepStrictEqual(unusedSpans(source), ['env']);
+		});
+
+		test('Should report suppressions covering only used imports when asked', () => {
+			const source = `#[allow(unused_imports)]
+use std::io::Write;
+#[allow(unused_imports)]
+use std::{fs::File, fmt};
+use std::env; // importlens: ignore
+
+fn main() { Write::flush; fmt::format; env::args(); }
+`;
+			assert.deepStrictEqual(analyzer.analyzeFile(source), []);
+
+			const unused = new RustAnalyzer({ reportUnusedSuppressions: true }).analyzeFile(source);
+
+			assert.deepStrictEqual(unused.map(u => [u.kind, u.line, u.symbols]), [
+				['useless-suppression', 1, ['Write']],
+				['useless-suppression', 5, ['env']],
+			]);
+			assert.strictEqual(unused[0].reason, '`#[allow(unused_imports)]` is not needed: `std::io::Write` is used');
+		});
+	});
+
+	suite('Trait methods', () => {
+		test('Should downgrade unnamed std traits whose methods are called', () => {
+			const source = `use std::io::{Read, Write};
+use std::fmt::Write as _;
+use std::io::BufRead;
+
+fn f(mut file: std::fs::File, out: &mut String) {
+    file.write_all(b"x").unwrap();
+    writeln!(out, "y").unwrap();
+}
+`;
+			const unused = analyzer.analyzeFile(source);
+
+			assert.deepStrictEqual(unused.map(u => [u.symbols[0], u.confidence]), [
+				['Read', undefined],
+				['Write', 'low'],
+				['BufRead', undefined],
+			]);
+			assert.strictEqual(unused[1].reason, 'Trait Write (std::io::Write) is never named, but `write_all` calls may need it in scope');
+		});
+
+		test('Should use the methods of traits defined in the same file', () => {
+			const source = `mod shapes {
+    pub trait Area { fn area(&self) -> f64; }
+    pub trait Named { fn name(&self) -> String; }
+}
+use shapes::{Area, Named};
+
+fn total(s: &dyn std::any::Any) -> f64 { s.area() }
+`;
+			assert.deepStrictEqual(analyzer.analyzeFile(source).map(u => [u.symbols[0], u.confidence]), [
+				['Area', 'low'],
+				['Named', undefined],
+			]);
+		});
+
+		test('Should hide low-confidence findings unless --min-confidence=low', async () => {
+			const sample = path.join(__dirname, '../../../test-samples/test.rs');
+			const analyze = async (argv: string[]) => {
+				const [result] = await new CLIAnalyzer(parseArgs(['--no-cache', ...argv, sample])).analyzeFiles([sample]);
+				return result.unusedImports.map(u => u.symbols[0]);
+			};
+
+			assert.deepStrictEqual(await analyze([]), ['File']);
+			assert.deepStrictEqual(await analyze(['--min-confidence=uncertain']), ['Write', 'File']);
+			assert.deepStrictEqual(await analyze(['--min-confidence=low']), ['Write', 'File']);
+			assert.throws(() => parseArgs(['--min-confidence=certain']), /Invalid confidence level: certain/);
+		});
+	});
+
+	suite('cfg-gated imports', () => {
+		test('Should only match gated imports against uses that can be compiled with them', () => {
+			const source = `#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+#[cfg(unix)]
+use std::os::unix::io::AsRawFd;
+use std::collections::HashMap;
+
+#[cfg(unix)]
+fn mode(m: &std::fs::Metadata) -> u32 { PermissionsExt::mode(m) }
+
+#[cfg(windows)]
+fn fd(f: &std::fs::File) -> Result<(), ()> { AsRawFd::as_raw_fd(f); Ok(()) }
+
+#[cfg(test)]
+mod tests {
+    fn t() { let _: HashMap<u8, u8>; }
+}
+`;
+			assert.deepStrictEqual(analyzer.analyzeFile(source).map(u => u.symbols[0]), ['AsRawFd']);
+		});
+
+		test('Should not report imports repeated under exclusive conditions', () => {
+			const source = `#[cfg(unix)]
+use std::os::unix::ffi::OsStrExt as Ext;
+#[cfg(not(unix))]
+use std::os::unix::ffi::OsStrExt as Ext;
+#[cfg(test)]
+use std::fmt::Debug;
+
+#[cfg(all(not(test), feature = "fmt"))]
+fn f(_: &dyn Debug) { Ext::as_bytes; }
+`;
+			assert.deepStrictEqual(analyzer.analyzeFile(source).map(u => [u.symbols[0], u.kind]), [['Debug', 'unused-import']]);
+		});
+
+		test('Should honour options pinned with --cfg', () => {
+			const source = `use std::os::windows::ffi::OsStrExt;
+#[cfg(target_os = "macos")]
+use std::os::macos::fs::MetadataExt;
+
+#[cfg(target_os = "windows")]
+fn wide(s: &std::ffi::OsStr) { OsStrExt::encode_wide(s); }
+`;
+			const pinned = new RustAnalyzer({ cfg: parseArgs(['--cfg', 'target_os=linux']).cfg });
+
+			assert.deepStrictEqual(analyzer.analyzeFile(source).map(u => u.symbols[0]), ['MetadataExt']);
+			assert.deepStrictEqual(pinned.analyzeFile(source).map(u => u.symbols[0]), ['OsStrExt']);
+			assert.deepStrictEqual(parseArgs(['--cfg', 'feature=serde', '--cfg=unix']).cfg, ['feature="serde"', 'target_family="unix"']);
+			assert.throws(() => parseArgs(['--cfg', 'a b']), /Invalid --cfg value/);
+		});
+	});
+
+	suite('Re-exports', () => {
+		let root: string;
+
+		setup(() => {
+			root = fs.mkdtempSync(path.join(os.tmpdir(), 'importlens-crate-'));
+		});
+
+		teardown(() => {
+			fs.rmSync(root, { recursive: true, force: true });
+		});
+
+		// Helper to lay out a crate: { 'src/lib.rs': '...', ... }
+		function writeCrate(files: Record<string, string>): void {
+			fs.writeFileSync(path.join(root, 'Cargo.toml'), '[package]\nname = "demo"\n');
+			for (const [name, content] of Object.entries(files)) {
+				fs.mkdirSync(path.dirname(path.join(root, name)), { recursive: true });
+				fs.writeFileSync(path.join(root, name), content);
+			}
+		}
+
+		function unusedReexports(file: string): string[] {
+			const crate = RustCrate.forFile(path.join(root, file), new Map());
+			const { content, modulePath } = crate.fileFor(path.join(root, file))!;
+			return analyzer.findUnusedReexports(content, crate, modulePath).map(u => u.importPath!);
+		}
+
+		test('Should never report pub use items as unused', () => {
+			const source = `pub use crate::internal::Widget;
+pub(crate) use crate::internal::Gadget;
+pub(in crate::ui) use crate::internal::Gizmo;
+pub(self) use std::fs::File;
+`;
+			assert.deepStrictEqual(analyzer.analyzeFile(source).map(u => u.symbols[0]), ['File']);
+		});
+
+		test('Should report crate-visible re-exports that no module refers to', () => {
+			writeCrate({
+				'src/lib.rs': 'mod internal;\nmod ui;\npub use internal::Widget;\npub(crate) use internal::{Gadget, Gizmo};\n',
+				'src/internal.rs': 'pub struct Widget;\npub struct Gadget;\npub struct Gizmo;\n',
+				'src/ui/mod.rs': 'mod button;\n',
+				'src/ui/button.rs': 'use crate::Gadget;\nfn f(_: Gadget) {}\n',
+			});
+
+			assert.deepStrictEqual(unusedReexports('src/lib.rs'), ['internal::Gizmo']);
+		});
+
+		test('Should resolve super paths, inline modules and glob imports', () => {
+			writeCrate({
+				'src/main.rs': `mod util {
+    pub(super) use std::collections::HashMap;
+    pub(crate) use std::collections::BTreeMap;
+    pub(crate) use std::collections::HashSet;
+
+    mod tests {
+        fn t() { super::BTreeMap::<u8, u8>::new(); }
+    }
+}
+
+mod prelude {
+    pub(crate) use std::fmt::Debug;
+}
+
+mod app {
+    use crate::prelude::*;
+}
+
+fn main() { util::HashMap::<u8, u8>::new(); }
+`,
+			});
+
+			assert.deepStrictEqual(unusedReexports('src/main.rs'), ['std::collections::HashSet']);
+		});
+	});
+
+	suite('Glob imports', () => {
+		let root: string;
+
+		setup(() => {
+			root = fs.mkdtempSync(path.join(os.tmpdir(), 'importlens-globs-'));
+		});
+
+		teardown(() => {
+			fs.rmSync(root, { recursive: true, force: true });
+		});
+
+		// Helper to lay out a crate and check the globs of its src/lib.rs
+		function analyzeGlobs(files: Record<string, string>) {
+			fs.writeFileSync(path.join(root, 'Cargo.toml'), '[package]\nname = "demo"\n');
+			for (const [name, content] of Object.entries(files)) {
+				fs.mkdirSync(path.dirname(path.join(root, name)), { recursive: true });
+				fs.writeFileSync(path.join(root, name), content);
+			}
+
+			const lib = path.join(root, 'src/lib.rs');
+			const crate = RustCrate.forFile(lib, new Map());
+			const { content, modulePath } = crate.fileFor(lib)!;
+			return { content, ...analyzer.analyzeGlobs(content, crate, modulePath) };
+		}
+
+		test('Should report globs of crate modules whose items are never used', () => {
+			const { content, unused, expansions, skipped } = analyzeGlobs({
+				'src/lib.rs': `mod shapes;
+mod util;
+use std::io::prelude::*;
+use crate::shapes::*;
+use util::*;
+use crate::shapes::Kind::*;
+
+pub fn total() -> f64 { area(&Circle { r: 1.0 }) + Square::default().side }
+`,
+				'src/shapes.rs': 'pub struct Circle { pub r: f64 }\n#[derive(Default)]\npub struct Square { pub side: f64 }\npub enum Kind { Round }\npub fn area(c: &Circle) -> f64 { c.r * c.r }\nfn scale() {}\n',
+				'src/util.rs': 'pub fn unused_util() {}\npub const LIMIT: u32 = 3;\n',
+			});
+
+			assert.deepStrictEqual(unused.map(u => [u.line, u.importPath, u.confidence, u.confidenceReason]), [[5, 'util::*', 'medium', 'glob-items-listed']]);
+			assert.ok(applyEdits(content, expansions).includes('use crate::shapes::{Circle, Square, area};\nuse util::*;\n'));
+			assert.deepStrictEqual(skipped.map(glob => [glob.line, glob.reason]), [
+				[3, 'it points outside the crate'],
+				[6, 'it does not point at a module'],
+			]);
+		});
+
+		test('Should see private items from child modules and skip names imported or defined there', () => {
+			const { expansions, unused } = analyzeGlobs({
+				'src/lib.rs': `fn helper() -> u8 { 1 }
+fn other() -> u8 { 2 }
+struct Config;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fmt::Debug as Config;
+
+    fn other() -> u8 { 3 }
+
+    #[test]
+    fn t() { assert_eq!(helper() + other(), 4); let _: Option<Config> = None; }
+}
+`,
+			});
+
+			assert.deepStrictEqual(unused, []);
+			assert.deepStrictEqual(expansions.map(edit => edit.newText), ['helper']);
+		});
+
+		test('Should expand used globs with --fix --expand-globs', async () => {
+			analyzeGlobs({
+				'src/lib.rs': 'mod shapes;\nmod util;\nuse shapes::*;\nuse util::*;\n\npub fn f() -> Circle { Circle }\n',
+				'src/shapes.rs': 'pub struct Circle;\npub struct Square;\n',
+				'src/util.rs': 'pub fn g() {}\n',
+			});
+			const lib = path.join(root, 'src/lib.rs');
+			const [result] = await new CLIAnalyzer(parseArgs(['--no-cache', '--fix', '--expand-globs', lib])).analyzeFiles([lib]);
+			const fix = new FixEngine().fixFile(result, false);
+
+			assert.deepStrictEqual([fix.fixedCount, fix.expandedCount], [1, 1]);
+			assert.strictEqual(fs.readFileSync(lib, 'utf-8'), 'mod shapes;\nmod util;\nuse shapes::Circle;\n\npub fn f() -> Circle { Circle }\n');
+			assert.throws(() => parseArgs(['--expand-globs', 'src']), /--expand-globs can only be used with --fix/);
+		});
+
+		test('Should leave the re-exports and globs of a file with syntax errors alone', async () => {
+			const source = 'mod shapes;\nmod util;\npub(crate) use shapes::Circle;\nuse util::*;\n\nfn broken(n: u32 {\n    n\n}\n\npub fn f() {}\n';
+			analyzeGlobs({
+				'src/lib.rs': source,
+				'src/shapes.rs': 'pub struct Circle;\n',
+				'src/util.rs': 'pub fn g() {}\n',
+			});
+			const lib = path.join(root, 'src/lib.rs');
+			const analyze = async (args: string[]) => (await new CLIAnalyzer(parseArgs(['--no-cache', '--check-reexports', ...args, lib])).analyzeFiles([lib]))[0];
+
+			const low = await analyze(['--min-confidence=low']);
+			assert.deepStrictEqual(low.unusedImports.map(u => [u.line, u.kind, u.confidence, u.confidenceReason]), [
+				[3, 'unused-reexport', 'low', 'parse-degraded'],
+				[4, 'unused-import', 'low', 'parse-degraded'],
+			]);
+
+			const fix = new FixEngine().fixFile(await analyze(['--fix']), false);
+			assert.strictEqual(fix.fixedCount, 0);
+			assert.strictEqual(fs.readFileSync(lib, 'utf-8'), source);
+		});
+	});
+
+	suite('Crate module tree', () => {
+		let root: string;
+
+		setup(() => {
+			root = fs.mkdtempSync(path.join(os.tmpdir(), 'importlens-modules-'));
+		});
+
+		teardown(() => {
+			fs.rmSync(root, { recursive: true, force: true });
+		});
+
+		function writeCrate(files: Record<string, string>): void {
+			for (const [name, content] of Object.entries(files)) {
+				fs.mkdirSync(path.dirname(path.join(root, name)), { recursive: true });
+				fs.writeFileSync(path.join(root, name), content);
+			}
+		}
+
+		test('Should follow mod.rs and name.rs layouts and #[path] attributes', () => {
+			writeCrate({
+				'src/lib.rs': 'mod net;\n#[path = "platform/unix_impl.rs"]\npub(crate) mod sys;\nmod outer {\n    #[path = "inner_file.rs"]\n    mod inner;\n}\n',
+				'src/net/mod.rs': 'mod http;\n',
+				'src/net/http.rs': 'mod headers;\n',
+				'src/net/http/headers.rs': '',
+				'src/platform/unix_impl.rs': 'mod child;\n',
+				'src/platform/child.rs': '',
+				'src/outer/inner_file.rs': '',
+			});
+
+			const crate = RustCrate.forFile(path.join(root, 'src/lib.rs'), new Map(), path.join(root, 'src/lib.rs'));
+
+			assert.deepStrictEqual(crate.files.map(file => [path.relative(root, file.filePath).split(path.sep).join('/'), file.modulePath.join('::')]), [
+				['src/lib.rs', ''],
+				['src/net/mod.rs', 'net'],
+				['src/net/http.rs', 'net::http'],
+				['src/net/http/headers.rs', 'net::http::headers'],
+				['src/platform/unix_impl.rs', 'sys'],
+				['src/platform/child.rs', 'sys::child'],
+				['src/outer/inner_file.rs', 'outer::inner'],
+			]);
+			assert.deepStrictEqual(crate.diagnostics, []);
+		});
+
+		test('Should count uses from other modules with --crate-root', async () => {
+			writeCrate({
+				'src/lib.rs': 'mod net;\n',
+				'src/net/mod.rs': 'mod http;\nuse std::collections::HashMap;\nuse std::collections::BTreeMap;\nuse std::fs::File;\n',
+				'src/net/http.rs': 'use super::HashMap;\nfn f() -> HashMap<u8, u8> { crate::net::BTreeMap::new(); HashMap::new() }\n',
+			});
+			const lib = path.join(root, 'src/lib.rs');
+			const mod = path.join(root, 'src/net/mod.rs');
+			const analyze = async (...flags: string[]) => {
+				const [result] = await new CLIAnalyzer(parseArgs(['--no-cache', ...flags, mod])).analyzeFiles([mod]);
+				return result.unusedImports.map(u => u.symbols[0]);
+			};
+
+			assert.deepStrictEqual(await analyze(), ['HashMap', 'BTreeMap', 'File']);
+			assert.deepStrictEqual(await analyze('--crate-root', lib), ['File']);
+			assert.throws(() => parseArgs(['--crate-root', path.join(root, 'missing.rs')]), /Crate root not found/);
+		});
+
+		test('Should report missing module files and modules mounted twice', async () => {
+			writeCrate({
+				'src/lib.rs': '// crate root\nmod gone;\n#[path = "lib.rs"]\nmod again;\n',
+			});
+			const lib = path.join(root, 'src/lib.rs');
+
+			const [result] = await new CLIAnalyzer(parseArgs(['--no-cache', '--crate-root', lib, lib])).analyzeFiles([lib]);
+
+			assert.deepStrictEqual(result.diagnostics, [
+				`[WARNING] ${lib}:2: file not found for module \`gone\` (looked for gone.rs, gone/mod.rs)`,
+				`[WARNING] ${lib}:4: module \`again\` points at ${lib}, which is already the crate root; not loading it again`,
+			]);
+		});
+	});
+
+	suite('Doctests', () => {
+		const doctests = new RustAnalyzer({ checkDoctests: true });
+
+		test('Should not count names in doc comments as uses', () => {
+			const source = `use crate::Builder;
+
+/// \`\`\`
+/// let b = Builder::new();
+/// \`\`\`
+pub fn build() {}
+`;
+
+			assert.deepStrictEqual(unusedSpans(source), ['crate::Builder']);
+		});
+
+		test('Should report unused imports inside doctests at their file positions', () => {
+			const source = `//! \`\`\`
+//! use mycrate::Builder;
+//! use std::fmt;
+//! # use std::io::Write;
+//! let b = Builder::new();
+//! \`\`\`
+
+use std::fs::File;
+
+/**
+ * \`\`\`no_run
+ * use mycrate::open;
+ * \`\`\`
+ */
+pub fn open() -> File { todo!() }
+`;
+			const unused = doctests.analyzeFile(source);
+
+			assert.deepStrictEqual(unused.map(u => [source.slice(u.span!.start, u.span!.end), u.line, u.doctestLine]), [
+				['std::fmt', 3, 1],
+				['std::io::Write', 4, 1],
+				['mycrate::open', 12, 11],
+			]);
+			assert.strictEqual(unused[0].reason, 'Doctest at line 1: Symbol fmt (std::fmt) not used in code');
+			assert.strictEqual(unused[0].column, 9);
+		});
+
+		test('Should skip other languages, ignored blocks and plain comments', () => {
+			const source = `/// \`\`\`text
+/// use std::fmt;
+/// \`\`\`
+/// \`\`\`ignore
+/// use std::fmt;
+/// \`\`\`
+//// \`\`\`
+//// use std::fmt;
+//// \`\`\`
+/// ~~~rust,should_panic
+/// use std::fmt::Display;
+/// ~~~
+pub fn f() {}
+`;
+
+			assert.deepStrictEqual(doctests.analyzeFile(source).map(u => u.importPath), ['std::fmt::Display']);
+		});
+
+		test('Should leave doctests alone when fixing', () => {
+			const source = `/// \`\`\`
+/// use std::fmt;
+/// \`\`\`
+use std::io::Read;
+
+pub fn f() {}
+`;
+			const unused = doctests.analyzeFile(source);
+			const { fixed, fixedCount } = new FixEngine().fixSource(source, { filePath: 'lib.rs', language: 'rust', unusedImports: unused });
+
+			assert.strictEqual(unused.length, 2);
+			assert.strictEqual(fixedCount, 1);
+			assert.strictEqual(fixed, '/// ```\n/// use std::fmt;\n/// ```\n\npub fn f() {}\n');
+		});
+	});
+
+	suite('Extern crates and dependencies', () => {
+		let root: string;
+
+		setup(() => {
+			root = fs.mkdtempSync(path.join(os.tmpdir(), 'importlens-deps-'));
+		});
+
+		teardown(() => {
+			fs.rmSync(root, { recursive: true, force: true });
+		});
+
+		// Helper to lay out a package and return the unused dependency findings of its manifest
+		async function unusedDependencies(manifest: string, files: Record<string, string>): Promise<string[]> {
+			fs.writeFileSync(path.join(root, 'Cargo.toml'), manifest);
+			for (const [name, content] of Object.entries(files)) {
+				fs.mkdirSync(path.dirname(path.join(root, name)), { recursive: true });
+				fs.writeFileSync(path.join(root, name), content);
+			}
+
+			const lib = path.join(root, 'src/lib.rs');
+			const results = await new CLIAnalyzer(parseArgs(['--no-cache', '--check-dependencies', lib])).analyzeFiles([lib]);
+			const manifestResult = results.find(r => r.filePath === path.join(root, 'Cargo.toml'))!;
+			return manifestResult.unusedImports.map(u => `${u.line}:${u.column} ${u.symbols[0]}`);
+		}
+
+		test('Should report unused extern crate items but keep macro_use and re-exports', () => {
+			const source = `#[macro_use]
+extern crate lazy_static;
+extern crate rand;
+extern crate regex as re;
+extern crate alloc as _;
+pub extern crate log;
+extern crate self as demo;
+
+fn f() { re::Regex::new("x"); }
+`;
+			assert.deepStrictEqual(unusedSpans(source), ['extern crate rand;']);
+			assert.deepStrictEqual(analyzer.analyzeFile(source).map(u => u.reason), ['Extern crate rand not used in code']);
+		});
+
+		test('Should report dependencies no source file refers to, at their manifest line', async () => {
+			const manifest = `[package]
+name = "demo"
+
+[dependencies]
+serde = { version = "1", features = ["derive"] }
+serde-json = "1"
+json5 = { package = "json5", version = "0.4" }
+fancy = { package = "fancy-regex", version = "0.11" }
+rand = "0.8"
+
+[dependencies.itertools]
+version = "0.12"
+`;
+			const found = await unusedDependencies(manifest, {
+				'src/lib.rs': `#[derive(Serialize)]
+struct S;
+
+fn f() {
+    serde_json::to_string(&1).unwrap();
+    fancy::Regex::new("x");
+}
+`,
+			});
+
+			assert.deepStrictEqual(found, ['7:1 json5', '9:1 rand', '11:15 itertools']);
+		});
+
+		test('Should only count dev-dependency uses in tests and cfg(test) code', async () => {
+			const manifest = `[package]
+name = "demo"
+
+[dependencies]
+regex = "1"
+
+[dev-dependencies]
+tempfile = "3"
+proptest = "1"
+criterion = "0.5"
+`;
+			const found = await unusedDependencies(manifest, {
+				'src/lib.rs': `use proptest::prelude::*;
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+}
+`,
+				'benches/bench.rs': 'use regex::Regex;\nuse criterion::Criterion;\n',
+			});
+
+			assert.deepStrictEqual(found, ['9:1 proptest']);
+		});
+
+		test('Should read renames and target-specific tables', () => {
+			const dependencies = parseDependencies(`[target.'cfg(unix)'.dependencies]
+nix = "0.27"
+
+[build-dependencies]
+cc = { package = "cc-rs", version = "1" }
+`);
+
+			assert.deepStrictEqual(dependencies.map(d => [d.key, d.packageName, d.kind]), [
+				['cc', 'cc-rs', 'build'],
+				['nix', 'nix', 'normal'],
+			]);
+		});
+	});
+
+	suite('Over-qualified paths', () => {
+		const qualified = new RustAnalyzer({ qualifiedPathThreshold: 3 });
+
+		test('Should report paths written out as often as the threshold', () => {
+			const source = `use std::io;
+
+fn main() {
+    let a = std::collections::HashMap::<u8, u8>::new();
+    let b: std::collections::HashMap<u8, u8> = std::collections::HashMap::default();
+    std::fs::read("a").ok();
+    std::fs::read("b").ok();
+    std::fs::write("c", "").ok();
+    let e = [io::Error::last_os_error(), io::Error::last_os_error(), io::Error::last_os_error()];
+    let r: [std::fmt::Result; 3] = [std::fmt::Result::Ok(()), std::fmt::Result::Ok(()), std::fmt::Result::Ok(())];
+    println!("{:?}", (std::time::Instant::now(), std::time::Instant::now(), std::time::Instant::now()));
+}
+`;
+			const found = qualified.analyzeFile(source).filter(u => u.kind === 'over-qualified-path');
+
+			assert.deepStrictEqual(found.map(u => [u.line, u.column, u.importPath, u.qualifiedUses!.length]), [
+				[4, 13, 'std::collections::HashMap', 3],
+				[6, 5, 'std::fs', 3],
+			]);
+			assert.strictEqual(found[0].reason, 'Path std::collections::HashMap is written out 3 times without an import; `use std::collections::HashMap;` shortens it to HashMap');
+			assert.strictEqual(analyzer.analyzeFile(source).filter(u => u.kind === 'over-qualified-path').length, 0);
+			assert.strictEqual(new RustAnalyzer({ qualifiedPathThreshold: 4 }).analyzeFile(source).filter(u => u.kind === 'over-qualified-path').length, 0);
+		});
+
+		test('Should not report paths whose name is taken in their module', () => {
+			const source = `struct Instant;
+
+fn main() {
+    let _ = (std::time::Instant::now(), std::time::Instant::now(), std::time::Instant::now());
+    let _ = (std::fmt::Error, std::fmt::Error, std::io::Error::other("x"), std::io::Error::other("y"), std::io::Error::other("z"));
+}
+
+mod inner {
+    fn f() { let _ = (std::time::Instant::now(), std::time::Instant::now(), std::time::Instant::now()); }
+}
+`;
+			assert.deepStrictEqual(qualified.analyzeFile(source).map(u => [u.line, u.importPath]), [
+				[5, 'std::io::Error'],
+				[9, 'std::time::Instant'],
+			]);
+		});
+
+		test('Should import the path in its block and shorten every use', () => {
+			const engine = new FixEngine();
+			const fix = (source: string) => engine.applyFixes(source, 'rust', qualified.analyzeFile(source));
+
+			assert.strictEqual(fix(`use std::fs::File;
+use std::io::Read;
+
+use crate::config::Config;
+
+fn main() {
+    let a = std::collections::HashMap::<u8, u8>::new();
+    let b = std::collections::HashMap::<u8, u8>::new();
+    let c = std::collections::HashMap::<u8, u8>::new();
+    let _ = (serde_json::Value::Null, serde_json::Value::Null, serde_json::Value::Null);
+    let _: Option<(File, Box<dyn Read>)> = None;
+    let _: Option<Config> = None;
+}
+`), `use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+
+use serde_json::Value;
+
+use crate::config::Config;
+
+fn main() {
+    let a = HashMap::<u8, u8>::new();
+    let b = HashMap::<u8, u8>::new();
+    let c = HashMap::<u8, u8>::new();
+    let _ = (Value::Null, Value::Null, Value::Null);
+    let _: Option<(File, Box<dyn Read>)> = None;
+    let _: Option<Config> = None;
+}
+`);
+			assert.strictEqual(fix(`//! Crate docs
+#![allow(dead_code)]
+
+/// Entry point
+fn main() { std::fs::read("a").ok(); std::fs::read("b").ok(); std::fs::read("c").ok(); }
+`), `//! Crate docs
+#![allow(dead_code)]
+
+use std::fs;
+
+/// Entry point
+fn main() { fs::read("a").ok(); fs::read("b").ok(); fs::read("c").ok(); }
+`);
+			assert.throws(() => parseArgs(['--check-qualified-paths=0', '.']), /Invalid --check-qualified-paths value: 0/);
+			assert.strictEqual(parseArgs(['--check-qualified-paths', '.']).qualifiedPathThreshold, 3);
+		});
+	});
+
+	suite('Banned imports', () => {
+		const bans = [{ path: 'chrono::*', message: 'use time instead' }, { path: 'crate::db::raw' }, { path: 'std::sync::Mutex*' }];
+
+		test('Should ban matching imports, nested paths and extern crates', () => {
+			const source = `use chrono::{NaiveDate, prelude::Utc};
+use std::sync::{Arc, MutexGuard};
+use self::db::raw::Conn;
+extern crate chrono as time_crate;
+
+mod db { pub mod raw { pub struct Conn; } }
+fn main() { let _ = (NaiveDate::MIN, Utc, Arc::new(0), Conn, chrono::Local::now()); let _: Option<MutexGuard<u8>> = None; }
+`;
+			const found = analyzer.findBannedImports(source, bans);
+
+			assert.deepStrictEqual(found.map(u => [u.line, u.column, u.kind, u.importPath]), [
+				[1, 14, 'banned-import', 'chrono::NaiveDate'],
+				[1, 25, 'banned-import', 'chrono::prelude::Utc'],
+				[2, 22, 'banned-import', 'std::sync::MutexGuard'],
+				[3, 5, 'banned-import', 'self::db::raw::Conn'],
+				[4, 1, 'banned-import', 'chrono'],
+			]);
+			assert.strictEqual(found[0].reason, '`chrono::NaiveDate` is banned by `chrono::*`: use time instead');
+			assert.strictEqual(found[3].reason, '`self::db::raw::Conn` is banned by `crate::db::raw`');
+		});
+
+		test('Should ban written-out paths with --check-qualified-paths, through imported names', () => {
+			const source = `use crate::db;
+use chrono::Utc;
+
+mod db { pub mod raw { pub fn query() {} } }
+fn main() {
+    db::raw::query();
+    ::chrono::Local::now();
+    let _ = Utc::now();
+    let _ = std::sync::Mutex::new(0);
+}
+`;
+			const found = new RustAnalyzer({ qualifiedPathThreshold: 3 }).findBannedImports(source, bans);
+
+			// `Utc::now` is already reported at its import
+			assert.deepStrictEqual(found.map(u => [u.line, u.column, u.importStatement]), [
+				[2, 5, 'use chrono::Utc;'],
+				[6, 5, 'db::raw::query'],
+				[7, 5, '::chrono::Local::now'],
+				[9, 13, 'std::sync::Mutex::new'],
+			]);
+			assert.strictEqual(analyzer.findBannedImports(source, bans).length, 1);
+			assert.deepStrictEqual(new FixEngine().computeEdits(source, 'rust', found.filter(isFixable)), []);
+		});
+	});
+
+	suite('Prelude imports', () => {
+		const unnecessary = (source: string, edition?: '2015' | '2018' | '2021' | '2024') =>
+			analyzer.findUnnecessaryImports(source, edition).map(u => [u.line, u.importPath]);
+
+		test('Should report imports of prelude items of the edition', () => {
+			const source = `use std::vec::Vec;
+use std::option::Option::{self, Some};
+use std::convert::{TryFrom, TryInto};
+use core::future::Future;
+use std::collections::HashMap;
+use std::fmt::Result;
+use std::string::String as Text;
+use alloc::boxed::Box;
+pub use std::result::Result::Ok;
+
+fn f(v: Vec<u8>, m: HashMap<u8, Text>) -> Option<u8> { Some(u8::try_from(v.len()).ok()?) }
+`;
+			assert.deepStrictEqual(unnecessary(source, '2018'), [[1, 'std::vec::Vec'], [2, 'std::option::Option::self'], [2, 'std::option::Option::Some']]);
+			assert.deepStrictEqual(unnecessary(source, '2021').map(([line]) => line), [1, 2, 2, 3, 3]);
+			assert.deepStrictEqual(unnecessary(source, '2024').map(([line]) => line), [1, 2, 2, 3, 3, 4]);
+			assert.strictEqual(analyzer.findUnnecessaryImports(source, '2021')[3].reason, 'TryFrom is already in scope from the Rust 2021 prelude');
+			// Without an edition, only what every prelude has
+			assert.deepStrictEqual(unnecessary(source), unnecessary(source, '2015'));
+		});
+
+		test('Should report imports of prelude modules the edition already has', () => {
+			const source = `use std::prelude::v1::*;
+use std::prelude::rust_2018::*;
+use core::prelude::rust_2024::*;
+use std::prelude::rust_2024::Future;
+`;
+			assert.deepStrictEqual(unnecessary(source, '2021'), [[1, 'std::prelude::v1::*'], [2, 'std::prelude::rust_2018::*']]);
+			assert.strictEqual(unnecessary(source, '2024').length, 4);
+			assert.strictEqual(analyzer.findUnnecessaryImports(source)[0].reason, '`std::prelude::v1::*` imports the prelude, which every module already has');
+		});
+
+		test('Should report Debug and Hash imports only derive macros use', () => {
+			const source = `use std::fmt::Debug;
+use std::hash::Hash;
+
+#[derive(Debug, Hash, PartialEq)]
+struct Key(u8);
+
+fn show<T: Debug>(value: T) {}
+`;
+			assert.deepStrictEqual(unnecessary(source), [[2, 'std::hash::Hash']]);
+			assert.strictEqual(analyzer.findUnnecessaryImports(source)[0].reason, 'std::hash::Hash is only used by `#[derive(Hash)]`, and the derive macro is in the prelude');
+		});
+
+		test('Should keep imports that override other items of the same name', () => {
+			// A local item or a glob may bring in another Result, which the import overrides
+			assert.deepStrictEqual(unnecessary('use std::result::Result;\nmod inner { pub struct Result; }\n'), []);
+			assert.deepStrictEqual(unnecessary('use std::io::*;\nuse std::result::Result;\nmod m {\n    use std::vec::Vec;\n}\n'), [[4, 'std::vec::Vec']]);
+			assert.deepStrictEqual(unnecessary('#![no_implicit_prelude]\nuse std::vec::Vec;\n'), []);
+			assert.deepStrictEqual(unnecessary('// importlens: ignore\nuse std::vec::Vec;\n'), []);
+		});
+
+		suite('Editions from Cargo.toml', () => {
+			let root: string;
+
+			setup(() => {
+				root = fs.mkdtempSync(path.join(os.tmpdir(), 'importlens-prelude-'));
+			});
+
+			teardown(() => {
+				fs.rmSync(root, { recursive: true, force: true });
+			});
+
+			const source = 'use std::convert::TryInto;\nuse std::vec::Vec;\n\nfn f(v: Vec<u8>) -> u8 { v.len().try_into().unwrap() }\n';
+
+			async function findings(args: string[], manifest?: string): Promise<Array<string | undefined>> {
+				const file = path.join(root, 'app', 'src', 'main.rs');
+				fs.mkdirSync(path.dirname(file), { recursive: true });
+				fs.writeFileSync(file, source);
+				if (manifest !== undefined) fs.writeFileSync(path.join(root, 'app', 'Cargo.toml'), manifest);
+				const [result] = await new CLIAnalyzer(parseArgs(['--no-cache', ...args, file])).analyzeFiles([file]);
+				return result.unusedImports.filter(u => u.kind === 'unnecessary-import').map(u => u.importPath);
+			}
+
+			test('Should read the edition of the package, or of its workspace', async () => {
+				assert.deepStrictEqual(await findings(['--check-prelude'], '[package]\nname = "app"\nedition = "2021"\n'), ['std::convert::TryInto', 'std::vec::Vec']);
+				// Cargo's default edition
+				assert.deepStrictEqual(await findings(['--check-prelude'], '[package]\nname = "app"\n'), ['std::vec::Vec']);
+
+				fs.writeFileSync(path.join(root, 'Cargo.toml'), '[workspace]\nmembers = ["app"]\n\n[workspace.package]\nedition = "2021"\n');
+				assert.deepStrictEqual(await findings(['--check-prelude'], '[package]\nname = "app"\nedition.workspace = true\n'), ['std::convert::TryInto', 'std::vec::Vec']);
+				assert.deepStrictEqual(await findings(['--check-prelude', '--edition=2018']), ['std::vec::Vec']);
+				assert.deepStrictEqual(await findings([]), []);
+			});
+
+			test('Should fall back to what every prelude has without a Cargo.toml', async () => {
+				assert.deepStrictEqual(await findings(['--check-prelude']), ['std::vec::Vec']);
+				assert.deepStrictEqual(await findings(['--check-prelude', '--edition', '2021']), ['std::convert::TryInto', 'std::vec::Vec']);
+				assert.throws(() => parseArgs(['--edition=2027', '.']), /Invalid --edition value: 2027. Must be one of: 2015, 2018, 2021, 2024/);
+			});
+
+			test('Should remove unnecessary imports with --fix', async () => {
+				const file = path.join(root, 'lib.rs');
+				fs.writeFileSync(file, 'use std::vec::Vec;\nuse std::prelude::v1::*;\nuse std::env;\n\nfn f(v: Vec<u8>) {}\n');
+				const [result] = await new CLIAnalyzer(parseArgs(['--no-cache', '--check-prelude', file])).analyzeFiles([file]);
+				assert.deepStrictEqual(result.unusedImports.map(u => [u.kind, u.suggestedFix?.description]), [
+					['unnecessary-import', 'Remove unnecessary import `std::vec::Vec`'],
+					['unnecessary-import', 'Remove unnecessary import `std::prelude::v1::*`'],
+					['unused-import', 'Remove unused import `std::env`'],
+				]);
+
+				const edits = new FixEngine().computeEdits(fs.readFileSync(file, 'utf-8'), 'rust', result.unusedImports);
+				assert.strictEqual(applyEdits(fs.readFileSync(file, 'utf-8'), edits), '\nfn f(v: Vec<u8>) {}\n');
+			});
+		});
+	});
+
+	suite('Test-only imports', () => {
+		const testOnly = (source: string) => analyzer.findTestOnlyImports(source).map(u => [u.line, u.importPath, u.testModule?.name]);
+		const moved = (source: string) => new FixEngine().fixSource(source, { filePath: 'lib.rs', language: 'rust', unusedImports: analyzer.findTestOnlyImports(source) }).fixed;
+
+		test('Should report imports only test code uses', () => {
+			const source = `use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::Write;
+
+pub fn show(set: &HashSet<u8>) -> fmt::Result { Ok(()) }
+
+#[test]
+fn writes() { let _ = Write::flush; }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts() { let _: HashMap<u8, u8> = HashMap::new(); }
+}
+`;
+			assert.deepStrictEqual(testOnly(source), [[1, 'std::collections::HashMap', 'tests'], [3, 'std::io::Write', undefined]]);
+			assert.strictEqual(analyzer.findTestOnlyImports(source)[0].reason, 'Symbol HashMap (std::collections::HashMap) is only used by tests; it belongs in `mod tests`');
+			// Used by non-test code too, unused, gated, or named through `super::`
+			assert.deepStrictEqual(testOnly('use std::fmt;\nfn f() -> fmt::Result { Ok(()) }\n#[cfg(test)]\nmod tests { fn g() -> super::fmt::Result { Ok(()) } }\n'), []);
+			assert.deepStrictEqual(testOnly('use std::env;\n#[cfg(test)]\nuse std::fs;\n#[cfg(test)]\nmod tests { fn f() { fs::read(env::args()); } }\n'), [[1, 'std::env', 'tests']]);
+			assert.deepStrictEqual(testOnly('use std::fs;\n#[cfg(test)]\nmod tests {\n    use super::fs;\n    fn f() { fs::read(""); }\n}\n'), []);
+		});
+
+		test('Should move the import after the imports of the test module', () => {
+			assert.strictEqual(
+				moved('use std::collections::{BTreeMap, HashMap};\n\npub type Map = BTreeMap<u8, u8>;\n\n#[cfg(test)]\nmod tests {\n    use super::*;\n\n    #[test]\n    fn t() { HashMap::<u8, u8>::new(); }\n}\n'),
+				'use std::collections::BTreeMap;\n\npub type Map = BTreeMap<u8, u8>;\n\n#[cfg(test)]\nmod tests {\n    use super::*;\n    use std::collections::HashMap;\n\n    #[test]\n    fn t() { HashMap::<u8, u8>::new(); }\n}\n'
+			);
+			// Ahead of the first item of a module without imports, with paths relative to the module rewritten
+			assert.strictEqual(
+				moved('mod util { pub fn run() {} }\nuse util::run;\nuse self::util as u;\n\n#[cfg(test)]\nmod tests {\n    #[test]\n    fn t() { run(); u::run(); }\n}\n'),
+				'mod util { pub fn run() {} }\n\n#[cfg(test)]\nmod tests {\n    use super::util::run;\n    use super::util as u;\n\n    #[test]\n    fn t() { run(); u::run(); }\n}\n'
+			);
+		});
+
+		test('Should not duplicate an import the test module has', () => {
+			assert.strictEqual(
+				moved('use std::rc::Rc;\n\n#[cfg(test)]\nmod tests {\n    use std::rc::Rc;\n\n    fn t() { Rc::new(1); }\n}\n'),
+				'\n#[cfg(test)]\nmod tests {\n    use std::rc::Rc;\n\n    fn t() { Rc::new(1); }\n}\n'
+			);
+			// Without a test module to move it to, the import stays
+			const source = 'use std::io::Write;\n\n#[test]\nfn t() { let _ = Write::flush; }\n';
+			const [finding] = analyzer.findTestOnlyImports(source);
+			assert.deepStrictEqual(new FixEngine().computeEdits(source, 'rust', [finding]), []);
+		});
+
+		test('Should only report test-only imports where --check-test-only or config turns them on', async () => {
+			const root = fs.mkdtempSync(path.join(os.tmpdir(), 'importlens-test-only-'));
+			try {
+				const file = path.join(root, 'lib.rs');
+				fs.writeFileSync(file, 'use std::rc::Rc;\n\n#[cfg(test)]\nmod tests {\n    use super::*;\n\n    #[test]\n    fn t() { Rc::new(1); }\n}\n');
+				const findings = async (...args: string[]) => {
+					const [result] = await new CLIAnalyzer(parseArgs(['--no-cache', ...args, file])).analyzeFiles([file]);
+					return result.unusedImports.map(u => [u.kind, u.severity, u.suggestedFix?.description]);
+				};
+
+				assert.deepStrictEqual(await findings(), []);
+				assert.deepStrictEqual(await findings('--check-test-only'), [['test-only-import', 'warning', 'Move test-only import `std::rc::Rc` into `mod tests`']]);
+				fs.writeFileSync(path.join(root, 'importlens.toml'), '[severity]\ntest-only-import = "error"\n');
+				assert.deepStrictEqual(await findings(), [['test-only-import', 'error', 'Move test-only import `std::rc::Rc` into `mod tests`']]);
+				fs.writeFileSync(path.join(root, 'importlens.toml'), '[severity]\ntest-only-import = "off"\n');
+				assert.deepStrictEqual(await findings('--check-test-only'), []);
+			} finally {
+				fs.rmSync(root, { recursive: true, force: true });
+			}
+		});
+	});
+
+	suite('Edge-case syntax', () => {
+		const sample = (name: string) => fs.readFileSync(path.join(__dirname, '../../../test-samples', name), 'utf-8');
+
+		test('Should match raw identifiers with and without the r# prefix', () => {
+			const unused = analyzer.analyzeFile(sample('raw_identifiers.rs'));
+
+			assert.deepStrictEqual(unused.map(u => [u.line, u.symbols[0]]), [[5, 'r#try'], [7, 'r#loop']]);
+			assert.deepStrictEqual(analyzer.analyzeFile('use a::r#b;\nuse a::b;\nfn f() { b(); }\n').map(u => [u.kind, u.line]), [['duplicate-import', 2]]);
+		});
+
+		test('Should only count uses inside the block a use item is in', () => {
+			const source = sample('block_scoped.rs');
+			const unused = analyzer.analyzeFile(source);
+
+			assert.deepStrictEqual(unused.map(u => [u.line, u.symbols[0]]), [[16, 'Read'], [27, 'Cursor']]);
+			assert.deepStrictEqual(unused.map(u => source.slice(u.span!.start, u.span!.end)), ['std::io::Read', 'std::io::Cursor']);
+			assert.ok(!new FixEngine().applyFixes(source, 'rust', unused).includes('use std::io::Read;'));
+		});
+
+		test('Should skip use items in impl, trait and extern blocks with a warning', () => {
+			const file = path.join(__dirname, '../../../test-samples/misplaced_use.rs');
+			const result = new CLIAnalyzer(parseArgs(['--no-cache', '.'])).analyzeContent(file, sample('misplaced_use.rs'));
+
+			assert.deepStrictEqual(result.unusedImports.map(u => [u.line, u.symbols[0]]), [[3, 'env']]);
+			assert.deepStrictEqual(result.diagnostics, [
+				`[WARNING] ${file}:11: skipping \`use\` item, which is not allowed in \`impl\` blocks`,
+				`[WARNING] ${file}:19: skipping \`use\` item, which is not allowed in \`trait\` blocks`,
+				`[WARNING] ${file}:25: skipping \`use\` item, which is not allowed in \`extern\` blocks`,
+			]);
+
+			// Used on its own, the analyzer hands them to the sink it is given
+			const messages: string[] = [];
+			analyzer.analyzeFile('impl S {\n    use std::fmt;\n}\n', 'lib.rs', message => messages.push(message));
+			assert.deepStrictEqual(messages, ['[WARNING] lib.rs:2: skipping `use` item, which is not allowed in `impl` blocks']);
+		});
+
+		test('Should cut off the statement of findings in a large generated use group', () => {
+			const names = Array.from({ length: 3000 }, (_, i) => `ty_${i}`);
+			const source = `use crate::ffi::{${names.join(', ')}, ty_0};\nfn f() { ty_1(); }\n`;
+			const unused = analyzer.analyzeFile(source);
+
+			assert.strictEqual(unused.length, 3000);
+			assert.deepStrictEqual(unused.filter(u => u.kind === 'duplicate-import').map(u => u.column), [source.lastIndexOf('ty_0') + 1]);
+			assert.ok(unused.every(u => u.importStatement.length === MAX_STATEMENT_LENGTH + 1 && u.importStatement.endsWith('…')));
+			assert.ok(unused[0].importStatement.startsWith('use crate::ffi::{ty_0, ty_1, ty_2,'));
+		});
+
+		test('Should analyze a file with syntax errors around the items they are in', () => {
+			const file = path.join(__dirname, '../../../test-samples/syntax_error.rs');
+			const result = new CLIAnalyzer(parseArgs(['--no-cache', '--min-confidence=low', '.'])).analyzeContent(file, sample('syntax_error.rs'));
+
+			assert.deepStrictEqual(result.unusedImports.map(u => [u.line, u.symbols[0], u.confidence, u.confidenceReason]), [[9, 'env', 'low', 'parse-degraded']]);
+			assert.deepStrictEqual(result.diagnostics, [
+				`[NOTE] ${file}: syntax errors, skipped lines 4-7 (unclosed \`{\`); lines 11-13 (unclosed \`(\`); lines 15-19 (unclosed \`{\`); findings in this file have low confidence`,
+			]);
+			assert.ok(result.unusedImports.every(u => !meetsConfidence(u, 'high')));
+			assert.deepStrictEqual(new CLIAnalyzer(parseArgs(['--no-cache', '.'])).analyzeContent(file, sample('syntax_error.rs')).unusedImports, []);
+			assert.deepStrictEqual(analyzer.analyzeFile('use std::fmt;\nuse std::env;\nfn f() -> fmt::Result { Ok(()) }\n').map(u => u.confidence), [undefined]);
+		});
+	});
+});
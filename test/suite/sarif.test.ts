@@ -0,0 +1,110 @@
+import * as assert from 'assert';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { parseArgs } from '../../src/cli/ArgumentParser';
+import { AnalysisResult, CLIAnalyzer, FINDING_KINDS } from '../../src/cli/CLIAnalyzer';
+import { buildSarif, SarifLog, SarifRegion } from '../../src/cli/Sarif';
+
+suite('SARIF Output Tests', () => {
+	let root: string;
+
+	setup(() => {
+		root = fs.mkdtempSync(path.join(os.tmpdir(), 'importlens-sarif-'));
+	});
+
+	teardown(() => {
+		fs.rmSync(root, { recursive: true, force: true });
+	});
+
+	// Checks the parts of the SARIF 2.1.0 schema that the log relies on
+	function assertValidSarif(log: SarifLog): void {
+		assert.strictEqual(log.version, '2.1.0');
+		assert.strictEqual(log.$schema, 'https://json.schemastore.org/sarif-2.1.0.json');
+		assert.strictEqual(log.runs.length, 1);
+
+		const [run] = log.runs;
+		const rules = run.tool.driver.rules;
+		assert.ok(run.tool.driver.name);
+		assert.strictEqual(new Set(rules.map(rule => rule.id)).size, rules.length, 'rule ids must be unique');
+
+		const assertRegion = (region: SarifRegion) => {
+			for (const value of [region.startLine, region.startColumn, region.endLine, region.endColumn]) {
+				assert.ok(value === undefined || (Number.isInteger(value) && value >= 1), `invalid position ${value}`);
+			}
+			if (region.endLine !== undefined) assert.ok(region.endLine >= region.startLine!);
+		};
+
+		for (const result of run.results) {
+			assert.strictEqual(rules[result.ruleIndex].id, result.ruleId);
+			assert.ok(['error', 'warning', 'note', 'none'].includes(result.level));
+			assert.ok(result.message.text);
+			for (const location of result.locations) {
+				const { artifactLocation, region } = location.physicalLocation;
+				assert.ok(!path.isAbsolute(artifactLocation.uri) && !artifactLocation.uri.includes('\\'));
+				assert.ok(run.originalUriBaseIds[artifactLocation.uriBaseId!]);
+				assertRegion(region!);
+			}
+		}
+	}
+
+	test('Should match the snapshot for a Rust file', async () => {
+		fs.writeFileSync(path.join(root, 'lib.rs'), 'use std::fs::File;\nuse std::io::Read;\n\nfn main() {\n    let _ = File::open("x");\n}\n');
+		const filePath = path.join(root, 'lib.rs');
+		const results = await new CLIAnalyzer(parseArgs(['--no-cache', filePath])).analyzeFiles([filePath]);
+
+		const log = buildSarif(results, root, '1.0.0');
+
+		assertValidSarif(log);
+		assert.deepStrictEqual(log.runs[0].tool.driver.rules.map(rule => rule.id), [...FINDING_KINDS]);
+		assert.strictEqual(log.runs[0].tool.driver.version, '1.0.0');
+		assert.deepStrictEqual(log.runs[0].results, [{
+			ruleId: 'unused-import',
+			ruleIndex: 0,
+			level: 'error',
+			message: { text: 'Symbol Read (std::io::Read) not used in code' },
+			locations: [{
+				physicalLocation: {
+					artifactLocation: { uri: 'lib.rs', uriBaseId: 'SRCROOT' },
+					region: { startLine: 2, startColumn: 5, endLine: 2, endColumn: 18, byteOffset: 23, byteLength: 13 },
+				},
+			}],
+			fixes: [{
+				description: { text: 'Remove unused import `std::io::Read`' },
+				artifactChanges: [{
+					artifactLocation: { uri: 'lib.rs', uriBaseId: 'SRCROOT' },
+					replacements: [{ deletedRegion: { byteOffset: 19, byteLength: 19 }, insertedContent: { text: '' } }],
+				}],
+			}],
+			properties: { confidence: 'high', confidenceReason: 'never-referenced' },
+		}]);
+	});
+
+	test('Should use paths relative to the scan directory and keep start-only regions', () => {
+		const results: AnalysisResult[] = [
+			{
+				filePath: path.join(root, 'pkg', 'app.py'),
+				language: 'python',
+				unusedImports: [{ line: 3, importStatement: 'import os', symbols: ['os'], reason: 'Module os not used in code', severity: 'error' }],
+			},
+			{ filePath: path.join(root, 'broken.rs'), language: 'rust', unusedImports: [], error: 'Unexpected token' },
+		];
+
+		const log = buildSarif(results, root, '1.0.0');
+		const [run] = log.runs;
+
+		assertValidSarif(log);
+		assert.deepStrictEqual(run.results[0].locations[0].physicalLocation, {
+			artifactLocation: { uri: 'pkg/app.py', uriBaseId: 'SRCROOT' },
+			region: { startLine: 3 },
+		});
+		assert.strictEqual(run.results[0].level, 'error');
+		assert.strictEqual(run.originalUriBaseIds.SRCROOT.uri.endsWith('/'), true);
+		assert.deepStrictEqual(run.invocations[0].toolExecutionNotifications.map(n => n.locations[0].physicalLocation.artifactLocation.uri), ['broken.rs']);
+	});
+
+	test('Should accept --format sarif', () => {
+		assert.strictEqual(parseArgs(['--format', 'sarif', 'src']).format, 'sarif');
+		assert.throws(() => parseArgs(['--format=sarif2']), /Must be one of: text, json, github, junit, sarif/);
+	});
+});
@@ -0,0 +1,73 @@
+import * as assert from 'assert';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { parseArgs } from '../../src/cli/ArgumentParser';
+import { CLIAnalyzer } from '../../src/cli/CLIAnalyzer';
+import { FileDiscovery } from '../../src/cli/FileDiscovery';
+import { buildImportStats, formatStatsText, ImportStats, STATS_SCHEMA_VERSION } from '../../src/cli/ImportStats';
+
+suite('Import Stats Tests', () => {
+	let root: string;
+
+	setup(() => {
+		root = fs.mkdtempSync(path.join(os.tmpdir(), 'importlens-stats-'));
+	});
+
+	teardown(() => {
+		fs.rmSync(root, { recursive: true, force: true });
+	});
+
+	// Helper: stats of the files as `stats` computes them, through discovery and analysis
+	async function statsOf(files: Record<string, string>): Promise<ImportStats> {
+		for (const [name, content] of Object.entries(files)) {
+			fs.mkdirSync(path.dirname(path.join(root, name)), { recursive: true });
+			fs.writeFileSync(path.join(root, name), content);
+		}
+		const args = parseArgs(['stats', '--no-cache', root]);
+		const results = await new CLIAnalyzer(args).analyzeFiles(await new FileDiscovery(args).discoverFiles());
+		return buildImportStats(results, { cwd: root, version: '9.9.9', now: new Date('2026-05-01T12:00:00Z') });
+	}
+
+	const app = {
+		'Cargo.toml': '[package]\nname = "app"\n',
+		'src/lib.rs': 'mod model;\nuse std::collections::{HashMap, HashSet};\nuse serde::Serialize;\nuse crate::model::User;\n\npub fn users() -> HashMap<u32, User> { HashMap::new() }\n#[derive(Serialize)]\npub struct Page;\n',
+		'src/model.rs': 'use std::fmt;\nuse std::io::Read;\nextern crate serde;\n\npub struct User;\nimpl fmt::Debug for User { fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { Ok(()) } }\n',
+	};
+
+	test('Should count imports, unused imports and imports per file', async () => {
+		const stats = await statsOf(app);
+
+		assert.strictEqual(stats.schemaVersion, STATS_SCHEMA_VERSION);
+		assert.strictEqual(stats.importlensVersion, '9.9.9');
+		assert.strictEqual(stats.generatedAt, '2026-05-01T12:00:00.000Z');
+		// `HashSet`, `Read` and `extern crate serde` are unused
+		assert.deepStrictEqual(stats.totals, { files: 2, imports: 7, unused: 3, unusedPercent: 42.9, averageImportsPerFile: 3.5 });
+		assert.deepStrictEqual(stats.crates, [{ name: 'app', files: 2, imports: 7, unused: 3, unusedPercent: 42.9 }]);
+		assert.deepStrictEqual(stats.topFiles, [
+			{ file: 'src/lib.rs', crate: 'app', imports: 4, unused: 1 },
+			{ file: 'src/model.rs', crate: 'app', imports: 3, unused: 2 },
+		]);
+		assert.deepStrictEqual(stats.groupSizes, [{ size: 1, count: 4 }, { size: 2, count: 1 }]);
+	});
+
+	test('Should rank the external crates imported, leaving out the crate itself', async () => {
+		const stats = await statsOf(app);
+
+		assert.deepStrictEqual(stats.externalCrates, [
+			{ name: 'std', imports: 4, files: 2 },
+			{ name: 'serde', imports: 2, files: 2 },
+		]);
+		assert.ok(formatStatsText(stats).includes('  std          4      2'));
+	});
+
+	test('Should follow the configuration of the check command', async () => {
+		// model.rs is ignored; duplicates are the only kind reported
+		const stats = await statsOf({ ...app, 'importlens.toml': 'ignore = ["src/model.rs"]\nkinds = ["duplicate-import"]\n' });
+
+		assert.deepStrictEqual(stats.totals, { files: 1, imports: 4, unused: 0, unusedPercent: 0, averageImportsPerFile: 4 });
+		assert.throws(() => parseArgs(['stats', '--format=sarif', '.']), /Invalid stats format/);
+		assert.throws(() => parseArgs(['stats', '--fix', '.']), /stats only reports numbers/);
+		assert.strictEqual(parseArgs(['stats', '--format=json', '.']).format, 'json');
+	});
+});
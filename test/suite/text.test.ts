@@ -0,0 +1,163 @@
+import * as assert from 'assert';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { parseArgs } from '../../src/cli/ArgumentParser';
+import { AnalysisResult, FindingKind, FindingSeverity } from '../../src/cli/CLIAnalyzer';
+import { OutputFormatter, TextOptions, useColor } from '../../src/cli/OutputFormatter';
+
+suite('Text Output Tests', () => {
+	let root: string;
+
+	setup(() => {
+		root = fs.mkdtempSync(path.join(os.tmpdir(), 'importlens-text-'));
+		fs.writeFileSync(path.join(root, 'Cargo.toml'), '[workspace]\nmembers = ["core", "cli"]\n');
+		for (const name of ['core', 'cli']) {
+			fs.mkdirSync(path.join(root, name, 'src'), { recursive: true });
+			fs.writeFileSync(path.join(root, name, 'Cargo.toml'), `[package]\nname = "app-${name}"\n`);
+		}
+	});
+
+	teardown(() => {
+		fs.rmSync(root, { recursive: true, force: true });
+	});
+
+	// Helper: a result for `file` under the workspace with a finding per [line, kind, severity]
+	function resultWith(file: string, findings: Array<[number, FindingKind, FindingSeverity]>): AnalysisResult {
+		return {
+			filePath: path.join(root, file),
+			language: 'rust',
+			unusedImports: findings.map(([line, kind, severity]) => ({
+				line,
+				importStatement: `use m::item${line};`,
+				symbols: [`item${line}`],
+				reason: `reason ${line}`,
+				kind,
+				severity,
+			})),
+		};
+	}
+
+	const results = () => [
+		resultWith('core/src/lib.rs', [
+			[9, 'duplicate-import', 'warning'],
+			[1, 'unused-import', 'error'],
+			[2, 'unused-import', 'error'],
+			[3, 'unused-import', 'error'],
+			[4, 'unused-import', 'error'],
+		]),
+		resultWith('cli/src/main.rs', [[5, 'unused-import', 'error']]),
+		resultWith('cli/src/clean.rs', []),
+	];
+
+	// Helper: text output with the workspace root shortened to `<root>`
+	function text(input: AnalysisResult[], options: TextOptions = {}): string {
+		return new OutputFormatter('text', false, options).format(input).split(root + path.sep).join('<root>/');
+	}
+
+	test('Should group findings by file and kind, collapsing long runs of one kind', () => {
+		assert.strictEqual(text(results()), [
+			'ImportLens Analysis Results',
+			'===========================',
+			'',
+			'<root>/cli/src/main.rs: 1 finding (1 error(s), 0 warning(s), 0 info)',
+			'  Unused import (1)',
+			'    [error] Line 5: use m::item5;',
+			'    → reason 5',
+			'',
+			'<root>/core/src/lib.rs: 5 findings (4 error(s), 1 warning(s), 0 info)',
+			'  Unused import (4)',
+			'    [error] Line 1: use m::item1;',
+			'    → reason 1',
+			'    [error] Line 2: use m::item2;',
+			'    → reason 2',
+			'    [error] Line 3: use m::item3;',
+			'    → reason 3',
+			'    4 unused imports in this file, showing first 3; pass --verbose for all',
+			'  Duplicate import (1)',
+			'    [warning] Line 9: use m::item9;',
+			'    → reason 9',
+			'',
+			'='.repeat(50),
+			'By kind:',
+			'  Unused import     5',
+			'  Duplicate import  1',
+			'By crate:',
+			'  app-cli   1 in 1 file',
+			'  app-core  5 in 1 file',
+			'Total: 6 findings in 3 files (5 error(s), 1 warning(s), 0 info)',
+		].join('\n'));
+
+		const verbose = text(results(), { verbose: true });
+		assert.ok(verbose.includes('[OK] <root>/cli/src/clean.rs - No findings'));
+		assert.ok(verbose.includes('    [error] Line 4: use m::item4;'));
+		assert.ok(!verbose.includes('showing first'));
+	});
+
+	test('Should group by kind or crate and show only the top groups', () => {
+		assert.strictEqual(text(results(), { groupBy: 'kind', top: 1 }).split('\n' + '='.repeat(50))[0], [
+			'ImportLens Analysis Results',
+			'===========================',
+			'',
+			'Unused import: 5 findings in 2 files (5 error(s), 0 warning(s), 0 info)',
+			'  <root>/cli/src/main.rs',
+			'    [error] Line 5: use m::item5;',
+			'    → reason 5',
+			'  <root>/core/src/lib.rs',
+			'    [error] Line 1: use m::item1;',
+			'    → reason 1',
+			'    [error] Line 2: use m::item2;',
+			'    → reason 2',
+			'    [error] Line 3: use m::item3;',
+			'    → reason 3',
+			'    4 unused imports in this file, showing first 3; pass --verbose for all',
+			'',
+			'Showing the top 1 of 2 kinds by number of findings',
+			'',
+		].join('\n'));
+
+		const crates = text(results(), { groupBy: 'crate', top: 1 });
+		assert.ok(crates.includes('Crate app-core: 5 findings in 1 file (4 error(s), 1 warning(s), 0 info)\n  <root>/core/src/lib.rs\n    Unused import (4)\n      [error] Line 1'));
+		assert.ok(!crates.includes('Crate app-cli'));
+		assert.ok(crates.includes('Showing the top 1 of 2 crates by number of findings'));
+		// The summary still counts every finding
+		assert.ok(crates.endsWith('Total: 6 findings in 3 files (5 error(s), 1 warning(s), 0 info)'));
+	});
+
+	test('Should give the same text for findings in any order, each listed once', () => {
+		const [core, ...rest] = results();
+		const shuffled = [...rest.reverse(), { ...core, unusedImports: [...core.unusedImports].reverse() }];
+		assert.strictEqual(text(shuffled), text(results()));
+
+		// The same file given twice, e.g. through an overlapping path
+		const twice = text([...results(), resultWith('cli/src/main.rs', [[5, 'unused-import', 'error']])]);
+		assert.strictEqual(twice, text(results()));
+
+		// Findings of a single crate get no crate summary
+		assert.ok(!text(results().slice(0, 1)).includes('By crate:'));
+	});
+
+	test('Should color severities by --color and NO_COLOR', () => {
+		const colored = new OutputFormatter('text', true).format(results());
+		assert.ok(colored.includes('\x1b[31m[error]\x1b[0m Line 1'));
+		assert.ok(colored.includes('\x1b[33m[warning]\x1b[0m Line 9'));
+
+		const noColor = process.env.NO_COLOR;
+		try {
+			delete process.env.NO_COLOR;
+			assert.deepStrictEqual([useColor('auto', { isTTY: true }), useColor('auto', { isTTY: false })], [true, false]);
+			process.env.NO_COLOR = '1';
+			assert.deepStrictEqual([useColor('auto', { isTTY: true }), useColor('always', {}), useColor('never', { isTTY: true })], [false, true, false]);
+		} finally {
+			if (noColor === undefined) delete process.env.NO_COLOR;
+			else process.env.NO_COLOR = noColor;
+		}
+
+		const args = parseArgs(['--group-by=crate', '--top', '5', '--color', 'never', '--verbose', '.']);
+		assert.deepStrictEqual([args.groupBy, args.top, args.color, args.verbose], ['crate', 5, 'never', true]);
+		assert.throws(() => parseArgs(['--group-by', 'module', '.']), /Invalid --group-by value: module. Must be one of: file, kind, crate/);
+		assert.throws(() => parseArgs(['--top=0', '.']), /Invalid --top value/);
+		assert.throws(() => parseArgs(['--color=yes', '.']), /Invalid --color value/);
+		assert.throws(() => parseArgs(['--format=json', '--group-by', 'kind', '.']), /only apply to --format=text/);
+	});
+});
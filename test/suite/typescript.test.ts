@@ -0,0 +1,109 @@
+import * as assert from 'assert';
+import { ASTAnalyzer } from '../../src/cli/ASTAnalyzer';
+import { resolveLanguage } from '../../src/cli/Languages';
+
+suite('TypeScript Analyzer Tests', () => {
+	const analyzer = new ASTAnalyzer();
+
+	// Helper: [unused, type-only] names across all findings
+	function analyze(source: string, filePath = 'file.tsx'): { unused: string[]; typeOnly: string[] } {
+		const { unusedImports } = analyzer.analyzeTypeScriptFile(source, filePath);
+		return {
+			unused: unusedImports.flatMap(u => u.unusedSpecifiers),
+			typeOnly: unusedImports.flatMap(u => u.typeOnlySpecifiers),
+		};
+	}
+
+	suite('ES modules', () => {
+		test('Should report unused default, named and namespace imports', () => {
+			const source = `import a from 'a';\nimport { b, c as d } from 'b';\nimport * as e from 'e';\n\nb();\n`;
+
+			assert.deepStrictEqual(analyze(source).unused, ['a', 'd', 'e']);
+		});
+
+		test('Should never report side-effect imports', () => {
+			const source = `import './polyfills';\nimport 'reflect-metadata';\n`;
+
+			assert.deepStrictEqual(analyzer.analyzeTypeScriptFile(source, 'file.ts').unusedImports, []);
+		});
+
+		test('Should not count property keys and member names as uses', () => {
+			const source = `import { id, name } from 'x';\n\nconst o = { id: 1 };\no.name = 2;\n`;
+
+			assert.deepStrictEqual(analyze(source).unused, ['id', 'name']);
+		});
+	});
+
+	suite('Type-only usage', () => {
+		test('Should report value imports used only in type positions', () => {
+			const source = `import { Config, load } from './config';\n\nexport const c: Config = load();\n`;
+
+			assert.deepStrictEqual(analyze(source), { unused: [], typeOnly: ['Config'] });
+		});
+
+		test('Should accept type imports used as types', () => {
+			const source = `import type { Props } from './props';\nimport { type State, reducer } from './state';\n\nexport function f(p: Props): State { return reducer(p); }\n`;
+
+			assert.deepStrictEqual(analyze(source), { unused: [], typeOnly: [] });
+		});
+
+		test('Should report unused type imports as fully unused', () => {
+			const source = `import type { Props } from './props';\nexport const x = 1;\n`;
+
+			assert.deepStrictEqual(analyze(source), { unused: ['Props'], typeOnly: [] });
+		});
+
+		test('Should treat implements clauses, generics and type exports as type usage', () => {
+			const source = `import { Base, Item, Shape } from './types';\n\nexport type { Shape };\nclass A implements Base { items = new Array<Item>(); }\n`;
+
+			assert.deepStrictEqual(analyze(source).typeOnly, ['Base', 'Item', 'Shape']);
+		});
+
+		test('Should count typeof in value position as a runtime use', () => {
+			const source = `import { schema } from './schema';\n\nexport const kind = typeof schema;\n`;
+
+			assert.deepStrictEqual(analyze(source), { unused: [], typeOnly: [] });
+		});
+	});
+
+	suite('CommonJS', () => {
+		test('Should report unused require bindings', () => {
+			const source = `const fs = require('fs');\nconst { join, resolve: r } = require('path');\nconst parse = require('url').parse;\n\njoin('a');\n`;
+
+			assert.deepStrictEqual(analyze(source, 'file.cjs').unused, ['fs', 'r', 'parse']);
+		});
+
+		test('Should never report bare require calls', () => {
+			const source = `require('./register');\n`;
+
+			assert.deepStrictEqual(analyzer.analyzeTypeScriptFile(source, 'file.cjs').unusedImports, []);
+		});
+	});
+
+	suite('JSX', () => {
+		test('Should count component names and member roots as uses', () => {
+			const source = `import React from 'react';\nimport { Button, Icon } from './ui';\nimport * as Layout from './layout';\n\nexport const App = () => <Layout.Page><Button disabled /></Layout.Page>;\n`;
+
+			assert.deepStrictEqual(analyze(source).unused, ['Icon']);
+		});
+
+		test('Should not count attribute names or intrinsic elements as uses', () => {
+			const source = `import { onClick, div } from './x';\nimport React from 'react';\n\nexport const A = () => <div onClick={null} />;\n`;
+
+			assert.deepStrictEqual(analyze(source).unused, ['onClick', 'div']);
+		});
+
+		test('Should honour the @jsx pragma instead of React', () => {
+			const source = `/** @jsx h */\nimport { h } from 'preact';\nimport React from 'react';\n\nexport const A = () => <p />;\n`;
+
+			assert.deepStrictEqual(analyze(source).unused, ['React']);
+		});
+	});
+
+	test('Should route every JavaScript and TypeScript extension to this analyzer', () => {
+		assert.deepStrictEqual(
+			['a.ts', 'a.tsx', 'a.js', 'a.jsx', 'a.mjs', 'a.cjs'].map(file => resolveLanguage(file)),
+			['typescript', 'typescriptreact', 'javascript', 'javascriptreact', 'javascript', 'javascript']
+		);
+	});
+});
@@ -0,0 +1,112 @@
+import * as assert from 'assert';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { parseArgs } from '../../src/cli/ArgumentParser';
+import { AnalysisResult, CLIAnalyzer } from '../../src/cli/CLIAnalyzer';
+import { FixEngine } from '../../src/cli/FixEngine';
+import { LineIndex } from '../../src/cli/LineIndex';
+import { buildReport } from '../../src/cli/Report';
+import { buildSarif } from '../../src/cli/Sarif';
+
+suite('Unicode Span Tests', () => {
+	// Emoji in comments, CJK doc text and non-ASCII identifiers next to unused imports
+	const fixtures = path.join(__dirname, '../../../test/fixtures/unicode');
+
+	let root: string;
+
+	setup(() => {
+		root = fs.mkdtempSync(path.join(os.tmpdir(), 'importlens-unicode-'));
+		for (const name of fs.readdirSync(fixtures)) fs.copyFileSync(path.join(fixtures, name), path.join(root, name));
+	});
+
+	teardown(() => {
+		fs.rmSync(root, { recursive: true, force: true });
+	});
+
+	async function analyze(name: string): Promise<AnalysisResult> {
+		const [result] = await new CLIAnalyzer(parseArgs(['--no-cache', root])).analyzeFiles([path.join(root, name)]);
+		return result;
+	}
+
+	// Helper: line, UTF-8 byte column and the bytes `byte_span` covers, of every finding
+	function located(result: AnalysisResult): Array<[number, number | undefined, string]> {
+		const bytes = fs.readFileSync(result.filePath);
+		return buildReport([result], root).findings.map(f => [f.line, f.column ?? undefined, bytes.subarray(f.byte_span!.start, f.byte_span!.end).toString('utf-8')]);
+	}
+
+	test('Should report UTF-8 byte columns and spans that slice the file to the import', async () => {
+		const rust = await analyze('lib.rs');
+		assert.deepStrictEqual(located(rust), [
+			[11, 33, 'BTreeMap'],
+			[12, 38, 'Sortie'],
+			[12, 72, 'std::fmt::Write'],
+			[13, 21, 'Read'],
+		]);
+		assert.deepStrictEqual(located(await analyze('main.py')), [[2, 34, 'os'], [3, 25, 'OrderedDict']]);
+		assert.deepStrictEqual(located(await analyze('main.go')), [[6, 2, 'données "os"']]);
+
+		// Columns count the bytes before the span on its line, and string spans cover the same text
+		const content = fs.readFileSync(path.join(root, 'lib.rs'), 'utf-8');
+		const bytes = Buffer.from(content, 'utf-8');
+		for (const finding of rust.unusedImports) {
+			const { start, end } = finding.span!;
+			assert.strictEqual(finding.column, Buffer.byteLength(content.slice(content.lastIndexOf('\n', start - 1) + 1, start)) + 1);
+			assert.strictEqual(content.slice(start, end), bytes.subarray(finding.byteSpan!.start, finding.byteSpan!.end).toString('utf-8'));
+		}
+	});
+
+	test('Should splice suggested fixes on bytes exactly as --fix edits the file', async () => {
+		const result = await analyze('lib.rs');
+		const content = fs.readFileSync(result.filePath, 'utf-8');
+		const engine = new FixEngine();
+
+		for (const finding of result.unusedImports) {
+			let bytes = Buffer.from(content, 'utf-8');
+			for (const edit of [...finding.suggestedFix!.edits].sort((a, b) => b.start - a.start)) {
+				bytes = Buffer.concat([bytes.subarray(0, edit.start), Buffer.from(edit.newText, 'utf-8'), bytes.subarray(edit.end)]);
+			}
+			assert.strictEqual(bytes.toString('utf-8'), engine.fixSource(content, { ...result, unusedImports: [finding] }).fixed, finding.symbols[0]);
+		}
+
+		engine.fixFile(result, false);
+		assert.strictEqual(
+			fs.readFileSync(result.filePath, 'utf-8').split('\n').slice(10, 13).join('\n'),
+			'use std::collections::HashMap; // 🗺️ 地図\nuse données::{naïve_impl, Entrée}; /* 未使用 🚫 */ \nuse std::io::{self};'
+		);
+	});
+
+	test('Should give SARIF columns in UTF-16 code units', async () => {
+		const result = await analyze('lib.rs');
+		const lines = fs.readFileSync(result.filePath, 'utf-8').split('\n');
+		const text = (line: number, startColumn = 1, endColumn = 1) => lines[line - 1].slice(startColumn - 1, endColumn - 1);
+
+		const regions = buildSarif([result], root).runs[0].results.map(r => r.locations[0].physicalLocation.region);
+		assert.deepStrictEqual(
+			regions.map(region => text(region.startLine, region.startColumn, region.endColumn)),
+			['BTreeMap', 'Sortie', 'std::fmt::Write', 'Read']
+		);
+		// `données` and `未使用 🚫` are longer in bytes than in code units
+		assert.deepStrictEqual([regions[2].startColumn, result.unusedImports[2].column], [61, 72]);
+	});
+
+	test('Should convert every offset of a multi-byte file and back', () => {
+		const content = fs.readFileSync(path.join(root, 'lib.rs'), 'utf-8') + fs.readFileSync(path.join(root, 'main.go'), 'utf-8');
+		const index = new LineIndex(content);
+
+		for (let offset = 0; offset <= content.length; offset++) {
+			// Not between the halves of a surrogate pair
+			if (offset > 0 && (content.charCodeAt(offset) & 0xfc00) === 0xdc00) continue;
+
+			const byteOffset = Buffer.byteLength(content.slice(0, offset));
+			assert.strictEqual(index.byteOffsetAt(offset), byteOffset);
+			assert.strictEqual(index.offsetAtByte(byteOffset), offset);
+
+			const { line, column } = index.positionAt(offset);
+			assert.strictEqual(index.offsetAt(line, column), offset);
+			const utf16 = index.utf16PositionAt(offset);
+			assert.strictEqual(utf16.line, line);
+			assert.strictEqual(index.offsetAtUtf16(utf16.line, utf16.column), offset);
+		}
+	});
+});
@@ -0,0 +1,91 @@
+import * as assert from 'assert';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { parseArgs } from '../../src/cli/ArgumentParser';
+import { AnalysisResult } from '../../src/cli/CLIAnalyzer';
+import { Watcher } from '../../src/cli/Watcher';
+
+suite('Watch Mode Tests', () => {
+	let root: string;
+	let watcher: Watcher | undefined;
+	let updates: AnalysisResult[][];
+	let waiting: (() => void) | undefined;
+
+	setup(() => {
+		root = fs.mkdtempSync(path.join(os.tmpdir(), 'importlens-watch-'));
+		updates = [];
+	});
+
+	teardown(() => {
+		watcher?.close();
+		watcher = undefined;
+		fs.rmSync(root, { recursive: true, force: true });
+	});
+
+	async function startWatcher(): Promise<void> {
+		watcher = new Watcher(parseArgs(['watch', '--no-cache', root]), {
+			debounceMs: 50,
+			write: () => {},
+			onUpdate: results => {
+				updates.push(results);
+				waiting?.();
+			},
+		});
+		await watcher.start();
+	}
+
+	// Resolves with the results of the next analysis
+	function nextUpdate(): Promise<AnalysisResult[]> {
+		const count = updates.length;
+		return new Promise(resolve => {
+			waiting = () => {
+				if (updates.length > count) resolve(updates[updates.length - 1]);
+			};
+		});
+	}
+
+	// Findings as `file: symbols`, relative to the root
+	function summary(results: AnalysisResult[]): string[] {
+		return results.flatMap(r => r.unusedImports.map(u => `${path.basename(r.filePath)}: ${u.symbols.join(', ')}`));
+	}
+
+	test('Should re-analyze a changed file once per burst of saves', async () => {
+		fs.writeFileSync(path.join(root, 'lib.rs'), 'use std::fs::File;\n\nfn main() { File::open("x"); }\n');
+		await startWatcher();
+		assert.deepStrictEqual(summary(updates[0]), []);
+
+		const update = nextUpdate();
+		fs.writeFileSync(path.join(root, 'lib.rs'), 'use std::fs::File;\nuse std::io::Read;\n');
+		fs.writeFileSync(path.join(root, 'lib.rs'), 'use std::fs::File;\nuse std::io::Read;\n\nfn main() { File::open("x"); }\n');
+
+		assert.deepStrictEqual(summary(await update), ['lib.rs: Read']);
+		await new Promise(resolve => setTimeout(resolve, 200));
+		assert.strictEqual(updates.length, 2);
+	});
+
+	test('Should pick up new files and drop deleted ones', async () => {
+		fs.writeFileSync(path.join(root, 'a.rs'), 'use std::io::Read;\n');
+		await startWatcher();
+		assert.deepStrictEqual(summary(updates[0]), ['a.rs: Read']);
+
+		let update = nextUpdate();
+		fs.mkdirSync(path.join(root, 'nested'));
+		fs.writeFileSync(path.join(root, 'nested', 'b.rs'), 'use std::io::Write;\n');
+		assert.deepStrictEqual(summary(await update), ['a.rs: Read', 'b.rs: Write']);
+
+		update = nextUpdate();
+		fs.unlinkSync(path.join(root, 'a.rs'));
+		assert.deepStrictEqual(summary(await update), ['b.rs: Write']);
+	});
+
+	test('Should parse watch and --exec', () => {
+		const args = parseArgs(['watch', 'src', '--exec', 'echo done']);
+
+		assert.strictEqual(args.command, 'watch');
+		assert.strictEqual(args.exec, 'echo done');
+		assert.deepStrictEqual(args.files, ['src']);
+		assert.throws(() => parseArgs(['--exec=echo', 'src']), /--exec can only be used with watch/);
+		assert.throws(() => parseArgs(['watch', '--fix', 'src']), /--fix cannot be used with watch/);
+	});
+});